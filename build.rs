@@ -21,7 +21,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .type_attribute(".", "#[derive(serde::Serialize, serde::Deserialize)]")
             // Add an attribute to silence large_enum_variant warnings in generated code
             .type_attribute(".", "#[allow(clippy::large_enum_variant)]")
-            .build_server(false)
+            .build_server(true)
             .compile_protos(
                 &[
                     "vendor/snapchain/proto/definitions/admin_rpc.proto",
@@ -0,0 +1,63 @@
+-- Migration: Add frame_actions and link_compact_states tables
+-- Description: Persist MESSAGE_TYPE_FRAME_ACTION (13) and MESSAGE_TYPE_LINK_COMPACT_STATE (14)
+
+-- Frame actions (FrameActionBody)
+CREATE TABLE public.frame_actions
+(
+    id             uuid                     DEFAULT public.generate_ulid() NOT NULL,
+    created_at     timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    updated_at     timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    "timestamp"    timestamp with time zone                                NOT NULL,
+    deleted_at     timestamp with time zone,
+    fid            bigint                                                  NOT NULL,
+    hash           bytea                                                   NOT NULL,
+    url            text                                                    NOT NULL,
+    button_index   smallint                                                NOT NULL,
+    cast_fid       bigint,
+    cast_hash      bytea,
+    input_text     text,
+    state          bytea,
+    transaction_id bytea,
+    address        bytea,
+    CONSTRAINT frame_actions_pkey PRIMARY KEY (id),
+    CONSTRAINT frame_actions_hash_key UNIQUE (hash)
+);
+
+CREATE INDEX frame_actions_fid_index ON public.frame_actions USING btree (fid);
+CREATE INDEX frame_actions_timestamp_index ON public.frame_actions USING btree ("timestamp");
+CREATE INDEX frame_actions_cast_hash_index ON public.frame_actions USING btree (cast_hash)
+    WHERE (cast_hash IS NOT NULL);
+CREATE INDEX frame_actions_url_index ON public.frame_actions USING btree (url);
+
+CREATE TRIGGER update_frame_actions_updated_at BEFORE UPDATE ON public.frame_actions
+    FOR EACH ROW EXECUTE FUNCTION public.update_updated_at_column();
+
+-- Link compact states (LinkCompactStateBody)
+-- One row per (fid, type); the latest compact state by timestamp wins
+CREATE TABLE public.link_compact_states
+(
+    id          uuid                     DEFAULT public.generate_ulid() NOT NULL,
+    created_at  timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    updated_at  timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    "timestamp" timestamp with time zone                                NOT NULL,
+    deleted_at  timestamp with time zone,
+    fid         bigint                                                  NOT NULL,
+    type        text                                                    NOT NULL,
+    hash        bytea                                                   NOT NULL,
+    target_fids bigint[]                 DEFAULT '{}'::bigint[]         NOT NULL,
+    CONSTRAINT link_compact_states_pkey PRIMARY KEY (id),
+    CONSTRAINT link_compact_states_fid_type_unique UNIQUE (fid, type)
+);
+
+CREATE INDEX link_compact_states_hash_index ON public.link_compact_states USING btree (hash);
+CREATE INDEX link_compact_states_timestamp_index ON public.link_compact_states USING btree ("timestamp");
+
+CREATE TRIGGER update_link_compact_states_updated_at BEFORE UPDATE ON public.link_compact_states
+    FOR EACH ROW EXECUTE FUNCTION public.update_updated_at_column();
+
+-- Links materialized from a compact state reference the compact state message they came from.
+-- Rows created from individual LinkAdd messages keep this NULL.
+ALTER TABLE public.links ADD COLUMN compact_state_hash bytea;
+
+CREATE INDEX links_compact_state_hash_index ON public.links USING btree (compact_state_hash)
+    WHERE (compact_state_hash IS NOT NULL);
@@ -0,0 +1,65 @@
+-- Migration: Add derived fids registry
+-- Description: Current custody/recovery state per FID, derived from id_register_events
+
+-- One row per FID; recomputed from id_register_events whenever a new IdRegister event arrives
+CREATE TABLE public.fids
+(
+    id                         uuid                     DEFAULT public.generate_ulid() NOT NULL,
+    created_at                 timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    updated_at                 timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    deleted_at                 timestamp with time zone,
+    fid                        bigint                                                  NOT NULL,
+    custody_address            bytea,
+    recovery_address           bytea,
+    registered_at              timestamp with time zone,
+    register_block_number      bigint,
+    register_tx_hash           bytea,
+    last_transfer_at           timestamp with time zone,
+    last_transfer_block_number bigint,
+    transfer_count             integer                  DEFAULT 0                      NOT NULL,
+    last_event_block_number    bigint                                                  NOT NULL,
+    last_event_log_index       integer                                                 NOT NULL,
+    chain_id                   bigint                                                  NOT NULL,
+    CONSTRAINT fids_pkey PRIMARY KEY (id),
+    CONSTRAINT fids_fid_unique UNIQUE (fid)
+);
+
+CREATE INDEX fids_custody_address_index ON public.fids USING btree (custody_address)
+    WHERE (custody_address IS NOT NULL);
+CREATE INDEX fids_recovery_address_index ON public.fids USING btree (recovery_address)
+    WHERE (recovery_address IS NOT NULL);
+CREATE INDEX fids_registered_at_index ON public.fids USING btree (registered_at);
+
+CREATE TRIGGER update_fids_updated_at BEFORE UPDATE ON public.fids
+    FOR EACH ROW EXECUTE FUNCTION public.update_updated_at_column();
+
+-- Ordered custody/recovery changes per FID.
+-- old_address is the previous custody (transfer) or recovery (change recovery) address.
+CREATE VIEW public.fid_history AS
+SELECT fid,
+       CASE event_type
+           WHEN 1 THEN 'register'
+           WHEN 2 THEN 'transfer'
+           WHEN 3 THEN 'change_recovery'
+           ELSE 'unknown'
+       END AS change_type,
+       CASE event_type
+           WHEN 2 THEN from_address
+           WHEN 3 THEN lag(recovery_address) OVER recovery_window
+       END AS old_address,
+       CASE event_type
+           WHEN 3 THEN recovery_address
+           ELSE to_address
+       END AS new_address,
+       recovery_address,
+       block_number,
+       log_index,
+       tx_hash,
+       block_timestamp,
+       chain_id
+FROM public.id_register_events
+WHERE deleted_at IS NULL
+WINDOW recovery_window AS (
+    PARTITION BY fid, (event_type IN (1, 3))
+    ORDER BY block_number, log_index
+);
@@ -0,0 +1,33 @@
+-- Migration: Add derived signers table
+-- Description: Current app key state per (fid, key), derived from signer_events and signer_migrated_events
+
+CREATE TABLE public.signers
+(
+    id                      uuid                     DEFAULT public.generate_ulid() NOT NULL,
+    created_at              timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    updated_at              timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    deleted_at              timestamp with time zone,
+    fid                     bigint                                                  NOT NULL,
+    key                     bytea                                                   NOT NULL,
+    key_type                smallint                                                NOT NULL,
+    metadata_type           smallint,
+    request_fid             bigint,           -- decoded from SignedKeyRequest metadata
+    request_signer          bytea,
+    added_at                timestamp with time zone,
+    removed_at              timestamp with time zone,
+    removal_type            smallint,         -- REMOVE=2, ADMIN_RESET=3
+    migrated_at             timestamp with time zone,
+    last_event_block_number bigint                                                  NOT NULL,
+    last_event_log_index    integer                                                 NOT NULL,
+    chain_id                bigint                                                  NOT NULL,
+    CONSTRAINT signers_pkey PRIMARY KEY (id),
+    CONSTRAINT signers_fid_key_unique UNIQUE (fid, key)
+);
+
+CREATE INDEX signers_active_index ON public.signers USING btree (fid) WHERE (removed_at IS NULL);
+CREATE INDEX signers_key_index ON public.signers USING btree (key);
+CREATE INDEX signers_request_fid_index ON public.signers USING btree (request_fid)
+    WHERE (request_fid IS NOT NULL);
+
+CREATE TRIGGER update_signers_updated_at BEFORE UPDATE ON public.signers
+    FOR EACH ROW EXECUTE FUNCTION public.update_updated_at_column();
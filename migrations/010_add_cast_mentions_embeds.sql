@@ -0,0 +1,51 @@
+-- Migration: Add normalized cast mention and embed tables
+-- Description: One row per mention / embed of a cast, mirroring casts.mentions, casts.mentions_positions
+-- and casts.embeds so they can be indexed. deleted_at follows the parent cast.
+
+CREATE TABLE public.cast_mentions
+(
+    id          uuid                     DEFAULT public.generate_ulid() NOT NULL,
+    created_at  timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    updated_at  timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    deleted_at  timestamp with time zone,
+    fid         bigint                                                  NOT NULL, -- mentioned FID
+    cast_hash   bytea                                                   NOT NULL,
+    author_fid  bigint                                                  NOT NULL,
+    position    integer                                                 NOT NULL, -- byte offset in cast text
+    "timestamp" timestamp with time zone                                NOT NULL,
+    CONSTRAINT cast_mentions_pkey PRIMARY KEY (id),
+    CONSTRAINT cast_mentions_cast_hash_fid_position_unique UNIQUE (cast_hash, fid, position)
+);
+
+CREATE INDEX cast_mentions_fid_timestamp_index ON public.cast_mentions USING btree (fid, "timestamp" DESC)
+    WHERE (deleted_at IS NULL);
+
+CREATE TRIGGER update_cast_mentions_updated_at BEFORE UPDATE ON public.cast_mentions
+    FOR EACH ROW EXECUTE FUNCTION public.update_updated_at_column();
+
+CREATE TABLE public.cast_embeds
+(
+    id            uuid                     DEFAULT public.generate_ulid() NOT NULL,
+    created_at    timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    updated_at    timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    deleted_at    timestamp with time zone,
+    cast_hash     bytea                                                   NOT NULL,
+    author_fid    bigint                                                  NOT NULL,
+    position      smallint                                                NOT NULL, -- index in casts.embeds
+    kind          text                                                    NOT NULL,
+    url           text,                                  -- kind = 'url'
+    cast_fid      bigint,                                -- kind = 'cast': author of the quoted cast
+    cast_hash_ref bytea,                                 -- kind = 'cast': hash of the quoted cast
+    "timestamp"   timestamp with time zone                                NOT NULL,
+    CONSTRAINT cast_embeds_pkey PRIMARY KEY (id),
+    CONSTRAINT cast_embeds_cast_hash_position_unique UNIQUE (cast_hash, position),
+    CONSTRAINT cast_embeds_kind_check CHECK (kind IN ('url', 'cast'))
+);
+
+CREATE INDEX cast_embeds_url_index ON public.cast_embeds USING btree (url, "timestamp" DESC)
+    WHERE (url IS NOT NULL AND deleted_at IS NULL);
+CREATE INDEX cast_embeds_cast_hash_ref_index ON public.cast_embeds USING btree (cast_hash_ref)
+    WHERE (cast_hash_ref IS NOT NULL AND deleted_at IS NULL);
+
+CREATE TRIGGER update_cast_embeds_updated_at BEFORE UPDATE ON public.cast_embeds
+    FOR EACH ROW EXECUTE FUNCTION public.update_updated_at_column();
@@ -0,0 +1,49 @@
+-- Migration: Add channel registry
+-- Description: Channels derived from casts.root_parent_url, with daily activity and optional imported metadata
+
+CREATE TABLE public.channels
+(
+    id                  uuid                     DEFAULT public.generate_ulid() NOT NULL,
+    created_at          timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    updated_at          timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    deleted_at          timestamp with time zone,
+    url                 text                                                    NOT NULL,
+    channel_id          text,             -- imported metadata, e.g. "memes"
+    name                text,             -- imported metadata
+    description         text,             -- imported metadata
+    lead_fid            bigint,           -- imported metadata
+    first_cast_at       timestamp with time zone,
+    last_cast_at        timestamp with time zone,
+    cast_count          bigint                   DEFAULT 0                      NOT NULL,
+    reply_count         bigint                   DEFAULT 0                      NOT NULL,
+    metadata_updated_at timestamp with time zone,
+    CONSTRAINT channels_pkey PRIMARY KEY (id),
+    CONSTRAINT channels_url_unique UNIQUE (url)
+);
+
+CREATE UNIQUE INDEX channels_channel_id_index ON public.channels USING btree (channel_id)
+    WHERE (channel_id IS NOT NULL);
+CREATE INDEX channels_last_cast_at_index ON public.channels USING btree (last_cast_at DESC);
+
+CREATE TRIGGER update_channels_updated_at BEFORE UPDATE ON public.channels
+    FOR EACH ROW EXECUTE FUNCTION public.update_updated_at_column();
+
+CREATE TABLE public.channel_activity
+(
+    id          uuid                     DEFAULT public.generate_ulid() NOT NULL,
+    created_at  timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    updated_at  timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    url         text                                                    NOT NULL,
+    day         date                                                    NOT NULL, -- UTC
+    cast_count  bigint                   DEFAULT 0                      NOT NULL,
+    reply_count bigint                   DEFAULT 0                      NOT NULL,
+    CONSTRAINT channel_activity_pkey PRIMARY KEY (id),
+    CONSTRAINT channel_activity_url_day_unique UNIQUE (url, day)
+);
+
+CREATE TRIGGER update_channel_activity_updated_at BEFORE UPDATE ON public.channel_activity
+    FOR EACH ROW EXECUTE FUNCTION public.update_updated_at_column();
+
+-- Channel feeds are newest-first top-level casts
+CREATE INDEX casts_parent_url_timestamp_index ON public.casts USING btree (parent_url, "timestamp" DESC)
+    WHERE (parent_url IS NOT NULL AND deleted_at IS NULL);
@@ -0,0 +1,68 @@
+-- Migration: Add webhook subscriptions and deliveries
+-- Description: Outbound webhooks, with an outbox of pending deliveries, per-attempt logs and dead letters
+
+CREATE TABLE public.webhook_subscriptions
+(
+    id                  uuid                     DEFAULT public.generate_ulid() NOT NULL,
+    created_at          timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    updated_at          timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    deleted_at          timestamp with time zone,
+    url                 text                                                    NOT NULL,
+    secret              text                                                    NOT NULL,
+    active              boolean                  DEFAULT true                   NOT NULL,
+    -- Filters; an empty array matches anything
+    fids                bigint[]                 DEFAULT '{}'                   NOT NULL,
+    mentioned_fids      bigint[]                 DEFAULT '{}'                   NOT NULL,
+    parent_urls         text[]                   DEFAULT '{}'                   NOT NULL,
+    message_types       text[]                   DEFAULT '{}'                   NOT NULL, -- e.g. cast_add
+    onchain_event_types text[]                   DEFAULT '{}'                   NOT NULL, -- e.g. id_register
+    CONSTRAINT webhook_subscriptions_pkey PRIMARY KEY (id)
+);
+
+CREATE TRIGGER update_webhook_subscriptions_updated_at BEFORE UPDATE ON public.webhook_subscriptions
+    FOR EACH ROW EXECUTE FUNCTION public.update_updated_at_column();
+
+CREATE TABLE public.webhook_deliveries
+(
+    id              uuid                     DEFAULT public.generate_ulid() NOT NULL,
+    created_at      timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    updated_at      timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    subscription_id uuid                                                    NOT NULL,
+    event_id        bigint                                                  NOT NULL, -- Hub event ID
+    payload         jsonb                                                   NOT NULL,
+    status          text                     DEFAULT 'pending'              NOT NULL, -- pending, delivered, dead
+    attempts        integer                  DEFAULT 0                      NOT NULL,
+    next_attempt_at timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    last_error      text,
+    delivered_at    timestamp with time zone,
+    CONSTRAINT webhook_deliveries_pkey PRIMARY KEY (id),
+    CONSTRAINT webhook_deliveries_subscription_event_unique UNIQUE (subscription_id, event_id),
+    CONSTRAINT webhook_deliveries_subscription_fkey FOREIGN KEY (subscription_id)
+        REFERENCES public.webhook_subscriptions (id) ON DELETE CASCADE
+);
+
+CREATE TRIGGER update_webhook_deliveries_updated_at BEFORE UPDATE ON public.webhook_deliveries
+    FOR EACH ROW EXECUTE FUNCTION public.update_updated_at_column();
+
+-- The dispatcher polls for due pending deliveries
+CREATE INDEX webhook_deliveries_due_index ON public.webhook_deliveries USING btree (next_attempt_at)
+    WHERE (status = 'pending');
+-- Dead letters are listed and replayed per subscription
+CREATE INDEX webhook_deliveries_dead_index ON public.webhook_deliveries USING btree (subscription_id, created_at DESC)
+    WHERE (status = 'dead');
+
+CREATE TABLE public.webhook_delivery_attempts
+(
+    id          uuid                     DEFAULT public.generate_ulid() NOT NULL,
+    created_at  timestamp with time zone DEFAULT CURRENT_TIMESTAMP      NOT NULL,
+    delivery_id uuid                                                    NOT NULL,
+    attempt     integer                                                 NOT NULL,
+    status_code integer,                 -- null if the request never got a response
+    error       text,
+    duration_ms integer                                                 NOT NULL,
+    CONSTRAINT webhook_delivery_attempts_pkey PRIMARY KEY (id),
+    CONSTRAINT webhook_delivery_attempts_delivery_fkey FOREIGN KEY (delivery_id)
+        REFERENCES public.webhook_deliveries (id) ON DELETE CASCADE
+);
+
+CREATE INDEX webhook_delivery_attempts_delivery_index ON public.webhook_delivery_attempts USING btree (delivery_id);
@@ -0,0 +1,5 @@
+-- Migration: Store message signatures
+-- Description: Keep the Ed25519 signature of each message so the signed Message proto can be
+-- rebuilt from messages.raw. Rows stored before this migration keep a NULL signature.
+
+ALTER TABLE public.messages ADD COLUMN signature bytea;
@@ -0,0 +1,21 @@
+-- Migration: Add change outbox
+-- Description: Ordered feed of the changes Waypoint applied, written in the same transaction
+-- as each change. Rows are appended under a transaction-level advisory lock so that seq order
+-- matches commit order and a reader polling `seq > cursor` never skips a row.
+
+CREATE TABLE public.change_outbox
+(
+    seq        bigint GENERATED ALWAYS AS IDENTITY,
+    created_at timestamp with time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    entity     text                                               NOT NULL, -- e.g. cast, reaction, user_data
+    operation  text                                               NOT NULL, -- e.g. add, remove, set, prune
+    fid        bigint,
+    hash       bytea,
+    payload    jsonb                                              NOT NULL,
+    CONSTRAINT change_outbox_pkey PRIMARY KEY (seq)
+);
+
+-- Retention deletes by age
+CREATE INDEX change_outbox_created_at_index ON public.change_outbox USING btree (created_at);
+-- Readers may follow a single entity type
+CREATE INDEX change_outbox_entity_seq_index ON public.change_outbox USING btree (entity, seq);
@@ -0,0 +1,9 @@
+-- Migration: Add export indexes
+-- Description: Incremental `waypoint export` runs select rows by updated_at. BRIN indexes keep
+-- that from scanning whole tables while costing almost nothing on writes, since updated_at
+-- follows insertion order closely.
+
+CREATE INDEX casts_updated_at_brin_index ON public.casts USING brin (updated_at);
+CREATE INDEX reactions_updated_at_brin_index ON public.reactions USING brin (updated_at);
+CREATE INDEX links_updated_at_brin_index ON public.links USING brin (updated_at);
+CREATE INDEX onchain_events_updated_at_brin_index ON public.onchain_events USING brin (updated_at);
@@ -0,0 +1,15 @@
+-- Migration: Add cast root orphans
+-- Description: A reply that streams in before its parent, when the Hub can't walk the chain
+-- either, is stored with NULL root_parent columns and queued here. When the parent lands, its
+-- queued descendants are given its root and removed.
+
+CREATE TABLE public.cast_root_orphans
+(
+    hash        bytea                                              NOT NULL,
+    parent_hash bytea                                              NOT NULL,
+    created_at  timestamp with time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT cast_root_orphans_pkey PRIMARY KEY (hash)
+);
+
+-- Adoption looks orphans up by the parent that just arrived
+CREATE INDEX cast_root_orphans_parent_hash_index ON public.cast_root_orphans USING btree (parent_hash);
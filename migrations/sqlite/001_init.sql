@@ -0,0 +1,518 @@
+-- SQLite schema for the `sqlite` storage backend
+-- Description: Same logical schema as the Postgres migrations in ../, adapted to SQLite types:
+--   - ids are integer rowids instead of ULIDs
+--   - timestamps are RFC 3339 UTC text, which sorts chronologically
+--   - json and bigint[] columns are JSON text
+--   - bytea columns are blobs
+
+-- Message log
+CREATE TABLE messages
+(
+    id               INTEGER PRIMARY KEY,
+    created_at       TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at       TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp"      TEXT    NOT NULL,
+    deleted_at       TEXT,
+    pruned_at        TEXT,
+    revoked_at       TEXT,
+    fid              INTEGER NOT NULL,
+    type             INTEGER NOT NULL,
+    hash_scheme      INTEGER NOT NULL,
+    signature_scheme INTEGER NOT NULL,
+    hash             BLOB    NOT NULL,
+    signer           BLOB    NOT NULL,
+    body             TEXT    NOT NULL,
+    raw              BLOB    NOT NULL,
+    CONSTRAINT messages_hash_unique UNIQUE (hash)
+);
+
+CREATE INDEX messages_fid_index ON messages (fid);
+CREATE INDEX messages_signer_index ON messages (signer);
+CREATE INDEX messages_timestamp_index ON messages ("timestamp");
+CREATE INDEX messages_fid_timestamp_type_idx ON messages (fid, "timestamp", type)
+    WHERE pruned_at IS NULL AND revoked_at IS NULL AND deleted_at IS NULL;
+
+-- Casts
+CREATE TABLE casts
+(
+    id                 INTEGER PRIMARY KEY,
+    created_at         TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at         TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp"        TEXT    NOT NULL,
+    deleted_at         TEXT,
+    fid                INTEGER,          -- Nullable to support CRDT out-of-order messages
+    parent_fid         INTEGER,
+    hash               BLOB    NOT NULL,
+    parent_hash        BLOB,
+    parent_url         TEXT,
+    text               TEXT,
+    embeds             TEXT    NOT NULL DEFAULT '[]',
+    mentions           TEXT    NOT NULL DEFAULT '[]',
+    mentions_positions TEXT    NOT NULL DEFAULT '[]',
+    type               INTEGER NOT NULL DEFAULT 0,
+    root_parent_fid    INTEGER,
+    root_parent_hash   BLOB,
+    root_parent_url    TEXT,
+    CONSTRAINT casts_hash_key UNIQUE (hash)
+);
+
+CREATE INDEX casts_fid_timestamp ON casts (fid, "timestamp");
+CREATE INDEX casts_parent_hash_index ON casts (parent_hash) WHERE parent_hash IS NOT NULL;
+CREATE INDEX casts_parent_url_index ON casts (parent_url) WHERE parent_url IS NOT NULL;
+CREATE INDEX casts_timestamp_index ON casts ("timestamp");
+CREATE INDEX casts_root_parent_hash_index ON casts (root_parent_hash) WHERE root_parent_hash IS NOT NULL;
+CREATE INDEX casts_root_parent_url_index ON casts (root_parent_url) WHERE root_parent_url IS NOT NULL;
+
+-- Reactions
+CREATE TABLE reactions
+(
+    id               INTEGER PRIMARY KEY,
+    created_at       TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at       TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp"      TEXT    NOT NULL,
+    deleted_at       TEXT,
+    fid              INTEGER,            -- Nullable to support CRDT out-of-order messages
+    target_cast_fid  INTEGER,
+    type             INTEGER NOT NULL,
+    hash             BLOB    NOT NULL,
+    target_cast_hash BLOB,
+    target_url       TEXT,
+    CONSTRAINT reactions_hash_key UNIQUE (hash)
+);
+
+CREATE INDEX reactions_active_index ON reactions (fid, type, target_cast_hash) WHERE deleted_at IS NULL;
+CREATE INDEX reactions_target_cast_hash_index ON reactions (target_cast_hash) WHERE target_cast_hash IS NOT NULL;
+CREATE INDEX reactions_target_url_index ON reactions (target_url) WHERE target_url IS NOT NULL;
+
+-- Links
+CREATE TABLE links
+(
+    id                 INTEGER PRIMARY KEY,
+    created_at         TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at         TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp"        TEXT    NOT NULL,
+    deleted_at         TEXT,
+    fid                INTEGER,          -- Nullable to support CRDT out-of-order messages
+    target_fid         INTEGER NOT NULL,
+    display_timestamp  TEXT,
+    type               TEXT    NOT NULL,
+    hash               BLOB    NOT NULL,
+    compact_state_hash BLOB,             -- Set on links materialized from a compact state
+    CONSTRAINT links_hash_key UNIQUE (hash)
+);
+
+CREATE INDEX links_fid_index ON links (fid);
+CREATE INDEX links_target_fid_index ON links (target_fid);
+CREATE INDEX links_active_index ON links (fid, target_fid, type) WHERE deleted_at IS NULL;
+CREATE INDEX links_compact_state_hash_index ON links (compact_state_hash) WHERE compact_state_hash IS NOT NULL;
+
+-- One row per (fid, type); the latest compact state by timestamp wins
+CREATE TABLE link_compact_states
+(
+    id          INTEGER PRIMARY KEY,
+    created_at  TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at  TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp" TEXT    NOT NULL,
+    deleted_at  TEXT,
+    fid         INTEGER NOT NULL,
+    type        TEXT    NOT NULL,
+    hash        BLOB    NOT NULL,
+    target_fids TEXT    NOT NULL DEFAULT '[]',
+    CONSTRAINT link_compact_states_fid_type_unique UNIQUE (fid, type)
+);
+
+-- User data
+CREATE TABLE user_data
+(
+    id          INTEGER PRIMARY KEY,
+    created_at  TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at  TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp" TEXT    NOT NULL,
+    deleted_at  TEXT,
+    fid         INTEGER NOT NULL,
+    type        INTEGER NOT NULL,
+    hash        BLOB    NOT NULL,
+    value       TEXT    NOT NULL,
+    CONSTRAINT user_data_fid_type_unique UNIQUE (fid, type)
+);
+
+-- Verifications
+CREATE TABLE verifications
+(
+    id             INTEGER PRIMARY KEY,
+    created_at     TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at     TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp"    TEXT    NOT NULL,
+    deleted_at     TEXT,
+    fid            INTEGER,              -- Nullable to support CRDT out-of-order messages
+    hash           BLOB    NOT NULL,
+    signer_address BLOB    NOT NULL,
+    block_hash     BLOB    NOT NULL,
+    signature      BLOB    NOT NULL,
+    protocol       INTEGER,
+    CONSTRAINT verifications_signer_address_fid_unique UNIQUE (signer_address, fid)
+);
+
+CREATE INDEX verifications_fid_timestamp_index ON verifications (fid, "timestamp");
+
+-- Username proofs
+CREATE TABLE username_proofs
+(
+    id          INTEGER PRIMARY KEY,
+    created_at  TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at  TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp" TEXT    NOT NULL,
+    deleted_at  TEXT,
+    fid         INTEGER NOT NULL,
+    username    TEXT    NOT NULL,
+    type        INTEGER NOT NULL,
+    signature   BLOB    NOT NULL,
+    owner       BLOB,
+    CONSTRAINT username_proofs_username_fid_unique UNIQUE (username, fid)
+);
+
+CREATE INDEX username_proofs_fid_index ON username_proofs (fid);
+
+-- Storage lending
+CREATE TABLE lend_storage
+(
+    id          INTEGER PRIMARY KEY,
+    created_at  TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at  TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp" TEXT    NOT NULL,
+    deleted_at  TEXT,
+    fid         INTEGER NOT NULL,        -- Lender FID
+    to_fid      INTEGER NOT NULL,        -- Recipient FID
+    num_units   INTEGER NOT NULL,
+    unit_type   INTEGER NOT NULL,
+    hash        BLOB    NOT NULL,
+    CONSTRAINT lend_storage_hash_key UNIQUE (hash)
+);
+
+CREATE INDEX lend_storage_fid_to_fid_index ON lend_storage (fid, to_fid);
+
+-- Frame actions
+CREATE TABLE frame_actions
+(
+    id             INTEGER PRIMARY KEY,
+    created_at     TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at     TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp"    TEXT    NOT NULL,
+    deleted_at     TEXT,
+    fid            INTEGER NOT NULL,
+    hash           BLOB    NOT NULL,
+    url            TEXT    NOT NULL,
+    button_index   INTEGER NOT NULL,
+    cast_fid       INTEGER,
+    cast_hash      BLOB,
+    input_text     TEXT,
+    state          BLOB,
+    transaction_id BLOB,
+    address        BLOB,
+    CONSTRAINT frame_actions_hash_key UNIQUE (hash)
+);
+
+CREATE INDEX frame_actions_fid_index ON frame_actions (fid);
+
+-- Normalized cast mentions and embeds; deleted_at follows the parent cast
+CREATE TABLE cast_mentions
+(
+    id          INTEGER PRIMARY KEY,
+    created_at  TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at  TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    deleted_at  TEXT,
+    fid         INTEGER NOT NULL,        -- mentioned FID
+    cast_hash   BLOB    NOT NULL,
+    author_fid  INTEGER NOT NULL,
+    position    INTEGER NOT NULL,        -- byte offset in cast text
+    "timestamp" TEXT    NOT NULL,
+    CONSTRAINT cast_mentions_cast_hash_fid_position_unique UNIQUE (cast_hash, fid, position)
+);
+
+CREATE INDEX cast_mentions_fid_timestamp_index ON cast_mentions (fid, "timestamp" DESC)
+    WHERE deleted_at IS NULL;
+
+CREATE TABLE cast_embeds
+(
+    id            INTEGER PRIMARY KEY,
+    created_at    TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at    TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    deleted_at    TEXT,
+    cast_hash     BLOB    NOT NULL,
+    author_fid    INTEGER NOT NULL,
+    position      INTEGER NOT NULL,      -- index in casts.embeds
+    kind          TEXT    NOT NULL CHECK (kind IN ('url', 'cast')),
+    url           TEXT,                  -- kind = 'url'
+    cast_fid      INTEGER,               -- kind = 'cast': author of the quoted cast
+    cast_hash_ref BLOB,                  -- kind = 'cast': hash of the quoted cast
+    "timestamp"   TEXT    NOT NULL,
+    CONSTRAINT cast_embeds_cast_hash_position_unique UNIQUE (cast_hash, position)
+);
+
+CREATE INDEX cast_embeds_url_index ON cast_embeds (url, "timestamp" DESC)
+    WHERE url IS NOT NULL AND deleted_at IS NULL;
+
+-- Channel registry derived from casts.root_parent_url
+CREATE TABLE channels
+(
+    id                  INTEGER PRIMARY KEY,
+    created_at          TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at          TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    deleted_at          TEXT,
+    url                 TEXT    NOT NULL,
+    channel_id          TEXT,
+    name                TEXT,
+    description         TEXT,
+    lead_fid            INTEGER,
+    first_cast_at       TEXT,
+    last_cast_at        TEXT,
+    cast_count          INTEGER NOT NULL DEFAULT 0,
+    reply_count         INTEGER NOT NULL DEFAULT 0,
+    metadata_updated_at TEXT,
+    CONSTRAINT channels_url_unique UNIQUE (url)
+);
+
+CREATE UNIQUE INDEX channels_channel_id_index ON channels (channel_id) WHERE channel_id IS NOT NULL;
+
+CREATE TABLE channel_activity
+(
+    id          INTEGER PRIMARY KEY,
+    created_at  TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at  TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    url         TEXT    NOT NULL,
+    day         TEXT    NOT NULL,        -- UTC, YYYY-MM-DD
+    cast_count  INTEGER NOT NULL DEFAULT 0,
+    reply_count INTEGER NOT NULL DEFAULT 0,
+    CONSTRAINT channel_activity_url_day_unique UNIQUE (url, day)
+);
+
+-- Onchain events
+CREATE TABLE onchain_events
+(
+    id              INTEGER PRIMARY KEY,
+    created_at      TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at      TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp"     TEXT    NOT NULL,
+    deleted_at      TEXT,
+    fid             INTEGER NOT NULL,
+    hash            BLOB    NOT NULL,
+    type            INTEGER NOT NULL,
+    block_number    INTEGER NOT NULL,
+    block_hash      BLOB    NOT NULL,
+    log_index       INTEGER NOT NULL,
+    tx_index        INTEGER NOT NULL,
+    tx_hash         BLOB    NOT NULL,
+    signer_address  BLOB,
+    block_timestamp TEXT    NOT NULL,
+    chain_id        INTEGER NOT NULL,
+    CONSTRAINT onchain_events_hash_key UNIQUE (hash)
+);
+
+CREATE INDEX onchain_events_fid_index ON onchain_events (fid);
+
+CREATE TABLE signer_events
+(
+    id              INTEGER PRIMARY KEY,
+    created_at      TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at      TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp"     TEXT    NOT NULL,
+    deleted_at      TEXT,
+    fid             INTEGER NOT NULL,
+    key             BLOB    NOT NULL,
+    key_type        INTEGER NOT NULL,
+    event_type      INTEGER NOT NULL,    -- ADD=1, REMOVE=2, ADMIN_RESET=3
+    metadata        BLOB,
+    metadata_type   INTEGER,
+    block_number    INTEGER NOT NULL,
+    block_hash      BLOB    NOT NULL,
+    log_index       INTEGER NOT NULL,
+    tx_index        INTEGER NOT NULL,
+    tx_hash         BLOB    NOT NULL,
+    block_timestamp TEXT    NOT NULL,
+    chain_id        INTEGER NOT NULL,
+    CONSTRAINT signer_events_tx_log_unique UNIQUE (tx_hash, log_index)
+);
+
+CREATE INDEX signer_events_fid_index ON signer_events (fid);
+CREATE INDEX signer_events_key_index ON signer_events (key);
+
+CREATE TABLE signer_migrated_events
+(
+    id              INTEGER PRIMARY KEY,
+    created_at      TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at      TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp"     TEXT    NOT NULL,
+    deleted_at      TEXT,
+    fid             INTEGER NOT NULL,
+    migrated_at     INTEGER NOT NULL,
+    block_number    INTEGER NOT NULL,
+    block_hash      BLOB    NOT NULL,
+    log_index       INTEGER NOT NULL,
+    tx_index        INTEGER NOT NULL,
+    tx_hash         BLOB    NOT NULL,
+    block_timestamp TEXT    NOT NULL,
+    chain_id        INTEGER NOT NULL,
+    CONSTRAINT signer_migrated_events_tx_log_unique UNIQUE (tx_hash, log_index)
+);
+
+CREATE TABLE id_register_events
+(
+    id               INTEGER PRIMARY KEY,
+    created_at       TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at       TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp"      TEXT    NOT NULL,
+    deleted_at       TEXT,
+    fid              INTEGER NOT NULL,
+    to_address       BLOB    NOT NULL,
+    event_type       INTEGER NOT NULL,   -- REGISTER=1, TRANSFER=2, CHANGE_RECOVERY=3
+    from_address     BLOB,
+    recovery_address BLOB,
+    block_number     INTEGER NOT NULL,
+    block_hash       BLOB    NOT NULL,
+    log_index        INTEGER NOT NULL,
+    tx_index         INTEGER NOT NULL,
+    tx_hash          BLOB    NOT NULL,
+    block_timestamp  TEXT    NOT NULL,
+    chain_id         INTEGER NOT NULL,
+    CONSTRAINT id_register_events_tx_log_unique UNIQUE (tx_hash, log_index)
+);
+
+CREATE INDEX id_register_events_fid_index ON id_register_events (fid);
+
+CREATE TABLE storage_rent_events
+(
+    id              INTEGER PRIMARY KEY,
+    created_at      TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at      TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp"     TEXT    NOT NULL,
+    deleted_at      TEXT,
+    fid             INTEGER NOT NULL,
+    payer           BLOB    NOT NULL,
+    units           INTEGER NOT NULL,
+    expiry          INTEGER NOT NULL,
+    block_number    INTEGER NOT NULL,
+    block_hash      BLOB    NOT NULL,
+    log_index       INTEGER NOT NULL,
+    tx_index        INTEGER NOT NULL,
+    tx_hash         BLOB    NOT NULL,
+    block_timestamp TEXT    NOT NULL,
+    chain_id        INTEGER NOT NULL,
+    CONSTRAINT storage_rent_events_tx_log_unique UNIQUE (tx_hash, log_index)
+);
+
+CREATE INDEX storage_rent_events_fid_index ON storage_rent_events (fid);
+
+CREATE TABLE tier_purchases
+(
+    id              INTEGER PRIMARY KEY,
+    created_at      TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at      TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    "timestamp"     TEXT    NOT NULL,
+    deleted_at      TEXT,
+    fid             INTEGER NOT NULL,
+    tier_type       INTEGER NOT NULL,    -- 0=None, 1=Pro
+    for_days        INTEGER NOT NULL,
+    payer           BLOB    NOT NULL,
+    block_number    INTEGER NOT NULL,
+    block_hash      BLOB    NOT NULL,
+    log_index       INTEGER NOT NULL,
+    tx_index        INTEGER NOT NULL,
+    tx_hash         BLOB    NOT NULL,
+    block_timestamp TEXT    NOT NULL,
+    chain_id        INTEGER NOT NULL,
+    CONSTRAINT tier_purchases_tx_log_unique UNIQUE (tx_hash, log_index)
+);
+
+CREATE INDEX tier_purchases_fid_index ON tier_purchases (fid);
+
+-- Derived registries, recomputed from the event tables
+CREATE TABLE fids
+(
+    id                         INTEGER PRIMARY KEY,
+    created_at                 TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at                 TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    deleted_at                 TEXT,
+    fid                        INTEGER NOT NULL,
+    custody_address            BLOB,
+    recovery_address           BLOB,
+    registered_at              TEXT,
+    register_block_number      INTEGER,
+    register_tx_hash           BLOB,
+    last_transfer_at           TEXT,
+    last_transfer_block_number INTEGER,
+    transfer_count             INTEGER NOT NULL DEFAULT 0,
+    last_event_block_number    INTEGER NOT NULL,
+    last_event_log_index       INTEGER NOT NULL,
+    chain_id                   INTEGER NOT NULL,
+    CONSTRAINT fids_fid_unique UNIQUE (fid)
+);
+
+CREATE INDEX fids_custody_address_index ON fids (custody_address) WHERE custody_address IS NOT NULL;
+
+CREATE TABLE signers
+(
+    id                      INTEGER PRIMARY KEY,
+    created_at              TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at              TEXT    NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    deleted_at              TEXT,
+    fid                     INTEGER NOT NULL,
+    key                     BLOB    NOT NULL,
+    key_type                INTEGER NOT NULL,
+    metadata_type           INTEGER,
+    request_fid             INTEGER,     -- decoded from SignedKeyRequest metadata
+    request_signer          BLOB,
+    added_at                TEXT,
+    removed_at              TEXT,
+    removal_type            INTEGER,     -- REMOVE=2, ADMIN_RESET=3
+    migrated_at             TEXT,
+    last_event_block_number INTEGER NOT NULL,
+    last_event_log_index    INTEGER NOT NULL,
+    chain_id                INTEGER NOT NULL,
+    CONSTRAINT signers_fid_key_unique UNIQUE (fid, key)
+);
+
+CREATE INDEX signers_active_index ON signers (fid) WHERE removed_at IS NULL;
+CREATE INDEX signers_key_index ON signers (key);
+
+-- Keep updated_at current; recursive triggers are off, so the inner UPDATE doesn't re-fire
+CREATE TRIGGER update_casts_updated_at AFTER UPDATE ON casts FOR EACH ROW
+BEGIN UPDATE casts SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_reactions_updated_at AFTER UPDATE ON reactions FOR EACH ROW
+BEGIN UPDATE reactions SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_links_updated_at AFTER UPDATE ON links FOR EACH ROW
+BEGIN UPDATE links SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_link_compact_states_updated_at AFTER UPDATE ON link_compact_states FOR EACH ROW
+BEGIN UPDATE link_compact_states SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_user_data_updated_at AFTER UPDATE ON user_data FOR EACH ROW
+BEGIN UPDATE user_data SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_verifications_updated_at AFTER UPDATE ON verifications FOR EACH ROW
+BEGIN UPDATE verifications SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_username_proofs_updated_at AFTER UPDATE ON username_proofs FOR EACH ROW
+BEGIN UPDATE username_proofs SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_lend_storage_updated_at AFTER UPDATE ON lend_storage FOR EACH ROW
+BEGIN UPDATE lend_storage SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_cast_mentions_updated_at AFTER UPDATE ON cast_mentions FOR EACH ROW
+BEGIN UPDATE cast_mentions SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_cast_embeds_updated_at AFTER UPDATE ON cast_embeds FOR EACH ROW
+BEGIN UPDATE cast_embeds SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_channels_updated_at AFTER UPDATE ON channels FOR EACH ROW
+BEGIN UPDATE channels SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_channel_activity_updated_at AFTER UPDATE ON channel_activity FOR EACH ROW
+BEGIN UPDATE channel_activity SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_fids_updated_at AFTER UPDATE ON fids FOR EACH ROW
+BEGIN UPDATE fids SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
+
+CREATE TRIGGER update_signers_updated_at AFTER UPDATE ON signers FOR EACH ROW
+BEGIN UPDATE signers SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id; END;
@@ -0,0 +1,3 @@
+-- Store message signatures alongside the raw message data (see migrations/013)
+
+ALTER TABLE messages ADD COLUMN signature BLOB;
@@ -70,8 +70,8 @@ impl ProcessorRegistry {
         self
     }
 
-    /// Process an event with all registered processors
-    pub async fn process_event(&self, event: HubEvent) -> Result<()> {
+    /// The message type whose processors handle `event`, or `None` if the registry ignores it
+    pub fn message_type(event: &HubEvent) -> Option<MessageType> {
         use proto::hub_event::Body;
 
         // Map the protobuf event type to our domain message type
@@ -104,23 +104,37 @@ impl ProcessorRegistry {
                                 i if i == ProtoMessageType::LendStorage as i32 => {
                                     MessageType::LendStorage
                                 },
-                                _ => return Ok(()), // Ignore unknown message types
+                                i if i == ProtoMessageType::FrameAction as i32 => {
+                                    MessageType::FrameAction
+                                },
+                                i if i == ProtoMessageType::LinkCompactState as i32 => {
+                                    MessageType::LinkCompactState
+                                },
+                                _ => return None, // Ignore unknown message types
                             }
                         } else {
-                            return Ok(());
+                            return None;
                         }
                     } else {
-                        return Ok(());
+                        return None;
                     }
                 } else {
-                    return Ok(());
+                    return None;
                 }
             },
             // Handle other event types directly
             i if i == HubEventType::MergeOnChainEvent as i32 => MessageType::OnchainSigner,
             i if i == HubEventType::MergeUsernameProof as i32 => MessageType::UsernameProof,
-            i if i == HubEventType::PruneMessage as i32 => return Ok(()), // Ignore prune events
-            _ => return Ok(()),                                           // Ignore unknown events
+            i if i == HubEventType::PruneMessage as i32 => return None, // Ignore prune events
+            _ => return None,                                           // Ignore unknown events
+        };
+        Some(message_type)
+    }
+
+    /// Process an event with all registered processors
+    pub async fn process_event(&self, event: HubEvent) -> Result<()> {
+        let Some(message_type) = Self::message_type(&event) else {
+            return Ok(());
         };
 
         // Get processors for this message type
@@ -2,7 +2,10 @@
 use crate::{
     app::{AppError, Result},
     config::{Config, ServiceMode},
-    database::client::Database,
+    database::{
+        client::Database,
+        store::{self, MessageStore, PostgresStore},
+    },
     hub::client::Hub,
     redis::client::Redis,
 };
@@ -20,8 +23,10 @@ pub struct AppState {
     pub hub: Option<Arc<Mutex<Hub>>>,
     /// Redis client (always required - the message bus)
     pub redis: Arc<Redis>,
-    /// Database client (required for consumer/both modes)
+    /// Postgres client (consumer/both modes with a Postgres `database.url`)
     pub database: Option<Arc<Database>>,
+    /// Storage backend for processed events (required for consumer/both modes)
+    pub store: Option<Arc<dyn MessageStore>>,
     /// Current service mode
     pub mode: ServiceMode,
 }
@@ -74,21 +79,32 @@ impl StateProvider {
             None
         };
 
-        // Initialize Database only for Consumer or Both modes
-        let database = if matches!(mode, ServiceMode::Consumer | ServiceMode::Both) {
+        // Initialize storage only for Consumer or Both modes
+        let (database, store) = if !matches!(mode, ServiceMode::Consumer | ServiceMode::Both) {
+            (None, None)
+        } else if self.config.database.is_sqlite() {
+            // SQLite has no Postgres client; only the store is available
+            let store = store::open(&self.config.database, hub.clone())
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            (None, Some(store))
+        } else {
             let db = Arc::new(
                 Database::new(&self.config.database)
                     .await
                     .map_err(|e| AppError::Database(e.to_string()))?,
             );
             db.log_connection_info();
-            Some(db)
-        } else {
-            None
+            let store: Arc<dyn MessageStore> = Arc::new(
+                PostgresStore::new(db.pool.clone(), hub.clone(), self.config.database.batch_size)
+                    .with_outbox(self.config.outbox.enabled)
+                    .with_root_parent_cache(self.config.database.root_parent_cache_size),
+            );
+            (Some(db), Some(store))
         };
 
         // Create the state
-        let state = AppState { hub, redis, database, mode };
+        let state = AppState { hub, redis, database, store, mode };
 
         Ok(Arc::new(state))
     }
@@ -0,0 +1,278 @@
+//! Continuous anti-entropy against the Hub.
+//!
+//! Backfills fix drift once; anti-entropy keeps looking for it. FIDs are sampled
+//! round-robin over the FID space, interleaved with recently active ("hot") FIDs,
+//! and each one gets a cheap fingerprint check before anything is re-fetched: the
+//! Hub's per-store message counts from `GetCurrentStorageLimitsByFid` against row
+//! counts in Postgres. Only a FID whose counts disagree is re-ingested with
+//! [`MessageReconciler::reconcile_fid`].
+//!
+//! Postgres keeps pruned messages and fname proofs the Hub no longer counts, so it
+//! may legitimately hold more rows than the Hub holds messages. Only a shortfall is
+//! drift. A missed remove doesn't change either count and isn't caught here; the
+//! [audit](super::audit) compares message by message.
+
+use crate::{
+    backfill::reconciler::MessageReconciler,
+    database::audit::{self as db, MessageCounts},
+    hub::client::{Error as HubError, Hub},
+    metrics,
+    processor::consumer::EventProcessor,
+    proto::{FidRequest, StorageLimitsResponse, StoreType},
+};
+use sqlx::PgPool;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+};
+use tracing::info;
+
+/// Checks per message type the drift rate is computed over
+const DRIFT_WINDOW: usize = 1000;
+
+/// Hub stores compared, with the [`MessageKind`](super::reconciler::MessageKind) names
+/// used in metrics
+const STORES: [(StoreType, &str); 7] = [
+    (StoreType::Casts, "casts"),
+    (StoreType::Reactions, "reactions"),
+    (StoreType::Links, "links"),
+    (StoreType::Verifications, "verifications"),
+    (StoreType::UserData, "user_data"),
+    (StoreType::UsernameProofs, "username_proofs"),
+    (StoreType::StorageLends, "lend_storage"),
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum AntiEntropyError {
+    #[error("Hub error: {0}")]
+    Hub(#[from] HubError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Message counts for one type of one FID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeCount {
+    pub message_type: &'static str,
+    pub hub: u64,
+    pub postgres: u64,
+}
+
+impl TypeCount {
+    /// Whether Postgres is missing messages the Hub holds
+    pub fn drifted(&self) -> bool {
+        self.postgres < self.hub
+    }
+}
+
+/// Pair the Hub's per-store usage with the Postgres row counts
+pub fn compare_counts(limits: &StorageLimitsResponse, counts: &MessageCounts) -> Vec<TypeCount> {
+    STORES
+        .iter()
+        .map(|&(store, message_type)| {
+            let hub = limits
+                .limits
+                .iter()
+                .filter(|limit| limit.store_type == store as i32)
+                .map(|limit| limit.used)
+                .sum();
+            let postgres = match store {
+                StoreType::Casts => counts.casts,
+                StoreType::Reactions => counts.reactions,
+                StoreType::Links => counts.links,
+                StoreType::Verifications => counts.verifications,
+                StoreType::UserData => counts.user_data,
+                StoreType::UsernameProofs => counts.username_proofs,
+                StoreType::StorageLends => counts.lend_storage,
+                StoreType::None => 0,
+            };
+            TypeCount { message_type, hub, postgres: postgres.max(0) as u64 }
+        })
+        .collect()
+}
+
+/// Picks the next FID to check: round-robin over `1..=max_fid`, with a share of the
+/// picks going to hot FIDs while any are queued
+#[derive(Debug, Clone)]
+pub struct FidSampler {
+    cursor: u64,
+    max_fid: u64,
+    hot: VecDeque<u64>,
+    hot_share: f64,
+    hot_credit: f64,
+}
+
+impl FidSampler {
+    /// `hot_share` is the fraction of picks that go to hot FIDs, from 0 to 1
+    pub fn new(max_fid: u64, hot_share: f64) -> Self {
+        Self {
+            cursor: 0,
+            max_fid,
+            hot: VecDeque::new(),
+            hot_share: hot_share.clamp(0.0, 1.0),
+            hot_credit: 0.0,
+        }
+    }
+
+    pub fn set_max_fid(&mut self, max_fid: u64) {
+        self.max_fid = max_fid;
+    }
+
+    /// Replace the queued hot FIDs, most important first
+    pub fn set_hot_fids(&mut self, fids: impl IntoIterator<Item = u64>) {
+        self.hot = fids.into_iter().collect();
+    }
+
+    /// Where the round-robin pass is, for logging
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+
+    pub fn next_fid(&mut self) -> Option<u64> {
+        if !self.hot.is_empty() {
+            self.hot_credit += self.hot_share;
+            if self.hot_credit >= 1.0 {
+                self.hot_credit -= 1.0;
+                return self.hot.pop_front();
+            }
+        }
+
+        if self.max_fid == 0 {
+            return self.hot.pop_front();
+        }
+        self.cursor = if self.cursor >= self.max_fid { 1 } else { self.cursor + 1 };
+        Some(self.cursor)
+    }
+}
+
+/// Drift rate per message type over the last [`DRIFT_WINDOW`] checks
+#[derive(Debug, Default)]
+pub struct DriftTracker {
+    recent: BTreeMap<&'static str, VecDeque<bool>>,
+}
+
+impl DriftTracker {
+    /// Record a check and return the type's updated drift rate
+    pub fn record(&mut self, message_type: &'static str, drifted: bool) -> f64 {
+        let recent = self.recent.entry(message_type).or_default();
+        if recent.len() == DRIFT_WINDOW {
+            recent.pop_front();
+        }
+        recent.push_back(drifted);
+        recent.iter().filter(|&&drifted| drifted).count() as f64 / recent.len() as f64
+    }
+}
+
+/// What checking one FID found
+#[derive(Debug, Clone)]
+pub struct CheckOutcome {
+    pub fid: u64,
+    pub counts: Vec<TypeCount>,
+    pub reconciled: bool,
+}
+
+impl CheckOutcome {
+    pub fn drifted_types(&self) -> Vec<&'static str> {
+        self.counts.iter().filter(|count| count.drifted()).map(|count| count.message_type).collect()
+    }
+}
+
+/// Fingerprints FIDs and reconciles the ones that have drifted
+pub struct AntiEntropy {
+    hub: Arc<Hub>,
+    pool: PgPool,
+    reconciler: Arc<MessageReconciler>,
+    processor: Arc<dyn EventProcessor>,
+    drift: DriftTracker,
+}
+
+impl AntiEntropy {
+    pub fn new(
+        hub: Arc<Hub>,
+        pool: PgPool,
+        reconciler: Arc<MessageReconciler>,
+        processor: Arc<dyn EventProcessor>,
+    ) -> Self {
+        Self { hub, pool, reconciler, processor, drift: DriftTracker::default() }
+    }
+
+    /// Compare a FID's fingerprints and reconcile it if any type has drifted
+    pub async fn check_fid(&mut self, fid: u64) -> Result<CheckOutcome, AntiEntropyError> {
+        let limits = self
+            .hub
+            .get_current_storage_limits_by_fid(FidRequest {
+                fid,
+                page_size: None,
+                page_token: None,
+                reverse: None,
+            })
+            .await?;
+        let counts = db::message_counts(&self.pool, fid as i64).await?;
+        let counts = compare_counts(&limits, &counts);
+
+        for count in &counts {
+            let drifted = count.drifted();
+            let rate = self.drift.record(count.message_type, drifted);
+            metrics::record_anti_entropy_check(count.message_type, drifted, rate);
+        }
+
+        let mut outcome = CheckOutcome { fid, counts, reconciled: false };
+        let drifted = outcome.drifted_types();
+        if !drifted.is_empty() {
+            info!("FID {} drifted ({}); reconciling", fid, drifted.join(", "));
+            self.reconciler.reconcile_fid(fid, None, self.processor.clone()).await?;
+            metrics::increment_anti_entropy_reconciled();
+            outcome.reconciled = true;
+        }
+        Ok(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::StorageLimit;
+
+    fn limit(store: StoreType, used: u64) -> StorageLimit {
+        StorageLimit { store_type: store as i32, used, ..Default::default() }
+    }
+
+    #[test]
+    fn only_a_postgres_shortfall_is_drift() {
+        let limits = StorageLimitsResponse {
+            limits: vec![limit(StoreType::Casts, 10), limit(StoreType::Reactions, 5)],
+            ..Default::default()
+        };
+        let counts = MessageCounts { casts: 9, reactions: 7, ..Default::default() };
+
+        let compared = compare_counts(&limits, &counts);
+        assert_eq!(compared.len(), STORES.len());
+        assert_eq!(compared[0], TypeCount { message_type: "casts", hub: 10, postgres: 9 });
+        assert!(compared[0].drifted());
+        // Pruned reactions are still in Postgres
+        assert!(!compared[1].drifted());
+        assert!(compared[2..].iter().all(|count| !count.drifted()));
+    }
+
+    #[test]
+    fn sampler_interleaves_hot_fids_with_round_robin() {
+        let mut sampler = FidSampler::new(3, 0.5);
+        sampler.set_hot_fids([100, 200]);
+        let picks: Vec<u64> = (0..7).filter_map(|_| sampler.next_fid()).collect();
+        assert_eq!(picks, vec![1, 100, 2, 200, 3, 1, 2]);
+        assert_eq!(sampler.cursor(), 2);
+    }
+
+    #[test]
+    fn drift_rate_covers_recent_checks() {
+        let mut tracker = DriftTracker::default();
+        assert_eq!(tracker.record("casts", true), 1.0);
+        assert_eq!(tracker.record("casts", false), 0.5);
+        assert_eq!(tracker.record("links", false), 0.0);
+        for _ in 0..DRIFT_WINDOW {
+            tracker.record("casts", false);
+        }
+        assert_eq!(tracker.record("casts", false), 0.0);
+    }
+}
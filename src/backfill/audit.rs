@@ -0,0 +1,579 @@
+//! Read-only consistency audit of FIDs against the Hub.
+//!
+//! [`MessageReconciler::reconcile_fid`](super::reconciler::MessageReconciler::reconcile_fid)
+//! re-ingests a FID; the audit only measures how far Postgres has drifted. For
+//! each FID it fetches every message the Hub holds through the
+//! `GetAll*MessagesByFid` RPCs, adds and removes alike, plus onchain events, and
+//! compares them with the live rows in Postgres.
+//!
+//! Messages are compared by CRDT slot, the thing an add or remove applies to: a
+//! cast hash, a reaction's type and target, a link's type and target FID, a
+//! verified address or a user data type. That matches how Postgres stores removes,
+//! which sometimes tombstone the added row and sometimes insert a row of their own.
+
+use crate::{
+    core::util::sanitize_string_for_postgres,
+    database::{audit as db, batch::LinkCompactStateInsert, store::onchain_event_hash},
+    hub::client::{Error as HubError, Hub},
+    processor::format::format_eth_hex,
+    proto::{
+        FidRequest, FidTimestampRequest, Message, MessagesResponse, OnChainEvent,
+        OnChainEventRequest, OnChainEventType, link_body::Target as LinkTarget, message_data::Body,
+        reaction_body::Target as ReactionTarget,
+    },
+};
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, stream};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+const PAGE_SIZE: u32 = 1000;
+
+const REACTION_REMOVE: i32 = 4;
+const LINK_REMOVE: i32 = 6;
+
+const ONCHAIN_EVENT_TYPES: [OnChainEventType; 5] = [
+    OnChainEventType::EventTypeSigner,
+    OnChainEventType::EventTypeSignerMigrated,
+    OnChainEventType::EventTypeIdRegister,
+    OnChainEventType::EventTypeStorageRent,
+    OnChainEventType::EventTypeTierPurchase,
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuditError {
+    #[error("Hub error: {0}")]
+    Hub(#[from] HubError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// What the Hub holds for a slot
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expected {
+    /// A live message with this hash
+    Live(Vec<u8>),
+    /// A target of a link compact state. Any live link row counts; the hash is the
+    /// row the compact state would materialize.
+    Compacted(Vec<u8>),
+    /// Removed by a message with this hash
+    Removed(Vec<u8>),
+}
+
+impl Expected {
+    /// Which of two messages for the same slot decides it
+    fn rank(&self) -> u8 {
+        match self {
+            Expected::Live(_) => 0,
+            Expected::Compacted(_) => 1,
+            Expected::Removed(_) => 2,
+        }
+    }
+}
+
+type Slots = HashMap<String, Expected>;
+
+fn hash_slot(hash: &[u8]) -> String {
+    hex::encode(hash)
+}
+
+fn reaction_slot(reaction_type: i32, cast_hash: Option<&[u8]>, url: Option<&str>) -> String {
+    match (cast_hash, url) {
+        (Some(hash), _) => format!("{}:{}", reaction_type, hex::encode(hash)),
+        (None, Some(url)) => format!("{}:{}", reaction_type, sanitize_string_for_postgres(url)),
+        (None, None) => format!("{}:", reaction_type),
+    }
+}
+
+fn link_slot(link_type: &str, target_fid: i64) -> String {
+    format!("{}:{}", sanitize_string_for_postgres(link_type), target_fid)
+}
+
+/// Slots a Hub message adds to or removes from
+fn message_slots(msg: &Message) -> Vec<(String, Expected)> {
+    let Some(data) = &msg.data else {
+        return Vec::new();
+    };
+    let live = || Expected::Live(msg.hash.clone());
+    let removed = || Expected::Removed(msg.hash.clone());
+
+    let slot = match &data.body {
+        Some(Body::CastAddBody(_)) => (hash_slot(&msg.hash), live()),
+        Some(Body::CastRemoveBody(body)) => (hash_slot(&body.target_hash), removed()),
+        Some(Body::ReactionBody(reaction)) => {
+            let slot = match &reaction.target {
+                Some(ReactionTarget::TargetCastId(cast)) => {
+                    reaction_slot(reaction.r#type, Some(&cast.hash), None)
+                },
+                Some(ReactionTarget::TargetUrl(url)) => {
+                    reaction_slot(reaction.r#type, None, Some(url))
+                },
+                None => return Vec::new(),
+            };
+            (slot, if data.r#type == REACTION_REMOVE { removed() } else { live() })
+        },
+        Some(Body::LinkBody(link)) => {
+            let Some(LinkTarget::TargetFid(target_fid)) = link.target else {
+                return Vec::new();
+            };
+            let slot = link_slot(&link.r#type, target_fid as i64);
+            (slot, if data.r#type == LINK_REMOVE { removed() } else { live() })
+        },
+        Some(Body::LinkCompactStateBody(_)) => {
+            let Some(state) = LinkCompactStateInsert::from_message(msg) else {
+                return Vec::new();
+            };
+            return state
+                .target_fids
+                .iter()
+                .map(|&target_fid| {
+                    (
+                        link_slot(state.link_type, target_fid),
+                        Expected::Compacted(state.materialized_link_hash(target_fid)),
+                    )
+                })
+                .collect();
+        },
+        Some(Body::VerificationAddAddressBody(body)) => (hash_slot(&body.address), live()),
+        Some(Body::VerificationRemoveBody(body)) => (hash_slot(&body.address), removed()),
+        Some(Body::UserDataBody(body)) => (body.r#type.to_string(), live()),
+        Some(Body::LendStorageBody(_)) => (hash_slot(&msg.hash), live()),
+        // Username proofs also come from fname events, and frame actions aren't kept by hubs
+        _ => return Vec::new(),
+    };
+    vec![slot]
+}
+
+fn hub_slots(messages: &[Message]) -> Slots {
+    let mut slots = Slots::new();
+    for (slot, expected) in messages.iter().flat_map(message_slots) {
+        match slots.get(&slot) {
+            Some(existing) if existing.rank() >= expected.rank() => {},
+            _ => {
+                slots.insert(slot, expected);
+            },
+        }
+    }
+    slots
+}
+
+/// Comparison of one entity for one FID
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EntityAudit {
+    /// Live messages on the Hub
+    pub hub: usize,
+    /// Live rows in Postgres
+    pub postgres: usize,
+    /// Live on the Hub but absent or deleted in Postgres
+    pub missing: Vec<String>,
+    /// Live in Postgres but unknown to the Hub, or superseded there
+    pub extra: Vec<String>,
+    /// Removed on the Hub but still live in Postgres
+    pub not_deleted: Vec<String>,
+}
+
+impl EntityAudit {
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.not_deleted.is_empty()
+    }
+}
+
+/// Compare the Hub's slots with live Postgres rows given as `(slot, hash)`
+fn compare(hub: &Slots, postgres: &[(String, Vec<u8>)]) -> EntityAudit {
+    let mut by_slot: HashMap<&str, Vec<&[u8]>> = HashMap::new();
+    for (slot, hash) in postgres {
+        by_slot.entry(slot.as_str()).or_default().push(hash);
+    }
+
+    let mut audit = EntityAudit { postgres: postgres.len(), ..Default::default() };
+    for (slot, expected) in hub {
+        let rows = by_slot.get(slot.as_str()).map(Vec::as_slice).unwrap_or_default();
+        let (hash, present) = match expected {
+            Expected::Live(hash) => (hash, rows.contains(&hash.as_slice())),
+            Expected::Compacted(hash) => (hash, !rows.is_empty()),
+            Expected::Removed(_) => continue,
+        };
+        audit.hub += 1;
+        if !present {
+            audit.missing.push(format_eth_hex(hash));
+        }
+    }
+
+    for (slot, hash) in postgres {
+        match hub.get(slot) {
+            Some(Expected::Removed(_)) => audit.not_deleted.push(format_eth_hex(hash)),
+            Some(Expected::Live(live)) if live != hash => audit.extra.push(format_eth_hex(hash)),
+            Some(_) => {},
+            None => audit.extra.push(format_eth_hex(hash)),
+        }
+    }
+
+    audit.missing.sort_unstable();
+    audit.extra.sort_unstable();
+    audit.not_deleted.sort_unstable();
+    audit
+}
+
+/// Audit result for one FID
+#[derive(Debug, Clone, Serialize)]
+pub struct FidAudit {
+    pub fid: u64,
+    pub drifted: bool,
+    /// Set when the FID couldn't be audited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub entities: BTreeMap<&'static str, EntityAudit>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AuditSummary {
+    pub fids: usize,
+    pub drifted: usize,
+    pub errors: usize,
+    pub missing: usize,
+    pub extra: usize,
+    pub not_deleted: usize,
+}
+
+/// Report for a set of FIDs
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditReport {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub summary: AuditSummary,
+    pub fids: Vec<FidAudit>,
+    /// FIDs queued for backfill
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enqueued: Option<Vec<u64>>,
+}
+
+impl AuditReport {
+    /// FIDs with any drift
+    pub fn drifted_fids(&self) -> Vec<u64> {
+        self.fids.iter().filter(|audit| audit.drifted).map(|audit| audit.fid).collect()
+    }
+}
+
+/// Fetch every page of a `GetAll*MessagesByFid` RPC
+async fn fetch_all<F, Fut>(fid: u64, fetch: F) -> Result<Vec<Message>, HubError>
+where
+    F: Fn(FidTimestampRequest) -> Fut,
+    Fut: Future<Output = Result<MessagesResponse, HubError>>,
+{
+    let mut messages = Vec::new();
+    let mut page_token = None;
+    loop {
+        let response = fetch(FidTimestampRequest {
+            fid,
+            page_size: Some(PAGE_SIZE),
+            page_token: page_token.take(),
+            reverse: Some(false),
+            start_timestamp: None,
+            stop_timestamp: None,
+        })
+        .await?;
+        messages.extend(response.messages);
+
+        match response.next_page_token {
+            Some(token) if !token.is_empty() => page_token = Some(token),
+            _ => return Ok(messages),
+        }
+    }
+}
+
+pub struct FidAuditor {
+    hub: Arc<Hub>,
+    pool: PgPool,
+}
+
+impl FidAuditor {
+    pub fn new(hub: Arc<Hub>, pool: PgPool) -> Self {
+        Self { hub, pool }
+    }
+
+    async fn link_compact_states(&self, fid: u64) -> Result<Vec<Message>, HubError> {
+        let mut messages = Vec::new();
+        let mut page_token = None;
+        loop {
+            let response = self
+                .hub
+                .get_link_compact_state_message_by_fid(FidRequest {
+                    fid,
+                    page_size: Some(PAGE_SIZE),
+                    page_token: page_token.take(),
+                    reverse: Some(false),
+                })
+                .await?;
+            messages.extend(response.messages);
+
+            match response.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => return Ok(messages),
+            }
+        }
+    }
+
+    async fn onchain_events(&self, fid: u64) -> Result<Vec<OnChainEvent>, HubError> {
+        let mut events = Vec::new();
+        for event_type in ONCHAIN_EVENT_TYPES {
+            let mut page_token = None;
+            loop {
+                let response = self
+                    .hub
+                    .get_on_chain_events(OnChainEventRequest {
+                        fid,
+                        event_type: event_type as i32,
+                        page_size: Some(PAGE_SIZE),
+                        page_token: page_token.take(),
+                        reverse: Some(false),
+                    })
+                    .await?;
+                events.extend(response.events);
+
+                match response.next_page_token {
+                    Some(token) if !token.is_empty() => page_token = Some(token),
+                    _ => break,
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    async fn compare_fid(
+        &self,
+        fid: u64,
+    ) -> Result<BTreeMap<&'static str, EntityAudit>, AuditError> {
+        let hub = &self.hub;
+        let (casts, reactions, mut links, compact_states, verifications, user_data, lend_storage) =
+            tokio::try_join!(
+                fetch_all(fid, |request| hub.get_all_cast_messages_by_fid(request)),
+                fetch_all(fid, |request| hub.get_all_reaction_messages_by_fid(request)),
+                fetch_all(fid, |request| hub.get_all_link_messages_by_fid(request)),
+                self.link_compact_states(fid),
+                fetch_all(fid, |request| hub.get_all_verification_messages_by_fid(request)),
+                fetch_all(fid, |request| hub.get_all_user_data_messages_by_fid(request)),
+                fetch_all(fid, |request| hub.get_all_lend_storage_messages_by_fid(request)),
+            )?;
+        let onchain_events = self.onchain_events(fid).await?;
+        links.extend(compact_states);
+
+        // Read Postgres after the Hub so anything the Hub returned has had time to land
+        let rows = db::live_rows(&self.pool, fid as i64).await?;
+
+        let onchain_slots: Slots = onchain_events
+            .iter()
+            .map(|event| {
+                let hash = onchain_event_hash(event);
+                (hash_slot(&hash), Expected::Live(hash))
+            })
+            .collect();
+
+        let by_hash = |hashes: Vec<Vec<u8>>| -> Vec<(String, Vec<u8>)> {
+            hashes.into_iter().map(|hash| (hash_slot(&hash), hash)).collect()
+        };
+        let postgres_reactions: Vec<(String, Vec<u8>)> = rows
+            .reactions
+            .into_iter()
+            .map(|row| {
+                let slot = reaction_slot(
+                    row.reaction_type as i32,
+                    row.target_cast_hash.as_deref(),
+                    row.target_url.as_deref(),
+                );
+                (slot, row.hash)
+            })
+            .collect();
+        let postgres_links: Vec<(String, Vec<u8>)> = rows
+            .links
+            .into_iter()
+            .map(|row| (link_slot(&row.link_type, row.target_fid), row.hash))
+            .collect();
+        let postgres_verifications: Vec<(String, Vec<u8>)> =
+            rows.verifications.into_iter().map(|row| (hash_slot(&row.address), row.hash)).collect();
+        let postgres_user_data: Vec<(String, Vec<u8>)> =
+            rows.user_data.into_iter().map(|row| (row.data_type.to_string(), row.hash)).collect();
+
+        Ok(BTreeMap::from([
+            ("casts", compare(&hub_slots(&casts), &by_hash(rows.casts))),
+            ("reactions", compare(&hub_slots(&reactions), &postgres_reactions)),
+            ("links", compare(&hub_slots(&links), &postgres_links)),
+            ("verifications", compare(&hub_slots(&verifications), &postgres_verifications)),
+            ("user_data", compare(&hub_slots(&user_data), &postgres_user_data)),
+            ("lend_storage", compare(&hub_slots(&lend_storage), &by_hash(rows.lend_storage))),
+            ("onchain_events", compare(&onchain_slots, &by_hash(rows.onchain_events))),
+        ]))
+    }
+
+    /// Audit one FID; failures are recorded in the result
+    pub async fn audit_fid(&self, fid: u64) -> FidAudit {
+        match self.compare_fid(fid).await {
+            Ok(entities) => {
+                let drifted = entities.values().any(|audit| !audit.is_clean());
+                if drifted {
+                    info!("FID {} has drifted from the Hub", fid);
+                }
+                FidAudit { fid, drifted, error: None, entities }
+            },
+            Err(e) => {
+                warn!("Failed to audit FID {}: {}", fid, e);
+                FidAudit {
+                    fid,
+                    drifted: false,
+                    error: Some(e.to_string()),
+                    entities: BTreeMap::new(),
+                }
+            },
+        }
+    }
+
+    /// Audit FIDs, `concurrency` at a time, reporting them in the given order
+    pub async fn audit_fids(&self, fids: &[u64], concurrency: usize) -> AuditReport {
+        let started_at = Utc::now();
+        let results: Vec<FidAudit> = stream::iter(fids.iter().copied())
+            .map(|fid| self.audit_fid(fid))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+
+        let mut summary = AuditSummary { fids: results.len(), ..Default::default() };
+        for result in &results {
+            summary.drifted += result.drifted as usize;
+            summary.errors += result.error.is_some() as usize;
+            for audit in result.entities.values() {
+                summary.missing += audit.missing.len();
+                summary.extra += audit.extra.len();
+                summary.not_deleted += audit.not_deleted.len();
+            }
+        }
+
+        AuditReport { started_at, finished_at: Utc::now(), summary, fids: results, enqueued: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{
+        CastAddBody, CastId, CastRemoveBody, LinkBody, LinkCompactStateBody, MessageData,
+        ReactionBody, UserDataBody,
+    };
+
+    fn message(msg_type: i32, hash: &[u8], body: Body) -> Message {
+        Message {
+            data: Some(MessageData {
+                r#type: msg_type,
+                fid: 1,
+                timestamp: 100,
+                body: Some(body),
+                ..Default::default()
+            }),
+            hash: hash.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn like(msg_type: i32, hash: &[u8], target: &[u8]) -> Message {
+        let target = ReactionTarget::TargetCastId(CastId { fid: 2, hash: target.to_vec() });
+        message(
+            msg_type,
+            hash,
+            Body::ReactionBody(ReactionBody { r#type: 1, target: Some(target) }),
+        )
+    }
+
+    fn follow(msg_type: i32, hash: &[u8], target_fid: u64) -> Message {
+        let body = LinkBody {
+            r#type: "follow".to_string(),
+            target: Some(LinkTarget::TargetFid(target_fid)),
+            ..Default::default()
+        };
+        message(msg_type, hash, Body::LinkBody(body))
+    }
+
+    fn row(slot: String, hash: &[u8]) -> (String, Vec<u8>) {
+        (slot, hash.to_vec())
+    }
+
+    #[test]
+    fn test_casts() {
+        let hub = hub_slots(&[
+            message(1, &[1], Body::CastAddBody(CastAddBody::default())),
+            message(1, &[2], Body::CastAddBody(CastAddBody::default())),
+            message(2, &[9], Body::CastRemoveBody(CastRemoveBody { target_hash: vec![3] })),
+        ]);
+        let postgres =
+            [row(hash_slot(&[1]), &[1]), row(hash_slot(&[3]), &[3]), row(hash_slot(&[4]), &[4])];
+
+        let audit = compare(&hub, &postgres);
+        assert_eq!(audit.hub, 2);
+        assert_eq!(audit.postgres, 3);
+        assert_eq!(audit.missing, vec!["0x02"]);
+        assert_eq!(audit.extra, vec!["0x04"]);
+        assert_eq!(audit.not_deleted, vec!["0x03"]);
+        assert!(!audit.is_clean());
+    }
+
+    #[test]
+    fn test_reactions_by_target() {
+        // The Hub keeps only the remove; Postgres still has the add live
+        let hub = hub_slots(&[like(4, &[2], &[7]), like(3, &[3], &[8])]);
+        let postgres = [
+            row(reaction_slot(1, Some(&[7]), None), &[1]),
+            row(reaction_slot(1, Some(&[8]), None), &[3]),
+        ];
+
+        let audit = compare(&hub, &postgres);
+        assert_eq!(audit.hub, 1);
+        assert!(audit.missing.is_empty());
+        assert!(audit.extra.is_empty());
+        assert_eq!(audit.not_deleted, vec!["0x01"]);
+    }
+
+    #[test]
+    fn test_superseded_user_data() {
+        let body = UserDataBody { r#type: 2, value: "new".to_string() };
+        let hub = hub_slots(&[message(11, &[5], Body::UserDataBody(body))]);
+
+        let audit = compare(&hub, &[row("2".to_string(), &[4])]);
+        assert_eq!(audit.missing, vec!["0x05"]);
+        assert_eq!(audit.extra, vec!["0x04"]);
+
+        assert!(compare(&hub, &[row("2".to_string(), &[5])]).is_clean());
+    }
+
+    #[test]
+    fn test_link_compact_state() {
+        let state = message(
+            14,
+            &[6],
+            Body::LinkCompactStateBody(LinkCompactStateBody {
+                r#type: "follow".to_string(),
+                target_fids: vec![10, 11],
+            }),
+        );
+        let hub = hub_slots(&[follow(5, &[1], 10), state.clone(), follow(6, &[2], 12)]);
+        let materialized =
+            LinkCompactStateInsert::from_message(&state).unwrap().materialized_link_hash(11);
+
+        // A LinkAdd row, a materialized row and a removed link
+        let postgres = [row(link_slot("follow", 10), &[9]), row(link_slot("follow", 12), &[3])];
+        let audit = compare(&hub, &postgres);
+        assert_eq!(audit.hub, 2);
+        assert_eq!(audit.missing, vec![format_eth_hex(&materialized)]);
+        assert!(audit.extra.is_empty());
+        assert_eq!(audit.not_deleted, vec!["0x03"]);
+    }
+
+    #[test]
+    fn test_remove_outranks_add() {
+        let hub = hub_slots(&[follow(6, &[2], 10), follow(5, &[1], 10)]);
+        assert_eq!(hub.get(&link_slot("follow", 10)), Some(&Expected::Removed(vec![2])));
+    }
+}
@@ -1,15 +1,36 @@
 use crate::{
-    database::{batch::BatchInserter, client::Database},
-    processor::database::DatabaseProcessor,
-    proto::{self, Message, MessageData},
+    app::{AppState, ProcessorError, ProcessorRegistry, ProcessorResult},
+    backfill::workload::{EventKind, Workload, WorkloadCounts, WorkloadMix},
+    config::Config,
+    core::MessageType,
+    database::{
+        batch::BatchInserter,
+        client::Database,
+        store::{MessageStore, PostgresStore},
+    },
+    hub::subscriber::classify_hub_event,
+    processor::{AppResources, consumer::EventProcessor, database::DatabaseProcessor},
+    proto::{self, HubEvent, Message, MessageData},
+    redis::{error::Error as RedisError, stream::RedisStream},
+    services::streaming::Consumer,
+    types::get_stream_key,
 };
 use chrono::Utc;
+use dashmap::DashMap;
+use futures::future::try_join_all;
+use prost::Message as _;
 use rand::Rng;
+use serde::Serialize;
+use sqlx::PgConnection;
 use std::{
-    sync::Arc,
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
     time::{Duration, Instant},
 };
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 // Helper function to generate a random message of given type
 fn generate_random_message(message_type: i32, fid: u64) -> Message {
@@ -165,10 +186,16 @@ pub async fn run_benchmark(
     // Test with batch inserts at different batch sizes
     for batch_size in [50, 100, 200, 500, 1000].iter() {
         info!("Testing batch inserts with batch size {}", batch_size);
-        let batch_inserter = BatchInserter::new(&db.pool, *batch_size);
+        let mut tx = db.pool.begin().await.map_err(|e| format!("Transaction error: {}", e))?;
 
         let start = Instant::now();
-        match batch_inserter.process_message_batch(&messages).await {
+        let result =
+            BatchInserter::new(&mut tx, *batch_size).process_message_batch(&messages).await;
+        let result = match result {
+            Ok(()) => tx.commit().await.map_err(Into::into),
+            Err(e) => Err(e),
+        };
+        match result {
             Ok(_) => {
                 let duration = start.elapsed();
                 info!(
@@ -208,3 +235,452 @@ pub async fn run_benchmark(
 
     Ok(())
 }
+
+/// Stream host the pipeline benchmark publishes under, so it never touches live streams
+const PIPELINE_HOST: &str = "bench";
+const PIPELINE_GROUP: &str = "bench";
+
+#[derive(Debug, thiserror::Error)]
+pub enum PipelineBenchError {
+    #[error("Redis error: {0}")]
+    Redis(#[from] RedisError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("The pipeline benchmark needs a PostgreSQL database")]
+    NoDatabase,
+}
+
+/// What the pipeline benchmark generates and how hard it pushes
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineSettings {
+    pub seed: u64,
+    pub events: usize,
+    pub fids: u64,
+    pub mix: WorkloadMix,
+    /// Events the producer publishes per flush
+    pub batch_size: usize,
+    /// Events the consumer processes at once
+    pub concurrency: usize,
+    /// Events published per second; unthrottled when `None`
+    pub rate: Option<u64>,
+    /// Give up on events that haven't been processed after this long without progress
+    pub drain_timeout_secs: u64,
+}
+
+impl Default for PipelineSettings {
+    fn default() -> Self {
+        Self {
+            seed: 1,
+            events: 100_000,
+            fids: 10_000,
+            mix: WorkloadMix::default(),
+            batch_size: 100,
+            concurrency: 200,
+            rate: None,
+            drain_timeout_secs: 30,
+        }
+    }
+}
+
+/// Latency of one stage, in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    fn from_durations(mut durations: Vec<Duration>) -> Self {
+        if durations.is_empty() {
+            return Self::default();
+        }
+        durations.sort_unstable();
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        let percentile =
+            |q: f64| ms(durations[((durations.len() - 1) as f64 * q).round() as usize]);
+        Self {
+            count: durations.len(),
+            mean_ms: ms(durations.iter().sum::<Duration>()) / durations.len() as f64,
+            p50_ms: percentile(0.5),
+            p99_ms: percentile(0.99),
+            max_ms: durations.last().copied().map(ms).unwrap_or_default(),
+        }
+    }
+}
+
+/// What happened to the generated events
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PipelineOutcome {
+    /// Events the consumer hands to processors
+    pub dispatched: usize,
+    /// Events the consumer acknowledges without processing, such as prunes
+    pub ignored: usize,
+    /// Dispatched events processed successfully at least once
+    pub processed: usize,
+    /// Dispatched events still unprocessed when the benchmark gave up
+    pub unfinished: usize,
+    /// Failed processing attempts, including ones retried successfully
+    pub failed_attempts: usize,
+    /// Extra successful processing of an event, e.g. from the `messages` stream
+    pub duplicates: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Throughput {
+    pub wall_secs: f64,
+    /// Events published to Redis per second
+    pub published_per_sec: f64,
+    /// Dispatched events stored in PostgreSQL per second, from first publish to last write
+    pub processed_per_sec: f64,
+}
+
+/// Size and tuple counts of one table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TableStats {
+    pub table: String,
+    pub bytes: i64,
+    pub live_tuples: i64,
+    pub dead_tuples: i64,
+}
+
+/// How much a table grew during the run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TableGrowth {
+    pub table: String,
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+    pub live_tuples_added: i64,
+    pub dead_tuples_added: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DatabaseGrowth {
+    pub bytes_added: i64,
+    pub dead_tuples_added: i64,
+    /// Tables whose size or tuple counts changed
+    pub tables: Vec<TableGrowth>,
+}
+
+impl DatabaseGrowth {
+    fn between(before: &[TableStats], after: &[TableStats]) -> Self {
+        let before: HashMap<&str, &TableStats> =
+            before.iter().map(|stats| (stats.table.as_str(), stats)).collect();
+        let empty = TableStats::default();
+        let tables: Vec<TableGrowth> = after
+            .iter()
+            .map(|after| {
+                let before = before.get(after.table.as_str()).copied().unwrap_or(&empty);
+                TableGrowth {
+                    table: after.table.clone(),
+                    bytes_before: before.bytes,
+                    bytes_after: after.bytes,
+                    live_tuples_added: after.live_tuples - before.live_tuples,
+                    dead_tuples_added: after.dead_tuples - before.dead_tuples,
+                }
+            })
+            .filter(|growth| {
+                growth.bytes_after != growth.bytes_before
+                    || growth.live_tuples_added != 0
+                    || growth.dead_tuples_added != 0
+            })
+            .collect();
+        Self {
+            bytes_added: tables.iter().map(|table| table.bytes_after - table.bytes_before).sum(),
+            dead_tuples_added: tables.iter().map(|table| table.dead_tuples_added).sum(),
+            tables,
+        }
+    }
+}
+
+/// Machine-readable result of [`run_pipeline_benchmark`], meant to be diffed between releases
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineReport {
+    pub version: &'static str,
+    pub started_at: chrono::DateTime<Utc>,
+    pub settings: PipelineSettings,
+    /// Generated events by kind
+    pub workload: WorkloadCounts,
+    pub outcome: PipelineOutcome,
+    pub throughput: Throughput,
+    /// `publish`, `queue`, `process` and `end_to_end` latency of dispatched events
+    pub stages: BTreeMap<&'static str, LatencySummary>,
+    /// End-to-end latency by event kind
+    pub end_to_end_by_kind: BTreeMap<&'static str, LatencySummary>,
+    pub database: DatabaseGrowth,
+}
+
+/// When one event passed each stage
+struct EventTiming {
+    kind: EventKind,
+    created: Instant,
+    published: Option<Instant>,
+    started: Option<Instant>,
+    finished: Option<Instant>,
+}
+
+#[derive(Default)]
+struct PipelineTimings {
+    events: DashMap<u64, EventTiming>,
+    processed: AtomicUsize,
+    failed_attempts: AtomicUsize,
+    duplicates: AtomicUsize,
+}
+
+/// Database processor that records when each event reached and left it
+struct TimedProcessor {
+    processor: DatabaseProcessor,
+    timings: Arc<PipelineTimings>,
+}
+
+#[async_trait::async_trait]
+impl crate::app::EventProcessor for TimedProcessor {
+    fn name(&self) -> &str {
+        "bench"
+    }
+
+    async fn process(&self, event: HubEvent) -> ProcessorResult<()> {
+        let id = event.id;
+        let started = Instant::now();
+        let result = self.processor.process_event(event).await;
+        let finished = Instant::now();
+
+        if let Err(e) = result {
+            self.timings.failed_attempts.fetch_add(1, Ordering::Relaxed);
+            return Err(ProcessorError::Processing(e.to_string()));
+        }
+        if let Some(mut timing) = self.timings.events.get_mut(&id) {
+            if timing.finished.is_some() {
+                self.timings.duplicates.fetch_add(1, Ordering::Relaxed);
+            } else {
+                timing.started = Some(started);
+                timing.finished = Some(finished);
+                self.timings.processed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        Ok(())
+    }
+
+    fn supported_types(&self) -> Vec<MessageType> {
+        MessageType::all().collect()
+    }
+}
+
+/// Size and tuple counts of every table in the public schema
+pub async fn table_stats(conn: &mut PgConnection) -> Result<Vec<TableStats>, sqlx::Error> {
+    sqlx::query_as!(
+        TableStats,
+        r#"
+        SELECT relname::text as "table!",
+               pg_total_relation_size(relid) as "bytes!",
+               n_live_tup as "live_tuples!",
+               n_dead_tup as "dead_tuples!"
+        FROM pg_stat_user_tables
+        WHERE schemaname = 'public'
+        ORDER BY relname
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await
+}
+
+/// Drive a seeded synthetic workload through producer → Redis → consumer → PostgreSQL.
+///
+/// Events are generated up front, then published in `batch_size` flushes to `hub:bench:*`
+/// streams the same way the Hub subscriber publishes, and consumed by a [`Consumer`] whose
+/// only processor is the database processor. The streams are deleted before and after.
+/// The store is built without a Hub client, so resolving root parents never calls a live
+/// Hub and the timings only cover Redis and PostgreSQL.
+pub async fn run_pipeline_benchmark(
+    state: Arc<AppState>,
+    config: &Config,
+    settings: PipelineSettings,
+) -> Result<PipelineReport, PipelineBenchError> {
+    let database = state.database.clone().ok_or(PipelineBenchError::NoDatabase)?;
+    let store: Arc<dyn MessageStore> = Arc::new(
+        PostgresStore::new(database.pool.clone(), None, config.database.batch_size)
+            .with_outbox(config.outbox.enabled)
+            .with_root_parent_cache(config.database.root_parent_cache_size),
+    );
+    let redis_stream = Arc::new(RedisStream::new(state.redis.clone()).with_config(&config.stream));
+    let started_at = Utc::now();
+
+    info!(
+        "Generating {} events from {} FIDs (seed {})",
+        settings.events, settings.fids, settings.seed
+    );
+    let mut workload = Workload::new(settings.seed, settings.events, settings.fids, &settings.mix);
+    let events: Vec<(EventKind, HubEvent)> = workload.by_ref().collect();
+    let dispatched: HashSet<u64> = events
+        .iter()
+        .filter(|(_, event)| ProcessorRegistry::message_type(event).is_some())
+        .map(|(_, event)| event.id)
+        .collect();
+
+    let stream_keys: Vec<String> = MessageType::all()
+        .map(|message_type| get_stream_key(PIPELINE_HOST, message_type.to_stream_key()))
+        .collect();
+    for key in &stream_keys {
+        state.redis.del(key).await?;
+        // Create the groups now so nothing published before the consumer starts is skipped
+        redis_stream.create_group(key, PIPELINE_GROUP).await?;
+    }
+
+    let before = table_stats(&mut *database.pool.acquire().await?).await?;
+
+    let timings = Arc::new(PipelineTimings::default());
+    let resources =
+        AppResources::with_config_consumer_only(state.redis.clone(), store, config.clone());
+    let mut registry = ProcessorRegistry::new(state.clone());
+    registry.register(TimedProcessor {
+        processor: DatabaseProcessor::new(Arc::new(resources)),
+        timings: timings.clone(),
+    });
+    let consumer = Consumer::new(
+        redis_stream.clone(),
+        Arc::new(registry),
+        PIPELINE_HOST.to_string(),
+        PIPELINE_GROUP.to_string(),
+        config.stream.clone(),
+    )
+    .with_concurrency(settings.concurrency.max(1));
+    let shutdown = consumer.cancellation_token();
+    let consumer_handle = consumer.start().await;
+
+    let maxlen = settings.events as u64 + 1;
+    let start = Instant::now();
+    let mut published = 0usize;
+    for chunk in events.chunks(settings.batch_size.max(1)) {
+        if let Some(rate) = settings.rate.filter(|rate| *rate > 0) {
+            let due = start + Duration::from_secs_f64(published as f64 / rate as f64);
+            tokio::time::sleep_until(due.into()).await;
+        }
+
+        let created = Instant::now();
+        let mut groups: HashMap<&str, Vec<Vec<u8>>> = HashMap::new();
+        for (kind, event) in chunk {
+            timings.events.insert(
+                event.id,
+                EventTiming {
+                    kind: *kind,
+                    created,
+                    published: None,
+                    started: None,
+                    finished: None,
+                },
+            );
+            let bytes = event.encode_to_vec();
+            let (event_type, is_message_event) = classify_hub_event(event);
+            if is_message_event {
+                groups.entry("messages").or_default().push(bytes.clone());
+            }
+            groups.entry(event_type).or_default().push(bytes);
+        }
+        try_join_all(groups.into_iter().map(|(event_type, bytes)| {
+            let redis_stream = redis_stream.clone();
+            let key = get_stream_key(PIPELINE_HOST, event_type);
+            async move { redis_stream.add_batch_maxlen(&key, maxlen, bytes).await }
+        }))
+        .await?;
+
+        let now = Instant::now();
+        for (_, event) in chunk {
+            if let Some(mut timing) = timings.events.get_mut(&event.id) {
+                timing.published = Some(now);
+            }
+        }
+        published += chunk.len();
+    }
+    let publish_elapsed = start.elapsed();
+    info!("Published {} events in {:.2?}", published, publish_elapsed);
+
+    // Wait for the consumer to catch up, or to stop making progress
+    let drain_timeout = Duration::from_secs(settings.drain_timeout_secs);
+    let mut last_processed = 0;
+    let mut last_progress = Instant::now();
+    loop {
+        let processed = timings.processed.load(Ordering::Relaxed);
+        if processed >= dispatched.len() {
+            break;
+        }
+        if processed != last_processed {
+            last_processed = processed;
+            last_progress = Instant::now();
+        } else if last_progress.elapsed() >= drain_timeout {
+            warn!(
+                "Giving up on {} events after {:?} without progress",
+                dispatched.len() - processed,
+                drain_timeout
+            );
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    shutdown.cancel();
+    if tokio::time::timeout(Duration::from_secs(10), consumer_handle).await.is_err() {
+        warn!("Consumer didn't shut down within 10s");
+    }
+    for key in &stream_keys {
+        state.redis.del(key).await?;
+    }
+
+    // Let connections report their table statistics before measuring dead tuples
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let after = table_stats(&mut *database.pool.acquire().await?).await?;
+
+    let mut stages: BTreeMap<&'static str, Vec<Duration>> = BTreeMap::new();
+    let mut by_kind: BTreeMap<&'static str, Vec<Duration>> = BTreeMap::new();
+    let mut last_finished = start;
+    for timing in timings.events.iter() {
+        let (Some(published), Some(started), Some(finished)) =
+            (timing.published, timing.started, timing.finished)
+        else {
+            continue;
+        };
+        last_finished = last_finished.max(finished);
+        let end_to_end = finished.duration_since(timing.created);
+        stages.entry("publish").or_default().push(published.duration_since(timing.created));
+        stages.entry("queue").or_default().push(started.saturating_duration_since(published));
+        stages.entry("process").or_default().push(finished.duration_since(started));
+        stages.entry("end_to_end").or_default().push(end_to_end);
+        by_kind.entry(timing.kind.name()).or_default().push(end_to_end);
+    }
+
+    let processed = timings.processed.load(Ordering::Relaxed);
+    let rate = |count: usize, elapsed: Duration| {
+        if elapsed.is_zero() { 0.0 } else { count as f64 / elapsed.as_secs_f64() }
+    };
+    Ok(PipelineReport {
+        version: env!("CARGO_PKG_VERSION"),
+        started_at,
+        workload: workload.counts().clone(),
+        outcome: PipelineOutcome {
+            dispatched: dispatched.len(),
+            ignored: events.len() - dispatched.len(),
+            processed,
+            unfinished: dispatched.len().saturating_sub(processed),
+            failed_attempts: timings.failed_attempts.load(Ordering::Relaxed),
+            duplicates: timings.duplicates.load(Ordering::Relaxed),
+        },
+        throughput: Throughput {
+            wall_secs: start.elapsed().as_secs_f64(),
+            published_per_sec: rate(published, publish_elapsed),
+            processed_per_sec: rate(processed, last_finished.duration_since(start)),
+        },
+        stages: stages
+            .into_iter()
+            .map(|(stage, durations)| (stage, LatencySummary::from_durations(durations)))
+            .collect(),
+        end_to_end_by_kind: by_kind
+            .into_iter()
+            .map(|(kind, durations)| (kind, LatencySummary::from_durations(durations)))
+            .collect(),
+        database: DatabaseGrowth::between(&before, &after),
+        settings,
+    })
+}
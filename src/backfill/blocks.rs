@@ -0,0 +1,478 @@
+//! Historical backfill by replaying Snapchain blocks.
+//!
+//! Per-FID backfill asks the Hub for every message type and onchain event of every
+//! FID. Replaying blocks instead reads each shard's history once, in order, through
+//! `GetShardChunks`: every transaction in a chunk carries the user messages and system
+//! messages (onchain events and fname transfers) the shard applied at that height.
+//!
+//! A shard's heights are split into [`HeightRange`]s so ranges can run in parallel,
+//! either locally or as block jobs on the FID backfill queue. Each range records its
+//! progress in the `backfill:blocks:checkpoints` hash after every request, so a
+//! restarted replay resumes where it stopped and a finished range is skipped.
+//!
+//! Within a range, messages are applied in block order. Add messages are collected and
+//! bulk-inserted through the `DatabaseProcessor` batch path; the batch is flushed before
+//! any message or event that can't be batched, so a remove is never applied ahead of
+//! the add it removes. Prunes aren't part of blocks, so messages the Hub has pruned
+//! since are stored as if they were still live.
+
+use crate::{
+    backfill::reconciler::MessageReconciler,
+    database::batch::BATCHED_MESSAGE_TYPES,
+    hub::client::{Error as HubError, Hub},
+    processor::{consumer::EventProcessor, database::DatabaseProcessor},
+    proto::{
+        FnameTransfer, HubEvent, HubEventType, MergeUserNameProofBody, Message, MessageType,
+        OnChainEvent, ShardChunk, hub_event::Body,
+    },
+    redis::{client::Redis, error::Error as RedisError},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, info, trace, warn};
+
+pub const BLOCK_CHECKPOINT_KEY: &str = "backfill:blocks:checkpoints";
+
+/// Heights requested per `GetShardChunks` call
+const CHUNKS_PER_REQUEST: u64 = 100;
+
+/// Add messages inserted per batch
+const BATCH_SIZE: usize = 1000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BlockReplayError {
+    #[error("Hub error: {0}")]
+    Hub(#[from] HubError),
+
+    #[error("Redis error: {0}")]
+    Redis(#[from] RedisError),
+
+    #[error("{errors} entries failed replaying {range} from height {height}")]
+    Failed { range: HeightRange, height: u64, errors: u64 },
+}
+
+/// Block heights `start..end` of one shard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeightRange {
+    pub shard_id: u32,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl HeightRange {
+    /// Split `start..end` of a shard into ranges of at most `size` heights
+    pub fn split(shard_id: u32, start: u64, end: u64, size: u64) -> Vec<HeightRange> {
+        let size = size.max(1);
+        (start..end)
+            .step_by(size as usize)
+            .map(|from| HeightRange { shard_id, start: from, end: (from + size).min(end) })
+            .collect()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Field of this range in the checkpoint hash
+    fn key(&self) -> String {
+        format!("{}:{}-{}", self.shard_id, self.start, self.end)
+    }
+}
+
+impl std::fmt::Display for HeightRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "shard {} blocks {}..{}", self.shard_id, self.start, self.end)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayStats {
+    pub chunks: u64,
+    pub user_messages: u64,
+    pub system_messages: u64,
+}
+
+impl ReplayStats {
+    pub fn merge(&mut self, other: ReplayStats) {
+        self.chunks += other.chunks;
+        self.user_messages += other.user_messages;
+        self.system_messages += other.system_messages;
+    }
+}
+
+/// How far a range has been replayed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RangeCheckpoint {
+    /// First height not yet replayed
+    pub next_height: u64,
+    pub stats: ReplayStats,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Per-range checkpoints in a single Redis hash
+pub struct BlockCheckpointStore {
+    redis: Arc<Redis>,
+}
+
+impl BlockCheckpointStore {
+    pub fn new(redis: Arc<Redis>) -> Self {
+        Self { redis }
+    }
+
+    pub async fn load(&self, range: &HeightRange) -> Result<Option<RangeCheckpoint>, RedisError> {
+        match self.redis.hget(BLOCK_CHECKPOINT_KEY, &range.key()).await? {
+            Some(value) => serde_json::from_str(&value)
+                .map(Some)
+                .map_err(|e| RedisError::DeserializationError(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn save(
+        &self,
+        range: &HeightRange,
+        checkpoint: &RangeCheckpoint,
+    ) -> Result<(), RedisError> {
+        let value = serde_json::to_string(checkpoint).unwrap();
+        self.redis.hset(BLOCK_CHECKPOINT_KEY, &range.key(), &value).await
+    }
+
+    /// Forget every range's progress so the next replay starts over
+    pub async fn clear(&self) -> Result<(), RedisError> {
+        self.redis.del(BLOCK_CHECKPOINT_KEY).await.map(|_| ())
+    }
+}
+
+/// One thing a shard applied, in block order
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockEntry {
+    Message(Message),
+    OnChainEvent(OnChainEvent),
+    FnameTransfer(FnameTransfer),
+}
+
+impl BlockEntry {
+    fn is_system(&self) -> bool {
+        !matches!(self, BlockEntry::Message(_))
+    }
+
+    /// The event the processors would have received from the Hub's stream
+    fn into_hub_event(self) -> Option<HubEvent> {
+        match self {
+            BlockEntry::Message(message) => Some(MessageReconciler::merge_message_event(message)),
+            BlockEntry::OnChainEvent(event) => {
+                Some(MessageReconciler::onchain_event_to_hub_event(event))
+            },
+            BlockEntry::FnameTransfer(transfer) => fname_transfer_event(&transfer),
+        }
+    }
+}
+
+/// The height of a chunk, from its header
+pub fn chunk_height(chunk: &ShardChunk) -> Option<u64> {
+    chunk.header.as_ref()?.height.as_ref().map(|height| height.block_number)
+}
+
+/// Everything a chunk applied, in order: each transaction's system messages, then its
+/// user messages. Block events carry nothing to store and are dropped.
+pub fn decode_chunk(chunk: ShardChunk) -> Vec<BlockEntry> {
+    let mut entries = Vec::new();
+    for transaction in chunk.transactions {
+        for system in transaction.system_messages {
+            if let Some(event) = system.on_chain_event {
+                entries.push(BlockEntry::OnChainEvent(event));
+            }
+            if let Some(transfer) = system.fname_transfer {
+                entries.push(BlockEntry::FnameTransfer(transfer));
+            }
+        }
+        entries.extend(transaction.user_messages.into_iter().map(BlockEntry::Message));
+    }
+    entries
+}
+
+/// A username proof event for an fname transfer: the name leaves `from_fid` (unless
+/// it was newly registered) and goes to the proof's FID (unless it was unregistered)
+pub fn fname_transfer_event(transfer: &FnameTransfer) -> Option<HubEvent> {
+    let proof = transfer.proof.as_ref()?;
+    let deleted = (transfer.from_fid != 0)
+        .then(|| crate::proto::UserNameProof { fid: transfer.from_fid, ..proof.clone() });
+    let added = (proof.fid != 0).then(|| proof.clone());
+
+    Some(HubEvent {
+        id: 0,
+        r#type: HubEventType::MergeUsernameProof as i32,
+        body: Some(Body::MergeUsernameProofBody(MergeUserNameProofBody {
+            username_proof: added,
+            deleted_username_proof: deleted,
+            username_proof_message: None,
+            deleted_username_proof_message: None,
+        })),
+        block_number: 0,
+        shard_index: 0,
+        timestamp: 0,
+    })
+}
+
+/// Whether a message can go through the bulk insert path. User data is applied message
+/// by message, as in FID backfill, so complete profiles are built.
+fn is_batched(message: &Message) -> bool {
+    message.data.as_ref().is_some_and(|data| {
+        BATCHED_MESSAGE_TYPES.contains(&data.r#type)
+            && data.r#type != MessageType::UserDataAdd as i32
+    })
+}
+
+pub struct BlockReplayer {
+    hub: Arc<Hub>,
+    checkpoints: Option<Arc<BlockCheckpointStore>>,
+}
+
+impl BlockReplayer {
+    pub fn new(hub: Arc<Hub>) -> Self {
+        Self { hub, checkpoints: None }
+    }
+
+    /// Record progress per range and resume from it
+    pub fn with_checkpoints(mut self, checkpoints: Arc<BlockCheckpointStore>) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
+    /// Replay a range's blocks through every processor, resuming from its checkpoint
+    pub async fn replay_range(
+        &self,
+        range: HeightRange,
+        processors: &[Arc<dyn EventProcessor>],
+    ) -> Result<ReplayStats, BlockReplayError> {
+        let start_time = std::time::Instant::now();
+        let mut checkpoint = match &self.checkpoints {
+            Some(store) => store.load(&range).await?.unwrap_or_default(),
+            None => RangeCheckpoint::default(),
+        };
+        let mut next = checkpoint.next_height.max(range.start);
+        if next >= range.end {
+            debug!("{} already replayed", range);
+            return Ok(checkpoint.stats);
+        }
+        if next > range.start {
+            info!("Resuming {} from height {}", range, next);
+        }
+
+        while next < range.end {
+            let stop = (next + CHUNKS_PER_REQUEST).min(range.end);
+            let response = self.hub.get_shard_chunks(range.shard_id, next, Some(stop)).await?;
+            let mut chunks: Vec<(u64, ShardChunk)> = response
+                .shard_chunks
+                .into_iter()
+                .filter_map(|chunk| chunk_height(&chunk).map(|height| (height, chunk)))
+                .filter(|(height, _)| (next..stop).contains(height))
+                .collect();
+            chunks.sort_by_key(|(height, _)| *height);
+
+            // The Hub may return fewer chunks than asked for; resume after the last one
+            let reached = match chunks.last() {
+                Some((height, _)) => height + 1,
+                None => stop,
+            };
+
+            let mut step = ReplayStats { chunks: chunks.len() as u64, ..Default::default() };
+            let entries: Vec<BlockEntry> =
+                chunks.into_iter().flat_map(|(_, chunk)| decode_chunk(chunk)).collect();
+            let system = entries.iter().filter(|entry| entry.is_system()).count() as u64;
+            step.system_messages = system;
+            step.user_messages = entries.len() as u64 - system;
+            let mut errors = 0;
+            for processor in processors {
+                errors += apply(entries.clone(), processor).await;
+            }
+            trace!("{}: heights {}..{}: {:?}", range, next, reached, step);
+            // Keep the checkpoint before these heights, so a retry applies them again
+            if errors > 0 {
+                return Err(BlockReplayError::Failed { range, height: next, errors });
+            }
+
+            next = reached;
+            checkpoint.next_height = next;
+            checkpoint.stats.merge(step);
+            checkpoint.updated_at = Some(Utc::now());
+            if let Some(store) = &self.checkpoints {
+                store.save(&range, &checkpoint).await?;
+            }
+        }
+
+        let stats = checkpoint.stats;
+        info!(
+            "Replayed {} in {:.2?}: {} chunks, {} user messages, {} system messages",
+            range,
+            start_time.elapsed(),
+            stats.chunks,
+            stats.user_messages,
+            stats.system_messages
+        );
+        Ok(stats)
+    }
+}
+
+/// Apply entries in order, bulk-inserting runs of add messages when the processor is a
+/// `DatabaseProcessor`. Returns the number of entries that failed.
+async fn apply(entries: Vec<BlockEntry>, processor: &Arc<dyn EventProcessor>) -> u64 {
+    let database = processor.as_any().downcast_ref::<DatabaseProcessor>();
+    let mut batch: Vec<Message> = Vec::new();
+    let mut errors = 0;
+
+    for entry in entries {
+        if let (Some(_), BlockEntry::Message(message)) = (database, &entry)
+            && is_batched(message)
+        {
+            batch.push(message.clone());
+            if batch.len() >= BATCH_SIZE {
+                errors += flush(&mut batch, database, processor).await;
+            }
+            continue;
+        }
+
+        errors += flush(&mut batch, database, processor).await;
+        let Some(event) = entry.into_hub_event() else {
+            continue;
+        };
+        if let Err(e) = processor.process_event(event).await {
+            trace!("Error processing block entry: {:?}", e);
+            errors += 1;
+        }
+    }
+    errors + flush(&mut batch, database, processor).await
+}
+
+/// Bulk-insert pending add messages, falling back to one at a time if the batch fails
+async fn flush(
+    batch: &mut Vec<Message>,
+    database: Option<&DatabaseProcessor>,
+    processor: &Arc<dyn EventProcessor>,
+) -> u64 {
+    if batch.is_empty() {
+        return 0;
+    }
+    let messages = std::mem::take(batch);
+    if let Some(database) = database {
+        match database.process_message_batch(&messages, "merge").await {
+            Ok(()) => return 0,
+            Err(e) => warn!(
+                "Error batch processing {} block messages, applying individually: {:?}",
+                messages.len(),
+                e
+            ),
+        }
+    }
+
+    let mut errors = 0;
+    for message in messages {
+        if let Err(e) =
+            processor.process_event(MessageReconciler::merge_message_event(message)).await
+        {
+            trace!("Error processing block message: {:?}", e);
+            errors += 1;
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{
+        Height, MessageData, ShardHeader, Transaction, UserNameProof, ValidatorMessage,
+    };
+
+    fn message(fid: u64, r#type: MessageType) -> Message {
+        Message {
+            data: Some(MessageData { fid, r#type: r#type as i32, ..Default::default() }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn split_covers_the_heights_once() {
+        let ranges = HeightRange::split(2, 10, 35, 10);
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[0], HeightRange { shard_id: 2, start: 10, end: 20 });
+        assert_eq!(ranges[2], HeightRange { shard_id: 2, start: 30, end: 35 });
+        assert_eq!(ranges.iter().map(HeightRange::len).sum::<u64>(), 25);
+        assert_eq!(ranges[2].to_string(), "shard 2 blocks 30..35");
+        assert!(HeightRange::split(1, 5, 5, 10).is_empty());
+    }
+
+    #[test]
+    fn decode_keeps_block_order_with_system_messages_first() {
+        let signer = OnChainEvent { fid: 3, ..Default::default() };
+        let chunk = ShardChunk {
+            header: Some(ShardHeader {
+                height: Some(Height { shard_index: 1, block_number: 42 }),
+                ..Default::default()
+            }),
+            transactions: vec![
+                Transaction {
+                    fid: 3,
+                    user_messages: vec![message(3, MessageType::CastAdd)],
+                    system_messages: vec![ValidatorMessage {
+                        on_chain_event: Some(signer.clone()),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                Transaction {
+                    fid: 4,
+                    user_messages: vec![
+                        message(4, MessageType::CastAdd),
+                        message(4, MessageType::CastRemove),
+                    ],
+                    system_messages: vec![ValidatorMessage::default()],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(chunk_height(&chunk), Some(42));
+        let entries = decode_chunk(chunk);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0], BlockEntry::OnChainEvent(signer));
+        assert_eq!(entries[1], BlockEntry::Message(message(3, MessageType::CastAdd)));
+        assert_eq!(entries[3], BlockEntry::Message(message(4, MessageType::CastRemove)));
+        assert!(is_batched(&message(4, MessageType::CastAdd)));
+        assert!(!is_batched(&message(4, MessageType::CastRemove)));
+        assert!(!is_batched(&message(4, MessageType::UserDataAdd)));
+    }
+
+    #[test]
+    fn fname_transfer_moves_the_proof() {
+        let proof = UserNameProof { fid: 9, name: b"alice".to_vec(), ..Default::default() };
+        let proof_body = |transfer: &FnameTransfer| match fname_transfer_event(transfer)
+            .and_then(|event| event.body)
+        {
+            Some(Body::MergeUsernameProofBody(body)) => body,
+            other => panic!("unexpected body: {:?}", other),
+        };
+
+        let body = proof_body(&FnameTransfer { id: 1, from_fid: 5, proof: Some(proof.clone()) });
+        assert_eq!(body.username_proof, Some(proof.clone()));
+        assert_eq!(body.deleted_username_proof.map(|deleted| deleted.fid), Some(5));
+
+        // A new registration has no previous owner
+        let body = proof_body(&FnameTransfer { id: 2, from_fid: 0, proof: Some(proof.clone()) });
+        assert!(body.deleted_username_proof.is_none());
+
+        // An unregistration moves the name to FID 0
+        let unregistered = UserNameProof { fid: 0, ..proof };
+        let body = proof_body(&FnameTransfer { id: 3, from_fid: 9, proof: Some(unregistered) });
+        assert!(body.username_proof.is_none());
+        assert_eq!(body.deleted_username_proof.map(|deleted| deleted.fid), Some(9));
+
+        assert!(fname_transfer_event(&FnameTransfer::default()).is_none());
+    }
+}
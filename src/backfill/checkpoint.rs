@@ -0,0 +1,191 @@
+//! Per-FID backfill checkpoints.
+//!
+//! While a FID is reconciled page by page, each message type's progress is kept in a
+//! Redis hash at `backfill:fid:checkpoint:{fid}`: one field per type holding the next
+//! Hub page token. A worker that restarts mid-FID resumes from those tokens instead of
+//! the first page. The hash is deleted once the FID completes, so any hash that exists
+//! belongs to a FID that is partially done.
+
+use crate::redis::{client::Redis, error::Error};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
+
+pub const CHECKPOINT_KEY_PREFIX: &str = "backfill:fid:checkpoint:";
+
+/// Abandoned checkpoints (e.g. a FID whose job was never retried) expire after a week
+const CHECKPOINT_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Progress through one message type for one FID
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TypeCheckpoint {
+    /// Hex-encoded Hub page token for the next page, if any
+    pub page_token: Option<String>,
+    pub pages: u64,
+    pub messages: u64,
+    pub done: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl TypeCheckpoint {
+    /// Decoded page token to resume from
+    pub fn next_page_token(&self) -> Option<Vec<u8>> {
+        self.page_token.as_deref().and_then(|token| hex::decode(token).ok())
+    }
+
+    /// Record a processed page and the token the Hub returned with it
+    pub fn advance(&mut self, messages: usize, next_page_token: Option<Vec<u8>>) {
+        self.pages += 1;
+        self.messages += messages as u64;
+        match next_page_token {
+            Some(token) if !token.is_empty() => self.page_token = Some(hex::encode(token)),
+            _ => {
+                self.page_token = None;
+                self.done = true;
+            },
+        }
+        self.updated_at = Some(Utc::now());
+    }
+}
+
+/// Saved progress for one FID, keyed by message type name
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FidCheckpoint {
+    pub fid: u64,
+    pub types: BTreeMap<String, TypeCheckpoint>,
+}
+
+impl FidCheckpoint {
+    pub fn get(&self, message_type: &str) -> Option<&TypeCheckpoint> {
+        self.types.get(message_type)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Names of the types that have started but not finished
+    pub fn partial_types(&self) -> Vec<&str> {
+        self.types.iter().filter(|(_, state)| !state.done).map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Most recent update across all types
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.types.values().filter_map(|state| state.updated_at).max()
+    }
+}
+
+pub fn checkpoint_key(fid: u64) -> String {
+    format!("{}{}", CHECKPOINT_KEY_PREFIX, fid)
+}
+
+/// Redis-backed store for [`FidCheckpoint`]s
+pub struct CheckpointStore {
+    redis: Arc<Redis>,
+}
+
+impl CheckpointStore {
+    pub fn new(redis: Arc<Redis>) -> Self {
+        Self { redis }
+    }
+
+    /// Load the saved progress for a FID; empty if it has none
+    pub async fn load(&self, fid: u64) -> Result<FidCheckpoint, Error> {
+        let fields = self.redis.hgetall(&checkpoint_key(fid)).await?;
+        let mut types = BTreeMap::new();
+        for (message_type, value) in fields {
+            let state: TypeCheckpoint = serde_json::from_str(&value)
+                .map_err(|e| Error::DeserializationError(e.to_string()))?;
+            types.insert(message_type, state);
+        }
+        Ok(FidCheckpoint { fid, types })
+    }
+
+    /// Save progress for one message type, refreshing the expiry
+    pub async fn save(
+        &self,
+        fid: u64,
+        message_type: &str,
+        state: &TypeCheckpoint,
+    ) -> Result<(), Error> {
+        let key = checkpoint_key(fid);
+        let value = serde_json::to_string(state).unwrap();
+        self.redis.hset(&key, message_type, &value).await?;
+        self.redis.expire(&key, CHECKPOINT_TTL_SECS).await
+    }
+
+    /// Forget a FID's progress once it has been fully reconciled
+    pub async fn clear(&self, fid: u64) -> Result<(), Error> {
+        self.redis.del(&checkpoint_key(fid)).await.map(|_| ())
+    }
+
+    /// Every FID with saved progress, ordered by FID
+    pub async fn list(&self) -> Result<Vec<FidCheckpoint>, Error> {
+        let pattern = format!("{}*", CHECKPOINT_KEY_PREFIX);
+        let mut fids: Vec<u64> = self
+            .redis
+            .keys(&pattern)
+            .await?
+            .iter()
+            .filter_map(|key| key.strip_prefix(CHECKPOINT_KEY_PREFIX)?.parse().ok())
+            .collect();
+        fids.sort_unstable();
+        fids.dedup();
+
+        let mut checkpoints = Vec::with_capacity(fids.len());
+        for fid in fids {
+            let checkpoint = self.load(fid).await?;
+            // The key may have been cleared between the scan and the load
+            if !checkpoint.is_empty() {
+                checkpoints.push(checkpoint);
+            }
+        }
+        Ok(checkpoints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance_stores_token_until_last_page() {
+        let mut state = TypeCheckpoint::default();
+        state.advance(1000, Some(vec![0xab, 0x01]));
+        assert_eq!(state.page_token.as_deref(), Some("ab01"));
+        assert_eq!(state.next_page_token(), Some(vec![0xab, 0x01]));
+        assert!(!state.done);
+
+        state.advance(10, Some(Vec::new()));
+        assert_eq!(state.pages, 2);
+        assert_eq!(state.messages, 1010);
+        assert!(state.done);
+        assert_eq!(state.next_page_token(), None);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut state = TypeCheckpoint::default();
+        state.advance(1000, Some(vec![1, 2, 3]));
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<TypeCheckpoint>(&json).unwrap(), state);
+    }
+
+    #[test]
+    fn partial_types_skip_finished_ones() {
+        let mut reactions = TypeCheckpoint::default();
+        reactions.advance(1000, Some(vec![1]));
+        let mut casts = TypeCheckpoint::default();
+        casts.advance(5, None);
+
+        let checkpoint = FidCheckpoint {
+            fid: 3,
+            types: BTreeMap::from([
+                ("casts".to_string(), casts),
+                ("reactions".to_string(), reactions),
+            ]),
+        };
+        assert_eq!(checkpoint.partial_types(), vec!["reactions"]);
+        assert_eq!(checkpoint_key(3), "backfill:fid:checkpoint:3");
+    }
+}
@@ -0,0 +1,218 @@
+//! Backfill of whole conversations rather than whole FIDs.
+//!
+//! A thread job fetches a root cast, walks its replies breadth first with
+//! `GetCastsByParent` and fetches the reactions on every cast it finds. A channel job
+//! pages through the casts under a parent URL, newest first, and stops once it passes
+//! the start of the job's window. Only the casts and reactions that make up the
+//! conversation are fetched, not the participating FIDs' full histories.
+
+use crate::{
+    backfill::reconciler::{MessageReconciler, ReconcileStats, TimeWindow},
+    hub::client::{Error, Hub},
+    processor::consumer::EventProcessor,
+    proto::{
+        CastId, CastsByParentRequest, Message, MessagesResponse, ReactionsByTargetRequest,
+        casts_by_parent_request::Parent, reactions_by_target_request::Target,
+    },
+};
+use futures::{StreamExt, stream};
+use std::{collections::VecDeque, future::Future, sync::Arc};
+use tracing::{info, trace, warn};
+
+const PAGE_SIZE: u32 = 1000;
+
+/// Casts one thread job descends into; replies past this are stored but not walked
+const THREAD_CAST_LIMIT: usize = 50_000;
+
+/// The ID of a cast message, for walking its replies and reactions
+pub fn cast_id(message: &Message) -> Option<CastId> {
+    let data = message.data.as_ref()?;
+    (!message.hash.is_empty()).then(|| CastId { fid: data.fid, hash: message.hash.clone() })
+}
+
+pub struct ConversationReconciler {
+    hub: Arc<Hub>,
+}
+
+impl ConversationReconciler {
+    pub fn new(hub: Arc<Hub>) -> Self {
+        Self { hub }
+    }
+
+    /// Reconcile a root cast, every reply below it and the reactions on each of them
+    pub async fn reconcile_thread(
+        &self,
+        root: CastId,
+        processor: Arc<dyn EventProcessor>,
+    ) -> Result<ReconcileStats, Error> {
+        let start_time = std::time::Instant::now();
+        let root_hash = hex::encode(&root.hash);
+        let mut stats = process(vec![self.hub.get_cast(root.clone()).await?], &processor).await;
+
+        let mut pending = VecDeque::from([root]);
+        let mut casts = 1;
+        while let Some(cast) = pending.pop_front() {
+            let replies = self
+                .all_pages(|page_token| {
+                    self.hub.get_casts_by_parent(CastsByParentRequest {
+                        parent: Some(Parent::ParentCastId(cast.clone())),
+                        page_size: Some(PAGE_SIZE),
+                        page_token,
+                        reverse: None,
+                    })
+                })
+                .await?;
+            for reply in &replies {
+                if casts == THREAD_CAST_LIMIT {
+                    warn!(
+                        "Thread 0x{} has more than {} casts; not walking further replies",
+                        root_hash, THREAD_CAST_LIMIT
+                    );
+                }
+                if casts < THREAD_CAST_LIMIT
+                    && let Some(id) = cast_id(reply)
+                {
+                    pending.push_back(id);
+                }
+                casts += 1;
+            }
+            stats.merge(process(replies, &processor).await);
+
+            let reactions = self
+                .all_pages(|page_token| {
+                    self.hub.get_reactions_by_cast(ReactionsByTargetRequest {
+                        target: Some(Target::TargetCastId(cast.clone())),
+                        reaction_type: None,
+                        page_size: Some(PAGE_SIZE),
+                        page_token,
+                        reverse: None,
+                    })
+                })
+                .await?;
+            stats.merge(process(reactions, &processor).await);
+        }
+
+        info!(
+            "Reconciled thread 0x{} in {:.2?}: {} casts, {} messages ({} failed)",
+            root_hash,
+            start_time.elapsed(),
+            casts,
+            stats.success,
+            stats.errors
+        );
+        Ok(stats)
+    }
+
+    /// Reconcile the casts under a parent URL inside `window`, paging newest first
+    pub async fn reconcile_channel(
+        &self,
+        url: &str,
+        window: Option<TimeWindow>,
+        processor: Arc<dyn EventProcessor>,
+    ) -> Result<ReconcileStats, Error> {
+        let start_time = std::time::Instant::now();
+        let window = window.unwrap_or_default();
+        let mut stats = ReconcileStats::default();
+        let mut page_token = None;
+        let mut pages = 0;
+
+        loop {
+            let response = self
+                .hub
+                .get_casts_by_parent(CastsByParentRequest {
+                    parent: Some(Parent::ParentUrl(url.to_string())),
+                    page_size: Some(PAGE_SIZE),
+                    page_token,
+                    reverse: Some(true),
+                })
+                .await?;
+            pages += 1;
+
+            let passed_start = response.messages.iter().any(|cast| window.is_before_start(cast));
+            let casts: Vec<Message> = response
+                .messages
+                .into_iter()
+                .filter(|cast| window.contains_message(cast))
+                .collect();
+            trace!("Channel {} page {}: {} casts in the window", url, pages, casts.len());
+            stats.merge(process(casts, &processor).await);
+
+            match response.next_page_token {
+                Some(token) if !token.is_empty() && !passed_start => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        info!(
+            "Reconciled channel {} ({}) in {:.2?}: {} casts over {} pages ({} failed)",
+            url,
+            window,
+            start_time.elapsed(),
+            stats.success,
+            pages,
+            stats.errors
+        );
+        Ok(stats)
+    }
+
+    /// Follow the Hub's page tokens until the last page
+    async fn all_pages<F, Fut>(&self, mut fetch: F) -> Result<Vec<Message>, Error>
+    where
+        F: FnMut(Option<Vec<u8>>) -> Fut,
+        Fut: Future<Output = Result<MessagesResponse, Error>>,
+    {
+        let mut messages = Vec::new();
+        let mut page_token = None;
+        loop {
+            let response = fetch(page_token).await?;
+            messages.extend(response.messages);
+            match response.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => return Ok(messages),
+            }
+        }
+    }
+}
+
+/// Merge messages through the processor, ten at a time
+async fn process(messages: Vec<Message>, processor: &Arc<dyn EventProcessor>) -> ReconcileStats {
+    let results: Vec<_> =
+        stream::iter(messages)
+            .map(|message| {
+                let processor = Arc::clone(processor);
+                async move {
+                    processor.process_event(MessageReconciler::merge_message_event(message)).await
+                }
+            })
+            .buffer_unordered(10)
+            .collect()
+            .await;
+
+    results.into_iter().fold(ReconcileStats::default(), |mut stats, result| {
+        match result {
+            Ok(_) => stats.success += 1,
+            Err(e) => {
+                trace!("Error processing conversation message: {:?}", e);
+                stats.errors += 1;
+            },
+        }
+        stats
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cast_id_needs_data_and_hash() {
+        let cast = Message {
+            data: Some(crate::proto::MessageData { fid: 3, ..Default::default() }),
+            hash: vec![0xab; 20],
+            ..Default::default()
+        };
+        assert_eq!(cast_id(&cast), Some(CastId { fid: 3, hash: vec![0xab; 20] }));
+        assert_eq!(cast_id(&Message { hash: vec![1], ..Default::default() }), None);
+        assert_eq!(cast_id(&Message { data: cast.data.clone(), ..Default::default() }), None);
+    }
+}
@@ -1,5 +1,14 @@
+pub mod anti_entropy;
+pub mod audit;
 pub mod bench;
+pub mod blocks;
+pub mod checkpoint;
+pub mod conversation;
 pub mod onchain_events;
+pub mod priority;
+pub mod progress;
 pub mod reconciler;
 pub mod root_parent;
+pub mod trie_sync;
 pub mod worker;
+pub mod workload;
@@ -1,4 +1,3 @@
-use crate::database::client::Database;
 use crate::database::error::Error;
 use crate::hub::client::Hub;
 use crate::processor::database::DatabaseProcessor;
@@ -8,13 +7,12 @@ use tracing::{error, info, trace, warn};
 
 pub struct OnChainEventBackfiller {
     pub hub: Arc<Hub>,
-    pub database: Arc<Database>,
     pub processor: Arc<DatabaseProcessor>,
 }
 
 impl OnChainEventBackfiller {
-    pub fn new(hub: Arc<Hub>, database: Arc<Database>, processor: Arc<DatabaseProcessor>) -> Self {
-        Self { hub, database, processor }
+    pub fn new(hub: Arc<Hub>, processor: Arc<DatabaseProcessor>) -> Self {
+        Self { hub, processor }
     }
 
     /// Backfill all missing onchain events for a specific FID
@@ -0,0 +1,131 @@
+//! Strategies for choosing which FIDs to backfill first.
+//!
+//! Each strategy returns FIDs most important first; `waypoint backfill fid queue` splits
+//! them into jobs in that order, by default on the High priority queue.
+
+use crate::database::priority;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::{collections::HashSet, path::PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PriorityError {
+    #[error("Failed to read FID list: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid FID {0:?} in FID list")]
+    InvalidFid(String),
+
+    #[error("The {0} strategy needs a database connection")]
+    DatabaseRequired(&'static str),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PriorityStrategy {
+    /// FIDs listed in a file, in file order
+    File(PathBuf),
+    /// Most-followed FIDs first, counted from `links`
+    Followers,
+    /// FIDs the live stream has seen activity from since the given time, most active first
+    RecentlyActive { since: DateTime<Utc> },
+    /// Registered FIDs without any `user_data`, i.e. no profile
+    MissingUserData,
+}
+
+impl PriorityStrategy {
+    pub const NAMES: [&'static str; 4] = ["file", "followers", "active", "missing-user-data"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PriorityStrategy::File(_) => "file",
+            PriorityStrategy::Followers => "followers",
+            PriorityStrategy::RecentlyActive { .. } => "active",
+            PriorityStrategy::MissingUserData => "missing-user-data",
+        }
+    }
+
+    pub fn needs_database(&self) -> bool {
+        !matches!(self, PriorityStrategy::File(_))
+    }
+
+    /// Up to `limit` FIDs, most important first
+    pub async fn select_fids(
+        &self,
+        pool: Option<&PgPool>,
+        limit: usize,
+    ) -> Result<Vec<u64>, PriorityError> {
+        let fids = match self {
+            PriorityStrategy::File(path) => {
+                let text = tokio::fs::read_to_string(path).await?;
+                let mut fids = parse_fid_list(&text)?;
+                fids.truncate(limit);
+                return Ok(fids);
+            },
+            PriorityStrategy::Followers => {
+                priority::most_followed_fids(self.pool(pool)?, limit as i64).await?
+            },
+            PriorityStrategy::RecentlyActive { since } => {
+                priority::recently_active_fids(self.pool(pool)?, *since, limit as i64).await?
+            },
+            PriorityStrategy::MissingUserData => {
+                priority::fids_missing_user_data(self.pool(pool)?, limit as i64).await?
+            },
+        };
+        Ok(fids.into_iter().filter(|&fid| fid > 0).map(|fid| fid as u64).collect())
+    }
+
+    fn pool<'a>(&self, pool: Option<&'a PgPool>) -> Result<&'a PgPool, PriorityError> {
+        pool.ok_or(PriorityError::DatabaseRequired(self.name()))
+    }
+}
+
+/// Parse FIDs separated by commas or whitespace, one list per line. `#` starts a comment.
+/// Duplicates are dropped, keeping the first occurrence.
+pub fn parse_fid_list(text: &str) -> Result<Vec<u64>, PriorityError> {
+    let mut seen = HashSet::new();
+    let mut fids = Vec::new();
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        for token in line.split(|c: char| c == ',' || c.is_whitespace()) {
+            if token.is_empty() {
+                continue;
+            }
+            let fid: u64 =
+                token.parse().map_err(|_| PriorityError::InvalidFid(token.to_string()))?;
+            if seen.insert(fid) {
+                fids.push(fid);
+            }
+        }
+    }
+    Ok(fids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fid_lists_in_order() {
+        let text = "# VIPs\n3, 2\n\n194 3  # dupe\n5\t6\n";
+        assert_eq!(parse_fid_list(text).unwrap(), vec![3, 2, 194, 5, 6]);
+    }
+
+    #[test]
+    fn rejects_invalid_fids() {
+        assert!(matches!(
+            parse_fid_list("1\ndwr\n"),
+            Err(PriorityError::InvalidFid(token)) if token == "dwr"
+        ));
+    }
+
+    #[tokio::test]
+    async fn database_strategies_need_a_pool() {
+        assert!(matches!(
+            PriorityStrategy::Followers.select_fids(None, 10).await,
+            Err(PriorityError::DatabaseRequired("followers"))
+        ));
+    }
+}
@@ -0,0 +1,287 @@
+//! Structured backfill progress shared through Redis.
+//!
+//! Totals for the whole backfill live in the `backfill:progress` hash: FIDs queued,
+//! FIDs done and failed, and error counts by kind. The queue adds to `fids_queued`
+//! whenever new work is enqueued, and workers add to the rest as jobs finish, so the
+//! totals survive worker restarts. Each running worker also publishes a snapshot of
+//! itself (current jobs, rate, errors) to `backfill:progress:worker:{id}`, which
+//! expires shortly after the worker stops refreshing it. [`ProgressStore::load`]
+//! combines both into a [`BackfillProgress`].
+
+use crate::{
+    backfill::worker::QueueMetrics,
+    redis::{client::Redis, error::Error},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+pub const PROGRESS_KEY: &str = "backfill:progress";
+pub const WORKER_KEY_PREFIX: &str = "backfill:progress:worker:";
+
+/// Worker snapshots outlive a stopped worker by this long
+const WORKER_TTL_SECS: i64 = 60;
+
+const FIDS_QUEUED: &str = "fids_queued";
+const FIDS_DONE: &str = "fids_done";
+const FIDS_FAILED: &str = "fids_failed";
+const ERROR_FIELD_PREFIX: &str = "errors:";
+const SNAPSHOT_FIELD: &str = "snapshot";
+
+/// What went wrong, for the error breakdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Some FIDs in a batch failed to reconcile
+    Reconcile,
+    /// A whole batch failed to reconcile
+    Batch,
+    /// Onchain events for a spam FID failed to reconcile
+    OnchainEvents,
+    /// Taking a job from the queue failed
+    Queue,
+    /// A thread or channel job failed
+    Conversation,
+    /// A block range job failed
+    Blocks,
+}
+
+impl ErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Reconcile => "reconcile",
+            ErrorKind::Batch => "batch",
+            ErrorKind::OnchainEvents => "onchain_events",
+            ErrorKind::Queue => "queue",
+            ErrorKind::Conversation => "conversation",
+            ErrorKind::Blocks => "blocks",
+        }
+    }
+}
+
+/// A job a worker is processing right now
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrentJob {
+    pub id: String,
+    pub fids: usize,
+    pub first_fid: Option<u64>,
+    pub last_fid: Option<u64>,
+    /// The thread, channel or block range of a job that isn't a list of FIDs
+    #[serde(default)]
+    pub target: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// One worker's view of its own progress since it started
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkerProgress {
+    pub worker_id: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub jobs_processed: u64,
+    pub fids_processed: u64,
+    pub spam_fids_skipped: u64,
+    pub fids_per_second: f64,
+    pub errors: BTreeMap<String, u64>,
+    pub current_jobs: Vec<CurrentJob>,
+}
+
+/// Combined progress across every worker
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BackfillProgress {
+    pub fids_queued: u64,
+    pub fids_done: u64,
+    pub fids_failed: u64,
+    pub pending_jobs: u64,
+    pub in_progress_jobs: u64,
+    /// Sum of the live workers' rates
+    pub fids_per_second: f64,
+    pub eta_secs: Option<u64>,
+    pub errors: BTreeMap<String, u64>,
+    pub workers: Vec<WorkerProgress>,
+}
+
+impl BackfillProgress {
+    /// Combine the shared totals, the worker snapshots and the queue sizes
+    pub fn combine(
+        totals: &HashMap<String, String>,
+        mut workers: Vec<WorkerProgress>,
+        queue: &QueueMetrics,
+    ) -> Self {
+        let total = |field: &str| -> u64 {
+            totals.get(field).and_then(|value| value.parse::<i64>().ok()).unwrap_or(0).max(0) as u64
+        };
+        let errors = totals
+            .iter()
+            .filter_map(|(field, _)| field.strip_prefix(ERROR_FIELD_PREFIX))
+            .map(|kind| (kind.to_string(), total(&format!("{}{}", ERROR_FIELD_PREFIX, kind))))
+            .collect();
+        workers.sort_by(|a, b| a.worker_id.cmp(&b.worker_id));
+
+        let mut progress = Self {
+            fids_queued: total(FIDS_QUEUED),
+            fids_done: total(FIDS_DONE),
+            fids_failed: total(FIDS_FAILED),
+            pending_jobs: queue.pending_jobs(),
+            in_progress_jobs: queue.in_progress_queue_size,
+            fids_per_second: workers.iter().map(|worker| worker.fids_per_second).sum(),
+            eta_secs: None,
+            errors,
+            workers,
+        };
+        progress.eta_secs = eta_secs(progress.fids_remaining(), progress.fids_per_second);
+        progress
+    }
+
+    pub fn fids_remaining(&self) -> u64 {
+        self.fids_queued.saturating_sub(self.fids_done + self.fids_failed)
+    }
+
+    /// Share of queued FIDs that are finished, from 0 to 100
+    pub fn percent_done(&self) -> Option<f64> {
+        (self.fids_queued > 0).then(|| {
+            ((self.fids_done + self.fids_failed) as f64 / self.fids_queued as f64 * 100.0)
+                .min(100.0)
+        })
+    }
+}
+
+/// Seconds until `remaining` FIDs are done at `fids_per_second`; `None` while stalled
+pub fn eta_secs(remaining: u64, fids_per_second: f64) -> Option<u64> {
+    if remaining == 0 {
+        return Some(0);
+    }
+    (fids_per_second > 0.0).then(|| (remaining as f64 / fids_per_second).ceil() as u64)
+}
+
+pub fn worker_key(worker_id: &str) -> String {
+    format!("{}{}", WORKER_KEY_PREFIX, worker_id)
+}
+
+/// Redis-backed store for backfill progress
+#[derive(Clone)]
+pub struct ProgressStore {
+    redis: Arc<Redis>,
+}
+
+impl ProgressStore {
+    pub fn new(redis: Arc<Redis>) -> Self {
+        Self { redis }
+    }
+
+    /// Count newly queued FIDs towards the total
+    pub async fn add_queued(&self, fids: u64) -> Result<(), Error> {
+        self.redis.hincrby(PROGRESS_KEY, FIDS_QUEUED, fids as i64).await.map(|_| ())
+    }
+
+    /// Count the FIDs of a finished job
+    pub async fn add_finished(&self, done: u64, failed: u64) -> Result<(), Error> {
+        if done > 0 {
+            self.redis.hincrby(PROGRESS_KEY, FIDS_DONE, done as i64).await?;
+        }
+        if failed > 0 {
+            self.redis.hincrby(PROGRESS_KEY, FIDS_FAILED, failed as i64).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn add_error(&self, kind: ErrorKind) -> Result<(), Error> {
+        let field = format!("{}{}", ERROR_FIELD_PREFIX, kind.as_str());
+        self.redis.hincrby(PROGRESS_KEY, &field, 1).await.map(|_| ())
+    }
+
+    /// Publish a worker's snapshot, refreshing its expiry
+    pub async fn publish_worker(&self, worker: &WorkerProgress) -> Result<(), Error> {
+        let key = worker_key(&worker.worker_id);
+        let value = serde_json::to_string(worker).unwrap();
+        self.redis.hset(&key, SNAPSHOT_FIELD, &value).await?;
+        self.redis.expire(&key, WORKER_TTL_SECS).await
+    }
+
+    /// Drop a worker's snapshot when it shuts down
+    pub async fn remove_worker(&self, worker_id: &str) -> Result<(), Error> {
+        self.redis.del(&worker_key(worker_id)).await.map(|_| ())
+    }
+
+    /// Snapshots of every worker that has published recently
+    pub async fn workers(&self) -> Result<Vec<WorkerProgress>, Error> {
+        let pattern = format!("{}*", WORKER_KEY_PREFIX);
+        let mut workers = Vec::new();
+        for key in self.redis.keys(&pattern).await? {
+            // The key may have expired between the scan and the read
+            let Some(value) = self.redis.hgetall(&key).await?.remove(SNAPSHOT_FIELD) else {
+                continue;
+            };
+            let worker = serde_json::from_str(&value)
+                .map_err(|e| Error::DeserializationError(e.to_string()))?;
+            workers.push(worker);
+        }
+        Ok(workers)
+    }
+
+    /// The combined view across all workers
+    pub async fn load(&self, queue: &QueueMetrics) -> Result<BackfillProgress, Error> {
+        let totals = self.redis.hgetall(PROGRESS_KEY).await?;
+        let workers = self.workers().await?;
+        Ok(BackfillProgress::combine(&totals, workers, queue))
+    }
+
+    /// Forget the totals, e.g. before queueing a fresh backfill
+    pub async fn reset(&self) -> Result<(), Error> {
+        self.redis.del(PROGRESS_KEY).await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(id: &str, fids_per_second: f64) -> WorkerProgress {
+        WorkerProgress { worker_id: id.to_string(), fids_per_second, ..Default::default() }
+    }
+
+    #[test]
+    fn combine_sums_worker_rates_into_an_eta() {
+        let totals: HashMap<String, String> = [
+            ("fids_queued", "1000"),
+            ("fids_done", "550"),
+            ("fids_failed", "50"),
+            ("errors:reconcile", "3"),
+            ("errors:queue", "1"),
+        ]
+        .into_iter()
+        .map(|(field, value)| (field.to_string(), value.to_string()))
+        .collect();
+        let queue = QueueMetrics {
+            normal_priority_queue_size: 7,
+            in_progress_queue_size: 2,
+            ..Default::default()
+        };
+
+        let progress =
+            BackfillProgress::combine(&totals, vec![worker("b", 1.5), worker("a", 2.5)], &queue);
+        assert_eq!(progress.fids_remaining(), 400);
+        assert_eq!(progress.percent_done(), Some(60.0));
+        assert_eq!(progress.fids_per_second, 4.0);
+        assert_eq!(progress.eta_secs, Some(100));
+        assert_eq!(progress.pending_jobs, 7);
+        assert_eq!(progress.in_progress_jobs, 2);
+        assert_eq!(progress.errors.get("reconcile"), Some(&3));
+        assert_eq!(progress.errors.get("queue"), Some(&1));
+        assert_eq!(progress.workers[0].worker_id, "a");
+    }
+
+    #[test]
+    fn eta_is_unknown_while_stalled() {
+        assert_eq!(eta_secs(10, 0.0), None);
+        assert_eq!(eta_secs(0, 0.0), Some(0));
+        assert_eq!(eta_secs(10, 3.0), Some(4));
+
+        let progress =
+            BackfillProgress::combine(&HashMap::new(), Vec::new(), &QueueMetrics::default());
+        assert_eq!(progress.percent_done(), None);
+        assert_eq!(progress.eta_secs, Some(0));
+    }
+}
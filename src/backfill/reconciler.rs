@@ -1,15 +1,24 @@
 use crate::{
-    database::client::Database,
+    backfill::checkpoint::{CheckpointStore, FidCheckpoint, TypeCheckpoint},
+    core::util::to_farcaster_time,
     hub::client::{Error, Hub},
+    metrics,
     processor::consumer::EventProcessor,
     proto::{
         self, FidRequest, FidTimestampRequest, HubEvent, HubEventType, LinksByFidRequest,
         MergeMessageBody, MergeOnChainEventBody, Message, OnChainEventRequest, OnChainEventType,
-        ReactionsByFidRequest, hub_event::Body,
+        ReactionsByFidRequest,
     },
 };
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::{sync::Arc, time::Duration};
-use tracing::{debug, error, info, trace};
+use tokio::sync::Semaphore;
+use tracing::{debug, error, info, trace, warn};
+
+/// Pages of any one message type a FID may have and still be fetched whole for batch
+/// processing. Larger FIDs are reconciled page by page with checkpoints instead.
+const BATCH_PAGE_LIMIT: usize = 10;
 
 /// Statistics from a reconciliation operation
 #[derive(Debug, Default, Clone, Copy)]
@@ -31,19 +40,150 @@ impl ReconcileStats {
     }
 }
 
+/// Time range a backfill is limited to, e.g. the gap left by a Hub outage.
+///
+/// `since` is inclusive and `until` exclusive; a missing bound is open.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeWindow {
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl TimeWindow {
+    /// `start_timestamp` for a `FidTimestampRequest`, in Farcaster time
+    fn start_timestamp(&self) -> Option<u64> {
+        self.since.map(Self::farcaster_seconds)
+    }
+
+    /// `stop_timestamp` for a `FidTimestampRequest`, in Farcaster time
+    fn stop_timestamp(&self) -> Option<u64> {
+        self.until.map(Self::farcaster_seconds)
+    }
+
+    fn farcaster_seconds(at: DateTime<Utc>) -> u64 {
+        // Anything before the Farcaster epoch clamps to its start
+        to_farcaster_time(at.timestamp_millis().max(0) as u64).map(u64::from).unwrap_or(0)
+    }
+
+    /// Whether a message's Farcaster timestamp falls inside the window
+    pub fn contains_message(&self, message: &Message) -> bool {
+        let Some(data) = &message.data else {
+            return false;
+        };
+        let timestamp = u64::from(data.timestamp);
+        self.start_timestamp().is_none_or(|start| timestamp >= start)
+            && self.stop_timestamp().is_none_or(|stop| timestamp < stop)
+    }
+
+    /// Whether a message is older than the window's start, e.g. to stop paging newest first
+    pub fn is_before_start(&self, message: &Message) -> bool {
+        let Some(data) = &message.data else {
+            return false;
+        };
+        self.start_timestamp().is_some_and(|start| u64::from(data.timestamp) < start)
+    }
+
+    /// Whether a Unix timestamp in seconds (e.g. an onchain event's block time) falls
+    /// inside the window
+    pub fn contains_unix_seconds(&self, timestamp: u64) -> bool {
+        let timestamp = timestamp as i64;
+        self.since.is_none_or(|since| timestamp >= since.timestamp())
+            && self.until.is_none_or(|until| timestamp < until.timestamp())
+    }
+}
+
+impl std::fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bound = |at: Option<DateTime<Utc>>| at.map(|at| at.to_rfc3339());
+        write!(
+            f,
+            "{}..{}",
+            bound(self.since).unwrap_or_default(),
+            bound(self.until).unwrap_or_default()
+        )
+    }
+}
+
+/// Message types reconciled for each FID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    Casts,
+    Reactions,
+    Links,
+    LinkCompactStates,
+    Verifications,
+    UserData,
+    UsernameProofs,
+    LendStorage,
+}
+
+impl MessageKind {
+    /// Name used in logs and as the checkpoint field
+    pub fn name(self) -> &'static str {
+        match self {
+            MessageKind::Casts => "casts",
+            MessageKind::Reactions => "reactions",
+            MessageKind::Links => "links",
+            MessageKind::LinkCompactStates => "link_compact_states",
+            MessageKind::Verifications => "verifications",
+            MessageKind::UserData => "user_data",
+            MessageKind::UsernameProofs => "username_proofs",
+            MessageKind::LendStorage => "lend_storage",
+        }
+    }
+
+    fn page_size(self) -> u32 {
+        match self {
+            MessageKind::Links => 500,
+            MessageKind::LinkCompactStates => 100,
+            _ => 1000,
+        }
+    }
+
+    /// The kind a `MessageType` value is fetched as; adds and removes share one
+    pub fn from_message_type(message_type: i32) -> Option<Self> {
+        match message_type {
+            1 | 2 => Some(MessageKind::Casts),
+            3 | 4 => Some(MessageKind::Reactions),
+            5 | 6 => Some(MessageKind::Links),
+            7 | 8 => Some(MessageKind::Verifications),
+            11 => Some(MessageKind::UserData),
+            12 => Some(MessageKind::UsernameProofs),
+            14 => Some(MessageKind::LinkCompactStates),
+            15 => Some(MessageKind::LendStorage),
+            _ => None,
+        }
+    }
+}
+
+/// State shared by the message types of one [`MessageReconciler::reconcile_fid`] run
+struct FidRun<'a> {
+    fid: u64,
+    window: Option<TimeWindow>,
+    checkpoint: &'a FidCheckpoint,
+    /// Save progress after each page; windowed and partial runs don't
+    checkpointed: bool,
+    processor: &'a Arc<dyn EventProcessor>,
+    semaphore: &'a Arc<Semaphore>,
+}
+
 pub struct MessageReconciler {
     hub: Arc<Hub>,
-    _database: Arc<Database>, // Prefixed with underscore to indicate intentionally unused
+    checkpoints: Option<Arc<CheckpointStore>>,
     _connection_timeout: Duration, // Prefixed with underscore to indicate intentionally unused
-    _use_streaming_rpcs: bool, // Prefixed with underscore to indicate intentionally unused
+    _use_streaming_rpcs: bool,     // Prefixed with underscore to indicate intentionally unused
 }
 
 impl MessageReconciler {
     /// Reconcile multiple FIDs in a batch for better efficiency
-    /// This method fetches data for multiple FIDs concurrently and processes them in batches
+    /// This method fetches data for multiple FIDs concurrently and processes them in batches.
+    /// With a `window`, only messages and onchain events inside it are fetched.
     pub async fn reconcile_fids_batch(
         &self,
         fids: &[u64],
+        window: Option<TimeWindow>,
         processor: Arc<dyn EventProcessor>,
     ) -> Result<(usize, usize), Error> {
         if fids.is_empty() {
@@ -56,55 +196,32 @@ impl MessageReconciler {
         let mut total_success = 0;
         let mut total_errors = 0;
 
+        // FIDs with saved progress resume from their checkpoint instead of being refetched,
+        // and FIDs too large to hold in memory join them once discovered. Checkpoints only
+        // track full-history runs, so windowed runs ignore them.
+        let mut batch_fids = Vec::with_capacity(fids.len());
+        let mut paged_fids = Vec::new();
+        for &fid in fids {
+            if window.is_some() || self.load_checkpoint(fid).await.is_empty() {
+                batch_fids.push(fid);
+            } else {
+                paged_fids.push(fid);
+            }
+        }
+
         // Process FIDs in smaller concurrent batches to avoid overwhelming the hub
         const CONCURRENT_BATCH_SIZE: usize = 3;
 
-        for fid_chunk in fids.chunks(CONCURRENT_BATCH_SIZE) {
+        for fid_chunk in batch_fids.chunks(CONCURRENT_BATCH_SIZE) {
             let mut fetch_tasks = Vec::new();
 
             // Fetch data for all FIDs in this chunk concurrently
             for &fid in fid_chunk {
                 let hub = self.hub.clone();
                 let task = tokio::spawn(async move {
-                    let reconciler = MessageReconciler {
-                        hub,
-                        _database: Arc::new(crate::database::Database::empty()), // Placeholder
-                        _connection_timeout: Duration::from_secs(30),
-                        _use_streaming_rpcs: false,
-                    };
-
-                    // Fetch all message types concurrently
-                    let (
-                        casts,
-                        reactions,
-                        links,
-                        verifications,
-                        user_data,
-                        username_proofs,
-                        lend_storage,
-                        onchain_events,
-                    ) = tokio::try_join!(
-                        reconciler.get_all_cast_messages(fid),
-                        reconciler.get_all_reaction_messages(fid),
-                        reconciler.get_all_link_messages(fid),
-                        reconciler.get_all_verification_messages(fid),
-                        reconciler.get_all_user_data_messages(fid),
-                        reconciler.get_all_username_proofs(fid),
-                        reconciler.get_all_lend_storage_messages(fid),
-                        reconciler.get_all_onchain_events(fid),
-                    )?;
-
-                    Ok::<_, Error>((
-                        fid,
-                        casts,
-                        reactions,
-                        links,
-                        verifications,
-                        user_data,
-                        username_proofs,
-                        lend_storage,
-                        onchain_events,
-                    ))
+                    let reconciler = MessageReconciler::new(hub, Duration::from_secs(30), false);
+                    let fetched = reconciler.fetch_fid_for_batch(fid, window).await?;
+                    Ok::<_, Error>((fid, fetched))
                 });
                 fetch_tasks.push(task);
             }
@@ -115,40 +232,26 @@ impl MessageReconciler {
 
             for task in fetch_tasks {
                 match task.await {
-                    Ok(Ok((
-                        fid,
-                        casts,
-                        reactions,
-                        links,
-                        verifications,
-                        user_data,
-                        username_proofs,
-                        lend_storage,
-                        onchain_events,
-                    ))) => {
-                        let total_count = casts.len()
-                            + reactions.len()
-                            + links.len()
-                            + verifications.len()
-                            + user_data.len()
-                            + username_proofs.len()
-                            + lend_storage.len()
-                            + onchain_events.len();
-
-                        info!("Fetched {} total messages for FID {}", total_count, fid);
+                    Ok(Ok((fid, Some((messages, onchain_events))))) => {
+                        info!(
+                            "Fetched {} total messages for FID {}",
+                            messages.len() + onchain_events.len(),
+                            fid
+                        );
 
                         // Collect all messages for batch processing
-                        all_messages.extend(casts);
-                        all_messages.extend(reactions);
-                        all_messages.extend(links);
-                        all_messages.extend(verifications);
-                        all_messages.extend(user_data);
-                        all_messages.extend(username_proofs);
-                        all_messages.extend(lend_storage);
+                        all_messages.extend(messages);
 
                         // Onchain events are a separate type, processed before messages
                         fid_results.push((fid, onchain_events));
                     },
+                    Ok(Ok((fid, None))) => {
+                        info!(
+                            "FID {} has more than {} pages of a message type, reconciling it page by page",
+                            fid, BATCH_PAGE_LIMIT
+                        );
+                        paged_fids.push(fid);
+                    },
                     Ok(Err(e)) => {
                         error!("Error fetching data for FID in batch: {:?}", e);
                         total_errors += 1;
@@ -206,7 +309,7 @@ impl MessageReconciler {
                             // Fall back to individual processing (onchain events already processed above, idempotent via ON CONFLICT)
                             debug!("Falling back to individual FID processing");
                             for (fid, _) in &fid_results {
-                                match self.reconcile_fid(*fid, processor.clone()).await {
+                                match self.reconcile_fid(*fid, window, processor.clone()).await {
                                     Ok(_) => total_success += 1,
                                     Err(_) => total_errors += 1,
                                 }
@@ -217,7 +320,7 @@ impl MessageReconciler {
                     debug!("Processor is not DatabaseProcessor, using individual FID processing");
                     // Fall back to individual FID processing for non-database processors
                     for (fid, _) in &fid_results {
-                        match self.reconcile_fid(*fid, processor.clone()).await {
+                        match self.reconcile_fid(*fid, window, processor.clone()).await {
                             Ok(_) => total_success += 1,
                             Err(_) => total_errors += 1,
                         }
@@ -226,6 +329,16 @@ impl MessageReconciler {
             }
         }
 
+        for &fid in &paged_fids {
+            match self.reconcile_fid(fid, window, processor.clone()).await {
+                Ok(_) => total_success += 1,
+                Err(e) => {
+                    error!("Error reconciling FID {} page by page: {:?}", fid, e);
+                    total_errors += 1;
+                },
+            }
+        }
+
         let elapsed = start_time.elapsed();
         info!(
             "Completed batch reconciliation for {} FIDs in {:.2?}: {} succeeded, {} failed",
@@ -237,295 +350,96 @@ impl MessageReconciler {
 
         Ok((total_success, total_errors))
     }
-    pub fn new(
-        hub: Arc<Hub>,
-        database: Arc<Database>,
-        connection_timeout: Duration,
-        use_streaming_rpcs: bool,
-    ) -> Self {
+    pub fn new(hub: Arc<Hub>, connection_timeout: Duration, use_streaming_rpcs: bool) -> Self {
         Self {
             hub,
-            _database: database,
+            checkpoints: None,
             _connection_timeout: connection_timeout,
             _use_streaming_rpcs: use_streaming_rpcs,
         }
     }
 
+    /// The Hub client messages are fetched from
+    pub fn hub(&self) -> Arc<Hub> {
+        Arc::clone(&self.hub)
+    }
+
+    /// Save per-FID progress so restarted workers resume partially reconciled FIDs
+    pub fn with_checkpoints(mut self, checkpoints: Arc<CheckpointStore>) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
+    /// Reconcile every message type for a FID, page by page.
+    ///
+    /// Each page is written before the next one is fetched, so memory stays bounded for
+    /// FIDs with millions of messages. When checkpoints are enabled, progress is saved
+    /// after every page and a restarted worker picks up from the saved page tokens.
+    /// A `window` limits the run to messages and onchain events inside it; windowed runs
+    /// are not checkpointed.
     pub async fn reconcile_fid(
         &self,
         fid: u64,
+        window: Option<TimeWindow>,
         processor: Arc<dyn EventProcessor>,
     ) -> Result<(), Error> {
         trace!("Starting reconciliation for FID {}", fid);
         let start_time = std::time::Instant::now();
 
-        // Fetch all message types concurrently — each creates its own gRPC client
-        let (
-            casts,
-            reactions,
-            links,
-            verifications,
-            user_data,
-            username_proofs,
-            lend_storage,
-            onchain_events,
-        ) = tokio::try_join!(
-            self.get_all_cast_messages(fid),
-            self.get_all_reaction_messages(fid),
-            self.get_all_link_messages(fid),
-            self.get_all_verification_messages(fid),
-            self.get_all_user_data_messages(fid),
-            self.get_all_username_proofs(fid),
-            self.get_all_lend_storage_messages(fid),
-            self.get_all_onchain_events(fid),
-        )?;
-
-        // Process count for each message type
-        let casts_count = casts.len();
-        let reactions_count = reactions.len();
-        let links_count = links.len();
-        let verifications_count = verifications.len();
-        let user_data_count = user_data.len();
-        let username_proofs_count = username_proofs.len();
-        let lend_storage_count = lend_storage.len();
-        let onchain_events_count = onchain_events.len();
+        let checkpoint = match window {
+            Some(_) => FidCheckpoint { fid, ..Default::default() },
+            None => self.load_checkpoint(fid).await,
+        };
+        let partial_types = checkpoint.partial_types();
+        if !partial_types.is_empty() {
+            info!(
+                "Resuming FID {} from checkpoint ({} in progress)",
+                fid,
+                partial_types.join(", ")
+            );
+        }
 
-        info!(
-            "Fetched messages for FID {}: {} casts, {} reactions, {} links, {} verifications, {} user data, {} username proofs, {} lend storage, {} onchain events",
+        // Using a semaphore to limit concurrent processing
+        let semaphore = Arc::new(Semaphore::new(10));
+        let run = &FidRun {
             fid,
+            window,
+            checkpoint: &checkpoint,
+            checkpointed: window.is_none(),
+            processor: &processor,
+            semaphore: &semaphore,
+        };
+        let (processor, semaphore) = (&processor, &semaphore);
+        let reconcile = move |kind| self.reconcile_message_type(run, kind);
+
+        // Types are independent except that compact states must follow links, so they
+        // replace the rows they summarize
+        let (
             casts_count,
             reactions_count,
-            links_count,
+            (links_count, link_compact_states_count),
             verifications_count,
-            user_data_count,
             username_proofs_count,
             lend_storage_count,
-            onchain_events_count
-        );
-
-        // Process each message type, but handle user_data specially
-        let non_user_data_groups = vec![
-            ("casts", casts),
-            ("reactions", reactions),
-            ("links", links),
-            ("verifications", verifications),
-            ("username_proofs", username_proofs),
-            ("lend_storage", lend_storage),
-        ];
-
-        // Using a semaphore to limit concurrent processing
-        let semaphore = Arc::new(tokio::sync::Semaphore::new(10));
-
-        // First process all non-user_data messages
-        for (message_type, messages) in non_user_data_groups {
-            let count = messages.len();
-            if count == 0 {
-                continue;
-            }
-
-            trace!("Processing {} {} messages for FID {}", count, message_type, fid);
-
-            let mut success_count = 0;
-            let mut error_count = 0;
-
-            // Create a set of tasks to process messages in batches
-            let mut handles = Vec::new();
-
-            // Process in chunks of 100 messages
-            for chunk in messages.chunks(100) {
-                let processor_clone = Arc::clone(&processor);
-                let chunk_vec = chunk.to_vec();
-                let message_type_str = message_type.to_string();
-                let semaphore_clone = Arc::clone(&semaphore);
-
-                // Extract messages from chunk for potential batch processing
-                let message_batch = chunk_vec.clone();
-
-                let handle = tokio::spawn(async move {
-                    // Acquire permit to limit concurrency
-                    let _permit = semaphore_clone.acquire().await.unwrap();
-
-                    let mut chunk_success = 0;
-                    let mut chunk_error = 0;
-
-                    // Try to downcast to DatabaseProcessor to use batch processing
-                    if let Some(db_processor) = (processor_clone.as_any())
-                        .downcast_ref::<crate::processor::database::DatabaseProcessor>(
-                    ) {
-                        // Process the entire batch at once
-                        match db_processor.process_message_batch(&message_batch, "merge").await {
-                            Ok(_) => {
-                                chunk_success = message_batch.len();
-                            },
-                            Err(e) => {
-                                error!(
-                                    "Error batch processing {} messages for FID {}: {:?}",
-                                    message_type_str, fid, e
-                                );
-
-                                // If batch processing fails, fall back to individual processing
-                                // Create events for individual processing
-                                let mut events = Vec::with_capacity(message_batch.len());
-                                for message in &message_batch {
-                                    // Can't use self within the async closure - reconstruct the event instead
-                                    events.push(HubEvent {
-                                        id: 0,     // We don't use this ID
-                                        r#type: 1, // MERGE_MESSAGE is type 1
-                                        body: Some(Body::MergeMessageBody(MergeMessageBody {
-                                            message: Some(message.clone()),
-                                            deleted_messages: Vec::new(),
-                                        })),
-                                        block_number: 0,
-                                        shard_index: 0,
-                                        timestamp: 0,
-                                    });
-                                }
-
-                                for event in events {
-                                    match processor_clone.process_event(event).await {
-                                        Ok(_) => chunk_success += 1,
-                                        Err(e) => {
-                                            error!(
-                                                "Error processing {} message for FID {}: {:?}",
-                                                message_type_str, fid, e
-                                            );
-                                            chunk_error += 1;
-                                        },
-                                    }
-                                }
-                            },
-                        }
-                    } else {
-                        // Fall back to individual processing for other processor types
-                        // Create events before processing
-                        let mut events = Vec::with_capacity(chunk_vec.len());
-                        for message in chunk_vec {
-                            events.push(HubEvent {
-                                id: 0,     // We don't use this ID
-                                r#type: 1, // MERGE_MESSAGE is type 1
-                                body: Some(Body::MergeMessageBody(MergeMessageBody {
-                                    message: Some(message),
-                                    deleted_messages: Vec::new(),
-                                })),
-                                block_number: 0,
-                                shard_index: 0,
-                                timestamp: 0,
-                            });
-                        }
-
-                        for event in events {
-                            match processor_clone.process_event(event).await {
-                                Ok(_) => chunk_success += 1,
-                                Err(e) => {
-                                    error!(
-                                        "Error processing {} message for FID {}: {:?}",
-                                        message_type_str, fid, e
-                                    );
-                                    chunk_error += 1;
-                                },
-                            }
-                        }
-                    }
-
-                    (chunk_success, chunk_error)
-                });
-
-                handles.push(handle);
-            }
-
-            // Wait for all processing to complete
-            for handle in handles {
-                match handle.await {
-                    Ok((chunk_success, chunk_error)) => {
-                        success_count += chunk_success;
-                        error_count += chunk_error;
-                    },
-                    Err(e) => {
-                        error!("Task error processing {} for FID {}: {:?}", message_type, fid, e);
-                        error_count += 1;
-                    },
-                }
-            }
-
-            trace!(
-                "Completed processing {} {} messages for FID {} ({} succeeded, {} failed)",
-                count, message_type, fid, success_count, error_count
-            );
-        }
-
-        // Now handle user_data specially to ensure complete profiles are built
-        let user_data_count = user_data.len();
-        if user_data_count > 0 {
-            trace!("Processing {} user_data messages for FID {}", user_data_count, fid);
-
-            // Regular user data processing
-            {
-                // Process user data
-                let mut success_count = 0;
-                let mut error_count = 0;
-
-                // Process in chunks of 100 messages
-                let mut handles = Vec::new();
-
-                for chunk in user_data.chunks(100) {
-                    let processor_clone = Arc::clone(&processor);
-                    let chunk_vec = chunk.to_vec();
-                    let semaphore_clone = Arc::clone(&semaphore);
-
-                    // Create events before spawning the task to avoid self reference
-                    let mut events = Vec::with_capacity(chunk_vec.len());
-                    for message in chunk_vec {
-                        events.push(self.message_to_hub_event(message));
-                    }
-
-                    let handle = tokio::spawn(async move {
-                        // Acquire permit to limit concurrency
-                        let _permit = semaphore_clone.acquire().await.unwrap();
-
-                        let mut chunk_success = 0;
-                        let mut chunk_error = 0;
-
-                        for event in events {
-                            match processor_clone.process_event(event).await {
-                                Ok(_) => chunk_success += 1,
-                                Err(e) => {
-                                    error!(
-                                        "Error processing user_data message for FID {}: {:?}",
-                                        fid, e
-                                    );
-                                    chunk_error += 1;
-                                },
-                            }
-                        }
-
-                        (chunk_success, chunk_error)
-                    });
-
-                    handles.push(handle);
-                }
-
-                // Wait for all processing to complete
-                for handle in handles {
-                    match handle.await {
-                        Ok((chunk_success, chunk_error)) => {
-                            success_count += chunk_success;
-                            error_count += chunk_error;
-                        },
-                        Err(e) => {
-                            error!("Task error processing user_data for FID {}: {:?}", fid, e);
-                            error_count += 1;
-                        },
-                    }
-                }
+        ) = tokio::try_join!(
+            reconcile(MessageKind::Casts),
+            reconcile(MessageKind::Reactions),
+            async {
+                let links = reconcile(MessageKind::Links).await?;
+                let link_compact_states = reconcile(MessageKind::LinkCompactStates).await?;
+                Ok((links, link_compact_states))
+            },
+            reconcile(MessageKind::Verifications),
+            reconcile(MessageKind::UsernameProofs),
+            reconcile(MessageKind::LendStorage),
+        )?;
 
-                trace!(
-                    "Completed processing {} user_data messages for FID {} ({} succeeded, {} failed)",
-                    user_data_count, fid, success_count, error_count
-                );
-            }
-        }
+        // User data goes last so complete profiles are built
+        let user_data_count = reconcile(MessageKind::UserData).await?;
 
-        // Now handle onchain events, if any
+        // Onchain events are few per FID, so they are refetched rather than checkpointed
+        let onchain_events = self.get_all_onchain_events(fid, window).await?;
+        let onchain_events_count = onchain_events.len();
         if onchain_events_count > 0 {
             trace!("Processing {} onchain events for FID {}", onchain_events_count, fid);
 
@@ -536,23 +450,14 @@ impl MessageReconciler {
             let mut handles = Vec::new();
 
             for (idx, event) in onchain_events.into_iter().enumerate() {
-                let processor_clone = Arc::clone(&processor);
-                let semaphore_clone = Arc::clone(&semaphore);
+                let processor_clone = Arc::clone(processor);
+                let semaphore_clone = Arc::clone(semaphore);
 
                 let handle = tokio::spawn(async move {
                     // Acquire permit to limit concurrency
                     let _permit = semaphore_clone.acquire().await.unwrap();
 
-                    let onchain_event = HubEvent {
-                        id: 0,
-                        r#type: HubEventType::MergeOnChainEvent as i32,
-                        body: Some(proto::hub_event::Body::MergeOnChainEventBody(
-                            MergeOnChainEventBody { on_chain_event: Some(event) },
-                        )),
-                        block_number: 0,
-                        shard_index: 0,
-                        timestamp: 0,
-                    };
+                    let onchain_event = Self::onchain_event_to_hub_event(event);
 
                     match processor_clone.process_event(onchain_event).await {
                         Ok(_) => (1, 0),
@@ -592,10 +497,15 @@ impl MessageReconciler {
             );
         }
 
+        if window.is_none() {
+            self.clear_checkpoint(fid).await;
+        }
+
         // Calculate total messages processed
         let total_count = casts_count
             + reactions_count
             + links_count
+            + link_compact_states_count
             + verifications_count
             + user_data_count
             + username_proofs_count
@@ -603,13 +513,14 @@ impl MessageReconciler {
             + onchain_events_count;
         let elapsed = start_time.elapsed();
         info!(
-            "Completed reconciliation for FID {} in {:.2?}: processed {} total messages ({} casts, {} reactions, {} links, {} verifications, {} user data, {} username proofs, {} lend storage, {} onchain events)",
+            "Completed reconciliation for FID {} in {:.2?}: processed {} total messages ({} casts, {} reactions, {} links, {} link compact states, {} verifications, {} user data, {} username proofs, {} lend storage, {} onchain events)",
             fid,
             elapsed,
             total_count,
             casts_count,
             reactions_count,
             links_count,
+            link_compact_states_count,
             verifications_count,
             user_data_count,
             username_proofs_count,
@@ -620,368 +531,417 @@ impl MessageReconciler {
         Ok(())
     }
 
-    async fn get_all_cast_messages(&self, fid: u64) -> Result<Vec<Message>, Error> {
-        let mut messages = Vec::new();
-        let page_size = 1000u32;
-        let mut page_token = None;
-        let mut page_count = 0;
-
-        trace!("Fetching casts for FID {} with page size {}", fid, page_size);
-
-        loop {
-            page_count += 1;
-            let request = FidRequest {
-                fid,
-                page_size: Some(page_size),
-                page_token: page_token.clone(),
-                reverse: Some(false),
-            };
-
-            let response = self.hub.get_casts_by_fid(request).await?;
-            let page_messages_count = response.messages.len();
-            messages.extend(response.messages);
-
-            trace!(
-                "Received page {} with {} casts for FID {}",
-                page_count, page_messages_count, fid
-            );
-
-            if let Some(token) = response.next_page_token {
-                if token.is_empty() {
-                    break;
-                }
-                page_token = Some(token);
-            } else {
-                break;
+    /// Reconcile only some message types for a FID, e.g. the ones a trie diff found.
+    /// Links go before link compact states and user data goes last, as in
+    /// [`reconcile_fid`](Self::reconcile_fid), but progress isn't checkpointed. Returns
+    /// the number of messages processed.
+    pub async fn reconcile_message_types(
+        &self,
+        fid: u64,
+        kinds: &[MessageKind],
+        processor: Arc<dyn EventProcessor>,
+    ) -> Result<usize, Error> {
+        let checkpoint = FidCheckpoint { fid, ..Default::default() };
+        let semaphore = Arc::new(Semaphore::new(10));
+        let run = FidRun {
+            fid,
+            window: None,
+            checkpoint: &checkpoint,
+            checkpointed: false,
+            processor: &processor,
+            semaphore: &semaphore,
+        };
+
+        let mut processed = 0;
+        for kind in [
+            MessageKind::Casts,
+            MessageKind::Reactions,
+            MessageKind::Links,
+            MessageKind::LinkCompactStates,
+            MessageKind::Verifications,
+            MessageKind::UsernameProofs,
+            MessageKind::LendStorage,
+            MessageKind::UserData,
+        ] {
+            if kinds.contains(&kind) {
+                processed += self.reconcile_message_type(&run, kind).await?;
             }
         }
-
-        trace!(
-            "Fetched a total of {} casts for FID {} in {} pages",
-            messages.len(),
-            fid,
-            page_count
-        );
-        Ok(messages)
+        Ok(processed)
     }
 
-    async fn get_all_reaction_messages(&self, fid: u64) -> Result<Vec<Message>, Error> {
-        let mut messages = Vec::new();
-        let page_size = 1000u32;
-        let mut page_token = None;
-        let mut page_count = 0;
-
-        trace!("Fetching reactions for FID {} with page size {}", fid, page_size);
+    /// Fetch and apply one message type page by page, starting from its checkpoint.
+    /// Returns the number of messages processed in this run.
+    async fn reconcile_message_type(
+        &self,
+        run: &FidRun<'_>,
+        kind: MessageKind,
+    ) -> Result<usize, Error> {
+        let FidRun { fid, window, checkpoint, checkpointed, processor, semaphore } = *run;
+        let mut state = checkpoint.get(kind.name()).cloned().unwrap_or_default();
+        if state.done {
+            trace!("Skipping {} for FID {}: already reconciled", kind.name(), fid);
+            return Ok(0);
+        }
 
+        let mut processed = 0;
         loop {
-            page_count += 1;
-            let request = ReactionsByFidRequest {
-                fid,
-                reaction_type: None, // Get all reaction types
-                page_size: Some(page_size),
-                page_token: page_token.clone(),
-                reverse: Some(false),
-            };
-
-            let response = self.hub.get_reactions_by_fid(request).await?;
-            let page_messages_count = response.messages.len();
-            messages.extend(response.messages);
-
-            trace!(
-                "Received page {} with {} reactions for FID {}",
-                page_count, page_messages_count, fid
-            );
+            let (messages, next_page_token) =
+                self.fetch_page(kind, fid, window, state.next_page_token()).await?;
+            let count = messages.len();
+            if count > 0 {
+                let (success_count, error_count) =
+                    self.process_messages(fid, kind, messages, processor, semaphore).await;
+                trace!(
+                    "Processed page {} of {} for FID {} ({} succeeded, {} failed)",
+                    state.pages + 1,
+                    kind.name(),
+                    fid,
+                    success_count,
+                    error_count
+                );
+            }
+            processed += count;
 
-            if let Some(token) = response.next_page_token {
-                if token.is_empty() {
-                    break;
-                }
-                page_token = Some(token);
-            } else {
+            state.advance(count, next_page_token);
+            // Types that fit in a single page are cheaper to refetch than to checkpoint
+            if checkpointed && (state.pages > 1 || !state.done) {
+                self.save_checkpoint(fid, kind, &state).await;
+            }
+            if state.done {
                 break;
             }
         }
 
-        trace!(
-            "Fetched a total of {} reactions for FID {} in {} pages",
-            messages.len(),
-            fid,
-            page_count
-        );
-        Ok(messages)
+        Ok(processed)
     }
 
-    async fn get_all_link_messages(&self, fid: u64) -> Result<Vec<Message>, Error> {
-        let mut messages = Vec::new();
-        let page_size = 500u32;
-        let mut page_token = None;
-        let mut page_count = 0;
-
-        trace!("Fetching links for FID {} with page size {}", fid, page_size);
+    /// Apply a page of messages in chunks of 100, returning (succeeded, failed) counts
+    async fn process_messages(
+        &self,
+        fid: u64,
+        kind: MessageKind,
+        messages: Vec<Message>,
+        processor: &Arc<dyn EventProcessor>,
+        semaphore: &Arc<Semaphore>,
+    ) -> (usize, usize) {
+        let mut success_count = 0;
+        let mut error_count = 0;
+
+        // Create a set of tasks to process messages in batches
+        let mut handles = Vec::new();
+
+        for chunk in messages.chunks(100) {
+            let processor_clone = Arc::clone(processor);
+            let message_batch = chunk.to_vec();
+            let semaphore_clone = Arc::clone(semaphore);
+
+            let handle = tokio::spawn(async move {
+                // Acquire permit to limit concurrency
+                let _permit = semaphore_clone.acquire().await.unwrap();
+
+                // User data is applied message by message so complete profiles are built;
+                // everything else goes through the DatabaseProcessor batch path when available
+                if kind != MessageKind::UserData
+                    && let Some(db_processor) = (processor_clone.as_any())
+                        .downcast_ref::<crate::processor::database::DatabaseProcessor>(
+                    )
+                {
+                    match db_processor.process_message_batch(&message_batch, "merge").await {
+                        Ok(_) => return (message_batch.len(), 0),
+                        Err(e) => {
+                            // If batch processing fails, fall back to individual processing
+                            error!(
+                                "Error batch processing {} messages for FID {}: {:?}",
+                                kind.name(),
+                                fid,
+                                e
+                            );
+                        },
+                    }
+                }
 
-        loop {
-            page_count += 1;
-            let request = LinksByFidRequest {
-                fid,
-                link_type: None, // Get all link types
-                page_size: Some(page_size),
-                page_token: page_token.clone(),
-                reverse: Some(false),
-            };
+                let mut chunk_success = 0;
+                let mut chunk_error = 0;
+                for message in message_batch {
+                    match processor_clone.process_event(Self::merge_message_event(message)).await {
+                        Ok(_) => chunk_success += 1,
+                        Err(e) => {
+                            error!(
+                                "Error processing {} message for FID {}: {:?}",
+                                kind.name(),
+                                fid,
+                                e
+                            );
+                            chunk_error += 1;
+                        },
+                    }
+                }
 
-            let response = self.hub.get_links_by_fid(request).await?;
-            let page_messages_count = response.messages.len();
-            messages.extend(response.messages);
+                (chunk_success, chunk_error)
+            });
 
-            trace!(
-                "Received page {} with {} links for FID {}",
-                page_count, page_messages_count, fid
-            );
+            handles.push(handle);
+        }
 
-            if let Some(token) = response.next_page_token {
-                if token.is_empty() {
-                    break;
-                }
-                page_token = Some(token);
-            } else {
-                break;
+        // Wait for all processing to complete
+        for handle in handles {
+            match handle.await {
+                Ok((chunk_success, chunk_error)) => {
+                    success_count += chunk_success;
+                    error_count += chunk_error;
+                },
+                Err(e) => {
+                    error!("Task error processing {} for FID {}: {:?}", kind.name(), fid, e);
+                    error_count += 1;
+                },
             }
         }
 
-        trace!(
-            "Fetched a total of {} links for FID {} in {} pages",
-            messages.len(),
-            fid,
-            page_count
-        );
-        Ok(messages)
+        (success_count, error_count)
     }
 
-    async fn get_all_verification_messages(&self, fid: u64) -> Result<Vec<Message>, Error> {
-        let mut messages = Vec::new();
-        let page_size = 1000u32;
-        let mut page_token = None;
-        let mut page_count = 0;
-
-        trace!("Fetching verifications for FID {} with page size {}", fid, page_size);
-
-        loop {
-            page_count += 1;
-            let request = FidRequest {
-                fid,
-                page_size: Some(page_size),
-                page_token: page_token.clone(),
-                reverse: Some(false),
-            };
-
-            let response = self.hub.get_verifications_by_fid(request).await?;
-            let page_messages_count = response.messages.len();
-            messages.extend(response.messages);
-
-            trace!(
-                "Received page {} with {} verifications for FID {}",
-                page_count, page_messages_count, fid
-            );
-
-            if let Some(token) = response.next_page_token {
-                if token.is_empty() {
-                    break;
-                }
-                page_token = Some(token);
-            } else {
-                break;
-            }
+    /// Saved progress for a FID; empty when checkpoints are disabled or unreadable
+    async fn load_checkpoint(&self, fid: u64) -> FidCheckpoint {
+        let Some(checkpoints) = &self.checkpoints else {
+            return FidCheckpoint { fid, ..Default::default() };
+        };
+        match checkpoints.load(fid).await {
+            Ok(checkpoint) => checkpoint,
+            Err(e) => {
+                warn!("Failed to load checkpoint for FID {}, starting from scratch: {}", fid, e);
+                FidCheckpoint { fid, ..Default::default() }
+            },
         }
-
-        trace!(
-            "Fetched a total of {} verifications for FID {} in {} pages",
-            messages.len(),
-            fid,
-            page_count
-        );
-        Ok(messages)
     }
 
-    pub async fn get_all_user_data_messages(&self, fid: u64) -> Result<Vec<Message>, Error> {
-        let mut messages = Vec::new();
-        let page_size = 1000u32;
-        let mut page_token = None;
-        let mut page_count = 0;
-
-        trace!("Fetching user data for FID {} with page size {}", fid, page_size);
+    async fn save_checkpoint(&self, fid: u64, kind: MessageKind, state: &TypeCheckpoint) {
+        if let Some(checkpoints) = &self.checkpoints
+            && let Err(e) = checkpoints.save(fid, kind.name(), state).await
+        {
+            warn!("Failed to save {} checkpoint for FID {}: {}", kind.name(), fid, e);
+        }
+    }
 
-        loop {
-            page_count += 1;
-            let request = FidRequest {
-                fid,
-                page_size: Some(page_size),
-                page_token: page_token.clone(),
-                reverse: Some(false),
-            };
+    async fn clear_checkpoint(&self, fid: u64) {
+        if let Some(checkpoints) = &self.checkpoints
+            && let Err(e) = checkpoints.clear(fid).await
+        {
+            warn!("Failed to clear checkpoint for FID {}: {}", fid, e);
+        }
+    }
 
-            let response = self.hub.get_user_data_by_fid(request).await?;
-            let page_messages_count = response.messages.len();
-            messages.extend(response.messages);
+    /// Fetch everything for a FID so it can be applied as part of a batch, or `None` if
+    /// any message type runs past [`BATCH_PAGE_LIMIT`] pages
+    async fn fetch_fid_for_batch(
+        &self,
+        fid: u64,
+        window: Option<TimeWindow>,
+    ) -> Result<Option<(Vec<Message>, Vec<proto::OnChainEvent>)>, Error> {
+        let limit = Some(BATCH_PAGE_LIMIT);
 
-            trace!(
-                "Received page {} with {} user data messages for FID {}",
-                page_count, page_messages_count, fid
-            );
+        // Fetch all message types concurrently
+        let (
+            casts,
+            reactions,
+            links,
+            link_compact_states,
+            verifications,
+            user_data,
+            username_proofs,
+            lend_storage,
+            onchain_events,
+        ) = tokio::try_join!(
+            self.collect_pages(MessageKind::Casts, fid, window, limit),
+            self.collect_pages(MessageKind::Reactions, fid, window, limit),
+            self.collect_pages(MessageKind::Links, fid, window, limit),
+            self.collect_pages(MessageKind::LinkCompactStates, fid, window, limit),
+            self.collect_pages(MessageKind::Verifications, fid, window, limit),
+            self.collect_pages(MessageKind::UserData, fid, window, limit),
+            self.collect_pages(MessageKind::UsernameProofs, fid, window, limit),
+            self.collect_pages(MessageKind::LendStorage, fid, window, limit),
+            self.get_all_onchain_events(fid, window),
+        )?;
 
-            if let Some(token) = response.next_page_token {
-                if token.is_empty() {
-                    break;
-                }
-                page_token = Some(token);
-            } else {
-                break;
-            }
+        // Compact states follow links so they replace the rows they summarize
+        let groups = [
+            casts,
+            reactions,
+            links,
+            link_compact_states,
+            verifications,
+            user_data,
+            username_proofs,
+            lend_storage,
+        ];
+        if groups.iter().any(Option::is_none) {
+            return Ok(None);
         }
+        let messages = groups.into_iter().flatten().flatten().collect();
 
-        trace!(
-            "Fetched a total of {} user data messages for FID {} in {} pages",
-            messages.len(),
-            fid,
-            page_count
-        );
-        Ok(messages)
+        Ok(Some((messages, onchain_events)))
     }
 
-    /// Get all username proofs for the given FID
-    async fn get_all_username_proofs(&self, fid: u64) -> Result<Vec<Message>, Error> {
+    /// Fetch every page of one message type for a FID, or `None` if it needs more than
+    /// `max_pages` pages
+    async fn collect_pages(
+        &self,
+        kind: MessageKind,
+        fid: u64,
+        window: Option<TimeWindow>,
+        max_pages: Option<usize>,
+    ) -> Result<Option<Vec<Message>>, Error> {
         let mut messages = Vec::new();
-        let page_size = 1000u32;
         let mut page_token = None;
         let mut page_count = 0;
 
-        trace!("Fetching username proofs for FID {} with page size {}", fid, page_size);
+        trace!("Fetching {} for FID {} with page size {}", kind.name(), fid, kind.page_size());
 
         loop {
+            if max_pages.is_some_and(|max| page_count >= max) {
+                return Ok(None);
+            }
             page_count += 1;
-            let request = FidRequest {
-                fid,
-                page_size: Some(page_size),
-                page_token: page_token.clone(),
-                reverse: Some(false),
-            };
-
-            // Try to get username proofs - first from user data, then filter
-            // We need to use FidTimestampRequest for bulk methods
-            let ts_request = FidTimestampRequest {
-                fid,
-                page_size: Some(page_size),
-                page_token: page_token.clone(),
-                reverse: Some(false),
-                start_timestamp: None,
-                stop_timestamp: None,
-            };
-
-            let response = match self.hub.get_all_user_data_messages_by_fid(ts_request).await {
-                Ok(resp) => resp,
-                Err(e) => {
-                    debug!("Error getting username proof messages: {}", e);
-                    // Try regular user data as fallback
-                    self.hub.get_user_data_by_fid(request).await?
-                },
-            };
-
-            // Filter the messages to keep only username proofs
-            let username_messages: Vec<Message> = response
-                .messages
-                .into_iter()
-                .filter(|msg| {
-                    if let Some(data) = &msg.data {
-                        if let Some(body) = &data.body {
-                            use proto::message_data::Body;
-                            matches!(body, Body::UsernameProofBody(_))
-                        } else {
-                            false
-                        }
-                    } else {
-                        false
-                    }
-                })
-                .collect();
-
-            let page_messages_count = username_messages.len();
-            messages.extend(username_messages);
 
+            let (page, next_page_token) = self.fetch_page(kind, fid, window, page_token).await?;
             trace!(
-                "Received page {} with {} username proof messages for FID {}",
-                page_count, page_messages_count, fid
+                "Received page {} with {} {} for FID {}",
+                page_count,
+                page.len(),
+                kind.name(),
+                fid
             );
+            messages.extend(page);
 
-            if let Some(token) = response.next_page_token {
-                if token.is_empty() {
-                    break;
-                }
-                page_token = Some(token);
-            } else {
-                break;
+            match next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
             }
         }
 
         trace!(
-            "Fetched a total of {} username proof messages for FID {} in {} pages",
+            "Fetched a total of {} {} for FID {} in {} pages",
             messages.len(),
+            kind.name(),
             fid,
             page_count
         );
-        Ok(messages)
+        Ok(Some(messages))
     }
 
-    /// Get all lend storage messages for the given FID
-    async fn get_all_lend_storage_messages(&self, fid: u64) -> Result<Vec<Message>, Error> {
-        let mut messages = Vec::new();
-        let page_size = 1000u32;
-        let mut page_token = None;
-        let mut page_count = 0;
-
-        trace!("Fetching lend storage messages for FID {} with page size {}", fid, page_size);
-
-        loop {
-            page_count += 1;
-            let request = FidTimestampRequest {
-                fid,
-                page_size: Some(page_size),
-                page_token: page_token.clone(),
-                reverse: Some(false),
-                start_timestamp: None,
-                stop_timestamp: None,
-            };
-
-            let response = self.hub.get_all_lend_storage_messages_by_fid(request).await?;
-            let page_messages_count = response.messages.len();
-            messages.extend(response.messages);
-
-            trace!(
-                "Received page {} with {} lend storage messages for FID {}",
-                page_count, page_messages_count, fid
-            );
-
-            if let Some(token) = response.next_page_token {
-                if token.is_empty() {
-                    break;
+    /// Fetch one page of messages, returning them with the Hub's next page token.
+    ///
+    /// Without a window the regular by-FID RPCs return current messages. With one, the
+    /// `GetAll*MessagesByFid` RPCs are used with the window's start and stop timestamps,
+    /// which also return removes made during the window.
+    async fn fetch_page(
+        &self,
+        kind: MessageKind,
+        fid: u64,
+        window: Option<TimeWindow>,
+        page_token: Option<Vec<u8>>,
+    ) -> Result<(Vec<Message>, Option<Vec<u8>>), Error> {
+        let page_size = Some(kind.page_size());
+        let fid_request =
+            FidRequest { fid, page_size, page_token: page_token.clone(), reverse: Some(false) };
+        // The bulk GetAll*MessagesByFid methods take a FidTimestampRequest
+        let timestamp_request = FidTimestampRequest {
+            fid,
+            page_size,
+            page_token: page_token.clone(),
+            reverse: Some(false),
+            start_timestamp: window.and_then(|w| w.start_timestamp()),
+            stop_timestamp: window.and_then(|w| w.stop_timestamp()),
+        };
+
+        let response = match kind {
+            MessageKind::Casts if window.is_some() => {
+                self.hub.get_all_cast_messages_by_fid(timestamp_request).await?
+            },
+            MessageKind::Reactions if window.is_some() => {
+                self.hub.get_all_reaction_messages_by_fid(timestamp_request).await?
+            },
+            MessageKind::Links if window.is_some() => {
+                self.hub.get_all_link_messages_by_fid(timestamp_request).await?
+            },
+            MessageKind::Verifications if window.is_some() => {
+                self.hub.get_all_verification_messages_by_fid(timestamp_request).await?
+            },
+            MessageKind::UserData if window.is_some() => {
+                self.hub.get_all_user_data_messages_by_fid(timestamp_request).await?
+            },
+            MessageKind::Casts => self.hub.get_casts_by_fid(fid_request).await?,
+            MessageKind::Reactions => {
+                let request = ReactionsByFidRequest {
+                    fid,
+                    reaction_type: None, // Get all reaction types
+                    page_size,
+                    page_token,
+                    reverse: Some(false),
+                };
+                self.hub.get_reactions_by_fid(request).await?
+            },
+            MessageKind::Links => {
+                let request = LinksByFidRequest {
+                    fid,
+                    link_type: None, // Get all link types
+                    page_size,
+                    page_token,
+                    reverse: Some(false),
+                };
+                self.hub.get_links_by_fid(request).await?
+            },
+            // Frame actions are not retained by hubs, so they have no reconciliation
+            // counterpart and are only persisted from the live stream
+            MessageKind::LinkCompactStates => {
+                self.hub.get_link_compact_state_message_by_fid(fid_request).await?
+            },
+            MessageKind::Verifications => self.hub.get_verifications_by_fid(fid_request).await?,
+            MessageKind::UserData => self.hub.get_user_data_by_fid(fid_request).await?,
+            MessageKind::UsernameProofs => {
+                // Username proofs come back among the user data messages and are filtered below
+                match self.hub.get_all_user_data_messages_by_fid(timestamp_request).await {
+                    Ok(resp) => resp,
+                    Err(e) => {
+                        debug!("Error getting username proof messages: {}", e);
+                        // Try regular user data as fallback
+                        self.hub.get_user_data_by_fid(fid_request).await?
+                    },
                 }
-                page_token = Some(token);
-            } else {
-                break;
-            }
+            },
+            MessageKind::LendStorage => {
+                self.hub.get_all_lend_storage_messages_by_fid(timestamp_request).await?
+            },
+        };
+
+        let mut messages = response.messages;
+        if kind == MessageKind::UsernameProofs {
+            use proto::message_data::Body;
+            messages.retain(|msg| {
+                matches!(
+                    msg.data.as_ref().and_then(|data| data.body.as_ref()),
+                    Some(Body::UsernameProofBody(_))
+                )
+            });
+        }
+        if let Some(window) = window {
+            // Link compact states and the user data fallback can't be filtered by the Hub
+            messages.retain(|msg| window.contains_message(msg));
+            metrics::increment_backfill_window_fetched(kind.name(), messages.len() as u64);
         }
 
-        trace!(
-            "Fetched a total of {} lend storage messages for FID {} in {} pages",
-            messages.len(),
-            fid,
-            page_count
-        );
-        Ok(messages)
+        Ok((messages, response.next_page_token))
+    }
+
+    pub async fn get_all_user_data_messages(&self, fid: u64) -> Result<Vec<Message>, Error> {
+        Ok(self.collect_pages(MessageKind::UserData, fid, None, None).await?.unwrap_or_default())
     }
 
     /// Reconcile only onchain events for a FID (used for spam FIDs where we skip messages but still need onchain data)
     pub async fn reconcile_onchain_events_only(
         &self,
         fid: u64,
+        window: Option<TimeWindow>,
         processor: Arc<dyn EventProcessor>,
     ) -> Result<ReconcileStats, Error> {
         use futures::{StreamExt, stream};
@@ -989,7 +949,7 @@ impl MessageReconciler {
         trace!("Starting onchain-only reconciliation for FID {}", fid);
         let start_time = std::time::Instant::now();
 
-        let onchain_events = self.get_all_onchain_events(fid).await?;
+        let onchain_events = self.get_all_onchain_events(fid, window).await?;
         let event_count = onchain_events.len();
 
         if event_count == 0 {
@@ -1030,7 +990,7 @@ impl MessageReconciler {
     }
 
     /// Convert an OnChainEvent to a HubEvent for processing
-    fn onchain_event_to_hub_event(event: proto::OnChainEvent) -> HubEvent {
+    pub(crate) fn onchain_event_to_hub_event(event: proto::OnChainEvent) -> HubEvent {
         HubEvent {
             id: 0,
             r#type: HubEventType::MergeOnChainEvent as i32,
@@ -1074,8 +1034,13 @@ impl MessageReconciler {
         Ok(events)
     }
 
-    /// Get all onchain events for the given FID, fetching all types concurrently
-    async fn get_all_onchain_events(&self, fid: u64) -> Result<Vec<proto::OnChainEvent>, Error> {
+    /// Get all onchain events for the given FID, fetching all types concurrently.
+    /// `OnChainEventRequest` has no time range, so a window is applied to block timestamps.
+    async fn get_all_onchain_events(
+        &self,
+        fid: u64,
+        window: Option<TimeWindow>,
+    ) -> Result<Vec<proto::OnChainEvent>, Error> {
         trace!("Fetching onchain events for FID {} concurrently", fid);
 
         let (signer, migrated, id_register, storage_rent, tier_purchase) = tokio::join!(
@@ -1102,11 +1067,20 @@ impl MessageReconciler {
             }
         }
 
+        if let Some(window) = window {
+            events.retain(|event| window.contains_unix_seconds(event.block_timestamp));
+            metrics::increment_backfill_window_fetched("onchain_events", events.len() as u64);
+        }
+
         trace!("Fetched {} total onchain events for FID {}", events.len(), fid);
         Ok(events)
     }
 
     pub fn message_to_hub_event(&self, message: Message) -> HubEvent {
+        Self::merge_message_event(message)
+    }
+
+    pub(crate) fn merge_message_event(message: Message) -> HubEvent {
         HubEvent {
             id: 0,
             r#type: HubEventType::MergeMessage as i32,
@@ -1135,6 +1109,8 @@ impl MessageReconciler {
                 Body::UserDataBody(_) => "UserData".to_string(),
                 Body::LinkBody(_) => "Link".to_string(),
                 Body::UsernameProofBody(_) => "UsernameProof".to_string(),
+                Body::FrameActionBody(_) => "FrameAction".to_string(),
+                Body::LinkCompactStateBody(_) => "LinkCompactState".to_string(),
                 _ => "Unknown".to_string(),
             };
         }
@@ -1166,6 +1142,41 @@ mod tests {
         assert_eq!(OnChainEventType::EventTypeTierPurchase as i32, 5);
     }
 
+    #[test]
+    fn test_time_window_bounds() {
+        let since = chrono::DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap().to_utc();
+        let until = chrono::DateTime::parse_from_rfc3339("2025-01-02T00:00:00Z").unwrap().to_utc();
+        let window = TimeWindow { since: Some(since), until: Some(until) };
+
+        // Farcaster time counts seconds from 2021-01-01
+        let start = (since.timestamp() - 1_609_459_200) as u64;
+        assert_eq!(window.start_timestamp(), Some(start));
+        assert_eq!(window.stop_timestamp(), Some(start + 86_400));
+
+        let message_at = |timestamp: u64| Message {
+            data: Some(proto::MessageData { timestamp: timestamp as u32, ..Default::default() }),
+            ..Default::default()
+        };
+        assert!(window.contains_message(&message_at(start)));
+        assert!(!window.contains_message(&message_at(start - 1)));
+        assert!(!window.contains_message(&message_at(start + 86_400)));
+        assert!(window.is_before_start(&message_at(start - 1)));
+        assert!(!window.is_before_start(&message_at(start + 86_400)));
+        assert!(!TimeWindow::default().is_before_start(&message_at(0)));
+
+        assert!(window.contains_unix_seconds(since.timestamp() as u64));
+        assert!(!window.contains_unix_seconds(until.timestamp() as u64));
+        assert!(TimeWindow::default().contains_unix_seconds(0));
+    }
+
+    #[test]
+    fn test_time_window_before_farcaster_epoch_clamps() {
+        let since = chrono::DateTime::parse_from_rfc3339("2020-06-01T00:00:00Z").unwrap().to_utc();
+        let window = TimeWindow { since: Some(since), until: None };
+        assert_eq!(window.start_timestamp(), Some(0));
+        assert_eq!(window.stop_timestamp(), None);
+    }
+
     #[test]
     fn test_lend_storage_message_type_is_15() {
         // Verify the proto enum value matches what flush_batch routes
@@ -4,7 +4,7 @@
 //! `root_parent_fid`, and `root_parent_url` columns for casts that were
 //! inserted before this feature was added.
 
-use crate::{hub::client::Hub, proto::CastId};
+use crate::{database::root_parent::forget_orphan, hub::client::Hub, proto::CastId};
 use sqlx::PgPool;
 use std::sync::Arc;
 use tracing::{debug, error, info, trace, warn};
@@ -166,6 +166,8 @@ impl RootParentBackfill {
             )
             .execute(&self.pool)
             .await?;
+            // Streaming may have queued it waiting for its parent
+            forget_orphan(&mut *self.pool.acquire().await?, cast_hash).await?;
 
             trace!("Updated cast {} with root_parent", hex::encode(cast_hash));
             Ok(true)
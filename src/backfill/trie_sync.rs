@@ -0,0 +1,525 @@
+//! Merkle trie diff sync.
+//!
+//! Each Hub shard keeps a merkle trie with one key per message, onchain event and
+//! fname proof, and `GetTrieMetadataByPrefix` returns any node's entry count and hash
+//! along with its children. Instead of fetching every FID, the sync walks a shard's
+//! trie top-down and only descends where the Hub and Postgres disagree:
+//!
+//! - Above the FID level, the Hub's count for a prefix is compared with the Postgres
+//!   entries of the shard's FIDs under that prefix. Local hashes there would need
+//!   every key in the database, so only counts are compared.
+//! - At the FID level, the FID's keys are loaded from Postgres and its count and
+//!   hash are computed the way the Hub computes them.
+//! - For a FID that differs, the Hub node for the FID is compared child by child,
+//!   one child per message type, onchain event type and fnames. Only those types
+//!   are fetched again.
+//!
+//! Keys are laid out as a bucket byte (the FID modulo 256), the FID as a big-endian
+//! u32, a postfix byte and the entry's identity: the message hash, the transaction
+//! hash and log index of an onchain event, or the fname. Message postfixes are the
+//! message type shifted left by 3, clear of onchain event types (1-6) and fnames (7).
+//! A leaf's hash is the first 20 bytes of the blake3 hash of its key, and a branch's
+//! hash is that of its children's hashes concatenated in byte order.
+//!
+//! Entries Postgres has and the Hub doesn't are reported by the counts but never
+//! fetched or deleted.
+
+use crate::{
+    backfill::reconciler::MessageKind,
+    core::util::calculate_message_hash,
+    database::trie::{self as db, FidKeyCount, TrieKeyRows},
+    hub::client::{Error as HubError, Hub},
+    proto::TrieNodeMetadataResponse,
+};
+use sqlx::PgPool;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+use tracing::debug;
+
+/// Leading key bytes that identify a FID: the bucket byte and the FID itself
+pub const FID_PREFIX_LEN: usize = 5;
+
+/// Postfix byte of fname proofs; onchain events use their type (1-6)
+const FNAME_POSTFIX: u8 = 7;
+
+/// FIDs requested per `GetFids` page
+const FID_PAGE_SIZE: u32 = 1000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TrieSyncError {
+    #[error("Hub error: {0}")]
+    Hub(#[from] HubError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+pub fn fid_prefix(fid: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(FID_PREFIX_LEN);
+    key.push((fid % 256) as u8);
+    key.extend_from_slice(&(fid as u32).to_be_bytes());
+    key
+}
+
+/// The FID a key or prefix belongs to, once it is long enough to tell
+pub fn fid_from_prefix(prefix: &[u8]) -> Option<u64> {
+    let bytes: [u8; 4] = prefix.get(1..FID_PREFIX_LEN)?.try_into().ok()?;
+    Some(u32::from_be_bytes(bytes) as u64)
+}
+
+pub fn message_key(fid: u64, message_type: u8, hash: &[u8]) -> Vec<u8> {
+    let mut key = fid_prefix(fid);
+    key.push(message_type << 3);
+    key.extend_from_slice(hash);
+    key
+}
+
+pub fn onchain_event_key(fid: u64, event_type: u8, tx_hash: &[u8], log_index: u32) -> Vec<u8> {
+    let mut key = fid_prefix(fid);
+    key.push(event_type);
+    key.extend_from_slice(tx_hash);
+    key.extend_from_slice(&log_index.to_be_bytes());
+    key
+}
+
+pub fn fname_key(fid: u64, name: &str) -> Vec<u8> {
+    let mut key = fid_prefix(fid);
+    key.push(FNAME_POSTFIX);
+    key.extend_from_slice(name.as_bytes());
+    key
+}
+
+/// What the child of a FID node holds, from its postfix byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrieEntry {
+    OnchainEvents,
+    Fnames,
+    Messages(MessageKind),
+    Unknown(u8),
+}
+
+impl TrieEntry {
+    pub fn from_postfix(postfix: u8) -> Self {
+        match postfix {
+            1..=6 => TrieEntry::OnchainEvents,
+            FNAME_POSTFIX => TrieEntry::Fnames,
+            _ if postfix.is_multiple_of(8) => MessageKind::from_message_type((postfix >> 3) as i32)
+                .map_or(TrieEntry::Unknown(postfix), TrieEntry::Messages),
+            _ => TrieEntry::Unknown(postfix),
+        }
+    }
+}
+
+/// Entry count and hash of a trie node
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeSummary {
+    pub count: u64,
+    pub hash: Vec<u8>,
+}
+
+impl NodeSummary {
+    fn matches(&self, node: &TrieNodeMetadataResponse) -> bool {
+        self.count == node.num_messages && self.hash == hub_hash(&node.hash)
+    }
+}
+
+/// Summarize sorted, deduplicated keys that share their first `depth` bytes, as the
+/// node at that depth
+pub fn summarize(keys: &[Vec<u8>], depth: usize) -> NodeSummary {
+    match keys {
+        [] => NodeSummary::default(),
+        [key] => NodeSummary { count: 1, hash: calculate_message_hash(key) },
+        _ => {
+            let hashes: Vec<u8> =
+                children(keys, depth).into_values().flat_map(|child| child.hash).collect();
+            NodeSummary { count: keys.len() as u64, hash: calculate_message_hash(&hashes) }
+        },
+    }
+}
+
+/// Summaries of the children of the node at `depth`, by their byte at that depth
+pub fn children(keys: &[Vec<u8>], depth: usize) -> BTreeMap<u8, NodeSummary> {
+    keys.chunk_by(|a, b| a.get(depth) == b.get(depth))
+        .filter_map(|group| Some((*group[0].get(depth)?, summarize(group, depth + 1))))
+        .collect()
+}
+
+/// Hub hashes are hex strings
+fn hub_hash(hash: &str) -> Vec<u8> {
+    hex::decode(hash).unwrap_or_else(|_| hash.as_bytes().to_vec())
+}
+
+/// Full prefix of a child node, whether the Hub returned it whole or as its last byte
+fn child_prefix(parent: &[u8], child: &TrieNodeMetadataResponse) -> Vec<u8> {
+    if child.prefix.len() == parent.len() + 1 && child.prefix.starts_with(parent) {
+        return child.prefix.clone();
+    }
+    let mut prefix = parent.to_vec();
+    prefix.extend(child.prefix.last());
+    prefix
+}
+
+/// Postgres entry counts of one shard's FIDs, ordered by FID prefix
+#[derive(Debug, Clone, Default)]
+pub struct LocalCounts {
+    prefixes: Vec<(Vec<u8>, u64)>,
+    /// Running totals: `totals[i]` is the sum of the first `i` counts
+    totals: Vec<u64>,
+}
+
+impl LocalCounts {
+    pub fn new(counts: impl IntoIterator<Item = (u64, u64)>) -> Self {
+        let mut prefixes: Vec<(Vec<u8>, u64)> =
+            counts.into_iter().map(|(fid, count)| (fid_prefix(fid), count)).collect();
+        prefixes.sort_unstable();
+        let mut totals = Vec::with_capacity(prefixes.len() + 1);
+        totals.push(0);
+        for (_, count) in &prefixes {
+            totals.push(totals.last().copied().unwrap_or_default() + count);
+        }
+        Self { prefixes, totals }
+    }
+
+    /// Entries of the FIDs whose keys start with `prefix`
+    pub fn count_under(&self, prefix: &[u8]) -> u64 {
+        let start = self.prefixes.partition_point(|(key, _)| key.as_slice() < prefix);
+        let end = self
+            .prefixes
+            .partition_point(|(key, _)| key.as_slice() < prefix || key.starts_with(prefix));
+        self.totals[end] - self.totals[start]
+    }
+
+    /// FIDs whose keys start with `prefix`
+    pub fn fids_under(&self, prefix: &[u8]) -> Vec<u64> {
+        self.prefixes
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .filter_map(|(key, _)| fid_from_prefix(key))
+            .collect()
+    }
+}
+
+/// What differs for one FID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FidDiff {
+    pub fid: u64,
+    pub hub_count: u64,
+    pub local_count: u64,
+    pub kinds: Vec<MessageKind>,
+    pub onchain_events: bool,
+    pub fnames: bool,
+}
+
+impl FidDiff {
+    fn new(fid: u64, hub_count: u64, local_count: u64) -> Self {
+        Self {
+            fid,
+            hub_count,
+            local_count,
+            kinds: Vec::new(),
+            onchain_events: false,
+            fnames: false,
+        }
+    }
+
+    /// Mark everything, for FIDs the Hub doesn't break down by type
+    fn everything(mut self) -> Self {
+        self.kinds = vec![
+            MessageKind::Casts,
+            MessageKind::Reactions,
+            MessageKind::Links,
+            MessageKind::LinkCompactStates,
+            MessageKind::Verifications,
+            MessageKind::UserData,
+            MessageKind::UsernameProofs,
+            MessageKind::LendStorage,
+        ];
+        self.onchain_events = true;
+        self.fnames = true;
+        self
+    }
+
+    fn add(&mut self, entry: TrieEntry) {
+        match entry {
+            TrieEntry::OnchainEvents => self.onchain_events = true,
+            TrieEntry::Fnames => self.fnames = true,
+            TrieEntry::Messages(kind) if !self.kinds.contains(&kind) => self.kinds.push(kind),
+            TrieEntry::Messages(_) => {},
+            TrieEntry::Unknown(postfix) => {
+                debug!("FID {} differs under unknown trie postfix {}", self.fid, postfix)
+            },
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty() && !self.onchain_events && !self.fnames
+    }
+}
+
+/// Result of diffing one shard
+#[derive(Debug, Clone, Default)]
+pub struct ShardDiff {
+    pub shard_id: u32,
+    pub fids_in_shard: usize,
+    pub nodes_fetched: u64,
+    pub fids: Vec<FidDiff>,
+}
+
+/// Walks Hub tries against Postgres
+pub struct TrieSync {
+    hub: Arc<Hub>,
+    pool: PgPool,
+}
+
+impl TrieSync {
+    pub fn new(hub: Arc<Hub>, pool: PgPool) -> Self {
+        Self { hub, pool }
+    }
+
+    /// Entry counts of every FID in Postgres, shared by all shards of a run
+    pub async fn local_counts(&self) -> Result<HashMap<u64, u64>, TrieSyncError> {
+        Ok(db::live_key_counts(&self.pool)
+            .await?
+            .into_iter()
+            .filter(|row| row.fid > 0)
+            .map(|FidKeyCount { fid, count }| (fid as u64, count.max(0) as u64))
+            .collect())
+    }
+
+    /// Find the FIDs of a shard, and the types within them, that Postgres is behind on
+    pub async fn diff_shard(
+        &self,
+        shard_id: u32,
+        local_counts: &HashMap<u64, u64>,
+    ) -> Result<ShardDiff, TrieSyncError> {
+        let shard_fids = self.shard_fids(shard_id).await?;
+        let counts = LocalCounts::new(
+            shard_fids.iter().map(|fid| (*fid, local_counts.get(fid).copied().unwrap_or(0))),
+        );
+        let mut diff =
+            ShardDiff { shard_id, fids_in_shard: shard_fids.len(), ..Default::default() };
+
+        let mut pending = vec![Vec::new()];
+        while let Some(prefix) = pending.pop() {
+            let node = self.hub.get_trie_metadata_by_prefix(shard_id, prefix.clone()).await?;
+            diff.nodes_fetched += 1;
+
+            // A node with a single entry is a leaf, and the Hub won't break it down
+            if node.children.is_empty() && node.num_messages > 0 {
+                for fid in counts.fids_under(&prefix) {
+                    let local = local_counts.get(&fid).copied().unwrap_or(0);
+                    diff.fids.push(FidDiff::new(fid, node.num_messages, local).everything());
+                }
+                continue;
+            }
+
+            let mut fid_nodes = Vec::new();
+            for child in &node.children {
+                let child_prefix = child_prefix(&prefix, child);
+                if child_prefix.len() < FID_PREFIX_LEN {
+                    if counts.count_under(&child_prefix) != child.num_messages {
+                        pending.push(child_prefix);
+                    }
+                } else if let Some(fid) = fid_from_prefix(&child_prefix) {
+                    fid_nodes.push((fid, child));
+                }
+            }
+            if !fid_nodes.is_empty() {
+                self.diff_fids(shard_id, &fid_nodes, &mut diff).await?;
+            }
+        }
+
+        diff.fids.sort_unstable_by_key(|fid_diff| fid_diff.fid);
+        Ok(diff)
+    }
+
+    /// Compare FID nodes by count and hash, and the differing ones type by type
+    async fn diff_fids(
+        &self,
+        shard_id: u32,
+        fid_nodes: &[(u64, &TrieNodeMetadataResponse)],
+        diff: &mut ShardDiff,
+    ) -> Result<(), TrieSyncError> {
+        let fids: Vec<i64> = fid_nodes.iter().map(|(fid, _)| *fid as i64).collect();
+        let keys = local_keys(db::live_keys(&self.pool, &fids).await?);
+
+        for &(fid, hub_node) in fid_nodes {
+            let fid_keys = keys.get(&fid).map(Vec::as_slice).unwrap_or_default();
+            let local = summarize(fid_keys, FID_PREFIX_LEN);
+            if local.matches(hub_node) {
+                continue;
+            }
+
+            let mut fid_diff = FidDiff::new(fid, hub_node.num_messages, local.count);
+            let prefix = fid_prefix(fid);
+            let node = self.hub.get_trie_metadata_by_prefix(shard_id, prefix.clone()).await?;
+            diff.nodes_fetched += 1;
+            if node.children.is_empty() {
+                fid_diff = fid_diff.everything();
+            } else {
+                let local_children = children(fid_keys, FID_PREFIX_LEN);
+                for child in &node.children {
+                    let Some(&postfix) = child_prefix(&prefix, child).get(FID_PREFIX_LEN) else {
+                        continue;
+                    };
+                    if !local_children.get(&postfix).is_some_and(|local| local.matches(child)) {
+                        fid_diff.add(TrieEntry::from_postfix(postfix));
+                    }
+                }
+            }
+
+            if !fid_diff.is_empty() {
+                diff.fids.push(fid_diff);
+            }
+        }
+        Ok(())
+    }
+
+    async fn shard_fids(&self, shard_id: u32) -> Result<Vec<u64>, TrieSyncError> {
+        let mut fids = Vec::new();
+        let mut page_token = None;
+        loop {
+            let response =
+                self.hub.get_shard_fids(shard_id, Some(FID_PAGE_SIZE), page_token).await?;
+            fids.extend(response.fids);
+            match response.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+        Ok(fids)
+    }
+}
+
+/// Trie keys per FID, sorted and deduplicated
+fn local_keys(rows: TrieKeyRows) -> HashMap<u64, Vec<Vec<u8>>> {
+    let mut keys: HashMap<u64, Vec<Vec<u8>>> = HashMap::new();
+    for row in rows.messages {
+        let fid = row.fid as u64;
+        keys.entry(fid).or_default().push(message_key(fid, row.message_type as u8, &row.hash));
+    }
+    for row in rows.onchain_events {
+        let fid = row.fid as u64;
+        keys.entry(fid).or_default().push(onchain_event_key(
+            fid,
+            row.event_type as u8,
+            &row.tx_hash,
+            row.log_index as u32,
+        ));
+    }
+    for row in rows.fnames {
+        let fid = row.fid as u64;
+        keys.entry(fid).or_default().push(fname_key(fid, &row.username));
+    }
+    for fid_keys in keys.values_mut() {
+        fid_keys.sort_unstable();
+        fid_keys.dedup();
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_start_with_the_fid_prefix() {
+        let key = message_key(258, 3, &[0xaa; 20]);
+        assert_eq!(&key[..FID_PREFIX_LEN], &[2, 0, 0, 1, 2]);
+        assert_eq!(key[FID_PREFIX_LEN], 3 << 3);
+        assert_eq!(fid_from_prefix(&key), Some(258));
+        assert_eq!(fid_from_prefix(&key[..3]), None);
+
+        let event = onchain_event_key(258, 4, &[0xbb; 32], 7);
+        assert_eq!(TrieEntry::from_postfix(event[FID_PREFIX_LEN]), TrieEntry::OnchainEvents);
+        let fname = fname_key(258, "dwr");
+        assert_eq!(TrieEntry::from_postfix(fname[FID_PREFIX_LEN]), TrieEntry::Fnames);
+        assert_eq!(TrieEntry::from_postfix(3 << 3), TrieEntry::Messages(MessageKind::Reactions));
+        assert_eq!(TrieEntry::from_postfix(13 << 3), TrieEntry::Unknown(13 << 3));
+    }
+
+    #[test]
+    fn summaries_hash_leaves_and_branches() {
+        let a = message_key(3, 1, &[1; 20]);
+        let b = message_key(3, 1, &[2; 20]);
+        let c = message_key(3, 3, &[1; 20]);
+
+        let single = summarize(std::slice::from_ref(&a), FID_PREFIX_LEN);
+        assert_eq!(single, NodeSummary { count: 1, hash: calculate_message_hash(&a) });
+
+        let keys = vec![a.clone(), b.clone(), c.clone()];
+        let by_type = children(&keys, FID_PREFIX_LEN);
+        assert_eq!(by_type.keys().copied().collect::<Vec<_>>(), vec![1 << 3, 3 << 3]);
+        assert_eq!(by_type[&(3 << 3)].hash, calculate_message_hash(&c));
+        assert_eq!(by_type[&(1 << 3)].count, 2);
+
+        let casts = children(&keys[..2], FID_PREFIX_LEN + 1);
+        let mut concat = casts[&1].hash.clone();
+        concat.extend(&casts[&2].hash);
+        assert_eq!(by_type[&(1 << 3)].hash, calculate_message_hash(&concat));
+
+        let mut concat = by_type[&(1 << 3)].hash.clone();
+        concat.extend(&by_type[&(3 << 3)].hash);
+        assert_eq!(summarize(&keys, FID_PREFIX_LEN).hash, calculate_message_hash(&concat));
+    }
+
+    #[test]
+    fn local_counts_sum_by_prefix() {
+        // FIDs 1 and 257 share bucket 1; FID 2 is in bucket 2
+        let counts = LocalCounts::new([(1, 10), (257, 5), (2, 7)]);
+        assert_eq!(counts.count_under(&[]), 22);
+        assert_eq!(counts.count_under(&[1]), 15);
+        assert_eq!(counts.count_under(&fid_prefix(257)), 5);
+        assert_eq!(counts.count_under(&[3]), 0);
+        assert_eq!(counts.fids_under(&[1]), vec![1, 257]);
+    }
+
+    #[test]
+    fn child_prefixes_are_made_whole() {
+        let child = |prefix: Vec<u8>| TrieNodeMetadataResponse { prefix, ..Default::default() };
+        assert_eq!(child_prefix(&[1, 0], &child(vec![1, 0, 5])), vec![1, 0, 5]);
+        assert_eq!(child_prefix(&[1, 0], &child(vec![5])), vec![1, 0, 5]);
+    }
+
+    /// Rebuilds a FID's CastAdd node from the casts `GetCastsByFid` returns and compares
+    /// it with the node on a live Hub, which pins down the key layout and hashing
+    #[tokio::test]
+    #[ignore] // Run with --ignored when a Hub is reachable
+    async fn fid_node_matches_the_hub() {
+        use crate::{config::HubConfig, proto::FidRequest};
+
+        let fid = 3;
+        let mut hub = Hub::new(HubConfig::default()).expect("hub client");
+        hub.connect().await.expect("connect to hub");
+
+        let mut keys = Vec::new();
+        let mut page_token = None;
+        loop {
+            let request =
+                FidRequest { fid, page_size: Some(1000), page_token, ..Default::default() };
+            let page = hub.get_casts_by_fid(request).await.expect("casts by fid");
+            keys.extend(page.messages.iter().map(|msg| message_key(fid, 1, &msg.hash)));
+            page_token = page.next_page_token.filter(|token| !token.is_empty());
+            if page_token.is_none() {
+                break;
+            }
+        }
+        keys.sort_unstable();
+        keys.dedup();
+
+        let mut prefix = fid_prefix(fid);
+        prefix.push(1 << 3);
+        let info = hub.get_hub_info().await.expect("hub info");
+        for shard_id in 1..=info.num_shards {
+            let node = hub.get_trie_metadata_by_prefix(shard_id, prefix.clone()).await.unwrap();
+            if node.num_messages > 0 {
+                let local = summarize(&keys, prefix.len());
+                assert!(local.matches(&node), "local {local:?} differs from hub {node:?}");
+                return;
+            }
+        }
+        panic!("FID {fid} has no casts on any shard");
+    }
+}
@@ -1,18 +1,31 @@
 use crate::{
-    backfill::reconciler::{MessageReconciler, ReconcileStats},
+    backfill::{
+        blocks::{BlockCheckpointStore, BlockReplayer, HeightRange},
+        conversation::ConversationReconciler,
+        progress::{CurrentJob, ErrorKind, ProgressStore, WorkerProgress},
+        reconciler::{MessageReconciler, ReconcileStats, TimeWindow},
+    },
     hub::filter::SpamFilter,
     metrics,
     processor::consumer::EventProcessor,
+    proto::CastId,
     redis::client::Redis,
 };
 use futures::{StreamExt, stream};
 use serde::{Deserialize, Serialize};
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
 use tokio::{
     sync::{RwLock, mpsc},
     time,
 };
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BackfillJob {
@@ -31,6 +44,67 @@ pub struct BackfillJob {
     pub id: String,
     #[serde(default)]
     pub started_at: Option<chrono::DateTime<chrono::Utc>>, // Track when job processing started
+    /// Only reconcile messages and onchain events inside this window; `None` means full history
+    #[serde(default)]
+    pub window: Option<TimeWindow>,
+    #[serde(default)]
+    pub kind: JobKind,
+}
+
+/// What a job backfills
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobKind {
+    /// Every message type and onchain event of the job's FIDs
+    #[default]
+    Fids,
+    /// A cast, its replies at every depth and the reactions on all of them
+    Thread {
+        fid: u64,
+        /// Hex-encoded cast hash
+        hash: String,
+    },
+    /// Casts under a parent URL, such as a channel, inside the job's window
+    Channel { url: String },
+    /// Block heights `start..end` of a shard, replayed from Snapchain
+    Blocks { shard_id: u32, start: u64, end: u64 },
+}
+
+impl JobKind {
+    /// The root cast of a thread job, if the hash is valid hex
+    pub fn thread_root(&self) -> Option<CastId> {
+        match self {
+            JobKind::Thread { fid, hash } => hex::decode(hash.trim_start_matches("0x"))
+                .ok()
+                .map(|hash| CastId { fid: *fid, hash }),
+            _ => None,
+        }
+    }
+
+    /// The heights of a block job
+    pub fn height_range(&self) -> Option<HeightRange> {
+        match self {
+            JobKind::Blocks { shard_id, start, end } => {
+                Some(HeightRange { shard_id: *shard_id, start: *start, end: *end })
+            },
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for JobKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobKind::Fids => write!(f, "FIDs"),
+            JobKind::Thread { fid, hash } => {
+                write!(f, "thread {}:0x{}", fid, hash.trim_start_matches("0x"))
+            },
+            JobKind::Channel { url } => write!(f, "channel {}", url),
+            JobKind::Blocks { shard_id, start, end } => {
+                write!(f, "shard {} blocks {}..{}", shard_id, start, end)
+            },
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
@@ -41,6 +115,28 @@ pub enum JobPriority {
     Low,
 }
 
+impl JobPriority {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "high" => Some(JobPriority::High),
+            "normal" => Some(JobPriority::Normal),
+            "low" => Some(JobPriority::Low),
+            _ => None,
+        }
+    }
+
+    /// Order in which the priority queues are polled on a given turn. Out of every 7 polls,
+    /// High is tried first 4 times, Normal twice and Low once, so a busy High queue drains
+    /// fastest without starving the others.
+    fn poll_order(turn: u64) -> [JobPriority; 3] {
+        match turn % 7 {
+            0..=3 => [JobPriority::High, JobPriority::Normal, JobPriority::Low],
+            4 | 5 => [JobPriority::Normal, JobPriority::High, JobPriority::Low],
+            _ => [JobPriority::Low, JobPriority::High, JobPriority::Normal],
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
 pub enum JobState {
     #[default]
@@ -52,9 +148,14 @@ pub enum JobState {
 
 pub struct BackfillQueue {
     redis: Arc<Redis>,
+    high_queue_key: String,
     queue_key: String,
+    low_queue_key: String,
     in_progress_queue_key: String,
     metrics: Arc<tokio::sync::RwLock<QueueMetrics>>,
+    /// Number of polls so far, used to rotate between the priority queues
+    turn: AtomicU64,
+    progress: ProgressStore,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -64,10 +165,21 @@ pub struct QueueMetrics {
     pub jobs_failed: u64,
     pub fids_processed: u64,
     pub avg_job_time_ms: f64,
+    pub high_priority_queue_size: u64,
     pub normal_priority_queue_size: u64,
+    pub low_priority_queue_size: u64,
     pub in_progress_queue_size: u64,
 }
 
+impl QueueMetrics {
+    /// Jobs waiting across all priority queues
+    pub fn pending_jobs(&self) -> u64 {
+        self.high_priority_queue_size
+            + self.normal_priority_queue_size
+            + self.low_priority_queue_size
+    }
+}
+
 impl BackfillQueue {
     pub fn new(redis: Arc<Redis>, queue_key: String) -> Self {
         let base_key = if queue_key.ends_with(":queue") {
@@ -77,10 +189,27 @@ impl BackfillQueue {
         };
 
         Self {
-            redis,
+            redis: redis.clone(),
+            high_queue_key: format!("{}:high", base_key),
             queue_key: format!("{}:normal", base_key),
+            low_queue_key: format!("{}:low", base_key),
             in_progress_queue_key: format!("{}:inprogress", base_key),
             metrics: Arc::new(tokio::sync::RwLock::new(QueueMetrics::default())),
+            turn: AtomicU64::new(0),
+            progress: ProgressStore::new(redis),
+        }
+    }
+
+    /// Shared progress totals for jobs on this queue
+    pub fn progress(&self) -> &ProgressStore {
+        &self.progress
+    }
+
+    fn key_for(&self, priority: &JobPriority) -> &str {
+        match priority {
+            JobPriority::High => &self.high_queue_key,
+            JobPriority::Normal => &self.queue_key,
+            JobPriority::Low => &self.low_queue_key,
         }
     }
 
@@ -88,7 +217,9 @@ impl BackfillQueue {
     /// Uses more efficient pattern without unnecessary cloning
     pub async fn get_metrics(&self) -> QueueMetrics {
         // First fetch the queue sizes without holding the lock
+        let high = self.get_queue_length_for_key(&self.high_queue_key).await.unwrap_or(0);
         let normal = self.get_queue_length_for_key(&self.queue_key).await.unwrap_or(0);
+        let low = self.get_queue_length_for_key(&self.low_queue_key).await.unwrap_or(0);
         let in_progress =
             self.get_queue_length_for_key(&self.in_progress_queue_key).await.unwrap_or(0);
 
@@ -102,7 +233,9 @@ impl BackfillQueue {
             jobs_failed: metrics_guard.jobs_failed,
             fids_processed: metrics_guard.fids_processed,
             avg_job_time_ms: metrics_guard.avg_job_time_ms,
+            high_priority_queue_size: high as u64,
             normal_priority_queue_size: normal as u64,
+            low_priority_queue_size: low as u64,
             in_progress_queue_size: in_progress as u64,
         }
     }
@@ -113,7 +246,7 @@ impl BackfillQueue {
     ) -> Result<usize, crate::redis::error::Error> {
         match self.redis.llen(key).await {
             Ok(len) => {
-                info!("Queue {} has {} jobs remaining", key, len as usize);
+                debug!("Queue {} has {} jobs remaining", key, len as usize);
                 Ok(len as usize)
             },
             Err(e) => {
@@ -124,13 +257,16 @@ impl BackfillQueue {
     }
 
     pub async fn add_job(&self, mut job: BackfillJob) -> Result<(), crate::redis::error::Error> {
+        // Retries and split-off remainders keep an ID; only jobs without one are new work
+        let new_work = job.id.is_empty();
+
         // Set job ID if not provided
         if job.id.is_empty() {
             job.id = uuid::Uuid::new_v4().to_string();
         }
 
-        // Always use the normal queue now
-        let queue_key = &self.queue_key;
+        // Each priority has its own list
+        let queue_key = self.key_for(&job.priority);
 
         // Serialize job once to avoid cloning
         let job_data = serde_json::to_string(&job)
@@ -165,6 +301,10 @@ impl BackfillQueue {
                 // Update StatsD metrics
                 metrics::set_jobs_in_queue(fid_count as u64);
 
+                if new_work && let Err(e) = self.progress.add_queued(fid_count as u64).await {
+                    warn!("Failed to count job {} towards backfill progress: {:?}", job_id, e);
+                }
+
                 // Log current queue length after adding
                 if let Ok(queue_len) = self.get_queue_length_for_key(queue_key).await {
                     info!(
@@ -189,12 +329,19 @@ impl BackfillQueue {
             tokio::time::sleep(Duration::from_millis(100)).await;
         }
 
-        // Only check the normal queue now
+        // BRPOP pops from the first non-empty list, so rotating which priority comes first
+        // gives weighted fair scheduling across the queues
+        let turn = self.turn.fetch_add(1, Ordering::Relaxed);
+        let keys = JobPriority::poll_order(turn)
+            .iter()
+            .map(|priority| self.key_for(priority).to_string())
+            .collect();
+
         // Use longer timeout for BRPOP in containerized environments
-        let result = self.redis.brpop(vec![self.queue_key.clone()], 5).await?; // 5 second timeout
+        let result = self.redis.brpop(keys, 5).await?; // 5 second timeout
 
         match result {
-            Some((_, job_data)) => {
+            Some((queue_key, job_data)) => {
                 let mut job: BackfillJob = serde_json::from_str(&job_data)
                     .map_err(|e| crate::redis::error::Error::DeserializationError(e.to_string()))?;
 
@@ -216,7 +363,7 @@ impl BackfillQueue {
                     "Retrieved backfill job {} with {} FIDs from queue {} (attempt: {}), FID range: {:?}..{:?}",
                     job.id,
                     job.fids.len(),
-                    &self.queue_key,
+                    queue_key,
                     job.attempts,
                     job.fids.first(),
                     job.fids.last()
@@ -286,9 +433,12 @@ impl BackfillQueue {
     }
 
     pub async fn get_queue_length(&self) -> Result<usize, crate::redis::error::Error> {
-        let normal = self.get_queue_length_for_key(&self.queue_key).await?;
-        info!("Total queue length: {}", normal);
-        Ok(normal)
+        let mut total = 0;
+        for key in [&self.high_queue_key, &self.queue_key, &self.low_queue_key] {
+            total += self.get_queue_length_for_key(key).await?;
+        }
+        info!("Total queue length: {}", total);
+        Ok(total)
     }
 
     /// Clean up expired jobs from the in-progress queue
@@ -341,8 +491,8 @@ impl BackfillQueue {
 
 // Message types for worker stats updates
 enum StatsUpdate {
-    JobCompleted { fid_count: usize, spam_count: usize },
-    Error,
+    JobCompleted { fid_count: usize, spam_count: usize, failed_count: usize },
+    Error(ErrorKind),
     HighestFidUpdate(u64),
 }
 
@@ -352,12 +502,16 @@ struct WorkerStats {
     fids_processed: usize,
     spam_fids_skipped: usize,
     errors: usize,
+    errors_by_kind: BTreeMap<&'static str, u64>,
     start_time: Option<std::time::Instant>,
+    started_at: Option<chrono::DateTime<chrono::Utc>>,
     highest_fid_processed: u64,
 }
 
 pub struct Worker {
     reconciler: Arc<MessageReconciler>,
+    conversations: Arc<ConversationReconciler>,
+    blocks: Arc<BlockReplayer>,
     queue: Arc<BackfillQueue>,
     processors: Vec<Arc<dyn EventProcessor>>,
     concurrency: usize,
@@ -371,6 +525,10 @@ pub struct Worker {
     max_fid_to_process: Option<u64>,
     // Exit when queue is empty for this many seconds (None = run forever)
     exit_on_complete_timeout: Option<u64>,
+    // Identifies this worker's progress snapshot in Redis
+    worker_id: String,
+    // Jobs being processed right now, keyed by job ID
+    current_jobs: Arc<RwLock<BTreeMap<String, CurrentJob>>>,
 }
 
 impl Worker {
@@ -394,6 +552,11 @@ impl Worker {
         let hub_connection_limiter = Arc::new(tokio::sync::Semaphore::new(10));
 
         Self {
+            conversations: Arc::new(ConversationReconciler::new(reconciler.hub())),
+            blocks: Arc::new(
+                BlockReplayer::new(reconciler.hub())
+                    .with_checkpoints(Arc::new(BlockCheckpointStore::new(queue.redis.clone()))),
+            ),
             reconciler,
             queue,
             processors: Vec::new(),
@@ -401,6 +564,7 @@ impl Worker {
             shutdown: Arc::new(RwLock::new(false)),
             stats: WorkerStats {
                 start_time: Some(std::time::Instant::now()),
+                started_at: Some(chrono::Utc::now()),
                 ..Default::default()
             },
             spam_filter,
@@ -408,6 +572,8 @@ impl Worker {
             auto_queue_enabled: false,
             max_fid_to_process: None,
             exit_on_complete_timeout: None,
+            worker_id: uuid::Uuid::new_v4().simple().to_string()[..12].to_string(),
+            current_jobs: Arc::new(RwLock::new(BTreeMap::new())),
         }
     }
 
@@ -428,6 +594,163 @@ impl Worker {
         info!("Auto-queueing enabled with max FID: {}", max_fid);
     }
 
+    /// Publish this worker's progress snapshot for `waypoint backfill status`
+    async fn publish_progress(&self) {
+        let elapsed = self.stats.start_time.map(|start| start.elapsed()).unwrap_or_default();
+        let finished = self.stats.fids_processed + self.stats.spam_fids_skipped;
+        let snapshot = WorkerProgress {
+            worker_id: self.worker_id.clone(),
+            started_at: self.stats.started_at,
+            updated_at: Some(chrono::Utc::now()),
+            jobs_processed: self.stats.jobs_processed as u64,
+            fids_processed: self.stats.fids_processed as u64,
+            spam_fids_skipped: self.stats.spam_fids_skipped as u64,
+            fids_per_second: if elapsed.as_secs() > 0 {
+                finished as f64 / elapsed.as_secs_f64()
+            } else {
+                0.0
+            },
+            errors: self
+                .stats
+                .errors_by_kind
+                .iter()
+                .map(|(kind, count)| (kind.to_string(), *count))
+                .collect(),
+            current_jobs: self.current_jobs.read().await.values().cloned().collect(),
+        };
+
+        if let Err(e) = self.queue.progress().publish_worker(&snapshot).await {
+            warn!("Failed to publish backfill progress: {:?}", e);
+        }
+    }
+
+    /// Run a thread or channel job, which backfills a conversation rather than FIDs
+    async fn spawn_conversation_job(
+        &self,
+        job: BackfillJob,
+        tx: mpsc::Sender<StatsUpdate>,
+    ) -> tokio::task::JoinHandle<Result<(), Box<dyn std::error::Error + Send + Sync>>> {
+        info!("Starting backfill job {} for {}", job.id, job.kind);
+        self.current_jobs.write().await.insert(
+            job.id.clone(),
+            CurrentJob {
+                id: job.id.clone(),
+                fids: 0,
+                first_fid: None,
+                last_fid: None,
+                target: Some(job.kind.to_string()),
+                started_at: job.started_at.unwrap_or_else(chrono::Utc::now),
+            },
+        );
+
+        let conversations = Arc::clone(&self.conversations);
+        let processors = self.processors.clone();
+        let hub_connection_limiter = Arc::clone(&self.hub_connection_limiter);
+        let queue = Arc::clone(&self.queue);
+        let current_jobs = Arc::clone(&self.current_jobs);
+
+        tokio::spawn(async move {
+            let _hub_permit = hub_connection_limiter
+                .acquire()
+                .await
+                .expect("hub connection semaphore should not be closed");
+
+            for processor in &processors {
+                let result = match &job.kind {
+                    JobKind::Channel { url } => {
+                        conversations.reconcile_channel(url, job.window, processor.clone()).await
+                    },
+                    kind => match kind.thread_root() {
+                        Some(root) => conversations.reconcile_thread(root, processor.clone()).await,
+                        None => {
+                            error!("Job {} has an invalid thread root: {}", job.id, kind);
+                            let _ = tx.send(StatsUpdate::Error(ErrorKind::Conversation)).await;
+                            break;
+                        },
+                    },
+                };
+                if let Err(e) = result {
+                    error!("Error backfilling {} for job {}: {:?}", job.kind, job.id, e);
+                    let _ = tx.send(StatsUpdate::Error(ErrorKind::Conversation)).await;
+                }
+            }
+
+            let _ = tx
+                .send(StatsUpdate::JobCompleted { fid_count: 0, spam_count: 0, failed_count: 0 })
+                .await;
+            if let Err(e) = queue.complete_job(&job.id).await {
+                error!("Failed to mark job {} as complete: {:?}", job.id, e);
+            }
+            current_jobs.write().await.remove(&job.id);
+            Ok(())
+        })
+    }
+
+    /// Run a block job, which replays a range of a shard's blocks through every processor
+    async fn spawn_block_job(
+        &self,
+        job: BackfillJob,
+        tx: mpsc::Sender<StatsUpdate>,
+    ) -> tokio::task::JoinHandle<Result<(), Box<dyn std::error::Error + Send + Sync>>> {
+        info!("Starting backfill job {} for {}", job.id, job.kind);
+        self.current_jobs.write().await.insert(
+            job.id.clone(),
+            CurrentJob {
+                id: job.id.clone(),
+                fids: 0,
+                first_fid: None,
+                last_fid: None,
+                target: Some(job.kind.to_string()),
+                started_at: job.started_at.unwrap_or_else(chrono::Utc::now),
+            },
+        );
+
+        let blocks = Arc::clone(&self.blocks);
+        let processors = self.processors.clone();
+        let hub_connection_limiter = Arc::clone(&self.hub_connection_limiter);
+        let queue = Arc::clone(&self.queue);
+        let current_jobs = Arc::clone(&self.current_jobs);
+
+        tokio::spawn(async move {
+            let _hub_permit = hub_connection_limiter
+                .acquire()
+                .await
+                .expect("hub connection semaphore should not be closed");
+
+            let mut failed = false;
+            if let Some(range) = job.kind.height_range()
+                && let Err(e) = blocks.replay_range(range, &processors).await
+            {
+                error!("Error replaying {} for job {}: {:?}", range, job.id, e);
+                let _ = tx.send(StatsUpdate::Error(ErrorKind::Blocks)).await;
+                failed = true;
+            }
+
+            if !failed {
+                let _ = tx
+                    .send(StatsUpdate::JobCompleted {
+                        fid_count: 0,
+                        spam_count: 0,
+                        failed_count: 0,
+                    })
+                    .await;
+            }
+            if let Err(e) = queue.complete_job(&job.id).await {
+                error!("Failed to mark job {} as complete: {:?}", job.id, e);
+            }
+            // The range keeps its checkpoint, so the retry resumes where this one failed
+            if failed {
+                let mut retry = job.clone();
+                retry.started_at = None;
+                if let Err(e) = queue.retry_job(retry).await {
+                    error!("Failed to requeue job {}: {:?}", job.id, e);
+                }
+            }
+            current_jobs.write().await.remove(&job.id);
+            Ok(())
+        })
+    }
+
     async fn log_stats(&self, queue_length: usize) {
         let elapsed = if let Some(start_time) = self.stats.start_time {
             start_time.elapsed()
@@ -483,15 +806,23 @@ impl Worker {
         let stats = Arc::new(RwLock::new(self.stats.clone()));
         let stats_task = {
             let stats = stats.clone();
+            let progress = self.queue.progress().clone();
             tokio::spawn(async move {
                 while let Some(update) = rx.recv().await {
                     let mut stats_guard = stats.write().await;
                     match update {
-                        StatsUpdate::JobCompleted { fid_count, spam_count } => {
+                        StatsUpdate::JobCompleted { fid_count, spam_count, failed_count } => {
                             stats_guard.jobs_processed += 1;
                             stats_guard.fids_processed += fid_count;
                             stats_guard.spam_fids_skipped += spam_count;
 
+                            if let Err(e) = progress
+                                .add_finished((fid_count + spam_count) as u64, failed_count as u64)
+                                .await
+                            {
+                                warn!("Failed to record backfill progress: {:?}", e);
+                            }
+
                             // Update metrics
                             metrics::increment_jobs_processed();
                             metrics::increment_fids_processed(fid_count as u64);
@@ -504,9 +835,14 @@ impl Worker {
                                 stats_guard.errors
                             );
                         },
-                        StatsUpdate::Error => {
+                        StatsUpdate::Error(kind) => {
                             stats_guard.errors += 1;
+                            *stats_guard.errors_by_kind.entry(kind.as_str()).or_default() += 1;
                             metrics::increment_job_errors();
+
+                            if let Err(e) = progress.add_error(kind).await {
+                                warn!("Failed to record backfill error: {:?}", e);
+                            }
                         },
                         StatsUpdate::HighestFidUpdate(fid) => {
                             if fid > stats_guard.highest_fid_processed {
@@ -521,6 +857,7 @@ impl Worker {
 
         let mut handles = Vec::new();
         let mut last_progress_log = std::time::Instant::now();
+        let mut last_progress_publish: Option<std::time::Instant> = None;
         let mut last_cleanup = std::time::Instant::now();
         let mut idle_since: Option<std::time::Instant> = None;
 
@@ -548,17 +885,25 @@ impl Worker {
             );
             let active_tasks = handles.len();
 
+            // Publish progress every 5 seconds, well inside the snapshot's expiry
+            if last_progress_publish.is_none_or(|at| at.elapsed() > Duration::from_secs(5)) {
+                self.publish_progress().await;
+                last_progress_publish = Some(std::time::Instant::now());
+            }
+
             // Log progress every 10 seconds
             if last_progress_log.elapsed() > Duration::from_secs(10) {
                 let metrics = self.queue.get_metrics().await;
                 info!(
-                    "Queue state - Normal: {}, In Progress: {}, Total processed: {}",
+                    "Queue state - High: {}, Normal: {}, Low: {}, In Progress: {}, Total processed: {}",
+                    metrics.high_priority_queue_size,
                     metrics.normal_priority_queue_size,
+                    metrics.low_priority_queue_size,
                     metrics.in_progress_queue_size,
                     metrics.jobs_processed
                 );
 
-                let total_queue_length = metrics.normal_priority_queue_size;
+                let total_queue_length = metrics.pending_jobs();
                 self.log_stats(total_queue_length as usize).await;
                 last_progress_log = std::time::Instant::now();
             }
@@ -567,8 +912,9 @@ impl Worker {
             if active_tasks < self.concurrency {
                 let pre_metrics = self.queue.get_metrics().await;
                 debug!(
-                    "Attempting to get job. Current queue state - Normal: {}, In Progress: {}",
-                    pre_metrics.normal_priority_queue_size, pre_metrics.in_progress_queue_size
+                    "Attempting to get job. Current queue state - Pending: {}, In Progress: {}",
+                    pre_metrics.pending_jobs(),
+                    pre_metrics.in_progress_queue_size
                 );
 
                 match self.queue.get_job().await {
@@ -576,6 +922,18 @@ impl Worker {
                         // Reset idle timer when we get a job
                         idle_since = None;
 
+                        match job.kind {
+                            JobKind::Fids => {},
+                            JobKind::Blocks { .. } => {
+                                handles.push(self.spawn_block_job(job, tx.clone()).await);
+                                continue;
+                            },
+                            _ => {
+                                handles.push(self.spawn_conversation_job(job, tx.clone()).await);
+                                continue;
+                            },
+                        }
+
                         // Limit the number of FIDs processed per job to avoid overwhelming the Hub and database
                         // If there are too many FIDs in a job, split it into smaller chunks
                         // Increased batch size since we're no longer limiting database connections per task
@@ -596,6 +954,8 @@ impl Worker {
                                 created_at: chrono::Utc::now(),
                                 id: uuid::Uuid::new_v4().to_string(),
                                 started_at: None, // New jobs haven't started yet
+                                window: job.window,
+                                kind: JobKind::Fids,
                             };
 
                             // Add the remaining job back to the queue with the same priority
@@ -633,17 +993,31 @@ impl Worker {
                         let processors = self.processors.clone();
                         let tx_clone = tx.clone();
                         let fids = job.fids.clone();
+                        let window = job.window;
                         let fid_count = job.fids.len();
                         let spam_filter = Arc::clone(&self.spam_filter);
                         let hub_connection_limiter = Arc::clone(&self.hub_connection_limiter);
                         let highest_fid_in_job = *job.fids.iter().max().unwrap_or(&0);
 
+                        self.current_jobs.write().await.insert(
+                            job.id.clone(),
+                            CurrentJob {
+                                id: job.id.clone(),
+                                fids: fid_count,
+                                first_fid: job.fids.first().copied(),
+                                last_fid: job.fids.last().copied(),
+                                target: None,
+                                started_at: job.started_at.unwrap_or_else(chrono::Utc::now),
+                            },
+                        );
+
                         info!(
-                            "Starting backfill job {} with {} FIDs (range: {:?}..{:?})",
+                            "Starting backfill job {} with {} FIDs (range: {:?}..{:?}, window: {})",
                             job.id,
                             fid_count,
                             job.fids.first(),
-                            job.fids.last()
+                            job.fids.last(),
+                            window.map_or_else(|| "full history".to_string(), |w| w.to_string())
                         );
 
                         let tx_for_highest = tx_clone.clone();
@@ -682,7 +1056,11 @@ impl Worker {
                             if !non_spam_fids.is_empty() {
                                 for processor in &processors {
                                     match reconciler
-                                        .reconcile_fids_batch(&non_spam_fids, processor.clone())
+                                        .reconcile_fids_batch(
+                                            &non_spam_fids,
+                                            window,
+                                            processor.clone(),
+                                        )
                                         .await
                                     {
                                         Ok((success_count, error_count)) => {
@@ -698,14 +1076,18 @@ impl Worker {
 
                                             metrics::increment_fids_processed(success_count as u64);
                                             if error_count > 0 {
-                                                let _ = tx_clone.send(StatsUpdate::Error).await;
+                                                let _ = tx_clone
+                                                    .send(StatsUpdate::Error(ErrorKind::Reconcile))
+                                                    .await;
                                                 metrics::increment_job_errors();
                                             }
                                         },
                                         Err(e) => {
                                             error!("Error in batch reconciliation: {:?}", e);
                                             job_error_count += non_spam_fids.len();
-                                            let _ = tx_clone.send(StatsUpdate::Error).await;
+                                            let _ = tx_clone
+                                                .send(StatsUpdate::Error(ErrorKind::Batch))
+                                                .await;
                                             metrics::increment_job_errors();
                                         },
                                     }
@@ -726,7 +1108,9 @@ impl Worker {
                                         let processor = Arc::clone(&processors[0]); // Use first processor
                                         async move {
                                             reconciler
-                                                .reconcile_onchain_events_only(fid, processor)
+                                                .reconcile_onchain_events_only(
+                                                    fid, window, processor,
+                                                )
                                                 .await
                                         }
                                     })
@@ -742,6 +1126,9 @@ impl Worker {
                                                 "Error processing onchain events for spam FID: {:?}",
                                                 e
                                             );
+                                            let _ = tx_clone
+                                                .send(StatsUpdate::Error(ErrorKind::OnchainEvents))
+                                                .await;
                                         },
                                     }
                                 }
@@ -768,6 +1155,7 @@ impl Worker {
                                 .send(StatsUpdate::JobCompleted {
                                     fid_count: job_success_count,
                                     spam_count,
+                                    failed_count: job_error_count,
                                 })
                                 .await
                             {
@@ -788,10 +1176,12 @@ impl Worker {
                         // Clone job ID and queue reference for completion tracking
                         let job_id = job.id.clone();
                         let queue_for_completion = Arc::clone(&self.queue);
+                        let current_jobs = Arc::clone(&self.current_jobs);
 
                         // Wrap the handle to mark job as complete after processing
                         let wrapped_handle = tokio::spawn(async move {
                             let result = handle.await;
+                            current_jobs.write().await.remove(&job_id);
 
                             // Mark job as complete regardless of success/failure
                             // This ensures jobs are properly removed from tracking
@@ -812,14 +1202,12 @@ impl Worker {
                     },
                     Ok(None) => {
                         let post_metrics = self.queue.get_metrics().await;
-                        let total_queued = post_metrics.normal_priority_queue_size;
+                        let total_queued = post_metrics.pending_jobs();
 
                         // Log detailed state when no jobs found
                         debug!(
-                            "No jobs retrieved. Queue state - Normal: {}, In Progress: {}, Active tasks: {}",
-                            post_metrics.normal_priority_queue_size,
-                            post_metrics.in_progress_queue_size,
-                            active_tasks
+                            "No jobs retrieved. Queue state - Pending: {}, In Progress: {}, Active tasks: {}",
+                            total_queued, post_metrics.in_progress_queue_size, active_tasks
                         );
 
                         if total_queued == 0
@@ -881,6 +1269,8 @@ impl Worker {
                                                 created_at: chrono::Utc::now(),
                                                 id: String::new(),
                                                 started_at: None, // New jobs haven't started yet
+                                                window: None,
+                                                kind: JobKind::Fids,
                                             })
                                             .await
                                         {
@@ -922,7 +1312,7 @@ impl Worker {
                     },
                     Err(e) => {
                         error!("Error getting job: {:?}", e);
-                        self.stats.errors += 1;
+                        let _ = tx.send(StatsUpdate::Error(ErrorKind::Queue)).await;
                         time::sleep(Duration::from_secs(1)).await;
                     },
                 }
@@ -940,6 +1330,11 @@ impl Worker {
         // Final stats sync
         self.stats = stats.read().await.clone();
 
+        // The combined totals stay; only this worker's snapshot goes away
+        if let Err(e) = self.queue.progress().remove_worker(&self.worker_id).await {
+            warn!("Failed to remove backfill progress for worker {}: {:?}", self.worker_id, e);
+        }
+
         // Wait for in-progress tasks to complete
         for handle in handles {
             if !handle.is_finished() {
@@ -962,3 +1357,62 @@ impl Worker {
         *self.shutdown.write().await = true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poll_order_weights_high_over_normal_over_low() {
+        let mut first = std::collections::BTreeMap::new();
+        for turn in 0..70 {
+            *first.entry(JobPriority::poll_order(turn)[0].clone()).or_insert(0) += 1;
+        }
+        assert_eq!(first[&JobPriority::High], 40);
+        assert_eq!(first[&JobPriority::Normal], 20);
+        assert_eq!(first[&JobPriority::Low], 10);
+
+        // Every turn still falls through to all three queues
+        for turn in 0..7 {
+            let mut order = JobPriority::poll_order(turn).to_vec();
+            order.sort();
+            assert_eq!(order, vec![JobPriority::High, JobPriority::Normal, JobPriority::Low]);
+        }
+    }
+
+    #[test]
+    fn jobs_without_window_or_priority_deserialize() {
+        let job: BackfillJob = serde_json::from_str(r#"{"fids":[1,2,3]}"#).unwrap();
+        assert_eq!(job.priority, JobPriority::Normal);
+        assert_eq!(job.window, None);
+        assert_eq!(job.kind, JobKind::Fids);
+    }
+
+    #[test]
+    fn conversation_jobs_round_trip() {
+        let kind = JobKind::Thread { fid: 3, hash: "0xab01".to_string() };
+        let json = serde_json::to_string(&kind).unwrap();
+        assert_eq!(json, r#"{"type":"thread","fid":3,"hash":"0xab01"}"#);
+        assert_eq!(serde_json::from_str::<JobKind>(&json).unwrap(), kind);
+        assert_eq!(kind.thread_root(), Some(CastId { fid: 3, hash: vec![0xab, 0x01] }));
+        assert_eq!(kind.to_string(), "thread 3:0xab01");
+
+        let channel = JobKind::Channel { url: "https://farcaster.xyz/~/channel/dev".to_string() };
+        assert_eq!(channel.thread_root(), None);
+        assert_eq!(
+            serde_json::from_str::<JobKind>(
+                r#"{"type":"channel","url":"https://farcaster.xyz/~/channel/dev"}"#
+            )
+            .unwrap(),
+            channel
+        );
+        assert_eq!(channel.height_range(), None);
+
+        let blocks = JobKind::Blocks { shard_id: 2, start: 100, end: 200 };
+        let json = serde_json::to_string(&blocks).unwrap();
+        assert_eq!(json, r#"{"type":"blocks","shard_id":2,"start":100,"end":200}"#);
+        assert_eq!(serde_json::from_str::<JobKind>(&json).unwrap(), blocks);
+        assert_eq!(blocks.height_range(), Some(HeightRange { shard_id: 2, start: 100, end: 200 }));
+        assert_eq!(blocks.to_string(), "shard 2 blocks 100..200");
+    }
+}
@@ -0,0 +1,624 @@
+//! Seeded synthetic Hub events for the pipeline benchmark.
+//!
+//! [`Workload`] yields a reproducible stream of `HubEvent`s that looks like live traffic:
+//! casts with mentions, embeds and channel parents, replies to earlier casts, reactions that
+//! get undone, follows and unfollows, link compact states, onchain events, deletes and
+//! prunes. Removes and prunes always target a message generated earlier in the same run.
+//! The same seed, event count, FID count and mix produce byte-identical events.
+
+use crate::proto::{
+    self, CastId, HubEvent, HubEventType, Message, MessageData, OnChainEvent, hub_event,
+    message_data::Body,
+};
+use rand::{Rng, SeedableRng, distr::weighted::WeightedIndex, prelude::Distribution};
+use rand_chacha::ChaCha8Rng;
+use serde::{Serialize, Serializer};
+use std::{collections::VecDeque, fmt, str::FromStr};
+
+/// Farcaster time of the first event: 2025-01-01T00:00:00Z
+const START_TIMESTAMP: u32 = (1_735_689_600 - crate::types::FARCASTER_EPOCH) as u32;
+
+/// OP mainnet block of the first onchain event
+const START_BLOCK: u32 = 130_000_000;
+
+/// Earlier messages kept per pool for replies, reactions, removes and prunes
+const POOL_SIZE: usize = 10_000;
+
+const CHANNELS: &[&str] = &[
+    "https://farcaster.xyz/~/channel/dev",
+    "https://farcaster.xyz/~/channel/memes",
+    "https://farcaster.xyz/~/channel/music",
+    "https://farcaster.xyz/~/channel/base",
+];
+
+const WORDS: &[&str] = &[
+    "gm",
+    "shipping",
+    "today",
+    "onchain",
+    "frames",
+    "channel",
+    "thread",
+    "builders",
+    "hub",
+    "snapchain",
+    "wen",
+    "launch",
+    "casting",
+    "replies",
+    "everyone",
+    "weekend",
+    "protocol",
+];
+
+/// Kinds of event a [`Workload`] generates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EventKind {
+    Cast,
+    Reply,
+    CastRemove,
+    Reaction,
+    ReactionRemove,
+    Link,
+    LinkRemove,
+    LinkCompactState,
+    OnchainEvent,
+    Prune,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 10] = [
+        EventKind::Cast,
+        EventKind::Reply,
+        EventKind::CastRemove,
+        EventKind::Reaction,
+        EventKind::ReactionRemove,
+        EventKind::Link,
+        EventKind::LinkRemove,
+        EventKind::LinkCompactState,
+        EventKind::OnchainEvent,
+        EventKind::Prune,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EventKind::Cast => "casts",
+            EventKind::Reply => "replies",
+            EventKind::CastRemove => "cast_removes",
+            EventKind::Reaction => "reactions",
+            EventKind::ReactionRemove => "reaction_removes",
+            EventKind::Link => "links",
+            EventKind::LinkRemove => "link_removes",
+            EventKind::LinkCompactState => "link_compact_states",
+            EventKind::OnchainEvent => "onchain_events",
+            EventKind::Prune => "prunes",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Relative weight of each [`EventKind`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkloadMix {
+    weights: [u32; EventKind::ALL.len()],
+}
+
+impl WorkloadMix {
+    pub fn weight(&self, kind: EventKind) -> u32 {
+        self.weights[kind.index()]
+    }
+
+    pub fn with_weight(mut self, kind: EventKind, weight: u32) -> Self {
+        self.weights[kind.index()] = weight;
+        self
+    }
+}
+
+impl Default for WorkloadMix {
+    /// Roughly the shape of mainnet traffic: mostly reactions and casts
+    fn default() -> Self {
+        Self { weights: [18, 12, 2, 30, 8, 12, 4, 2, 6, 6] }
+    }
+}
+
+impl Serialize for WorkloadMix {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(EventKind::ALL.iter().map(|kind| (kind.name(), self.weight(*kind))))
+    }
+}
+
+impl FromStr for WorkloadMix {
+    type Err = String;
+
+    /// Parse `kind=weight` pairs, e.g. `casts=40,prunes=0`; unnamed kinds keep their default
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut mix = WorkloadMix::default();
+        for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (name, weight) =
+                pair.split_once('=').ok_or_else(|| format!("Expected kind=weight: {}", pair))?;
+            let kind = EventKind::ALL
+                .into_iter()
+                .find(|kind| kind.name() == name.trim())
+                .ok_or_else(|| format!("Unknown event kind: {}", name))?;
+            let weight =
+                weight.trim().parse().map_err(|_| format!("Invalid weight: {}", weight))?;
+            mix = mix.with_weight(kind, weight);
+        }
+        if mix.weights.iter().all(|weight| *weight == 0) {
+            return Err("At least one event kind needs a weight".to_string());
+        }
+        Ok(mix)
+    }
+}
+
+/// Number of events generated of each kind
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct WorkloadCounts(std::collections::BTreeMap<&'static str, u64>);
+
+impl WorkloadCounts {
+    pub fn get(&self, kind: EventKind) -> u64 {
+        self.0.get(kind.name()).copied().unwrap_or(0)
+    }
+
+    fn add(&mut self, kind: EventKind) {
+        *self.0.entry(kind.name()).or_default() += 1;
+    }
+}
+
+/// Deterministic generator of synthetic Hub events
+pub struct Workload {
+    rng: ChaCha8Rng,
+    kinds: WeightedIndex<u32>,
+    fids: u64,
+    remaining: usize,
+    next_id: u64,
+    timestamp: u32,
+    block_number: u32,
+    casts: VecDeque<Message>,
+    reactions: VecDeque<Message>,
+    links: VecDeque<Message>,
+    counts: WorkloadCounts,
+}
+
+impl Workload {
+    /// `events` events from FIDs `1..=fids`
+    pub fn new(seed: u64, events: usize, fids: u64, mix: &WorkloadMix) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            kinds: WeightedIndex::new(mix.weights).unwrap_or_else(|_| {
+                WeightedIndex::new(WorkloadMix::default().weights).expect("default mix is valid")
+            }),
+            fids: fids.max(2),
+            remaining: events,
+            next_id: 1,
+            timestamp: START_TIMESTAMP,
+            block_number: START_BLOCK,
+            casts: VecDeque::new(),
+            reactions: VecDeque::new(),
+            links: VecDeque::new(),
+            counts: WorkloadCounts::default(),
+        }
+    }
+
+    /// Events generated so far, by the kind actually produced
+    pub fn counts(&self) -> &WorkloadCounts {
+        &self.counts
+    }
+
+    /// Generate the next event and the kind it turned out to be. Kinds that need an
+    /// earlier message fall back to an add while their pool is empty.
+    fn generate(&mut self) -> (EventKind, HubEvent) {
+        self.timestamp += self.rng.random_range(0..=2);
+        let kind = EventKind::ALL[self.kinds.sample(&mut self.rng)];
+        let (kind, body, event_type) = match kind {
+            EventKind::Reply if !self.casts.is_empty() => {
+                let parent = self.pick(Pool::Casts).map(|cast| cast_id(&cast));
+                (kind, self.cast(parent.map(proto::cast_add_body::Parent::ParentCastId)), None)
+            },
+            EventKind::CastRemove if !self.casts.is_empty() => (kind, self.cast_remove(), None),
+            EventKind::Reaction if !self.casts.is_empty() => (kind, self.reaction(), None),
+            EventKind::ReactionRemove if !self.reactions.is_empty() => {
+                (kind, self.reaction_remove(), None)
+            },
+            EventKind::LinkRemove if !self.links.is_empty() => (kind, self.link_remove(), None),
+            EventKind::Link | EventKind::LinkRemove => (EventKind::Link, self.link(), None),
+            EventKind::LinkCompactState => (kind, self.link_compact_state(), None),
+            EventKind::OnchainEvent => {
+                (kind, self.onchain_event(), Some(HubEventType::MergeOnChainEvent))
+            },
+            EventKind::Prune if !self.is_empty_pools() => {
+                (kind, self.prune(), Some(HubEventType::PruneMessage))
+            },
+            _ => {
+                let parent = self.rng.random_bool(0.2).then(|| {
+                    let url = CHANNELS[self.rng.random_range(0..CHANNELS.len())];
+                    proto::cast_add_body::Parent::ParentUrl(url.to_string())
+                });
+                (EventKind::Cast, self.cast(parent), None)
+            },
+        };
+
+        let event = HubEvent {
+            r#type: event_type.unwrap_or(HubEventType::MergeMessage) as i32,
+            id: self.next_id,
+            block_number: u64::from(self.block_number),
+            shard_index: 1,
+            timestamp: u64::from(self.timestamp),
+            body: Some(body),
+        };
+        self.next_id += 1;
+        self.counts.add(kind);
+        (kind, event)
+    }
+
+    fn is_empty_pools(&self) -> bool {
+        self.casts.is_empty() && self.reactions.is_empty() && self.links.is_empty()
+    }
+
+    fn fid(&mut self) -> u64 {
+        self.rng.random_range(1..=self.fids)
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.rng.random()).collect()
+    }
+
+    fn message(&mut self, fid: u64, message_type: proto::MessageType, body: Body) -> Message {
+        Message {
+            data: Some(MessageData {
+                r#type: message_type as i32,
+                fid,
+                timestamp: self.timestamp,
+                network: proto::FarcasterNetwork::Mainnet as i32,
+                body: Some(body),
+            }),
+            hash: self.bytes(20),
+            hash_scheme: proto::HashScheme::Blake3 as i32,
+            signature: self.bytes(64),
+            signature_scheme: proto::SignatureScheme::Ed25519 as i32,
+            signer: self.bytes(32),
+            data_bytes: None,
+        }
+    }
+
+    /// A random earlier message from `pool`, left in place for later events
+    fn pick(&mut self, pool: Pool) -> Option<Message> {
+        let len = self.pool(pool).len();
+        if len == 0 {
+            return None;
+        }
+        let index = self.rng.random_range(0..len);
+        self.pool(pool).get(index).cloned()
+    }
+
+    /// Take a random earlier message out of `pool` for good
+    fn take(&mut self, pool: Pool) -> Option<Message> {
+        let len = self.pool(pool).len();
+        if len == 0 {
+            return None;
+        }
+        let index = self.rng.random_range(0..len);
+        self.pool(pool).swap_remove_back(index)
+    }
+
+    fn pool(&mut self, pool: Pool) -> &mut VecDeque<Message> {
+        match pool {
+            Pool::Casts => &mut self.casts,
+            Pool::Reactions => &mut self.reactions,
+            Pool::Links => &mut self.links,
+        }
+    }
+
+    fn remember(&mut self, pool: Pool, message: Message) {
+        let pool = self.pool(pool);
+        if pool.len() >= POOL_SIZE {
+            pool.pop_front();
+        }
+        pool.push_back(message);
+    }
+
+    fn cast(&mut self, parent: Option<proto::cast_add_body::Parent>) -> hub_event::Body {
+        let fid = self.fid();
+        let mut text = Vec::new();
+        let mut mentions = Vec::new();
+        let mut mentions_positions = Vec::new();
+        for _ in 0..self.rng.random_range(3..20) {
+            if self.rng.random_bool(0.08) && mentions.len() < 5 {
+                mentions.push(self.fid());
+                mentions_positions.push(text.join(" ").len() as u32);
+            } else {
+                text.push(WORDS[self.rng.random_range(0..WORDS.len())]);
+            }
+        }
+        let mut embeds = Vec::new();
+        for _ in 0..self.rng.random_range(0..=2) {
+            let embed = if self.rng.random_bool(0.3)
+                && let Some(cast) = self.pick(Pool::Casts)
+            {
+                proto::embed::Embed::CastId(cast_id(&cast))
+            } else {
+                let path = self.rng.random::<u32>();
+                proto::embed::Embed::Url(format!("https://example.com/{:08x}", path))
+            };
+            embeds.push(proto::Embed { embed: Some(embed) });
+        }
+
+        let message = self.message(
+            fid,
+            proto::MessageType::CastAdd,
+            Body::CastAddBody(proto::CastAddBody {
+                text: text.join(" "),
+                mentions,
+                mentions_positions,
+                embeds,
+                parent,
+                ..Default::default()
+            }),
+        );
+        self.remember(Pool::Casts, message.clone());
+        merge(message, Vec::new())
+    }
+
+    fn cast_remove(&mut self) -> hub_event::Body {
+        let cast = self.take(Pool::Casts).expect("cast pool is not empty");
+        let fid = cast.data.as_ref().map_or(0, |data| data.fid);
+        let message = self.message(
+            fid,
+            proto::MessageType::CastRemove,
+            Body::CastRemoveBody(proto::CastRemoveBody { target_hash: cast.hash.clone() }),
+        );
+        merge(message, vec![cast])
+    }
+
+    fn reaction(&mut self) -> hub_event::Body {
+        let fid = self.fid();
+        let target = self.pick(Pool::Casts).map(|cast| cast_id(&cast));
+        let reaction_type = if self.rng.random_bool(0.8) {
+            proto::ReactionType::Like
+        } else {
+            proto::ReactionType::Recast
+        };
+        let message = self.message(
+            fid,
+            proto::MessageType::ReactionAdd,
+            Body::ReactionBody(proto::ReactionBody {
+                r#type: reaction_type as i32,
+                target: target.map(proto::reaction_body::Target::TargetCastId),
+            }),
+        );
+        self.remember(Pool::Reactions, message.clone());
+        merge(message, Vec::new())
+    }
+
+    fn reaction_remove(&mut self) -> hub_event::Body {
+        let reaction = self.take(Pool::Reactions).expect("reaction pool is not empty");
+        let data = reaction.data.clone().unwrap_or_default();
+        let message = self.message(
+            data.fid,
+            proto::MessageType::ReactionRemove,
+            data.body.unwrap_or(Body::ReactionBody(proto::ReactionBody::default())),
+        );
+        merge(message, vec![reaction])
+    }
+
+    fn link(&mut self) -> hub_event::Body {
+        let fid = self.fid();
+        let target = self.fid();
+        let message = self.message(
+            fid,
+            proto::MessageType::LinkAdd,
+            Body::LinkBody(proto::LinkBody {
+                r#type: "follow".to_string(),
+                display_timestamp: None,
+                target: Some(proto::link_body::Target::TargetFid(target)),
+            }),
+        );
+        self.remember(Pool::Links, message.clone());
+        merge(message, Vec::new())
+    }
+
+    fn link_remove(&mut self) -> hub_event::Body {
+        let link = self.take(Pool::Links).expect("link pool is not empty");
+        let data = link.data.clone().unwrap_or_default();
+        let message = self.message(
+            data.fid,
+            proto::MessageType::LinkRemove,
+            data.body.unwrap_or(Body::LinkBody(proto::LinkBody::default())),
+        );
+        merge(message, vec![link])
+    }
+
+    /// Every follow of a random FID still in the link pool
+    fn link_compact_state(&mut self) -> hub_event::Body {
+        let fid = self.fid();
+        let target_fids = self
+            .links
+            .iter()
+            .filter_map(|link| link.data.as_ref())
+            .filter(|data| data.fid == fid)
+            .filter_map(|data| match &data.body {
+                Some(Body::LinkBody(proto::LinkBody {
+                    target: Some(proto::link_body::Target::TargetFid(target)),
+                    ..
+                })) => Some(*target),
+                _ => None,
+            })
+            .collect();
+        let message = self.message(
+            fid,
+            proto::MessageType::LinkCompactState,
+            Body::LinkCompactStateBody(proto::LinkCompactStateBody {
+                r#type: "follow".to_string(),
+                target_fids,
+            }),
+        );
+        merge(message, Vec::new())
+    }
+
+    /// A signer add, FID registration or storage rent
+    fn onchain_event(&mut self) -> hub_event::Body {
+        self.block_number += self.rng.random_range(1..30);
+        let fid = self.fid();
+        let block_timestamp = u64::from(self.timestamp) + crate::types::FARCASTER_EPOCH;
+        let (event_type, body) = match self.rng.random_range(0..3) {
+            0 => (
+                proto::OnChainEventType::EventTypeSigner,
+                proto::on_chain_event::Body::SignerEventBody(proto::SignerEventBody {
+                    key: self.bytes(32),
+                    key_type: 1,
+                    event_type: proto::SignerEventType::Add as i32,
+                    metadata: Vec::new(),
+                    metadata_type: 1,
+                }),
+            ),
+            1 => (
+                proto::OnChainEventType::EventTypeIdRegister,
+                proto::on_chain_event::Body::IdRegisterEventBody(proto::IdRegisterEventBody {
+                    to: self.bytes(20),
+                    event_type: proto::IdRegisterEventType::Register as i32,
+                    from: Vec::new(),
+                    recovery_address: self.bytes(20),
+                }),
+            ),
+            _ => (
+                proto::OnChainEventType::EventTypeStorageRent,
+                proto::on_chain_event::Body::StorageRentEventBody(proto::StorageRentEventBody {
+                    payer: self.bytes(20),
+                    units: self.rng.random_range(1..=5),
+                    expiry: (block_timestamp + 365 * 24 * 60 * 60) as u32,
+                }),
+            ),
+        };
+        hub_event::Body::MergeOnChainEventBody(proto::MergeOnChainEventBody {
+            on_chain_event: Some(OnChainEvent {
+                r#type: event_type as i32,
+                chain_id: 10,
+                block_number: self.block_number,
+                block_hash: self.bytes(32),
+                block_timestamp,
+                transaction_hash: self.bytes(32),
+                log_index: self.rng.random_range(0..200),
+                fid,
+                tx_index: self.rng.random_range(0..100),
+                version: 2,
+                body: Some(body),
+            }),
+        })
+    }
+
+    /// The oldest message of a random non-empty pool, as the Hub prunes when storage is full
+    fn prune(&mut self) -> hub_event::Body {
+        let pools: Vec<Pool> = [Pool::Casts, Pool::Reactions, Pool::Links]
+            .into_iter()
+            .filter(|pool| !self.pool(*pool).is_empty())
+            .collect();
+        let pool = pools[self.rng.random_range(0..pools.len())];
+        let message = self.pool(pool).pop_front();
+        hub_event::Body::PruneMessageBody(proto::PruneMessageBody { message })
+    }
+}
+
+impl Iterator for Workload {
+    type Item = (EventKind, HubEvent);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(self.generate())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Workload {}
+
+#[derive(Debug, Clone, Copy)]
+enum Pool {
+    Casts,
+    Reactions,
+    Links,
+}
+
+fn cast_id(cast: &Message) -> CastId {
+    CastId { fid: cast.data.as_ref().map_or(0, |data| data.fid), hash: cast.hash.clone() }
+}
+
+fn merge(message: Message, deleted_messages: Vec<Message>) -> hub_event::Body {
+    hub_event::Body::MergeMessageBody(proto::MergeMessageBody {
+        message: Some(message),
+        deleted_messages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_events() {
+        let mix = WorkloadMix::default();
+        let first: Vec<_> = Workload::new(7, 500, 100, &mix).collect();
+        let second: Vec<_> = Workload::new(7, 500, 100, &mix).collect();
+        assert_eq!(first, second);
+        let other: Vec<_> = Workload::new(8, 500, 100, &mix).collect();
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn removes_and_prunes_target_earlier_messages() {
+        let mut workload = Workload::new(1, 5_000, 50, &WorkloadMix::default());
+        let mut seen = std::collections::HashSet::new();
+        for (kind, event) in workload.by_ref() {
+            let (message, deleted) = match event.body {
+                Some(hub_event::Body::MergeMessageBody(body)) => {
+                    (body.message, body.deleted_messages)
+                },
+                Some(hub_event::Body::PruneMessageBody(body)) => {
+                    assert_eq!(kind, EventKind::Prune);
+                    (None, body.message.into_iter().collect())
+                },
+                _ => (None, Vec::new()),
+            };
+            for earlier in deleted {
+                assert!(seen.contains(&earlier.hash), "{} targets an unknown message", kind);
+            }
+            if let Some(message) = message {
+                seen.insert(message.hash);
+            }
+        }
+        let counts = workload.counts();
+        assert_eq!(EventKind::ALL.iter().map(|kind| counts.get(*kind)).sum::<u64>(), 5_000);
+        for kind in EventKind::ALL {
+            assert!(counts.get(kind) > 0, "no {} generated", kind);
+        }
+    }
+
+    #[test]
+    fn mix_overrides_defaults() {
+        let mix: WorkloadMix = "casts=40, prunes=0".parse().unwrap();
+        assert_eq!(mix.weight(EventKind::Cast), 40);
+        assert_eq!(mix.weight(EventKind::Prune), 0);
+        assert_eq!(
+            mix.weight(EventKind::Reaction),
+            WorkloadMix::default().weight(EventKind::Reaction)
+        );
+        assert!("casts".parse::<WorkloadMix>().is_err());
+        assert!("likes=3".parse::<WorkloadMix>().is_err());
+        let zeroes = EventKind::ALL.iter().map(|kind| format!("{}=0", kind)).collect::<Vec<_>>();
+        assert!(zeroes.join(",").parse::<WorkloadMix>().is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use clap::{Arg, ArgAction, ArgMatches, Command, value_parser};
+use color_eyre::eyre::{Result, eyre};
+use std::{path::PathBuf, sync::Arc};
+use tracing::info;
+use waypoint::{
+    backfill::{
+        audit::FidAuditor,
+        worker::{BackfillJob, BackfillQueue, JobKind, JobPriority, JobState},
+    },
+    config::Config,
+    database::{audit, client::Database},
+    hub::client::Hub,
+};
+
+/// FIDs per backfill job when enqueueing drifted FIDs
+const ENQUEUE_BATCH_SIZE: usize = 50;
+
+/// Register audit commands
+pub fn register_commands(app: Command) -> Command {
+    app.about("Compare PostgreSQL with the Hub without changing either")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("fid")
+                .about("Compare message hashes and onchain events per FID, printing a JSON report")
+                .arg(
+                    Arg::new("fids")
+                        .long("fids")
+                        .help("Comma-separated list of FIDs to audit")
+                        .value_delimiter(',')
+                        .value_parser(value_parser!(u64))
+                        .conflicts_with("sample"),
+                )
+                .arg(
+                    Arg::new("sample")
+                        .long("sample")
+                        .help("Audit this many random FIDs from the fids registry")
+                        .value_parser(value_parser!(i64).range(1..)),
+                )
+                .arg(
+                    Arg::new("concurrency")
+                        .long("concurrency")
+                        .help("FIDs audited at once")
+                        .default_value("4")
+                        .value_parser(value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("Write the report to this file instead of stdout")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("enqueue")
+                        .long("enqueue")
+                        .help("Queue drifted FIDs for backfill")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+}
+
+/// Handle audit commands
+pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()> {
+    match matches.subcommand() {
+        Some(("fid", fid_matches)) => audit_fids(fid_matches, config).await,
+        _ => {
+            println!("Please specify a subcommand. Use --help for more information.");
+            Ok(())
+        },
+    }
+}
+
+async fn audit_fids(matches: &ArgMatches, config: &Config) -> Result<()> {
+    let database = Database::new(&config.database).await?;
+    let pool = database.pool.clone();
+
+    let fids: Vec<u64> = if let Some(fids) = matches.get_many::<u64>("fids") {
+        fids.copied().collect()
+    } else if let Some(&count) = matches.get_one::<i64>("sample") {
+        let fids = audit::sample_fids(&pool, count).await?;
+        if fids.is_empty() {
+            return Err(eyre!(
+                "The fids registry is empty; run `waypoint backfill fid-registry` or pass --fids"
+            ));
+        }
+        fids.into_iter().map(|fid| fid as u64).collect()
+    } else {
+        return Err(eyre!("Pass --fids or --sample"));
+    };
+    let concurrency = *matches.get_one::<usize>("concurrency").expect("has a default");
+
+    let mut hub = Hub::new(config.hub.clone())?;
+    hub.connect().await?;
+
+    info!("Auditing {} FIDs against the Hub", fids.len());
+    let auditor = FidAuditor::new(Arc::new(hub), pool);
+    let mut report = auditor.audit_fids(&fids, concurrency).await;
+    info!(
+        "Audited {} FIDs: {} drifted, {} failed ({} missing, {} extra, {} not deleted)",
+        report.summary.fids,
+        report.summary.drifted,
+        report.summary.errors,
+        report.summary.missing,
+        report.summary.extra,
+        report.summary.not_deleted
+    );
+
+    if matches.get_flag("enqueue") {
+        let drifted = report.drifted_fids();
+        if !drifted.is_empty() {
+            let redis = Arc::new(waypoint::redis::client::Redis::new(&config.redis).await?);
+            let queue = BackfillQueue::new(redis, "backfill:fid:queue".to_string());
+            for chunk in drifted.chunks(ENQUEUE_BATCH_SIZE) {
+                queue
+                    .add_job(BackfillJob {
+                        fids: chunk.to_vec(),
+                        priority: JobPriority::Normal,
+                        state: JobState::Pending,
+                        visibility_timeout: None,
+                        attempts: 0,
+                        created_at: chrono::Utc::now(),
+                        id: String::new(),
+                        started_at: None,
+                        window: None,
+                        kind: JobKind::Fids,
+                    })
+                    .await?;
+            }
+            info!("Queued {} drifted FIDs for backfill", drifted.len());
+        }
+        report.enqueued = Some(drifted);
+    }
+
+    let json = serde_json::to_string_pretty(&report)?;
+    match matches.get_one::<PathBuf>("output") {
+        Some(path) => tokio::fs::write(path, json).await?,
+        None => println!("{}", json),
+    }
+    Ok(())
+}
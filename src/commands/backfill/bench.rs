@@ -1,10 +1,18 @@
-use clap::Parser;
-use color_eyre::eyre::Result;
-use std::sync::Arc;
+use clap::{Arg, ArgMatches, Command, Parser};
+use color_eyre::eyre::{Result, eyre};
+use std::{path::PathBuf, sync::Arc};
 use tracing::info;
 
 use waypoint::{
-    backfill::bench::run_benchmark, processor::AppResources, processor::database::DatabaseProcessor,
+    app::StateProvider,
+    backfill::{
+        bench::{PipelineSettings, run_benchmark, run_pipeline_benchmark},
+        workload::{EventKind, WorkloadMix},
+    },
+    config::{Config, ServiceMode},
+    database::client::Database,
+    processor::AppResources,
+    processor::database::DatabaseProcessor,
 };
 
 /// Run database batch insert performance benchmarks
@@ -16,14 +24,18 @@ pub struct BenchmarkCommand {
 }
 
 impl BenchmarkCommand {
-    pub async fn execute(self, resources: Arc<AppResources>) -> Result<()> {
+    pub async fn execute(
+        self,
+        resources: Arc<AppResources>,
+        database: Arc<Database>,
+    ) -> Result<()> {
         info!("Starting database batch insert benchmark");
 
         // Create a processor instance for testing
         let processor = Arc::new(DatabaseProcessor::new(resources.clone()));
 
         // Run the benchmark
-        if let Err(e) = run_benchmark(resources.database.clone(), processor, self.messages).await {
+        if let Err(e) = run_benchmark(database, processor, self.messages).await {
             return Err(color_eyre::eyre::eyre!("Benchmark error: {}", e));
         }
 
@@ -32,3 +44,120 @@ impl BenchmarkCommand {
         Ok(())
     }
 }
+
+/// `backfill bench pipeline`: end-to-end benchmark with a synthetic workload
+pub fn pipeline_command() -> Command {
+    Command::new("pipeline")
+        .about(
+            "Benchmark producer → Redis → consumer → PostgreSQL with a seeded synthetic workload",
+        )
+        .arg(
+            Arg::new("events")
+                .long("events")
+                .help("Number of Hub events to generate")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("100000"),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .help("Seed for the workload; the same seed generates the same events")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("1"),
+        )
+        .arg(
+            Arg::new("fids")
+                .long("fids")
+                .help("Number of FIDs the events come from")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("10000"),
+        )
+        .arg(
+            Arg::new("mix")
+                .long("mix")
+                .value_name("KIND=WEIGHT,...")
+                .help(format!(
+                    "Override event weights, e.g. casts=40,prunes=0 (kinds: {})",
+                    EventKind::ALL.map(EventKind::name).join(", ")
+                ))
+                .value_parser(clap::value_parser!(String)),
+        )
+        .arg(
+            Arg::new("batch_size")
+                .long("batch-size")
+                .help("Events the producer publishes per flush")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("100"),
+        )
+        .arg(
+            Arg::new("concurrency")
+                .long("concurrency")
+                .help("Events the consumer processes at once")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("200"),
+        )
+        .arg(
+            Arg::new("rate")
+                .long("rate")
+                .help("Events to publish per second (default: as fast as possible)")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("drain_timeout")
+                .long("drain-timeout")
+                .help("Seconds without progress before giving up on unprocessed events")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("30"),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .short('o')
+                .help("Write the JSON report to this file instead of stdout")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+}
+
+/// Run the pipeline benchmark and print or save its report
+pub async fn run_pipeline(config: &Config, args: &ArgMatches) -> Result<()> {
+    let defaults = PipelineSettings::default();
+    let mix = match args.get_one::<String>("mix") {
+        Some(mix) => mix.parse::<WorkloadMix>().map_err(|e| eyre!("Invalid --mix: {}", e))?,
+        None => defaults.mix.clone(),
+    };
+    let settings = PipelineSettings {
+        seed: args.get_one::<u64>("seed").copied().unwrap_or(defaults.seed),
+        events: args.get_one::<usize>("events").copied().unwrap_or(defaults.events),
+        fids: args.get_one::<u64>("fids").copied().unwrap_or(defaults.fids),
+        mix,
+        batch_size: args.get_one::<usize>("batch_size").copied().unwrap_or(defaults.batch_size),
+        concurrency: args.get_one::<usize>("concurrency").copied().unwrap_or(defaults.concurrency),
+        rate: args.get_one::<u64>("rate").copied(),
+        drain_timeout_secs: args
+            .get_one::<u64>("drain_timeout")
+            .copied()
+            .unwrap_or(defaults.drain_timeout_secs),
+    };
+
+    // The benchmark consumes its own streams and never talks to a Hub
+    let state = StateProvider::new(config).await?.provide_for_mode(ServiceMode::Consumer).await?;
+    let report = run_pipeline_benchmark(state, config, settings).await?;
+
+    info!(
+        "Processed {} of {} dispatched events at {:.0}/s (end-to-end p50 {:.1}ms, p99 {:.1}ms)",
+        report.outcome.processed,
+        report.outcome.dispatched,
+        report.throughput.processed_per_sec,
+        report.stages.get("end_to_end").map_or(0.0, |stage| stage.p50_ms),
+        report.stages.get("end_to_end").map_or(0.0, |stage| stage.p99_ms)
+    );
+    let json = serde_json::to_string_pretty(&report)?;
+    match args.get_one::<PathBuf>("output") {
+        Some(path) => {
+            tokio::fs::write(path, json).await?;
+            info!("Wrote benchmark report to {}", path.display());
+        },
+        None => println!("{}", json),
+    }
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use clap::{Arg, ArgMatches, Command};
+use color_eyre::eyre::{Result, eyre};
+use futures::{StreamExt, stream};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{error, info};
+use waypoint::{
+    backfill::{
+        blocks::{BlockCheckpointStore, BlockReplayer, HeightRange, ReplayStats},
+        worker::{BackfillJob, BackfillQueue, JobKind, JobPriority, JobState},
+    },
+    config::Config,
+    hub::client::Hub,
+    processor::{AppResources, consumer::EventProcessor, database::DatabaseProcessor},
+};
+
+/// Arguments shared by both subcommands
+fn range_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("shards")
+                .long("shards")
+                .help("Comma-separated shard IDs to replay (default: every shard the Hub reports)")
+                .value_parser(clap::value_parser!(String)),
+        )
+        .arg(
+            Arg::new("from")
+                .long("from")
+                .help("First block height to replay")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("to")
+                .long("to")
+                .help("Block height to stop before (default: each shard's current height)")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("range_size")
+                .long("range-size")
+                .help("Block heights per range; each range is checkpointed separately")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("10000"),
+        )
+        .arg(
+            Arg::new("restart")
+                .long("restart")
+                .help("Forget every range's checkpoint and replay from the start")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// Register block replay commands
+pub fn register_commands(app: Command) -> Command {
+    app.subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(range_args(
+            Command::new("queue").about("Queue block ranges for the FID backfill workers").arg(
+                Arg::new("priority")
+                    .long("priority")
+                    .help("Queue to add the ranges to")
+                    .value_parser(["high", "normal", "low"])
+                    .default_value("normal"),
+            ),
+        ))
+        .subcommand(range_args(
+            Command::new("run").about("Replay block ranges in this process").arg(
+                Arg::new("concurrency")
+                    .long("concurrency")
+                    .help("Number of ranges to replay at once")
+                    .value_parser(clap::value_parser!(usize))
+                    .default_value("4"),
+            ),
+        ))
+}
+
+/// Handle block replay commands
+pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()> {
+    match matches.subcommand() {
+        Some(("queue", args)) => queue(config, args).await,
+        Some(("run", args)) => run(config, args).await,
+        // Just for clarity in error messages
+        None => {
+            println!("Please specify a valid blocks command. Available commands:");
+            println!("  queue  - Queue block ranges for the FID backfill workers");
+            println!("  run    - Replay block ranges in this process");
+            Ok(())
+        },
+        Some((cmd, _)) => {
+            println!("Unknown command: {}. Available commands:", cmd);
+            println!("  queue  - Queue block ranges for the FID backfill workers");
+            println!("  run    - Replay block ranges in this process");
+            Ok(())
+        },
+    }
+}
+
+/// Split the requested heights of each shard into ranges
+async fn ranges_from_args(hub: &Hub, args: &ArgMatches) -> Result<Vec<HeightRange>> {
+    let info = hub.get_hub_info().await?;
+    let shards: Vec<u32> = match args.get_one::<String>("shards") {
+        Some(list) => list
+            .split(',')
+            .map(|shard| shard.trim().parse().map_err(|_| eyre!("Invalid shard ID: {}", shard)))
+            .collect::<Result<_>>()?,
+        None => (1..=info.num_shards).collect(),
+    };
+    let from = args.get_one::<u64>("from").copied().unwrap_or(0);
+    let range_size = args.get_one::<u64>("range_size").copied().unwrap_or(10000).max(1);
+
+    let mut ranges = Vec::new();
+    for shard_id in shards {
+        let to = match args.get_one::<u64>("to") {
+            Some(to) => *to,
+            None => info
+                .shard_infos
+                .iter()
+                .find(|shard| shard.shard_id == shard_id)
+                .map(|shard| shard.max_height + 1)
+                .ok_or_else(|| eyre!("Hub doesn't report shard {}", shard_id))?,
+        };
+        let shard_ranges = HeightRange::split(shard_id, from, to, range_size);
+        info!("Shard {}: blocks {}..{} in {} ranges", shard_id, from, to, shard_ranges.len());
+        ranges.extend(shard_ranges);
+    }
+    Ok(ranges)
+}
+
+/// Queue each range as a block job on the FID backfill queue
+async fn queue(config: &Config, args: &ArgMatches) -> Result<()> {
+    let redis = Arc::new(waypoint::redis::client::Redis::new(&config.redis).await?);
+    let mut hub = Hub::new(config.hub.clone())?;
+    hub.connect().await?;
+    let fid_queue = BackfillQueue::new(redis.clone(), "backfill:fid:queue".to_string());
+
+    if args.get_flag("restart") {
+        BlockCheckpointStore::new(redis).clear().await?;
+        info!("Cleared block replay checkpoints");
+    }
+
+    let priority = args
+        .get_one::<String>("priority")
+        .and_then(|name| JobPriority::from_name(name))
+        .unwrap_or_default();
+    let ranges = ranges_from_args(&hub, args).await?;
+    for range in &ranges {
+        fid_queue
+            .add_job(BackfillJob {
+                fids: Vec::new(),
+                priority: priority.clone(),
+                state: JobState::Pending,
+                visibility_timeout: None,
+                attempts: 0,
+                created_at: chrono::Utc::now(),
+                id: String::new(),
+                started_at: None,
+                window: None,
+                kind: JobKind::Blocks {
+                    shard_id: range.shard_id,
+                    start: range.start,
+                    end: range.end,
+                },
+            })
+            .await?;
+    }
+
+    info!(
+        "Queued {} block ranges ({} blocks) at {:?} priority; run `waypoint backfill fid worker` to replay them",
+        ranges.len(),
+        ranges.iter().map(HeightRange::len).sum::<u64>(),
+        priority
+    );
+    Ok(())
+}
+
+/// Replay the ranges here, several at a time
+async fn run(config: &Config, args: &ArgMatches) -> Result<()> {
+    let concurrency = args.get_one::<usize>("concurrency").copied().unwrap_or(4).max(1);
+
+    let redis = Arc::new(waypoint::redis::client::Redis::new(&config.redis).await?);
+    let mut hub = Hub::new(config.hub.clone())?;
+    hub.connect().await?;
+    let hub = Arc::new(hub);
+
+    let checkpoints = Arc::new(BlockCheckpointStore::new(redis.clone()));
+    if args.get_flag("restart") {
+        checkpoints.clear().await?;
+        info!("Cleared block replay checkpoints");
+    }
+
+    let hub_mutex = Arc::new(Mutex::new(hub.as_ref().clone()));
+    let store = waypoint::database::store::open(&config.database, Some(hub_mutex.clone())).await?;
+    let processors: Vec<Arc<dyn EventProcessor>> = vec![Arc::new(DatabaseProcessor::new(
+        Arc::new(AppResources::new(hub_mutex, redis, store)),
+    ))];
+    let replayer = Arc::new(BlockReplayer::new(hub.clone()).with_checkpoints(checkpoints));
+
+    let ranges = ranges_from_args(&hub, args).await?;
+    let start_time = std::time::Instant::now();
+    let total = ranges.len();
+    let results: Vec<Option<ReplayStats>> = stream::iter(ranges)
+        .map(|range| {
+            let replayer = replayer.clone();
+            let processors = processors.clone();
+            async move {
+                match replayer.replay_range(range, &processors).await {
+                    Ok(stats) => Some(stats),
+                    Err(e) => {
+                        error!("Error replaying {}: {}", range, e);
+                        None
+                    },
+                }
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+
+    let mut stats = ReplayStats::default();
+    for range_stats in results.iter().flatten() {
+        stats.merge(*range_stats);
+    }
+    let failed = results.iter().filter(|result| result.is_none()).count();
+    info!(
+        "Block replay complete in {:.2?}: {} of {} ranges, {} chunks, {} user messages, {} system messages",
+        start_time.elapsed(),
+        total - failed,
+        total,
+        stats.chunks,
+        stats.user_messages,
+        stats.system_messages
+    );
+    if failed > 0 {
+        return Err(eyre!("{} block ranges failed; run again to resume them", failed));
+    }
+    Ok(())
+}
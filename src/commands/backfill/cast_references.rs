@@ -0,0 +1,29 @@
+use clap::{Arg, ArgMatches, Command};
+use color_eyre::eyre::Result;
+use tracing::info;
+use waypoint::{config::Config, database::cast_references::rebuild_cast_references};
+
+/// Register cast references backfill command
+pub fn register_commands(app: Command) -> Command {
+    app.about("Populate cast_mentions and cast_embeds from existing casts").arg(
+        Arg::new("batch-size")
+            .long("batch-size")
+            .value_name("SIZE")
+            .help("Number of casts to read per batch")
+            .default_value("1000")
+            .value_parser(clap::value_parser!(usize)),
+    )
+}
+
+/// Handle cast references backfill command
+pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()> {
+    let batch_size = *matches.get_one::<usize>("batch-size").unwrap();
+
+    info!("Backfilling cast references with batch_size={}", batch_size);
+
+    let database = waypoint::database::client::Database::new(&config.database).await?;
+    let written = rebuild_cast_references(&database.pool, batch_size).await?;
+
+    info!("Cast references backfill complete: {} rows written", written);
+    Ok(())
+}
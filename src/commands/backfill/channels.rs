@@ -0,0 +1,63 @@
+use clap::{Arg, ArgMatches, Command};
+use color_eyre::eyre::{Result, eyre};
+use tracing::info;
+use waypoint::{
+    config::Config,
+    database::channels::{
+        import_channel_metadata, parse_channel_metadata_csv, parse_channel_metadata_json,
+        rebuild_channels,
+    },
+};
+
+/// Register channels backfill command
+pub fn register_commands(app: Command) -> Command {
+    app.about("Rebuild channel counters from casts and optionally import channel metadata")
+        .arg(
+            Arg::new("metadata")
+                .long("metadata")
+                .value_name("FILE")
+                .help("JSON or CSV file with channel id, url, name, description and lead_fid"),
+        )
+        .arg(
+            Arg::new("metadata-only")
+                .long("metadata-only")
+                .help("Only import metadata; skip recounting casts")
+                .requires("metadata")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// Handle channels backfill command
+pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()> {
+    let metadata_path = matches.get_one::<String>("metadata");
+    let metadata_only = matches.get_flag("metadata-only");
+
+    // Parse the file before touching the database so a bad file fails fast
+    let metadata = match metadata_path {
+        Some(path) => {
+            let contents = std::fs::read_to_string(path)?;
+            let parsed = if path.to_lowercase().ends_with(".csv") {
+                parse_channel_metadata_csv(&contents)
+            } else {
+                parse_channel_metadata_json(&contents)
+            };
+            Some(parsed.map_err(|e| eyre!("Invalid channel metadata in {}: {}", path, e))?)
+        },
+        None => None,
+    };
+
+    let database = waypoint::database::client::Database::new(&config.database).await?;
+
+    if !metadata_only {
+        info!("Rebuilding channel counters from casts...");
+        let channels = rebuild_channels(&database.pool).await?;
+        info!("Channel rebuild complete: {} channels with casts", channels);
+    }
+
+    if let Some(metadata) = metadata {
+        let written = import_channel_metadata(&database.pool, &metadata).await?;
+        info!("Imported metadata for {} channels", written);
+    }
+
+    Ok(())
+}
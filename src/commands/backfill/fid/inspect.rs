@@ -2,11 +2,15 @@ use clap::{ArgMatches, Command};
 use color_eyre::eyre::Result;
 use std::sync::Arc;
 use tracing::info;
-use waypoint::{backfill::worker::BackfillQueue, config::Config};
+use waypoint::{
+    backfill::{checkpoint::CheckpointStore, worker::BackfillQueue},
+    config::Config,
+};
 
 /// Register inspect command
 pub fn register_command() -> Command {
-    Command::new("inspect").about("Inspect the current state of the backfill queue")
+    Command::new("inspect")
+        .about("Inspect the current state of the backfill queue and partially reconciled FIDs")
 }
 
 /// Inspect the backfill queue state
@@ -28,10 +32,12 @@ pub async fn execute(config: &Config, _args: &ArgMatches) -> Result<()> {
     info!("  Average job time: {:.2}ms", metrics.avg_job_time_ms);
     info!("");
     info!("Queue Sizes:");
+    info!("  High queue: {} jobs", metrics.high_priority_queue_size);
     info!("  Normal queue: {} jobs", metrics.normal_priority_queue_size);
+    info!("  Low queue: {} jobs", metrics.low_priority_queue_size);
     info!("  In-progress queue: {} jobs", metrics.in_progress_queue_size);
 
-    let total_pending = metrics.normal_priority_queue_size;
+    let total_pending = metrics.pending_jobs();
     info!("");
     info!("Total pending jobs: {}", total_pending);
 
@@ -52,5 +58,26 @@ pub async fn execute(config: &Config, _args: &ArgMatches) -> Result<()> {
         info!("   These jobs may be stuck. Consider running cleanup.");
     }
 
+    // FIDs a worker stopped part way through; the next worker to pick them up resumes here
+    let checkpoints = CheckpointStore::new(redis).list().await?;
+    info!("");
+    info!("Partially reconciled FIDs: {}", checkpoints.len());
+    for checkpoint in &checkpoints {
+        let updated_at = checkpoint
+            .updated_at()
+            .map(|at| at.to_rfc3339())
+            .unwrap_or_else(|| "unknown".to_string());
+        info!("  FID {} (last progress {}):", checkpoint.fid, updated_at);
+        for (message_type, state) in &checkpoint.types {
+            info!(
+                "    {}: {} ({} pages, {} messages)",
+                message_type,
+                if state.done { "done" } else { "in progress" },
+                state.pages,
+                state.messages
+            );
+        }
+    }
+
     Ok(())
 }
@@ -1,5 +1,6 @@
 pub mod inspect;
 pub mod queue;
+pub mod sync;
 pub mod user_data;
 pub mod worker;
 
@@ -14,6 +15,7 @@ pub fn register_commands(app: Command) -> Command {
         .subcommand(inspect::register_command())
         .subcommand(queue::register_command())
         .subcommand(worker::register_command())
+        .subcommand(sync::register_command())
         .subcommand(user_data::register_command())
 }
 
@@ -23,21 +25,24 @@ pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()>
         Some(("inspect", args)) => inspect::execute(config, args).await,
         Some(("queue", args)) => queue::execute(config, args).await,
         Some(("worker", args)) => worker::execute(config, args).await,
+        Some(("sync", args)) => sync::execute(config, args).await,
         Some(("user-data", args)) => user_data::execute(config, args).await,
         // Just for clarity in error messages
         None => {
             println!("Please specify a valid FID command. Available commands:");
-            println!("  inspect    - Inspect the current state of the backfill queue");
+            println!("  inspect    - Inspect the backfill queue and partially reconciled FIDs");
             println!("  queue      - Queue FIDs for backfill");
             println!("  worker     - Start FID-based backfill worker");
+            println!("  sync       - Diff Hub tries against PostgreSQL and fetch what's missing");
             println!("  user-data  - Update user_data for FIDs");
             Ok(())
         },
         Some((cmd, _)) => {
             println!("Unknown command: {}. Available commands:", cmd);
-            println!("  inspect    - Inspect the current state of the backfill queue");
+            println!("  inspect    - Inspect the backfill queue and partially reconciled FIDs");
             println!("  queue      - Queue FIDs for backfill");
             println!("  worker     - Start FID-based backfill worker");
+            println!("  sync       - Diff Hub tries against PostgreSQL and fetch what's missing");
             println!("  user-data  - Update user_data for FIDs");
             Ok(())
         },
@@ -1,10 +1,17 @@
+use crate::commands::export::parse_time;
+use chrono::{DateTime, Utc};
 use clap::{Arg, ArgMatches, Command};
 use color_eyre::eyre::Result;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 use tracing::info;
 use waypoint::{
-    backfill::worker::{BackfillJob, BackfillQueue, JobPriority, JobState},
+    backfill::{
+        priority::PriorityStrategy,
+        reconciler::TimeWindow,
+        worker::{BackfillJob, BackfillQueue, JobKind, JobPriority, JobState},
+    },
     config::Config,
+    database::client::Database,
     hub::{client::Hub, filter::SpamFilter},
 };
 
@@ -78,6 +85,112 @@ pub fn register_command() -> Command {
                 .value_parser(clap::value_parser!(u64))
                 .default_value("50"),
         )
+        .arg(
+            Arg::new("since")
+                .long("since")
+                .help("Only backfill messages and onchain events at or after this (YYYY-MM-DD or RFC 3339)")
+                .value_parser(parse_time),
+        )
+        .arg(
+            Arg::new("until")
+                .long("until")
+                .help("Only backfill messages and onchain events before this (YYYY-MM-DD or RFC 3339)")
+                .value_parser(parse_time),
+        )
+        .arg(
+            Arg::new("strategy")
+                .long("strategy")
+                .help("Queue FIDs chosen by a prioritization strategy instead of 1..max FID")
+                .value_parser(PriorityStrategy::NAMES)
+                .conflicts_with_all(["fids", "max_fid"]),
+        )
+        .arg(
+            Arg::new("fids_file")
+                .long("fids-file")
+                .help("File of FIDs for --strategy file, separated by commas or whitespace")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("limit")
+                .long("limit")
+                .help("Maximum number of FIDs a strategy queues")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("10000"),
+        )
+        .arg(
+            Arg::new("active_hours")
+                .long("active-hours")
+                .help("For --strategy active, how far back to look for activity")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .default_value("24"),
+        )
+        .arg(
+            Arg::new("thread")
+                .long("thread")
+                .value_name("FID:HASH")
+                .help("Queue a thread job: the cast FID:HASH, its replies at every depth and their reactions")
+                .value_parser(clap::value_parser!(String))
+                .conflicts_with_all(["fids", "max_fid", "strategy", "channel"]),
+        )
+        .arg(
+            Arg::new("channel")
+                .long("channel")
+                .value_name("URL")
+                .help("Queue a channel job: casts under this parent URL, limited by --since/--until")
+                .value_parser(clap::value_parser!(String))
+                .conflicts_with_all(["fids", "max_fid", "strategy"]),
+        )
+        .arg(
+            Arg::new("priority")
+                .long("priority")
+                .help("Queue to put jobs on (default: high with --strategy, normal otherwise)")
+                .value_parser(["high", "normal", "low"]),
+        )
+}
+
+/// Build the strategy selected on the command line, if any
+fn strategy_from_args(args: &ArgMatches) -> Result<Option<PriorityStrategy>> {
+    let Some(name) = args.get_one::<String>("strategy") else {
+        return Ok(None);
+    };
+    let strategy = match name.as_str() {
+        "file" => {
+            let path = args.get_one::<PathBuf>("fids_file").ok_or_else(|| {
+                color_eyre::eyre::eyre!("--strategy file needs --fids-file <path>")
+            })?;
+            PriorityStrategy::File(path.clone())
+        },
+        "followers" => PriorityStrategy::Followers,
+        "active" => {
+            let hours = *args.get_one::<u64>("active_hours").expect("has a default");
+            PriorityStrategy::RecentlyActive {
+                since: Utc::now() - chrono::Duration::hours(hours as i64),
+            }
+        },
+        "missing-user-data" => PriorityStrategy::MissingUserData,
+        other => unreachable!("clap only accepts known strategies, got {}", other),
+    };
+    Ok(Some(strategy))
+}
+
+/// Build the thread or channel job selected on the command line, if any
+fn conversation_from_args(args: &ArgMatches) -> Result<Option<JobKind>> {
+    if let Some(url) = args.get_one::<String>("channel") {
+        return Ok(Some(JobKind::Channel { url: url.clone() }));
+    }
+    let Some(thread) = args.get_one::<String>("thread") else {
+        return Ok(None);
+    };
+    let (fid, hash) = thread
+        .split_once(':')
+        .ok_or_else(|| color_eyre::eyre::eyre!("--thread expects FID:HASH, got {}", thread))?;
+    let fid =
+        fid.parse().map_err(|_| color_eyre::eyre::eyre!("Invalid FID in --thread: {}", fid))?;
+    let kind = JobKind::Thread { fid, hash: hash.trim_start_matches("0x").to_lowercase() };
+    if kind.thread_root().is_none() {
+        return Err(color_eyre::eyre::eyre!("Invalid cast hash in --thread: {}", hash));
+    }
+    Ok(Some(kind))
 }
 
 /// Queue FIDs for backfill
@@ -99,6 +212,93 @@ pub async fn execute(config: &Config, args: &ArgMatches) -> Result<()> {
     let max_fid_str = args.get_one::<String>("max_fid");
     let batch_size = args.get_one::<u64>("batch_size").copied().unwrap_or(50);
 
+    // A time window limits each job to the messages and onchain events inside it
+    let since = args.get_one::<DateTime<Utc>>("since").copied();
+    let until = args.get_one::<DateTime<Utc>>("until").copied();
+    if let (Some(since), Some(until)) = (since, until)
+        && since >= until
+    {
+        return Err(color_eyre::eyre::eyre!("--since must be before --until"));
+    }
+    let window = (since.is_some() || until.is_some()).then_some(TimeWindow { since, until });
+    if let Some(window) = window {
+        info!("Queueing jobs limited to the window {}", window);
+    }
+
+    let strategy = strategy_from_args(args)?;
+    let priority = match args.get_one::<String>("priority") {
+        Some(name) => JobPriority::from_name(name).expect("clap only accepts known priorities"),
+        None if strategy.is_some() => JobPriority::High,
+        None => JobPriority::Normal,
+    };
+
+    // Thread and channel jobs backfill one conversation instead of FIDs
+    if let Some(kind) = conversation_from_args(args)? {
+        if matches!(kind, JobKind::Thread { .. }) && window.is_some() {
+            info!("Thread jobs fetch the whole thread; ignoring --since/--until");
+        }
+        let window = if matches!(kind, JobKind::Channel { .. }) { window } else { None };
+        fid_queue
+            .add_job(BackfillJob {
+                fids: Vec::new(),
+                priority,
+                state: JobState::Pending,
+                visibility_timeout: None,
+                attempts: 0,
+                created_at: chrono::Utc::now(),
+                id: String::new(),
+                started_at: None,
+                window,
+                kind: kind.clone(),
+            })
+            .await?;
+        info!("Queued a backfill job for {}", kind);
+        return Ok(());
+    }
+
+    // Prioritized FIDs are queued in the order the strategy returns them
+    if let Some(strategy) = strategy {
+        let limit = *args.get_one::<usize>("limit").expect("has a default");
+        let database = match strategy.needs_database() {
+            true => Some(Database::new(&config.database).await?),
+            false => None,
+        };
+        let fids = strategy.select_fids(database.as_ref().map(|db| &db.pool), limit).await?;
+        let original_count = fids.len();
+        let filtered_fids = filter_spam_fids(fids, &spam_filter).await;
+        info!(
+            "Strategy {} selected {} FIDs ({} spam filtered), queueing at {:?} priority",
+            strategy.name(),
+            original_count,
+            original_count - filtered_fids.len(),
+            priority
+        );
+
+        for chunk in filtered_fids.chunks(batch_size as usize) {
+            fid_queue
+                .add_job(BackfillJob {
+                    fids: chunk.to_vec(),
+                    priority: priority.clone(),
+                    state: JobState::Pending,
+                    visibility_timeout: None,
+                    attempts: 0,
+                    created_at: chrono::Utc::now(),
+                    id: String::new(),
+                    started_at: None,
+                    window,
+                    kind: JobKind::Fids,
+                })
+                .await?;
+        }
+
+        info!(
+            "Queued {} FIDs in {} jobs",
+            filtered_fids.len(),
+            filtered_fids.len().div_ceil(batch_size as usize)
+        );
+        return Ok(());
+    }
+
     // Get the maximum FID - try GetFids first, fall back to hub info
     let hub_max_fid = match hub.get_fids(Some(1), None, Some(true)).await {
         Ok(fids_response) => {
@@ -143,13 +343,15 @@ pub async fn execute(config: &Config, args: &ArgMatches) -> Result<()> {
                 fid_queue
                     .add_job(BackfillJob {
                         fids: filtered_fids,
-                        priority: JobPriority::Normal,
+                        priority: priority.clone(),
                         state: JobState::Pending,
                         visibility_timeout: None,
                         attempts: 0,
                         created_at: chrono::Utc::now(),
                         id: String::new(),
                         started_at: None,
+                        window,
+                        kind: JobKind::Fids,
                     })
                     .await?;
 
@@ -191,13 +393,15 @@ pub async fn execute(config: &Config, args: &ArgMatches) -> Result<()> {
             fid_queue
                 .add_job(BackfillJob {
                     fids: filtered_fids.clone(),
-                    priority: JobPriority::Normal,
+                    priority: priority.clone(),
                     state: JobState::Pending,
                     visibility_timeout: None,
                     attempts: 0,
                     created_at: chrono::Utc::now(),
                     id: String::new(),
                     started_at: None,
+                    window,
+                    kind: JobKind::Fids,
                 })
                 .await?;
             info!(
@@ -235,13 +439,15 @@ pub async fn execute(config: &Config, args: &ArgMatches) -> Result<()> {
                 fid_queue
                     .add_job(BackfillJob {
                         fids: filtered_fids,
-                        priority: JobPriority::Normal,
+                        priority: priority.clone(),
                         state: JobState::Pending,
                         visibility_timeout: None,
                         attempts: 0,
                         created_at: chrono::Utc::now(),
                         id: String::new(),
                         started_at: None,
+                        window,
+                        kind: JobKind::Fids,
                     })
                     .await?;
 
@@ -0,0 +1,161 @@
+use clap::{Arg, ArgMatches, Command};
+use color_eyre::eyre::{Result, eyre};
+use futures::{StreamExt, stream};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+use waypoint::{
+    backfill::{
+        reconciler::MessageReconciler,
+        trie_sync::{FidDiff, TrieSync},
+    },
+    config::Config,
+    database::client::Database,
+    processor::{AppResources, consumer::EventProcessor, database::DatabaseProcessor},
+};
+
+/// Register trie sync command
+pub fn register_command() -> Command {
+    Command::new("sync")
+        .about("Diff the Hub's merkle tries against PostgreSQL and fetch only what's missing")
+        .arg(
+            Arg::new("shards")
+                .long("shards")
+                .help("Comma-separated shard IDs to sync (default: every shard the Hub reports)")
+                .value_parser(clap::value_parser!(String)),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .help("Report the FIDs and types that differ without fetching them")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("concurrency")
+                .long("concurrency")
+                .help("Number of FIDs to reconcile at once")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("4"),
+        )
+}
+
+/// Walk each shard's trie and reconcile the FIDs and types that differ
+pub async fn execute(config: &Config, args: &ArgMatches) -> Result<()> {
+    if config.database.is_sqlite() {
+        return Err(eyre!("Trie sync requires a PostgreSQL database"));
+    }
+    if !config.database.store_messages {
+        warn!("database.store_messages is off; messages will look missing from PostgreSQL");
+    }
+    let dry_run = args.get_flag("dry_run");
+    let concurrency = args.get_one::<usize>("concurrency").copied().unwrap_or(4).max(1);
+
+    let database = Database::new(&config.database).await?;
+    let mut hub = waypoint::hub::client::Hub::new(config.hub.clone())?;
+    hub.connect().await?;
+    let hub = Arc::new(hub);
+
+    let shards: Vec<u32> = match args.get_one::<String>("shards") {
+        Some(list) => list
+            .split(',')
+            .map(|shard| shard.trim().parse().map_err(|_| eyre!("Invalid shard ID: {}", shard)))
+            .collect::<Result<_>>()?,
+        None => (1..=hub.get_hub_info().await?.num_shards).collect(),
+    };
+
+    let sync = TrieSync::new(hub.clone(), database.pool.clone());
+    info!("Counting trie entries in PostgreSQL");
+    let local_counts = sync.local_counts().await?;
+    info!("{} FIDs have entries in PostgreSQL", local_counts.len());
+
+    let mut diffs: Vec<FidDiff> = Vec::new();
+    for shard_id in shards {
+        let start = std::time::Instant::now();
+        let shard = sync.diff_shard(shard_id, &local_counts).await?;
+        info!(
+            "Shard {}: {} of {} FIDs differ ({} trie nodes fetched in {:.2?})",
+            shard_id,
+            shard.fids.len(),
+            shard.fids_in_shard,
+            shard.nodes_fetched,
+            start.elapsed()
+        );
+        diffs.extend(shard.fids);
+    }
+
+    if dry_run {
+        for diff in &diffs {
+            info!(
+                "FID {}: Hub {} entries, PostgreSQL {}; differs in {}",
+                diff.fid,
+                diff.hub_count,
+                diff.local_count,
+                describe(diff)
+            );
+        }
+        info!("Dry run: {} FIDs differ, nothing fetched", diffs.len());
+        return Ok(());
+    }
+
+    let redis = Arc::new(waypoint::redis::client::Redis::new(&config.redis).await?);
+    let hub_mutex = Arc::new(Mutex::new(hub.as_ref().clone()));
+    let store = waypoint::database::store::open(&config.database, Some(hub_mutex.clone())).await?;
+    let processor: Arc<dyn EventProcessor> =
+        Arc::new(DatabaseProcessor::new(Arc::new(AppResources::new(hub_mutex, redis, store))));
+    let reconciler =
+        Arc::new(MessageReconciler::new(hub.clone(), std::time::Duration::from_secs(30), true));
+
+    let total = diffs.len();
+    let results: Vec<(u64, bool)> = stream::iter(diffs)
+        .map(|diff| {
+            let reconciler = reconciler.clone();
+            let processor = processor.clone();
+            async move { (diff.fid, reconcile(&reconciler, processor, &diff).await) }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    let failed = results.iter().filter(|(_, ok)| !ok).count();
+
+    info!("Trie sync complete: {} FIDs reconciled, {} failed", total - failed, failed);
+    Ok(())
+}
+
+/// Fetch only the types that differ for one FID
+async fn reconcile(
+    reconciler: &MessageReconciler,
+    processor: Arc<dyn EventProcessor>,
+    diff: &FidDiff,
+) -> bool {
+    info!("Reconciling FID {}: {}", diff.fid, describe(diff));
+    if diff.fnames {
+        // Fname proofs come from the fname registry, not per-FID Hub RPCs
+        warn!("FID {} has fname proofs missing; they arrive with the event stream", diff.fid);
+    }
+
+    if !diff.kinds.is_empty()
+        && let Err(e) =
+            reconciler.reconcile_message_types(diff.fid, &diff.kinds, processor.clone()).await
+    {
+        error!("Failed to reconcile messages for FID {}: {}", diff.fid, e);
+        return false;
+    }
+    if diff.onchain_events
+        && let Err(e) = reconciler.reconcile_onchain_events_only(diff.fid, None, processor).await
+    {
+        error!("Failed to reconcile onchain events for FID {}: {}", diff.fid, e);
+        return false;
+    }
+    true
+}
+
+fn describe(diff: &FidDiff) -> String {
+    let mut parts: Vec<&str> = diff.kinds.iter().map(|kind| kind.name()).collect();
+    if diff.onchain_events {
+        parts.push("onchain_events");
+    }
+    if diff.fnames {
+        parts.push("fnames");
+    }
+    parts.join(", ")
+}
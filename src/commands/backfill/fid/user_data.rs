@@ -42,7 +42,6 @@ pub async fn execute(config: &Config, args: &ArgMatches) -> Result<()> {
     let mut hub = waypoint::hub::client::Hub::new(config.hub.clone())?;
     hub.connect().await?;
     let hub = Arc::new(hub);
-    let database = Arc::new(waypoint::database::client::Database::new(&config.database).await?);
 
     // Get parameters
     let max_fid_str = args.get_one::<String>("max_fid");
@@ -61,18 +60,15 @@ pub async fn execute(config: &Config, args: &ArgMatches) -> Result<()> {
 
     // Create shared application resources (wraps hub in Mutex for DatabaseProcessor compatibility)
     let hub_mutex = Arc::new(Mutex::new(hub.as_ref().clone()));
-    let app_resources = Arc::new(AppResources::new(hub_mutex, redis.clone(), database.clone()));
+    let store = waypoint::database::store::open(&config.database, Some(hub_mutex.clone())).await?;
+    let app_resources = Arc::new(AppResources::new(hub_mutex, redis.clone(), store));
 
     // Create processor for database
     let db_processor = Arc::new(DatabaseProcessor::new(app_resources.clone()));
 
     // Create reconciler for user_data operations
-    let reconciler = Arc::new(MessageReconciler::new(
-        hub.clone(),
-        database.clone(),
-        std::time::Duration::from_secs(30),
-        true,
-    ));
+    let reconciler =
+        Arc::new(MessageReconciler::new(hub.clone(), std::time::Duration::from_secs(30), true));
 
     let start_time = std::time::Instant::now();
     let mut total_processed = 0;
@@ -5,6 +5,7 @@ use tokio::sync::Mutex;
 use tracing::info;
 use waypoint::{
     backfill::{
+        checkpoint::CheckpointStore,
         reconciler::MessageReconciler,
         worker::{BackfillQueue, Worker},
     },
@@ -42,23 +43,21 @@ pub async fn execute(config: &Config, args: &ArgMatches) -> Result<()> {
     let mut hub = waypoint::hub::client::Hub::new(config.hub.clone())?;
     hub.connect().await?;
     let hub = Arc::new(hub);
-    let database = Arc::new(waypoint::database::client::Database::new(&config.database).await?);
     let fid_queue = Arc::new(BackfillQueue::new(redis.clone(), "backfill:fid:queue".to_string()));
 
     // Create shared application resources (wraps hub in Mutex for DatabaseProcessor compatibility)
     let hub_mutex = Arc::new(Mutex::new(hub.as_ref().clone()));
-    let app_resources = Arc::new(AppResources::new(hub_mutex, redis.clone(), database.clone()));
+    let store = waypoint::database::store::open(&config.database, Some(hub_mutex.clone())).await?;
+    let app_resources = Arc::new(AppResources::new(hub_mutex, redis.clone(), store));
 
     // Create processors
     let db_processor = Arc::new(DatabaseProcessor::new(app_resources.clone()));
 
-    // Create reconciler
-    let reconciler = Arc::new(MessageReconciler::new(
-        hub.clone(),
-        database,
-        std::time::Duration::from_secs(30),
-        true,
-    ));
+    // Create reconciler, checkpointing per-FID progress so a restarted worker resumes mid-FID
+    let reconciler = Arc::new(
+        MessageReconciler::new(hub.clone(), std::time::Duration::from_secs(30), true)
+            .with_checkpoints(Arc::new(CheckpointStore::new(redis.clone()))),
+    );
 
     // Set concurrency to match database connection pool capacity
     // Use 40% of max database connections to avoid saturating the pool
@@ -1,7 +1,13 @@
 pub mod bench;
+pub mod blocks;
+pub mod cast_references;
+pub mod channels;
 pub mod fid;
+pub mod fid_registry;
 pub mod onchain_events;
 pub mod root_parent;
+pub mod signers;
+pub mod status;
 
 use clap::{ArgMatches, Command};
 use color_eyre::eyre::Result;
@@ -16,6 +22,9 @@ pub fn register_commands(app: Command) -> Command {
         // FID-based backfill commands
         .subcommand(fid::register_commands(Command::new("fid")
             .about("FID-based backfill operations")))
+        // Historical replay of Snapchain blocks
+        .subcommand(blocks::register_commands(Command::new("blocks")
+            .about("Replay shard history block by block instead of per FID")))
         // Onchain events backfill commands
         .subcommand(onchain_events::register_commands(Command::new("onchain-events")
             .about("Backfill onchain events for Farcaster FIDs")))
@@ -27,21 +36,49 @@ pub fn register_commands(app: Command) -> Command {
                 .long("messages")
                 .value_name("COUNT")
                 .help("Number of messages to generate for the benchmark")
-                .default_value("10000")))
+                .default_value("10000"))
+            .subcommand(bench::pipeline_command()))
         // Root parent backfill commands
         .subcommand(root_parent::register_commands(Command::new("root-parent")
             .about("Backfill root_parent columns for existing casts")))
+        // Normalized cast mentions and embeds
+        .subcommand(cast_references::register_commands(Command::new("cast-references")
+            .about("Populate cast_mentions and cast_embeds from existing casts")))
+        // Channel registry
+        .subcommand(channels::register_commands(Command::new("channels")
+            .about("Rebuild channel counters and import channel metadata")))
+        // Derived fids registry
+        .subcommand(fid_registry::register_commands(Command::new("fid-registry")
+            .about("Rebuild the fids registry from id_register_events")))
+        .subcommand(signers::register_commands(Command::new("signers")
+            .about("Rebuild the signers table from signer_events")))
+        // Combined FID backfill progress
+        .subcommand(status::register_command())
 }
 
 /// Handle backfill commands based on matches
 pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()> {
     match matches.subcommand() {
         Some(("fid", submatches)) => fid::handle_command(submatches, config).await,
+        Some(("blocks", submatches)) => blocks::handle_command(submatches, config).await,
         Some(("onchain-events", submatches)) => {
             onchain_events::handle_command(submatches, config).await
         },
         Some(("root-parent", submatches)) => root_parent::handle_command(submatches, config).await,
+        Some(("cast-references", submatches)) => {
+            cast_references::handle_command(submatches, config).await
+        },
+        Some(("channels", submatches)) => channels::handle_command(submatches, config).await,
+        Some(("fid-registry", submatches)) => {
+            fid_registry::handle_command(submatches, config).await
+        },
+        Some(("signers", submatches)) => signers::handle_command(submatches, config).await,
+        Some(("status", submatches)) => status::execute(config, submatches).await,
         Some(("bench", submatches)) => {
+            if let Some(("pipeline", args)) = submatches.subcommand() {
+                return bench::run_pipeline(config, args).await;
+            }
+
             // Get the message count parameter
             let messages = submatches
                 .get_one::<String>("messages")
@@ -56,18 +93,21 @@ pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()>
             let hub = Arc::new(tokio::sync::Mutex::new(waypoint::hub::client::Hub::new(
                 config.hub.clone(),
             )?));
+            // Benchmarks exercise the Postgres batch inserter directly
             let database =
                 Arc::new(waypoint::database::client::Database::new(&config.database).await?);
+            let store = Arc::new(waypoint::database::store::PostgresStore::new(
+                database.pool.clone(),
+                Some(hub.clone()),
+                config.database.batch_size,
+            ));
 
             // Create shared application resources
-            let resources = Arc::new(waypoint::processor::AppResources::new(
-                hub.clone(),
-                redis.clone(),
-                database.clone(),
-            ));
+            let resources =
+                Arc::new(waypoint::processor::AppResources::new(hub.clone(), redis.clone(), store));
 
             // Execute benchmark command
-            command.execute(resources).await?;
+            command.execute(resources, database).await?;
 
             Ok(())
         },
@@ -75,16 +115,28 @@ pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()>
         None => {
             println!("Please specify a backfill subcommand. Available command groups:");
             println!("  fid             - FID-based backfill operations");
+            println!("  blocks          - Replay shard history block by block");
             println!("  onchain-events  - Backfill onchain events for Farcaster FIDs");
             println!("  root-parent     - Backfill root_parent columns for casts");
+            println!("  cast-references - Populate cast_mentions and cast_embeds from casts");
+            println!("  channels        - Rebuild channel counters and import channel metadata");
+            println!("  fid-registry    - Rebuild the fids registry from id_register_events");
+            println!("  signers         - Rebuild the signers table from signer_events");
+            println!("  status          - Show FID backfill progress across all workers");
             println!("  bench           - Database benchmark operations");
             Ok(())
         },
         Some((cmd, _)) => {
             println!("Unknown command group: {}. Available command groups:", cmd);
             println!("  fid             - FID-based backfill operations");
+            println!("  blocks          - Replay shard history block by block");
             println!("  onchain-events  - Backfill onchain events for Farcaster FIDs");
             println!("  root-parent     - Backfill root_parent columns for casts");
+            println!("  cast-references - Populate cast_mentions and cast_embeds from casts");
+            println!("  channels        - Rebuild channel counters and import channel metadata");
+            println!("  fid-registry    - Rebuild the fids registry from id_register_events");
+            println!("  signers         - Rebuild the signers table from signer_events");
+            println!("  status          - Show FID backfill progress across all workers");
             println!("  bench           - Database benchmark operations");
             Ok(())
         },
@@ -73,18 +73,17 @@ pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()>
 
     // Initialize individual clients
     let redis = Arc::new(waypoint::redis::client::Redis::new(&config.redis).await?);
-    let database = Arc::new(waypoint::database::client::Database::new(&config.database).await?);
     let mut hub = Hub::new(config.hub.clone())?;
     hub.connect().await.map_err(|e| color_eyre::eyre::eyre!("{}", e))?;
     let hub_client = Arc::new(hub);
 
     // Create shared application resources (wraps hub in Mutex for DatabaseProcessor compatibility)
     let hub_mutex = Arc::new(Mutex::new(hub_client.as_ref().clone()));
-    let resources =
-        Arc::new(AppResources::with_config(hub_mutex, redis, database.clone(), config.clone()));
+    let store = waypoint::database::store::open(&config.database, Some(hub_mutex.clone())).await?;
+    let resources = Arc::new(AppResources::with_config(hub_mutex, redis, store, config.clone()));
 
     let processor = Arc::new(DatabaseProcessor::new(Arc::clone(&resources)));
-    let backfiller = OnChainEventBackfiller::new(hub_client, database, processor);
+    let backfiller = OnChainEventBackfiller::new(hub_client, processor);
 
     if dry_run {
         info!("DRY RUN MODE - No changes will be made");
@@ -0,0 +1,29 @@
+use clap::{Arg, ArgMatches, Command};
+use color_eyre::eyre::Result;
+use tracing::info;
+use waypoint::{config::Config, database::registry::rebuild_signers};
+
+/// Register signers rebuild command
+pub fn register_commands(app: Command) -> Command {
+    app.about("Rebuild the derived signers table from signer_events").arg(
+        Arg::new("batch-size")
+            .long("batch-size")
+            .value_name("SIZE")
+            .help("Number of FIDs to recompute per batch")
+            .default_value("500")
+            .value_parser(clap::value_parser!(usize)),
+    )
+}
+
+/// Handle signers rebuild command
+pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()> {
+    let batch_size = *matches.get_one::<usize>("batch-size").unwrap();
+
+    info!("Rebuilding signers with batch_size={}", batch_size);
+
+    let database = waypoint::database::client::Database::new(&config.database).await?;
+    let written = rebuild_signers(&database.pool, batch_size).await?;
+
+    info!("Signers rebuild complete: {} signers written", written);
+    Ok(())
+}
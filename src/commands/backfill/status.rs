@@ -0,0 +1,176 @@
+use clap::{Arg, ArgMatches, Command};
+use color_eyre::eyre::Result;
+use std::{fmt::Write as _, sync::Arc, time::Duration};
+use waypoint::{
+    backfill::{
+        progress::{BackfillProgress, ProgressStore},
+        worker::BackfillQueue,
+    },
+    config::Config,
+};
+
+/// Register status command
+pub fn register_command() -> Command {
+    Command::new("status")
+        .about("Show combined FID backfill progress across all workers")
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .help("Redraw the status until interrupted")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("interval")
+                .long("interval")
+                .help("Seconds between redraws with --watch")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("2"),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print the status as JSON")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("reset")
+                .long("reset")
+                .help("Clear the progress totals (e.g. before queueing a fresh backfill)")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// Print backfill progress, once or continuously
+pub async fn execute(config: &Config, args: &ArgMatches) -> Result<()> {
+    let redis = Arc::new(waypoint::redis::client::Redis::new(&config.redis).await?);
+    let queue = BackfillQueue::new(redis.clone(), "backfill:fid:queue".to_string());
+    let progress = ProgressStore::new(redis);
+
+    if args.get_flag("reset") {
+        progress.reset().await?;
+        println!("Backfill progress totals cleared");
+        return Ok(());
+    }
+
+    let json = args.get_flag("json");
+    if !args.get_flag("watch") {
+        let status = progress.load(&queue.get_metrics().await).await?;
+        println!("{}", if json { serde_json::to_string_pretty(&status)? } else { render(&status) });
+        return Ok(());
+    }
+
+    let interval = args.get_one::<u64>("interval").copied().unwrap_or(2).max(1);
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval));
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let status = progress.load(&queue.get_metrics().await).await?;
+                if json {
+                    println!("{}", serde_json::to_string(&status)?);
+                } else {
+                    // Clear the screen and redraw from the top-left corner
+                    print!("\x1b[2J\x1b[H{}", render(&status));
+                    println!("\nRefreshing every {}s, Ctrl-C to exit", interval);
+                }
+            },
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+fn render(status: &BackfillProgress) -> String {
+    let mut out = String::new();
+    let finished = status.fids_done + status.fids_failed;
+    let percent =
+        status.percent_done().map(|percent| format!(" ({:.1}%)", percent)).unwrap_or_default();
+    let eta = match status.eta_secs {
+        Some(0) => "done".to_string(),
+        Some(secs) => format_duration(secs),
+        None => "unknown (no progress)".to_string(),
+    };
+    let errors = if status.errors.is_empty() {
+        "none".to_string()
+    } else {
+        status
+            .errors
+            .iter()
+            .map(|(kind, count)| format!("{} {}", kind, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let _ = writeln!(out, "FID backfill");
+    let _ = writeln!(
+        out,
+        "  FIDs:    {} / {}{}, {} failed",
+        finished, status.fids_queued, percent, status.fids_failed
+    );
+    let _ = writeln!(
+        out,
+        "  Rate:    {:.2} FIDs/sec across {} workers",
+        status.fids_per_second,
+        status.workers.len()
+    );
+    let _ = writeln!(out, "  ETA:     {}", eta);
+    let _ = writeln!(
+        out,
+        "  Jobs:    {} pending, {} in progress",
+        status.pending_jobs, status.in_progress_jobs
+    );
+    let _ = writeln!(out, "  Errors:  {}", errors);
+
+    let now = chrono::Utc::now();
+    for worker in &status.workers {
+        let uptime = worker
+            .started_at
+            .map(|at| format_duration((now - at).num_seconds().max(0) as u64))
+            .unwrap_or_else(|| "?".to_string());
+        let errors: u64 = worker.errors.values().sum();
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "Worker {} (up {}): {} jobs, {} FIDs, {} spam, {:.2} FIDs/sec, {} errors",
+            worker.worker_id,
+            uptime,
+            worker.jobs_processed,
+            worker.fids_processed,
+            worker.spam_fids_skipped,
+            worker.fids_per_second,
+            errors
+        );
+        if worker.current_jobs.is_empty() {
+            let _ = writeln!(out, "  idle");
+        }
+        for job in &worker.current_jobs {
+            if let Some(target) = &job.target {
+                let _ = writeln!(
+                    out,
+                    "  job {}: {} for {}",
+                    job.id,
+                    target,
+                    format_duration((now - job.started_at).num_seconds().max(0) as u64)
+                );
+                continue;
+            }
+            let _ = writeln!(
+                out,
+                "  job {}: {} FIDs ({}..{}) for {}",
+                job.id,
+                job.fids,
+                job.first_fid.unwrap_or_default(),
+                job.last_fid.unwrap_or_default(),
+                format_duration((now - job.started_at).num_seconds().max(0) as u64)
+            );
+        }
+    }
+    out
+}
+
+fn format_duration(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m {}s", secs / 60, secs % 60),
+        3600..86400 => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("{}d {}h", secs / 86400, secs % 86400 / 3600),
+    }
+}
@@ -0,0 +1,147 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{Arg, ArgAction, ArgMatches, Command, value_parser};
+use color_eyre::eyre::Result;
+use std::{path::PathBuf, time::Duration};
+use waypoint::{
+    config::Config,
+    database::client::Database,
+    export::{self, Compression, ExportEntity, ExportFormat, ExportOptions},
+};
+
+/// Parse a date (`YYYY-MM-DD`, midnight UTC) or an RFC 3339 timestamp
+pub(crate) fn parse_time(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).expect("midnight is valid").and_utc());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|ts| ts.with_timezone(&Utc))
+        .map_err(|_| format!("expected YYYY-MM-DD or an RFC 3339 timestamp, got {}", value))
+}
+
+/// Register export commands
+pub fn register_commands(app: Command) -> Command {
+    let entities: Vec<&'static str> = ExportEntity::ALL.iter().map(|e| e.name()).collect();
+    let formats: Vec<&'static str> = ExportFormat::ALL.iter().map(|f| f.name()).collect();
+
+    app.about("Export a table to CSV, JSONL or Parquet files")
+        .arg(
+            Arg::new("entity")
+                .long("entity")
+                .help("Table to export")
+                .required(true)
+                .value_parser(entities),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .short('o')
+                .help("Directory to write files under; each entity gets a subdirectory")
+                .required(true)
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help("Output file format")
+                .default_value("parquet")
+                .value_parser(formats),
+        )
+        .arg(
+            Arg::new("compression")
+                .long("compression")
+                .help("gzip compresses whole CSV/JSONL files, or Parquet pages")
+                .default_value("none")
+                .value_parser(["none", "gzip"]),
+        )
+        .arg(
+            Arg::new("since")
+                .long("since")
+                .help("Only messages with a timestamp at or after this (YYYY-MM-DD or RFC 3339)")
+                .value_parser(parse_time),
+        )
+        .arg(
+            Arg::new("until")
+                .long("until")
+                .help("Only messages with a timestamp before this (YYYY-MM-DD or RFC 3339)")
+                .value_parser(parse_time),
+        )
+        .arg(
+            Arg::new("min-fid")
+                .long("min-fid")
+                .help("Lowest FID to export")
+                .value_parser(value_parser!(i64)),
+        )
+        .arg(
+            Arg::new("max-fid")
+                .long("max-fid")
+                .help("Highest FID to export")
+                .value_parser(value_parser!(i64)),
+        )
+        .arg(
+            Arg::new("partition-by-day")
+                .long("partition-by-day")
+                .help("Write one file per UTC day of message timestamp, under date=YYYY-MM-DD/")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("watermark")
+                .long("watermark")
+                .help("Only export rows changed since the run that last saved this file, then update it")
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("watermark-lag-secs")
+                .long("watermark-lag-secs")
+                .help("Leave changes newer than this for the next incremental run")
+                .default_value("60")
+                .value_parser(value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("row-group-size")
+                .long("row-group-size")
+                .help("Rows per Parquet row group")
+                .default_value("10000")
+                .value_parser(value_parser!(u64).range(1..)),
+        )
+}
+
+/// Handle export commands
+pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()> {
+    let entity = matches.get_one::<String>("entity").expect("entity is required");
+    let format = matches.get_one::<String>("format").expect("format has a default");
+    let compression =
+        match matches.get_one::<String>("compression").expect("compression has a default").as_str()
+        {
+            "gzip" => Compression::Gzip,
+            _ => Compression::None,
+        };
+
+    let options = ExportOptions {
+        entity: ExportEntity::from_name(entity).expect("entity is validated by clap"),
+        format: ExportFormat::from_name(format).expect("format is validated by clap"),
+        compression,
+        output_dir: matches.get_one::<PathBuf>("output").expect("output is required").clone(),
+        since: matches.get_one::<DateTime<Utc>>("since").copied(),
+        until: matches.get_one::<DateTime<Utc>>("until").copied(),
+        min_fid: matches.get_one::<i64>("min-fid").copied(),
+        max_fid: matches.get_one::<i64>("max-fid").copied(),
+        partition_by_day: matches.get_flag("partition-by-day"),
+        row_group_size: *matches.get_one::<u64>("row-group-size").expect("has a default") as usize,
+        watermark: matches.get_one::<PathBuf>("watermark").cloned(),
+        watermark_lag: Duration::from_secs(
+            *matches.get_one::<u64>("watermark-lag-secs").expect("has a default"),
+        ),
+    };
+
+    let database = Database::new(&config.database).await?;
+    let summary = export::export(&database.pool, &options).await?;
+
+    println!("Exported {} {} rows to {} files", summary.rows, entity, summary.files.len());
+    for file in &summary.files {
+        println!("  {}", file.display());
+    }
+    if let Some(through) = summary.exported_through {
+        println!("Watermark: rows updated before {}", through);
+    }
+    Ok(())
+}
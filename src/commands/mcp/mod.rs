@@ -6,33 +6,40 @@ use rmcp::transport::streamable_http_server::{
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
-use tracing::info;
+use tracing::{info, warn};
 use waypoint::config::Config;
-use waypoint::core::data_context::{DataContext, DataContextBuilder};
-use waypoint::services::mcp::{NullDb, WaypointMcpService, WaypointMcpTools};
-
-const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1:8000";
+use waypoint::core::data_context::ReadPolicy;
+use waypoint::database::client::Database;
+use waypoint::services::mcp::{WaypointMcpService, WaypointMcpTools, build_data_context};
 
 /// Register MCP commands
 pub fn register_commands(app: Command) -> Command {
     app.about("MCP service commands").subcommand(
-        Command::new("serve").about("Serve MCP service").arg(
-            clap::Arg::new("bind")
-                .long("bind")
-                .short('b')
-                .help("Bind address for the server")
-                .default_value(DEFAULT_BIND_ADDRESS)
-                .action(ArgAction::Set)
-                .value_parser(value_parser!(std::net::SocketAddr)),
-        ),
+        Command::new("serve")
+            .about("Serve MCP service")
+            .arg(
+                clap::Arg::new("bind")
+                    .long("bind")
+                    .short('b')
+                    .help("Bind address for the server (defaults to mcp.bind_address and mcp.port)")
+                    .action(ArgAction::Set)
+                    .value_parser(value_parser!(std::net::SocketAddr)),
+            )
+            .arg(
+                clap::Arg::new("read-policy")
+                    .long("read-policy")
+                    .help("Where tools read from: db-first, db-only or hub-only (defaults to mcp.read_policy)")
+                    .action(ArgAction::Set)
+                    .value_parser(value_parser!(ReadPolicy)),
+            ),
     )
 }
 
 /// Handle MCP commands
-pub async fn handle_command(matches: &ArgMatches, _config: &Config) -> Result<()> {
+pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()> {
     match matches.subcommand() {
         Some(("serve", serve_matches)) => {
-            serve_mcp(serve_matches).await?;
+            serve_mcp(serve_matches, config).await?;
         },
         _ => {
             let cmd = Command::new("mcp");
@@ -43,27 +50,41 @@ pub async fn handle_command(matches: &ArgMatches, _config: &Config) -> Result<()
 }
 
 /// Serve MCP service with Farcaster data tools
-async fn serve_mcp(matches: &ArgMatches) -> Result<()> {
-    let bind_address = matches
-        .get_one::<std::net::SocketAddr>("bind")
-        .copied()
-        .unwrap_or_else(|| DEFAULT_BIND_ADDRESS.parse().unwrap());
+async fn serve_mcp(matches: &ArgMatches, config: &Config) -> Result<()> {
+    let mut config = config.clone();
+    if let Some(read_policy) = matches.get_one::<ReadPolicy>("read-policy") {
+        config.mcp.read_policy = *read_policy;
+    }
 
-    info!("Starting MCP service on {}", bind_address);
+    let bind_address = match matches.get_one::<std::net::SocketAddr>("bind") {
+        Some(bind_address) => *bind_address,
+        None => format!("{}:{}", config.mcp.bind_address, config.mcp.port).parse()?,
+    };
 
-    // Create the required clients for the DataContext
-    let hub_config = Arc::new(Config::default().hub);
+    info!("Starting MCP service on {}", bind_address);
 
     // Create Hub client (connection will happen automatically on first use)
-    let hub = waypoint::hub::client::Hub::new(hub_config)
+    let hub = waypoint::hub::client::Hub::new(Arc::new(config.hub.clone()))
         .map_err(|e| color_eyre::eyre::eyre!("Failed to create Hub client: {}", e))?;
 
     // Create Hub client for data context
     let hub_client = waypoint::hub::providers::FarcasterHubClient::new(Arc::new(Mutex::new(hub)));
 
-    // Create the data context with the Hub client and NullDb
-    let data_context: DataContext<NullDb, _> =
-        DataContextBuilder::new().with_database(NullDb).with_hub_client(hub_client).build();
+    // Connect to Postgres unless reads are Hub-only
+    let database = match config.mcp.read_policy {
+        ReadPolicy::HubOnly => None,
+        ReadPolicy::DbOnly => Some(Arc::new(Database::new(&config.database).await?)),
+        ReadPolicy::DbFirst => match Database::new(&config.database).await {
+            Ok(database) => Some(Arc::new(database)),
+            Err(e) => {
+                warn!("Failed to connect to database, serving from the Hub only: {}", e);
+                None
+            },
+        },
+    };
+
+    let data_context = build_data_context(&config, hub_client, database);
+    info!("MCP reads use the {} policy", data_context.read_policy());
 
     // Create a cancellation token for the service
     let cancellation_token = CancellationToken::new();
@@ -1,5 +1,9 @@
+pub mod audit;
 pub mod backfill;
+pub mod export;
 pub mod mcp;
+pub mod outbox;
+pub mod webhooks;
 
 use clap::Command;
 use color_eyre::eyre::Result;
@@ -16,6 +20,10 @@ pub fn register_commands(app: Command) -> Command {
     )
     .subcommand(backfill::register_commands(Command::new("backfill")))
     .subcommand(mcp::register_commands(Command::new("mcp")))
+    .subcommand(webhooks::register_commands(Command::new("webhooks")))
+    .subcommand(outbox::register_commands(Command::new("outbox")))
+    .subcommand(export::register_commands(Command::new("export")))
+    .subcommand(audit::register_commands(Command::new("audit")))
 }
 
 /// Handle all application commands
@@ -34,6 +42,12 @@ pub async fn handle_commands(matches: clap::ArgMatches, config: &Config) -> Resu
             backfill::handle_command(backfill_matches, config).await
         },
         Some(("mcp", mcp_matches)) => mcp::handle_command(mcp_matches, config).await,
+        Some(("webhooks", webhook_matches)) => {
+            webhooks::handle_command(webhook_matches, config).await
+        },
+        Some(("outbox", outbox_matches)) => outbox::handle_command(outbox_matches, config).await,
+        Some(("export", export_matches)) => export::handle_command(export_matches, config).await,
+        Some(("audit", audit_matches)) => audit::handle_command(audit_matches, config).await,
         _ => {
             println!("Please specify a subcommand. Use --help for more information.");
             Ok(())
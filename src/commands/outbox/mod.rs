@@ -0,0 +1,128 @@
+use clap::{Arg, ArgAction, ArgMatches, Command, value_parser};
+use color_eyre::eyre::Result;
+use std::time::Duration;
+use waypoint::{
+    config::Config,
+    database::{client::Database, outbox},
+};
+
+/// Register change outbox commands
+pub fn register_commands(app: Command) -> Command {
+    app.about("Read and maintain the change outbox")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("read")
+                .about("Print changes after a sequence number, one JSON object per line")
+                .arg(
+                    Arg::new("after")
+                        .long("after")
+                        .help("Cursor: only changes with a higher sequence number")
+                        .default_value("0")
+                        .value_parser(value_parser!(i64)),
+                )
+                .arg(
+                    Arg::new("limit")
+                        .long("limit")
+                        .help("Changes per read")
+                        .default_value("100")
+                        .value_parser(value_parser!(i64)),
+                )
+                .arg(
+                    Arg::new("entity")
+                        .long("entity")
+                        .help("Only changes to this entity, e.g. cast or user_data (repeatable)")
+                        .action(ArgAction::Append)
+                        .value_parser(outbox::ENTITIES),
+                )
+                .arg(
+                    Arg::new("follow")
+                        .long("follow")
+                        .help("Keep polling for new changes")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("poll-interval-ms")
+                        .long("poll-interval-ms")
+                        .help("How often to poll when following and caught up")
+                        .default_value("1000")
+                        .value_parser(value_parser!(u64)),
+                ),
+        )
+        .subcommand(Command::new("status").about("Show the size and sequence range of the outbox"))
+        .subcommand(
+            Command::new("cleanup").about("Delete expired changes").arg(
+                Arg::new("older-than-hours")
+                    .long("older-than-hours")
+                    .help("Delete changes older than this (defaults to outbox.retention_hours)")
+                    .value_parser(value_parser!(u64)),
+            ),
+        )
+}
+
+/// Handle change outbox commands
+pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()> {
+    let database = Database::new(&config.database).await?;
+    let pool = &database.pool;
+
+    match matches.subcommand() {
+        Some(("read", read_matches)) => {
+            let mut after = *read_matches.get_one::<i64>("after").expect("after has a default");
+            let limit = *read_matches.get_one::<i64>("limit").expect("limit has a default");
+            let entities: Vec<String> = read_matches
+                .get_many::<String>("entity")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default();
+            let follow = read_matches.get_flag("follow");
+            let poll_interval = Duration::from_millis(
+                *read_matches.get_one::<u64>("poll-interval-ms").expect("has a default"),
+            );
+
+            loop {
+                let rows = outbox::read(pool, after, &entities, limit).await?;
+                for row in &rows {
+                    println!("{}", row.to_json());
+                }
+
+                match rows.last() {
+                    Some(last) => after = last.seq,
+                    None if follow => tokio::time::sleep(poll_interval).await,
+                    None => break,
+                }
+                if !follow && (rows.len() as i64) < limit {
+                    break;
+                }
+            }
+        },
+        Some(("status", _)) => {
+            let status = outbox::status(pool).await?;
+            println!("Rows: {}", status.rows);
+            match (status.first_seq, status.last_seq) {
+                (Some(first), Some(last)) => println!("Sequence: {} to {}", first, last),
+                _ => println!("Sequence: empty"),
+            }
+            if let Some(oldest) = status.oldest_at {
+                println!("Oldest change: {}", oldest);
+            }
+            if !config.outbox.enabled {
+                println!("Note: outbox.enabled is false, so no new changes are being recorded");
+            }
+        },
+        Some(("cleanup", cleanup_matches)) => {
+            let hours = cleanup_matches
+                .get_one::<u64>("older-than-hours")
+                .copied()
+                .unwrap_or(config.outbox.retention_hours);
+            let cutoff =
+                sqlx::types::time::OffsetDateTime::now_utc() - Duration::from_secs(hours * 3600);
+            let deleted =
+                outbox::cleanup(pool, cutoff, config.outbox.cleanup_batch_size as i64).await?;
+            println!("Deleted {} changes older than {}h", deleted, hours);
+        },
+        _ => {
+            register_commands(Command::new("outbox")).print_help()?;
+        },
+    }
+
+    Ok(())
+}
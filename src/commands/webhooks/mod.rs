@@ -0,0 +1,240 @@
+use clap::{Arg, ArgAction, ArgMatches, Command, value_parser};
+use color_eyre::eyre::{Result, eyre};
+use uuid::Uuid;
+use waypoint::{
+    config::Config,
+    database::{
+        client::Database,
+        webhooks::{self, WebhookFilters},
+    },
+    processor::webhook::{is_message_type_name, is_onchain_event_type_name},
+};
+
+fn subscription_id_arg() -> Arg {
+    Arg::new("id").help("Subscription ID").required(true).value_parser(value_parser!(Uuid))
+}
+
+fn limit_arg() -> Arg {
+    Arg::new("limit")
+        .long("limit")
+        .help("Number of rows to show")
+        .default_value("20")
+        .value_parser(value_parser!(i64))
+}
+
+/// Register webhook commands
+pub fn register_commands(app: Command) -> Command {
+    app.about("Manage webhook subscriptions")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("create")
+                .about("Create a subscription")
+                .arg(Arg::new("url").long("url").help("Endpoint to POST events to").required(true))
+                .arg(
+                    Arg::new("secret")
+                        .long("secret")
+                        .help("HMAC signing secret (generated if omitted)"),
+                )
+                .arg(
+                    Arg::new("fid")
+                        .long("fid")
+                        .help("Only events by this FID (repeatable)")
+                        .action(ArgAction::Append)
+                        .value_parser(value_parser!(i64)),
+                )
+                .arg(
+                    Arg::new("mention")
+                        .long("mention")
+                        .help("Only casts mentioning this FID (repeatable)")
+                        .action(ArgAction::Append)
+                        .value_parser(value_parser!(i64)),
+                )
+                .arg(
+                    Arg::new("parent-url")
+                        .long("parent-url")
+                        .help("Only casts with this parent URL (repeatable)")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("message-type")
+                        .long("message-type")
+                        .help("Only messages of this type, e.g. cast_add or link_add (repeatable)")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("onchain-event-type")
+                        .long("onchain-event-type")
+                        .help("Only on-chain events of this type, e.g. id_register (repeatable)")
+                        .action(ArgAction::Append),
+                ),
+        )
+        .subcommand(Command::new("list").about("List subscriptions"))
+        .subcommand(
+            Command::new("pause")
+                .about("Stop delivering to a subscription")
+                .arg(subscription_id_arg()),
+        )
+        .subcommand(
+            Command::new("resume").about("Resume a paused subscription").arg(subscription_id_arg()),
+        )
+        .subcommand(
+            Command::new("delete")
+                .about("Delete a subscription and drop its pending deliveries")
+                .arg(subscription_id_arg()),
+        )
+        .subcommand(
+            Command::new("deliveries")
+                .about("Show a subscription's recent deliveries")
+                .arg(subscription_id_arg())
+                .arg(
+                    Arg::new("status")
+                        .long("status")
+                        .help("Only deliveries with this status")
+                        .value_parser(["pending", "delivered", "dead"]),
+                )
+                .arg(limit_arg()),
+        )
+        .subcommand(
+            Command::new("attempts")
+                .about("Show the delivery log of a subscription")
+                .arg(subscription_id_arg())
+                .arg(limit_arg()),
+        )
+        .subcommand(
+            Command::new("replay")
+                .about("Requeue a subscription's dead-lettered deliveries")
+                .arg(subscription_id_arg()),
+        )
+}
+
+/// Values of a repeatable argument, or an empty filter
+fn values<T: Clone + Send + Sync + 'static>(matches: &ArgMatches, id: &str) -> Vec<T> {
+    matches.get_many::<T>(id).map(|values| values.cloned().collect()).unwrap_or_default()
+}
+
+fn subscription_id(matches: &ArgMatches) -> Uuid {
+    *matches.get_one::<Uuid>("id").expect("id is required")
+}
+
+/// Handle webhook commands
+pub async fn handle_command(matches: &ArgMatches, config: &Config) -> Result<()> {
+    let database = Database::new(&config.database).await?;
+    let pool = &database.pool;
+
+    match matches.subcommand() {
+        Some(("create", create_matches)) => {
+            let url = create_matches.get_one::<String>("url").expect("url is required");
+            url::Url::parse(url).map_err(|e| eyre!("Invalid URL {}: {}", url, e))?;
+
+            let filters = WebhookFilters {
+                fids: values(create_matches, "fid"),
+                mentioned_fids: values(create_matches, "mention"),
+                parent_urls: values(create_matches, "parent-url"),
+                message_types: values(create_matches, "message-type"),
+                onchain_event_types: values(create_matches, "onchain-event-type"),
+            };
+            if let Some(name) = filters.message_types.iter().find(|t| !is_message_type_name(t)) {
+                return Err(eyre!("Unknown message type: {}", name));
+            }
+            if let Some(name) =
+                filters.onchain_event_types.iter().find(|t| !is_onchain_event_type_name(t))
+            {
+                return Err(eyre!("Unknown on-chain event type: {}", name));
+            }
+
+            let secret = match create_matches.get_one::<String>("secret") {
+                Some(secret) => secret.clone(),
+                None => hex::encode(rand::random::<[u8; 32]>()),
+            };
+
+            let id = webhooks::create_subscription(pool, url, &secret, &filters).await?;
+            println!("Created subscription {}", id);
+            println!("Signing secret: {}", secret);
+        },
+        Some(("list", _)) => {
+            let subscriptions = webhooks::list_subscriptions(pool).await?;
+            if subscriptions.is_empty() {
+                println!("No webhook subscriptions");
+            }
+            for subscription in subscriptions {
+                let filters = &subscription.filters;
+                println!(
+                    "{}  {}  {}",
+                    subscription.id,
+                    if subscription.active { "active" } else { "paused" },
+                    subscription.url
+                );
+                println!(
+                    "    fids={:?} mentions={:?} parent_urls={:?} message_types={:?} onchain_event_types={:?}",
+                    filters.fids,
+                    filters.mentioned_fids,
+                    filters.parent_urls,
+                    filters.message_types,
+                    filters.onchain_event_types
+                );
+            }
+        },
+        Some((action @ ("pause" | "resume"), id_matches)) => {
+            let id = subscription_id(id_matches);
+            if !webhooks::set_subscription_active(pool, id, action == "resume").await? {
+                return Err(eyre!("No subscription {}", id));
+            }
+            println!(
+                "Subscription {} {}",
+                id,
+                if action == "resume" { "resumed" } else { "paused" }
+            );
+        },
+        Some(("delete", id_matches)) => {
+            let id = subscription_id(id_matches);
+            if !webhooks::delete_subscription(pool, id).await? {
+                return Err(eyre!("No subscription {}", id));
+            }
+            println!("Deleted subscription {}", id);
+        },
+        Some(("deliveries", delivery_matches)) => {
+            let id = subscription_id(delivery_matches);
+            let status = delivery_matches.get_one::<String>("status").map(String::as_str);
+            let limit = *delivery_matches.get_one::<i64>("limit").expect("limit has a default");
+
+            for delivery in webhooks::list_deliveries(pool, id, status, limit).await? {
+                println!(
+                    "{}  event {}  {}  attempts={}  updated {}{}",
+                    delivery.id,
+                    delivery.event_id,
+                    delivery.status,
+                    delivery.attempts,
+                    delivery.updated_at,
+                    delivery.last_error.map(|e| format!("  error: {}", e)).unwrap_or_default()
+                );
+            }
+        },
+        Some(("attempts", attempt_matches)) => {
+            let id = subscription_id(attempt_matches);
+            let limit = *attempt_matches.get_one::<i64>("limit").expect("limit has a default");
+
+            for attempt in webhooks::list_attempts(pool, id, limit).await? {
+                println!(
+                    "{}  {} #{}  status={}  {}ms{}",
+                    attempt.created_at,
+                    attempt.delivery_id,
+                    attempt.attempt,
+                    attempt.status_code.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string()),
+                    attempt.duration_ms,
+                    attempt.error.map(|e| format!("  error: {}", e)).unwrap_or_default()
+                );
+            }
+        },
+        Some(("replay", id_matches)) => {
+            let id = subscription_id(id_matches);
+            let requeued = webhooks::replay_dead_letters(pool, id).await?;
+            println!("Requeued {} dead-lettered deliveries for {}", requeued, id);
+        },
+        _ => {
+            register_commands(Command::new("webhooks")).print_help()?;
+        },
+    }
+
+    Ok(())
+}
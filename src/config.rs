@@ -1,4 +1,5 @@
 //! Configuration management for the application
+use crate::core::data_context::ReadPolicy;
 use crate::eth::EthConfig;
 use figment::{
     Figment,
@@ -68,6 +69,9 @@ impl std::str::FromStr for ServiceMode {
 }
 
 /// Database configuration
+///
+/// `url` is a Postgres connection string, or a `sqlite:` URL such as
+/// `sqlite://waypoint.db` when built with the `sqlite` feature.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseConfig {
     pub url: String,
@@ -79,6 +83,9 @@ pub struct DatabaseConfig {
     pub batch_size: usize,
     #[serde(default = "default_skip_migrations")]
     pub skip_migrations: bool,
+    /// Recent casts whose roots are kept in memory for resolving replies
+    #[serde(default = "default_root_parent_cache_size")]
+    pub root_parent_cache_size: usize,
 }
 
 /// Redis configuration
@@ -511,6 +518,9 @@ pub struct McpConfig {
     pub bind_address: String,
     #[serde(default = "default_mcp_port")]
     pub port: u16,
+    /// Where tool reads are served from: db-first, db-only or hub-only
+    #[serde(default)]
+    pub read_policy: ReadPolicy,
 }
 
 impl Default for McpConfig {
@@ -519,6 +529,7 @@ impl Default for McpConfig {
             enabled: default_mcp_enabled(),
             bind_address: default_mcp_bind_address(),
             port: default_mcp_port(),
+            read_policy: ReadPolicy::default(),
         }
     }
 }
@@ -535,6 +546,393 @@ fn default_mcp_port() -> u16 {
     8000
 }
 
+/// REST API configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_rest_bind_address")]
+    pub bind_address: String,
+    #[serde(default = "default_rest_port")]
+    pub port: u16,
+    /// Page size when a request doesn't set `limit`
+    #[serde(default = "default_rest_page_size")]
+    pub default_page_size: usize,
+    /// Upper bound on `limit`
+    #[serde(default = "default_rest_max_page_size")]
+    pub max_page_size: usize,
+}
+
+impl Default for RestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: default_rest_bind_address(),
+            port: default_rest_port(),
+            default_page_size: default_rest_page_size(),
+            max_page_size: default_rest_max_page_size(),
+        }
+    }
+}
+
+fn default_rest_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_rest_port() -> u16 {
+    8081
+}
+
+fn default_rest_page_size() -> usize {
+    25
+}
+
+fn default_rest_max_page_size() -> usize {
+    100
+}
+
+/// GraphQL endpoint configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphqlConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_graphql_bind_address")]
+    pub bind_address: String,
+    #[serde(default = "default_graphql_port")]
+    pub port: u16,
+    /// Deepest selection set a query may nest
+    #[serde(default = "default_graphql_max_depth")]
+    pub max_depth: usize,
+    /// Upper bound on a query's complexity, where list fields cost `first` times their children
+    #[serde(default = "default_graphql_max_complexity")]
+    pub max_complexity: usize,
+    /// Upper bound on `first` for list fields
+    #[serde(default = "default_graphql_max_page_size")]
+    pub max_page_size: usize,
+}
+
+impl Default for GraphqlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: default_graphql_bind_address(),
+            port: default_graphql_port(),
+            max_depth: default_graphql_max_depth(),
+            max_complexity: default_graphql_max_complexity(),
+            max_page_size: default_graphql_max_page_size(),
+        }
+    }
+}
+
+fn default_graphql_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_graphql_port() -> u16 {
+    8082
+}
+
+fn default_graphql_max_depth() -> usize {
+    10
+}
+
+fn default_graphql_max_complexity() -> usize {
+    1000
+}
+
+fn default_graphql_max_page_size() -> usize {
+    100
+}
+
+/// Outbound webhook configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Attempts per delivery before it is dead-lettered
+    #[serde(default = "default_webhook_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles on each attempt
+    #[serde(default = "default_webhook_initial_backoff_secs")]
+    pub initial_backoff_secs: u64,
+    /// Upper bound on the retry delay
+    #[serde(default = "default_webhook_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+    /// Per-request timeout
+    #[serde(default = "default_webhook_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Deliveries in flight at once
+    #[serde(default = "default_webhook_concurrency")]
+    pub concurrency: usize,
+    /// How often the dispatcher checks for due deliveries when idle
+    #[serde(default = "default_webhook_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// How often subscriptions are reloaded from the database
+    #[serde(default = "default_webhook_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_attempts: default_webhook_max_attempts(),
+            initial_backoff_secs: default_webhook_initial_backoff_secs(),
+            max_backoff_secs: default_webhook_max_backoff_secs(),
+            timeout_secs: default_webhook_timeout_secs(),
+            concurrency: default_webhook_concurrency(),
+            poll_interval_ms: default_webhook_poll_interval_ms(),
+            refresh_interval_secs: default_webhook_refresh_interval_secs(),
+        }
+    }
+}
+
+fn default_webhook_max_attempts() -> u32 {
+    8
+}
+
+fn default_webhook_initial_backoff_secs() -> u64 {
+    5
+}
+
+fn default_webhook_max_backoff_secs() -> u64 {
+    3600
+}
+
+fn default_webhook_timeout_secs() -> u64 {
+    10
+}
+
+fn default_webhook_concurrency() -> usize {
+    8
+}
+
+fn default_webhook_poll_interval_ms() -> u64 {
+    1000
+}
+
+fn default_webhook_refresh_interval_secs() -> u64 {
+    30
+}
+
+/// Live event feed (SSE and WebSocket) configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveFeedConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_live_bind_address")]
+    pub bind_address: String,
+    #[serde(default = "default_live_port")]
+    pub port: u16,
+    /// Consumer group the feed reads the messages stream with; use one per instance
+    #[serde(default = "default_live_consumer_group")]
+    pub consumer_group: String,
+    /// Open connections allowed at once
+    #[serde(default = "default_live_max_connections")]
+    pub max_connections: usize,
+    /// Events a connection may fall behind by before it catches up from the stream
+    #[serde(default = "default_live_buffer_size")]
+    pub buffer_size: usize,
+    /// Values allowed per filter parameter
+    #[serde(default = "default_live_max_filter_values")]
+    pub max_filter_values: usize,
+    /// Events a connection may replay from the stream, resuming or catching up, before it is closed
+    #[serde(default = "default_live_max_replay")]
+    pub max_replay: usize,
+    /// Interval between keep-alive comments and pings
+    #[serde(default = "default_live_keepalive_secs")]
+    pub keepalive_secs: u64,
+}
+
+impl Default for LiveFeedConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: default_live_bind_address(),
+            port: default_live_port(),
+            consumer_group: default_live_consumer_group(),
+            max_connections: default_live_max_connections(),
+            buffer_size: default_live_buffer_size(),
+            max_filter_values: default_live_max_filter_values(),
+            max_replay: default_live_max_replay(),
+            keepalive_secs: default_live_keepalive_secs(),
+        }
+    }
+}
+
+fn default_live_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_live_port() -> u16 {
+    8083
+}
+
+fn default_live_consumer_group() -> String {
+    "live".to_string()
+}
+
+fn default_live_max_connections() -> usize {
+    1000
+}
+
+fn default_live_buffer_size() -> usize {
+    256
+}
+
+fn default_live_max_filter_values() -> usize {
+    100
+}
+
+fn default_live_max_replay() -> usize {
+    10000
+}
+
+fn default_live_keepalive_secs() -> u64 {
+    15
+}
+
+/// Hub-compatible gRPC read server configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrpcConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_grpc_bind_address")]
+    pub bind_address: String,
+    #[serde(default = "default_grpc_port")]
+    pub port: u16,
+    /// Page size when a request doesn't set `page_size`
+    #[serde(default = "default_grpc_page_size")]
+    pub default_page_size: usize,
+    /// Upper bound on `page_size`
+    #[serde(default = "default_grpc_max_page_size")]
+    pub max_page_size: usize,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: default_grpc_bind_address(),
+            port: default_grpc_port(),
+            default_page_size: default_grpc_page_size(),
+            max_page_size: default_grpc_max_page_size(),
+        }
+    }
+}
+
+fn default_grpc_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_grpc_port() -> u16 {
+    8084
+}
+
+fn default_grpc_page_size() -> usize {
+    100
+}
+
+fn default_grpc_max_page_size() -> usize {
+    1000
+}
+
+/// Change outbox configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxConfig {
+    /// Record each change the consumer applies in `change_outbox`
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long changes are kept; 0 keeps them forever
+    #[serde(default = "default_outbox_retention_hours")]
+    pub retention_hours: u64,
+    /// How often expired changes are deleted
+    #[serde(default = "default_outbox_cleanup_interval_secs")]
+    pub cleanup_interval_secs: u64,
+    /// Rows deleted per statement during cleanup
+    #[serde(default = "default_outbox_cleanup_batch_size")]
+    pub cleanup_batch_size: usize,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_hours: default_outbox_retention_hours(),
+            cleanup_interval_secs: default_outbox_cleanup_interval_secs(),
+            cleanup_batch_size: default_outbox_cleanup_batch_size(),
+        }
+    }
+}
+
+fn default_outbox_retention_hours() -> u64 {
+    168
+}
+
+fn default_outbox_cleanup_interval_secs() -> u64 {
+    3600
+}
+
+fn default_outbox_cleanup_batch_size() -> usize {
+    10000
+}
+
+/// Anti-entropy configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AntiEntropyConfig {
+    /// Keep sampling FIDs and reconcile the ones that drifted from the Hub
+    #[serde(default)]
+    pub enabled: bool,
+    /// FIDs checked per minute
+    #[serde(default = "default_anti_entropy_fids_per_minute")]
+    pub fids_per_minute: u32,
+    /// Share of checks spent on recently active FIDs, from 0 to 1
+    #[serde(default = "default_anti_entropy_hot_share")]
+    pub hot_share: f64,
+    /// Most active FIDs queued as hot on each refresh
+    #[serde(default = "default_anti_entropy_hot_fids")]
+    pub hot_fids: usize,
+    /// How far back activity counts towards a FID being hot
+    #[serde(default = "default_anti_entropy_hot_window_hours")]
+    pub hot_window_hours: u64,
+    /// How often the hot FIDs and the highest FID are reloaded
+    #[serde(default = "default_anti_entropy_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+}
+
+impl Default for AntiEntropyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            fids_per_minute: default_anti_entropy_fids_per_minute(),
+            hot_share: default_anti_entropy_hot_share(),
+            hot_fids: default_anti_entropy_hot_fids(),
+            hot_window_hours: default_anti_entropy_hot_window_hours(),
+            refresh_interval_secs: default_anti_entropy_refresh_interval_secs(),
+        }
+    }
+}
+
+fn default_anti_entropy_fids_per_minute() -> u32 {
+    60
+}
+
+fn default_anti_entropy_hot_share() -> f64 {
+    0.25
+}
+
+fn default_anti_entropy_hot_fids() -> usize {
+    1000
+}
+
+fn default_anti_entropy_hot_window_hours() -> u64 {
+    24
+}
+
+fn default_anti_entropy_refresh_interval_secs() -> u64 {
+    3600
+}
+
 /// Default value for clear_db - default to false for safety
 fn default_clear_db() -> bool {
     false
@@ -554,6 +952,20 @@ pub struct Config {
     #[serde(default)]
     pub mcp: McpConfig,
     #[serde(default)]
+    pub rest: RestConfig,
+    #[serde(default)]
+    pub graphql: GraphqlConfig,
+    #[serde(default)]
+    pub webhooks: WebhookConfig,
+    #[serde(default)]
+    pub live: LiveFeedConfig,
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+    #[serde(default)]
+    pub outbox: OutboxConfig,
+    #[serde(default)]
+    pub anti_entropy: AntiEntropyConfig,
+    #[serde(default)]
     pub eth: EthConfig,
     #[serde(default)]
     pub stream: StreamProcessorConfig,
@@ -589,6 +1001,10 @@ fn default_skip_migrations() -> bool {
     false // Run migrations by default
 }
 
+fn default_root_parent_cache_size() -> usize {
+    crate::database::root_parent::DEFAULT_CACHE_SIZE
+}
+
 impl Default for DatabaseConfig {
     fn default() -> Self {
         Self {
@@ -598,10 +1014,18 @@ impl Default for DatabaseConfig {
             store_messages: default_store_messages(),
             batch_size: default_db_batch_size(),
             skip_migrations: default_skip_migrations(),
+            root_parent_cache_size: default_root_parent_cache_size(),
         }
     }
 }
 
+impl DatabaseConfig {
+    /// Whether `url` selects the SQLite storage backend
+    pub fn is_sqlite(&self) -> bool {
+        self.url.starts_with("sqlite:")
+    }
+}
+
 impl Default for RedisConfig {
     fn default() -> Self {
         Self {
@@ -1,7 +1,12 @@
 //! Data access abstractions and context
-use crate::core::types::{Fid, Message, MessageId, MessageType};
+use crate::core::types::{
+    AppSignerStats, Channel, ChannelActivity, Fid, FidRegistration, Message, MessageId,
+    MessageType, Signer,
+};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tracing::warn;
 
 /// Error type for data access operations
 #[derive(Error, Debug)]
@@ -51,6 +56,155 @@ pub trait Database: Send + Sync {
 
     /// Delete a message
     async fn delete_message(&self, id: &MessageId, message_type: MessageType) -> Result<()>;
+
+    /// Get the current registry state for a FID
+    async fn get_fid_registration(&self, fid: Fid) -> Result<Option<FidRegistration>>;
+
+    /// Get the FID currently held by a custody address
+    async fn get_fid_by_custody_address(&self, address: &[u8]) -> Result<Option<FidRegistration>>;
+
+    /// Get app keys for a FID, optionally including removed ones
+    async fn get_signers_by_fid(&self, fid: Fid, include_removed: bool) -> Result<Vec<Signer>>;
+
+    /// Get signer and message totals per app (request FID) for a FID
+    async fn get_app_signer_stats(&self, fid: Fid) -> Result<Vec<AppSignerStats>>;
+
+    /// Look up a channel by URL or channel ID
+    async fn get_channel(&self, channel: &str) -> Result<Option<Channel>>;
+
+    /// Get daily cast totals for a channel over the last `days` days, newest first
+    async fn get_channel_activity(&self, url: &str, days: u32) -> Result<Vec<ChannelActivity>>;
+
+    // Hub message reads, mirroring `HubClient` so `DataContext` can serve them from either source
+
+    /// Get user data by FID
+    async fn get_user_data_by_fid(&self, fid: Fid, limit: usize) -> Result<Vec<Message>>;
+
+    /// Get specific user data type
+    async fn get_user_data(&self, fid: Fid, data_type: &str) -> Result<Option<Message>>;
+
+    /// Get username proofs
+    async fn get_username_proofs_by_fid(&self, fid: Fid) -> Result<Vec<Message>>;
+
+    /// Get username proof by name
+    async fn get_username_proof_by_name(
+        &self,
+        name: &str,
+    ) -> Result<Option<crate::proto::UserNameProof>>;
+
+    /// Get verifications
+    async fn get_verifications_by_fid(&self, fid: Fid, limit: usize) -> Result<Vec<Message>>;
+
+    /// Get a specific verification by FID and address
+    async fn get_verification(&self, fid: Fid, address: &[u8]) -> Result<Option<Message>>;
+
+    /// Get casts by FID
+    async fn get_casts_by_fid(&self, fid: Fid, limit: usize) -> Result<Vec<Message>>;
+
+    /// Get a specific cast by ID
+    async fn get_cast(&self, fid: Fid, hash: &[u8]) -> Result<Option<Message>>;
+
+    /// Get casts mentioning an FID
+    async fn get_casts_by_mention(&self, fid: Fid, limit: usize) -> Result<Vec<Message>>;
+
+    /// Get casts by parent
+    async fn get_casts_by_parent(
+        &self,
+        parent_fid: Fid,
+        parent_hash: &[u8],
+        limit: usize,
+    ) -> Result<Vec<Message>>;
+
+    /// Get casts by parent URL
+    async fn get_casts_by_parent_url(&self, parent_url: &str, limit: usize)
+    -> Result<Vec<Message>>;
+
+    /// Get all casts by FID with timestamp filtering
+    async fn get_all_casts_by_fid(
+        &self,
+        fid: Fid,
+        limit: usize,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> Result<Vec<Message>>;
+
+    /// Get a specific reaction by params
+    async fn get_reaction(
+        &self,
+        fid: Fid,
+        reaction_type: u8,
+        target_cast_fid: Option<Fid>,
+        target_cast_hash: Option<&[u8]>,
+        target_url: Option<&str>,
+    ) -> Result<Option<Message>>;
+
+    /// Get reactions by FID
+    async fn get_reactions_by_fid(
+        &self,
+        fid: Fid,
+        reaction_type: Option<u8>,
+        limit: usize,
+    ) -> Result<Vec<Message>>;
+
+    /// Get reactions by target (cast or URL)
+    async fn get_reactions_by_target(
+        &self,
+        target_cast_fid: Option<Fid>,
+        target_cast_hash: Option<&[u8]>,
+        target_url: Option<&str>,
+        reaction_type: Option<u8>,
+        limit: usize,
+    ) -> Result<Vec<Message>>;
+
+    /// Get all reactions by FID with timestamp filtering
+    async fn get_all_reactions_by_fid(
+        &self,
+        fid: Fid,
+        limit: usize,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> Result<Vec<Message>>;
+
+    /// Get all verification messages by FID with timestamp filtering
+    async fn get_all_verification_messages_by_fid(
+        &self,
+        fid: Fid,
+        limit: usize,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> Result<Vec<Message>>;
+
+    /// Get a specific link by params
+    async fn get_link(&self, fid: Fid, link_type: &str, target_fid: Fid)
+    -> Result<Option<Message>>;
+
+    /// Get links by FID
+    async fn get_links_by_fid(
+        &self,
+        fid: Fid,
+        link_type: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Message>>;
+
+    /// Get links by target
+    async fn get_links_by_target(
+        &self,
+        target_fid: Fid,
+        link_type: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Message>>;
+
+    /// Get link compact state messages by FID
+    async fn get_link_compact_state_by_fid(&self, fid: Fid) -> Result<Vec<Message>>;
+
+    /// Get all links by FID with timestamp filtering
+    async fn get_all_links_by_fid(
+        &self,
+        fid: Fid,
+        limit: usize,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> Result<Vec<Message>>;
 }
 
 /// Generic trait for hub operations
@@ -184,6 +338,57 @@ pub trait HubClient: Send + Sync {
         start_time: Option<u64>,
         end_time: Option<u64>,
     ) -> Result<Vec<Message>>;
+
+    /// Get the latest IdRegistry event for a FID
+    async fn get_id_registry_event(&self, fid: Fid) -> Result<Option<crate::proto::OnChainEvent>>;
+
+    /// Get the latest IdRegistry event whose recipient is the given address
+    async fn get_id_registry_event_by_address(
+        &self,
+        address: &[u8],
+    ) -> Result<Option<crate::proto::OnChainEvent>>;
+
+    /// Get the KeyRegistry add events for a FID's currently active signers
+    async fn get_onchain_signers_by_fid(&self, fid: Fid)
+    -> Result<Vec<crate::proto::OnChainEvent>>;
+}
+
+/// Which source `DataContext` reads Hub messages from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReadPolicy {
+    /// Read from the database, falling back to the Hub when it has nothing or fails
+    #[default]
+    DbFirst,
+    /// Read from the database only
+    DbOnly,
+    /// Read from the Hub only
+    HubOnly,
+}
+
+impl std::str::FromStr for ReadPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "db-first" => Ok(Self::DbFirst),
+            "db-only" => Ok(Self::DbOnly),
+            "hub-only" => Ok(Self::HubOnly),
+            _ => {
+                Err(format!("Invalid read policy '{}': expected db-first, db-only or hub-only", s))
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for ReadPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::DbFirst => "db-first",
+            Self::DbOnly => "db-only",
+            Self::HubOnly => "hub-only",
+        })
+    }
 }
 
 /// Generic data access context
@@ -191,6 +396,7 @@ pub trait HubClient: Send + Sync {
 pub struct DataContext<DB, HC> {
     database: Option<DB>,
     hub_client: Option<HC>,
+    read_policy: ReadPolicy,
 }
 
 impl<DB, HC> DataContext<DB, HC>
@@ -198,38 +404,86 @@ where
     DB: Database,
     HC: HubClient,
 {
-    /// Get user data by FID, using Hub if available, falling back to database
-    pub async fn get_user_data_by_fid(&self, fid: Fid, limit: usize) -> Result<Vec<Message>> {
-        // Try hub client first if available
-        if let Some(hub) = &self.hub_client {
-            match hub.get_user_data_by_fid(fid, limit).await {
-                Ok(data) if !data.is_empty() => return Ok(data),
-                _ => {},
+    /// The policy used for reads both sources can answer
+    pub fn read_policy(&self) -> ReadPolicy {
+        self.read_policy
+    }
+
+    /// Read from the sources allowed by the read policy.
+    ///
+    /// With both sources in play, the Hub is asked when the database result
+    /// fails `found` or the database query errors.
+    async fn read<T>(
+        &self,
+        found: impl Fn(&T) -> bool,
+        from_db: impl AsyncFnOnce(&DB) -> Result<T>,
+        from_hub: impl AsyncFnOnce(&HC) -> Result<T>,
+    ) -> Result<T> {
+        let database = self.database.as_ref().filter(|_| self.read_policy != ReadPolicy::HubOnly);
+        let hub = self.hub_client.as_ref().filter(|_| self.read_policy != ReadPolicy::DbOnly);
+
+        if let Some(db) = database {
+            match from_db(db).await {
+                Ok(value) if hub.is_none() || found(&value) => return Ok(value),
+                Ok(_) => {},
+                Err(e) if hub.is_none() => return Err(e),
+                Err(e) => warn!("Database read failed, falling back to Hub: {}", e),
             }
         }
 
-        Err(DataAccessError::Other("No data source available".to_string()))
+        if let Some(hub) = hub {
+            return from_hub(hub).await;
+        }
+
+        Err(DataAccessError::Other(format!(
+            "No data source available for read policy '{}'",
+            self.read_policy
+        )))
     }
 
-    /// Get specific user data, with Hub priority
-    pub async fn get_user_data(&self, fid: Fid, data_type: &str) -> Result<Option<Message>> {
-        if let Some(hub) = &self.hub_client {
-            // Fall through to database on error
-            if let Ok(data) = hub.get_user_data(fid, data_type).await {
-                return Ok(data);
-            }
-        }
+    /// Read a list, treating an empty database result as a miss
+    async fn read_many<T>(
+        &self,
+        from_db: impl AsyncFnOnce(&DB) -> Result<Vec<T>>,
+        from_hub: impl AsyncFnOnce(&HC) -> Result<Vec<T>>,
+    ) -> Result<Vec<T>> {
+        self.read(|items: &Vec<T>| !items.is_empty(), from_db, from_hub).await
+    }
+
+    /// Read a single value, treating `None` from the database as a miss
+    async fn read_one<T>(
+        &self,
+        from_db: impl AsyncFnOnce(&DB) -> Result<Option<T>>,
+        from_hub: impl AsyncFnOnce(&HC) -> Result<Option<T>>,
+    ) -> Result<Option<T>> {
+        self.read(Option::is_some, from_db, from_hub).await
+    }
+
+    /// Get user data by FID
+    pub async fn get_user_data_by_fid(&self, fid: Fid, limit: usize) -> Result<Vec<Message>> {
+        self.read_many(
+            async |db: &DB| db.get_user_data_by_fid(fid, limit).await,
+            async |hub: &HC| hub.get_user_data_by_fid(fid, limit).await,
+        )
+        .await
+    }
 
-        Err(DataAccessError::Other("No data source available".to_string()))
+    /// Get specific user data
+    pub async fn get_user_data(&self, fid: Fid, data_type: &str) -> Result<Option<Message>> {
+        self.read_one(
+            async |db: &DB| db.get_user_data(fid, data_type).await,
+            async |hub: &HC| hub.get_user_data(fid, data_type).await,
+        )
+        .await
     }
 
     /// Get username proofs
     pub async fn get_username_proofs_by_fid(&self, fid: Fid) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_username_proofs_by_fid(fid).await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_many(
+            async |db: &DB| db.get_username_proofs_by_fid(fid).await,
+            async |hub: &HC| hub.get_username_proofs_by_fid(fid).await,
+        )
+        .await
     }
 
     /// Get username proof by name
@@ -237,11 +491,11 @@ where
         &self,
         username: &str,
     ) -> Result<Option<crate::proto::UserNameProof>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_username_proof_by_name(username).await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_one(
+            async |db: &DB| db.get_username_proof_by_name(username).await,
+            async |hub: &HC| hub.get_username_proof_by_name(username).await,
+        )
+        .await
     }
 
     /// Get FID by username
@@ -252,55 +506,47 @@ where
 
     /// Get verifications
     pub async fn get_verifications_by_fid(&self, fid: Fid, limit: usize) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_verifications_by_fid(fid, limit).await;
-        }
-
-        if let Some(db) = &self.database {
-            return db.get_messages_by_fid(fid, MessageType::Verification, limit, None).await;
-        }
-
-        Err(DataAccessError::Other("No data source available".to_string()))
+        self.read_many(
+            async |db: &DB| db.get_verifications_by_fid(fid, limit).await,
+            async |hub: &HC| hub.get_verifications_by_fid(fid, limit).await,
+        )
+        .await
     }
 
     /// Get a specific verification by FID and address
     pub async fn get_verification(&self, fid: Fid, address: &[u8]) -> Result<Option<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_verification(fid, address).await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_one(
+            async |db: &DB| db.get_verification(fid, address).await,
+            async |hub: &HC| hub.get_verification(fid, address).await,
+        )
+        .await
     }
 
     /// Get casts by FID
     pub async fn get_casts_by_fid(&self, fid: Fid, limit: usize) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_casts_by_fid(fid, limit).await;
-        }
-
-        if let Some(db) = &self.database {
-            return db.get_messages_by_fid(fid, MessageType::Cast, limit, None).await;
-        }
-
-        Err(DataAccessError::Other("No data source available".to_string()))
+        self.read_many(
+            async |db: &DB| db.get_casts_by_fid(fid, limit).await,
+            async |hub: &HC| hub.get_casts_by_fid(fid, limit).await,
+        )
+        .await
     }
 
     /// Get a specific cast by ID
     pub async fn get_cast(&self, fid: Fid, hash: &[u8]) -> Result<Option<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_cast(fid, hash).await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_one(
+            async |db: &DB| db.get_cast(fid, hash).await,
+            async |hub: &HC| hub.get_cast(fid, hash).await,
+        )
+        .await
     }
 
     /// Get casts mentioning an FID
     pub async fn get_casts_by_mention(&self, fid: Fid, limit: usize) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_casts_by_mention(fid, limit).await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_many(
+            async |db: &DB| db.get_casts_by_mention(fid, limit).await,
+            async |hub: &HC| hub.get_casts_by_mention(fid, limit).await,
+        )
+        .await
     }
 
     /// Get casts by parent
@@ -310,11 +556,11 @@ where
         parent_hash: &[u8],
         limit: usize,
     ) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_casts_by_parent(parent_fid, parent_hash, limit).await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_many(
+            async |db: &DB| db.get_casts_by_parent(parent_fid, parent_hash, limit).await,
+            async |hub: &HC| hub.get_casts_by_parent(parent_fid, parent_hash, limit).await,
+        )
+        .await
     }
 
     /// Get casts by parent URL
@@ -323,11 +569,11 @@ where
         parent_url: &str,
         limit: usize,
     ) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_casts_by_parent_url(parent_url, limit).await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_many(
+            async |db: &DB| db.get_casts_by_parent_url(parent_url, limit).await,
+            async |hub: &HC| hub.get_casts_by_parent_url(parent_url, limit).await,
+        )
+        .await
     }
 
     /// Get all casts by FID with timestamp filtering
@@ -338,11 +584,11 @@ where
         start_time: Option<u64>,
         end_time: Option<u64>,
     ) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_all_casts_by_fid(fid, limit, start_time, end_time).await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_many(
+            async |db: &DB| db.get_all_casts_by_fid(fid, limit, start_time, end_time).await,
+            async |hub: &HC| hub.get_all_casts_by_fid(fid, limit, start_time, end_time).await,
+        )
+        .await
     }
 
     /// Get a specific reaction
@@ -354,13 +600,17 @@ where
         target_cast_hash: Option<&[u8]>,
         target_url: Option<&str>,
     ) -> Result<Option<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub
-                .get_reaction(fid, reaction_type, target_cast_fid, target_cast_hash, target_url)
-                .await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_one(
+            async |db: &DB| {
+                db.get_reaction(fid, reaction_type, target_cast_fid, target_cast_hash, target_url)
+                    .await
+            },
+            async |hub: &HC| {
+                hub.get_reaction(fid, reaction_type, target_cast_fid, target_cast_hash, target_url)
+                    .await
+            },
+        )
+        .await
     }
 
     /// Get reactions by FID
@@ -370,15 +620,11 @@ where
         reaction_type: Option<u8>,
         limit: usize,
     ) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_reactions_by_fid(fid, reaction_type, limit).await;
-        }
-
-        if let Some(db) = &self.database {
-            return db.get_messages_by_fid(fid, MessageType::Reaction, limit, None).await;
-        }
-
-        Err(DataAccessError::Other("No data source available".to_string()))
+        self.read_many(
+            async |db: &DB| db.get_reactions_by_fid(fid, reaction_type, limit).await,
+            async |hub: &HC| hub.get_reactions_by_fid(fid, reaction_type, limit).await,
+        )
+        .await
     }
 
     /// Get reactions by target
@@ -390,19 +636,29 @@ where
         reaction_type: Option<u8>,
         limit: usize,
     ) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub
-                .get_reactions_by_target(
+        self.read_many(
+            async |db: &DB| {
+                db.get_reactions_by_target(
                     target_cast_fid,
                     target_cast_hash,
                     target_url,
                     reaction_type,
                     limit,
                 )
-                .await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+                .await
+            },
+            async |hub: &HC| {
+                hub.get_reactions_by_target(
+                    target_cast_fid,
+                    target_cast_hash,
+                    target_url,
+                    reaction_type,
+                    limit,
+                )
+                .await
+            },
+        )
+        .await
     }
 
     /// Get all reactions by FID with timestamp filtering
@@ -413,11 +669,11 @@ where
         start_time: Option<u64>,
         end_time: Option<u64>,
     ) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_all_reactions_by_fid(fid, limit, start_time, end_time).await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_many(
+            async |db: &DB| db.get_all_reactions_by_fid(fid, limit, start_time, end_time).await,
+            async |hub: &HC| hub.get_all_reactions_by_fid(fid, limit, start_time, end_time).await,
+        )
+        .await
     }
 
     /// Get all verification messages by FID with timestamp filtering
@@ -428,13 +684,15 @@ where
         start_time: Option<u64>,
         end_time: Option<u64>,
     ) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub
-                .get_all_verification_messages_by_fid(fid, limit, start_time, end_time)
-                .await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_many(
+            async |db: &DB| {
+                db.get_all_verification_messages_by_fid(fid, limit, start_time, end_time).await
+            },
+            async |hub: &HC| {
+                hub.get_all_verification_messages_by_fid(fid, limit, start_time, end_time).await
+            },
+        )
+        .await
     }
 
     /// Get a specific link
@@ -444,11 +702,11 @@ where
         link_type: &str,
         target_fid: Fid,
     ) -> Result<Option<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_link(fid, link_type, target_fid).await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_one(
+            async |db: &DB| db.get_link(fid, link_type, target_fid).await,
+            async |hub: &HC| hub.get_link(fid, link_type, target_fid).await,
+        )
+        .await
     }
 
     /// Get links by FID
@@ -458,15 +716,11 @@ where
         link_type: Option<&str>,
         limit: usize,
     ) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_links_by_fid(fid, link_type, limit).await;
-        }
-
-        if let Some(db) = &self.database {
-            return db.get_messages_by_fid(fid, MessageType::Link, limit, None).await;
-        }
-
-        Err(DataAccessError::Other("No data source available".to_string()))
+        self.read_many(
+            async |db: &DB| db.get_links_by_fid(fid, link_type, limit).await,
+            async |hub: &HC| hub.get_links_by_fid(fid, link_type, limit).await,
+        )
+        .await
     }
 
     /// Get links by target
@@ -476,20 +730,20 @@ where
         link_type: Option<&str>,
         limit: usize,
     ) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_links_by_target(target_fid, link_type, limit).await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_many(
+            async |db: &DB| db.get_links_by_target(target_fid, link_type, limit).await,
+            async |hub: &HC| hub.get_links_by_target(target_fid, link_type, limit).await,
+        )
+        .await
     }
 
     /// Get link compact state messages by FID
     pub async fn get_link_compact_state_by_fid(&self, fid: Fid) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_link_compact_state_by_fid(fid).await;
-        }
-
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        self.read_many(
+            async |db: &DB| db.get_link_compact_state_by_fid(fid).await,
+            async |hub: &HC| hub.get_link_compact_state_by_fid(fid).await,
+        )
+        .await
     }
 
     /// Get all links by FID with timestamp filtering
@@ -500,11 +754,106 @@ where
         start_time: Option<u64>,
         end_time: Option<u64>,
     ) -> Result<Vec<Message>> {
-        if let Some(hub) = &self.hub_client {
-            return hub.get_all_links_by_fid(fid, limit, start_time, end_time).await;
+        self.read_many(
+            async |db: &DB| db.get_all_links_by_fid(fid, limit, start_time, end_time).await,
+            async |hub: &HC| hub.get_all_links_by_fid(fid, limit, start_time, end_time).await,
+        )
+        .await
+    }
+
+    /// Get the current registry state for a FID.
+    ///
+    /// The derived `fids` table is preferred; the Hub only knows the latest
+    /// IdRegister event, so its answer is partial.
+    pub async fn get_fid_registration(&self, fid: Fid) -> Result<Option<FidRegistration>> {
+        self.read_one(
+            async |db: &DB| db.get_fid_registration(fid).await,
+            async |hub: &HC| {
+                let event = hub.get_id_registry_event(fid).await?;
+                Ok(event.as_ref().and_then(FidRegistration::from_id_register_event))
+            },
+        )
+        .await
+    }
+
+    /// Get the FID currently held by a custody address
+    pub async fn get_fid_by_custody_address(
+        &self,
+        address: &[u8],
+    ) -> Result<Option<FidRegistration>> {
+        self.read_one(
+            async |db: &DB| db.get_fid_by_custody_address(address).await,
+            async |hub: &HC| {
+                let event = hub.get_id_registry_event_by_address(address).await?;
+                Ok(event.as_ref().and_then(FidRegistration::from_id_register_event))
+            },
+        )
+        .await
+    }
+
+    /// Get app keys for a FID.
+    ///
+    /// The `signers` table is preferred; the Hub only reports active keys, so
+    /// `include_removed` has no effect on the Hub fallback.
+    pub async fn get_signers_by_fid(&self, fid: Fid, include_removed: bool) -> Result<Vec<Signer>> {
+        self.read_many(
+            async |db: &DB| db.get_signers_by_fid(fid, include_removed).await,
+            async |hub: &HC| {
+                let events = hub.get_onchain_signers_by_fid(fid).await?;
+                Ok(events.iter().filter_map(Signer::from_signer_event).collect())
+            },
+        )
+        .await
+    }
+
+    /// Get signer and message totals per app for a FID (database only)
+    pub async fn get_app_signer_stats(&self, fid: Fid) -> Result<Vec<AppSignerStats>> {
+        if let Some(db) = &self.database {
+            return db.get_app_signer_stats(fid).await;
         }
 
-        Err(DataAccessError::Other("Hub client not available".to_string()))
+        Err(DataAccessError::Other("Database not available".to_string()))
+    }
+
+    /// Look up a channel by URL or channel ID (database only)
+    pub async fn get_channel(&self, channel: &str) -> Result<Option<Channel>> {
+        if let Some(db) = &self.database {
+            return db.get_channel(channel).await;
+        }
+
+        Err(DataAccessError::Other("Database not available".to_string()))
+    }
+
+    /// Get the newest top-level casts in a channel.
+    ///
+    /// `channel` may be a URL or, when the database has imported metadata, a
+    /// channel ID.
+    pub async fn get_channel_feed(&self, channel: &str, limit: usize) -> Result<Vec<Message>> {
+        let mut url = channel.to_string();
+
+        if let Some(db) = &self.database
+            && let Some(found) = db.get_channel(channel).await?
+        {
+            url = found.url;
+        }
+
+        self.get_casts_by_parent_url(&url, limit).await
+    }
+
+    /// Get a channel's totals and daily activity (database only)
+    pub async fn get_channel_stats(
+        &self,
+        channel: &str,
+        days: u32,
+    ) -> Result<Option<(Channel, Vec<ChannelActivity>)>> {
+        let db = self.database()?;
+
+        let Some(found) = db.get_channel(channel).await? else {
+            return Ok(None);
+        };
+        let activity = db.get_channel_activity(&found.url, days).await?;
+
+        Ok(Some((found, activity)))
     }
 
     /// Generic database operation
@@ -526,11 +875,12 @@ where
 pub struct DataContextBuilder<DB, HC> {
     database: Option<DB>,
     hub_client: Option<HC>,
+    read_policy: ReadPolicy,
 }
 
 impl<DB, HC> Default for DataContextBuilder<DB, HC> {
     fn default() -> Self {
-        Self { database: None, hub_client: None }
+        Self { database: None, hub_client: None, read_policy: ReadPolicy::default() }
     }
 }
 
@@ -552,8 +902,18 @@ impl<DB, HC> DataContextBuilder<DB, HC> {
         self
     }
 
+    /// Set the read policy (defaults to database first)
+    pub fn with_read_policy(mut self, read_policy: ReadPolicy) -> Self {
+        self.read_policy = read_policy;
+        self
+    }
+
     /// Build the context
     pub fn build(self) -> DataContext<DB, HC> {
-        DataContext { database: self.database, hub_client: self.hub_client }
+        DataContext {
+            database: self.database,
+            hub_client: self.hub_client,
+            read_policy: self.read_policy,
+        }
     }
 }
@@ -8,4 +8,7 @@ pub mod util;
 pub use data_context::{
     DataAccessError, DataContext, DataContextBuilder, Database, HubClient, Result,
 };
-pub use types::{FARCASTER_EPOCH, Fid, Message, MessageId, MessageType};
+pub use types::{
+    AppSignerStats, Channel, ChannelActivity, FARCASTER_EPOCH, Fid, FidRegistration, Message,
+    MessageId, MessageType, Signer, SignerStatus,
+};
@@ -53,4 +53,17 @@ impl NormalizedEmbed {
             None => panic!("Invalid embed: no url or cast_id"),
         }
     }
+
+    pub fn to_protobuf_embed(&self) -> crate::proto::Embed {
+        let embed = match &self.data {
+            EmbedData::Url { url } => crate::proto::embed::Embed::Url(url.clone()),
+            EmbedData::CastId { cast_id } => {
+                crate::proto::embed::Embed::CastId(crate::proto::CastId {
+                    fid: cast_id.fid,
+                    hash: cast_id.hash.data.clone(),
+                })
+            },
+        };
+        crate::proto::Embed { embed: Some(embed) }
+    }
 }
@@ -118,6 +118,10 @@ pub enum MessageType {
     OnchainTierPurchase,
     /// Lend storage messages
     LendStorage,
+    /// Frame action messages
+    FrameAction,
+    /// Link compact state messages
+    LinkCompactState,
     /// Raw messages stream (all message types combined for external consumption)
     Messages,
 }
@@ -138,6 +142,8 @@ impl MessageType {
             Self::OnchainStorageRent => "onchain:storage_rent",
             Self::OnchainTierPurchase => "onchain:tier_purchase",
             Self::LendStorage => "lend_storage",
+            Self::FrameAction => "frame_actions",
+            Self::LinkCompactState => "link_compact_states",
             Self::Messages => "messages",
         }
     }
@@ -157,6 +163,8 @@ impl MessageType {
             | Self::OnchainStorageRent
             | Self::OnchainTierPurchase => "onchain",
             Self::LendStorage => "lend_storage",
+            Self::FrameAction => "frame_actions",
+            Self::LinkCompactState => "link_compact_states",
             Self::Messages => "messages",
         }
     }
@@ -176,6 +184,8 @@ impl MessageType {
             Self::OnchainStorageRent,
             Self::OnchainTierPurchase,
             Self::LendStorage,
+            Self::FrameAction,
+            Self::LinkCompactState,
             Self::Messages,
         ]
         .into_iter()
@@ -200,6 +210,8 @@ impl fmt::Display for MessageType {
                 Self::OnchainStorageRent => "onchain_storage_rent",
                 Self::OnchainTierPurchase => "onchain_tier_purchase",
                 Self::LendStorage => "lend_storage",
+                Self::FrameAction => "frame_action",
+                Self::LinkCompactState => "link_compact_state",
                 Self::Messages => "messages",
             }
         )
@@ -224,6 +236,186 @@ impl Message {
     }
 }
 
+/// Current IdRegistry state for a FID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FidRegistration {
+    /// The registered FID
+    pub fid: Fid,
+    /// Current custody address
+    pub custody_address: Option<Vec<u8>>,
+    /// Current recovery address
+    pub recovery_address: Option<Vec<u8>>,
+    /// Registration time (unix seconds)
+    pub registered_at: Option<u64>,
+    /// Time of the most recent custody transfer (unix seconds)
+    pub last_transfer_at: Option<u64>,
+    /// Number of custody transfers seen
+    pub transfer_count: u32,
+    /// Chain the registry events came from
+    pub chain_id: u64,
+}
+
+impl FidRegistration {
+    /// Build a partial registration from a single IdRegister onchain event.
+    ///
+    /// Only the fields carried by that one event are populated; the `fids`
+    /// table is the source of truth for the full history.
+    pub fn from_id_register_event(event: &crate::proto::OnChainEvent) -> Option<Self> {
+        use crate::proto::{IdRegisterEventType, on_chain_event::Body};
+
+        let Some(Body::IdRegisterEventBody(body)) = &event.body else {
+            return None;
+        };
+        let non_empty = |bytes: &[u8]| (!bytes.is_empty()).then(|| bytes.to_vec());
+        let timestamp = Some(event.block_timestamp);
+
+        let mut registration = Self {
+            fid: Fid::new(event.fid),
+            custody_address: None,
+            recovery_address: None,
+            registered_at: None,
+            last_transfer_at: None,
+            transfer_count: 0,
+            chain_id: event.chain_id as u64,
+        };
+
+        match IdRegisterEventType::try_from(body.event_type) {
+            Ok(IdRegisterEventType::Register) => {
+                registration.custody_address = non_empty(&body.to);
+                registration.recovery_address = non_empty(&body.recovery_address);
+                registration.registered_at = timestamp;
+            },
+            Ok(IdRegisterEventType::Transfer) => {
+                registration.custody_address = non_empty(&body.to);
+                registration.last_transfer_at = timestamp;
+                registration.transfer_count = 1;
+            },
+            Ok(IdRegisterEventType::ChangeRecovery) => {
+                registration.recovery_address = non_empty(&body.recovery_address);
+            },
+            _ => return None,
+        }
+
+        Some(registration)
+    }
+}
+
+/// Lifecycle state of an app key in the KeyRegistry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerStatus {
+    /// Key was added and has not been removed
+    Active,
+    /// Key was removed by the user
+    Removed,
+    /// Key was reset by the KeyRegistry admin
+    AdminReset,
+}
+
+/// An app key (signer) registered for a FID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signer {
+    /// The FID that owns the key
+    pub fid: Fid,
+    /// The public key
+    pub key: Vec<u8>,
+    /// Key type (1 = ed25519)
+    pub key_type: u32,
+    /// FID of the app that requested the key, decoded from SignedKeyRequest metadata
+    pub request_fid: Option<u64>,
+    /// Current status
+    pub status: SignerStatus,
+    /// Time the key was added (unix seconds)
+    pub added_at: Option<u64>,
+    /// Time the key was removed or reset (unix seconds)
+    pub removed_at: Option<u64>,
+    /// Time the key was carried over by the signer migration (unix seconds)
+    pub migrated_at: Option<u64>,
+}
+
+impl Signer {
+    /// Build a signer from a single KeyRegistry onchain event.
+    ///
+    /// Used when only the Hub is available; the `signers` table tracks the
+    /// full add/remove history.
+    pub fn from_signer_event(event: &crate::proto::OnChainEvent) -> Option<Self> {
+        use crate::proto::{SignerEventType, on_chain_event::Body};
+
+        let Some(Body::SignerEventBody(body)) = &event.body else {
+            return None;
+        };
+        let timestamp = Some(event.block_timestamp);
+
+        let (status, added_at, removed_at) = match SignerEventType::try_from(body.event_type) {
+            Ok(SignerEventType::Add) => (SignerStatus::Active, timestamp, None),
+            Ok(SignerEventType::Remove) => (SignerStatus::Removed, None, timestamp),
+            Ok(SignerEventType::AdminReset) => (SignerStatus::AdminReset, None, timestamp),
+            _ => return None,
+        };
+
+        Some(Self {
+            fid: Fid::new(event.fid),
+            key: body.key.clone(),
+            key_type: body.key_type,
+            request_fid: (body.metadata_type == 1)
+                .then(|| crate::core::util::decode_signed_key_request_metadata(&body.metadata))
+                .flatten()
+                .map(|m| m.request_fid),
+            status,
+            added_at,
+            removed_at,
+            migrated_at: None,
+        })
+    }
+}
+
+/// Signer and message totals for one app (request FID) on a user's account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppSignerStats {
+    /// FID of the app; `None` for keys without decodable request metadata
+    pub request_fid: Option<u64>,
+    /// Keys ever added by this app
+    pub signer_count: u64,
+    /// Keys from this app that are still active
+    pub active_signer_count: u64,
+    /// Stored messages signed by this app's keys
+    pub message_count: u64,
+}
+
+/// A channel, keyed by the URL casts use as their root parent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Channel {
+    /// Channel URL (`casts.root_parent_url`)
+    pub url: String,
+    /// Short channel ID, when metadata has been imported
+    pub channel_id: Option<String>,
+    /// Display name, when metadata has been imported
+    pub name: Option<String>,
+    /// Description, when metadata has been imported
+    pub description: Option<String>,
+    /// Channel lead, when metadata has been imported
+    pub lead_fid: Option<u64>,
+    /// First cast seen in the channel (unix seconds)
+    pub first_cast_at: Option<u64>,
+    /// Most recent cast seen in the channel (unix seconds)
+    pub last_cast_at: Option<u64>,
+    /// Casts seen in the channel, including replies
+    pub cast_count: u64,
+    /// Replies seen in the channel
+    pub reply_count: u64,
+}
+
+/// Casts in a channel on one UTC day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelActivity {
+    /// Day as `YYYY-MM-DD`
+    pub day: String,
+    /// Casts on that day, including replies
+    pub cast_count: u64,
+    /// Replies on that day
+    pub reply_count: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -379,6 +571,8 @@ mod tests {
             assert_eq!(MessageType::OnchainStorageRent.to_stream_key(), "onchain:storage_rent");
             assert_eq!(MessageType::OnchainTierPurchase.to_stream_key(), "onchain:tier_purchase");
             assert_eq!(MessageType::LendStorage.to_stream_key(), "lend_storage");
+            assert_eq!(MessageType::FrameAction.to_stream_key(), "frame_actions");
+            assert_eq!(MessageType::LinkCompactState.to_stream_key(), "link_compact_states");
             assert_eq!(MessageType::Messages.to_stream_key(), "messages");
         }
 
@@ -397,6 +591,8 @@ mod tests {
             assert_eq!(MessageType::OnchainStorageRent.to_group_suffix(), "onchain");
             assert_eq!(MessageType::OnchainTierPurchase.to_group_suffix(), "onchain");
             assert_eq!(MessageType::LendStorage.to_group_suffix(), "lend_storage");
+            assert_eq!(MessageType::FrameAction.to_group_suffix(), "frame_actions");
+            assert_eq!(MessageType::LinkCompactState.to_group_suffix(), "link_compact_states");
             assert_eq!(MessageType::Messages.to_group_suffix(), "messages");
         }
 
@@ -404,7 +600,7 @@ mod tests {
         fn test_all_returns_all_types() {
             let all_types: Vec<_> = MessageType::all().collect();
 
-            assert_eq!(all_types.len(), 13);
+            assert_eq!(all_types.len(), 15);
             assert!(all_types.contains(&MessageType::Cast));
             assert!(all_types.contains(&MessageType::Reaction));
             assert!(all_types.contains(&MessageType::Link));
@@ -417,6 +613,8 @@ mod tests {
             assert!(all_types.contains(&MessageType::OnchainStorageRent));
             assert!(all_types.contains(&MessageType::OnchainTierPurchase));
             assert!(all_types.contains(&MessageType::LendStorage));
+            assert!(all_types.contains(&MessageType::FrameAction));
+            assert!(all_types.contains(&MessageType::LinkCompactState));
             assert!(all_types.contains(&MessageType::Messages));
         }
 
@@ -437,6 +635,8 @@ mod tests {
             assert_eq!(format!("{}", MessageType::OnchainStorageRent), "onchain_storage_rent");
             assert_eq!(format!("{}", MessageType::OnchainTierPurchase), "onchain_tier_purchase");
             assert_eq!(format!("{}", MessageType::LendStorage), "lend_storage");
+            assert_eq!(format!("{}", MessageType::FrameAction), "frame_action");
+            assert_eq!(format!("{}", MessageType::LinkCompactState), "link_compact_state");
             assert_eq!(format!("{}", MessageType::Messages), "messages");
         }
 
@@ -508,6 +708,63 @@ mod tests {
         }
     }
 
+    mod fid_registration_tests {
+        use super::*;
+        use crate::proto::{
+            IdRegisterEventBody, IdRegisterEventType, OnChainEvent, on_chain_event,
+        };
+
+        fn id_register_event(event_type: IdRegisterEventType) -> OnChainEvent {
+            OnChainEvent {
+                fid: 42,
+                chain_id: 10,
+                block_timestamp: 1_700_000_000,
+                body: Some(on_chain_event::Body::IdRegisterEventBody(IdRegisterEventBody {
+                    to: vec![0xaa; 20],
+                    event_type: event_type as i32,
+                    from: vec![0xbb; 20],
+                    recovery_address: vec![0xcc; 20],
+                })),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn test_from_register_event() {
+            let registration = FidRegistration::from_id_register_event(&id_register_event(
+                IdRegisterEventType::Register,
+            ))
+            .unwrap();
+
+            assert_eq!(registration.fid, Fid::new(42));
+            assert_eq!(registration.custody_address, Some(vec![0xaa; 20]));
+            assert_eq!(registration.recovery_address, Some(vec![0xcc; 20]));
+            assert_eq!(registration.registered_at, Some(1_700_000_000));
+            assert_eq!(registration.transfer_count, 0);
+            assert_eq!(registration.chain_id, 10);
+        }
+
+        #[test]
+        fn test_from_transfer_event() {
+            let registration = FidRegistration::from_id_register_event(&id_register_event(
+                IdRegisterEventType::Transfer,
+            ))
+            .unwrap();
+
+            assert_eq!(registration.custody_address, Some(vec![0xaa; 20]));
+            assert_eq!(registration.recovery_address, None);
+            assert_eq!(registration.registered_at, None);
+            assert_eq!(registration.last_transfer_at, Some(1_700_000_000));
+            assert_eq!(registration.transfer_count, 1);
+        }
+
+        #[test]
+        fn test_from_non_id_register_event() {
+            let event = OnChainEvent { fid: 42, ..Default::default() };
+            assert!(FidRegistration::from_id_register_event(&event).is_none());
+        }
+    }
+
     mod farcaster_epoch_tests {
         use super::*;
 
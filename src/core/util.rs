@@ -101,6 +101,36 @@ pub fn sanitize_json_for_postgres(value: serde_json::Value) -> serde_json::Value
     }
 }
 
+/// Decoded `SignedKeyRequestMetadata` attached to KeyRegistry `Add` events.
+///
+/// `request_fid` is the FID of the app that asked the user to add the key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedKeyRequestMetadata {
+    pub request_fid: u64,
+    pub request_signer: Vec<u8>,
+    pub deadline: u64,
+}
+
+/// Decode ABI-encoded `SignedKeyRequestMetadata` (metadata_type = 1).
+///
+/// The metadata is `abi.encode((uint256 requestFid, address requestSigner, bytes signature,
+/// uint256 deadline))`, so the head starts with an offset to the tuple. Returns `None` for
+/// malformed input or values that do not fit in a u64.
+pub fn decode_signed_key_request_metadata(metadata: &[u8]) -> Option<SignedKeyRequestMetadata> {
+    let word = |offset: usize| metadata.get(offset..offset.checked_add(32)?);
+    let word_u64 = |offset: usize| {
+        let w = word(offset)?;
+        w[..24].iter().all(|b| *b == 0).then(|| u64::from_be_bytes(w[24..].try_into().unwrap()))
+    };
+
+    let base = usize::try_from(word_u64(0)?).ok()?;
+    let request_fid = word_u64(base)?;
+    let request_signer = word(base.checked_add(32)?)?[12..].to_vec();
+    let deadline = word_u64(base.checked_add(96)?)?;
+
+    Some(SignedKeyRequestMetadata { request_fid, request_signer, deadline })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -293,4 +323,45 @@ mod tests {
             })
         );
     }
+
+    fn abi_word(value: u64) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        word[24..].copy_from_slice(&value.to_be_bytes());
+        word
+    }
+
+    #[test]
+    fn test_decode_signed_key_request_metadata() {
+        let signer = [0x11u8; 20];
+        let signature = [0x22u8; 65];
+
+        let mut metadata = Vec::new();
+        metadata.extend_from_slice(&abi_word(0x20)); // offset to tuple
+        metadata.extend_from_slice(&abi_word(9152)); // requestFid
+        let mut signer_word = [0u8; 32];
+        signer_word[12..].copy_from_slice(&signer);
+        metadata.extend_from_slice(&signer_word); // requestSigner
+        metadata.extend_from_slice(&abi_word(0x80)); // offset to signature
+        metadata.extend_from_slice(&abi_word(1_700_000_000)); // deadline
+        metadata.extend_from_slice(&abi_word(signature.len() as u64));
+        metadata.extend_from_slice(&signature);
+        metadata.resize(metadata.len().next_multiple_of(32), 0);
+
+        let decoded = decode_signed_key_request_metadata(&metadata).unwrap();
+        assert_eq!(decoded.request_fid, 9152);
+        assert_eq!(decoded.request_signer, signer.to_vec());
+        assert_eq!(decoded.deadline, 1_700_000_000);
+    }
+
+    #[test]
+    fn test_decode_signed_key_request_metadata_malformed() {
+        assert!(decode_signed_key_request_metadata(&[]).is_none());
+        assert!(decode_signed_key_request_metadata(&[0u8; 40]).is_none());
+
+        // requestFid that overflows u64
+        let mut metadata = abi_word(0x20).to_vec();
+        metadata.extend_from_slice(&[0xff; 32]);
+        metadata.extend_from_slice(&[0u8; 96]);
+        assert!(decode_signed_key_request_metadata(&metadata).is_none());
+    }
 }
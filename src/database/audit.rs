@@ -0,0 +1,170 @@
+//! Read-only queries for auditing a FID against the Hub.
+//!
+//! Only live rows (`deleted_at IS NULL`) are read; anything removed in
+//! Postgres is treated as absent.
+
+use sqlx::PgPool;
+
+#[derive(Debug, Clone)]
+pub struct LiveReaction {
+    pub hash: Vec<u8>,
+    pub reaction_type: i16,
+    pub target_cast_hash: Option<Vec<u8>>,
+    pub target_url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LiveLink {
+    pub hash: Vec<u8>,
+    pub link_type: String,
+    pub target_fid: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LiveVerification {
+    pub hash: Vec<u8>,
+    pub address: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LiveUserData {
+    pub hash: Vec<u8>,
+    pub data_type: i16,
+}
+
+/// Live rows belonging to one FID
+#[derive(Debug, Clone, Default)]
+pub struct LiveRows {
+    pub casts: Vec<Vec<u8>>,
+    pub reactions: Vec<LiveReaction>,
+    pub links: Vec<LiveLink>,
+    pub verifications: Vec<LiveVerification>,
+    pub user_data: Vec<LiveUserData>,
+    pub lend_storage: Vec<Vec<u8>>,
+    pub onchain_events: Vec<Vec<u8>>,
+}
+
+/// Load every live row for a FID
+pub async fn live_rows(pool: &PgPool, fid: i64) -> Result<LiveRows, sqlx::Error> {
+    let casts =
+        sqlx::query_scalar!("SELECT hash FROM casts WHERE fid = $1 AND deleted_at IS NULL", fid)
+            .fetch_all(pool)
+            .await?;
+
+    let reactions = sqlx::query_as!(
+        LiveReaction,
+        r#"
+        SELECT hash, type AS reaction_type, target_cast_hash, target_url
+        FROM reactions
+        WHERE fid = $1 AND deleted_at IS NULL
+        "#,
+        fid
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let links = sqlx::query_as!(
+        LiveLink,
+        r#"
+        SELECT hash, type AS link_type, target_fid
+        FROM links
+        WHERE fid = $1 AND deleted_at IS NULL
+        "#,
+        fid
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let verifications = sqlx::query_as!(
+        LiveVerification,
+        r#"
+        SELECT hash, signer_address AS address
+        FROM verifications
+        WHERE fid = $1 AND deleted_at IS NULL
+        "#,
+        fid
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let user_data = sqlx::query_as!(
+        LiveUserData,
+        r#"
+        SELECT hash, type AS data_type
+        FROM user_data
+        WHERE fid = $1 AND deleted_at IS NULL
+        "#,
+        fid
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let lend_storage = sqlx::query_scalar!(
+        "SELECT hash FROM lend_storage WHERE fid = $1 AND deleted_at IS NULL",
+        fid
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let onchain_events = sqlx::query_scalar!(
+        "SELECT hash FROM onchain_events WHERE fid = $1 AND deleted_at IS NULL",
+        fid
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(LiveRows { casts, reactions, links, verifications, user_data, lend_storage, onchain_events })
+}
+
+/// Pick `count` random registered FIDs from the `fids` registry
+pub async fn sample_fids(pool: &PgPool, count: i64) -> Result<Vec<i64>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT fid FROM fids WHERE deleted_at IS NULL ORDER BY random() LIMIT $1",
+        count
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Rows per message table for a FID, removed ones included
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MessageCounts {
+    pub casts: i64,
+    pub reactions: i64,
+    pub links: i64,
+    pub verifications: i64,
+    pub user_data: i64,
+    pub username_proofs: i64,
+    pub lend_storage: i64,
+}
+
+/// Count a FID's rows in each message table.
+///
+/// Removes tombstone or insert a row per CRDT slot, so these track the number of
+/// messages the Hub holds for the FID. Pruned messages stay, so Postgres can be ahead.
+/// Links materialized from a compact state aren't messages of their own, so the compact
+/// state is counted in their place.
+pub async fn message_counts(pool: &PgPool, fid: i64) -> Result<MessageCounts, sqlx::Error> {
+    sqlx::query_as!(
+        MessageCounts,
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM casts WHERE fid = $1) AS "casts!",
+            (SELECT COUNT(*) FROM reactions WHERE fid = $1) AS "reactions!",
+            (SELECT COUNT(*) FROM links WHERE fid = $1 AND compact_state_hash IS NULL)
+                + (SELECT COUNT(*) FROM link_compact_states WHERE fid = $1) AS "links!",
+            (SELECT COUNT(*) FROM verifications WHERE fid = $1) AS "verifications!",
+            (SELECT COUNT(*) FROM user_data WHERE fid = $1) AS "user_data!",
+            (SELECT COUNT(*) FROM username_proofs WHERE fid = $1) AS "username_proofs!",
+            (SELECT COUNT(*) FROM lend_storage WHERE fid = $1) AS "lend_storage!"
+        "#,
+        fid
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// Highest FID in the `fids` registry, if any
+pub async fn max_fid(pool: &PgPool) -> Result<Option<i64>, sqlx::Error> {
+    sqlx::query_scalar!("SELECT MAX(fid) FROM fids WHERE deleted_at IS NULL").fetch_one(pool).await
+}
@@ -3,20 +3,24 @@ use crate::{
         normalize::NormalizedEmbed,
         util::{sanitize_json_for_postgres, sanitize_string_for_postgres},
     },
-    database::models::Fid,
+    database::{
+        cast_references::{CastReferences, upsert_cast_references},
+        channels::{ChannelCast, record_channel_casts},
+        models::Fid,
+    },
     proto::{
         Message,
         cast_add_body::Parent,
         link_body::Target as LinkTarget,
         message_data::Body::{
-            CastAddBody, LinkBody, ReactionBody, UserDataBody, UsernameProofBody,
-            VerificationAddAddressBody,
+            CastAddBody, FrameActionBody, LinkBody, LinkCompactStateBody, ReactionBody,
+            UserDataBody, UsernameProofBody, VerificationAddAddressBody,
         },
         reaction_body::Target as ReactionTarget,
     },
 };
 use serde_json::Value;
-use sqlx::{postgres::PgPool, types::time::OffsetDateTime};
+use sqlx::{Connection, PgConnection, Row, types::time::OffsetDateTime};
 use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use tracing::{debug, error, trace};
@@ -330,16 +334,120 @@ impl<'a> UsernameProofInsert<'a> {
     }
 }
 
+/// Structure for holding frame action data for bulk inserts
+#[derive(Debug)]
+pub struct FrameActionInsert<'a> {
+    pub fid: Fid,
+    pub hash: &'a [u8],
+    pub url: String,
+    pub button_index: i16,
+    pub cast_fid: Option<i64>,
+    pub cast_hash: Option<&'a [u8]>,
+    pub input_text: Option<String>,
+    pub state: Option<&'a [u8]>,
+    pub transaction_id: Option<&'a [u8]>,
+    pub address: Option<&'a [u8]>,
+    pub timestamp: OffsetDateTime,
+}
+
+impl<'a> FrameActionInsert<'a> {
+    pub fn from_message(msg: &'a Message) -> Option<Self> {
+        let data = msg.data.as_ref()?;
+        let frame_body = match &data.body {
+            Some(FrameActionBody(body)) => body,
+            _ => return None,
+        };
+
+        let non_empty = |bytes: &'a [u8]| if bytes.is_empty() { None } else { Some(bytes) };
+
+        // URL and input text are raw bytes in the proto, sanitize for text columns
+        let url =
+            sanitize_string_for_postgres(&String::from_utf8_lossy(&frame_body.url)).into_owned();
+        let input_text = if frame_body.input_text.is_empty() {
+            None
+        } else {
+            Some(
+                sanitize_string_for_postgres(&String::from_utf8_lossy(&frame_body.input_text))
+                    .into_owned(),
+            )
+        };
+
+        Some(Self {
+            fid: data.fid,
+            hash: &msg.hash,
+            url,
+            button_index: frame_body.button_index as i16,
+            cast_fid: frame_body.cast_id.as_ref().map(|c| c.fid as i64),
+            cast_hash: frame_body.cast_id.as_ref().map(|c| c.hash.as_slice()),
+            input_text,
+            state: non_empty(&frame_body.state),
+            transaction_id: non_empty(&frame_body.transaction_id),
+            address: non_empty(&frame_body.address),
+            timestamp: convert_timestamp(data.timestamp),
+        })
+    }
+}
+
+/// Structure for holding link compact state data
+///
+/// A compact state is the full set of targets for a (fid, link type) pair as of its
+/// timestamp, so it is applied per pair rather than bulk inserted.
+#[derive(Debug)]
+pub struct LinkCompactStateInsert<'a> {
+    pub fid: Fid,
+    pub hash: &'a [u8],
+    pub link_type: &'a str,
+    pub target_fids: Vec<i64>,
+    pub timestamp: OffsetDateTime,
+}
+
+impl<'a> LinkCompactStateInsert<'a> {
+    pub fn from_message(msg: &'a Message) -> Option<Self> {
+        let data = msg.data.as_ref()?;
+        let compact_body = match &data.body {
+            Some(LinkCompactStateBody(body)) => body,
+            _ => return None,
+        };
+
+        let mut target_fids: Vec<i64> =
+            compact_body.target_fids.iter().map(|&fid| fid as i64).collect();
+        target_fids.sort_unstable();
+        target_fids.dedup();
+
+        Some(Self {
+            fid: data.fid,
+            hash: &msg.hash,
+            link_type: &compact_body.r#type,
+            target_fids,
+            timestamp: convert_timestamp(data.timestamp),
+        })
+    }
+
+    /// Deterministic hash for a link row materialized from this compact state.
+    /// Derived from the compact state hash and target so reapplying is idempotent.
+    pub fn materialized_link_hash(&self, target_fid: i64) -> Vec<u8> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(self.hash);
+        hasher.update(&target_fid.to_be_bytes());
+        hasher.finalize().as_bytes()[..20].to_vec()
+    }
+}
+
+/// Message types [`BatchInserter::process_message_batch`] applies to their tables
+pub const BATCHED_MESSAGE_TYPES: [i32; 8] = [1, 3, 5, 7, 11, 12, 13, 14];
+
 /// Batch inserter for Farcaster message data
 pub struct BatchInserter<'a> {
-    pool: &'a PgPool,
+    conn: &'a mut PgConnection,
     batch_size: usize,
 }
 
 impl<'a> BatchInserter<'a> {
-    /// Create a new batch inserter with the given pool and batch size
-    pub fn new(pool: &'a PgPool, batch_size: usize) -> Self {
-        Self { pool, batch_size }
+    /// Create a new batch inserter on an open transaction, with the given batch size.
+    ///
+    /// Nothing is committed; the caller commits the transaction when done.
+    pub fn new(conn: &'a mut PgConnection, batch_size: usize) -> Self {
+        Self { conn, batch_size }
     }
 
     /// Set the batch size for this inserter
@@ -363,7 +471,7 @@ impl<'a> BatchInserter<'a> {
 
     /// Bulk insert messages into the messages table
     pub async fn bulk_insert_messages(
-        &self,
+        &mut self,
         messages: &[Message],
     ) -> Result<usize, Box<dyn Error + Send + Sync>> {
         if messages.is_empty() {
@@ -387,6 +495,7 @@ impl<'a> BatchInserter<'a> {
                     "signer",
                     "body",
                     "raw",
+                    "signature",
                     "deleted_at",
                     "pruned_at",
                     "revoked_at",
@@ -402,7 +511,7 @@ impl<'a> BatchInserter<'a> {
             for msg in chunk {
                 if let Some(data) = &msg.data {
                     let ts = convert_timestamp(data.timestamp);
-                    let raw_data = msg.data_bytes.as_deref().unwrap_or_default();
+                    let raw_data = crate::database::store::raw_data_bytes(msg);
                     // Sanitize null bytes from JSON - PostgreSQL jsonb rejects \u0000
                     let body_json = serde_json::to_value(data)
                         .map(sanitize_json_for_postgres)
@@ -418,13 +527,14 @@ impl<'a> BatchInserter<'a> {
                         .bind(&msg.signer)
                         .bind(body_json)
                         .bind(raw_data)
+                        .bind(&msg.signature)
                         .bind::<Option<OffsetDateTime>>(None) // deleted_at
                         .bind::<Option<OffsetDateTime>>(None) // pruned_at
                         .bind::<Option<OffsetDateTime>>(None); // revoked_at
                 }
             }
 
-            let result = query.execute(self.pool).await?;
+            let result = query.execute(&mut *self.conn).await?;
             total_inserted += result.rows_affected() as usize;
         }
 
@@ -434,7 +544,7 @@ impl<'a> BatchInserter<'a> {
     /// Bulk insert casts
     /// Note: Root parent fields in CastInsert should be resolved before calling this.
     pub async fn bulk_insert_casts(
-        &self,
+        &mut self,
         casts: Vec<CastInsert<'_>>,
     ) -> Result<usize, Box<dyn Error + Send + Sync>> {
         if casts.is_empty() {
@@ -477,6 +587,8 @@ impl<'a> BatchInserter<'a> {
                     "mentions_positions = EXCLUDED.mentions_positions",
                 ],
             );
+            // xmax = 0 only for freshly inserted rows, so channel counters skip replays
+            let sql = format!("{}\nRETURNING hash, (xmax = 0) AS inserted", sql);
 
             // Pre-sanitize all text fields - PostgreSQL text columns reject \x00
             let sanitized: Vec<_> = chunk
@@ -512,8 +624,43 @@ impl<'a> BatchInserter<'a> {
                     .bind(&cast.mentions_positions);
             }
 
-            let result = query.execute(self.pool).await?;
-            total_inserted += result.rows_affected() as usize;
+            let rows = query.fetch_all(&mut *self.conn).await?;
+            total_inserted += rows.len();
+
+            let inserted: HashSet<Vec<u8>> = rows
+                .iter()
+                .filter(|row| row.get::<bool, _>("inserted"))
+                .map(|row| row.get::<Vec<u8>, _>("hash"))
+                .collect();
+            let channel_casts: Vec<ChannelCast> = chunk
+                .iter()
+                .zip(sanitized.iter())
+                .filter(|(cast, _)| inserted.contains(cast.hash))
+                .filter_map(|(cast, (_, _, root_parent_url))| {
+                    Some(ChannelCast {
+                        url: root_parent_url.clone()?,
+                        timestamp: cast.timestamp,
+                        is_reply: cast.parent_hash.is_some(),
+                    })
+                })
+                .collect();
+            record_channel_casts(&mut *self.conn, &channel_casts).await?;
+
+            let references: Vec<CastReferences> = chunk
+                .iter()
+                .map(|cast| {
+                    CastReferences::from_stored(
+                        cast.hash,
+                        cast.fid,
+                        cast.timestamp,
+                        &cast.embeds,
+                        &cast.mentions,
+                        &cast.mentions_positions,
+                    )
+                })
+                .filter(|references| !references.is_empty())
+                .collect();
+            upsert_cast_references(&mut *self.conn, &references).await?;
         }
 
         Ok(total_inserted)
@@ -521,7 +668,7 @@ impl<'a> BatchInserter<'a> {
 
     /// Bulk insert reactions
     pub async fn bulk_insert_reactions(
-        &self,
+        &mut self,
         reactions: Vec<ReactionInsert<'_>>,
     ) -> Result<usize, Box<dyn Error + Send + Sync>> {
         if reactions.is_empty() {
@@ -573,7 +720,7 @@ impl<'a> BatchInserter<'a> {
                     .bind(reaction.timestamp);
             }
 
-            let result = query.execute(self.pool).await?;
+            let result = query.execute(&mut *self.conn).await?;
             total_inserted += result.rows_affected() as usize;
         }
 
@@ -582,7 +729,7 @@ impl<'a> BatchInserter<'a> {
 
     /// Bulk insert links
     pub async fn bulk_insert_links(
-        &self,
+        &mut self,
         links: Vec<LinkInsert<'_>>,
     ) -> Result<usize, Box<dyn Error + Send + Sync>> {
         if links.is_empty() {
@@ -621,7 +768,7 @@ impl<'a> BatchInserter<'a> {
                     .bind(link.display_timestamp);
             }
 
-            let result = query.execute(self.pool).await?;
+            let result = query.execute(&mut *self.conn).await?;
             total_inserted += result.rows_affected() as usize;
         }
 
@@ -630,7 +777,7 @@ impl<'a> BatchInserter<'a> {
 
     /// Bulk insert user data
     pub async fn bulk_insert_user_data(
-        &self,
+        &mut self,
         user_data: Vec<UserDataInsert<'_>>,
     ) -> Result<usize, Box<dyn Error + Send + Sync>> {
         if user_data.is_empty() {
@@ -668,7 +815,7 @@ impl<'a> BatchInserter<'a> {
                     .bind(data.timestamp);
             }
 
-            let result = query.execute(self.pool).await?;
+            let result = query.execute(&mut *self.conn).await?;
             total_inserted += result.rows_affected() as usize;
         }
 
@@ -677,7 +824,7 @@ impl<'a> BatchInserter<'a> {
 
     /// Bulk insert verifications
     pub async fn bulk_insert_verifications(
-        &self,
+        &mut self,
         verifications: Vec<VerificationInsert<'_>>,
     ) -> Result<usize, Box<dyn Error + Send + Sync>> {
         if verifications.is_empty() {
@@ -726,7 +873,7 @@ impl<'a> BatchInserter<'a> {
                     .bind::<Option<OffsetDateTime>>(None); // deleted_at
             }
 
-            let result = query.execute(self.pool).await?;
+            let result = query.execute(&mut *self.conn).await?;
             total_inserted += result.rows_affected() as usize;
         }
 
@@ -735,7 +882,7 @@ impl<'a> BatchInserter<'a> {
 
     /// Bulk insert username proofs
     pub async fn bulk_insert_username_proofs(
-        &self,
+        &mut self,
         proofs: Vec<UsernameProofInsert<'_>>,
     ) -> Result<usize, Box<dyn Error + Send + Sync>> {
         if proofs.is_empty() {
@@ -778,18 +925,195 @@ impl<'a> BatchInserter<'a> {
                     .bind(proof.signature);
             }
 
-            let result = query.execute(self.pool).await?;
+            let result = query.execute(&mut *self.conn).await?;
             total_inserted += result.rows_affected() as usize;
         }
 
         Ok(total_inserted)
     }
 
+    /// Bulk insert frame actions
+    pub async fn bulk_insert_frame_actions(
+        &mut self,
+        frame_actions: Vec<FrameActionInsert<'_>>,
+    ) -> Result<usize, Box<dyn Error + Send + Sync>> {
+        if frame_actions.is_empty() {
+            return Ok(0);
+        }
+
+        let mut total_inserted = 0;
+
+        for chunk in frame_actions.chunks(self.batch_size) {
+            // Frame actions are immutable, so duplicates are simply skipped
+            let sql = build_insert_sql(
+                "frame_actions",
+                &[
+                    "fid",
+                    "hash",
+                    "url",
+                    "button_index",
+                    "cast_fid",
+                    "cast_hash",
+                    "input_text",
+                    "state",
+                    "transaction_id",
+                    "address",
+                    "timestamp",
+                ],
+                chunk.len(),
+                "hash",
+                &[],
+            );
+
+            let mut query = sqlx::query(&sql);
+
+            // Bind all parameters for each frame action
+            for action in chunk {
+                query = query
+                    .bind(action.fid as i64)
+                    .bind(action.hash)
+                    .bind(action.url.as_str())
+                    .bind(action.button_index)
+                    .bind(action.cast_fid)
+                    .bind(action.cast_hash)
+                    .bind(action.input_text.as_deref())
+                    .bind(action.state)
+                    .bind(action.transaction_id)
+                    .bind(action.address)
+                    .bind(action.timestamp);
+            }
+
+            let result = query.execute(&mut *self.conn).await?;
+            total_inserted += result.rows_affected() as usize;
+        }
+
+        Ok(total_inserted)
+    }
+
+    /// Apply a link compact state in a single transaction.
+    ///
+    /// The compact state replaces the individual link rows it summarizes:
+    /// - Only the newest compact state per (fid, type) is kept (higher timestamp wins)
+    /// - Active links of that type whose target is not in the state and that are not
+    ///   newer than the state are soft-deleted
+    /// - Targets in the state without an active link row get one materialized, tagged
+    ///   with `compact_state_hash`
+    ///
+    /// Returns false if a newer compact state was already applied.
+    pub async fn apply_link_compact_state(
+        &mut self,
+        state: &LinkCompactStateInsert<'_>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let link_type = sanitize_string_for_postgres(state.link_type);
+        // A savepoint within the caller's transaction
+        let mut tx = self.conn.begin().await?;
+
+        let applied = sqlx::query(
+            r#"
+            INSERT INTO link_compact_states (fid, type, hash, target_fids, timestamp)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (fid, type) DO UPDATE SET
+                hash = EXCLUDED.hash,
+                target_fids = EXCLUDED.target_fids,
+                timestamp = EXCLUDED.timestamp,
+                deleted_at = NULL
+            WHERE link_compact_states.timestamp <= EXCLUDED.timestamp
+            RETURNING id
+            "#,
+        )
+        .bind(state.fid as i64)
+        .bind(link_type.as_ref())
+        .bind(state.hash)
+        .bind(&state.target_fids)
+        .bind(state.timestamp)
+        .fetch_optional(&mut *tx)
+        .await?
+        .is_some();
+
+        if !applied {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE links
+            SET deleted_at = $4
+            WHERE fid = $1
+              AND type = $2
+              AND deleted_at IS NULL
+              AND timestamp <= $4
+              AND target_fid <> ALL($3)
+            "#,
+        )
+        .bind(state.fid as i64)
+        .bind(link_type.as_ref())
+        .bind(&state.target_fids)
+        .bind(state.timestamp)
+        .execute(&mut *tx)
+        .await?;
+
+        let hashes: Vec<Vec<u8>> =
+            state.target_fids.iter().map(|&t| state.materialized_link_hash(t)).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO links (fid, target_fid, type, hash, timestamp, compact_state_hash)
+            SELECT $1, t.target_fid, $2, t.hash, $5, $6
+            FROM unnest($3::bigint[], $4::bytea[]) AS t(target_fid, hash)
+            WHERE NOT EXISTS (
+                SELECT 1 FROM links l
+                WHERE l.fid = $1
+                  AND l.type = $2
+                  AND l.target_fid = t.target_fid
+                  AND l.deleted_at IS NULL
+            )
+            ON CONFLICT (hash) DO UPDATE SET deleted_at = NULL
+            "#,
+        )
+        .bind(state.fid as i64)
+        .bind(link_type.as_ref())
+        .bind(&state.target_fids)
+        .bind(&hashes)
+        .bind(state.timestamp)
+        .bind(state.hash)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Apply link compact states, keeping only the newest state per (fid, type)
+    pub async fn bulk_apply_link_compact_states(
+        &mut self,
+        states: Vec<LinkCompactStateInsert<'_>>,
+    ) -> Result<usize, Box<dyn Error + Send + Sync>> {
+        let mut latest: HashMap<(Fid, &str), LinkCompactStateInsert<'_>> = HashMap::new();
+        for state in states {
+            match latest.get(&(state.fid, state.link_type)) {
+                Some(existing) if existing.timestamp > state.timestamp => {},
+                _ => {
+                    latest.insert((state.fid, state.link_type), state);
+                },
+            }
+        }
+
+        let mut applied = 0;
+        for state in latest.values() {
+            if self.apply_link_compact_state(state).await? {
+                applied += 1;
+            }
+        }
+
+        Ok(applied)
+    }
+
     /// Resolve root parent fields for a batch of CastInserts using a single DB query.
     /// - URL-parented casts: root_parent_url = parent_url (trivial, no lookup)
     /// - Cast-parented casts: batch query DB for parent's root_parent fields
     /// - Unresolvable: left as None (RootParentBackfill handles these)
-    async fn resolve_root_parents_batch(&self, casts: &mut [CastInsert<'_>]) {
+    async fn resolve_root_parents_batch(&mut self, casts: &mut [CastInsert<'_>]) {
         // 1. Handle URL parents (trivial — root is the URL itself)
         for cast in casts.iter_mut() {
             if let Some(url) = cast.parent_url {
@@ -824,7 +1148,7 @@ impl<'a> BatchInserter<'a> {
             "#,
         )
         .bind(&parent_hashes)
-        .fetch_all(self.pool)
+        .fetch_all(&mut *self.conn)
         .await
         {
             Ok(rows) => rows,
@@ -867,7 +1191,7 @@ impl<'a> BatchInserter<'a> {
 
     /// Process a batch of messages, grouping them by type and inserting in bulk
     pub async fn process_message_batch(
-        &self,
+        &mut self,
         messages: &[Message],
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
         if messages.is_empty() {
@@ -877,10 +1201,22 @@ impl<'a> BatchInserter<'a> {
         // Group messages by type to batch insert similar message types
         let grouped = Self::group_messages_by_type(messages);
 
-        // Insert into the messages table (all types go here regardless of specific type tables)
+        // Insert into the messages table (all types go here regardless of specific type tables).
+        // The savepoint keeps a failure here from aborting the type-specific inserts.
+        sqlx::query("SAVEPOINT bulk_insert_messages").execute(&mut *self.conn).await?;
         match self.bulk_insert_messages(messages).await {
-            Ok(count) => trace!("Bulk inserted {} messages", count),
-            Err(e) => error!("Error bulk inserting messages: {}", e),
+            Ok(count) => {
+                trace!("Bulk inserted {} messages", count);
+                sqlx::query("RELEASE SAVEPOINT bulk_insert_messages")
+                    .execute(&mut *self.conn)
+                    .await?;
+            },
+            Err(e) => {
+                error!("Error bulk inserting messages: {}", e);
+                sqlx::query("ROLLBACK TO SAVEPOINT bulk_insert_messages")
+                    .execute(&mut *self.conn)
+                    .await?;
+            },
         }
 
         // Process each message type in parallel
@@ -890,6 +1226,8 @@ impl<'a> BatchInserter<'a> {
         let mut user_data_inserts = Vec::new();
         let mut verification_inserts = Vec::new();
         let mut username_proof_inserts = Vec::new();
+        let mut frame_action_inserts = Vec::new();
+        let mut link_compact_state_inserts = Vec::new();
 
         // Collect all inserts for each type
         for (msg_type, msgs) in grouped {
@@ -942,6 +1280,22 @@ impl<'a> BatchInserter<'a> {
                         }
                     }
                 },
+                13 => {
+                    // FrameAction
+                    for msg in msgs {
+                        if let Some(action) = FrameActionInsert::from_message(msg) {
+                            frame_action_inserts.push(action);
+                        }
+                    }
+                },
+                14 => {
+                    // LinkCompactState
+                    for msg in msgs {
+                        if let Some(state) = LinkCompactStateInsert::from_message(msg) {
+                            link_compact_state_inserts.push(state);
+                        }
+                    }
+                },
                 // Add other message type handlers as needed
                 _ => {}, // Skip unsupported message types
             }
@@ -995,6 +1349,21 @@ impl<'a> BatchInserter<'a> {
             return Err(e);
         }
 
+        if !frame_action_inserts.is_empty()
+            && let Err(e) = self.bulk_insert_frame_actions(frame_action_inserts).await
+        {
+            error!("Error in bulk insert of frame actions: {}", e);
+            return Err(e);
+        }
+
+        // Compact states go after link inserts so they win over links in the same batch
+        if !link_compact_state_inserts.is_empty()
+            && let Err(e) = self.bulk_apply_link_compact_states(link_compact_state_inserts).await
+        {
+            error!("Error applying link compact states: {}", e);
+            return Err(e);
+        }
+
         // All bulk inserts have been completed
 
         Ok(())
@@ -1040,15 +1409,81 @@ mod tests {
         );
     }
 
+    fn message_with_body(body: crate::proto::message_data::Body, msg_type: i32) -> Message {
+        Message {
+            data: Some(crate::proto::MessageData {
+                r#type: msg_type,
+                fid: 42,
+                timestamp: 157785600,
+                network: 1,
+                body: Some(body),
+            }),
+            hash: vec![0xab; 20],
+            hash_scheme: 1,
+            signature: vec![],
+            signature_scheme: 1,
+            signer: vec![],
+            data_bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_link_compact_state_insert_dedups_targets() {
+        let msg = message_with_body(
+            LinkCompactStateBody(crate::proto::LinkCompactStateBody {
+                r#type: "follow".to_string(),
+                target_fids: vec![7, 3, 7, 1],
+            }),
+            crate::proto::MessageType::LinkCompactState as i32,
+        );
+
+        let state = LinkCompactStateInsert::from_message(&msg).unwrap();
+        assert_eq!(state.fid, 42);
+        assert_eq!(state.link_type, "follow");
+        assert_eq!(state.target_fids, vec![1, 3, 7]);
+
+        // Materialized hashes are deterministic per target and distinct across targets
+        assert_eq!(state.materialized_link_hash(3), state.materialized_link_hash(3));
+        assert_ne!(state.materialized_link_hash(3), state.materialized_link_hash(7));
+        assert_eq!(state.materialized_link_hash(3).len(), 20);
+    }
+
+    #[test]
+    fn test_frame_action_insert_from_message() {
+        let msg = message_with_body(
+            FrameActionBody(crate::proto::FrameActionBody {
+                url: b"https://frame.example/api".to_vec(),
+                button_index: 2,
+                cast_id: Some(crate::proto::CastId { fid: 7, hash: vec![0x01; 20] }),
+                input_text: vec![],
+                state: vec![],
+                transaction_id: vec![],
+                address: vec![0x02; 20],
+            }),
+            crate::proto::MessageType::FrameAction as i32,
+        );
+
+        let action = FrameActionInsert::from_message(&msg).unwrap();
+        assert_eq!(action.url, "https://frame.example/api");
+        assert_eq!(action.button_index, 2);
+        assert_eq!(action.cast_fid, Some(7));
+        assert!(action.input_text.is_none());
+        assert!(action.state.is_none());
+        assert_eq!(action.address, Some(&[0x02u8; 20][..]));
+
+        // Non-frame messages are rejected
+        assert!(LinkCompactStateInsert::from_message(&msg).is_none());
+    }
+
     #[test]
     fn test_batch_and_processor_timestamps_match() {
         // Ensure both convert_timestamp implementations produce identical results
-        use crate::processor::database::DatabaseProcessor;
+        use crate::database::store::PostgresStore;
 
         let farcaster_timestamp: u32 = 157785600;
 
         let batch_result = convert_timestamp(farcaster_timestamp);
-        let processor_result = DatabaseProcessor::convert_timestamp(farcaster_timestamp);
+        let processor_result = PostgresStore::convert_timestamp(farcaster_timestamp);
 
         assert_eq!(
             batch_result.unix_timestamp(),
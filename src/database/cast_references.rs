@@ -0,0 +1,338 @@
+//! Normalized `cast_mentions` and `cast_embeds` rows.
+//!
+//! `casts.mentions`, `casts.mentions_positions` and `casts.embeds` are stored as
+//! JSON arrays, which can't be indexed for "casts that mention FID X" or "casts
+//! embedding URL Y". These tables hold one row per mention / embed instead.
+//! A row's `deleted_at` always mirrors the parent cast, so a removal that
+//! arrives before the add still leaves the references deleted.
+
+use crate::core::{
+    normalize::{EmbedData, NormalizedEmbed},
+    util::sanitize_string_for_postgres,
+};
+use serde_json::Value;
+use sqlx::{PgConnection, PgPool, types::time::OffsetDateTime};
+use tracing::info;
+
+/// Mentions and embeds of a single cast
+#[derive(Debug, Clone)]
+pub struct CastReferences {
+    pub cast_hash: Vec<u8>,
+    pub author_fid: i64,
+    pub timestamp: OffsetDateTime,
+    /// (mentioned FID, byte position in text)
+    pub mentions: Vec<(i64, i32)>,
+    pub embeds: Vec<NormalizedEmbed>,
+}
+
+impl CastReferences {
+    pub fn new(
+        cast_hash: &[u8],
+        author_fid: u64,
+        timestamp: OffsetDateTime,
+        mentions: &[u64],
+        mentions_positions: &[u32],
+        embeds: Vec<NormalizedEmbed>,
+    ) -> Self {
+        Self {
+            cast_hash: cast_hash.to_vec(),
+            author_fid: author_fid as i64,
+            timestamp,
+            mentions: mentions
+                .iter()
+                .zip(mentions_positions)
+                .map(|(&fid, &position)| (fid as i64, position as i32))
+                .collect(),
+            embeds,
+        }
+    }
+
+    /// Build from the JSON columns of a stored `casts` row.
+    ///
+    /// Columns that don't parse are treated as empty.
+    pub fn from_stored(
+        cast_hash: &[u8],
+        author_fid: u64,
+        timestamp: OffsetDateTime,
+        embeds: &Value,
+        mentions: &Value,
+        mentions_positions: &Value,
+    ) -> Self {
+        let mentions: Vec<u64> = serde_json::from_value(mentions.clone()).unwrap_or_default();
+        let positions: Vec<u32> =
+            serde_json::from_value(mentions_positions.clone()).unwrap_or_default();
+        let embeds: Vec<NormalizedEmbed> =
+            serde_json::from_value(embeds.clone()).unwrap_or_default();
+
+        Self::new(cast_hash, author_fid, timestamp, &mentions, &positions, embeds)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mentions.is_empty() && self.embeds.is_empty()
+    }
+}
+
+/// Insert the mention and embed rows for the given casts.
+///
+/// Rows already present are left in place apart from `deleted_at`, which is
+/// copied from the parent cast. Returns the number of rows written.
+pub async fn upsert_cast_references(
+    conn: &mut PgConnection,
+    casts: &[CastReferences],
+) -> Result<u64, sqlx::Error> {
+    let mut mention_cast_hashes = Vec::new();
+    let mut mention_author_fids = Vec::new();
+    let mut mention_timestamps = Vec::new();
+    let mut mention_fids = Vec::new();
+    let mut mention_positions = Vec::new();
+
+    let mut embed_cast_hashes = Vec::new();
+    let mut embed_author_fids = Vec::new();
+    let mut embed_timestamps = Vec::new();
+    let mut embed_positions = Vec::new();
+    let mut embed_kinds = Vec::new();
+    let mut embed_urls = Vec::new();
+    let mut embed_cast_fids = Vec::new();
+    let mut embed_cast_hash_refs = Vec::new();
+
+    for cast in casts {
+        for &(fid, position) in &cast.mentions {
+            mention_cast_hashes.push(cast.cast_hash.clone());
+            mention_author_fids.push(cast.author_fid);
+            mention_timestamps.push(cast.timestamp);
+            mention_fids.push(fid);
+            mention_positions.push(position);
+        }
+
+        for (position, embed) in cast.embeds.iter().enumerate() {
+            embed_cast_hashes.push(cast.cast_hash.clone());
+            embed_author_fids.push(cast.author_fid);
+            embed_timestamps.push(cast.timestamp);
+            embed_positions.push(position as i16);
+            match &embed.data {
+                EmbedData::Url { url } => {
+                    embed_kinds.push("url");
+                    embed_urls.push(Some(sanitize_string_for_postgres(url).into_owned()));
+                    embed_cast_fids.push(None);
+                    embed_cast_hash_refs.push(None);
+                },
+                EmbedData::CastId { cast_id } => {
+                    embed_kinds.push("cast");
+                    embed_urls.push(None);
+                    embed_cast_fids.push(Some(cast_id.fid as i64));
+                    embed_cast_hash_refs.push(Some(cast_id.hash.data.clone()));
+                },
+            }
+        }
+    }
+
+    let mut written = 0;
+
+    if !mention_fids.is_empty() {
+        written += sqlx::query(
+            r#"
+            INSERT INTO cast_mentions (fid, cast_hash, author_fid, position, timestamp, deleted_at)
+            SELECT DISTINCT ON (u.cast_hash, u.fid, u.position)
+                   u.fid, u.cast_hash, u.author_fid, u.position, u.timestamp, c.deleted_at
+            FROM unnest($1::bigint[], $2::bytea[], $3::bigint[], $4::integer[], $5::timestamptz[])
+                AS u(fid, cast_hash, author_fid, position, timestamp)
+            LEFT JOIN casts c ON c.hash = u.cast_hash
+            ON CONFLICT (cast_hash, fid, position) DO UPDATE SET
+                deleted_at = EXCLUDED.deleted_at
+            WHERE cast_mentions.deleted_at IS DISTINCT FROM EXCLUDED.deleted_at
+            "#,
+        )
+        .bind(&mention_fids)
+        .bind(&mention_cast_hashes)
+        .bind(&mention_author_fids)
+        .bind(&mention_positions)
+        .bind(&mention_timestamps)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+    }
+
+    if !embed_kinds.is_empty() {
+        written += sqlx::query(
+            r#"
+            INSERT INTO cast_embeds (
+                cast_hash, author_fid, position, kind, url, cast_fid, cast_hash_ref, timestamp,
+                deleted_at
+            )
+            SELECT DISTINCT ON (u.cast_hash, u.position)
+                   u.cast_hash, u.author_fid, u.position, u.kind, u.url, u.cast_fid,
+                   u.cast_hash_ref, u.timestamp, c.deleted_at
+            FROM unnest(
+                $1::bytea[], $2::bigint[], $3::smallint[], $4::text[], $5::text[], $6::bigint[],
+                $7::bytea[], $8::timestamptz[]
+            ) AS u(cast_hash, author_fid, position, kind, url, cast_fid, cast_hash_ref, timestamp)
+            LEFT JOIN casts c ON c.hash = u.cast_hash
+            ON CONFLICT (cast_hash, position) DO UPDATE SET
+                deleted_at = EXCLUDED.deleted_at
+            WHERE cast_embeds.deleted_at IS DISTINCT FROM EXCLUDED.deleted_at
+            "#,
+        )
+        .bind(&embed_cast_hashes)
+        .bind(&embed_author_fids)
+        .bind(&embed_positions)
+        .bind(&embed_kinds)
+        .bind(&embed_urls)
+        .bind(&embed_cast_fids)
+        .bind(&embed_cast_hash_refs)
+        .bind(&embed_timestamps)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+    }
+
+    Ok(written)
+}
+
+/// Copy `casts.deleted_at` onto the mention and embed rows of the given casts.
+///
+/// Called after a cast removal so the references follow the cast's CRDT state.
+pub async fn sync_cast_references_deleted(
+    conn: &mut PgConnection,
+    cast_hashes: &[Vec<u8>],
+) -> Result<u64, sqlx::Error> {
+    if cast_hashes.is_empty() {
+        return Ok(0);
+    }
+
+    let mentions = sqlx::query!(
+        r#"
+        UPDATE cast_mentions m
+        SET deleted_at = c.deleted_at
+        FROM casts c
+        WHERE c.hash = m.cast_hash
+          AND m.cast_hash = ANY($1)
+          AND m.deleted_at IS DISTINCT FROM c.deleted_at
+        "#,
+        cast_hashes
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let embeds = sqlx::query!(
+        r#"
+        UPDATE cast_embeds e
+        SET deleted_at = c.deleted_at
+        FROM casts c
+        WHERE c.hash = e.cast_hash
+          AND e.cast_hash = ANY($1)
+          AND e.deleted_at IS DISTINCT FROM c.deleted_at
+        "#,
+        cast_hashes
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(mentions.rows_affected() + embeds.rows_affected())
+}
+
+/// Populate `cast_mentions` and `cast_embeds` from every stored cast, `batch_size` casts at a time.
+///
+/// Returns the number of rows written.
+pub async fn rebuild_cast_references(pool: &PgPool, batch_size: usize) -> Result<u64, sqlx::Error> {
+    let mut last_hash: Vec<u8> = Vec::new();
+    let mut scanned: u64 = 0;
+    let mut total: u64 = 0;
+
+    loop {
+        let rows = sqlx::query!(
+            r#"
+            SELECT hash, fid, timestamp, embeds, mentions, mentions_positions
+            FROM casts
+            WHERE hash > $1
+            ORDER BY hash
+            LIMIT $2
+            "#,
+            last_hash,
+            batch_size as i64
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let Some(last) = rows.last() else {
+            break;
+        };
+        last_hash = last.hash.clone();
+        scanned += rows.len() as u64;
+
+        let casts: Vec<CastReferences> = rows
+            .iter()
+            .filter_map(|row| {
+                // Rows without a FID are placeholders for removals that arrived first
+                let fid = row.fid?;
+                let refs = CastReferences::from_stored(
+                    &row.hash,
+                    fid as u64,
+                    row.timestamp,
+                    &row.embeds,
+                    &row.mentions,
+                    &row.mentions_positions,
+                );
+                (!refs.is_empty()).then_some(refs)
+            })
+            .collect();
+
+        total += upsert_cast_references(&mut *pool.acquire().await?, &casts).await?;
+
+        info!("Backfilled cast references for {} casts ({} rows so far)", scanned, total);
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_stored_round_trips_normalized_embeds() {
+        let embeds = vec![
+            NormalizedEmbed::from_protobuf_embed(&crate::proto::Embed {
+                embed: Some(crate::proto::embed::Embed::Url("https://example.com".to_string())),
+            }),
+            NormalizedEmbed::from_protobuf_embed(&crate::proto::Embed {
+                embed: Some(crate::proto::embed::Embed::CastId(crate::proto::CastId {
+                    fid: 3,
+                    hash: vec![0xab; 20],
+                })),
+            }),
+        ];
+        let refs = CastReferences::from_stored(
+            &[1; 20],
+            42,
+            OffsetDateTime::UNIX_EPOCH,
+            &serde_json::to_value(&embeds).unwrap(),
+            &serde_json::json!([2, 3]),
+            &serde_json::json!([0, 10]),
+        );
+
+        assert_eq!(refs.author_fid, 42);
+        assert_eq!(refs.mentions, vec![(2, 0), (3, 10)]);
+        assert_eq!(refs.embeds.len(), 2);
+        assert!(
+            matches!(&refs.embeds[0].data, EmbedData::Url { url } if url == "https://example.com")
+        );
+        assert!(matches!(
+            &refs.embeds[1].data,
+            EmbedData::CastId { cast_id } if cast_id.fid == 3 && cast_id.hash.data == vec![0xab; 20]
+        ));
+    }
+
+    #[test]
+    fn test_from_stored_malformed_columns() {
+        let refs = CastReferences::from_stored(
+            &[1; 20],
+            42,
+            OffsetDateTime::UNIX_EPOCH,
+            &serde_json::json!({"not": "an array"}),
+            &serde_json::json!(null),
+            &serde_json::json!([]),
+        );
+
+        assert!(refs.is_empty());
+    }
+}
@@ -0,0 +1,378 @@
+//! Channel registry derived from `casts.root_parent_url`.
+//!
+//! - `channels` holds first/last activity and cast totals per channel URL.
+//! - `channel_activity` holds the same totals per UTC day.
+//!
+//! Both are counters bumped as new casts are inserted; a cast that was already
+//! stored is never counted twice. Counts include casts that were later removed,
+//! and `rebuild_channels` recomputes them from `casts` under the same rule.
+//! Channel IDs, names and leads aren't on the Hub and come from an optional
+//! metadata import.
+
+use serde::Deserialize;
+use sqlx::{
+    PgConnection, PgPool,
+    types::time::{Date, OffsetDateTime, UtcOffset},
+};
+use std::collections::BTreeMap;
+
+/// A newly stored cast that belongs to a channel
+#[derive(Debug, Clone)]
+pub struct ChannelCast {
+    /// The cast's root parent URL
+    pub url: String,
+    pub timestamp: OffsetDateTime,
+    /// Whether the cast replies to another cast rather than the channel itself
+    pub is_reply: bool,
+}
+
+#[derive(Debug, Default)]
+struct ChannelTotals {
+    first_cast_at: Option<OffsetDateTime>,
+    last_cast_at: Option<OffsetDateTime>,
+    cast_count: i64,
+    reply_count: i64,
+}
+
+impl ChannelTotals {
+    fn add(&mut self, cast: &ChannelCast) {
+        self.first_cast_at =
+            Some(self.first_cast_at.map_or(cast.timestamp, |t| t.min(cast.timestamp)));
+        self.last_cast_at =
+            Some(self.last_cast_at.map_or(cast.timestamp, |t| t.max(cast.timestamp)));
+        self.cast_count += 1;
+        self.reply_count += cast.is_reply as i64;
+    }
+}
+
+/// Add newly stored casts to the channel counters.
+///
+/// Callers must only pass casts that were inserted for the first time.
+pub async fn record_channel_casts(
+    conn: &mut PgConnection,
+    casts: &[ChannelCast],
+) -> Result<(), sqlx::Error> {
+    if casts.is_empty() {
+        return Ok(());
+    }
+
+    // BTreeMaps keep row lock order stable across concurrent writers
+    let mut by_channel: BTreeMap<&str, ChannelTotals> = BTreeMap::new();
+    let mut by_day: BTreeMap<(&str, Date), ChannelTotals> = BTreeMap::new();
+    for cast in casts {
+        by_channel.entry(&cast.url).or_default().add(cast);
+        let day = cast.timestamp.to_offset(UtcOffset::UTC).date();
+        by_day.entry((&cast.url, day)).or_default().add(cast);
+    }
+
+    let urls: Vec<String> = by_channel.keys().map(|url| url.to_string()).collect();
+    let first: Vec<OffsetDateTime> = by_channel.values().filter_map(|t| t.first_cast_at).collect();
+    let last: Vec<OffsetDateTime> = by_channel.values().filter_map(|t| t.last_cast_at).collect();
+    let counts: Vec<i64> = by_channel.values().map(|t| t.cast_count).collect();
+    let replies: Vec<i64> = by_channel.values().map(|t| t.reply_count).collect();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO channels (url, first_cast_at, last_cast_at, cast_count, reply_count)
+        SELECT * FROM unnest($1::text[], $2::timestamptz[], $3::timestamptz[], $4::bigint[], $5::bigint[])
+        ON CONFLICT (url) DO UPDATE SET
+            first_cast_at = LEAST(channels.first_cast_at, EXCLUDED.first_cast_at),
+            last_cast_at = GREATEST(channels.last_cast_at, EXCLUDED.last_cast_at),
+            cast_count = channels.cast_count + EXCLUDED.cast_count,
+            reply_count = channels.reply_count + EXCLUDED.reply_count
+        "#,
+        &urls,
+        &first,
+        &last,
+        &counts,
+        &replies
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let urls: Vec<String> = by_day.keys().map(|(url, _)| url.to_string()).collect();
+    let days: Vec<Date> = by_day.keys().map(|(_, day)| *day).collect();
+    let counts: Vec<i64> = by_day.values().map(|t| t.cast_count).collect();
+    let replies: Vec<i64> = by_day.values().map(|t| t.reply_count).collect();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO channel_activity (url, day, cast_count, reply_count)
+        SELECT * FROM unnest($1::text[], $2::date[], $3::bigint[], $4::bigint[])
+        ON CONFLICT (url, day) DO UPDATE SET
+            cast_count = channel_activity.cast_count + EXCLUDED.cast_count,
+            reply_count = channel_activity.reply_count + EXCLUDED.reply_count
+        "#,
+        &urls,
+        &days,
+        &counts,
+        &replies
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Recompute all channel counters from `casts`, keeping imported metadata.
+///
+/// Returns the number of channels with casts.
+pub async fn rebuild_channels(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!("DELETE FROM channel_activity").execute(&mut *tx).await?;
+
+    // Rows without a FID are placeholders for removals that arrived before the cast
+    sqlx::query!(
+        r#"
+        INSERT INTO channel_activity (url, day, cast_count, reply_count)
+        SELECT root_parent_url,
+               (timestamp AT TIME ZONE 'UTC')::date,
+               count(*),
+               count(*) FILTER (WHERE parent_url IS NULL)
+        FROM casts
+        WHERE root_parent_url IS NOT NULL AND fid IS NOT NULL
+        GROUP BY 1, 2
+        "#
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE channels
+        SET first_cast_at = NULL, last_cast_at = NULL, cast_count = 0, reply_count = 0
+        "#
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO channels (url, first_cast_at, last_cast_at, cast_count, reply_count)
+        SELECT root_parent_url,
+               min(timestamp),
+               max(timestamp),
+               count(*),
+               count(*) FILTER (WHERE parent_url IS NULL)
+        FROM casts
+        WHERE root_parent_url IS NOT NULL AND fid IS NOT NULL
+        GROUP BY 1
+        ON CONFLICT (url) DO UPDATE SET
+            first_cast_at = EXCLUDED.first_cast_at,
+            last_cast_at = EXCLUDED.last_cast_at,
+            cast_count = EXCLUDED.cast_count,
+            reply_count = EXCLUDED.reply_count
+        "#
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Channel metadata from an external directory (e.g. the Warpcast channel list)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ChannelMetadata {
+    #[serde(alias = "id")]
+    pub channel_id: String,
+    pub url: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default, alias = "leadFid")]
+    pub lead_fid: Option<u64>,
+}
+
+/// Parse channel metadata from JSON.
+///
+/// Accepts a bare array, `{"channels": [...]}` or the Warpcast API's
+/// `{"result": {"channels": [...]}}`.
+pub fn parse_channel_metadata_json(input: &str) -> Result<Vec<ChannelMetadata>, String> {
+    let value: serde_json::Value = serde_json::from_str(input).map_err(|e| e.to_string())?;
+
+    let channels =
+        value.pointer("/result/channels").or_else(|| value.get("channels")).unwrap_or(&value);
+
+    serde_json::from_value(channels.clone()).map_err(|e| e.to_string())
+}
+
+/// Parse channel metadata from CSV with a header row.
+///
+/// `id` (or `channel_id`) and `url` columns are required; `name`,
+/// `description` and `lead_fid` (or `leadFid`) are optional.
+pub fn parse_channel_metadata_csv(input: &str) -> Result<Vec<ChannelMetadata>, String> {
+    let mut lines = input.lines().filter(|line| !line.trim().is_empty());
+    let header = split_csv_line(lines.next().ok_or("CSV file is empty")?);
+    let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.trim()));
+
+    let id_col = column(&["id", "channel_id"]).ok_or("CSV is missing an id column")?;
+    let url_col = column(&["url"]).ok_or("CSV is missing a url column")?;
+    let name_col = column(&["name"]);
+    let description_col = column(&["description"]);
+    let lead_col = column(&["lead_fid", "leadFid"]);
+
+    lines
+        .enumerate()
+        .map(|(i, line)| {
+            let fields = split_csv_line(line);
+            let field = |col: Option<usize>| {
+                col.and_then(|c| fields.get(c)).map(|f| f.trim()).filter(|f| !f.is_empty())
+            };
+
+            let channel_id = field(Some(id_col)).ok_or(format!("Row {}: missing id", i + 2))?;
+            let url = field(Some(url_col)).ok_or(format!("Row {}: missing url", i + 2))?;
+            let lead_fid = field(lead_col)
+                .map(|f| f.parse::<u64>().map_err(|_| format!("Row {}: invalid lead_fid", i + 2)))
+                .transpose()?;
+
+            Ok(ChannelMetadata {
+                channel_id: channel_id.to_string(),
+                url: url.to_string(),
+                name: field(name_col).map(str::to_string),
+                description: field(description_col).map(str::to_string),
+                lead_fid,
+            })
+        })
+        .collect()
+}
+
+/// Split one CSV line, honouring double-quoted fields with `""` escapes
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+/// Upsert imported channel metadata.
+///
+/// A channel ID moved to a different URL is cleared from the old row first.
+/// Returns the number of channels written.
+pub async fn import_channel_metadata(
+    pool: &PgPool,
+    channels: &[ChannelMetadata],
+) -> Result<u64, sqlx::Error> {
+    // Last entry wins for duplicate URLs
+    let by_url: BTreeMap<&str, &ChannelMetadata> =
+        channels.iter().map(|c| (c.url.as_str(), c)).collect();
+    if by_url.is_empty() {
+        return Ok(0);
+    }
+
+    let urls: Vec<String> = by_url.keys().map(|url| url.to_string()).collect();
+    let ids: Vec<String> = by_url.values().map(|c| c.channel_id.clone()).collect();
+    let names: Vec<Option<String>> = by_url.values().map(|c| c.name.clone()).collect();
+    let descriptions: Vec<Option<String>> =
+        by_url.values().map(|c| c.description.clone()).collect();
+    let leads: Vec<Option<i64>> = by_url.values().map(|c| c.lead_fid.map(|f| f as i64)).collect();
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE channels
+        SET channel_id = NULL
+        WHERE channel_id = ANY($1) AND NOT (url = ANY($2))
+        "#,
+        &ids,
+        &urls
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO channels (url, channel_id, name, description, lead_fid, metadata_updated_at)
+        SELECT u.*, now()
+        FROM unnest($1::text[], $2::text[], $3::text[], $4::text[], $5::bigint[])
+            AS u(url, channel_id, name, description, lead_fid)
+        ON CONFLICT (url) DO UPDATE SET
+            channel_id = EXCLUDED.channel_id,
+            name = EXCLUDED.name,
+            description = EXCLUDED.description,
+            lead_fid = EXCLUDED.lead_fid,
+            metadata_updated_at = EXCLUDED.metadata_updated_at
+        "#,
+    )
+    .bind(&urls)
+    .bind(&ids)
+    .bind(&names)
+    .bind(&descriptions)
+    .bind(&leads)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_channel_metadata_json_warpcast_shape() {
+        let input = r#"{"result": {"channels": [
+            {"id": "memes", "url": "chain://eip155:1/erc721:0xfd8427165df67df6d7fd689ae67c8ebf56d9ca61",
+             "name": "Memes", "leadFid": 3, "followerCount": 100}
+        ]}}"#;
+
+        let channels = parse_channel_metadata_json(input).unwrap();
+        assert_eq!(channels.len(), 1);
+        assert_eq!(channels[0].channel_id, "memes");
+        assert_eq!(channels[0].name.as_deref(), Some("Memes"));
+        assert_eq!(channels[0].lead_fid, Some(3));
+        assert_eq!(channels[0].description, None);
+    }
+
+    #[test]
+    fn test_parse_channel_metadata_json_array() {
+        let input = r#"[{"channel_id": "dev", "url": "https://warpcast.com/~/channel/dev"}]"#;
+
+        let channels = parse_channel_metadata_json(input).unwrap();
+        assert_eq!(channels[0].channel_id, "dev");
+        assert_eq!(channels[0].lead_fid, None);
+    }
+
+    #[test]
+    fn test_parse_channel_metadata_csv() {
+        let input = "id,name,url,lead_fid,description\n\
+                     dev,Dev,https://warpcast.com/~/channel/dev,2,\"Builders, \"\"devs\"\"\"\n\
+                     \n\
+                     memes,,chain://memes,,\n";
+
+        let channels = parse_channel_metadata_csv(input).unwrap();
+        assert_eq!(channels.len(), 2);
+        assert_eq!(channels[0].description.as_deref(), Some("Builders, \"devs\""));
+        assert_eq!(channels[0].lead_fid, Some(2));
+        assert_eq!(channels[1].name, None);
+        assert_eq!(channels[1].url, "chain://memes");
+    }
+
+    #[test]
+    fn test_parse_channel_metadata_csv_errors() {
+        assert!(parse_channel_metadata_csv("").is_err());
+        assert!(parse_channel_metadata_csv("name,url\nx,y").is_err());
+        assert!(parse_channel_metadata_csv("id,url,lead_fid\nx,y,abc").is_err());
+    }
+}
@@ -0,0 +1,388 @@
+//! Rebuild Hub-shaped messages from the typed tables.
+//!
+//! The typed tables (`casts`, `reactions`, `links`, ...) don't keep the signed
+//! message, so reads served from Postgres rebuild the `MessageData` the Hub
+//! would have returned. Message IDs are the hex message hash, as with the Hub
+//! client. Fields the tables don't store (network, verification chain ID) are
+//! left at their defaults.
+
+use crate::core::{
+    data_context::{DataAccessError, Result},
+    normalize::NormalizedEmbed,
+    types::{Message, MessageType},
+    util::{from_farcaster_time, to_farcaster_time},
+};
+use crate::proto::{
+    self, CastAddBody, CastId, LinkBody, LinkCompactStateBody, MessageData, ReactionBody,
+    UserDataBody, UserNameProof, VerificationAddAddressBody, cast_add_body, link_body,
+    message_data::Body, reaction_body,
+};
+use prost::Message as _;
+use serde_json::Value;
+use sqlx::types::time::OffsetDateTime;
+
+/// Convert a stored timestamp back to Farcaster time
+pub fn farcaster_timestamp(timestamp: OffsetDateTime) -> Result<u32> {
+    let unix_ms = (timestamp.unix_timestamp_nanos() / 1_000_000).max(0) as u64;
+    to_farcaster_time(unix_ms).map_err(|e| DataAccessError::Other(e.to_string()))
+}
+
+/// Convert an optional Farcaster-time bound into a timestamp for queries
+pub fn from_farcaster_bound(time: Option<u64>) -> Option<OffsetDateTime> {
+    time.and_then(|t| u32::try_from(t).ok()).and_then(|t| {
+        let unix_ms = from_farcaster_time(t) as i128;
+        OffsetDateTime::from_unix_timestamp_nanos(unix_ms * 1_000_000).ok()
+    })
+}
+
+/// Map the user data names the Hub client accepts to `user_data.type`
+pub fn user_data_type(name: &str) -> Result<i16> {
+    let data_type = match name.to_lowercase().as_str() {
+        "pfp" => proto::UserDataType::Pfp,
+        "display" => proto::UserDataType::Display,
+        "bio" => proto::UserDataType::Bio,
+        "url" => proto::UserDataType::Url,
+        "username" => proto::UserDataType::Username,
+        _ => return Err(DataAccessError::Other(format!("Invalid user data type: {}", name))),
+    };
+    Ok(data_type as i16)
+}
+
+fn encode(
+    hash: &[u8],
+    message_type: MessageType,
+    proto_type: proto::MessageType,
+    fid: i64,
+    timestamp: OffsetDateTime,
+    body: Body,
+) -> Result<Message> {
+    let data = MessageData {
+        r#type: proto_type as i32,
+        fid: fid as u64,
+        timestamp: farcaster_timestamp(timestamp)?,
+        network: 0,
+        body: Some(body),
+    };
+
+    Ok(Message::new(hex::encode(hash), message_type, data.encode_to_vec()))
+}
+
+/// A live row of `casts`
+#[derive(Debug, Clone)]
+pub struct CastRow {
+    pub hash: Vec<u8>,
+    pub fid: i64,
+    pub timestamp: OffsetDateTime,
+    pub text: Option<String>,
+    pub parent_fid: Option<i64>,
+    pub parent_hash: Option<Vec<u8>>,
+    pub parent_url: Option<String>,
+    pub embeds: Value,
+    pub mentions: Value,
+    pub mentions_positions: Value,
+}
+
+impl CastRow {
+    pub fn into_message(self) -> Result<Message> {
+        let embeds: Vec<NormalizedEmbed> = serde_json::from_value(self.embeds).unwrap_or_default();
+        let parent = match (self.parent_fid, self.parent_hash, self.parent_url) {
+            (Some(fid), Some(hash), _) => {
+                Some(cast_add_body::Parent::ParentCastId(CastId { fid: fid as u64, hash }))
+            },
+            (_, _, Some(url)) => Some(cast_add_body::Parent::ParentUrl(url)),
+            _ => None,
+        };
+
+        let body = Body::CastAddBody(CastAddBody {
+            text: self.text.unwrap_or_default(),
+            mentions: serde_json::from_value(self.mentions).unwrap_or_default(),
+            mentions_positions: serde_json::from_value(self.mentions_positions).unwrap_or_default(),
+            embeds: embeds.iter().map(NormalizedEmbed::to_protobuf_embed).collect(),
+            parent,
+            ..Default::default()
+        });
+
+        encode(
+            &self.hash,
+            MessageType::Cast,
+            proto::MessageType::CastAdd,
+            self.fid,
+            self.timestamp,
+            body,
+        )
+    }
+}
+
+/// A live row of `reactions`
+#[derive(Debug, Clone)]
+pub struct ReactionRow {
+    pub hash: Vec<u8>,
+    pub fid: i64,
+    pub timestamp: OffsetDateTime,
+    pub reaction_type: i16,
+    pub target_cast_fid: Option<i64>,
+    pub target_cast_hash: Option<Vec<u8>>,
+    pub target_url: Option<String>,
+}
+
+impl ReactionRow {
+    pub fn into_message(self) -> Result<Message> {
+        let target = match (self.target_cast_fid, self.target_cast_hash, self.target_url) {
+            (Some(fid), Some(hash), _) => {
+                Some(reaction_body::Target::TargetCastId(CastId { fid: fid as u64, hash }))
+            },
+            (_, _, Some(url)) => Some(reaction_body::Target::TargetUrl(url)),
+            _ => None,
+        };
+
+        let body = Body::ReactionBody(ReactionBody { r#type: self.reaction_type as i32, target });
+
+        encode(
+            &self.hash,
+            MessageType::Reaction,
+            proto::MessageType::ReactionAdd,
+            self.fid,
+            self.timestamp,
+            body,
+        )
+    }
+}
+
+/// A live row of `links`
+#[derive(Debug, Clone)]
+pub struct LinkRow {
+    pub hash: Vec<u8>,
+    pub fid: i64,
+    pub timestamp: OffsetDateTime,
+    pub link_type: String,
+    pub target_fid: i64,
+    pub display_timestamp: Option<OffsetDateTime>,
+}
+
+impl LinkRow {
+    pub fn into_message(self) -> Result<Message> {
+        let body = Body::LinkBody(LinkBody {
+            r#type: self.link_type,
+            display_timestamp: self.display_timestamp.map(farcaster_timestamp).transpose()?,
+            target: Some(link_body::Target::TargetFid(self.target_fid as u64)),
+        });
+
+        encode(
+            &self.hash,
+            MessageType::Link,
+            proto::MessageType::LinkAdd,
+            self.fid,
+            self.timestamp,
+            body,
+        )
+    }
+}
+
+/// A row of `link_compact_states`
+#[derive(Debug, Clone)]
+pub struct LinkCompactStateRow {
+    pub hash: Vec<u8>,
+    pub fid: i64,
+    pub timestamp: OffsetDateTime,
+    pub link_type: String,
+    pub target_fids: Vec<i64>,
+}
+
+impl LinkCompactStateRow {
+    pub fn into_message(self) -> Result<Message> {
+        let body = Body::LinkCompactStateBody(LinkCompactStateBody {
+            r#type: self.link_type,
+            target_fids: self.target_fids.into_iter().map(|f| f as u64).collect(),
+        });
+
+        encode(
+            &self.hash,
+            MessageType::Link,
+            proto::MessageType::LinkCompactState,
+            self.fid,
+            self.timestamp,
+            body,
+        )
+    }
+}
+
+/// A live row of `user_data`
+#[derive(Debug, Clone)]
+pub struct UserDataRow {
+    pub hash: Vec<u8>,
+    pub fid: i64,
+    pub timestamp: OffsetDateTime,
+    pub data_type: i16,
+    pub value: String,
+}
+
+impl UserDataRow {
+    pub fn into_message(self) -> Result<Message> {
+        let body =
+            Body::UserDataBody(UserDataBody { r#type: self.data_type as i32, value: self.value });
+
+        encode(
+            &self.hash,
+            MessageType::UserData,
+            proto::MessageType::UserDataAdd,
+            self.fid,
+            self.timestamp,
+            body,
+        )
+    }
+}
+
+/// A live row of `verifications`
+#[derive(Debug, Clone)]
+pub struct VerificationRow {
+    pub hash: Vec<u8>,
+    pub fid: i64,
+    pub timestamp: OffsetDateTime,
+    pub signer_address: Vec<u8>,
+    pub block_hash: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub protocol: Option<i16>,
+}
+
+impl VerificationRow {
+    pub fn into_message(self) -> Result<Message> {
+        let body = Body::VerificationAddAddressBody(VerificationAddAddressBody {
+            address: self.signer_address,
+            claim_signature: self.signature,
+            block_hash: self.block_hash,
+            protocol: self.protocol.unwrap_or_default() as i32,
+            ..Default::default()
+        });
+
+        encode(
+            &self.hash,
+            MessageType::Verification,
+            proto::MessageType::VerificationAddEthAddress,
+            self.fid,
+            self.timestamp,
+            body,
+        )
+    }
+}
+
+/// A live row of `username_proofs`
+#[derive(Debug, Clone)]
+pub struct UsernameProofRow {
+    pub fid: i64,
+    pub timestamp: OffsetDateTime,
+    pub username: String,
+    pub proof_type: i16,
+    pub signature: Vec<u8>,
+    pub owner: Option<Vec<u8>>,
+}
+
+impl UsernameProofRow {
+    pub fn into_proof(self) -> UserNameProof {
+        UserNameProof {
+            timestamp: self.timestamp.unix_timestamp().max(0) as u64,
+            name: self.username.into_bytes(),
+            owner: self.owner.unwrap_or_default(),
+            signature: self.signature,
+            fid: self.fid as u64,
+            r#type: self.proof_type as i32,
+        }
+    }
+
+    /// Encode the proof the way the Hub client does: JSON, keyed by FID and name
+    pub fn into_message(self) -> Result<Message> {
+        let id = format!("proof_{}_{}", self.fid, self.username);
+        let payload = serde_json::to_vec(&self.into_proof())
+            .map_err(|e| DataAccessError::Serialization(e.to_string()))?;
+
+        Ok(Message::new(id, MessageType::UsernameProof, payload))
+    }
+}
+
+/// Proto message types stored in `messages` for a domain message type
+pub fn proto_message_types(message_type: MessageType) -> Vec<i16> {
+    use proto::MessageType as P;
+
+    let types: &[P] = match message_type {
+        MessageType::Cast => &[P::CastAdd, P::CastRemove],
+        MessageType::Reaction => &[P::ReactionAdd, P::ReactionRemove],
+        MessageType::Link => &[P::LinkAdd, P::LinkRemove, P::LinkCompactState],
+        MessageType::Verification => &[P::VerificationAddEthAddress, P::VerificationRemove],
+        MessageType::UserData => &[P::UserDataAdd],
+        MessageType::UsernameProof => &[P::UsernameProof],
+        MessageType::FrameAction => &[P::FrameAction],
+        MessageType::LendStorage => &[P::LendStorage],
+        // Onchain events aren't Hub messages
+        _ => &[],
+    };
+    types.iter().map(|t| *t as i16).collect()
+}
+
+/// Payload of a `messages` row: the bytes its hash covers, or the JSON body re-encoded for
+/// rows stored before `raw` was always filled
+pub fn stored_payload(raw: Vec<u8>, body: Value) -> Result<Vec<u8>> {
+    if !raw.is_empty() {
+        return Ok(raw);
+    }
+
+    let data: MessageData =
+        serde_json::from_value(body).map_err(|e| DataAccessError::Serialization(e.to_string()))?;
+    Ok(data.encode_to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cast_row_round_trip() {
+        let timestamp = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let row = CastRow {
+            hash: vec![0xab; 20],
+            fid: 3,
+            timestamp,
+            text: Some("hello @".to_string()),
+            parent_fid: Some(2),
+            parent_hash: Some(vec![0xcd; 20]),
+            parent_url: None,
+            embeds: serde_json::json!([]),
+            mentions: serde_json::json!([5]),
+            mentions_positions: serde_json::json!([6]),
+        };
+
+        let message = row.into_message().unwrap();
+        assert_eq!(message.id.value(), "ab".repeat(20));
+
+        let data = MessageData::decode(message.payload.as_slice()).unwrap();
+        assert_eq!(data.fid, 3);
+        assert_eq!(data.r#type, proto::MessageType::CastAdd as i32);
+        assert_eq!(data.timestamp, farcaster_timestamp(timestamp).unwrap());
+        let Some(Body::CastAddBody(body)) = data.body else {
+            panic!("expected a cast body");
+        };
+        assert_eq!(body.text, "hello @");
+        assert_eq!(body.mentions, vec![5]);
+        assert_eq!(body.mentions_positions, vec![6]);
+        assert_eq!(
+            body.parent,
+            Some(cast_add_body::Parent::ParentCastId(CastId { fid: 2, hash: vec![0xcd; 20] }))
+        );
+    }
+
+    #[test]
+    fn test_farcaster_bound_round_trip() {
+        let timestamp = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let farcaster = farcaster_timestamp(timestamp).unwrap();
+
+        assert_eq!(from_farcaster_bound(Some(farcaster as u64)), Some(timestamp));
+        assert_eq!(from_farcaster_bound(None), None);
+    }
+
+    #[test]
+    fn test_stored_payload_prefers_raw() {
+        assert_eq!(stored_payload(vec![1, 2, 3], Value::Null).unwrap(), vec![1, 2, 3]);
+
+        let data = MessageData { fid: 7, timestamp: 10, ..Default::default() };
+        let body = serde_json::to_value(&data).unwrap();
+        assert_eq!(stored_payload(Vec::new(), body).unwrap(), data.encode_to_vec());
+    }
+}
@@ -1,11 +1,25 @@
 //! Database module for PostgreSQL interactions
+pub mod audit;
 pub mod batch;
+pub mod cast_references;
+pub mod channels;
 pub mod client;
 pub mod error;
+pub mod hub_messages;
 pub mod models;
+pub mod outbox;
+pub mod pages;
+pub mod priority;
 pub mod providers;
+pub mod registry;
+pub mod root_parent;
+pub mod signed_messages;
+pub mod store;
+pub mod trie;
+pub mod webhooks;
 
 // Re-export most commonly used types
 pub use client::Database;
 pub use error::Error;
 pub use providers::PostgresDatabaseClient;
+pub use store::MessageStore;
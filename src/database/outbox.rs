@@ -0,0 +1,337 @@
+//! Change outbox: an ordered feed of the changes Waypoint applied.
+//!
+//! [`append`] is called by [`PostgresStore`](super::store::PostgresStore) inside
+//! the transaction that applies each message, username proof or on-chain event,
+//! so a row exists exactly when its change was committed. Rows are appended under
+//! a transaction-level advisory lock, which makes `seq` order match commit order:
+//! a reader polling with [`read`] and `seq > cursor` never skips a row that
+//! commits late.
+
+use crate::{
+    processor::webhook::{message_json, onchain_event_json},
+    proto::{Message, MessageType, OnChainEvent, OnChainEventType, UserNameProof},
+};
+use serde_json::{Value, json};
+use sqlx::{PgConnection, PgPool, types::time::OffsetDateTime};
+
+/// Every `entity` the outbox records
+pub const ENTITIES: [&str; 10] = [
+    "cast",
+    "reaction",
+    "link",
+    "verification",
+    "user_data",
+    "username_proof",
+    "frame_action",
+    "link_compact_state",
+    "lend_storage",
+    "onchain_event",
+];
+
+/// Advisory lock key serializing appends ("wpoutbox")
+const APPEND_LOCK_KEY: i64 = 0x7770_6f75_7462_6f78;
+
+/// A change to record
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxEntry {
+    /// What changed, such as `cast`, `reaction` or `user_data`
+    pub entity: &'static str,
+    /// How it changed: `add`, `remove` or `set` for merges, otherwise the Hub
+    /// operation (`delete`, `prune` or `revoke`)
+    pub operation: String,
+    pub fid: Option<i64>,
+    pub hash: Option<Vec<u8>>,
+    pub payload: Value,
+}
+
+impl OutboxEntry {
+    /// Entry for a message applied with `operation` (merge, delete, prune or revoke)
+    pub fn from_message(msg: &Message, operation: &str) -> Option<Self> {
+        let data = msg.data.as_ref()?;
+        let message_type = MessageType::try_from(data.r#type).ok()?;
+        let (entity, merged) = match message_type {
+            MessageType::CastAdd => ("cast", "add"),
+            MessageType::CastRemove => ("cast", "remove"),
+            MessageType::ReactionAdd => ("reaction", "add"),
+            MessageType::ReactionRemove => ("reaction", "remove"),
+            MessageType::LinkAdd => ("link", "add"),
+            MessageType::LinkRemove => ("link", "remove"),
+            MessageType::VerificationAddEthAddress => ("verification", "add"),
+            MessageType::VerificationRemove => ("verification", "remove"),
+            MessageType::UserDataAdd => ("user_data", "set"),
+            MessageType::UsernameProof => ("username_proof", "add"),
+            MessageType::FrameAction => ("frame_action", "add"),
+            MessageType::LinkCompactState => ("link_compact_state", "set"),
+            MessageType::LendStorage => ("lend_storage", "add"),
+            _ => return None,
+        };
+        let operation = if operation == "merge" { merged } else { operation };
+
+        Some(Self {
+            entity,
+            operation: operation.to_string(),
+            fid: Some(data.fid as i64),
+            hash: Some(msg.hash.clone()),
+            payload: message_json(message_type, &msg.hash, data),
+        })
+    }
+
+    /// Entry for a username proof from a MergeUsernameProof event
+    pub fn from_username_proof(proof: &UserNameProof, deleted: bool) -> Self {
+        Self {
+            entity: "username_proof",
+            operation: if deleted { "remove" } else { "add" }.to_string(),
+            fid: Some(proof.fid as i64),
+            hash: None,
+            payload: json!({
+                "type": "username_proof",
+                "fid": proof.fid,
+                "username": String::from_utf8_lossy(&proof.name),
+                "owner": format!("0x{}", hex::encode(&proof.owner)),
+                "timestamp": proof.timestamp,
+            }),
+        }
+    }
+
+    /// Entry for a merged on-chain event
+    pub fn from_onchain_event(event: &OnChainEvent) -> Option<Self> {
+        let event_type = OnChainEventType::try_from(event.r#type).ok()?;
+        Some(Self {
+            entity: "onchain_event",
+            operation: "add".to_string(),
+            fid: Some(event.fid as i64),
+            hash: None,
+            payload: onchain_event_json(event_type, event),
+        })
+    }
+}
+
+/// A recorded change
+#[derive(Debug, Clone)]
+pub struct OutboxRow {
+    pub seq: i64,
+    pub created_at: OffsetDateTime,
+    pub entity: String,
+    pub operation: String,
+    pub fid: Option<i64>,
+    pub hash: Option<Vec<u8>>,
+    pub payload: Value,
+}
+
+impl OutboxRow {
+    /// The row as one line of the CLI feed
+    pub fn to_json(&self) -> Value {
+        json!({
+            "seq": self.seq,
+            "created_at": self.created_at.unix_timestamp(),
+            "entity": self.entity,
+            "operation": self.operation,
+            "fid": self.fid,
+            "hash": self.hash.as_ref().map(hex::encode),
+            "payload": self.payload,
+        })
+    }
+}
+
+/// Range and size of the outbox
+#[derive(Debug, Clone)]
+pub struct OutboxStatus {
+    pub rows: i64,
+    pub first_seq: Option<i64>,
+    pub last_seq: Option<i64>,
+    pub oldest_at: Option<OffsetDateTime>,
+}
+
+/// Append entries in the caller's transaction.
+///
+/// Takes the append lock until the transaction ends, so call this last, just
+/// before committing.
+pub async fn append(conn: &mut PgConnection, entries: &[OutboxEntry]) -> Result<u64, sqlx::Error> {
+    if entries.is_empty() {
+        return Ok(0);
+    }
+
+    sqlx::query!("SELECT pg_advisory_xact_lock($1)", APPEND_LOCK_KEY).execute(&mut *conn).await?;
+
+    let entities: Vec<&str> = entries.iter().map(|e| e.entity).collect();
+    let operations: Vec<&str> = entries.iter().map(|e| e.operation.as_str()).collect();
+    let fids: Vec<Option<i64>> = entries.iter().map(|e| e.fid).collect();
+    let hashes: Vec<Option<Vec<u8>>> = entries.iter().map(|e| e.hash.clone()).collect();
+    let payloads: Vec<Value> = entries.iter().map(|e| e.payload.clone()).collect();
+
+    // WITH ORDINALITY keeps seq in the order the entries were given
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO change_outbox (entity, operation, fid, hash, payload)
+        SELECT entity, operation, fid, hash, payload
+        FROM UNNEST($1::text[], $2::text[], $3::bigint[], $4::bytea[], $5::jsonb[])
+            WITH ORDINALITY AS e(entity, operation, fid, hash, payload, n)
+        ORDER BY n
+        "#,
+        &entities as &[&str],
+        &operations as &[&str],
+        &fids as &[Option<i64>],
+        &hashes as &[Option<Vec<u8>>],
+        &payloads
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Up to `limit` changes after `after_seq`, oldest first, optionally only of `entities`
+pub async fn read(
+    pool: &PgPool,
+    after_seq: i64,
+    entities: &[String],
+    limit: i64,
+) -> Result<Vec<OutboxRow>, sqlx::Error> {
+    sqlx::query_as!(
+        OutboxRow,
+        r#"
+        SELECT seq, created_at, entity, operation, fid, hash, payload
+        FROM change_outbox
+        WHERE seq > $1 AND (cardinality($2::text[]) = 0 OR entity = ANY($2))
+        ORDER BY seq
+        LIMIT $3
+        "#,
+        after_seq,
+        entities,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Row count, sequence range and age of the outbox
+pub async fn status(pool: &PgPool) -> Result<OutboxStatus, sqlx::Error> {
+    sqlx::query_as!(
+        OutboxStatus,
+        r#"
+        SELECT count(*) AS "rows!", min(seq) AS first_seq, max(seq) AS last_seq,
+               min(created_at) AS oldest_at
+        FROM change_outbox
+        "#
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// Delete changes recorded before `cutoff`, `batch_size` rows at a time.
+/// Returns how many were deleted.
+pub async fn cleanup(
+    pool: &PgPool,
+    cutoff: OffsetDateTime,
+    batch_size: i64,
+) -> Result<u64, sqlx::Error> {
+    let mut deleted = 0;
+    loop {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM change_outbox
+            WHERE seq IN (
+                SELECT seq FROM change_outbox
+                WHERE created_at < $1
+                ORDER BY seq
+                LIMIT $2
+            )
+            "#,
+            cutoff,
+            batch_size
+        )
+        .execute(pool)
+        .await?;
+
+        deleted += result.rows_affected();
+        if result.rows_affected() < batch_size as u64 {
+            return Ok(deleted);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{
+        CastAddBody, CastRemoveBody, MessageData, UserDataBody, UserDataType, message_data::Body,
+    };
+
+    fn message(message_type: MessageType, body: Body) -> Message {
+        Message {
+            data: Some(MessageData {
+                r#type: message_type as i32,
+                fid: 42,
+                timestamp: 100,
+                body: Some(body),
+                ..Default::default()
+            }),
+            hash: vec![0xab, 0xcd],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_entry_from_merged_messages() {
+        let cast = message(
+            MessageType::CastAdd,
+            Body::CastAddBody(CastAddBody { text: "hello".to_string(), ..Default::default() }),
+        );
+        let entry = OutboxEntry::from_message(&cast, "merge").unwrap();
+        assert_eq!((entry.entity, entry.operation.as_str()), ("cast", "add"));
+        assert_eq!(entry.fid, Some(42));
+        assert_eq!(entry.hash, Some(vec![0xab, 0xcd]));
+        assert_eq!(entry.payload["type"], "cast_add");
+        assert_eq!(entry.payload["hash"], "abcd");
+        assert_eq!(entry.payload["data"]["text"], "hello");
+
+        let remove = message(
+            MessageType::CastRemove,
+            Body::CastRemoveBody(CastRemoveBody { target_hash: vec![0x01] }),
+        );
+        let entry = OutboxEntry::from_message(&remove, "merge").unwrap();
+        assert_eq!((entry.entity, entry.operation.as_str()), ("cast", "remove"));
+        assert_eq!(entry.payload["data"]["target_hash"], "01");
+
+        let profile = message(
+            MessageType::UserDataAdd,
+            Body::UserDataBody(UserDataBody {
+                r#type: UserDataType::Display as i32,
+                value: "Alice".to_string(),
+            }),
+        );
+        let entry = OutboxEntry::from_message(&profile, "merge").unwrap();
+        assert_eq!((entry.entity, entry.operation.as_str()), ("user_data", "set"));
+        assert_eq!(entry.payload["data"]["value"], "Alice");
+    }
+
+    #[test]
+    fn test_entry_keeps_hub_operation() {
+        let cast = message(MessageType::CastAdd, Body::CastAddBody(CastAddBody::default()));
+        for operation in ["delete", "prune", "revoke"] {
+            let entry = OutboxEntry::from_message(&cast, operation).unwrap();
+            assert_eq!((entry.entity, entry.operation.as_str()), ("cast", operation));
+        }
+
+        assert!(OutboxEntry::from_message(&Message::default(), "merge").is_none());
+    }
+
+    #[test]
+    fn test_entry_from_username_proof() {
+        let proof = UserNameProof {
+            fid: 7,
+            name: b"alice".to_vec(),
+            owner: vec![0x12, 0x34],
+            timestamp: 1_700_000_000,
+            ..Default::default()
+        };
+
+        let entry = OutboxEntry::from_username_proof(&proof, false);
+        assert_eq!((entry.entity, entry.operation.as_str()), ("username_proof", "add"));
+        assert_eq!(entry.payload["username"], "alice");
+        assert_eq!(entry.payload["owner"], "0x1234");
+
+        let entry = OutboxEntry::from_username_proof(&proof, true);
+        assert_eq!(entry.operation, "remove");
+    }
+}
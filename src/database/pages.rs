@@ -0,0 +1,511 @@
+//! Cursor-paginated reads for the REST and GraphQL APIs.
+//!
+//! Pages are ordered by `(timestamp, hash)`, which is unique per table, so a
+//! cursor is the key of the last row returned and the next page starts right
+//! after it. Rows come back in the `hub_messages` shapes and are turned into
+//! Hub messages by the caller.
+
+use crate::database::hub_messages::{CastRow, LinkRow, ReactionRow, VerificationRow};
+use sqlx::{PgPool, types::time::OffsetDateTime};
+
+/// Position of the last row of a page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageCursor {
+    pub timestamp: OffsetDateTime,
+    pub hash: Vec<u8>,
+}
+
+impl PageCursor {
+    /// Opaque form used in `next_cursor`: `<unix micros>.<hex hash>`
+    pub fn encode(&self) -> String {
+        let micros = self.timestamp.unix_timestamp_nanos() / 1_000;
+        format!("{}.{}", micros, hex::encode(&self.hash))
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let (micros, hash) = cursor.split_once('.')?;
+        let micros: i128 = micros.parse().ok()?;
+        Some(Self {
+            timestamp: OffsetDateTime::from_unix_timestamp_nanos(micros * 1_000).ok()?,
+            hash: hex::decode(hash).ok()?,
+        })
+    }
+}
+
+/// One page of rows and the cursor for the next, if there is one
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<PageCursor>,
+}
+
+impl<T> Page<T> {
+    /// Build a page from up to `limit + 1` rows; the extra row only signals that more exist
+    pub(crate) fn from_rows(
+        mut rows: Vec<T>,
+        limit: usize,
+        key: impl Fn(&T) -> PageCursor,
+    ) -> Self {
+        let next = if rows.len() > limit {
+            rows.truncate(limit);
+            rows.last().map(key)
+        } else {
+            None
+        };
+        Self { items: rows, next }
+    }
+}
+
+fn cast_key(row: &CastRow) -> PageCursor {
+    PageCursor { timestamp: row.timestamp, hash: row.hash.clone() }
+}
+
+fn reaction_key(row: &ReactionRow) -> PageCursor {
+    PageCursor { timestamp: row.timestamp, hash: row.hash.clone() }
+}
+
+fn link_key(row: &LinkRow) -> PageCursor {
+    PageCursor { timestamp: row.timestamp, hash: row.hash.clone() }
+}
+
+fn verification_key(row: &VerificationRow) -> PageCursor {
+    PageCursor { timestamp: row.timestamp, hash: row.hash.clone() }
+}
+
+fn onchain_event_key(row: &OnchainEventRow) -> PageCursor {
+    PageCursor { timestamp: row.timestamp, hash: row.hash.clone() }
+}
+
+/// Split an optional cursor into the two bind parameters the queries take
+fn bounds(cursor: Option<&PageCursor>) -> (Option<OffsetDateTime>, Option<&[u8]>) {
+    (cursor.map(|c| c.timestamp), cursor.map(|c| c.hash.as_slice()))
+}
+
+/// On-chain event type names, indexed by `onchain_events.type`
+const ONCHAIN_EVENT_TYPES: [&str; 6] =
+    ["none", "signer", "signer_migrated", "id_register", "storage_rent", "tier_purchase"];
+
+/// Parse an on-chain event type name (`signer`, `id_register`, ...) into `onchain_events.type`
+pub fn onchain_event_type(name: &str) -> Option<i16> {
+    ONCHAIN_EVENT_TYPES.iter().skip(1).position(|t| *t == name).map(|i| i as i16 + 1)
+}
+
+/// A live row of `onchain_events`
+#[derive(Debug, Clone)]
+pub struct OnchainEventRow {
+    pub hash: Vec<u8>,
+    pub fid: i64,
+    pub timestamp: OffsetDateTime,
+    pub event_type: i16,
+    pub chain_id: i64,
+    pub block_number: i64,
+    pub block_hash: Vec<u8>,
+    pub log_index: i32,
+    pub tx_index: i32,
+    pub tx_hash: Vec<u8>,
+}
+
+impl OnchainEventRow {
+    pub fn type_name(&self) -> &'static str {
+        ONCHAIN_EVENT_TYPES.get(self.event_type as usize).copied().unwrap_or("unknown")
+    }
+}
+
+/// Look up a live cast by hash
+pub async fn cast_by_hash(pool: &PgPool, hash: &[u8]) -> Result<Option<CastRow>, sqlx::Error> {
+    sqlx::query_as!(
+        CastRow,
+        r#"
+        SELECT hash, fid AS "fid!", timestamp, text, parent_fid, parent_hash, parent_url,
+               embeds, mentions, mentions_positions
+        FROM casts
+        WHERE hash = $1 AND deleted_at IS NULL AND fid IS NOT NULL
+        "#,
+        hash
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Hash of the cast a live cast's thread starts from (the cast itself if it is the root)
+pub async fn cast_thread_root(pool: &PgPool, hash: &[u8]) -> Result<Option<Vec<u8>>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(root_parent_hash, hash) AS "root!"
+        FROM casts
+        WHERE hash = $1 AND deleted_at IS NULL
+        "#,
+        hash
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Casts by an FID, newest first
+pub async fn casts_by_fid(
+    pool: &PgPool,
+    fid: i64,
+    cursor: Option<&PageCursor>,
+    limit: usize,
+) -> Result<Page<CastRow>, sqlx::Error> {
+    let (before_ts, before_hash) = bounds(cursor);
+    let rows = sqlx::query_as!(
+        CastRow,
+        r#"
+        SELECT hash, fid AS "fid!", timestamp, text, parent_fid, parent_hash, parent_url,
+               embeds, mentions, mentions_positions
+        FROM casts
+        WHERE fid = $1
+          AND deleted_at IS NULL
+          AND ($2::timestamptz IS NULL OR (timestamp, hash) < ($2, $3::bytea))
+        ORDER BY timestamp DESC, hash DESC
+        LIMIT $4
+        "#,
+        fid,
+        before_ts,
+        before_hash,
+        limit as i64 + 1
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Page::from_rows(rows, limit, cast_key))
+}
+
+/// Every live cast in the thread rooted at `root_hash`, including the root, oldest first
+pub async fn cast_thread(
+    pool: &PgPool,
+    root_hash: &[u8],
+    cursor: Option<&PageCursor>,
+    limit: usize,
+) -> Result<Page<CastRow>, sqlx::Error> {
+    let (after_ts, after_hash) = bounds(cursor);
+    let rows = sqlx::query_as!(
+        CastRow,
+        r#"
+        SELECT hash, fid AS "fid!", timestamp, text, parent_fid, parent_hash, parent_url,
+               embeds, mentions, mentions_positions
+        FROM casts
+        WHERE (hash = $1 OR root_parent_hash = $1)
+          AND deleted_at IS NULL
+          AND fid IS NOT NULL
+          AND ($2::timestamptz IS NULL OR (timestamp, hash) > ($2, $3::bytea))
+        ORDER BY timestamp, hash
+        LIMIT $4
+        "#,
+        root_hash,
+        after_ts,
+        after_hash,
+        limit as i64 + 1
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Page::from_rows(rows, limit, cast_key))
+}
+
+/// Direct replies to a cast, newest first
+pub async fn cast_replies(
+    pool: &PgPool,
+    parent_hash: &[u8],
+    cursor: Option<&PageCursor>,
+    limit: usize,
+) -> Result<Page<CastRow>, sqlx::Error> {
+    let (before_ts, before_hash) = bounds(cursor);
+    let rows = sqlx::query_as!(
+        CastRow,
+        r#"
+        SELECT hash, fid AS "fid!", timestamp, text, parent_fid, parent_hash, parent_url,
+               embeds, mentions, mentions_positions
+        FROM casts
+        WHERE parent_hash = $1
+          AND deleted_at IS NULL
+          AND fid IS NOT NULL
+          AND ($2::timestamptz IS NULL OR (timestamp, hash) < ($2, $3::bytea))
+        ORDER BY timestamp DESC, hash DESC
+        LIMIT $4
+        "#,
+        parent_hash,
+        before_ts,
+        before_hash,
+        limit as i64 + 1
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Page::from_rows(rows, limit, cast_key))
+}
+
+/// Casts in a channel (by `root_parent_url`), including replies, newest first
+pub async fn casts_by_channel(
+    pool: &PgPool,
+    url: &str,
+    cursor: Option<&PageCursor>,
+    limit: usize,
+) -> Result<Page<CastRow>, sqlx::Error> {
+    let (before_ts, before_hash) = bounds(cursor);
+    let rows = sqlx::query_as!(
+        CastRow,
+        r#"
+        SELECT hash, fid AS "fid!", timestamp, text, parent_fid, parent_hash, parent_url,
+               embeds, mentions, mentions_positions
+        FROM casts
+        WHERE root_parent_url = $1
+          AND deleted_at IS NULL
+          AND fid IS NOT NULL
+          AND ($2::timestamptz IS NULL OR (timestamp, hash) < ($2, $3::bytea))
+        ORDER BY timestamp DESC, hash DESC
+        LIMIT $4
+        "#,
+        url,
+        before_ts,
+        before_hash,
+        limit as i64 + 1
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Page::from_rows(rows, limit, cast_key))
+}
+
+/// Reactions to a cast, optionally of one type, newest first
+pub async fn reactions_by_cast(
+    pool: &PgPool,
+    target_hash: &[u8],
+    reaction_type: Option<i16>,
+    cursor: Option<&PageCursor>,
+    limit: usize,
+) -> Result<Page<ReactionRow>, sqlx::Error> {
+    let (before_ts, before_hash) = bounds(cursor);
+    let rows = sqlx::query_as!(
+        ReactionRow,
+        r#"
+        SELECT hash, fid AS "fid!", timestamp, type AS reaction_type, target_cast_fid,
+               target_cast_hash, target_url
+        FROM reactions
+        WHERE target_cast_hash = $1
+          AND ($2::smallint IS NULL OR type = $2)
+          AND deleted_at IS NULL
+          AND ($3::timestamptz IS NULL OR (timestamp, hash) < ($3, $4::bytea))
+        ORDER BY timestamp DESC, hash DESC
+        LIMIT $5
+        "#,
+        target_hash,
+        reaction_type,
+        before_ts,
+        before_hash,
+        limit as i64 + 1
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Page::from_rows(rows, limit, reaction_key))
+}
+
+/// Reactions made by an FID, optionally of one type, newest first
+pub async fn reactions_by_fid(
+    pool: &PgPool,
+    fid: i64,
+    reaction_type: Option<i16>,
+    cursor: Option<&PageCursor>,
+    limit: usize,
+) -> Result<Page<ReactionRow>, sqlx::Error> {
+    let (before_ts, before_hash) = bounds(cursor);
+    let rows = sqlx::query_as!(
+        ReactionRow,
+        r#"
+        SELECT hash, fid AS "fid!", timestamp, type AS reaction_type, target_cast_fid,
+               target_cast_hash, target_url
+        FROM reactions
+        WHERE fid = $1
+          AND ($2::smallint IS NULL OR type = $2)
+          AND deleted_at IS NULL
+          AND ($3::timestamptz IS NULL OR (timestamp, hash) < ($3, $4::bytea))
+        ORDER BY timestamp DESC, hash DESC
+        LIMIT $5
+        "#,
+        fid,
+        reaction_type,
+        before_ts,
+        before_hash,
+        limit as i64 + 1
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Page::from_rows(rows, limit, reaction_key))
+}
+
+/// Links of `link_type` pointing at an FID (its followers, for "follow"), newest first
+pub async fn links_by_target(
+    pool: &PgPool,
+    target_fid: i64,
+    link_type: &str,
+    cursor: Option<&PageCursor>,
+    limit: usize,
+) -> Result<Page<LinkRow>, sqlx::Error> {
+    let (before_ts, before_hash) = bounds(cursor);
+    let rows = sqlx::query_as!(
+        LinkRow,
+        r#"
+        SELECT hash, fid AS "fid!", timestamp, type AS link_type, target_fid,
+               display_timestamp
+        FROM links
+        WHERE target_fid = $1
+          AND type = $2
+          AND deleted_at IS NULL
+          AND ($3::timestamptz IS NULL OR (timestamp, hash) < ($3, $4::bytea))
+        ORDER BY timestamp DESC, hash DESC
+        LIMIT $5
+        "#,
+        target_fid,
+        link_type,
+        before_ts,
+        before_hash,
+        limit as i64 + 1
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Page::from_rows(rows, limit, link_key))
+}
+
+/// Links of `link_type` made by an FID (who it follows, for "follow"), newest first
+pub async fn links_by_fid(
+    pool: &PgPool,
+    fid: i64,
+    link_type: &str,
+    cursor: Option<&PageCursor>,
+    limit: usize,
+) -> Result<Page<LinkRow>, sqlx::Error> {
+    let (before_ts, before_hash) = bounds(cursor);
+    let rows = sqlx::query_as!(
+        LinkRow,
+        r#"
+        SELECT hash, fid AS "fid!", timestamp, type AS link_type, target_fid,
+               display_timestamp
+        FROM links
+        WHERE fid = $1
+          AND type = $2
+          AND deleted_at IS NULL
+          AND ($3::timestamptz IS NULL OR (timestamp, hash) < ($3, $4::bytea))
+        ORDER BY timestamp DESC, hash DESC
+        LIMIT $5
+        "#,
+        fid,
+        link_type,
+        before_ts,
+        before_hash,
+        limit as i64 + 1
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Page::from_rows(rows, limit, link_key))
+}
+
+/// Live verifications of an FID, newest first
+pub async fn verifications_by_fid(
+    pool: &PgPool,
+    fid: i64,
+    cursor: Option<&PageCursor>,
+    limit: usize,
+) -> Result<Page<VerificationRow>, sqlx::Error> {
+    let (before_ts, before_hash) = bounds(cursor);
+    let rows = sqlx::query_as!(
+        VerificationRow,
+        r#"
+        SELECT hash, fid AS "fid!", timestamp, signer_address, block_hash, signature, protocol
+        FROM verifications
+        WHERE fid = $1
+          AND deleted_at IS NULL
+          AND ($2::timestamptz IS NULL OR (timestamp, hash) < ($2, $3::bytea))
+        ORDER BY timestamp DESC, hash DESC
+        LIMIT $4
+        "#,
+        fid,
+        before_ts,
+        before_hash,
+        limit as i64 + 1
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Page::from_rows(rows, limit, verification_key))
+}
+
+/// On-chain events of an FID, optionally of one type, newest first
+pub async fn onchain_events_by_fid(
+    pool: &PgPool,
+    fid: i64,
+    event_type: Option<i16>,
+    cursor: Option<&PageCursor>,
+    limit: usize,
+) -> Result<Page<OnchainEventRow>, sqlx::Error> {
+    let (before_ts, before_hash) = bounds(cursor);
+    let rows = sqlx::query_as!(
+        OnchainEventRow,
+        r#"
+        SELECT hash, fid, timestamp, type AS event_type, chain_id, block_number, block_hash,
+               log_index, tx_index, tx_hash
+        FROM onchain_events
+        WHERE fid = $1
+          AND ($2::smallint IS NULL OR type = $2)
+          AND deleted_at IS NULL
+          AND ($3::timestamptz IS NULL OR (timestamp, hash) < ($3, $4::bytea))
+        ORDER BY timestamp DESC, hash DESC
+        LIMIT $5
+        "#,
+        fid,
+        event_type,
+        before_ts,
+        before_hash,
+        limit as i64 + 1
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Page::from_rows(rows, limit, onchain_event_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = PageCursor {
+            timestamp: OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_123_456_000)
+                .unwrap(),
+            hash: vec![0xab, 0xcd, 0x01],
+        };
+
+        let encoded = cursor.encode();
+        assert_eq!(encoded, "1700000000123456.abcd01");
+        assert_eq!(PageCursor::decode(&encoded), Some(cursor));
+    }
+
+    #[test]
+    fn test_cursor_rejects_garbage() {
+        assert_eq!(PageCursor::decode(""), None);
+        assert_eq!(PageCursor::decode("abc.00"), None);
+        assert_eq!(PageCursor::decode("123.xyz"), None);
+        assert_eq!(PageCursor::decode("123"), None);
+    }
+
+    #[test]
+    fn test_page_from_rows() {
+        let key = |n: &i64| PageCursor {
+            timestamp: OffsetDateTime::from_unix_timestamp(*n).unwrap(),
+            hash: vec![*n as u8],
+        };
+
+        let page = Page::from_rows(vec![3, 2, 1], 2, key);
+        assert_eq!(page.items, vec![3, 2]);
+        assert_eq!(page.next, Some(key(&2)));
+
+        let page = Page::from_rows(vec![3, 2], 2, key);
+        assert_eq!(page.items, vec![3, 2]);
+        assert_eq!(page.next, None);
+    }
+}
@@ -0,0 +1,74 @@
+//! Queries that pick which FIDs to backfill first.
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, types::time::OffsetDateTime};
+
+/// FIDs with the most live followers, most followed first
+pub async fn most_followed_fids(pool: &PgPool, limit: i64) -> Result<Vec<i64>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT target_fid AS "fid!"
+        FROM links
+        WHERE type = 'follow' AND deleted_at IS NULL
+        GROUP BY target_fid
+        ORDER BY COUNT(*) DESC, target_fid
+        LIMIT $1
+        "#,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// FIDs that the live stream has written recent casts, reactions or links for, most
+/// active first
+pub async fn recently_active_fids(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<i64>, sqlx::Error> {
+    let since = OffsetDateTime::from_unix_timestamp(since.timestamp())
+        .unwrap_or(OffsetDateTime::UNIX_EPOCH);
+
+    // `updated_at` narrows the scan through the BRIN indexes; `timestamp` keeps out old
+    // messages that were only rewritten recently, e.g. by a backfill
+    sqlx::query_scalar!(
+        r#"
+        SELECT fid AS "fid!"
+        FROM (
+            SELECT fid FROM casts WHERE updated_at >= $1 AND timestamp >= $1
+            UNION ALL
+            SELECT fid FROM reactions WHERE updated_at >= $1 AND timestamp >= $1
+            UNION ALL
+            SELECT fid FROM links WHERE updated_at >= $1 AND timestamp >= $1
+        ) recent
+        WHERE fid IS NOT NULL
+        GROUP BY fid
+        ORDER BY COUNT(*) DESC, fid
+        LIMIT $2
+        "#,
+        since,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Registered FIDs with no live `user_data` rows, i.e. no profile yet
+pub async fn fids_missing_user_data(pool: &PgPool, limit: i64) -> Result<Vec<i64>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT f.fid
+        FROM fids f
+        WHERE f.deleted_at IS NULL
+          AND NOT EXISTS (
+              SELECT 1 FROM user_data u WHERE u.fid = f.fid AND u.deleted_at IS NULL
+          )
+        ORDER BY f.fid
+        LIMIT $1
+        "#,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -2,14 +2,22 @@
 
 use crate::core::{
     data_context::{DataAccessError, Database, Result},
-    types::{Fid, Message, MessageId, MessageType},
+    types::{
+        AppSignerStats, Channel, ChannelActivity, Fid, FidRegistration, Message, MessageId,
+        MessageType, Signer, SignerStatus,
+    },
+};
+use crate::database::{
+    client::Database as DbPool,
+    hub_messages::{
+        self, CastRow, LinkCompactStateRow, LinkRow, ReactionRow, UserDataRow, UsernameProofRow,
+        VerificationRow,
+    },
 };
-use crate::database::client::Database as DbPool;
 use async_trait::async_trait;
-use sqlx::Row;
 use std::collections::HashSet;
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::info;
 
 /// Read-only PostgreSQL data provider
 #[derive(Clone)]
@@ -29,25 +37,6 @@ impl PostgresDatabaseClient {
         Self { db }
     }
 
-    /// Get the table name for a message type
-    fn table_for_message_type(&self, message_type: MessageType) -> &'static str {
-        match message_type {
-            MessageType::Cast => "casts",
-            MessageType::Reaction => "reactions",
-            MessageType::Link => "links",
-            MessageType::Verification => "verifications",
-            MessageType::UserData => "user_data",
-            MessageType::UsernameProof => "username_proofs",
-            MessageType::OnchainSigner => "signer_events",
-            MessageType::OnchainSignerMigrated => "signer_migrated_events",
-            MessageType::OnchainIdRegister => "id_register_events",
-            MessageType::OnchainStorageRent => "storage_rent_events",
-            MessageType::OnchainTierPurchase => "tier_purchases",
-            MessageType::LendStorage => "lend_storage",
-            MessageType::Messages => "messages",
-        }
-    }
-
     /// Batch upsert spammy users efficiently using unnest
     /// Inserts new FIDs and clears deleted_at for existing ones
     /// Soft-deletes FIDs that are no longer in the set
@@ -196,36 +185,27 @@ impl Database for PostgresDatabaseClient {
     }
 
     async fn get_message(&self, id: &MessageId, message_type: MessageType) -> Result<Message> {
-        let table_name = self.table_for_message_type(message_type);
-
-        // Build SQL query
-        let sql = format!("SELECT id, payload FROM {} WHERE id = $1", table_name);
-
-        // Execute query
-        match self.db.pool.acquire().await {
-            Ok(mut conn) => {
-                let row = sqlx::query(&sql).bind(id.value()).fetch_one(&mut *conn).await.map_err(
-                    |e| {
-                        if e.to_string().contains("no rows") {
-                            DataAccessError::NotFound(format!("Message with ID {} not found", id))
-                        } else {
-                            DataAccessError::Database(e)
-                        }
-                    },
-                )?;
-
-                // Extract data
-                let id: String = row.try_get("id").map_err(DataAccessError::Database)?;
-
-                let payload: Vec<u8> = row.try_get("payload").map_err(DataAccessError::Database)?;
-
-                Ok(Message::new(id, message_type, payload))
-            },
-            Err(e) => {
-                error!("Failed to acquire database connection: {}", e);
-                Err(DataAccessError::Database(e))
-            },
-        }
+        let hash = hex::decode(id.value().trim_start_matches("0x"))
+            .map_err(|_| DataAccessError::NotFound(format!("Message with ID {} not found", id)))?;
+
+        let row = sqlx::query!(
+            r#"
+            SELECT raw, body
+            FROM messages
+            WHERE hash = $1 AND type = ANY($2) AND deleted_at IS NULL
+            "#,
+            &hash,
+            &hub_messages::proto_message_types(message_type)
+        )
+        .fetch_optional(&self.db.pool)
+        .await?
+        .ok_or_else(|| DataAccessError::NotFound(format!("Message with ID {} not found", id)))?;
+
+        Ok(Message::new(
+            hex::encode(&hash),
+            message_type,
+            hub_messages::stored_payload(row.raw, row.body)?,
+        ))
     }
 
     async fn get_messages_by_fid(
@@ -235,65 +215,754 @@ impl Database for PostgresDatabaseClient {
         limit: usize,
         cursor: Option<MessageId>,
     ) -> Result<Vec<Message>> {
-        let table_name = self.table_for_message_type(message_type);
-
-        // Convert FID to i64 for database
-        let fid_value = fid.value() as i64;
-
-        // Execute query
-        match self.db.pool.acquire().await {
-            Ok(mut conn) => {
-                let rows = match cursor {
-                    Some(cursor) => {
-                        // Build SQL query with cursor-based pagination
-                        let sql = format!(
-                            "SELECT id, payload FROM {} WHERE fid = $1 AND id > $2 ORDER BY id LIMIT $3",
-                            table_name
-                        );
-
-                        sqlx::query(&sql)
-                            .bind(fid_value)
-                            .bind(cursor.value())
-                            .bind(limit as i64)
-                            .fetch_all(&mut *conn)
-                            .await?
-                    },
-                    None => {
-                        // Build SQL query without cursor
-                        let sql = format!(
-                            "SELECT id, payload FROM {} WHERE fid = $1 ORDER BY id LIMIT $2",
-                            table_name
-                        );
-
-                        sqlx::query(&sql)
-                            .bind(fid_value)
-                            .bind(limit as i64)
-                            .fetch_all(&mut *conn)
-                            .await?
-                    },
-                };
-
-                // Process results
-                let mut messages = Vec::with_capacity(rows.len());
-                for row in rows {
-                    let id: String = row.try_get("id").map_err(DataAccessError::Database)?;
-
-                    let payload: Vec<u8> =
-                        row.try_get("payload").map_err(DataAccessError::Database)?;
-
-                    messages.push(Message::new(id, message_type, payload));
-                }
-
-                Ok(messages)
-            },
-            Err(e) => {
-                error!("Failed to acquire database connection: {}", e);
-                Err(DataAccessError::Database(e))
-            },
-        }
-    }
+        // The cursor is the ID (hex hash) of the last message of the previous page
+        let cursor = cursor
+            .map(|c| hex::decode(c.value().trim_start_matches("0x")))
+            .transpose()
+            .map_err(|e| DataAccessError::Other(format!("Invalid cursor: {}", e)))?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT m.hash, m.raw, m.body
+            FROM messages m
+            WHERE m.fid = $1
+              AND m.type = ANY($2)
+              AND m.deleted_at IS NULL
+              AND ($3::bytea IS NULL OR (m.timestamp, m.hash) < (
+                  SELECT c.timestamp, c.hash FROM messages c WHERE c.hash = $3
+              ))
+            ORDER BY m.timestamp DESC, m.hash DESC
+            LIMIT $4
+            "#,
+            fid.value() as i64,
+            &hub_messages::proto_message_types(message_type),
+            cursor,
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
 
+        rows.into_iter()
+            .map(|row| {
+                Ok(Message::new(
+                    hex::encode(&row.hash),
+                    message_type,
+                    hub_messages::stored_payload(row.raw, row.body)?,
+                ))
+            })
+            .collect()
+    }
     async fn delete_message(&self, _id: &MessageId, _message_type: MessageType) -> Result<()> {
         Err(DataAccessError::Other("Write operations not supported".to_string()))
     }
+
+    async fn get_fid_registration(&self, fid: Fid) -> Result<Option<FidRegistration>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT fid, custody_address, recovery_address, registered_at, last_transfer_at,
+                   transfer_count, chain_id
+            FROM fids
+            WHERE fid = $1 AND deleted_at IS NULL
+            "#,
+            fid.value() as i64
+        )
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        Ok(row.map(|row| FidRegistration {
+            fid: Fid::new(row.fid as u64),
+            custody_address: row.custody_address,
+            recovery_address: row.recovery_address,
+            registered_at: row.registered_at.map(|t| t.unix_timestamp() as u64),
+            last_transfer_at: row.last_transfer_at.map(|t| t.unix_timestamp() as u64),
+            transfer_count: row.transfer_count as u32,
+            chain_id: row.chain_id as u64,
+        }))
+    }
+
+    async fn get_fid_by_custody_address(&self, address: &[u8]) -> Result<Option<FidRegistration>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT fid, custody_address, recovery_address, registered_at, last_transfer_at,
+                   transfer_count, chain_id
+            FROM fids
+            WHERE custody_address = $1 AND deleted_at IS NULL
+            ORDER BY last_event_block_number DESC, last_event_log_index DESC
+            LIMIT 1
+            "#,
+            address
+        )
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        Ok(row.map(|row| FidRegistration {
+            fid: Fid::new(row.fid as u64),
+            custody_address: row.custody_address,
+            recovery_address: row.recovery_address,
+            registered_at: row.registered_at.map(|t| t.unix_timestamp() as u64),
+            last_transfer_at: row.last_transfer_at.map(|t| t.unix_timestamp() as u64),
+            transfer_count: row.transfer_count as u32,
+            chain_id: row.chain_id as u64,
+        }))
+    }
+
+    async fn get_signers_by_fid(&self, fid: Fid, include_removed: bool) -> Result<Vec<Signer>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT fid, key, key_type, request_fid, added_at, removed_at, removal_type,
+                   migrated_at
+            FROM signers
+            WHERE fid = $1
+              AND deleted_at IS NULL
+              AND ($2 OR removed_at IS NULL)
+            ORDER BY added_at DESC NULLS LAST
+            "#,
+            fid.value() as i64,
+            include_removed
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Signer {
+                fid: Fid::new(row.fid as u64),
+                key: row.key,
+                key_type: row.key_type as u32,
+                request_fid: row.request_fid.map(|f| f as u64),
+                status: match (row.removed_at, row.removal_type) {
+                    (None, _) => SignerStatus::Active,
+                    (Some(_), Some(3)) => SignerStatus::AdminReset,
+                    (Some(_), _) => SignerStatus::Removed,
+                },
+                added_at: row.added_at.map(|t| t.unix_timestamp() as u64),
+                removed_at: row.removed_at.map(|t| t.unix_timestamp() as u64),
+                migrated_at: row.migrated_at.map(|t| t.unix_timestamp() as u64),
+            })
+            .collect())
+    }
+
+    async fn get_app_signer_stats(&self, fid: Fid) -> Result<Vec<AppSignerStats>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT s.request_fid,
+                   count(DISTINCT s.key) AS "signer_count!",
+                   count(DISTINCT s.key) FILTER (WHERE s.removed_at IS NULL) AS "active_signer_count!",
+                   count(m.id) AS "message_count!"
+            FROM signers s
+            LEFT JOIN messages m
+                   ON m.fid = s.fid AND m.signer = s.key AND m.deleted_at IS NULL
+            WHERE s.fid = $1 AND s.deleted_at IS NULL
+            GROUP BY s.request_fid
+            ORDER BY 4 DESC
+            "#,
+            fid.value() as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| AppSignerStats {
+                request_fid: row.request_fid.map(|f| f as u64),
+                signer_count: row.signer_count as u64,
+                active_signer_count: row.active_signer_count as u64,
+                message_count: row.message_count as u64,
+            })
+            .collect())
+    }
+
+    async fn get_channel(&self, channel: &str) -> Result<Option<Channel>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT url, channel_id, name, description, lead_fid, first_cast_at, last_cast_at,
+                   cast_count, reply_count
+            FROM channels
+            WHERE (url = $1 OR channel_id = $1) AND deleted_at IS NULL
+            ORDER BY url = $1 DESC
+            LIMIT 1
+            "#,
+            channel
+        )
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        Ok(row.map(|row| Channel {
+            url: row.url,
+            channel_id: row.channel_id,
+            name: row.name,
+            description: row.description,
+            lead_fid: row.lead_fid.map(|f| f as u64),
+            first_cast_at: row.first_cast_at.map(|t| t.unix_timestamp() as u64),
+            last_cast_at: row.last_cast_at.map(|t| t.unix_timestamp() as u64),
+            cast_count: row.cast_count as u64,
+            reply_count: row.reply_count as u64,
+        }))
+    }
+
+    async fn get_channel_activity(&self, url: &str, days: u32) -> Result<Vec<ChannelActivity>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT day, cast_count, reply_count
+            FROM channel_activity
+            WHERE url = $1 AND day > (now() AT TIME ZONE 'UTC')::date - $2::integer
+            ORDER BY day DESC
+            "#,
+            url,
+            days as i32
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ChannelActivity {
+                day: row.day.to_string(),
+                cast_count: row.cast_count as u64,
+                reply_count: row.reply_count as u64,
+            })
+            .collect())
+    }
+
+    async fn get_user_data_by_fid(&self, fid: Fid, limit: usize) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            UserDataRow,
+            r#"
+            SELECT hash, fid, timestamp, type AS data_type, value
+            FROM user_data
+            WHERE fid = $1 AND deleted_at IS NULL
+            ORDER BY type
+            LIMIT $2
+            "#,
+            fid.value() as i64,
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(UserDataRow::into_message).collect()
+    }
+
+    async fn get_user_data(&self, fid: Fid, data_type: &str) -> Result<Option<Message>> {
+        let row = sqlx::query_as!(
+            UserDataRow,
+            r#"
+            SELECT hash, fid, timestamp, type AS data_type, value
+            FROM user_data
+            WHERE fid = $1 AND type = $2 AND deleted_at IS NULL
+            "#,
+            fid.value() as i64,
+            hub_messages::user_data_type(data_type)?
+        )
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        row.map(UserDataRow::into_message).transpose()
+    }
+
+    async fn get_username_proofs_by_fid(&self, fid: Fid) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            UsernameProofRow,
+            r#"
+            SELECT fid, timestamp, username, type AS proof_type, signature, owner
+            FROM username_proofs
+            WHERE fid = $1 AND deleted_at IS NULL
+            ORDER BY timestamp DESC
+            "#,
+            fid.value() as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(UsernameProofRow::into_message).collect()
+    }
+
+    async fn get_username_proof_by_name(
+        &self,
+        name: &str,
+    ) -> Result<Option<crate::proto::UserNameProof>> {
+        let row = sqlx::query_as!(
+            UsernameProofRow,
+            r#"
+            SELECT fid, timestamp, username, type AS proof_type, signature, owner
+            FROM username_proofs
+            WHERE username = $1 AND deleted_at IS NULL
+            ORDER BY timestamp DESC
+            LIMIT 1
+            "#,
+            name
+        )
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        Ok(row.map(UsernameProofRow::into_proof))
+    }
+
+    async fn get_verifications_by_fid(&self, fid: Fid, limit: usize) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            VerificationRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, signer_address, block_hash, signature, protocol
+            FROM verifications
+            WHERE fid = $1 AND deleted_at IS NULL
+            ORDER BY timestamp DESC
+            LIMIT $2
+            "#,
+            fid.value() as i64,
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(VerificationRow::into_message).collect()
+    }
+
+    async fn get_verification(&self, fid: Fid, address: &[u8]) -> Result<Option<Message>> {
+        let row = sqlx::query_as!(
+            VerificationRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, signer_address, block_hash, signature, protocol
+            FROM verifications
+            WHERE fid = $1 AND signer_address = $2 AND deleted_at IS NULL
+            "#,
+            fid.value() as i64,
+            address
+        )
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        row.map(VerificationRow::into_message).transpose()
+    }
+
+    async fn get_casts_by_fid(&self, fid: Fid, limit: usize) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            CastRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, text, parent_fid, parent_hash, parent_url,
+                   embeds, mentions, mentions_positions
+            FROM casts
+            WHERE fid = $1 AND deleted_at IS NULL
+            ORDER BY timestamp DESC
+            LIMIT $2
+            "#,
+            fid.value() as i64,
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(CastRow::into_message).collect()
+    }
+
+    async fn get_cast(&self, fid: Fid, hash: &[u8]) -> Result<Option<Message>> {
+        let row = sqlx::query_as!(
+            CastRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, text, parent_fid, parent_hash, parent_url,
+                   embeds, mentions, mentions_positions
+            FROM casts
+            WHERE hash = $1 AND fid = $2 AND deleted_at IS NULL
+            "#,
+            hash,
+            fid.value() as i64
+        )
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        row.map(CastRow::into_message).transpose()
+    }
+
+    async fn get_casts_by_mention(&self, fid: Fid, limit: usize) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            CastRow,
+            r#"
+            SELECT c.hash, c.fid AS "fid!", c.timestamp, c.text, c.parent_fid, c.parent_hash,
+                   c.parent_url, c.embeds, c.mentions, c.mentions_positions
+            FROM casts c
+            WHERE c.hash IN (
+                SELECT m.cast_hash
+                FROM cast_mentions m
+                WHERE m.fid = $1 AND m.deleted_at IS NULL
+                ORDER BY m.timestamp DESC
+                LIMIT $2
+            )
+              AND c.deleted_at IS NULL
+              AND c.fid IS NOT NULL
+            ORDER BY c.timestamp DESC
+            "#,
+            fid.value() as i64,
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(CastRow::into_message).collect()
+    }
+
+    async fn get_casts_by_parent(
+        &self,
+        parent_fid: Fid,
+        parent_hash: &[u8],
+        limit: usize,
+    ) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            CastRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, text, parent_fid, parent_hash, parent_url,
+                   embeds, mentions, mentions_positions
+            FROM casts
+            WHERE parent_hash = $1 AND parent_fid = $2
+              AND deleted_at IS NULL AND fid IS NOT NULL
+            ORDER BY timestamp DESC
+            LIMIT $3
+            "#,
+            parent_hash,
+            parent_fid.value() as i64,
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(CastRow::into_message).collect()
+    }
+
+    async fn get_casts_by_parent_url(
+        &self,
+        parent_url: &str,
+        limit: usize,
+    ) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            CastRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, text, parent_fid, parent_hash, parent_url,
+                   embeds, mentions, mentions_positions
+            FROM casts
+            WHERE parent_url = $1 AND deleted_at IS NULL AND fid IS NOT NULL
+            ORDER BY timestamp DESC
+            LIMIT $2
+            "#,
+            parent_url,
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(CastRow::into_message).collect()
+    }
+
+    async fn get_all_casts_by_fid(
+        &self,
+        fid: Fid,
+        limit: usize,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            CastRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, text, parent_fid, parent_hash, parent_url,
+                   embeds, mentions, mentions_positions
+            FROM casts
+            WHERE fid = $1
+              AND deleted_at IS NULL
+              AND ($2::timestamptz IS NULL OR timestamp >= $2)
+              AND ($3::timestamptz IS NULL OR timestamp <= $3)
+            ORDER BY timestamp DESC
+            LIMIT $4
+            "#,
+            fid.value() as i64,
+            hub_messages::from_farcaster_bound(start_time),
+            hub_messages::from_farcaster_bound(end_time),
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(CastRow::into_message).collect()
+    }
+
+    async fn get_reaction(
+        &self,
+        fid: Fid,
+        reaction_type: u8,
+        target_cast_fid: Option<Fid>,
+        target_cast_hash: Option<&[u8]>,
+        target_url: Option<&str>,
+    ) -> Result<Option<Message>> {
+        let row = sqlx::query_as!(
+            ReactionRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, type AS reaction_type, target_cast_fid,
+                   target_cast_hash, target_url
+            FROM reactions
+            WHERE fid = $1
+              AND type = $2
+              AND deleted_at IS NULL
+              AND (($3::bigint IS NOT NULL AND target_cast_fid = $3 AND target_cast_hash = $4)
+                   OR ($5::text IS NOT NULL AND target_url = $5))
+            LIMIT 1
+            "#,
+            fid.value() as i64,
+            reaction_type as i16,
+            target_cast_fid.map(|f| f.value() as i64),
+            target_cast_hash,
+            target_url
+        )
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        row.map(ReactionRow::into_message).transpose()
+    }
+
+    async fn get_reactions_by_fid(
+        &self,
+        fid: Fid,
+        reaction_type: Option<u8>,
+        limit: usize,
+    ) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            ReactionRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, type AS reaction_type, target_cast_fid,
+                   target_cast_hash, target_url
+            FROM reactions
+            WHERE fid = $1
+              AND ($2::smallint IS NULL OR type = $2)
+              AND deleted_at IS NULL
+            ORDER BY timestamp DESC
+            LIMIT $3
+            "#,
+            fid.value() as i64,
+            reaction_type.map(i16::from),
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(ReactionRow::into_message).collect()
+    }
+
+    async fn get_reactions_by_target(
+        &self,
+        target_cast_fid: Option<Fid>,
+        target_cast_hash: Option<&[u8]>,
+        target_url: Option<&str>,
+        reaction_type: Option<u8>,
+        limit: usize,
+    ) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            ReactionRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, type AS reaction_type, target_cast_fid,
+                   target_cast_hash, target_url
+            FROM reactions
+            WHERE (($1::bytea IS NOT NULL AND target_cast_hash = $1
+                    AND ($2::bigint IS NULL OR target_cast_fid = $2))
+                   OR ($3::text IS NOT NULL AND target_url = $3))
+              AND ($4::smallint IS NULL OR type = $4)
+              AND deleted_at IS NULL
+              AND fid IS NOT NULL
+            ORDER BY timestamp DESC
+            LIMIT $5
+            "#,
+            target_cast_hash,
+            target_cast_fid.map(|f| f.value() as i64),
+            target_url,
+            reaction_type.map(i16::from),
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(ReactionRow::into_message).collect()
+    }
+
+    async fn get_all_reactions_by_fid(
+        &self,
+        fid: Fid,
+        limit: usize,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            ReactionRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, type AS reaction_type, target_cast_fid,
+                   target_cast_hash, target_url
+            FROM reactions
+            WHERE fid = $1
+              AND deleted_at IS NULL
+              AND ($2::timestamptz IS NULL OR timestamp >= $2)
+              AND ($3::timestamptz IS NULL OR timestamp <= $3)
+            ORDER BY timestamp DESC
+            LIMIT $4
+            "#,
+            fid.value() as i64,
+            hub_messages::from_farcaster_bound(start_time),
+            hub_messages::from_farcaster_bound(end_time),
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(ReactionRow::into_message).collect()
+    }
+
+    async fn get_all_verification_messages_by_fid(
+        &self,
+        fid: Fid,
+        limit: usize,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            VerificationRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, signer_address, block_hash, signature, protocol
+            FROM verifications
+            WHERE fid = $1
+              AND deleted_at IS NULL
+              AND ($2::timestamptz IS NULL OR timestamp >= $2)
+              AND ($3::timestamptz IS NULL OR timestamp <= $3)
+            ORDER BY timestamp DESC
+            LIMIT $4
+            "#,
+            fid.value() as i64,
+            hub_messages::from_farcaster_bound(start_time),
+            hub_messages::from_farcaster_bound(end_time),
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(VerificationRow::into_message).collect()
+    }
+
+    async fn get_link(
+        &self,
+        fid: Fid,
+        link_type: &str,
+        target_fid: Fid,
+    ) -> Result<Option<Message>> {
+        let row = sqlx::query_as!(
+            LinkRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, type AS link_type, target_fid,
+                   display_timestamp
+            FROM links
+            WHERE fid = $1 AND type = $2 AND target_fid = $3 AND deleted_at IS NULL
+            "#,
+            fid.value() as i64,
+            link_type,
+            target_fid.value() as i64
+        )
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        row.map(LinkRow::into_message).transpose()
+    }
+
+    async fn get_links_by_fid(
+        &self,
+        fid: Fid,
+        link_type: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            LinkRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, type AS link_type, target_fid,
+                   display_timestamp
+            FROM links
+            WHERE fid = $1
+              AND ($2::text IS NULL OR type = $2)
+              AND deleted_at IS NULL
+            ORDER BY timestamp DESC
+            LIMIT $3
+            "#,
+            fid.value() as i64,
+            link_type,
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(LinkRow::into_message).collect()
+    }
+
+    async fn get_links_by_target(
+        &self,
+        target_fid: Fid,
+        link_type: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            LinkRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, type AS link_type, target_fid,
+                   display_timestamp
+            FROM links
+            WHERE target_fid = $1
+              AND ($2::text IS NULL OR type = $2)
+              AND deleted_at IS NULL
+              AND fid IS NOT NULL
+            ORDER BY timestamp DESC
+            LIMIT $3
+            "#,
+            target_fid.value() as i64,
+            link_type,
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(LinkRow::into_message).collect()
+    }
+
+    async fn get_link_compact_state_by_fid(&self, fid: Fid) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            LinkCompactStateRow,
+            r#"
+            SELECT hash, fid, timestamp, type AS link_type, target_fids
+            FROM link_compact_states
+            WHERE fid = $1 AND deleted_at IS NULL
+            "#,
+            fid.value() as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(LinkCompactStateRow::into_message).collect()
+    }
+
+    async fn get_all_links_by_fid(
+        &self,
+        fid: Fid,
+        limit: usize,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> Result<Vec<Message>> {
+        let rows = sqlx::query_as!(
+            LinkRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, type AS link_type, target_fid,
+                   display_timestamp
+            FROM links
+            WHERE fid = $1
+              AND deleted_at IS NULL
+              AND ($2::timestamptz IS NULL OR timestamp >= $2)
+              AND ($3::timestamptz IS NULL OR timestamp <= $3)
+            ORDER BY timestamp DESC
+            LIMIT $4
+            "#,
+            fid.value() as i64,
+            hub_messages::from_farcaster_bound(start_time),
+            hub_messages::from_farcaster_bound(end_time),
+            limit as i64
+        )
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        rows.into_iter().map(LinkRow::into_message).collect()
+    }
 }
@@ -0,0 +1,477 @@
+//! Derived registries maintained from onchain events.
+//!
+//! - `fids` holds the current custody/recovery state per FID (IdRegistry).
+//! - `signers` holds the current state of every app key per FID (KeyRegistry).
+//!
+//! Rows are always recomputed from the append-only event tables rather than
+//! patched in place, so out-of-order delivery (live stream vs. backfill)
+//! converges on the same state and both tables can be rebuilt at any time.
+
+use crate::core::util::decode_signed_key_request_metadata;
+use sqlx::{PgConnection, PgPool, types::time::OffsetDateTime};
+use tracing::info;
+
+/// KeyRegistry signer event types as stored in `signer_events.event_type`
+const SIGNER_EVENT_ADD: i16 = 1;
+const SIGNER_EVENT_REMOVE: i16 = 2;
+const SIGNER_EVENT_ADMIN_RESET: i16 = 3;
+
+/// SignedKeyRequest metadata type
+const METADATA_TYPE_SIGNED_KEY_REQUEST: i16 = 1;
+
+/// Recompute the `fids` rows for the given FIDs from `id_register_events`.
+///
+/// Returns the number of rows written.
+pub async fn refresh_fids(conn: &mut PgConnection, fids: &[i64]) -> Result<u64, sqlx::Error> {
+    if fids.is_empty() {
+        return Ok(0);
+    }
+
+    let result = sqlx::query!(
+        r#"
+        WITH ev AS (
+            SELECT fid, event_type, to_address, recovery_address, block_number, log_index,
+                   tx_hash, block_timestamp, chain_id
+            FROM id_register_events
+            WHERE fid = ANY($1::bigint[]) AND deleted_at IS NULL
+        ),
+        latest AS (
+            SELECT DISTINCT ON (fid) fid, block_number, log_index, chain_id
+            FROM ev
+            ORDER BY fid, block_number DESC, log_index DESC
+        ),
+        custody AS (
+            SELECT DISTINCT ON (fid) fid, to_address
+            FROM ev
+            WHERE event_type IN (1, 2)
+            ORDER BY fid, block_number DESC, log_index DESC
+        ),
+        recovery AS (
+            SELECT DISTINCT ON (fid) fid, recovery_address
+            FROM ev
+            WHERE event_type IN (1, 3)
+            ORDER BY fid, block_number DESC, log_index DESC
+        ),
+        registration AS (
+            SELECT DISTINCT ON (fid) fid, block_timestamp, block_number, tx_hash
+            FROM ev
+            WHERE event_type = 1
+            ORDER BY fid, block_number, log_index
+        ),
+        transfers AS (
+            SELECT fid,
+                   max(block_timestamp) AS last_transfer_at,
+                   max(block_number) AS last_transfer_block_number,
+                   count(*)::integer AS transfer_count
+            FROM ev
+            WHERE event_type = 2
+            GROUP BY fid
+        )
+        INSERT INTO fids (
+            fid,
+            custody_address,
+            recovery_address,
+            registered_at,
+            register_block_number,
+            register_tx_hash,
+            last_transfer_at,
+            last_transfer_block_number,
+            transfer_count,
+            last_event_block_number,
+            last_event_log_index,
+            chain_id
+        )
+        SELECT l.fid,
+               c.to_address,
+               r.recovery_address,
+               g.block_timestamp,
+               g.block_number,
+               g.tx_hash,
+               t.last_transfer_at,
+               t.last_transfer_block_number,
+               COALESCE(t.transfer_count, 0),
+               l.block_number,
+               l.log_index,
+               l.chain_id
+        FROM latest l
+        LEFT JOIN custody c ON c.fid = l.fid
+        LEFT JOIN recovery r ON r.fid = l.fid
+        LEFT JOIN registration g ON g.fid = l.fid
+        LEFT JOIN transfers t ON t.fid = l.fid
+        ON CONFLICT (fid) DO UPDATE SET
+            custody_address = EXCLUDED.custody_address,
+            recovery_address = EXCLUDED.recovery_address,
+            registered_at = EXCLUDED.registered_at,
+            register_block_number = EXCLUDED.register_block_number,
+            register_tx_hash = EXCLUDED.register_tx_hash,
+            last_transfer_at = EXCLUDED.last_transfer_at,
+            last_transfer_block_number = EXCLUDED.last_transfer_block_number,
+            transfer_count = EXCLUDED.transfer_count,
+            last_event_block_number = EXCLUDED.last_event_block_number,
+            last_event_log_index = EXCLUDED.last_event_log_index,
+            chain_id = EXCLUDED.chain_id,
+            deleted_at = NULL
+        "#,
+        fids
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Rebuild the whole `fids` table from `id_register_events`, `batch_size` FIDs at a time.
+///
+/// Returns the number of FIDs refreshed.
+pub async fn rebuild_fids(pool: &PgPool, batch_size: usize) -> Result<u64, sqlx::Error> {
+    let mut last_fid: i64 = 0;
+    let mut total: u64 = 0;
+
+    loop {
+        let fids = sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT fid AS "fid!"
+            FROM id_register_events
+            WHERE fid > $1 AND deleted_at IS NULL
+            ORDER BY fid
+            LIMIT $2
+            "#,
+            last_fid,
+            batch_size as i64
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let Some(&max_fid) = fids.last() else {
+            break;
+        };
+
+        total += refresh_fids(&mut *pool.acquire().await?, &fids).await?;
+        last_fid = max_fid;
+
+        info!("Rebuilt fids registry through FID {} ({} FIDs so far)", last_fid, total);
+    }
+
+    Ok(total)
+}
+
+/// A `signer_events` row, as needed to derive signer state
+#[derive(Debug, Clone)]
+pub struct SignerEventRow {
+    pub fid: i64,
+    pub key: Vec<u8>,
+    pub key_type: i16,
+    pub event_type: i16,
+    pub metadata: Option<Vec<u8>>,
+    pub metadata_type: Option<i16>,
+    pub block_number: i64,
+    pub log_index: i32,
+    pub block_timestamp: OffsetDateTime,
+    pub chain_id: i64,
+}
+
+/// Current state of one (fid, key) pair, ready to upsert into `signers`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignerRow {
+    pub fid: i64,
+    pub key: Vec<u8>,
+    pub key_type: i16,
+    pub metadata_type: Option<i16>,
+    pub request_fid: Option<i64>,
+    pub request_signer: Option<Vec<u8>>,
+    pub added_at: Option<OffsetDateTime>,
+    pub removed_at: Option<OffsetDateTime>,
+    pub removal_type: Option<i16>,
+    pub last_event_block_number: i64,
+    pub last_event_log_index: i32,
+    pub chain_id: i64,
+}
+
+impl SignerRow {
+    /// Fold the events of a single (fid, key) pair, given in chain order, into its current state.
+    pub fn fold(events: &[SignerEventRow]) -> Option<Self> {
+        let first = events.first()?;
+        let mut row = Self {
+            fid: first.fid,
+            key: first.key.clone(),
+            key_type: first.key_type,
+            metadata_type: None,
+            request_fid: None,
+            request_signer: None,
+            added_at: None,
+            removed_at: None,
+            removal_type: None,
+            last_event_block_number: first.block_number,
+            last_event_log_index: first.log_index,
+            chain_id: first.chain_id,
+        };
+
+        for event in events {
+            match event.event_type {
+                SIGNER_EVENT_ADD => {
+                    let request = (event.metadata_type == Some(METADATA_TYPE_SIGNED_KEY_REQUEST))
+                        .then(|| {
+                            event.metadata.as_deref().and_then(decode_signed_key_request_metadata)
+                        })
+                        .flatten();
+
+                    row.key_type = event.key_type;
+                    row.metadata_type = event.metadata_type;
+                    row.request_fid = request.as_ref().map(|r| r.request_fid as i64);
+                    row.request_signer = request.map(|r| r.request_signer);
+                    row.added_at = Some(event.block_timestamp);
+                    row.removed_at = None;
+                    row.removal_type = None;
+                },
+                SIGNER_EVENT_REMOVE | SIGNER_EVENT_ADMIN_RESET => {
+                    row.removed_at = Some(event.block_timestamp);
+                    row.removal_type = Some(event.event_type);
+                },
+                _ => {},
+            }
+            row.last_event_block_number = event.block_number;
+            row.last_event_log_index = event.log_index;
+            row.chain_id = event.chain_id;
+        }
+
+        Some(row)
+    }
+}
+
+/// Recompute the `signers` rows for every key of the given FIDs from `signer_events`.
+///
+/// Returns the number of rows written.
+pub async fn refresh_signers(conn: &mut PgConnection, fids: &[i64]) -> Result<u64, sqlx::Error> {
+    if fids.is_empty() {
+        return Ok(0);
+    }
+
+    let events: Vec<SignerEventRow> = sqlx::query!(
+        r#"
+        SELECT fid, key, key_type, event_type, metadata, metadata_type, block_number, log_index,
+               block_timestamp, chain_id
+        FROM signer_events
+        WHERE fid = ANY($1::bigint[]) AND deleted_at IS NULL
+        ORDER BY fid, key, block_number, log_index
+        "#,
+        fids
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|r| SignerEventRow {
+        fid: r.fid,
+        key: r.key,
+        key_type: r.key_type,
+        event_type: r.event_type,
+        metadata: r.metadata,
+        metadata_type: r.metadata_type,
+        block_number: r.block_number,
+        log_index: r.log_index,
+        block_timestamp: r.block_timestamp,
+        chain_id: r.chain_id,
+    })
+    .collect();
+
+    let rows: Vec<SignerRow> = events
+        .chunk_by(|a, b| a.fid == b.fid && a.key == b.key)
+        .filter_map(SignerRow::fold)
+        .collect();
+
+    upsert_signers(conn, &rows).await
+}
+
+/// Upsert folded signer rows, stamping `migrated_at` from `signer_migrated_events`
+async fn upsert_signers(conn: &mut PgConnection, rows: &[SignerRow]) -> Result<u64, sqlx::Error> {
+    if rows.is_empty() {
+        return Ok(0);
+    }
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO signers (
+            fid, key, key_type, metadata_type, request_fid, request_signer, added_at,
+            removed_at, removal_type, last_event_block_number, last_event_log_index, chain_id,
+            migrated_at
+        )
+        SELECT u.*,
+               (SELECT to_timestamp(min(m.migrated_at))
+                FROM signer_migrated_events m
+                WHERE m.deleted_at IS NULL
+                  AND m.fid IN (0, u.fid)
+                  AND to_timestamp(m.migrated_at) >= u.added_at)
+        FROM unnest(
+            $1::bigint[], $2::bytea[], $3::smallint[], $4::smallint[], $5::bigint[], $6::bytea[],
+            $7::timestamptz[], $8::timestamptz[], $9::smallint[], $10::bigint[], $11::integer[],
+            $12::bigint[]
+        ) AS u(
+            fid, key, key_type, metadata_type, request_fid, request_signer, added_at,
+            removed_at, removal_type, last_event_block_number, last_event_log_index, chain_id
+        )
+        ON CONFLICT (fid, key) DO UPDATE SET
+            key_type = EXCLUDED.key_type,
+            metadata_type = EXCLUDED.metadata_type,
+            request_fid = EXCLUDED.request_fid,
+            request_signer = EXCLUDED.request_signer,
+            added_at = EXCLUDED.added_at,
+            removed_at = EXCLUDED.removed_at,
+            removal_type = EXCLUDED.removal_type,
+            migrated_at = EXCLUDED.migrated_at,
+            last_event_block_number = EXCLUDED.last_event_block_number,
+            last_event_log_index = EXCLUDED.last_event_log_index,
+            chain_id = EXCLUDED.chain_id,
+            deleted_at = NULL
+        "#,
+    )
+    .bind(rows.iter().map(|r| r.fid).collect::<Vec<_>>())
+    .bind(rows.iter().map(|r| r.key.clone()).collect::<Vec<_>>())
+    .bind(rows.iter().map(|r| r.key_type).collect::<Vec<_>>())
+    .bind(rows.iter().map(|r| r.metadata_type).collect::<Vec<_>>())
+    .bind(rows.iter().map(|r| r.request_fid).collect::<Vec<_>>())
+    .bind(rows.iter().map(|r| r.request_signer.clone()).collect::<Vec<_>>())
+    .bind(rows.iter().map(|r| r.added_at).collect::<Vec<_>>())
+    .bind(rows.iter().map(|r| r.removed_at).collect::<Vec<_>>())
+    .bind(rows.iter().map(|r| r.removal_type).collect::<Vec<_>>())
+    .bind(rows.iter().map(|r| r.last_event_block_number).collect::<Vec<_>>())
+    .bind(rows.iter().map(|r| r.last_event_log_index).collect::<Vec<_>>())
+    .bind(rows.iter().map(|r| r.chain_id).collect::<Vec<_>>())
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Stamp `migrated_at` on keys carried over by a signer migration.
+///
+/// A migration event with FID 0 applies to every FID.
+pub async fn apply_signer_migration(
+    conn: &mut PgConnection,
+    fid: i64,
+    migrated_at: i64,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE signers
+        SET migrated_at = to_timestamp($2)
+        WHERE ($1::bigint = 0 OR fid = $1)
+          AND added_at <= to_timestamp($2)
+          AND (migrated_at IS NULL OR migrated_at > to_timestamp($2))
+        "#,
+        fid,
+        migrated_at as f64
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Rebuild the whole `signers` table from `signer_events`, `batch_size` FIDs at a time.
+///
+/// Returns the number of signer rows written.
+pub async fn rebuild_signers(pool: &PgPool, batch_size: usize) -> Result<u64, sqlx::Error> {
+    let mut last_fid: i64 = 0;
+    let mut total: u64 = 0;
+
+    loop {
+        let fids = sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT fid AS "fid!"
+            FROM signer_events
+            WHERE fid > $1 AND deleted_at IS NULL
+            ORDER BY fid
+            LIMIT $2
+            "#,
+            last_fid,
+            batch_size as i64
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let Some(&max_fid) = fids.last() else {
+            break;
+        };
+
+        total += refresh_signers(&mut *pool.acquire().await?, &fids).await?;
+        last_fid = max_fid;
+
+        info!("Rebuilt signers through FID {} ({} signers so far)", last_fid, total);
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event_type: i16, block_number: i64, metadata: Option<Vec<u8>>) -> SignerEventRow {
+        SignerEventRow {
+            fid: 42,
+            key: vec![0xab; 32],
+            key_type: 1,
+            event_type,
+            metadata_type: metadata.as_ref().map(|_| METADATA_TYPE_SIGNED_KEY_REQUEST),
+            metadata,
+            block_number,
+            log_index: 0,
+            block_timestamp: OffsetDateTime::from_unix_timestamp(1_700_000_000 + block_number)
+                .unwrap(),
+            chain_id: 10,
+        }
+    }
+
+    fn signed_key_request(request_fid: u64) -> Vec<u8> {
+        let word = |v: u64| {
+            let mut w = [0u8; 32];
+            w[24..].copy_from_slice(&v.to_be_bytes());
+            w
+        };
+        [word(0x20), word(request_fid), [0u8; 32], word(0x80), word(0), word(0)].concat()
+    }
+
+    #[test]
+    fn test_fold_add_decodes_request_fid() {
+        let row =
+            SignerRow::fold(&[event(SIGNER_EVENT_ADD, 1, Some(signed_key_request(9152)))]).unwrap();
+
+        assert_eq!(row.request_fid, Some(9152));
+        assert!(row.added_at.is_some());
+        assert!(row.removed_at.is_none());
+        assert_eq!(row.last_event_block_number, 1);
+    }
+
+    #[test]
+    fn test_fold_remove_keeps_add_metadata() {
+        let row = SignerRow::fold(&[
+            event(SIGNER_EVENT_ADD, 1, Some(signed_key_request(9152))),
+            event(SIGNER_EVENT_REMOVE, 2, None),
+        ])
+        .unwrap();
+
+        assert_eq!(row.request_fid, Some(9152));
+        assert_eq!(row.removal_type, Some(SIGNER_EVENT_REMOVE));
+        assert!(row.removed_at.is_some());
+        assert_eq!(row.last_event_block_number, 2);
+    }
+
+    #[test]
+    fn test_fold_readd_after_admin_reset() {
+        let row = SignerRow::fold(&[
+            event(SIGNER_EVENT_ADD, 1, None),
+            event(SIGNER_EVENT_ADMIN_RESET, 2, None),
+            event(SIGNER_EVENT_ADD, 3, Some(signed_key_request(7))),
+        ])
+        .unwrap();
+
+        assert_eq!(row.request_fid, Some(7));
+        assert!(row.removed_at.is_none());
+        assert!(row.removal_type.is_none());
+        assert_eq!(row.added_at, Some(OffsetDateTime::from_unix_timestamp(1_700_000_003).unwrap()));
+    }
+
+    #[test]
+    fn test_fold_empty() {
+        assert!(SignerRow::fold(&[]).is_none());
+    }
+}
@@ -0,0 +1,251 @@
+//! Root parents of casts as they stream in.
+//!
+//! A reply's `root_parent_*` columns point at the cast, or URL, that started its
+//! thread. [`RootParentCache`] remembers the root that replies to recently stored casts
+//! inherit, so most replies resolve without a query. A reply whose root can't be found,
+//! because its parent hasn't arrived and the Hub can't walk the chain, is queued in
+//! `cast_root_orphans`. When the parent lands, [`adopt_orphans`] gives every queued
+//! descendant the parent's root and takes them off the queue. Both sides hold
+//! [`lock_parent`] on the parent's hash, so a reply queued while its parent is being
+//! stored either sees the parent or is seen by it.
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use sqlx::{PgConnection, types::time::OffsetDateTime};
+use std::num::NonZeroUsize;
+
+/// `root_parent_fid`, `root_parent_hash` and `root_parent_url` of a cast
+pub type RootParent = (Option<i64>, Option<Vec<u8>>, Option<String>);
+
+pub const DEFAULT_CACHE_SIZE: usize = 100_000;
+
+/// Bounded LRU from a cast hash to the root its replies inherit
+pub struct RootParentCache {
+    roots: Mutex<LruCache<Vec<u8>, RootParent>>,
+}
+
+impl RootParentCache {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self { roots: Mutex::new(LruCache::new(capacity)) }
+    }
+
+    pub fn get(&self, hash: &[u8]) -> Option<RootParent> {
+        self.roots.lock().get(hash).cloned()
+    }
+
+    pub fn insert(&self, hash: Vec<u8>, root: RootParent) {
+        self.roots.lock().put(hash, root);
+    }
+
+    pub fn remove(&self, hash: &[u8]) {
+        self.roots.lock().pop(hash);
+    }
+
+    pub fn len(&self) -> usize {
+        self.roots.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for RootParentCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_SIZE)
+    }
+}
+
+/// The root that replies to a cast inherit: the cast itself when it starts a thread,
+/// otherwise the cast's own root. `None` for a reply whose root isn't known yet.
+pub fn inherited_root(
+    fid: i64,
+    hash: &[u8],
+    is_reply: bool,
+    root: &RootParent,
+) -> Option<RootParent> {
+    let (root_fid, root_hash, root_url) = root;
+    if is_reply && root_hash.is_none() && root_url.is_none() {
+        return None;
+    }
+    Some((
+        root_fid.or(Some(fid)),
+        root_hash.clone().or_else(|| Some(hash.to_vec())),
+        root_url.clone(),
+    ))
+}
+
+/// What the database knows about a reply's parent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoredParent {
+    /// Not stored, or deleted
+    Missing,
+    /// Stored, but waiting on its own root
+    Orphan,
+    /// Stored, with the root its replies inherit
+    Root(RootParent),
+}
+
+pub async fn lookup_parent(
+    conn: &mut PgConnection,
+    parent_hash: &[u8],
+) -> Result<StoredParent, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT fid as "fid!", parent_hash, root_parent_fid, root_parent_hash, root_parent_url
+        FROM casts
+        WHERE hash = $1 AND deleted_at IS NULL
+        "#,
+        parent_hash
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(StoredParent::Missing);
+    };
+    let root = (row.root_parent_fid, row.root_parent_hash, row.root_parent_url);
+    Ok(match inherited_root(row.fid, parent_hash, row.parent_hash.is_some(), &root) {
+        Some(root) => StoredParent::Root(root),
+        None => StoredParent::Orphan,
+    })
+}
+
+/// Hold a transaction-level advisory lock on `parent_hash`, serializing replies queued on a
+/// parent with the parent's own adoption of its orphans
+pub async fn lock_parent(conn: &mut PgConnection, parent_hash: &[u8]) -> Result<(), sqlx::Error> {
+    sqlx::query!("SELECT pg_advisory_xact_lock($1)", lock_key(parent_hash))
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Advisory lock key of a cast: the first 8 bytes of its hash. A collision only makes
+/// two unrelated casts wait on each other.
+fn lock_key(hash: &[u8]) -> i64 {
+    let mut key = [0u8; 8];
+    let len = hash.len().min(key.len());
+    key[..len].copy_from_slice(&hash[..len]);
+    i64::from_be_bytes(key)
+}
+
+/// Queue a reply until its parent arrives
+pub async fn queue_orphan(
+    conn: &mut PgConnection,
+    hash: &[u8],
+    parent_hash: &[u8],
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO cast_root_orphans (hash, parent_hash)
+        VALUES ($1, $2)
+        ON CONFLICT (hash) DO UPDATE SET parent_hash = EXCLUDED.parent_hash
+        "#,
+        hash,
+        parent_hash
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// A queued reply that was given its root
+#[derive(Debug, Clone)]
+pub struct AdoptedCast {
+    pub hash: Vec<u8>,
+    pub timestamp: OffsetDateTime,
+}
+
+/// Give `root` to every queued descendant of `parent_hash` and take them off the queue
+pub async fn adopt_orphans(
+    conn: &mut PgConnection,
+    parent_hash: &[u8],
+    root: &RootParent,
+) -> Result<Vec<AdoptedCast>, sqlx::Error> {
+    let (root_fid, root_hash, root_url) = root;
+    sqlx::query_as!(
+        AdoptedCast,
+        r#"
+        WITH RECURSIVE descendants AS (
+            SELECT hash FROM cast_root_orphans WHERE parent_hash = $1
+            UNION
+            SELECT o.hash
+            FROM cast_root_orphans o
+            JOIN descendants d ON o.parent_hash = d.hash
+        ),
+        adopted AS (
+            DELETE FROM cast_root_orphans o
+            USING descendants d
+            WHERE o.hash = d.hash
+            RETURNING o.hash
+        )
+        UPDATE casts
+        SET root_parent_fid = $2,
+            root_parent_hash = $3,
+            root_parent_url = $4
+        FROM adopted
+        WHERE casts.hash = adopted.hash AND casts.deleted_at IS NULL
+        RETURNING casts.hash, casts.timestamp
+        "#,
+        parent_hash,
+        *root_fid,
+        root_hash.as_deref(),
+        root_url.as_deref()
+    )
+    .fetch_all(&mut *conn)
+    .await
+}
+
+/// Take a cast off the queue once its root was found some other way
+pub async fn forget_orphan(conn: &mut PgConnection, hash: &[u8]) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM cast_root_orphans WHERE hash = $1", hash).execute(&mut *conn).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replies_inherit_the_thread_root() {
+        // A thread root is its replies' root
+        assert_eq!(
+            inherited_root(3, &[0xaa], false, &(None, None, None)),
+            Some((Some(3), Some(vec![0xaa]), None))
+        );
+        // A channel cast passes on itself along with the channel URL
+        let url = Some("https://farcaster.xyz/~/channel/dev".to_string());
+        assert_eq!(
+            inherited_root(3, &[0xaa], false, &(None, None, url.clone())),
+            Some((Some(3), Some(vec![0xaa]), url))
+        );
+        // A resolved reply passes on its own root
+        let root = (Some(1), Some(vec![0x01]), None);
+        assert_eq!(inherited_root(3, &[0xaa], true, &root), Some(root));
+        // An orphan has nothing to pass on
+        assert_eq!(inherited_root(3, &[0xaa], true, &(None, None, None)), None);
+    }
+
+    #[test]
+    fn lock_key_uses_the_hash_prefix() {
+        assert_eq!(lock_key(&[0, 0, 0, 0, 0, 0, 1, 2, 0xff, 0xff]), 0x0102);
+        assert_eq!(lock_key(&[0x80]), i64::MIN);
+        assert_eq!(lock_key(&[]), 0);
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        let cache = RootParentCache::new(2);
+        let root = |fid| (Some(fid), Some(vec![fid as u8]), None);
+        cache.insert(vec![1], root(1));
+        cache.insert(vec![2], root(2));
+        assert_eq!(cache.get(&[1]), Some(root(1)));
+        cache.insert(vec![3], root(3));
+        assert_eq!(cache.get(&[2]), None);
+        assert_eq!(cache.get(&[1]), Some(root(1)));
+        cache.remove(&[1]);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(RootParentCache::new(0).roots.lock().cap().get(), 1);
+    }
+}
@@ -0,0 +1,517 @@
+//! Signed Hub messages for the gRPC read server.
+//!
+//! `hub_messages` rebuilds message data from the typed tables, which is enough
+//! for JSON APIs but loses the hash, signer and signature. These reads return
+//! the `messages` rows themselves as `Message` protos, so clients get the same
+//! bytes the Hub served. `messages` keeps every message ever merged, so which
+//! ones are current still comes from the typed tables: a message is returned
+//! while its row there is live.
+//!
+//! Pages are ordered by `(timestamp, hash)`, oldest first unless reversed, and
+//! use the same cursors as `pages`.
+
+use crate::{
+    core::{
+        data_context::{DataAccessError, Result},
+        types::MessageType,
+    },
+    database::{
+        hub_messages::{proto_message_types, stored_payload},
+        pages::{Page, PageCursor},
+    },
+    proto::{self, MessageData, UserNameProof, message_data::Body},
+};
+use prost::Message as _;
+use serde_json::Value;
+use sqlx::{PgPool, Postgres, QueryBuilder, types::time::OffsetDateTime};
+use std::collections::HashMap;
+
+/// A row of `messages`
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoredMessage {
+    pub timestamp: OffsetDateTime,
+    pub hash: Vec<u8>,
+    pub hash_scheme: i16,
+    pub signature_scheme: i16,
+    pub signer: Vec<u8>,
+    pub body: Value,
+    pub raw: Vec<u8>,
+    /// Missing for messages stored before signatures were kept
+    pub signature: Option<Vec<u8>>,
+}
+
+impl StoredMessage {
+    fn cursor(&self) -> PageCursor {
+        PageCursor { timestamp: self.timestamp, hash: self.hash.clone() }
+    }
+
+    /// Rebuild the signed message from the bytes its hash covers. `data_bytes` is set
+    /// when those bytes aren't the canonical encoding of `data`.
+    pub fn into_proto(self) -> Result<proto::Message> {
+        let payload = stored_payload(self.raw, self.body)?;
+        let data = MessageData::decode(payload.as_slice())
+            .map_err(|e| DataAccessError::Serialization(e.to_string()))?;
+        let has_data_bytes = payload != data.encode_to_vec();
+
+        Ok(proto::Message {
+            data: Some(data),
+            hash: self.hash,
+            hash_scheme: self.hash_scheme as i32,
+            signature: self.signature.unwrap_or_default(),
+            signature_scheme: self.signature_scheme as i32,
+            signer: self.signer,
+            data_bytes: has_data_bytes.then_some(payload),
+        })
+    }
+}
+
+/// Target of a reaction
+#[derive(Debug, Clone, Copy)]
+pub enum ReactionTarget<'a> {
+    Cast(&'a [u8]),
+    Url(&'a str),
+}
+
+/// Parent of a cast
+#[derive(Debug, Clone, Copy)]
+pub enum CastParent<'a> {
+    Cast(&'a [u8]),
+    Url(&'a str),
+}
+
+/// Which live messages to read
+#[derive(Debug, Clone, Copy)]
+pub enum MessageQuery<'a> {
+    Cast {
+        fid: i64,
+        hash: &'a [u8],
+    },
+    CastsByFid(i64),
+    CastsByParent(CastParent<'a>),
+    CastsByMention(i64),
+    Reaction {
+        fid: i64,
+        reaction_type: i16,
+        target: ReactionTarget<'a>,
+    },
+    ReactionsByFid {
+        fid: i64,
+        reaction_type: Option<i16>,
+    },
+    ReactionsByTarget {
+        target: ReactionTarget<'a>,
+        reaction_type: Option<i16>,
+    },
+    Link {
+        fid: i64,
+        link_type: &'a str,
+        target_fid: i64,
+    },
+    LinksByFid {
+        fid: i64,
+        link_type: Option<&'a str>,
+    },
+    LinksByTarget {
+        target_fid: i64,
+        link_type: Option<&'a str>,
+    },
+    LinkCompactStateByFid(i64),
+    UserData {
+        fid: i64,
+        data_type: i16,
+    },
+    UserDataByFid(i64),
+    Verification {
+        fid: i64,
+        address: &'a [u8],
+    },
+    VerificationsByFid(i64),
+    /// Every message of a family by an FID, removes included, within Farcaster time bounds
+    AllByFid {
+        fid: i64,
+        family: MessageType,
+        start: Option<OffsetDateTime>,
+        stop: Option<OffsetDateTime>,
+    },
+}
+
+/// Typed tables holding the live adds of a message family
+fn family_tables(family: MessageType) -> &'static [&'static str] {
+    match family {
+        MessageType::Cast => &["casts"],
+        MessageType::Reaction => &["reactions"],
+        MessageType::Link => &["links", "link_compact_states"],
+        MessageType::Verification => &["verifications"],
+        MessageType::UserData => &["user_data"],
+        MessageType::LendStorage => &["lend_storage"],
+        _ => &[],
+    }
+}
+
+/// Remove messages of a family; the Hub keeps them as tombstones, so they are always returned
+fn remove_types(family: MessageType) -> Vec<i16> {
+    use proto::MessageType as P;
+
+    let types: &[P] = match family {
+        MessageType::Cast => &[P::CastRemove],
+        MessageType::Reaction => &[P::ReactionRemove],
+        MessageType::Link => &[P::LinkRemove],
+        MessageType::Verification => &[P::VerificationRemove],
+        _ => &[],
+    };
+    types.iter().map(|t| *t as i16).collect()
+}
+
+/// Push `FROM` and `WHERE` for `query`; the caller appends further conditions with `AND`
+fn push_query<'a>(builder: &mut QueryBuilder<'a, Postgres>, query: MessageQuery<'a>) {
+    let table = match query {
+        MessageQuery::Cast { .. }
+        | MessageQuery::CastsByFid(_)
+        | MessageQuery::CastsByParent(_)
+        | MessageQuery::CastsByMention(_) => Some("casts"),
+        MessageQuery::Reaction { .. }
+        | MessageQuery::ReactionsByFid { .. }
+        | MessageQuery::ReactionsByTarget { .. } => Some("reactions"),
+        MessageQuery::Link { .. }
+        | MessageQuery::LinksByFid { .. }
+        | MessageQuery::LinksByTarget { .. } => Some("links"),
+        MessageQuery::LinkCompactStateByFid(_) => Some("link_compact_states"),
+        MessageQuery::UserData { .. } | MessageQuery::UserDataByFid(_) => Some("user_data"),
+        MessageQuery::Verification { .. } | MessageQuery::VerificationsByFid(_) => {
+            Some("verifications")
+        },
+        MessageQuery::AllByFid { .. } => None,
+    };
+
+    builder.push(" FROM messages m");
+    if let Some(table) = table {
+        builder.push(format!(" JOIN {table} t ON t.hash = m.hash AND t.deleted_at IS NULL"));
+    }
+    builder.push(" WHERE TRUE");
+
+    match query {
+        MessageQuery::Cast { fid, hash } => {
+            builder.push(" AND m.fid = ").push_bind(fid);
+            builder.push(" AND m.hash = ").push_bind(hash);
+        },
+        MessageQuery::CastsByFid(fid)
+        | MessageQuery::LinkCompactStateByFid(fid)
+        | MessageQuery::UserDataByFid(fid)
+        | MessageQuery::VerificationsByFid(fid) => {
+            builder.push(" AND m.fid = ").push_bind(fid);
+        },
+        MessageQuery::CastsByParent(CastParent::Cast(hash)) => {
+            builder.push(" AND t.parent_hash = ").push_bind(hash);
+        },
+        MessageQuery::CastsByParent(CastParent::Url(url)) => {
+            builder.push(" AND t.parent_url = ").push_bind(url);
+        },
+        MessageQuery::CastsByMention(fid) => {
+            builder
+                .push(
+                    " AND EXISTS (SELECT 1 FROM cast_mentions cm WHERE cm.cast_hash = m.hash \
+                     AND cm.deleted_at IS NULL AND cm.fid = ",
+                )
+                .push_bind(fid)
+                .push(")");
+        },
+        MessageQuery::Reaction { fid, reaction_type, target } => {
+            builder.push(" AND m.fid = ").push_bind(fid);
+            builder.push(" AND t.type = ").push_bind(reaction_type);
+            push_reaction_target(builder, target);
+        },
+        MessageQuery::ReactionsByFid { fid, reaction_type } => {
+            builder.push(" AND m.fid = ").push_bind(fid);
+            if let Some(reaction_type) = reaction_type {
+                builder.push(" AND t.type = ").push_bind(reaction_type);
+            }
+        },
+        MessageQuery::ReactionsByTarget { target, reaction_type } => {
+            push_reaction_target(builder, target);
+            if let Some(reaction_type) = reaction_type {
+                builder.push(" AND t.type = ").push_bind(reaction_type);
+            }
+        },
+        MessageQuery::Link { fid, link_type, target_fid } => {
+            builder.push(" AND m.fid = ").push_bind(fid);
+            builder.push(" AND t.type = ").push_bind(link_type);
+            builder.push(" AND t.target_fid = ").push_bind(target_fid);
+        },
+        MessageQuery::LinksByFid { fid, link_type } => {
+            builder.push(" AND m.fid = ").push_bind(fid);
+            if let Some(link_type) = link_type {
+                builder.push(" AND t.type = ").push_bind(link_type);
+            }
+        },
+        MessageQuery::LinksByTarget { target_fid, link_type } => {
+            builder.push(" AND t.target_fid = ").push_bind(target_fid);
+            if let Some(link_type) = link_type {
+                builder.push(" AND t.type = ").push_bind(link_type);
+            }
+        },
+        MessageQuery::UserData { fid, data_type } => {
+            builder.push(" AND m.fid = ").push_bind(fid);
+            builder.push(" AND t.type = ").push_bind(data_type);
+        },
+        MessageQuery::Verification { fid, address } => {
+            builder.push(" AND m.fid = ").push_bind(fid);
+            builder.push(" AND t.signer_address = ").push_bind(address);
+        },
+        MessageQuery::AllByFid { fid, family, start, stop } => {
+            builder.push(" AND m.fid = ").push_bind(fid);
+            builder.push(" AND m.type = ANY(").push_bind(proto_message_types(family)).push(")");
+            builder.push(" AND (m.type = ANY(").push_bind(remove_types(family)).push(")");
+            for table in family_tables(family) {
+                builder.push(format!(
+                    " OR EXISTS (SELECT 1 FROM {table} t WHERE t.hash = m.hash \
+                     AND t.deleted_at IS NULL)"
+                ));
+            }
+            builder.push(")");
+            if let Some(start) = start {
+                builder.push(" AND m.timestamp >= ").push_bind(start);
+            }
+            if let Some(stop) = stop {
+                builder.push(" AND m.timestamp <= ").push_bind(stop);
+            }
+        },
+    }
+}
+
+fn push_reaction_target<'a>(builder: &mut QueryBuilder<'a, Postgres>, target: ReactionTarget<'a>) {
+    match target {
+        ReactionTarget::Cast(hash) => builder.push(" AND t.target_cast_hash = ").push_bind(hash),
+        ReactionTarget::Url(url) => builder.push(" AND t.target_url = ").push_bind(url),
+    };
+}
+
+fn select<'a>(query: MessageQuery<'a>) -> QueryBuilder<'a, Postgres> {
+    let mut builder = QueryBuilder::new(
+        "SELECT m.timestamp, m.hash, m.hash_scheme, m.signature_scheme, m.signer, m.body, m.raw, \
+         m.signature",
+    );
+    push_query(&mut builder, query);
+    builder
+}
+
+/// The live message `query` identifies, if there is one
+pub async fn message(pool: &PgPool, query: MessageQuery<'_>) -> Result<Option<StoredMessage>> {
+    let mut builder = select(query);
+    builder.push(" ORDER BY m.timestamp DESC, m.hash DESC LIMIT 1");
+
+    Ok(builder.build_query_as().fetch_optional(pool).await?)
+}
+
+/// A page of the live messages `query` matches, oldest first unless `reverse`
+pub async fn messages(
+    pool: &PgPool,
+    query: MessageQuery<'_>,
+    cursor: Option<&PageCursor>,
+    reverse: bool,
+    limit: usize,
+) -> Result<Page<StoredMessage>> {
+    let mut builder = select(query);
+    if let Some(cursor) = cursor {
+        builder
+            .push(if reverse {
+                " AND (m.timestamp, m.hash) < ("
+            } else {
+                " AND (m.timestamp, m.hash) > ("
+            })
+            .push_bind(cursor.timestamp)
+            .push(", ")
+            .push_bind(cursor.hash.as_slice())
+            .push(")");
+    }
+    builder.push(if reverse {
+        " ORDER BY m.timestamp DESC, m.hash DESC"
+    } else {
+        " ORDER BY m.timestamp, m.hash"
+    });
+    builder.push(" LIMIT ").push_bind(limit as i64 + 1);
+
+    let rows = builder.build_query_as().fetch_all(pool).await?;
+    Ok(Page::from_rows(rows, limit, StoredMessage::cursor))
+}
+
+#[derive(sqlx::FromRow)]
+struct ProofRow {
+    fid: i64,
+    username: String,
+    proof_type: i16,
+    signature: Vec<u8>,
+    owner: Option<Vec<u8>>,
+    timestamp: OffsetDateTime,
+}
+
+/// Live username proofs, by FID or by name.
+///
+/// `username_proofs` only keeps the message timestamp, so a proof that arrived
+/// in a `UsernameProof` message is returned from that message instead.
+async fn username_proofs(
+    pool: &PgPool,
+    fid: Option<i64>,
+    name: Option<&str>,
+) -> Result<Vec<UserNameProof>> {
+    let mut builder = QueryBuilder::new(
+        "SELECT fid, username, type AS proof_type, signature, owner, timestamp \
+         FROM username_proofs WHERE deleted_at IS NULL",
+    );
+    if let Some(fid) = fid {
+        builder.push(" AND fid = ").push_bind(fid);
+    }
+    if let Some(name) = name {
+        builder.push(" AND username = ").push_bind(name);
+    }
+    builder.push(" ORDER BY timestamp DESC");
+    let rows: Vec<ProofRow> = builder.build_query_as().fetch_all(pool).await?;
+    if rows.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let fids: Vec<i64> = rows.iter().map(|row| row.fid).collect();
+    let messages: Vec<StoredMessage> = QueryBuilder::new(
+        "SELECT m.timestamp, m.hash, m.hash_scheme, m.signature_scheme, m.signer, m.body, m.raw, \
+         m.signature FROM messages m WHERE m.fid = ANY(",
+    )
+    .push_bind(fids)
+    .push(") AND m.type = ")
+    .push_bind(proto::MessageType::UsernameProof as i16)
+    .build_query_as()
+    .fetch_all(pool)
+    .await?;
+
+    let mut signed: HashMap<(u64, Vec<u8>, Vec<u8>), UserNameProof> = HashMap::new();
+    for message in messages {
+        if let Some(Body::UsernameProofBody(proof)) =
+            message.into_proto()?.data.and_then(|d| d.body)
+        {
+            signed.insert((proof.fid, proof.name.clone(), proof.signature.clone()), proof);
+        }
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let key = (row.fid as u64, row.username.clone().into_bytes(), row.signature.clone());
+            signed.remove(&key).unwrap_or_else(|| UserNameProof {
+                timestamp: row.timestamp.unix_timestamp().max(0) as u64,
+                name: row.username.into_bytes(),
+                owner: row.owner.unwrap_or_default(),
+                signature: row.signature,
+                fid: row.fid as u64,
+                r#type: row.proof_type as i32,
+            })
+        })
+        .collect())
+}
+
+/// Live username proofs of an FID, newest first
+pub async fn username_proofs_by_fid(pool: &PgPool, fid: i64) -> Result<Vec<UserNameProof>> {
+    username_proofs(pool, Some(fid), None).await
+}
+
+/// The live proof for a name, if there is one
+pub async fn username_proof(pool: &PgPool, name: &str) -> Result<Option<UserNameProof>> {
+    Ok(username_proofs(pool, None, Some(name)).await?.into_iter().next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(raw: Vec<u8>, body: Value) -> StoredMessage {
+        StoredMessage {
+            timestamp: OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap(),
+            hash: vec![0xab; 20],
+            hash_scheme: 1,
+            signature_scheme: 1,
+            signer: vec![0xcd; 32],
+            body,
+            raw,
+            signature: Some(vec![0xef; 64]),
+        }
+    }
+
+    #[test]
+    fn test_into_proto_keeps_data_bytes() {
+        let data = MessageData { fid: 3, timestamp: 10, r#type: 1, ..Default::default() };
+        // The same fields, with an unknown field the Hub signed but prost drops
+        let mut data_bytes = data.encode_to_vec();
+        data_bytes.extend_from_slice(&[0xf8, 0x06, 0x01]);
+        let message = stored(data_bytes.clone(), Value::Null).into_proto().unwrap();
+
+        assert_eq!(message.data, Some(data.clone()));
+        assert_eq!(message.data_bytes, Some(data_bytes));
+        assert_eq!(message.hash, vec![0xab; 20]);
+        assert_eq!(message.signer, vec![0xcd; 32]);
+        assert_eq!(message.signature, vec![0xef; 64]);
+        assert_eq!(message.hash_scheme, 1);
+    }
+
+    #[test]
+    fn test_into_proto_from_body() {
+        let data = MessageData { fid: 7, timestamp: 10, ..Default::default() };
+        let mut row = stored(Vec::new(), serde_json::to_value(&data).unwrap());
+        row.signature = None;
+
+        let message = row.into_proto().unwrap();
+        assert_eq!(message.data, Some(data));
+        assert_eq!(message.data_bytes, None);
+        assert!(message.signature.is_empty());
+    }
+
+    #[test]
+    fn test_rebuilt_message_matches_hash() {
+        use crate::core::util::{calculate_message_hash, sanitize_json_for_postgres};
+
+        // NULs are stripped from the JSON body, so only the raw bytes still match the hash
+        let data = MessageData {
+            fid: 3,
+            timestamp: 10,
+            r#type: proto::MessageType::CastAdd as i32,
+            body: Some(Body::CastAddBody(proto::CastAddBody {
+                text: "nul\u{0}byte".to_string(),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let sent = proto::Message {
+            hash: calculate_message_hash(&data.encode_to_vec()),
+            data: Some(data.clone()),
+            data_bytes: None,
+            ..Default::default()
+        };
+        let body = sanitize_json_for_postgres(serde_json::to_value(&data).unwrap());
+        let mut row = stored(crate::database::store::raw_data_bytes(&sent), body);
+        row.hash = sent.hash.clone();
+
+        let message = row.into_proto().unwrap();
+        let data = message.data.unwrap();
+        let signed = message.data_bytes.unwrap_or_else(|| data.encode_to_vec());
+        assert_eq!(calculate_message_hash(&signed), message.hash);
+        assert_eq!(message.hash, sent.hash);
+    }
+
+    #[test]
+    fn test_query_sql() {
+        let sql = select(MessageQuery::ReactionsByTarget {
+            target: ReactionTarget::Url("https://example.com"),
+            reaction_type: Some(1),
+        })
+        .into_sql();
+        assert!(sql.contains("JOIN reactions t ON t.hash = m.hash AND t.deleted_at IS NULL"));
+        assert!(sql.contains("t.target_url = $1 AND t.type = $2"));
+
+        let sql = select(MessageQuery::AllByFid {
+            fid: 3,
+            family: MessageType::Link,
+            start: None,
+            stop: None,
+        })
+        .into_sql();
+        assert!(!sql.contains(" JOIN "));
+        assert!(sql.contains("EXISTS (SELECT 1 FROM links t"));
+        assert!(sql.contains("EXISTS (SELECT 1 FROM link_compact_states t"));
+    }
+}
@@ -0,0 +1,335 @@
+//! Storage backends for processed Hub data.
+//!
+//! [`MessageStore`] is everything `DatabaseProcessor` needs to persist messages,
+//! username proofs and onchain events. Every backend writes the same logical
+//! schema with the same upsert/CRDT rules:
+//!
+//! - [`PostgresStore`] is the default and uses the `migrations/` schema.
+//! - `SqliteStore` (behind the `sqlite` cargo feature) writes to a single
+//!   `.db` file with its own migrations under `migrations/sqlite/`.
+//!
+//! The backend is picked from `database.url`: `sqlite:` URLs select SQLite,
+//! anything else is treated as a Postgres connection string.
+
+pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+pub use postgres::PostgresStore;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
+
+use crate::{
+    config::DatabaseConfig,
+    database::{client::Database, error::Error},
+    hub::client::Hub,
+    proto::{
+        CastId, Message, OnChainEvent, UserNameProof, cast_add_body::Parent,
+        message_data::Body::CastAddBody,
+    },
+};
+use async_trait::async_trait;
+use prost::Message as _;
+use std::{hash::Hasher, sync::Arc};
+use tokio::sync::Mutex;
+use tracing::{trace, warn};
+
+pub type StoreResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Write side of a storage backend
+#[async_trait]
+pub trait MessageStore: Send + Sync + 'static {
+    /// Short backend name for logs
+    fn backend(&self) -> &'static str;
+
+    /// Record a message in the `messages` table.
+    ///
+    /// `operation` is one of merge, delete, prune or revoke. Messages already
+    /// stored are left untouched.
+    async fn store_message(&self, msg: &Message, operation: &str) -> StoreResult<()>;
+
+    /// Apply a single message to its type-specific table.
+    ///
+    /// `operation` is the Hub operation, as for [`store_message`](Self::store_message).
+    async fn apply_message(&self, msg: &Message, operation: &str) -> StoreResult<()>;
+
+    /// Apply a batch of merged messages to their type-specific tables
+    async fn apply_message_batch(&self, messages: &[Message]) -> StoreResult<()>;
+
+    /// Apply a username proof from a MergeUsernameProof event
+    async fn apply_username_proof(&self, proof: &UserNameProof, deleted: bool) -> StoreResult<()>;
+
+    /// Apply an onchain event, along with the registries derived from it
+    async fn apply_onchain_event(&self, event: &OnChainEvent) -> StoreResult<()>;
+}
+
+/// Open the store configured by `database.url`.
+///
+/// `hub` is used to resolve root parents of casts whose parent isn't stored yet.
+pub async fn open(
+    config: &DatabaseConfig,
+    hub: Option<Arc<Mutex<Hub>>>,
+) -> Result<Arc<dyn MessageStore>, Error> {
+    if config.is_sqlite() {
+        #[cfg(feature = "sqlite")]
+        {
+            return Ok(Arc::new(SqliteStore::connect(config, hub).await?));
+        }
+        #[cfg(not(feature = "sqlite"))]
+        {
+            return Err(Error::ConfigError(
+                "database.url points at SQLite but waypoint was built without the `sqlite` feature"
+                    .to_string(),
+            ));
+        }
+    }
+
+    let database = Database::new(config).await?;
+    database.log_connection_info();
+    Ok(Arc::new(
+        PostgresStore::new(database.pool, hub, config.batch_size)
+            .with_root_parent_cache(config.root_parent_cache_size),
+    ))
+}
+
+/// Bytes a message's hash covers: the Hub's `data_bytes`, or `data` encoded when the Hub
+/// didn't send them, which is what it hashed
+pub(crate) fn raw_data_bytes(msg: &Message) -> Vec<u8> {
+    match (&msg.data_bytes, &msg.data) {
+        (Some(bytes), _) => bytes.clone(),
+        (None, Some(data)) => data.encode_to_vec(),
+        (None, None) => Vec::new(),
+    }
+}
+
+/// Deterministic hash for an onchain event, which has no message hash of its own
+pub(crate) fn onchain_event_hash(event: &OnChainEvent) -> Vec<u8> {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    std::hash::Hash::hash(&event.transaction_hash, &mut hasher);
+    std::hash::Hash::hash(&event.log_index, &mut hasher);
+    hasher.finish().to_be_bytes().to_vec()
+}
+
+/// Extracts parent info from a proto::Message (the Hub gRPC response type)
+fn extract_parent_from_proto(msg: &Message) -> (Option<u64>, Option<Vec<u8>>, Option<String>) {
+    if let Some(data) = &msg.data
+        && let Some(CastAddBody(cast_body)) = &data.body
+    {
+        let parent_fid = match &cast_body.parent {
+            Some(Parent::ParentCastId(id)) => Some(id.fid),
+            _ => None,
+        };
+        let parent_hash = match &cast_body.parent {
+            Some(Parent::ParentCastId(id)) => Some(id.hash.clone()),
+            _ => None,
+        };
+        let parent_url = match &cast_body.parent {
+            Some(Parent::ParentUrl(url)) => Some(url.clone()),
+            _ => None,
+        };
+        return (parent_fid, parent_hash, parent_url);
+    }
+    (None, None, None)
+}
+
+/// Traverses parent chain via Hub gRPC API to find the true root cast.
+/// Uses iterative approach with max depth to prevent infinite loops.
+/// Returns None for all fields if the root cannot be definitively determined.
+///
+/// Note: In consumer-only mode (no hub), this function will return None for all fields
+/// since we cannot traverse the parent chain without hub access.
+pub(crate) async fn resolve_root_from_hub(
+    hub: Option<&Arc<Mutex<Hub>>>,
+    start_fid: i64,
+    start_hash: &[u8],
+) -> StoreResult<(Option<i64>, Option<Vec<u8>>, Option<String>)> {
+    // In consumer-only mode, hub is not available
+    let Some(hub_arc) = hub else {
+        trace!("Hub not available (consumer-only mode), cannot resolve root parent chain");
+        return Ok((None, None, None));
+    };
+
+    const MAX_DEPTH: usize = 100; // Prevent runaway traversal
+
+    let mut current_fid = start_fid as u64;
+    let mut current_hash = start_hash.to_vec();
+
+    for depth in 0..MAX_DEPTH {
+        // Fetch the cast from Hub via gRPC
+        let mut hub = hub_arc.lock().await;
+
+        // Ensure connected
+        if !hub.check_connection().await.unwrap_or(false) {
+            warn!("Hub not connected while resolving root parent, leaving root_parent NULL");
+            return Ok((None, None, None));
+        }
+
+        let cast_id = CastId { fid: current_fid, hash: current_hash.clone() };
+
+        let client = match hub.client() {
+            Some(c) => c,
+            None => {
+                warn!("Hub client not initialized, leaving root_parent NULL");
+                return Ok((None, None, None));
+            },
+        };
+
+        let result = client.get_cast(tonic::Request::new(cast_id)).await;
+
+        drop(hub); // Release lock before processing
+
+        let proto_msg = match result {
+            Ok(response) => response.into_inner(),
+            Err(status) if status.code() == tonic::Code::NotFound => {
+                // Cast not found in Hub - we cannot determine the true root
+                // Leave root_parent NULL rather than incorrectly assume parent is root
+                trace!(
+                    "Cast not found in Hub at depth {}, leaving root_parent NULL (chain broken)",
+                    depth
+                );
+                return Ok((None, None, None));
+            },
+            Err(e) => {
+                warn!("Error fetching cast from Hub: {}, leaving root_parent NULL", e);
+                return Ok((None, None, None));
+            },
+        };
+
+        // Extract parent info from protobuf
+        let (parent_fid_opt, parent_hash_opt, parent_url_opt) =
+            extract_parent_from_proto(&proto_msg);
+
+        match (parent_hash_opt, parent_url_opt) {
+            (None, None) => {
+                // Found root! This cast has no parent.
+                trace!("Found root cast at depth {}", depth);
+                return Ok((Some(current_fid as i64), Some(current_hash), None));
+            },
+            (None, Some(url)) => {
+                // Root is a URL (channel root)
+                trace!("Found URL root at depth {}: {}", depth, url);
+                return Ok((None, None, Some(url)));
+            },
+            (Some(p_hash), _) => {
+                // Continue traversing up
+                current_fid = parent_fid_opt.unwrap_or(current_fid);
+                current_hash = p_hash;
+            },
+        }
+    }
+
+    // Max depth reached - cannot determine root definitively
+    warn!("Max depth {} reached while resolving root parent, leaving root_parent NULL", MAX_DEPTH);
+    Ok((None, None, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_parent_from_proto_no_parent() {
+        // Create a cast message with no parent (root cast)
+        let msg = Message {
+            hash: vec![0x01; 20],
+            data: Some(crate::proto::MessageData {
+                fid: 12345,
+                timestamp: 1700000000,
+                network: 1,
+                r#type: 1, // CastAdd
+                body: Some(CastAddBody(crate::proto::CastAddBody {
+                    text: "Hello world".to_string(),
+                    parent: None,
+                    embeds: vec![],
+                    mentions: vec![],
+                    mentions_positions: vec![],
+                    embeds_deprecated: vec![],
+                    r#type: 0,
+                })),
+            }),
+            hash_scheme: 1,
+            signature: vec![],
+            signature_scheme: 1,
+            signer: vec![],
+            data_bytes: None,
+        };
+
+        let (parent_fid, parent_hash, parent_url) = super::extract_parent_from_proto(&msg);
+        assert!(parent_fid.is_none());
+        assert!(parent_hash.is_none());
+        assert!(parent_url.is_none());
+    }
+
+    #[test]
+    fn test_extract_parent_from_proto_with_cast_parent() {
+        use crate::proto::CastId;
+        use crate::proto::cast_add_body::Parent;
+
+        // Create a cast message with a cast parent
+        let msg = Message {
+            hash: vec![0x02; 20],
+            data: Some(crate::proto::MessageData {
+                fid: 12345,
+                timestamp: 1700000000,
+                network: 1,
+                r#type: 1, // CastAdd
+                body: Some(CastAddBody(crate::proto::CastAddBody {
+                    text: "Reply to cast".to_string(),
+                    parent: Some(Parent::ParentCastId(CastId { fid: 67890, hash: vec![0xAA; 20] })),
+                    embeds: vec![],
+                    mentions: vec![],
+                    mentions_positions: vec![],
+                    embeds_deprecated: vec![],
+                    r#type: 0,
+                })),
+            }),
+            hash_scheme: 1,
+            signature: vec![],
+            signature_scheme: 1,
+            signer: vec![],
+            data_bytes: None,
+        };
+
+        let (parent_fid, parent_hash, parent_url) = super::extract_parent_from_proto(&msg);
+        assert_eq!(parent_fid, Some(67890));
+        assert_eq!(parent_hash, Some(vec![0xAA; 20]));
+        assert!(parent_url.is_none());
+    }
+
+    #[test]
+    fn test_extract_parent_from_proto_with_url_parent() {
+        use crate::proto::cast_add_body::Parent;
+
+        // Create a cast message with a URL parent (channel post)
+        let msg = Message {
+            hash: vec![0x03; 20],
+            data: Some(crate::proto::MessageData {
+                fid: 12345,
+                timestamp: 1700000000,
+                network: 1,
+                r#type: 1, // CastAdd
+                body: Some(CastAddBody(crate::proto::CastAddBody {
+                    text: "Channel post".to_string(),
+                    parent: Some(Parent::ParentUrl(
+                        "https://warpcast.com/~/channel/rust".to_string(),
+                    )),
+                    embeds: vec![],
+                    mentions: vec![],
+                    mentions_positions: vec![],
+                    embeds_deprecated: vec![],
+                    r#type: 0,
+                })),
+            }),
+            hash_scheme: 1,
+            signature: vec![],
+            signature_scheme: 1,
+            signer: vec![],
+            data_bytes: None,
+        };
+
+        let (parent_fid, parent_hash, parent_url) = super::extract_parent_from_proto(&msg);
+        assert!(parent_fid.is_none());
+        assert!(parent_hash.is_none());
+        assert_eq!(parent_url, Some("https://warpcast.com/~/channel/rust".to_string()));
+    }
+}
@@ -0,0 +1,1422 @@
+//! SQLite implementation of [`MessageStore`].
+//!
+//! Mirrors [`PostgresStore`](super::PostgresStore) statement for statement against
+//! the schema in `migrations/sqlite/`, with the same upsert and CRDT rules. The
+//! differences are in representation only:
+//!
+//! - timestamps are RFC 3339 UTC text, which compares chronologically
+//! - JSON and array columns are JSON text
+//! - every write runs in a `BEGIN IMMEDIATE` transaction, since SQLite has a
+//!   single writer and a deferred transaction can't be upgraded under contention
+
+use super::{MessageStore, StoreResult, onchain_event_hash, raw_data_bytes, resolve_root_from_hub};
+use crate::{
+    config::DatabaseConfig,
+    core::{
+        normalize::{EmbedData, NormalizedEmbed},
+        util::{from_farcaster_time, sanitize_json_for_postgres, sanitize_string_for_postgres},
+    },
+    database::{
+        batch::LinkCompactStateInsert,
+        error::Error,
+        registry::{SignerEventRow, SignerRow},
+    },
+    hub::client::Hub,
+    metrics,
+    proto::{
+        Message, OnChainEvent, UserNameProof,
+        cast_add_body::Parent,
+        link_body::Target as LinkTarget,
+        message_data::Body::{
+            CastAddBody, CastRemoveBody, FrameActionBody, LendStorageBody, LinkBody, ReactionBody,
+            UserDataBody, UsernameProofBody, VerificationAddAddressBody, VerificationRemoveBody,
+        },
+        on_chain_event::Body as OnChainBody,
+        reaction_body::Target as ReactionTarget,
+    },
+};
+use async_trait::async_trait;
+use sqlx::{
+    Row, Sqlite, SqliteConnection, SqliteExecutor,
+    query::Query,
+    sqlite::{
+        SqliteArguments, SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions,
+        SqliteSynchronous,
+    },
+    types::time::{OffsetDateTime, UtcOffset},
+};
+use std::{str::FromStr, sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+use tracing::{info, trace};
+
+/// (root_parent_fid, root_parent_hash, root_parent_url) of a cast
+type RootParent = (Option<i64>, Option<Vec<u8>>, Option<String>);
+
+/// Writes Hub data to a single SQLite database file
+#[derive(Clone)]
+pub struct SqliteStore {
+    pool: SqlitePool,
+    hub: Option<Arc<Mutex<Hub>>>,
+    batch_size: usize,
+}
+
+impl SqliteStore {
+    pub fn new(pool: SqlitePool, hub: Option<Arc<Mutex<Hub>>>, batch_size: usize) -> Self {
+        Self { pool, hub, batch_size: batch_size.max(1) }
+    }
+
+    /// Open (creating if needed) the database at `config.url` and run migrations
+    pub async fn connect(
+        config: &DatabaseConfig,
+        hub: Option<Arc<Mutex<Hub>>>,
+    ) -> Result<Self, Error> {
+        let options = SqliteConnectOptions::from_str(&config.url)?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(Duration::from_secs(config.timeout_seconds));
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(config.max_connections)
+            .acquire_timeout(Duration::from_secs(config.timeout_seconds))
+            .connect_with(options.clone())
+            .await?;
+
+        if config.skip_migrations {
+            info!("Skipping database migrations (skip_migrations=true)");
+        } else {
+            info!("Running SQLite migrations...");
+            Self::migrate(&pool).await?;
+            info!("SQLite migrations completed successfully");
+        }
+
+        info!("Connected to SQLite database: {}", options.get_filename().display());
+        Ok(Self::new(pool, hub, config.batch_size))
+    }
+
+    /// Apply the migrations in `migrations/sqlite/`
+    pub async fn migrate(pool: &SqlitePool) -> Result<(), Error> {
+        sqlx::migrate!("./migrations/sqlite")
+            .run(pool)
+            .await
+            .map_err(|e| Error::ConnectionError(format!("Failed to run migrations: {}", e)))
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    fn convert_timestamp(timestamp: u32) -> OffsetDateTime {
+        let unix_time = from_farcaster_time(timestamp) / 1000;
+        OffsetDateTime::from_unix_timestamp(unix_time as i64).unwrap()
+    }
+
+    /// Resolves the root parent of a CastAdd message.
+    /// Priority: 1) Check DB for parent's root  2) Query Hub if `via_hub`  3) Leave NULL
+    async fn resolve_root_parent<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        msg: &Message,
+        via_hub: bool,
+    ) -> StoreResult<RootParent> {
+        let Some(CastAddBody(cast_body)) = msg.data.as_ref().and_then(|d| d.body.as_ref()) else {
+            return Ok((None, None, None));
+        };
+
+        match &cast_body.parent {
+            None => Ok((None, None, None)),
+            Some(Parent::ParentUrl(url)) => Ok((None, None, Some(url.clone()))),
+            Some(Parent::ParentCastId(cast_id)) => {
+                let row = sqlx::query(
+                    r#"
+                    SELECT
+                        COALESCE(root_parent_fid, fid) AS root_fid,
+                        COALESCE(root_parent_hash, hash) AS root_hash,
+                        root_parent_url
+                    FROM casts
+                    WHERE hash = $1 AND deleted_at IS NULL
+                    "#,
+                )
+                .bind(&cast_id.hash)
+                .fetch_optional(executor)
+                .await?;
+
+                if let Some(row) = row {
+                    return Ok((
+                        row.try_get("root_fid")?,
+                        row.try_get("root_hash")?,
+                        row.try_get("root_parent_url")?,
+                    ));
+                }
+
+                if via_hub {
+                    resolve_root_from_hub(self.hub.as_ref(), cast_id.fid as i64, &cast_id.hash)
+                        .await
+                } else {
+                    Ok((None, None, None))
+                }
+            },
+        }
+    }
+
+    async fn insert_message(
+        conn: &mut SqliteConnection,
+        msg: &Message,
+        operation: &str,
+    ) -> StoreResult<()> {
+        let Some(data) = &msg.data else {
+            return Ok(());
+        };
+
+        let ts = Self::convert_timestamp(data.timestamp);
+        let raw_data = raw_data_bytes(msg);
+        let body_json = sanitize_json_for_postgres(serde_json::to_value(data)?).to_string();
+        let now = OffsetDateTime::now_utc();
+
+        sqlx::query(
+            r#"
+            INSERT INTO messages (
+                fid, type, timestamp, hash, hash_scheme, signature_scheme, signer, body, raw,
+                signature, deleted_at, pruned_at, revoked_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (hash) DO UPDATE SET
+                deleted_at = COALESCE(messages.deleted_at, EXCLUDED.deleted_at),
+                pruned_at = COALESCE(messages.pruned_at, EXCLUDED.pruned_at),
+                revoked_at = COALESCE(messages.revoked_at, EXCLUDED.revoked_at),
+                updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+            WHERE EXCLUDED.deleted_at IS NOT NULL
+               OR EXCLUDED.pruned_at IS NOT NULL
+               OR EXCLUDED.revoked_at IS NOT NULL
+            "#,
+        )
+        .bind(data.fid as i64)
+        .bind(data.r#type as i16)
+        .bind(ts)
+        .bind(&msg.hash)
+        .bind(msg.hash_scheme as i16)
+        .bind(msg.signature_scheme as i16)
+        .bind(&msg.signer)
+        .bind(body_json)
+        .bind(raw_data)
+        .bind(&msg.signature)
+        .bind((operation == "delete").then_some(now))
+        .bind((operation == "prune").then_some(now))
+        .bind((operation == "revoke").then_some(now))
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn apply(
+        conn: &mut SqliteConnection,
+        msg: &Message,
+        root: RootParent,
+    ) -> StoreResult<()> {
+        let Some(data) = &msg.data else {
+            return Ok(());
+        };
+
+        match data.r#type {
+            1 => add_cast(conn, msg, root).await,
+            2 => remove_cast(conn, msg).await,
+            3 => add_reaction(conn, msg).await,
+            4 => remove_reaction(conn, msg).await,
+            5 => add_link(conn, msg).await,
+            6 => remove_link(conn, msg).await,
+            7 => add_verification(conn, msg).await,
+            8 => remove_verification(conn, msg).await,
+            11 => insert_user_data(conn, msg).await,
+            12 => insert_username_proof(conn, msg).await,
+            13 => add_frame_action(conn, msg).await,
+            14 => add_link_compact_state(conn, msg).await,
+            15 => add_lend_storage(conn, msg).await,
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl MessageStore for SqliteStore {
+    fn backend(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn store_message(&self, msg: &Message, operation: &str) -> StoreResult<()> {
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        Self::insert_message(&mut tx, msg, operation).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn apply_message(&self, msg: &Message, _operation: &str) -> StoreResult<()> {
+        // Resolve before taking the write lock, since this may walk the Hub
+        let root = self.resolve_root_parent(&self.pool, msg, true).await?;
+
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        Self::apply(&mut tx, msg, root).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn apply_message_batch(&self, messages: &[Message]) -> StoreResult<()> {
+        // Like the Postgres batch path: every message is logged, and root parents
+        // are only resolved from casts already stored (including earlier in the batch)
+        for chunk in messages.chunks(self.batch_size) {
+            let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+            for msg in chunk {
+                Self::insert_message(&mut tx, msg, "merge").await?;
+                let root = self.resolve_root_parent(&mut *tx, msg, false).await?;
+                Self::apply(&mut tx, msg, root).await?;
+            }
+            tx.commit().await?;
+            trace!("Applied batch of {} messages to SQLite", chunk.len());
+        }
+        Ok(())
+    }
+
+    async fn apply_username_proof(&self, proof: &UserNameProof, deleted: bool) -> StoreResult<()> {
+        let username = String::from_utf8(proof.name.clone()).unwrap_or_default();
+        let sanitized_username = sanitize_string_for_postgres(&username);
+        let ts = Self::convert_timestamp(proof.timestamp as u32);
+
+        sqlx::query(
+            r#"
+            INSERT INTO username_proofs (fid, username, timestamp, type, signature, owner, deleted_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (username, fid) DO UPDATE SET
+                deleted_at = CASE
+                    WHEN $7 IS NOT NULL THEN $7
+                    ELSE username_proofs.deleted_at
+                END
+            "#,
+        )
+        .bind(proof.fid as i64)
+        .bind(sanitized_username.as_ref())
+        .bind(ts)
+        .bind(proof.r#type as i16)
+        .bind(&proof.signature)
+        .bind(&proof.owner)
+        .bind(deleted.then_some(ts))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn apply_onchain_event(&self, event: &OnChainEvent) -> StoreResult<()> {
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        insert_onchain_event(&mut tx, event).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+async fn add_cast(conn: &mut SqliteConnection, msg: &Message, root: RootParent) -> StoreResult<()> {
+    let (Some(data), Some(CastAddBody(cast_body))) =
+        (&msg.data, msg.data.as_ref().and_then(|d| d.body.as_ref()))
+    else {
+        return Ok(());
+    };
+
+    metrics::increment_casts_processed();
+    let (parent_fid, parent_hash, parent_url) = match &cast_body.parent {
+        Some(Parent::ParentCastId(cast_id)) => {
+            (Some(cast_id.fid as i64), Some(cast_id.hash.as_slice()), None)
+        },
+        Some(Parent::ParentUrl(url)) => (None, None, Some(url.as_str())),
+        None => (None, None, None),
+    };
+    let (root_parent_fid, root_parent_hash, root_parent_url) = root;
+
+    let ts = SqliteStore::convert_timestamp(data.timestamp);
+    let sanitized_text = sanitize_string_for_postgres(&cast_body.text);
+    let sanitized_parent_url = parent_url.map(sanitize_string_for_postgres);
+    let sanitized_root_parent_url = root_parent_url.as_deref().map(sanitize_string_for_postgres);
+    let embeds: Vec<NormalizedEmbed> =
+        cast_body.embeds.iter().map(NormalizedEmbed::from_protobuf_embed).collect();
+
+    // Channel counters skip replays, so note whether this is the first insert
+    let existed = sqlx::query("SELECT 1 FROM casts WHERE hash = $1")
+        .bind(&msg.hash)
+        .fetch_optional(&mut *conn)
+        .await?
+        .is_some();
+
+    sqlx::query(
+        r#"
+        INSERT INTO casts (
+            fid, hash, text, parent_fid, parent_hash, parent_url,
+            root_parent_fid, root_parent_hash, root_parent_url,
+            timestamp, embeds, mentions, mentions_positions
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        ON CONFLICT (hash) DO UPDATE SET
+            text = excluded.text,
+            parent_fid = excluded.parent_fid,
+            parent_hash = excluded.parent_hash,
+            parent_url = excluded.parent_url,
+            root_parent_fid = excluded.root_parent_fid,
+            root_parent_hash = excluded.root_parent_hash,
+            root_parent_url = excluded.root_parent_url,
+            timestamp = excluded.timestamp,
+            embeds = excluded.embeds,
+            mentions = excluded.mentions,
+            mentions_positions = excluded.mentions_positions
+        "#,
+    )
+    .bind(data.fid as i64)
+    .bind(&msg.hash)
+    .bind(sanitized_text.as_ref())
+    .bind(parent_fid)
+    .bind(parent_hash)
+    .bind(sanitized_parent_url.as_deref())
+    .bind(root_parent_fid)
+    .bind(root_parent_hash.as_deref())
+    .bind(sanitized_root_parent_url.as_deref())
+    .bind(ts)
+    .bind(serde_json::to_string(&embeds)?)
+    .bind(serde_json::to_string(&cast_body.mentions)?)
+    .bind(serde_json::to_string(&cast_body.mentions_positions)?)
+    .execute(&mut *conn)
+    .await?;
+
+    if !existed && let Some(url) = sanitized_root_parent_url {
+        record_channel_cast(conn, &url, ts, parent_hash.is_some()).await?;
+    }
+
+    for (&fid, &position) in cast_body.mentions.iter().zip(&cast_body.mentions_positions) {
+        sqlx::query(
+            r#"
+            INSERT INTO cast_mentions (fid, cast_hash, author_fid, position, timestamp, deleted_at)
+            VALUES ($1, $2, $3, $4, $5, (SELECT deleted_at FROM casts WHERE hash = $2))
+            ON CONFLICT (cast_hash, fid, position) DO UPDATE SET
+                deleted_at = excluded.deleted_at
+            WHERE cast_mentions.deleted_at IS NOT excluded.deleted_at
+            "#,
+        )
+        .bind(fid as i64)
+        .bind(&msg.hash)
+        .bind(data.fid as i64)
+        .bind(position as i32)
+        .bind(ts)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    for (position, embed) in embeds.iter().enumerate() {
+        let (kind, url, cast_fid, cast_hash_ref) = match &embed.data {
+            EmbedData::Url { url } => {
+                ("url", Some(sanitize_string_for_postgres(url).into_owned()), None, None)
+            },
+            EmbedData::CastId { cast_id } => {
+                ("cast", None, Some(cast_id.fid as i64), Some(cast_id.hash.data.clone()))
+            },
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO cast_embeds (
+                cast_hash, author_fid, position, kind, url, cast_fid, cast_hash_ref, timestamp,
+                deleted_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, (SELECT deleted_at FROM casts WHERE hash = $1))
+            ON CONFLICT (cast_hash, position) DO UPDATE SET
+                deleted_at = excluded.deleted_at
+            WHERE cast_embeds.deleted_at IS NOT excluded.deleted_at
+            "#,
+        )
+        .bind(&msg.hash)
+        .bind(data.fid as i64)
+        .bind(position as i16)
+        .bind(kind)
+        .bind(url)
+        .bind(cast_fid)
+        .bind(cast_hash_ref)
+        .bind(ts)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Bump the channel counters for a newly stored cast
+async fn record_channel_cast(
+    conn: &mut SqliteConnection,
+    url: &str,
+    timestamp: OffsetDateTime,
+    is_reply: bool,
+) -> StoreResult<()> {
+    let reply_count = i64::from(is_reply);
+
+    sqlx::query(
+        r#"
+        INSERT INTO channels (url, first_cast_at, last_cast_at, cast_count, reply_count)
+        VALUES ($1, $2, $2, 1, $3)
+        ON CONFLICT (url) DO UPDATE SET
+            first_cast_at = min(COALESCE(channels.first_cast_at, excluded.first_cast_at), excluded.first_cast_at),
+            last_cast_at = max(COALESCE(channels.last_cast_at, excluded.last_cast_at), excluded.last_cast_at),
+            cast_count = channels.cast_count + 1,
+            reply_count = channels.reply_count + excluded.reply_count
+        "#,
+    )
+    .bind(url)
+    .bind(timestamp)
+    .bind(reply_count)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO channel_activity (url, day, cast_count, reply_count)
+        VALUES ($1, $2, 1, $3)
+        ON CONFLICT (url, day) DO UPDATE SET
+            cast_count = channel_activity.cast_count + 1,
+            reply_count = channel_activity.reply_count + excluded.reply_count
+        "#,
+    )
+    .bind(url)
+    .bind(timestamp.to_offset(UtcOffset::UTC).date())
+    .bind(reply_count)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn remove_cast(conn: &mut SqliteConnection, msg: &Message) -> StoreResult<()> {
+    let (Some(data), Some(CastRemoveBody(remove_body))) =
+        (&msg.data, msg.data.as_ref().and_then(|d| d.body.as_ref()))
+    else {
+        return Ok(());
+    };
+
+    let ts = SqliteStore::convert_timestamp(data.timestamp);
+
+    // Higher timestamp wins; on equal timestamps the remove wins
+    sqlx::query(
+        r#"
+        INSERT INTO casts (fid, hash, deleted_at, timestamp, text, embeds, mentions, mentions_positions, parent_fid)
+        VALUES ($1, $2, $3, $4, '', '[]', '[]', '[]', NULL)
+        ON CONFLICT (hash) DO UPDATE SET
+            deleted_at = CASE
+                WHEN excluded.timestamp >= COALESCE(casts.timestamp, excluded.timestamp) THEN excluded.deleted_at
+                ELSE casts.deleted_at
+            END,
+            timestamp = min(COALESCE(casts.timestamp, excluded.timestamp), excluded.timestamp)
+        "#,
+    )
+    .bind(data.fid as i64)
+    .bind(&remove_body.target_hash)
+    .bind(ts)
+    .bind(ts)
+    .execute(&mut *conn)
+    .await?;
+
+    // References follow the cast's CRDT state
+    for table in ["cast_mentions", "cast_embeds"] {
+        sqlx::query(&format!(
+            r#"
+            UPDATE {table}
+            SET deleted_at = (SELECT deleted_at FROM casts WHERE hash = $1)
+            WHERE cast_hash = $1
+              AND deleted_at IS NOT (SELECT deleted_at FROM casts WHERE hash = $1)
+            "#
+        ))
+        .bind(&remove_body.target_hash)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn add_reaction(conn: &mut SqliteConnection, msg: &Message) -> StoreResult<()> {
+    let (Some(data), Some(ReactionBody(reaction))) =
+        (&msg.data, msg.data.as_ref().and_then(|d| d.body.as_ref()))
+    else {
+        return Ok(());
+    };
+
+    metrics::increment_reactions_processed();
+    let (target_cast_hash, target_url) = match &reaction.target {
+        Some(ReactionTarget::TargetCastId(cast_id)) => (Some(&cast_id.hash), None),
+        Some(ReactionTarget::TargetUrl(url)) => (None, Some(url.as_str())),
+        None => (None, None),
+    };
+    let ts = SqliteStore::convert_timestamp(data.timestamp);
+    let sanitized_target_url = target_url.map(sanitize_string_for_postgres);
+
+    sqlx::query(
+        r#"
+        INSERT INTO reactions (fid, hash, target_cast_hash, target_url, type, timestamp, deleted_at)
+        VALUES ($1, $2, $3, $4, $5, $6, NULL)
+        ON CONFLICT (hash) DO UPDATE SET
+            target_cast_hash = excluded.target_cast_hash,
+            target_url = excluded.target_url,
+            type = excluded.type,
+            deleted_at = NULL,
+            timestamp = max(reactions.timestamp, excluded.timestamp)
+        "#,
+    )
+    .bind(data.fid as i64)
+    .bind(&msg.hash)
+    .bind(target_cast_hash)
+    .bind(sanitized_target_url.as_deref())
+    .bind(reaction.r#type as i16)
+    .bind(ts)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn remove_reaction(conn: &mut SqliteConnection, msg: &Message) -> StoreResult<()> {
+    let (Some(data), Some(ReactionBody(reaction))) =
+        (&msg.data, msg.data.as_ref().and_then(|d| d.body.as_ref()))
+    else {
+        return Ok(());
+    };
+
+    let (target_cast_hash, target_url) = match &reaction.target {
+        Some(ReactionTarget::TargetCastId(cast_id)) => (Some(&cast_id.hash), None),
+        Some(ReactionTarget::TargetUrl(url)) => (None, Some(sanitize_string_for_postgres(url))),
+        None => return Ok(()),
+    };
+    let ts = SqliteStore::convert_timestamp(data.timestamp);
+
+    // Higher timestamp wins; on equal timestamps the remove wins
+    sqlx::query(
+        r#"
+        INSERT INTO reactions (fid, type, target_cast_hash, target_url, hash, timestamp, deleted_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (hash) DO UPDATE SET
+            deleted_at = CASE
+                WHEN excluded.timestamp >= reactions.timestamp THEN excluded.deleted_at
+                ELSE reactions.deleted_at
+            END,
+            timestamp = max(reactions.timestamp, excluded.timestamp)
+        "#,
+    )
+    .bind(data.fid as i64)
+    .bind(reaction.r#type as i16)
+    .bind(target_cast_hash)
+    .bind(target_url.as_deref())
+    .bind(&msg.hash)
+    .bind(ts)
+    .bind(ts)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn add_link(conn: &mut SqliteConnection, msg: &Message) -> StoreResult<()> {
+    let (Some(data), Some(LinkBody(link))) =
+        (&msg.data, msg.data.as_ref().and_then(|d| d.body.as_ref()))
+    else {
+        return Ok(());
+    };
+
+    metrics::increment_follows_processed();
+    let Some(LinkTarget::TargetFid(target_fid)) = link.target else {
+        return Ok(());
+    };
+    let ts = SqliteStore::convert_timestamp(data.timestamp);
+    let display_ts = link.display_timestamp.map(SqliteStore::convert_timestamp);
+    let sanitized_link_type = sanitize_string_for_postgres(&link.r#type);
+
+    sqlx::query(
+        r#"
+        INSERT INTO links (fid, target_fid, type, hash, timestamp, deleted_at, display_timestamp)
+        VALUES ($1, $2, $3, $4, $5, NULL, $6)
+        ON CONFLICT (hash) DO UPDATE SET
+            deleted_at = NULL,
+            timestamp = min(excluded.timestamp, links.timestamp),
+            display_timestamp = COALESCE(links.display_timestamp, excluded.display_timestamp)
+        "#,
+    )
+    .bind(data.fid as i64)
+    .bind(target_fid as i64)
+    .bind(sanitized_link_type.as_ref())
+    .bind(&msg.hash)
+    .bind(ts)
+    .bind(display_ts)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn remove_link(conn: &mut SqliteConnection, msg: &Message) -> StoreResult<()> {
+    let (Some(data), Some(LinkBody(link))) =
+        (&msg.data, msg.data.as_ref().and_then(|d| d.body.as_ref()))
+    else {
+        return Ok(());
+    };
+
+    let Some(LinkTarget::TargetFid(target_fid)) = link.target else {
+        return Ok(());
+    };
+    let ts = SqliteStore::convert_timestamp(data.timestamp);
+    let display_ts = link.display_timestamp.map(SqliteStore::convert_timestamp);
+    let sanitized_link_type = sanitize_string_for_postgres(&link.r#type);
+
+    // Higher timestamp wins; on equal timestamps the remove wins.
+    // The earliest timestamp is kept, as in Postgres.
+    sqlx::query(
+        r#"
+        INSERT INTO links (fid, target_fid, type, hash, timestamp, deleted_at, display_timestamp)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (hash) DO UPDATE SET
+            deleted_at = CASE
+                WHEN excluded.timestamp >= links.timestamp THEN excluded.deleted_at
+                ELSE links.deleted_at
+            END,
+            timestamp = min(excluded.timestamp, links.timestamp),
+            display_timestamp = COALESCE(links.display_timestamp, excluded.display_timestamp)
+        "#,
+    )
+    .bind(data.fid as i64)
+    .bind(target_fid as i64)
+    .bind(sanitized_link_type.as_ref())
+    .bind(&msg.hash)
+    .bind(ts)
+    .bind(ts)
+    .bind(display_ts)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn insert_user_data(conn: &mut SqliteConnection, msg: &Message) -> StoreResult<()> {
+    let (Some(data), Some(UserDataBody(user_data))) =
+        (&msg.data, msg.data.as_ref().and_then(|d| d.body.as_ref()))
+    else {
+        return Ok(());
+    };
+
+    metrics::increment_user_data_processed();
+    let ts = SqliteStore::convert_timestamp(data.timestamp);
+    let sanitized_value = sanitize_string_for_postgres(&user_data.value);
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_data (fid, type, hash, value, timestamp)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (fid, type) DO UPDATE SET
+            hash = CASE WHEN excluded.timestamp >= user_data.timestamp THEN excluded.hash ELSE user_data.hash END,
+            value = CASE WHEN excluded.timestamp >= user_data.timestamp THEN excluded.value ELSE user_data.value END,
+            timestamp = max(user_data.timestamp, excluded.timestamp)
+        "#,
+    )
+    .bind(data.fid as i64)
+    .bind(user_data.r#type as i16)
+    .bind(&msg.hash)
+    .bind(sanitized_value.as_ref())
+    .bind(ts)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn insert_username_proof(conn: &mut SqliteConnection, msg: &Message) -> StoreResult<()> {
+    let (Some(data), Some(UsernameProofBody(proof_body))) =
+        (&msg.data, msg.data.as_ref().and_then(|d| d.body.as_ref()))
+    else {
+        return Ok(());
+    };
+
+    let ts = SqliteStore::convert_timestamp(data.timestamp);
+    let name = String::from_utf8(proof_body.name.clone()).unwrap_or_default();
+    let sanitized_name = sanitize_string_for_postgres(&name);
+
+    sqlx::query(
+        r#"
+        INSERT INTO username_proofs (fid, username, timestamp, type, signature, owner)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (username, fid) DO NOTHING
+        "#,
+    )
+    .bind(data.fid as i64)
+    .bind(sanitized_name.as_ref())
+    .bind(ts)
+    .bind(proof_body.r#type as i16)
+    .bind(&proof_body.signature)
+    .bind(&proof_body.owner)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn add_verification(conn: &mut SqliteConnection, msg: &Message) -> StoreResult<()> {
+    let (Some(data), Some(VerificationAddAddressBody(verification))) =
+        (&msg.data, msg.data.as_ref().and_then(|d| d.body.as_ref()))
+    else {
+        return Ok(());
+    };
+
+    let ts = SqliteStore::convert_timestamp(data.timestamp);
+
+    sqlx::query(
+        r#"
+        INSERT INTO verifications (fid, hash, signer_address, block_hash, signature, protocol, timestamp)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (signer_address, fid) DO UPDATE SET
+            hash = excluded.hash,
+            block_hash = excluded.block_hash,
+            signature = excluded.signature,
+            timestamp = excluded.timestamp
+        "#,
+    )
+    .bind(data.fid as i64)
+    .bind(&msg.hash)
+    .bind(&verification.address)
+    .bind(&verification.block_hash)
+    .bind(&verification.claim_signature)
+    .bind(verification.protocol as i16)
+    .bind(ts)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn remove_verification(conn: &mut SqliteConnection, msg: &Message) -> StoreResult<()> {
+    let (Some(data), Some(VerificationRemoveBody(verification))) =
+        (&msg.data, msg.data.as_ref().and_then(|d| d.body.as_ref()))
+    else {
+        return Ok(());
+    };
+
+    let ts = SqliteStore::convert_timestamp(data.timestamp);
+
+    // Higher timestamp wins; on equal timestamps the remove wins.
+    // A placeholder row keeps the address in block_hash/signature, as in Postgres.
+    sqlx::query(
+        r#"
+        INSERT INTO verifications (
+            fid, signer_address, hash, block_hash, signature, protocol, timestamp, deleted_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (signer_address, fid) DO UPDATE SET
+            deleted_at = CASE
+                WHEN excluded.timestamp >= verifications.timestamp THEN excluded.deleted_at
+                ELSE verifications.deleted_at
+            END,
+            timestamp = max(verifications.timestamp, excluded.timestamp)
+        "#,
+    )
+    .bind(data.fid as i64)
+    .bind(&verification.address)
+    .bind(&msg.hash)
+    .bind(&verification.address)
+    .bind(&verification.address)
+    .bind(0i16)
+    .bind(ts)
+    .bind(ts)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn add_lend_storage(conn: &mut SqliteConnection, msg: &Message) -> StoreResult<()> {
+    let (Some(data), Some(LendStorageBody(lend_storage))) =
+        (&msg.data, msg.data.as_ref().and_then(|d| d.body.as_ref()))
+    else {
+        return Ok(());
+    };
+
+    let ts = SqliteStore::convert_timestamp(data.timestamp);
+
+    sqlx::query(
+        r#"
+        INSERT INTO lend_storage (fid, to_fid, num_units, unit_type, hash, timestamp, deleted_at)
+        VALUES ($1, $2, $3, $4, $5, $6, NULL)
+        ON CONFLICT (hash) DO UPDATE SET
+            deleted_at = NULL,
+            timestamp = min(excluded.timestamp, lend_storage.timestamp)
+        "#,
+    )
+    .bind(data.fid as i64)
+    .bind(lend_storage.to_fid as i64)
+    .bind(lend_storage.num_units as i64)
+    .bind(lend_storage.unit_type as i16)
+    .bind(&msg.hash)
+    .bind(ts)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn add_frame_action(conn: &mut SqliteConnection, msg: &Message) -> StoreResult<()> {
+    let (Some(data), Some(FrameActionBody(frame_action))) =
+        (&msg.data, msg.data.as_ref().and_then(|d| d.body.as_ref()))
+    else {
+        return Ok(());
+    };
+
+    let ts = SqliteStore::convert_timestamp(data.timestamp);
+    let url = String::from_utf8_lossy(&frame_action.url);
+    let sanitized_url = sanitize_string_for_postgres(&url);
+    let input_text = String::from_utf8_lossy(&frame_action.input_text);
+    let sanitized_input_text = sanitize_string_for_postgres(&input_text);
+
+    sqlx::query(
+        r#"
+        INSERT INTO frame_actions (
+            fid, hash, url, button_index, cast_fid, cast_hash, input_text, state,
+            transaction_id, address, timestamp
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (hash) DO NOTHING
+        "#,
+    )
+    .bind(data.fid as i64)
+    .bind(&msg.hash)
+    .bind(sanitized_url.as_ref())
+    .bind(frame_action.button_index as i16)
+    .bind(frame_action.cast_id.as_ref().map(|c| c.fid as i64))
+    .bind(frame_action.cast_id.as_ref().map(|c| c.hash.as_slice()))
+    .bind(Some(sanitized_input_text.as_ref()).filter(|t| !t.is_empty()))
+    .bind(Some(frame_action.state.as_slice()).filter(|b| !b.is_empty()))
+    .bind(Some(frame_action.transaction_id.as_slice()).filter(|b| !b.is_empty()))
+    .bind(Some(frame_action.address.as_slice()).filter(|b| !b.is_empty()))
+    .bind(ts)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Replace the links of one (fid, type) with a compact state, unless a newer state is stored
+async fn add_link_compact_state(conn: &mut SqliteConnection, msg: &Message) -> StoreResult<()> {
+    let Some(state) = LinkCompactStateInsert::from_message(msg) else {
+        return Ok(());
+    };
+
+    let link_type = sanitize_string_for_postgres(state.link_type);
+    let target_fids = serde_json::to_string(&state.target_fids)?;
+
+    let applied = sqlx::query(
+        r#"
+        INSERT INTO link_compact_states (fid, type, hash, target_fids, timestamp)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (fid, type) DO UPDATE SET
+            hash = excluded.hash,
+            target_fids = excluded.target_fids,
+            timestamp = excluded.timestamp,
+            deleted_at = NULL
+        WHERE link_compact_states.timestamp <= excluded.timestamp
+        RETURNING id
+        "#,
+    )
+    .bind(state.fid as i64)
+    .bind(link_type.as_ref())
+    .bind(state.hash)
+    .bind(&target_fids)
+    .bind(state.timestamp)
+    .fetch_optional(&mut *conn)
+    .await?
+    .is_some();
+
+    if !applied {
+        trace!("Skipped stale link compact state for FID {} type {}", state.fid, state.link_type);
+        return Ok(());
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE links
+        SET deleted_at = $4
+        WHERE fid = $1
+          AND type = $2
+          AND deleted_at IS NULL
+          AND timestamp <= $4
+          AND target_fid NOT IN (SELECT value FROM json_each($3))
+        "#,
+    )
+    .bind(state.fid as i64)
+    .bind(link_type.as_ref())
+    .bind(&target_fids)
+    .bind(state.timestamp)
+    .execute(&mut *conn)
+    .await?;
+
+    for &target_fid in &state.target_fids {
+        sqlx::query(
+            r#"
+            INSERT INTO links (fid, target_fid, type, hash, timestamp, compact_state_hash)
+            SELECT $1, $3, $2, $4, $5, $6
+            WHERE NOT EXISTS (
+                SELECT 1 FROM links l
+                WHERE l.fid = $1
+                  AND l.type = $2
+                  AND l.target_fid = $3
+                  AND l.deleted_at IS NULL
+            )
+            ON CONFLICT (hash) DO UPDATE SET deleted_at = NULL
+            "#,
+        )
+        .bind(state.fid as i64)
+        .bind(link_type.as_ref())
+        .bind(target_fid)
+        .bind(state.materialized_link_hash(target_fid))
+        .bind(state.timestamp)
+        .bind(state.hash)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn insert_onchain_event(
+    conn: &mut SqliteConnection,
+    event: &OnChainEvent,
+) -> StoreResult<()> {
+    let ts = OffsetDateTime::from_unix_timestamp(event.block_timestamp as i64)?;
+    let fid = event.fid as i64;
+
+    sqlx::query(
+        r#"
+        INSERT INTO onchain_events (
+            fid, hash, type, timestamp, block_number, block_hash, log_index, tx_index, tx_hash,
+            block_timestamp, chain_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $4, $10)
+        ON CONFLICT (hash) DO NOTHING
+        "#,
+    )
+    .bind(fid)
+    .bind(onchain_event_hash(event))
+    .bind(event.r#type as i16)
+    .bind(ts)
+    .bind(event.block_number as i64)
+    .bind(&event.block_hash)
+    .bind(event.log_index as i32)
+    .bind(event.tx_index as i32)
+    .bind(&event.transaction_hash)
+    .bind(event.chain_id as i64)
+    .execute(&mut *conn)
+    .await?;
+
+    match &event.body {
+        Some(OnChainBody::SignerEventBody(body)) => {
+            let sql = typed_event_sql(
+                "signer_events",
+                "key, key_type, event_type, metadata, metadata_type",
+                "$9, $10, $11, $12, $13",
+            );
+            typed_event_query(&sql, event)
+                .bind(&body.key)
+                .bind(body.key_type as i16)
+                .bind(body.event_type as i16)
+                .bind(Some(body.metadata.as_slice()).filter(|m| !m.is_empty()))
+                .bind(body.metadata_type as i16)
+                .execute(&mut *conn)
+                .await?;
+
+            refresh_signers(conn, fid).await?;
+        },
+        Some(OnChainBody::SignerMigratedEventBody(body)) => {
+            let sql = typed_event_sql("signer_migrated_events", "migrated_at", "$9");
+            typed_event_query(&sql, event)
+                .bind(body.migrated_at as i64)
+                .execute(&mut *conn)
+                .await?;
+
+            apply_signer_migration(conn, fid, body.migrated_at as i64).await?;
+        },
+        Some(OnChainBody::IdRegisterEventBody(body)) => {
+            let sql = typed_event_sql(
+                "id_register_events",
+                "to_address, event_type, from_address, recovery_address",
+                "$9, $10, $11, $12",
+            );
+            typed_event_query(&sql, event)
+                .bind(&body.to)
+                .bind(body.event_type as i16)
+                .bind(Some(body.from.as_slice()).filter(|a| !a.is_empty()))
+                .bind(Some(body.recovery_address.as_slice()).filter(|a| !a.is_empty()))
+                .execute(&mut *conn)
+                .await?;
+
+            refresh_fid(conn, fid).await?;
+        },
+        Some(OnChainBody::StorageRentEventBody(body)) => {
+            let sql =
+                typed_event_sql("storage_rent_events", "payer, units, expiry", "$9, $10, $11");
+            typed_event_query(&sql, event)
+                .bind(&body.payer)
+                .bind(body.units as i32)
+                .bind(body.expiry as i32)
+                .execute(&mut *conn)
+                .await?;
+        },
+        Some(OnChainBody::TierPurchaseEventBody(body)) => {
+            let sql =
+                typed_event_sql("tier_purchases", "tier_type, for_days, payer", "$9, $10, $11");
+            typed_event_query(&sql, event)
+                .bind(body.tier_type as i16)
+                .bind(body.for_days as i64)
+                .bind(&body.payer)
+                .execute(&mut *conn)
+                .await?;
+        },
+        // Unknown event types are only kept in onchain_events
+        _ => {},
+    }
+
+    Ok(())
+}
+
+/// INSERT for a typed on-chain event table. `$1..$8` are the columns every table
+/// shares (see [`typed_event_query`]); `values` binds the table's own columns from `$9`.
+fn typed_event_sql(table: &str, columns: &str, values: &str) -> String {
+    format!(
+        "INSERT INTO {table} (fid, {columns}, timestamp, block_number, block_hash, log_index, \
+         tx_index, tx_hash, block_timestamp, chain_id) \
+         VALUES ($1, {values}, $2, $3, $4, $5, $6, $7, $2, $8) \
+         ON CONFLICT (tx_hash, log_index) DO NOTHING"
+    )
+}
+
+/// Bind the shared on-chain event columns for [`typed_event_sql`]
+fn typed_event_query<'q>(
+    sql: &'q str,
+    event: &'q OnChainEvent,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    sqlx::query(sql)
+        .bind(event.fid as i64)
+        .bind(OffsetDateTime::from_unix_timestamp(event.block_timestamp as i64).ok())
+        .bind(event.block_number as i64)
+        .bind(&event.block_hash)
+        .bind(event.log_index as i32)
+        .bind(event.tx_index as i32)
+        .bind(&event.transaction_hash)
+        .bind(event.chain_id as i64)
+}
+
+/// Recompute the `fids` row of one FID from `id_register_events`
+async fn refresh_fid(conn: &mut SqliteConnection, fid: i64) -> StoreResult<()> {
+    sqlx::query(
+        r#"
+        WITH ev AS (
+            SELECT * FROM id_register_events WHERE fid = $1 AND deleted_at IS NULL
+        )
+        INSERT INTO fids (
+            fid, custody_address, recovery_address, registered_at, register_block_number,
+            register_tx_hash, last_transfer_at, last_transfer_block_number, transfer_count,
+            last_event_block_number, last_event_log_index, chain_id
+        )
+        SELECT $1,
+               (SELECT to_address FROM ev WHERE event_type IN (1, 2)
+                ORDER BY block_number DESC, log_index DESC LIMIT 1),
+               (SELECT recovery_address FROM ev WHERE event_type IN (1, 3)
+                ORDER BY block_number DESC, log_index DESC LIMIT 1),
+               g.block_timestamp,
+               g.block_number,
+               g.tx_hash,
+               (SELECT max(block_timestamp) FROM ev WHERE event_type = 2),
+               (SELECT max(block_number) FROM ev WHERE event_type = 2),
+               (SELECT count(*) FROM ev WHERE event_type = 2),
+               l.block_number,
+               l.log_index,
+               l.chain_id
+        FROM (SELECT * FROM ev ORDER BY block_number DESC, log_index DESC LIMIT 1) l
+        LEFT JOIN (SELECT * FROM ev WHERE event_type = 1 ORDER BY block_number, log_index LIMIT 1) g
+            ON true
+        WHERE true
+        ON CONFLICT (fid) DO UPDATE SET
+            custody_address = excluded.custody_address,
+            recovery_address = excluded.recovery_address,
+            registered_at = excluded.registered_at,
+            register_block_number = excluded.register_block_number,
+            register_tx_hash = excluded.register_tx_hash,
+            last_transfer_at = excluded.last_transfer_at,
+            last_transfer_block_number = excluded.last_transfer_block_number,
+            transfer_count = excluded.transfer_count,
+            last_event_block_number = excluded.last_event_block_number,
+            last_event_log_index = excluded.last_event_log_index,
+            chain_id = excluded.chain_id,
+            deleted_at = NULL
+        "#,
+    )
+    .bind(fid)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Recompute the `signers` rows of one FID from `signer_events`
+async fn refresh_signers(conn: &mut SqliteConnection, fid: i64) -> StoreResult<()> {
+    let events = sqlx::query(
+        r#"
+        SELECT fid, key, key_type, event_type, metadata, metadata_type, block_number, log_index,
+               block_timestamp, chain_id
+        FROM signer_events
+        WHERE fid = $1 AND deleted_at IS NULL
+        ORDER BY key, block_number, log_index
+        "#,
+    )
+    .bind(fid)
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|r| {
+        Ok(SignerEventRow {
+            fid: r.try_get("fid")?,
+            key: r.try_get("key")?,
+            key_type: r.try_get("key_type")?,
+            event_type: r.try_get("event_type")?,
+            metadata: r.try_get("metadata")?,
+            metadata_type: r.try_get("metadata_type")?,
+            block_number: r.try_get("block_number")?,
+            log_index: r.try_get("log_index")?,
+            block_timestamp: r.try_get("block_timestamp")?,
+            chain_id: r.try_get("chain_id")?,
+        })
+    })
+    .collect::<Result<Vec<_>, sqlx::Error>>()?;
+
+    for row in events.chunk_by(|a, b| a.key == b.key).filter_map(SignerRow::fold) {
+        // Earliest signer migration at or after the key was added; FID 0 applies to everyone
+        let migrated_at: Option<i64> = match row.added_at {
+            Some(added_at) => {
+                sqlx::query_scalar(
+                    r#"
+                    SELECT min(migrated_at)
+                    FROM signer_migrated_events
+                    WHERE deleted_at IS NULL AND fid IN (0, $1) AND migrated_at >= $2
+                    "#,
+                )
+                .bind(fid)
+                .bind(added_at.unix_timestamp())
+                .fetch_one(&mut *conn)
+                .await?
+            },
+            None => None,
+        };
+        let migrated_at = migrated_at.map(OffsetDateTime::from_unix_timestamp).transpose()?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO signers (
+                fid, key, key_type, metadata_type, request_fid, request_signer, added_at,
+                removed_at, removal_type, last_event_block_number, last_event_log_index, chain_id,
+                migrated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (fid, key) DO UPDATE SET
+                key_type = excluded.key_type,
+                metadata_type = excluded.metadata_type,
+                request_fid = excluded.request_fid,
+                request_signer = excluded.request_signer,
+                added_at = excluded.added_at,
+                removed_at = excluded.removed_at,
+                removal_type = excluded.removal_type,
+                migrated_at = excluded.migrated_at,
+                last_event_block_number = excluded.last_event_block_number,
+                last_event_log_index = excluded.last_event_log_index,
+                chain_id = excluded.chain_id,
+                deleted_at = NULL
+            "#,
+        )
+        .bind(row.fid)
+        .bind(&row.key)
+        .bind(row.key_type)
+        .bind(row.metadata_type)
+        .bind(row.request_fid)
+        .bind(&row.request_signer)
+        .bind(row.added_at)
+        .bind(row.removed_at)
+        .bind(row.removal_type)
+        .bind(row.last_event_block_number)
+        .bind(row.last_event_log_index)
+        .bind(row.chain_id)
+        .bind(migrated_at)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Stamp `migrated_at` on keys carried over by a signer migration; FID 0 applies to every FID
+async fn apply_signer_migration(
+    conn: &mut SqliteConnection,
+    fid: i64,
+    migrated_at: i64,
+) -> StoreResult<()> {
+    let migrated_at = OffsetDateTime::from_unix_timestamp(migrated_at)?;
+
+    sqlx::query(
+        r#"
+        UPDATE signers
+        SET migrated_at = $2
+        WHERE ($1 = 0 OR fid = $1)
+          AND added_at <= $2
+          AND (migrated_at IS NULL OR migrated_at > $2)
+        "#,
+    )
+    .bind(fid)
+    .bind(migrated_at)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{
+        CastAddBody as CastAdd, CastRemoveBody as CastRemove, IdRegisterEventBody, MessageData,
+        UserDataBody as UserData, message_data::Body,
+    };
+
+    async fn store() -> SqliteStore {
+        let pool =
+            SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        SqliteStore::migrate(&pool).await.unwrap();
+        SqliteStore::new(pool, None, 100)
+    }
+
+    fn message(hash: &[u8], r#type: i32, timestamp: u32, body: Body) -> Message {
+        Message {
+            hash: hash.to_vec(),
+            data: Some(MessageData {
+                r#type,
+                fid: 42,
+                timestamp,
+                body: Some(body),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn cast_add(hash: &[u8], timestamp: u32) -> Message {
+        let body = CastAdd { text: "hello".into(), ..Default::default() };
+        message(hash, 1, timestamp, CastAddBody(body))
+    }
+
+    fn cast_remove(target: &[u8], timestamp: u32) -> Message {
+        let body = CastRemove { target_hash: target.to_vec() };
+        message(b"remove", 2, timestamp, CastRemoveBody(body))
+    }
+
+    async fn cast_deleted(store: &SqliteStore, hash: &[u8]) -> bool {
+        sqlx::query_scalar::<_, Option<OffsetDateTime>>(
+            "SELECT deleted_at FROM casts WHERE hash = $1",
+        )
+        .bind(hash)
+        .fetch_one(store.pool())
+        .await
+        .unwrap()
+        .is_some()
+    }
+
+    #[tokio::test]
+    async fn test_cast_remove_wins_over_later_add() {
+        let store = store().await;
+
+        store.apply_message(&cast_add(b"cast", 100), "merge").await.unwrap();
+        store.apply_message(&cast_remove(b"cast", 200), "merge").await.unwrap();
+        assert!(cast_deleted(&store, b"cast").await);
+
+        // The add arriving again (or out of order) does not resurrect it
+        store.apply_message(&cast_add(b"cast", 100), "merge").await.unwrap();
+        assert!(cast_deleted(&store, b"cast").await);
+    }
+
+    #[tokio::test]
+    async fn test_cast_remove_before_add() {
+        let store = store().await;
+
+        store.apply_message(&cast_remove(b"cast", 200), "merge").await.unwrap();
+        store.apply_message(&cast_add(b"cast", 100), "merge").await.unwrap();
+
+        assert!(cast_deleted(&store, b"cast").await);
+        let text: String = sqlx::query_scalar("SELECT text FROM casts WHERE hash = $1")
+            .bind(b"cast".as_slice())
+            .fetch_one(store.pool())
+            .await
+            .unwrap();
+        assert_eq!(text, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_user_data_latest_timestamp_wins() {
+        let store = store().await;
+        let user_data = |hash: &[u8], value: &str, timestamp| {
+            let body = UserData { r#type: 2, value: value.into() };
+            message(hash, 11, timestamp, UserDataBody(body))
+        };
+
+        store.apply_message(&user_data(b"new", "new name", 200), "merge").await.unwrap();
+        store.apply_message(&user_data(b"old", "old name", 100), "merge").await.unwrap();
+
+        let value: String =
+            sqlx::query_scalar("SELECT value FROM user_data WHERE fid = 42 AND type = 2")
+                .fetch_one(store.pool())
+                .await
+                .unwrap();
+        assert_eq!(value, "new name");
+    }
+
+    #[tokio::test]
+    async fn test_batch_logs_messages() {
+        let store = store().await;
+
+        store.apply_message_batch(&[cast_add(b"a", 100), cast_add(b"b", 101)]).await.unwrap();
+
+        let count: i64 = sqlx::query_scalar("SELECT count(*) FROM messages")
+            .fetch_one(store.pool())
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_id_register_refreshes_fids() {
+        let store = store().await;
+        let event = OnChainEvent {
+            r#type: 3,
+            chain_id: 10,
+            block_number: 1000,
+            block_timestamp: 1_700_000_000,
+            transaction_hash: vec![1; 32],
+            fid: 42,
+            body: Some(OnChainBody::IdRegisterEventBody(IdRegisterEventBody {
+                to: vec![0xaa; 20],
+                event_type: 1,
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        store.apply_onchain_event(&event).await.unwrap();
+        // Replays are ignored
+        store.apply_onchain_event(&event).await.unwrap();
+
+        let row = sqlx::query(
+            "SELECT custody_address, register_block_number, transfer_count FROM fids WHERE fid = 42",
+        )
+        .fetch_one(store.pool())
+        .await
+        .unwrap();
+        assert_eq!(row.get::<Vec<u8>, _>("custody_address"), vec![0xaa; 20]);
+        assert_eq!(row.get::<i64, _>("register_block_number"), 1000);
+        assert_eq!(row.get::<i64, _>("transfer_count"), 0);
+    }
+}
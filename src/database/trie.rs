@@ -0,0 +1,151 @@
+//! Queries for summarizing Postgres the way the Hub's merkle trie does.
+//!
+//! The trie holds one key per live message, onchain event and fname proof. Message
+//! keys come from the `messages` log, so `database.store_messages` must be on. Whether
+//! an add is still live is taken from its typed table, the same way
+//! [`signed_messages`](super::signed_messages) decides, since superseded adds aren't
+//! always marked in the log: backfilled and replayed removes don't carry the adds
+//! they replace. Removes and username proofs have no typed row of their own and are
+//! live until deleted, pruned or revoked in the log.
+
+use sqlx::PgPool;
+
+/// Trie entries per FID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FidKeyCount {
+    pub fid: i64,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct MessageKeyRow {
+    pub fid: i64,
+    pub message_type: i16,
+    pub hash: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OnchainEventKeyRow {
+    pub fid: i64,
+    pub event_type: i16,
+    pub tx_hash: Vec<u8>,
+    pub log_index: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct FnameKeyRow {
+    pub fid: i64,
+    pub username: String,
+}
+
+/// Everything the trie has a key for, for a set of FIDs
+#[derive(Debug, Clone, Default)]
+pub struct TrieKeyRows {
+    pub messages: Vec<MessageKeyRow>,
+    pub onchain_events: Vec<OnchainEventKeyRow>,
+    pub fnames: Vec<FnameKeyRow>,
+}
+
+/// Count the trie entries of every FID that has any. Frame actions aren't kept by
+/// the Hub and have no typed table here, so they are left out.
+pub async fn live_key_counts(pool: &PgPool) -> Result<Vec<FidKeyCount>, sqlx::Error> {
+    sqlx::query_as!(
+        FidKeyCount,
+        r#"
+        SELECT fid AS "fid!", SUM(n)::bigint AS "count!"
+        FROM (
+            SELECT m.fid, COUNT(*) AS n
+            FROM messages m
+            WHERE m.deleted_at IS NULL AND m.pruned_at IS NULL AND m.revoked_at IS NULL
+              AND (
+                  m.type IN (2, 4, 6, 8, 12)
+                  OR (m.type = 1 AND EXISTS (
+                      SELECT 1 FROM casts t WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+                  OR (m.type = 3 AND EXISTS (
+                      SELECT 1 FROM reactions t WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+                  OR (m.type = 5 AND EXISTS (
+                      SELECT 1 FROM links t WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+                  OR (m.type = 7 AND EXISTS (
+                      SELECT 1 FROM verifications t WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+                  OR (m.type = 11 AND EXISTS (
+                      SELECT 1 FROM user_data t WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+                  OR (m.type = 14 AND EXISTS (
+                      SELECT 1 FROM link_compact_states t
+                      WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+                  OR (m.type = 15 AND EXISTS (
+                      SELECT 1 FROM lend_storage t WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+              )
+            GROUP BY m.fid
+            UNION ALL
+            SELECT fid, COUNT(*) FROM onchain_events WHERE deleted_at IS NULL GROUP BY fid
+            UNION ALL
+            SELECT fid, COUNT(*) FROM username_proofs
+            WHERE type = 1 AND deleted_at IS NULL
+            GROUP BY fid
+        ) counts
+        GROUP BY fid
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Load the rows behind the trie keys of some FIDs
+pub async fn live_keys(pool: &PgPool, fids: &[i64]) -> Result<TrieKeyRows, sqlx::Error> {
+    let messages = sqlx::query_as!(
+        MessageKeyRow,
+        r#"
+        SELECT m.fid, m.type AS message_type, m.hash
+        FROM messages m
+        WHERE m.fid = ANY($1)
+          AND m.deleted_at IS NULL AND m.pruned_at IS NULL AND m.revoked_at IS NULL
+          AND (
+              m.type IN (2, 4, 6, 8, 12)
+              OR (m.type = 1 AND EXISTS (
+                  SELECT 1 FROM casts t WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+              OR (m.type = 3 AND EXISTS (
+                  SELECT 1 FROM reactions t WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+              OR (m.type = 5 AND EXISTS (
+                  SELECT 1 FROM links t WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+              OR (m.type = 7 AND EXISTS (
+                  SELECT 1 FROM verifications t WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+              OR (m.type = 11 AND EXISTS (
+                  SELECT 1 FROM user_data t WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+              OR (m.type = 14 AND EXISTS (
+                  SELECT 1 FROM link_compact_states t
+                  WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+              OR (m.type = 15 AND EXISTS (
+                  SELECT 1 FROM lend_storage t WHERE t.hash = m.hash AND t.deleted_at IS NULL))
+          )
+        "#,
+        fids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let onchain_events = sqlx::query_as!(
+        OnchainEventKeyRow,
+        r#"
+        SELECT fid, type AS event_type, tx_hash, log_index
+        FROM onchain_events
+        WHERE fid = ANY($1) AND deleted_at IS NULL
+        "#,
+        fids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let fnames = sqlx::query_as!(
+        FnameKeyRow,
+        r#"
+        SELECT fid, username
+        FROM username_proofs
+        WHERE fid = ANY($1) AND type = 1 AND deleted_at IS NULL
+        "#,
+        fids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(TrieKeyRows { messages, onchain_events, fnames })
+}
@@ -0,0 +1,419 @@
+//! Webhook subscriptions and their delivery outbox.
+//!
+//! - `webhook_subscriptions` holds each endpoint, its signing secret and its filters.
+//! - `webhook_deliveries` is the outbox: one row per matched event and subscription,
+//!   `pending` until it is `delivered` or runs out of attempts and goes `dead`.
+//! - `webhook_delivery_attempts` logs every POST made for a delivery.
+
+use serde_json::Value;
+use sqlx::{PgPool, types::time::OffsetDateTime};
+use uuid::Uuid;
+
+/// Which events a subscription receives. Every non-empty filter must match; an
+/// empty filter matches anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebhookFilters {
+    /// Author of the message, or FID of the on-chain event
+    pub fids: Vec<i64>,
+    /// FIDs mentioned by a cast
+    pub mentioned_fids: Vec<i64>,
+    /// Parent URL of a cast
+    pub parent_urls: Vec<String>,
+    /// Message types such as `cast_add` or `link_remove`
+    pub message_types: Vec<String>,
+    /// On-chain event types such as `id_register`
+    pub onchain_event_types: Vec<String>,
+}
+
+/// A webhook subscription
+#[derive(Debug, Clone)]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub active: bool,
+    pub filters: WebhookFilters,
+    pub created_at: OffsetDateTime,
+}
+
+/// A delivery to enqueue for a matched event
+#[derive(Debug, Clone)]
+pub struct NewWebhookDelivery {
+    pub subscription_id: Uuid,
+    pub event_id: i64,
+    pub payload: Value,
+}
+
+/// A pending delivery claimed by the dispatcher, with its subscription's endpoint
+#[derive(Debug, Clone)]
+pub struct ClaimedDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub payload: Value,
+    /// Attempts made before this one
+    pub attempts: i32,
+}
+
+/// A delivery as listed by the CLI
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub event_id: i64,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+/// One logged POST of a delivery
+#[derive(Debug, Clone)]
+pub struct WebhookDeliveryAttempt {
+    pub delivery_id: Uuid,
+    pub attempt: i32,
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub duration_ms: i32,
+    pub created_at: OffsetDateTime,
+}
+
+/// Create a subscription and return its ID
+pub async fn create_subscription(
+    pool: &PgPool,
+    url: &str,
+    secret: &str,
+    filters: &WebhookFilters,
+) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO webhook_subscriptions
+            (url, secret, fids, mentioned_fids, parent_urls, message_types, onchain_event_types)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id
+        "#,
+        url,
+        secret,
+        &filters.fids,
+        &filters.mentioned_fids,
+        &filters.parent_urls,
+        &filters.message_types,
+        &filters.onchain_event_types
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// Every subscription that hasn't been deleted, oldest first
+pub async fn list_subscriptions(pool: &PgPool) -> Result<Vec<WebhookSubscription>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id, url, secret, active, fids, mentioned_fids, parent_urls, message_types,
+               onchain_event_types, created_at
+        FROM webhook_subscriptions
+        WHERE deleted_at IS NULL
+        ORDER BY created_at
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| WebhookSubscription {
+            id: row.id,
+            url: row.url,
+            secret: row.secret,
+            active: row.active,
+            filters: WebhookFilters {
+                fids: row.fids,
+                mentioned_fids: row.mentioned_fids,
+                parent_urls: row.parent_urls,
+                message_types: row.message_types,
+                onchain_event_types: row.onchain_event_types,
+            },
+            created_at: row.created_at,
+        })
+        .collect())
+}
+
+/// Pause or resume a subscription; returns false if it doesn't exist
+pub async fn set_subscription_active(
+    pool: &PgPool,
+    id: Uuid,
+    active: bool,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        "UPDATE webhook_subscriptions SET active = $2 WHERE id = $1 AND deleted_at IS NULL",
+        id,
+        active
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Delete a subscription and drop its pending deliveries; returns false if it doesn't exist
+pub async fn delete_subscription(pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE webhook_subscriptions SET deleted_at = now(), active = false
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        "DELETE FROM webhook_deliveries WHERE subscription_id = $1 AND status = 'pending'",
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Add deliveries to the outbox. An event already enqueued for a subscription is skipped,
+/// so reprocessing a Hub event doesn't deliver it twice.
+pub async fn enqueue_deliveries(
+    pool: &PgPool,
+    deliveries: &[NewWebhookDelivery],
+) -> Result<u64, sqlx::Error> {
+    if deliveries.is_empty() {
+        return Ok(0);
+    }
+
+    let subscription_ids: Vec<Uuid> = deliveries.iter().map(|d| d.subscription_id).collect();
+    let event_ids: Vec<i64> = deliveries.iter().map(|d| d.event_id).collect();
+    let payloads: Vec<Value> = deliveries.iter().map(|d| d.payload.clone()).collect();
+
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO webhook_deliveries (subscription_id, event_id, payload)
+        SELECT * FROM UNNEST($1::uuid[], $2::bigint[], $3::jsonb[])
+        ON CONFLICT (subscription_id, event_id) DO NOTHING
+        "#,
+        &subscription_ids,
+        &event_ids,
+        &payloads
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Claim up to `limit` due deliveries of active subscriptions.
+///
+/// Claimed rows are pushed `lease` into the future, so another dispatcher won't pick
+/// them up while they are in flight, and they come back on their own if this one dies.
+pub async fn claim_due_deliveries(
+    pool: &PgPool,
+    limit: i64,
+    lease: std::time::Duration,
+) -> Result<Vec<ClaimedDelivery>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        WITH due AS (
+            SELECT d.id
+            FROM webhook_deliveries d
+            JOIN webhook_subscriptions s ON s.id = d.subscription_id
+            WHERE d.status = 'pending' AND d.next_attempt_at <= now() AND s.active
+            ORDER BY d.next_attempt_at
+            LIMIT $1
+            FOR UPDATE OF d SKIP LOCKED
+        ),
+        claimed AS (
+            UPDATE webhook_deliveries d
+            SET next_attempt_at = now() + make_interval(secs => $2)
+            FROM due
+            WHERE d.id = due.id
+            RETURNING d.id, d.subscription_id, d.payload, d.attempts
+        )
+        SELECT claimed.id, claimed.subscription_id, claimed.payload, claimed.attempts,
+               s.url, s.secret
+        FROM claimed
+        JOIN webhook_subscriptions s ON s.id = claimed.subscription_id
+        "#,
+        limit,
+        lease.as_secs_f64()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ClaimedDelivery {
+            id: row.id,
+            subscription_id: row.subscription_id,
+            url: row.url,
+            secret: row.secret,
+            payload: row.payload,
+            attempts: row.attempts,
+        })
+        .collect())
+}
+
+/// Log one POST of a delivery
+pub async fn record_attempt(
+    pool: &PgPool,
+    delivery_id: Uuid,
+    attempt: i32,
+    status_code: Option<i32>,
+    error: Option<&str>,
+    duration_ms: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO webhook_delivery_attempts (delivery_id, attempt, status_code, error, duration_ms)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        delivery_id,
+        attempt,
+        status_code,
+        error,
+        duration_ms
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Mark a delivery as delivered
+pub async fn mark_delivered(pool: &PgPool, id: Uuid, attempts: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE webhook_deliveries
+        SET status = 'delivered', attempts = $2, delivered_at = now(), last_error = NULL
+        WHERE id = $1
+        "#,
+        id,
+        attempts
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Put a failed delivery back in the queue until `next_attempt_at`
+pub async fn schedule_retry(
+    pool: &PgPool,
+    id: Uuid,
+    attempts: i32,
+    next_attempt_at: OffsetDateTime,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE webhook_deliveries
+        SET attempts = $2, next_attempt_at = $3, last_error = $4
+        WHERE id = $1
+        "#,
+        id,
+        attempts,
+        next_attempt_at,
+        error
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Move a delivery that ran out of attempts to its subscription's dead letters
+pub async fn dead_letter(
+    pool: &PgPool,
+    id: Uuid,
+    attempts: i32,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE webhook_deliveries
+        SET status = 'dead', attempts = $2, last_error = $3
+        WHERE id = $1
+        "#,
+        id,
+        attempts,
+        error
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Most recent deliveries of a subscription, optionally only those with `status`
+pub async fn list_deliveries(
+    pool: &PgPool,
+    subscription_id: Uuid,
+    status: Option<&str>,
+    limit: i64,
+) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+    let rows = sqlx::query_as!(
+        WebhookDelivery,
+        r#"
+        SELECT id, event_id, status, attempts, last_error, created_at, updated_at
+        FROM webhook_deliveries
+        WHERE subscription_id = $1 AND ($2::text IS NULL OR status = $2)
+        ORDER BY created_at DESC
+        LIMIT $3
+        "#,
+        subscription_id,
+        status,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Most recent attempts across a subscription's deliveries
+pub async fn list_attempts(
+    pool: &PgPool,
+    subscription_id: Uuid,
+    limit: i64,
+) -> Result<Vec<WebhookDeliveryAttempt>, sqlx::Error> {
+    let rows = sqlx::query_as!(
+        WebhookDeliveryAttempt,
+        r#"
+        SELECT a.delivery_id, a.attempt, a.status_code, a.error, a.duration_ms, a.created_at
+        FROM webhook_delivery_attempts a
+        JOIN webhook_deliveries d ON d.id = a.delivery_id
+        WHERE d.subscription_id = $1
+        ORDER BY a.created_at DESC
+        LIMIT $2
+        "#,
+        subscription_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Requeue a subscription's dead letters with a fresh set of attempts
+pub async fn replay_dead_letters(pool: &PgPool, subscription_id: Uuid) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE webhook_deliveries
+        SET status = 'pending', attempts = 0, next_attempt_at = now()
+        WHERE subscription_id = $1 AND status = 'dead'
+        "#,
+        subscription_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
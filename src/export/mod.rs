@@ -0,0 +1,483 @@
+//! Bulk export of synced tables to CSV, JSONL or Parquet files.
+//!
+//! [`export`] streams rows of one entity from PostgreSQL into files under an
+//! output directory, optionally one file per day of message `timestamp`. Rows
+//! are written as they arrive, so memory use stays flat (Parquet buffers one row
+//! group). Hashes and addresses are hex-encoded like
+//! [`format_eth_hex`](crate::processor::format::format_eth_hex).
+//!
+//! With a watermark file, each run only exports rows inserted or updated since
+//! the previous run, and saves the new watermark once every file is written.
+
+mod parquet;
+mod tables;
+mod writer;
+
+use crate::processor::format::format_eth_hex;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, QueryBuilder, Row, postgres::PgRow};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tracing::{info, warn};
+use writer::FileWriter;
+
+/// Rows between progress logs
+const PROGRESS_INTERVAL: u64 = 100_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExportError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid watermark file {}: {1}", .0.display())]
+    Watermark(PathBuf, String),
+}
+
+/// A table that can be exported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportEntity {
+    Casts,
+    Reactions,
+    Links,
+    OnchainEvents,
+}
+
+impl ExportEntity {
+    pub const ALL: [ExportEntity; 4] = [
+        ExportEntity::Casts,
+        ExportEntity::Reactions,
+        ExportEntity::Links,
+        ExportEntity::OnchainEvents,
+    ];
+
+    /// Name of the entity and of its table
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportEntity::Casts => "casts",
+            ExportEntity::Reactions => "reactions",
+            ExportEntity::Links => "links",
+            ExportEntity::OnchainEvents => "onchain_events",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|entity| entity.name() == name)
+    }
+
+    fn columns(self) -> &'static [Column] {
+        match self {
+            ExportEntity::Casts => &tables::CASTS,
+            ExportEntity::Reactions => &tables::REACTIONS,
+            ExportEntity::Links => &tables::LINKS,
+            ExportEntity::OnchainEvents => &tables::ONCHAIN_EVENTS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] =
+        [ExportFormat::Csv, ExportFormat::Jsonl, ExportFormat::Parquet];
+
+    /// Name of the format, which is also the file extension
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.name() == name)
+    }
+}
+
+/// Compression of CSV and JSONL files, or of Parquet pages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+}
+
+/// What to export and where
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub entity: ExportEntity,
+    pub format: ExportFormat,
+    pub compression: Compression,
+    /// Files go under `<output_dir>/<entity>/`
+    pub output_dir: PathBuf,
+    /// Only rows with a message `timestamp` at or after this
+    pub since: Option<DateTime<Utc>>,
+    /// Only rows with a message `timestamp` before this
+    pub until: Option<DateTime<Utc>>,
+    pub min_fid: Option<i64>,
+    pub max_fid: Option<i64>,
+    /// Write one file per UTC day of message `timestamp`, under `date=YYYY-MM-DD/`
+    pub partition_by_day: bool,
+    /// Rows per Parquet row group
+    pub row_group_size: usize,
+    /// Export incrementally from the watermark saved in this file
+    pub watermark: Option<PathBuf>,
+    /// How far behind the database clock the new watermark is set, so rows from
+    /// transactions still in flight are picked up by the next run
+    pub watermark_lag: Duration,
+}
+
+/// Result of an export
+#[derive(Debug, Clone, Default)]
+pub struct ExportSummary {
+    pub rows: u64,
+    pub files: Vec<PathBuf>,
+    /// The saved watermark, for incremental exports
+    pub exported_through: Option<DateTime<Utc>>,
+}
+
+/// Contents of a watermark file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Watermark {
+    pub entity: String,
+    /// Rows updated before this have been exported
+    pub exported_through: DateTime<Utc>,
+}
+
+/// How a column is read and written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColumnType {
+    Int,
+    Text,
+    /// `bytea`, written as 0x-prefixed hex
+    Hash,
+    /// `json` read as text; nested as JSON in JSONL output
+    Json,
+    Timestamp,
+}
+
+/// An exported column
+#[derive(Debug)]
+pub(crate) struct Column {
+    pub name: &'static str,
+    /// SQL expression selecting it
+    pub expr: &'static str,
+    pub ty: ColumnType,
+}
+
+/// A value read from a row
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Cell {
+    Null,
+    Int(i64),
+    Text(String),
+    Timestamp(DateTime<Utc>),
+}
+
+fn decode_row(row: &PgRow, columns: &[Column]) -> Result<Vec<Cell>, sqlx::Error> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            Ok(match column.ty {
+                ColumnType::Int => row.try_get::<Option<i64>, _>(i)?.map_or(Cell::Null, Cell::Int),
+                ColumnType::Text | ColumnType::Json => {
+                    row.try_get::<Option<String>, _>(i)?.map_or(Cell::Null, Cell::Text)
+                },
+                ColumnType::Hash => row
+                    .try_get::<Option<Vec<u8>>, _>(i)?
+                    .map_or(Cell::Null, |bytes| Cell::Text(format_eth_hex(&bytes))),
+                ColumnType::Timestamp => {
+                    row.try_get::<Option<DateTime<Utc>>, _>(i)?.map_or(Cell::Null, Cell::Timestamp)
+                },
+            })
+        })
+        .collect()
+}
+
+/// `(from, to)` range of `updated_at` for an incremental export
+type Window = (Option<DateTime<Utc>>, DateTime<Utc>);
+
+fn build_query(options: &ExportOptions, window: Option<Window>) -> QueryBuilder<'static, Postgres> {
+    let entity = options.entity;
+    let select: Vec<&str> = entity.columns().iter().map(|c| c.expr).collect();
+
+    // Rows without a FID are placeholders for removals that arrived before their add
+    let mut query = QueryBuilder::new(format!(
+        "SELECT {} FROM {} WHERE fid IS NOT NULL",
+        select.join(", "),
+        entity.name()
+    ));
+    if let Some(since) = options.since {
+        query.push(" AND \"timestamp\" >= ").push_bind(since);
+    }
+    if let Some(until) = options.until {
+        query.push(" AND \"timestamp\" < ").push_bind(until);
+    }
+    if let Some(min_fid) = options.min_fid {
+        query.push(" AND fid >= ").push_bind(min_fid);
+    }
+    if let Some(max_fid) = options.max_fid {
+        query.push(" AND fid <= ").push_bind(max_fid);
+    }
+    if let Some((from, to)) = window {
+        if let Some(from) = from {
+            query.push(" AND updated_at >= ").push_bind(from);
+        }
+        query.push(" AND updated_at < ").push_bind(to);
+    }
+    // Day partitions are written one at a time
+    if options.partition_by_day {
+        query.push(" ORDER BY \"timestamp\"");
+    }
+    query
+}
+
+/// Read a watermark file; `None` if it doesn't exist yet
+pub async fn read_watermark(
+    path: &Path,
+    entity: ExportEntity,
+) -> Result<Option<Watermark>, ExportError> {
+    let contents = match tokio::fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let watermark: Watermark = serde_json::from_str(&contents)
+        .map_err(|e| ExportError::Watermark(path.to_path_buf(), e.to_string()))?;
+    if watermark.entity != entity.name() {
+        return Err(ExportError::Watermark(
+            path.to_path_buf(),
+            format!("it belongs to {}, not {}", watermark.entity, entity.name()),
+        ));
+    }
+    Ok(Some(watermark))
+}
+
+/// Replace a watermark file atomically
+async fn write_watermark(path: &Path, watermark: &Watermark) -> Result<(), ExportError> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let json = serde_json::to_string_pretty(watermark)
+        .map_err(|e| ExportError::Watermark(path.to_path_buf(), e.to_string()))?;
+    tokio::fs::write(&tmp, json).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+/// Path of the file for a partition, named after the run so runs never overwrite each other
+fn file_path(options: &ExportOptions, run_id: &str, day: Option<NaiveDate>) -> PathBuf {
+    let entity = options.entity.name();
+    let mut path = options.output_dir.join(entity);
+    if let Some(day) = day {
+        path.push(format!("date={}", day.format("%Y-%m-%d")));
+    }
+
+    let mut name = format!("{}-{}.{}", entity, run_id, options.format.name());
+    if options.compression == Compression::Gzip && options.format != ExportFormat::Parquet {
+        name.push_str(".gz");
+    }
+    path.join(name)
+}
+
+/// Export one entity
+pub async fn export(pool: &PgPool, options: &ExportOptions) -> Result<ExportSummary, ExportError> {
+    let run_id = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    let window = match &options.watermark {
+        Some(path) => {
+            let from = read_watermark(path, options.entity).await?.map(|w| w.exported_through);
+            let to: DateTime<Utc> = sqlx::query_scalar("SELECT now() - make_interval(secs => $1)")
+                .bind(options.watermark_lag.as_secs_f64())
+                .fetch_one(pool)
+                .await?;
+            Some((from, to))
+        },
+        None => None,
+    };
+
+    let mut summary = ExportSummary::default();
+    if let Err(e) = write_files(pool, options, &run_id, window, &mut summary).await {
+        // Don't leave half an export behind; the next run starts from the same watermark
+        for file in &summary.files {
+            if let Err(remove_error) = std::fs::remove_file(file) {
+                warn!("Failed to remove {}: {}", file.display(), remove_error);
+            }
+        }
+        return Err(e);
+    }
+
+    if let (Some(path), Some((_, to))) = (&options.watermark, window) {
+        write_watermark(
+            path,
+            &Watermark { entity: options.entity.name().to_string(), exported_through: to },
+        )
+        .await?;
+        summary.exported_through = Some(to);
+    }
+
+    Ok(summary)
+}
+
+async fn write_files(
+    pool: &PgPool,
+    options: &ExportOptions,
+    run_id: &str,
+    window: Option<Window>,
+    summary: &mut ExportSummary,
+) -> Result<(), ExportError> {
+    let columns = options.entity.columns();
+    let timestamp_index =
+        columns.iter().position(|c| c.name == "timestamp").expect("every entity has a timestamp");
+
+    let mut query = build_query(options, window);
+    let mut rows = query.build().fetch(pool);
+    let mut current: Option<(Option<NaiveDate>, FileWriter)> = None;
+
+    while let Some(row) = rows.try_next().await? {
+        let cells = decode_row(&row, columns)?;
+        let day = match &cells[timestamp_index] {
+            Cell::Timestamp(ts) if options.partition_by_day => Some(ts.date_naive()),
+            _ => None,
+        };
+
+        if current.as_ref().is_none_or(|(open_day, _)| *open_day != day) {
+            if let Some((_, file)) = current.take() {
+                file.finish()?;
+            }
+            let path = file_path(options, run_id, day);
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let file = FileWriter::create(
+                &path,
+                options.format,
+                options.compression,
+                columns,
+                options.row_group_size,
+            )?;
+            summary.files.push(path);
+            current = Some((day, file));
+        }
+
+        if let Some((_, file)) = current.as_mut() {
+            file.write_row(cells)?;
+        }
+        summary.rows += 1;
+        if summary.rows.is_multiple_of(PROGRESS_INTERVAL) {
+            info!("Exported {} {} rows", summary.rows, options.entity.name());
+        }
+    }
+
+    if let Some((_, file)) = current.take() {
+        file.finish()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> ExportOptions {
+        ExportOptions {
+            entity: ExportEntity::Reactions,
+            format: ExportFormat::Csv,
+            compression: Compression::None,
+            output_dir: PathBuf::from("/tmp/export"),
+            since: None,
+            until: None,
+            min_fid: None,
+            max_fid: None,
+            partition_by_day: false,
+            row_group_size: 1000,
+            watermark: None,
+            watermark_lag: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        for entity in ExportEntity::ALL {
+            assert_eq!(ExportEntity::from_name(entity.name()), Some(entity));
+            assert_eq!(entity.columns()[3].name, "timestamp");
+        }
+        for format in ExportFormat::ALL {
+            assert_eq!(ExportFormat::from_name(format.name()), Some(format));
+        }
+        assert_eq!(ExportEntity::from_name("user_data"), None);
+    }
+
+    #[test]
+    fn test_query() {
+        let sql = build_query(&options(), None).into_sql();
+        assert!(sql.starts_with("SELECT id::text, fid, hash, \"timestamp\""));
+        assert!(sql.ends_with("FROM reactions WHERE fid IS NOT NULL"));
+
+        let mut filtered = options();
+        filtered.since = Some(Utc::now());
+        filtered.min_fid = Some(1);
+        filtered.max_fid = Some(10);
+        filtered.partition_by_day = true;
+        let sql = build_query(&filtered, Some((None, Utc::now()))).into_sql();
+        assert!(sql.ends_with(
+            "WHERE fid IS NOT NULL AND \"timestamp\" >= $1 AND fid >= $2 AND fid <= $3 \
+             AND updated_at < $4 ORDER BY \"timestamp\""
+        ));
+
+        let sql = build_query(&options(), Some((Some(Utc::now()), Utc::now()))).into_sql();
+        assert!(sql.ends_with("AND updated_at >= $1 AND updated_at < $2"));
+    }
+
+    #[test]
+    fn test_file_paths() {
+        let mut options = options();
+        let day = NaiveDate::from_ymd_opt(2024, 5, 1);
+        assert_eq!(
+            file_path(&options, "20240502T000000Z", None),
+            PathBuf::from("/tmp/export/reactions/reactions-20240502T000000Z.csv")
+        );
+
+        options.compression = Compression::Gzip;
+        assert_eq!(
+            file_path(&options, "run", day),
+            PathBuf::from("/tmp/export/reactions/date=2024-05-01/reactions-run.csv.gz")
+        );
+
+        options.format = ExportFormat::Parquet;
+        assert_eq!(
+            file_path(&options, "run", day),
+            PathBuf::from("/tmp/export/reactions/date=2024-05-01/reactions-run.parquet")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watermark_file() {
+        let dir = std::env::temp_dir().join(format!("waypoint-export-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("casts.json");
+
+        assert_eq!(read_watermark(&path, ExportEntity::Casts).await.unwrap(), None);
+
+        let watermark = Watermark { entity: "casts".to_string(), exported_through: Utc::now() };
+        write_watermark(&path, &watermark).await.unwrap();
+        assert_eq!(read_watermark(&path, ExportEntity::Casts).await.unwrap(), Some(watermark));
+        assert!(matches!(
+            read_watermark(&path, ExportEntity::Links).await,
+            Err(ExportError::Watermark(..))
+        ));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,210 @@
+//! Parquet output through the `parquet` crate's Arrow writer.
+//!
+//! Every column is nullable. Rows are buffered until a row group is full and then
+//! written as one record batch, so memory is bounded by the row group size. Pages
+//! are optionally gzip-compressed.
+
+use super::{Cell, Column, ColumnType};
+use ::parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, GzipLevel},
+    file::properties::WriterProperties,
+};
+use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use std::{
+    io::{self, Write},
+    sync::Arc,
+};
+
+/// Writes rows to a Parquet file
+pub struct ParquetWriter<W: Write + Send> {
+    writer: ArrowWriter<W>,
+    schema: Arc<Schema>,
+    columns: &'static [Column],
+    row_group_size: usize,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl<W: Write + Send> ParquetWriter<W> {
+    pub fn new(
+        out: W,
+        columns: &'static [Column],
+        gzip: bool,
+        row_group_size: usize,
+    ) -> io::Result<Self> {
+        let row_group_size = row_group_size.max(1);
+        let compression =
+            if gzip { Compression::GZIP(GzipLevel::default()) } else { Compression::UNCOMPRESSED };
+        let properties = WriterProperties::builder()
+            .set_compression(compression)
+            .set_max_row_group_size(row_group_size)
+            .set_created_by(concat!("waypoint version ", env!("CARGO_PKG_VERSION")).to_string())
+            .build();
+
+        let schema = Arc::new(schema(columns));
+        let writer = ArrowWriter::try_new(out, schema.clone(), Some(properties))
+            .map_err(io::Error::other)?;
+        Ok(Self { writer, schema, columns, row_group_size, rows: Vec::new() })
+    }
+
+    pub fn write_row(&mut self, row: Vec<Cell>) -> io::Result<()> {
+        self.rows.push(row);
+        if self.rows.len() >= self.row_group_size {
+            self.flush_row_group()?;
+        }
+        Ok(())
+    }
+
+    fn flush_row_group(&mut self) -> io::Result<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+
+        let rows = std::mem::take(&mut self.rows);
+        let arrays = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| column_array(column, rows.iter().map(|row| &row[i])))
+            .collect::<io::Result<Vec<ArrayRef>>>()?;
+        let batch = RecordBatch::try_new(self.schema.clone(), arrays).map_err(io::Error::other)?;
+        self.writer.write(&batch).map_err(io::Error::other)?;
+        self.writer.flush().map_err(io::Error::other)
+    }
+
+    /// Write the remaining rows and the footer, and return the inner writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush_row_group()?;
+        self.writer.into_inner().map_err(io::Error::other)
+    }
+}
+
+/// Arrow schema of the exported columns
+fn schema(columns: &[Column]) -> Schema {
+    let fields: Vec<Field> = columns
+        .iter()
+        .map(|column| {
+            let data_type = match column.ty {
+                ColumnType::Int => DataType::Int64,
+                ColumnType::Timestamp => {
+                    DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+                },
+                ColumnType::Text | ColumnType::Hash | ColumnType::Json => DataType::Utf8,
+            };
+            Field::new(column.name, data_type, true)
+        })
+        .collect();
+    Schema::new(fields)
+}
+
+/// One column of a row group
+fn column_array<'a>(
+    column: &Column,
+    cells: impl Iterator<Item = &'a Cell>,
+) -> io::Result<ArrayRef> {
+    let mismatch = |cell: &Cell| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{:?} value in {:?} column {}", cell, column.ty, column.name),
+        )
+    };
+
+    Ok(match column.ty {
+        ColumnType::Int => Arc::new(
+            cells
+                .map(|cell| match cell {
+                    Cell::Null => Ok(None),
+                    Cell::Int(value) => Ok(Some(*value)),
+                    cell => Err(mismatch(cell)),
+                })
+                .collect::<io::Result<Int64Array>>()?,
+        ),
+        ColumnType::Timestamp => Arc::new(
+            cells
+                .map(|cell| match cell {
+                    Cell::Null => Ok(None),
+                    Cell::Timestamp(value) => Ok(Some(value.timestamp_micros())),
+                    cell => Err(mismatch(cell)),
+                })
+                .collect::<io::Result<TimestampMicrosecondArray>>()?
+                .with_timezone("UTC"),
+        ),
+        ColumnType::Text | ColumnType::Hash | ColumnType::Json => Arc::new(
+            cells
+                .map(|cell| match cell {
+                    Cell::Null => Ok(None),
+                    Cell::Text(value) => Ok(Some(value.as_str())),
+                    cell => Err(mismatch(cell)),
+                })
+                .collect::<io::Result<StringArray>>()?,
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::parquet::{
+        arrow::arrow_reader::ParquetRecordBatchReaderBuilder, file::reader::FileReader,
+        file::serialized_reader::SerializedFileReader,
+    };
+    use arrow_array::Array;
+    use chrono::{TimeZone, Utc};
+
+    static COLUMNS: [Column; 3] = [
+        Column { name: "fid", expr: "fid", ty: ColumnType::Int },
+        Column { name: "text", expr: "text", ty: ColumnType::Text },
+        Column { name: "timestamp", expr: "timestamp", ty: ColumnType::Timestamp },
+    ];
+
+    #[test]
+    fn test_type_mismatch() {
+        let cells = [Cell::Text("x".to_string())];
+        assert!(column_array(&COLUMNS[0], cells.iter()).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = std::env::temp_dir().join(format!("waypoint-{}.parquet", uuid::Uuid::new_v4()));
+        let file = std::fs::File::create_new(&path).unwrap();
+        let mut writer = ParquetWriter::new(file, &COLUMNS, true, 2).unwrap();
+        for fid in 0..5 {
+            writer
+                .write_row(vec![
+                    Cell::Int(fid),
+                    if fid % 2 == 0 { Cell::Text(format!("cast {}", fid)) } else { Cell::Null },
+                    Cell::Timestamp(Utc.timestamp_opt(1_700_000_000 + fid, 0).unwrap()),
+                ])
+                .unwrap();
+        }
+        writer.finish().unwrap().sync_all().unwrap();
+
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.num_row_groups(), 3);
+        assert!(matches!(metadata.row_group(0).column(1).compression(), Compression::GZIP(_)));
+
+        let batches: Vec<RecordBatch> =
+            ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+                .unwrap()
+                .build()
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 5);
+
+        let fids = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(fids.values().to_vec(), vec![0, 1, 2, 3, 4]);
+        let texts = batch.column(1).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(texts.value(2), "cast 2");
+        assert!(texts.is_null(3));
+        let timestamps =
+            batch.column(2).as_any().downcast_ref::<TimestampMicrosecondArray>().unwrap();
+        assert_eq!(timestamps.value(4), 1_700_000_004_000_000);
+        assert_eq!(timestamps.timezone(), Some("UTC"));
+    }
+}
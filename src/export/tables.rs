@@ -0,0 +1,75 @@
+//! Columns exported for each entity.
+
+use super::{Column, ColumnType::*};
+
+const fn column(name: &'static str, expr: &'static str, ty: super::ColumnType) -> Column {
+    Column { name, expr, ty }
+}
+
+pub(super) static CASTS: [Column; 18] = [
+    column("id", "id::text", Text),
+    column("fid", "fid", Int),
+    column("hash", "hash", Hash),
+    column("timestamp", "\"timestamp\"", Timestamp),
+    column("type", "type::bigint", Int),
+    column("text", "text", Text),
+    column("parent_fid", "parent_fid", Int),
+    column("parent_hash", "parent_hash", Hash),
+    column("parent_url", "parent_url", Text),
+    column("root_parent_fid", "root_parent_fid", Int),
+    column("root_parent_hash", "root_parent_hash", Hash),
+    column("root_parent_url", "root_parent_url", Text),
+    column("embeds", "embeds::text", Json),
+    column("mentions", "mentions::text", Json),
+    column("mentions_positions", "mentions_positions::text", Json),
+    column("created_at", "created_at", Timestamp),
+    column("updated_at", "updated_at", Timestamp),
+    column("deleted_at", "deleted_at", Timestamp),
+];
+
+pub(super) static REACTIONS: [Column; 11] = [
+    column("id", "id::text", Text),
+    column("fid", "fid", Int),
+    column("hash", "hash", Hash),
+    column("timestamp", "\"timestamp\"", Timestamp),
+    column("type", "type::bigint", Int),
+    column("target_cast_fid", "target_cast_fid", Int),
+    column("target_cast_hash", "target_cast_hash", Hash),
+    column("target_url", "target_url", Text),
+    column("created_at", "created_at", Timestamp),
+    column("updated_at", "updated_at", Timestamp),
+    column("deleted_at", "deleted_at", Timestamp),
+];
+
+pub(super) static LINKS: [Column; 11] = [
+    column("id", "id::text", Text),
+    column("fid", "fid", Int),
+    column("hash", "hash", Hash),
+    column("timestamp", "\"timestamp\"", Timestamp),
+    column("type", "type", Text),
+    column("target_fid", "target_fid", Int),
+    column("display_timestamp", "display_timestamp", Timestamp),
+    column("compact_state_hash", "compact_state_hash", Hash),
+    column("created_at", "created_at", Timestamp),
+    column("updated_at", "updated_at", Timestamp),
+    column("deleted_at", "deleted_at", Timestamp),
+];
+
+pub(super) static ONCHAIN_EVENTS: [Column; 16] = [
+    column("id", "id::text", Text),
+    column("fid", "fid", Int),
+    column("hash", "hash", Hash),
+    column("timestamp", "\"timestamp\"", Timestamp),
+    column("type", "type::bigint", Int),
+    column("chain_id", "chain_id", Int),
+    column("block_number", "block_number", Int),
+    column("block_hash", "block_hash", Hash),
+    column("block_timestamp", "block_timestamp", Timestamp),
+    column("tx_hash", "tx_hash", Hash),
+    column("tx_index", "tx_index::bigint", Int),
+    column("log_index", "log_index::bigint", Int),
+    column("signer_address", "signer_address", Hash),
+    column("created_at", "created_at", Timestamp),
+    column("updated_at", "updated_at", Timestamp),
+    column("deleted_at", "deleted_at", Timestamp),
+];
@@ -0,0 +1,211 @@
+//! Output files in each export format.
+
+use super::{Cell, Column, ColumnType, Compression, ExportFormat, parquet::ParquetWriter};
+use chrono::SecondsFormat;
+use flate2::write::GzEncoder;
+use serde_json::{Map, Value};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+/// A file, gzipped or not
+pub(super) enum Sink {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl Sink {
+    fn finish(self) -> io::Result<()> {
+        let mut file = match self {
+            Sink::Plain(file) => file,
+            Sink::Gzip(gzip) => gzip.finish()?,
+        };
+        file.flush()?;
+        file.get_ref().sync_all()
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Sink::Plain(file) => file.write(buf),
+            Sink::Gzip(gzip) => gzip.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Sink::Plain(file) => file.flush(),
+            Sink::Gzip(gzip) => gzip.flush(),
+        }
+    }
+}
+
+/// One output file
+pub(super) enum FileWriter {
+    Csv(Sink),
+    Jsonl(Sink, &'static [Column]),
+    Parquet(ParquetWriter<BufWriter<File>>),
+}
+
+impl FileWriter {
+    /// Create `path`, which must not exist yet
+    pub fn create(
+        path: &Path,
+        format: ExportFormat,
+        compression: Compression,
+        columns: &'static [Column],
+        row_group_size: usize,
+    ) -> io::Result<Self> {
+        let file = BufWriter::new(File::create_new(path)?);
+        let gzip = compression == Compression::Gzip;
+
+        // Parquet compresses pages itself
+        if format == ExportFormat::Parquet {
+            return Ok(FileWriter::Parquet(ParquetWriter::new(
+                file,
+                columns,
+                gzip,
+                row_group_size,
+            )?));
+        }
+
+        let mut sink = if gzip {
+            Sink::Gzip(GzEncoder::new(file, flate2::Compression::default()))
+        } else {
+            Sink::Plain(file)
+        };
+        Ok(match format {
+            ExportFormat::Csv => {
+                let header: Vec<String> = columns.iter().map(|c| csv_field(c.name)).collect();
+                writeln!(sink, "{}", header.join(","))?;
+                FileWriter::Csv(sink)
+            },
+            _ => FileWriter::Jsonl(sink, columns),
+        })
+    }
+
+    pub fn write_row(&mut self, row: Vec<Cell>) -> io::Result<()> {
+        match self {
+            FileWriter::Csv(sink) => {
+                let fields: Vec<String> = row.iter().map(csv_cell).collect();
+                writeln!(sink, "{}", fields.join(","))
+            },
+            FileWriter::Jsonl(sink, columns) => {
+                let object: Map<String, Value> = columns
+                    .iter()
+                    .zip(row)
+                    .map(|(column, cell)| (column.name.to_string(), json_cell(column, cell)))
+                    .collect();
+                serde_json::to_writer(&mut *sink, &object)?;
+                sink.write_all(b"\n")
+            },
+            FileWriter::Parquet(writer) => writer.write_row(row),
+        }
+    }
+
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            FileWriter::Csv(sink) | FileWriter::Jsonl(sink, _) => sink.finish(),
+            FileWriter::Parquet(writer) => {
+                let mut file = writer.finish()?;
+                file.flush()?;
+                file.get_ref().sync_all()
+            },
+        }
+    }
+}
+
+fn timestamp(cell: &chrono::DateTime<chrono::Utc>) -> String {
+    cell.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// Quote a CSV field if it needs it (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_cell(cell: &Cell) -> String {
+    match cell {
+        Cell::Null => String::new(),
+        Cell::Int(value) => value.to_string(),
+        Cell::Text(value) => csv_field(value),
+        Cell::Timestamp(value) => timestamp(value),
+    }
+}
+
+fn json_cell(column: &Column, cell: Cell) -> Value {
+    match cell {
+        Cell::Null => Value::Null,
+        Cell::Int(value) => Value::from(value),
+        // JSON columns are nested as-is rather than as strings
+        Cell::Text(value) if column.ty == ColumnType::Json => {
+            serde_json::from_str(&value).unwrap_or(Value::String(value))
+        },
+        Cell::Text(value) => Value::String(value),
+        Cell::Timestamp(value) => Value::String(timestamp(&value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_csv_cells() {
+        assert_eq!(csv_cell(&Cell::Null), "");
+        assert_eq!(csv_cell(&Cell::Int(-3)), "-3");
+        assert_eq!(csv_cell(&Cell::Text("gm".to_string())), "gm");
+        assert_eq!(csv_cell(&Cell::Text("a, \"b\"\nc".to_string())), "\"a, \"\"b\"\"\nc\"");
+        assert_eq!(
+            csv_cell(&Cell::Timestamp(Utc.timestamp_opt(1_700_000_000, 0).unwrap())),
+            "2023-11-14T22:13:20Z"
+        );
+    }
+
+    #[test]
+    fn test_json_cells() {
+        let json = Column { name: "embeds", expr: "embeds::text", ty: ColumnType::Json };
+        let text = Column { name: "text", expr: "text", ty: ColumnType::Text };
+
+        assert_eq!(
+            json_cell(&json, Cell::Text(r#"[{"url":"https://x"}]"#.to_string())),
+            serde_json::json!([{ "url": "https://x" }])
+        );
+        assert_eq!(json_cell(&text, Cell::Text("[1]".to_string())), Value::from("[1]"));
+        assert_eq!(json_cell(&text, Cell::Null), Value::Null);
+    }
+
+    #[test]
+    fn test_gzip_round_trip() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        static COLUMNS: [Column; 2] = [
+            Column { name: "fid", expr: "fid", ty: ColumnType::Int },
+            Column { name: "text", expr: "text", ty: ColumnType::Text },
+        ];
+        let path = std::env::temp_dir().join(format!("waypoint-{}.csv.gz", uuid::Uuid::new_v4()));
+        let mut writer =
+            FileWriter::create(&path, ExportFormat::Csv, Compression::Gzip, &COLUMNS, 1).unwrap();
+        for fid in 0..1000 {
+            writer.write_row(vec![Cell::Int(fid), Cell::Text(format!("gm {}", fid))]).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let mut csv = String::new();
+        GzDecoder::new(File::open(&path).unwrap()).read_to_string(&mut csv).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 1001);
+        assert_eq!(lines[0], "fid,text");
+        assert_eq!(lines[1000], "999,gm 999");
+    }
+}
@@ -11,7 +11,11 @@ use tokio::sync::{Mutex, oneshot};
 use tracing::{error, info, warn};
 
 use crate::{
-    config::ServiceMode, database::client::Database, hub::client::Hub, redis::client::Redis,
+    backfill::{progress::ProgressStore, worker::BackfillQueue},
+    config::ServiceMode,
+    database::client::Database,
+    hub::client::Hub,
+    redis::client::Redis,
 };
 
 #[derive(Clone)]
@@ -66,6 +70,7 @@ impl HealthServer {
         let app = Router::new()
             .route("/health", get(health_check))
             .route("/ready", get(readiness_check))
+            .route("/backfill/progress", get(backfill_progress))
             .with_state(state);
 
         info!("Starting health check server on port {}", self.port);
@@ -214,6 +219,20 @@ async fn readiness_check(State(state): State<AppState>) -> Response {
     (status, Json(response)).into_response()
 }
 
+// Combined FID backfill progress across every worker, read from Redis
+async fn backfill_progress(State(state): State<AppState>) -> Response {
+    let queue = BackfillQueue::new(state.redis.clone(), "backfill:fid:queue".to_string());
+    let queue_metrics = queue.get_metrics().await;
+
+    match ProgressStore::new(state.redis).load(&queue_metrics).await {
+        Ok(progress) => (StatusCode::OK, Json(progress)).into_response(),
+        Err(e) => {
+            warn!("Failed to load backfill progress: {:?}", e);
+            (StatusCode::SERVICE_UNAVAILABLE, format!("Redis error: {}", e)).into_response()
+        },
+    }
+}
+
 async fn shutdown_signal(rx: oneshot::Receiver<()>) {
     let _ = rx.await;
     info!("Health check server received shutdown signal");
@@ -583,6 +583,65 @@ impl Hub {
         .await
     }
 
+    /// Get the FIDs stored on one shard with retry logic
+    pub async fn get_shard_fids(
+        &self,
+        shard_id: u32,
+        page_size: Option<u32>,
+        page_token: Option<Vec<u8>>,
+    ) -> Result<FidsResponse, Error> {
+        let channel = self.channel.clone();
+        let headers = Arc::clone(&self.headers);
+
+        self.retry_with_backoff(|| {
+            let channel = channel.clone();
+            let page_token = page_token.clone();
+            let headers = Arc::clone(&headers);
+            Box::pin(async move {
+                let channel = channel.ok_or(Error::NotConnected)?;
+                let mut client = Self::create_authenticated_client(channel, Arc::clone(&headers));
+                let request = tonic::Request::new(FidsRequest {
+                    page_size,
+                    page_token,
+                    reverse: None,
+                    shard_id,
+                });
+                match client.get_fids(request).await {
+                    Ok(response) => Ok(response.into_inner()),
+                    Err(status) => Err(Error::StatusError(status)),
+                }
+            })
+        })
+        .await
+    }
+
+    /// Get a shard's merkle trie node at `prefix`, with its direct children
+    pub async fn get_trie_metadata_by_prefix(
+        &self,
+        shard_id: u32,
+        prefix: Vec<u8>,
+    ) -> Result<crate::proto::TrieNodeMetadataResponse, Error> {
+        let channel = self.channel.clone();
+        let headers = Arc::clone(&self.headers);
+
+        self.retry_with_backoff(|| {
+            let channel = channel.clone();
+            let prefix = prefix.clone();
+            let headers = Arc::clone(&headers);
+            Box::pin(async move {
+                let channel = channel.ok_or(Error::NotConnected)?;
+                let mut client = Self::create_authenticated_client(channel, Arc::clone(&headers));
+                let request =
+                    tonic::Request::new(crate::proto::TrieNodeMetadataRequest { shard_id, prefix });
+                match client.get_trie_metadata_by_prefix(request).await {
+                    Ok(response) => Ok(response.into_inner()),
+                    Err(status) => Err(Error::StatusError(status)),
+                }
+            })
+        })
+        .await
+    }
+
     /// Get casts by FID with retry logic and custom headers
     pub async fn get_casts_by_fid(
         &self,
@@ -703,6 +762,105 @@ impl Hub {
         .await
     }
 
+    /// Get all cast messages by FID, including removes, with retry logic and custom headers
+    pub async fn get_all_cast_messages_by_fid(
+        &self,
+        request: crate::proto::FidTimestampRequest,
+    ) -> Result<crate::proto::MessagesResponse, Error> {
+        let channel = self.channel.clone();
+        let headers = Arc::clone(&self.headers);
+
+        self.retry_with_backoff(|| {
+            let channel = channel.clone();
+            let request = request.clone();
+            let headers = Arc::clone(&headers);
+            Box::pin(async move {
+                let channel = channel.ok_or(Error::NotConnected)?;
+                let mut client = Self::create_authenticated_client(channel, Arc::clone(&headers));
+                match client.get_all_cast_messages_by_fid(tonic::Request::new(request)).await {
+                    Ok(response) => Ok(response.into_inner()),
+                    Err(status) => Err(Error::StatusError(status)),
+                }
+            })
+        })
+        .await
+    }
+
+    /// Get all reaction messages by FID, including removes, with retry logic and custom headers
+    pub async fn get_all_reaction_messages_by_fid(
+        &self,
+        request: crate::proto::FidTimestampRequest,
+    ) -> Result<crate::proto::MessagesResponse, Error> {
+        let channel = self.channel.clone();
+        let headers = Arc::clone(&self.headers);
+
+        self.retry_with_backoff(|| {
+            let channel = channel.clone();
+            let request = request.clone();
+            let headers = Arc::clone(&headers);
+            Box::pin(async move {
+                let channel = channel.ok_or(Error::NotConnected)?;
+                let mut client = Self::create_authenticated_client(channel, Arc::clone(&headers));
+                match client.get_all_reaction_messages_by_fid(tonic::Request::new(request)).await {
+                    Ok(response) => Ok(response.into_inner()),
+                    Err(status) => Err(Error::StatusError(status)),
+                }
+            })
+        })
+        .await
+    }
+
+    /// Get all link messages by FID, including removes, with retry logic and custom headers
+    pub async fn get_all_link_messages_by_fid(
+        &self,
+        request: crate::proto::FidTimestampRequest,
+    ) -> Result<crate::proto::MessagesResponse, Error> {
+        let channel = self.channel.clone();
+        let headers = Arc::clone(&self.headers);
+
+        self.retry_with_backoff(|| {
+            let channel = channel.clone();
+            let request = request.clone();
+            let headers = Arc::clone(&headers);
+            Box::pin(async move {
+                let channel = channel.ok_or(Error::NotConnected)?;
+                let mut client = Self::create_authenticated_client(channel, Arc::clone(&headers));
+                match client.get_all_link_messages_by_fid(tonic::Request::new(request)).await {
+                    Ok(response) => Ok(response.into_inner()),
+                    Err(status) => Err(Error::StatusError(status)),
+                }
+            })
+        })
+        .await
+    }
+
+    /// Get all verification messages by FID, including removes, with retry logic and custom headers
+    pub async fn get_all_verification_messages_by_fid(
+        &self,
+        request: crate::proto::FidTimestampRequest,
+    ) -> Result<crate::proto::MessagesResponse, Error> {
+        let channel = self.channel.clone();
+        let headers = Arc::clone(&self.headers);
+
+        self.retry_with_backoff(|| {
+            let channel = channel.clone();
+            let request = request.clone();
+            let headers = Arc::clone(&headers);
+            Box::pin(async move {
+                let channel = channel.ok_or(Error::NotConnected)?;
+                let mut client = Self::create_authenticated_client(channel, Arc::clone(&headers));
+                match client
+                    .get_all_verification_messages_by_fid(tonic::Request::new(request))
+                    .await
+                {
+                    Ok(response) => Ok(response.into_inner()),
+                    Err(status) => Err(Error::StatusError(status)),
+                }
+            })
+        })
+        .await
+    }
+
     /// Get all user data messages by FID with retry logic and custom headers
     pub async fn get_all_user_data_messages_by_fid(
         &self,
@@ -727,6 +885,33 @@ impl Hub {
         .await
     }
 
+    /// Get link compact state messages by FID with retry logic and custom headers
+    pub async fn get_link_compact_state_message_by_fid(
+        &self,
+        request: crate::proto::FidRequest,
+    ) -> Result<crate::proto::MessagesResponse, Error> {
+        let channel = self.channel.clone();
+        let headers = Arc::clone(&self.headers);
+
+        self.retry_with_backoff(|| {
+            let channel = channel.clone();
+            let request = request.clone();
+            let headers = Arc::clone(&headers);
+            Box::pin(async move {
+                let channel = channel.ok_or(Error::NotConnected)?;
+                let mut client = Self::create_authenticated_client(channel, Arc::clone(&headers));
+                match client
+                    .get_link_compact_state_message_by_fid(tonic::Request::new(request))
+                    .await
+                {
+                    Ok(response) => Ok(response.into_inner()),
+                    Err(status) => Err(Error::StatusError(status)),
+                }
+            })
+        })
+        .await
+    }
+
     /// Get all lend storage messages by FID with retry logic and custom headers
     pub async fn get_all_lend_storage_messages_by_fid(
         &self,
@@ -753,6 +938,102 @@ impl Hub {
         .await
     }
 
+    /// Get a FID's storage limits, including how many messages each store holds
+    pub async fn get_current_storage_limits_by_fid(
+        &self,
+        request: crate::proto::FidRequest,
+    ) -> Result<crate::proto::StorageLimitsResponse, Error> {
+        let channel = self.channel.clone();
+        let headers = Arc::clone(&self.headers);
+
+        self.retry_with_backoff(|| {
+            let channel = channel.clone();
+            let request = request.clone();
+            let headers = Arc::clone(&headers);
+            Box::pin(async move {
+                let channel = channel.ok_or(Error::NotConnected)?;
+                let mut client = Self::create_authenticated_client(channel, Arc::clone(&headers));
+                match client.get_current_storage_limits_by_fid(tonic::Request::new(request)).await {
+                    Ok(response) => Ok(response.into_inner()),
+                    Err(status) => Err(Error::StatusError(status)),
+                }
+            })
+        })
+        .await
+    }
+
+    /// Get a single cast by its ID
+    pub async fn get_cast(
+        &self,
+        request: crate::proto::CastId,
+    ) -> Result<crate::proto::Message, Error> {
+        let channel = self.channel.clone();
+        let headers = Arc::clone(&self.headers);
+
+        self.retry_with_backoff(|| {
+            let channel = channel.clone();
+            let request = request.clone();
+            let headers = Arc::clone(&headers);
+            Box::pin(async move {
+                let channel = channel.ok_or(Error::NotConnected)?;
+                let mut client = Self::create_authenticated_client(channel, Arc::clone(&headers));
+                match client.get_cast(tonic::Request::new(request)).await {
+                    Ok(response) => Ok(response.into_inner()),
+                    Err(status) => Err(Error::StatusError(status)),
+                }
+            })
+        })
+        .await
+    }
+
+    /// Get replies to a cast, or casts under a parent URL such as a channel
+    pub async fn get_casts_by_parent(
+        &self,
+        request: crate::proto::CastsByParentRequest,
+    ) -> Result<crate::proto::MessagesResponse, Error> {
+        let channel = self.channel.clone();
+        let headers = Arc::clone(&self.headers);
+
+        self.retry_with_backoff(|| {
+            let channel = channel.clone();
+            let request = request.clone();
+            let headers = Arc::clone(&headers);
+            Box::pin(async move {
+                let channel = channel.ok_or(Error::NotConnected)?;
+                let mut client = Self::create_authenticated_client(channel, Arc::clone(&headers));
+                match client.get_casts_by_parent(tonic::Request::new(request)).await {
+                    Ok(response) => Ok(response.into_inner()),
+                    Err(status) => Err(Error::StatusError(status)),
+                }
+            })
+        })
+        .await
+    }
+
+    /// Get reactions to a cast
+    pub async fn get_reactions_by_cast(
+        &self,
+        request: crate::proto::ReactionsByTargetRequest,
+    ) -> Result<crate::proto::MessagesResponse, Error> {
+        let channel = self.channel.clone();
+        let headers = Arc::clone(&self.headers);
+
+        self.retry_with_backoff(|| {
+            let channel = channel.clone();
+            let request = request.clone();
+            let headers = Arc::clone(&headers);
+            Box::pin(async move {
+                let channel = channel.ok_or(Error::NotConnected)?;
+                let mut client = Self::create_authenticated_client(channel, Arc::clone(&headers));
+                match client.get_reactions_by_cast(tonic::Request::new(request)).await {
+                    Ok(response) => Ok(response.into_inner()),
+                    Err(status) => Err(Error::StatusError(status)),
+                }
+            })
+        })
+        .await
+    }
+
     /// Get on-chain events with retry logic and custom headers
     pub async fn get_on_chain_events(
         &self,
@@ -1109,4 +1109,88 @@ impl HubClient for FarcasterHubClient {
 
         Ok(messages)
     }
+
+    async fn get_id_registry_event(&self, fid: Fid) -> Result<Option<crate::proto::OnChainEvent>> {
+        debug!("Fetching IdRegistry event for FID: {}", fid);
+        let mut hub = self.hub.lock().await;
+
+        // Ensure hub is connected
+        if !hub.check_connection().await.map_err(|e| DataAccessError::HubClient(e.to_string()))? {
+            return Err(DataAccessError::HubClient("Hub not connected".to_string()));
+        }
+
+        // Create FidRequest
+        let request =
+            FidRequest { fid: fid.value(), page_size: None, page_token: None, reverse: None };
+
+        // Make the RPC call
+        let response = hub
+            .client()
+            .ok_or_else(|| DataAccessError::HubClient("Hub client not initialized".to_string()))?
+            .get_id_registry_on_chain_event(tonic::Request::new(request))
+            .await;
+
+        match response {
+            Ok(event) => Ok(Some(event.into_inner())),
+            Err(status) if status.code() == tonic::Code::NotFound => Ok(None),
+            Err(e) => Err(DataAccessError::HubClient(e.to_string())),
+        }
+    }
+
+    async fn get_id_registry_event_by_address(
+        &self,
+        address: &[u8],
+    ) -> Result<Option<crate::proto::OnChainEvent>> {
+        debug!("Fetching IdRegistry event for address: 0x{}", Self::format_hex(address));
+        let mut hub = self.hub.lock().await;
+
+        // Ensure hub is connected
+        if !hub.check_connection().await.map_err(|e| DataAccessError::HubClient(e.to_string()))? {
+            return Err(DataAccessError::HubClient("Hub not connected".to_string()));
+        }
+
+        // Create the request
+        let request = crate::proto::IdRegistryEventByAddressRequest { address: address.to_vec() };
+
+        // Make the RPC call
+        let response = hub
+            .client()
+            .ok_or_else(|| DataAccessError::HubClient("Hub client not initialized".to_string()))?
+            .get_id_registry_on_chain_event_by_address(tonic::Request::new(request))
+            .await;
+
+        match response {
+            Ok(event) => Ok(Some(event.into_inner())),
+            Err(status) if status.code() == tonic::Code::NotFound => Ok(None),
+            Err(e) => Err(DataAccessError::HubClient(e.to_string())),
+        }
+    }
+
+    async fn get_onchain_signers_by_fid(
+        &self,
+        fid: Fid,
+    ) -> Result<Vec<crate::proto::OnChainEvent>> {
+        debug!("Fetching onchain signers for FID: {}", fid);
+        let mut hub = self.hub.lock().await;
+
+        // Ensure hub is connected
+        if !hub.check_connection().await.map_err(|e| DataAccessError::HubClient(e.to_string()))? {
+            return Err(DataAccessError::HubClient("Hub not connected".to_string()));
+        }
+
+        // Create FidRequest
+        let request =
+            FidRequest { fid: fid.value(), page_size: None, page_token: None, reverse: None };
+
+        // Make the RPC call
+        let response = hub
+            .client()
+            .ok_or_else(|| DataAccessError::HubClient("Hub client not initialized".to_string()))?
+            .get_on_chain_signers_by_fid(tonic::Request::new(request))
+            .await
+            .map_err(|e| DataAccessError::HubClient(e.to_string()))?
+            .into_inner();
+
+        Ok(response.events)
+    }
 }
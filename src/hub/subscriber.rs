@@ -53,7 +53,7 @@ fn classify_onchain_event_type(onchain_type: Option<i32>) -> &'static str {
 }
 
 /// Classify a HubEvent into its stream key suffix and whether it is a message event.
-fn classify_hub_event(event: &HubEvent) -> (&'static str, bool) {
+pub(crate) fn classify_hub_event(event: &HubEvent) -> (&'static str, bool) {
     match event.r#type {
         // MERGE_MESSAGE | PRUNE_MESSAGE | REVOKE_MESSAGE
         1..=3 => {
@@ -11,6 +11,7 @@ pub mod core;
 pub mod database;
 pub mod error;
 pub mod eth;
+pub mod export;
 pub mod health;
 pub mod hub;
 pub mod metrics;
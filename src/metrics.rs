@@ -155,6 +155,26 @@ fn register_prometheus_metrics() {
     describe_gauge!("waypoint_backfill_jobs_in_queue", "Number of backfill jobs in queue");
     describe_counter!("waypoint_backfill_job_errors", "Total number of backfill job errors");
     describe_gauge!("waypoint_backfill_fids_per_second", "Backfill FIDs processing rate");
+    describe_counter!(
+        "waypoint_backfill_window_messages_fetched",
+        "Messages and onchain events fetched by time-windowed backfills, by type"
+    );
+    describe_counter!(
+        "waypoint_anti_entropy_checks",
+        "FIDs fingerprinted by anti-entropy, by message type"
+    );
+    describe_counter!(
+        "waypoint_anti_entropy_drift",
+        "Anti-entropy checks where Postgres was missing messages, by message type"
+    );
+    describe_gauge!(
+        "waypoint_anti_entropy_drift_rate",
+        "Share of recent anti-entropy checks that found drift, by message type"
+    );
+    describe_counter!(
+        "waypoint_anti_entropy_reconciled",
+        "FIDs reconciled by anti-entropy after their fingerprints differed"
+    );
 
     // Stream metrics
     describe_counter!("waypoint_stream_events_received", "Total number of stream events received");
@@ -174,6 +194,14 @@ fn register_prometheus_metrics() {
     describe_counter!("waypoint_reactions_processed", "Reaction events processed");
     describe_counter!("waypoint_follows_processed", "Follow/link events processed");
     describe_counter!("waypoint_user_data_processed", "User data events processed");
+    describe_counter!(
+        "waypoint_root_parents_resolved",
+        "Replies given a root parent on insert, by source (cache, database, hub, orphan)"
+    );
+    describe_counter!(
+        "waypoint_root_parent_orphans_adopted",
+        "Queued replies given a root parent when an ancestor arrived"
+    );
 
     // Error metrics by type
     describe_counter!("waypoint_errors_total", "Total number of errors by type");
@@ -374,6 +402,54 @@ pub fn set_backfill_fids_per_second(rate: f64) {
     metrics::gauge!("waypoint_backfill_fids_per_second").set(rate);
 }
 
+pub fn increment_backfill_window_fetched(message_type: &str, count: u64) {
+    // StatsD metrics
+    if let Some(client) = get_client() {
+        client.count(&format!("backfill.window.{}.fetched", message_type), count);
+    }
+    // Prometheus metrics
+    metrics::counter!(
+        "waypoint_backfill_window_messages_fetched",
+        "message_type" => message_type.to_string()
+    )
+    .increment(count);
+}
+
+pub fn record_anti_entropy_check(message_type: &str, drifted: bool, drift_rate: f64) {
+    // StatsD metrics
+    if let Some(client) = get_client() {
+        client.incr(&format!("anti_entropy.{}.checked", message_type));
+        if drifted {
+            client.incr(&format!("anti_entropy.{}.drifted", message_type));
+        }
+        client.gauge(&format!("anti_entropy.{}.drift_rate", message_type), drift_rate);
+    }
+    // Prometheus metrics
+    metrics::counter!("waypoint_anti_entropy_checks", "message_type" => message_type.to_string())
+        .increment(1);
+    if drifted {
+        metrics::counter!(
+            "waypoint_anti_entropy_drift",
+            "message_type" => message_type.to_string()
+        )
+        .increment(1);
+    }
+    metrics::gauge!(
+        "waypoint_anti_entropy_drift_rate",
+        "message_type" => message_type.to_string()
+    )
+    .set(drift_rate);
+}
+
+pub fn increment_anti_entropy_reconciled() {
+    // StatsD metrics
+    if let Some(client) = get_client() {
+        client.incr("anti_entropy.reconciled");
+    }
+    // Prometheus metrics
+    metrics::counter!("waypoint_anti_entropy_reconciled").increment(1);
+}
+
 // Stream metrics
 pub fn increment_events_received() {
     // StatsD metrics
@@ -501,6 +577,27 @@ pub fn increment_casts_processed() {
     metrics::counter!("waypoint_events_by_type", "type" => "cast").increment(1);
 }
 
+/// `source` is where a reply's root parent came from: cache, database, hub, or orphan
+/// when it wasn't found and the reply was queued
+pub fn increment_root_parent_resolved(source: &str) {
+    // StatsD metrics
+    if let Some(client) = get_client() {
+        client.incr(&format!("root_parent.{}", source));
+    }
+    // Prometheus metrics
+    metrics::counter!("waypoint_root_parents_resolved", "source" => source.to_string())
+        .increment(1);
+}
+
+pub fn increment_root_parent_orphans_adopted(count: u64) {
+    // StatsD metrics
+    if let Some(client) = get_client() {
+        client.count("root_parent.adopted", count);
+    }
+    // Prometheus metrics
+    metrics::counter!("waypoint_root_parent_orphans_adopted").increment(count);
+}
+
 pub fn increment_reactions_processed() {
     // StatsD metrics
     if let Some(client) = get_client() {
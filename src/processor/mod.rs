@@ -4,6 +4,7 @@ pub mod error;
 pub mod format;
 pub mod print;
 pub mod types;
+pub mod webhook;
 
 pub use consumer::EventProcessor;
 pub use error::Error;
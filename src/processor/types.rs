@@ -1,4 +1,6 @@
-use crate::{config::Config, database::client::Database, hub::client::Hub, redis::client::Redis};
+use crate::{
+    config::Config, database::store::MessageStore, hub::client::Hub, redis::client::Redis,
+};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -8,39 +10,40 @@ use tokio::sync::Mutex;
 /// of the application that are used by processors.
 ///
 /// Hub is optional to support consumer-only mode where we don't need
-/// a hub connection for database writes.
+/// a hub connection for database writes. The store is whichever storage
+/// backend `database.url` selects.
 pub struct AppResources {
     /// Hub client (optional for consumer-only mode)
     pub hub: Option<Arc<Mutex<Hub>>>,
     pub redis: Arc<Redis>,
-    pub database: Arc<Database>,
+    pub store: Arc<dyn MessageStore>,
     pub config: Config,
 }
 
 impl AppResources {
     /// Create resources with all components (backward compatible)
-    pub fn new(hub: Arc<Mutex<Hub>>, redis: Arc<Redis>, database: Arc<Database>) -> Self {
+    pub fn new(hub: Arc<Mutex<Hub>>, redis: Arc<Redis>, store: Arc<dyn MessageStore>) -> Self {
         // Create a default config for backward compatibility
-        Self { hub: Some(hub), redis, database, config: Config::default() }
+        Self { hub: Some(hub), redis, store, config: Config::default() }
     }
 
     /// Create resources with all components and custom config
     pub fn with_config(
         hub: Arc<Mutex<Hub>>,
         redis: Arc<Redis>,
-        database: Arc<Database>,
+        store: Arc<dyn MessageStore>,
         config: Config,
     ) -> Self {
-        Self { hub: Some(hub), redis, database, config }
+        Self { hub: Some(hub), redis, store, config }
     }
 
     /// Create resources without hub (for consumer-only mode)
     pub fn with_config_consumer_only(
         redis: Arc<Redis>,
-        database: Arc<Database>,
+        store: Arc<dyn MessageStore>,
         config: Config,
     ) -> Self {
-        Self { hub: None, redis, database, config }
+        Self { hub: None, redis, store, config }
     }
 }
 
@@ -50,7 +53,7 @@ impl Clone for AppResources {
         Self {
             hub: self.hub.as_ref().map(Arc::clone),
             redis: Arc::clone(&self.redis),
-            database: Arc::clone(&self.database),
+            store: Arc::clone(&self.store),
             config: self.config.clone(),
         }
     }
@@ -0,0 +1,645 @@
+//! Outbound webhooks.
+//!
+//! [`WebhookProcessor`] matches Hub events against the subscriptions in
+//! PostgreSQL and writes one delivery per match to the outbox.
+//! [`WebhookDispatcher`] POSTs due deliveries, signed with the subscription's
+//! secret, and retries failures with exponential backoff until they succeed or
+//! run out of attempts and are dead-lettered. Every POST is logged.
+
+use crate::{
+    config::WebhookConfig,
+    core::types::{Message as FarcasterMessage, MessageType},
+    database::webhooks::{
+        self, ClaimedDelivery, NewWebhookDelivery, WebhookFilters, WebhookSubscription,
+    },
+    processor::consumer::EventProcessor,
+    proto::{HubEvent, MessageData, OnChainEvent, OnChainEventType, hub_event::Body, message_data},
+    services::mcp::handlers::utils::{
+        process_cast_message, process_link_message, process_reaction_message,
+        process_verification_message, user_data_field_name,
+    },
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+use hmac::{Hmac, Mac};
+use parking_lot::RwLock;
+use prost::Message as ProstMessage;
+use serde_json::{Value, json};
+use sha2::Sha256;
+use sqlx::{PgPool, types::time::OffsetDateTime};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::{debug, error, warn};
+
+/// `sha256=<hex HMAC of "<timestamp>.<body>">`, keyed with the subscription secret
+pub const SIGNATURE_HEADER: &str = "x-waypoint-signature";
+/// Unix seconds when the request was signed
+pub const TIMESTAMP_HEADER: &str = "x-waypoint-timestamp";
+/// Delivery ID; stays the same across retries, so receivers can deduplicate
+pub const DELIVERY_HEADER: &str = "x-waypoint-delivery";
+
+/// What an event is, for the type filters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    /// A merged message, by type name (`cast_add`)
+    Message(String),
+    /// An on-chain event, by type name (`id_register`)
+    Onchain(String),
+}
+
+/// The parts of an event that subscription filters look at, and the payload to deliver
+#[derive(Debug, Clone)]
+pub struct WebhookEvent {
+    pub kind: EventKind,
+    pub fid: i64,
+    pub mentioned_fids: Vec<i64>,
+    pub parent_url: Option<String>,
+    pub payload: Value,
+}
+
+impl WebhookEvent {
+    /// Build from a Hub event; `None` for events webhooks don't carry (prunes, revokes, ...)
+    pub fn from_hub_event(event: &HubEvent) -> Option<Self> {
+        match event.body.as_ref()? {
+            Body::MergeMessageBody(body) => {
+                let message = body.message.as_ref()?;
+                Self::from_message(event.id, &message.hash, message.data.as_ref()?)
+            },
+            Body::MergeOnChainEventBody(body) => {
+                Self::from_onchain_event(event.id, body.on_chain_event.as_ref()?)
+            },
+            _ => None,
+        }
+    }
+
+    fn from_message(event_id: u64, hash: &[u8], data: &MessageData) -> Option<Self> {
+        let message_type = crate::proto::MessageType::try_from(data.r#type).ok()?;
+        let type_name = message_type_name(message_type);
+
+        let mut mentioned_fids = Vec::new();
+        let mut parent_url = None;
+        if let Some(message_data::Body::CastAddBody(cast)) = &data.body {
+            mentioned_fids = cast.mentions.iter().map(|fid| *fid as i64).collect();
+            if let Some(crate::proto::cast_add_body::Parent::ParentUrl(url)) = &cast.parent {
+                parent_url = Some(url.clone());
+            }
+        }
+
+        let mut payload = message_json(message_type, hash, data);
+        payload["event_id"] = json!(event_id);
+
+        Some(Self {
+            kind: EventKind::Message(type_name),
+            fid: data.fid as i64,
+            mentioned_fids,
+            parent_url,
+            payload,
+        })
+    }
+
+    fn from_onchain_event(event_id: u64, event: &OnChainEvent) -> Option<Self> {
+        let event_type = OnChainEventType::try_from(event.r#type).ok()?;
+        let type_name = onchain_event_type_name(event_type);
+
+        let mut payload = onchain_event_json(event_type, event);
+        payload["event_id"] = json!(event_id);
+
+        Some(Self {
+            kind: EventKind::Onchain(type_name),
+            fid: event.fid as i64,
+            mentioned_fids: Vec::new(),
+            parent_url: None,
+            payload,
+        })
+    }
+
+    /// Whether a subscription's filters select this event
+    pub fn matches(&self, filters: &WebhookFilters) -> bool {
+        let kind = if filters.message_types.is_empty() && filters.onchain_event_types.is_empty() {
+            true
+        } else {
+            match &self.kind {
+                EventKind::Message(name) => filters.message_types.contains(name),
+                EventKind::Onchain(name) => filters.onchain_event_types.contains(name),
+            }
+        };
+
+        kind && (filters.fids.is_empty() || filters.fids.contains(&self.fid))
+            && (filters.mentioned_fids.is_empty()
+                || self.mentioned_fids.iter().any(|fid| filters.mentioned_fids.contains(fid)))
+            && (filters.parent_urls.is_empty()
+                || self.parent_url.as_ref().is_some_and(|url| filters.parent_urls.contains(url)))
+    }
+}
+
+/// `MESSAGE_TYPE_CAST_ADD` → `cast_add`
+fn message_type_name(message_type: crate::proto::MessageType) -> String {
+    message_type.as_str_name().trim_start_matches("MESSAGE_TYPE_").to_lowercase()
+}
+
+/// `EVENT_TYPE_ID_REGISTER` → `id_register`
+fn onchain_event_type_name(event_type: OnChainEventType) -> String {
+    event_type.as_str_name().trim_start_matches("EVENT_TYPE_").to_lowercase()
+}
+
+/// Whether `name` is a message type name a subscription can filter on
+pub fn is_message_type_name(name: &str) -> bool {
+    crate::proto::MessageType::from_str_name(&format!("MESSAGE_TYPE_{}", name.to_uppercase()))
+        .is_some_and(|t| t != crate::proto::MessageType::None && message_type_name(t) == name)
+}
+
+/// Whether `name` is an on-chain event type name a subscription can filter on
+pub fn is_onchain_event_type_name(name: &str) -> bool {
+    OnChainEventType::from_str_name(&format!("EVENT_TYPE_{}", name.to_uppercase()))
+        .is_some_and(|t| t != OnChainEventType::EventTypeNone && onchain_event_type_name(t) == name)
+}
+
+/// A message as delivered: its type, author, hash and timestamp, plus the shaped body
+pub(crate) fn message_json(
+    message_type: crate::proto::MessageType,
+    hash: &[u8],
+    data: &MessageData,
+) -> Value {
+    let mut payload = json!({
+        "type": message_type_name(message_type),
+        "fid": data.fid,
+        "hash": hex::encode(hash),
+        "timestamp": data.timestamp,
+    });
+    if let Some(shaped) = message_payload(message_type, hash, data) {
+        payload["data"] = shaped;
+    }
+    payload
+}
+
+/// An on-chain event as delivered
+pub(crate) fn onchain_event_json(event_type: OnChainEventType, event: &OnChainEvent) -> Value {
+    json!({
+        "type": "onchain_event",
+        "event_type": onchain_event_type_name(event_type),
+        "fid": event.fid,
+        "chain_id": event.chain_id,
+        "block_number": event.block_number,
+        "block_timestamp": event.block_timestamp,
+        "transaction_hash": format!("0x{}", hex::encode(&event.transaction_hash)),
+        "log_index": event.log_index,
+    })
+}
+
+/// Message body in the same shape as the MCP tools and REST API, where there is one
+fn message_payload(
+    message_type: crate::proto::MessageType,
+    hash: &[u8],
+    data: &MessageData,
+) -> Option<Value> {
+    use crate::proto::MessageType as Proto;
+
+    let message = |kind| FarcasterMessage::new(hex::encode(hash), kind, data.encode_to_vec());
+    match message_type {
+        Proto::CastAdd => {
+            process_cast_message(&message(MessageType::Cast), data).map(Value::Object)
+        },
+        Proto::CastRemove => match &data.body {
+            Some(message_data::Body::CastRemoveBody(body)) => {
+                Some(json!({ "target_hash": hex::encode(&body.target_hash) }))
+            },
+            _ => None,
+        },
+        Proto::ReactionAdd | Proto::ReactionRemove => {
+            process_reaction_message(&message(MessageType::Reaction), data).map(Value::Object)
+        },
+        Proto::LinkAdd | Proto::LinkRemove => {
+            process_link_message(&message(MessageType::Link), data).map(Value::Object)
+        },
+        Proto::VerificationAddEthAddress | Proto::VerificationRemove => {
+            process_verification_message(&message(MessageType::Verification))
+        },
+        Proto::UserDataAdd => match &data.body {
+            Some(message_data::Body::UserDataBody(body)) => Some(json!({
+                "field": user_data_field_name(body.r#type).unwrap_or("unknown"),
+                "value": body.value,
+            })),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Matches events against subscriptions and enqueues deliveries
+pub struct WebhookProcessor {
+    pool: PgPool,
+    refresh_interval: Duration,
+    /// Active subscriptions and when they were loaded
+    subscriptions: RwLock<Option<(Instant, Arc<Vec<WebhookSubscription>>)>>,
+}
+
+impl WebhookProcessor {
+    pub fn new(pool: PgPool, config: &WebhookConfig) -> Self {
+        Self {
+            pool,
+            refresh_interval: Duration::from_secs(config.refresh_interval_secs),
+            subscriptions: RwLock::new(None),
+        }
+    }
+
+    /// Active subscriptions, reloaded once `refresh_interval` has passed
+    async fn subscriptions(&self) -> Result<Arc<Vec<WebhookSubscription>>, sqlx::Error> {
+        if let Some((loaded_at, subscriptions)) = self.subscriptions.read().as_ref()
+            && loaded_at.elapsed() < self.refresh_interval
+        {
+            return Ok(Arc::clone(subscriptions));
+        }
+
+        let subscriptions: Arc<Vec<_>> = Arc::new(
+            webhooks::list_subscriptions(&self.pool)
+                .await?
+                .into_iter()
+                .filter(|subscription| subscription.active)
+                .collect(),
+        );
+        *self.subscriptions.write() = Some((Instant::now(), Arc::clone(&subscriptions)));
+        Ok(subscriptions)
+    }
+}
+
+#[async_trait]
+impl EventProcessor for WebhookProcessor {
+    async fn process_event(
+        &self,
+        event: HubEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(webhook_event) = WebhookEvent::from_hub_event(&event) else {
+            return Ok(());
+        };
+
+        let deliveries: Vec<NewWebhookDelivery> = self
+            .subscriptions()
+            .await?
+            .iter()
+            .filter(|subscription| webhook_event.matches(&subscription.filters))
+            .map(|subscription| NewWebhookDelivery {
+                subscription_id: subscription.id,
+                event_id: event.id as i64,
+                payload: webhook_event.payload.clone(),
+            })
+            .collect();
+
+        if !deliveries.is_empty() {
+            let enqueued = webhooks::enqueue_deliveries(&self.pool, &deliveries).await?;
+            debug!("Enqueued {} webhook deliveries for event {}", enqueued, event.id);
+        }
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Sign a request body: `sha256=` followed by the hex HMAC-SHA256 of `"<timestamp>.<body>"`
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Delay before retrying after `attempt` failed attempts: doubles from the initial backoff,
+/// capped at the maximum
+pub fn backoff(config: &WebhookConfig, attempt: u32) -> Duration {
+    let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+    Duration::from_secs(
+        config.initial_backoff_secs.saturating_mul(factor).min(config.max_backoff_secs),
+    )
+}
+
+/// Result of one POST
+#[derive(Debug)]
+pub struct PostOutcome {
+    /// HTTP status, if the endpoint answered
+    pub status_code: Option<u16>,
+    /// Why the attempt failed, if it did
+    pub error: Option<String>,
+    pub duration: Duration,
+}
+
+impl PostOutcome {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// POST a delivery's payload to its endpoint. Any 2xx counts as delivered.
+pub async fn post_delivery(client: &reqwest::Client, delivery: &ClaimedDelivery) -> PostOutcome {
+    let started = Instant::now();
+    let body = match serde_json::to_vec(&delivery.payload) {
+        Ok(body) => body,
+        Err(e) => {
+            return PostOutcome {
+                status_code: None,
+                error: Some(e.to_string()),
+                duration: started.elapsed(),
+            };
+        },
+    };
+    let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+
+    let response = client
+        .post(&delivery.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(DELIVERY_HEADER, delivery.id.to_string())
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign(&delivery.secret, timestamp, &body))
+        .body(body)
+        .send()
+        .await;
+
+    let (status_code, error) = match response {
+        Ok(response) if response.status().is_success() => (Some(response.status().as_u16()), None),
+        Ok(response) => {
+            let status = response.status();
+            (Some(status.as_u16()), Some(format!("Endpoint answered {}", status)))
+        },
+        Err(e) => (None, Some(e.to_string())),
+    };
+    PostOutcome { status_code, error, duration: started.elapsed() }
+}
+
+/// Delivers due outbox rows
+pub struct WebhookDispatcher {
+    pool: PgPool,
+    client: reqwest::Client,
+    config: WebhookConfig,
+}
+
+impl WebhookDispatcher {
+    pub fn new(pool: PgPool, config: WebhookConfig) -> Result<Self, reqwest::Error> {
+        let client =
+            reqwest::Client::builder().timeout(Duration::from_secs(config.timeout_secs)).build()?;
+        Ok(Self { pool, client, config })
+    }
+
+    /// Deliver until the task is aborted, polling when there's nothing due
+    pub async fn run(&self) {
+        let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
+        loop {
+            match self.dispatch_due().await {
+                Ok(0) => tokio::time::sleep(poll_interval).await,
+                Ok(_) => {},
+                Err(e) => {
+                    error!("Failed to claim webhook deliveries: {}", e);
+                    tokio::time::sleep(poll_interval).await;
+                },
+            }
+        }
+    }
+
+    /// Claim and deliver one batch of due deliveries; returns how many were claimed
+    pub async fn dispatch_due(&self) -> Result<usize, sqlx::Error> {
+        // Hold claimed rows long enough for a request to time out and be recorded
+        let lease = Duration::from_secs(self.config.timeout_secs + 30);
+        let batch = (self.config.concurrency * 4) as i64;
+        let claimed = webhooks::claim_due_deliveries(&self.pool, batch, lease).await?;
+        let count = claimed.len();
+
+        futures::stream::iter(claimed)
+            .for_each_concurrent(self.config.concurrency, |delivery| async move {
+                if let Err(e) = self.deliver(&delivery).await {
+                    error!("Failed to record webhook delivery {}: {}", delivery.id, e);
+                }
+            })
+            .await;
+
+        Ok(count)
+    }
+
+    async fn deliver(&self, delivery: &ClaimedDelivery) -> Result<(), sqlx::Error> {
+        let attempt = delivery.attempts + 1;
+        let outcome = post_delivery(&self.client, delivery).await;
+
+        webhooks::record_attempt(
+            &self.pool,
+            delivery.id,
+            attempt,
+            outcome.status_code.map(i32::from),
+            outcome.error.as_deref(),
+            outcome.duration.as_millis().min(i32::MAX as u128) as i32,
+        )
+        .await?;
+
+        match outcome.error {
+            None => webhooks::mark_delivered(&self.pool, delivery.id, attempt).await,
+            Some(error) if attempt as u32 >= self.config.max_attempts => {
+                warn!(
+                    "Webhook delivery {} to {} dead-lettered after {} attempts: {}",
+                    delivery.id, delivery.url, attempt, error
+                );
+                webhooks::dead_letter(&self.pool, delivery.id, attempt, &error).await
+            },
+            Some(error) => {
+                let delay = backoff(&self.config, attempt as u32);
+                debug!(
+                    "Webhook delivery {} failed (attempt {}), retrying in {:?}: {}",
+                    delivery.id, attempt, delay, error
+                );
+                let next_attempt_at = OffsetDateTime::now_utc() + delay;
+                webhooks::schedule_retry(&self.pool, delivery.id, attempt, next_attempt_at, &error)
+                    .await
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{CastAddBody, MergeMessageBody, Message, cast_add_body};
+    use axum::{Router, extract::State, http::HeaderMap, http::StatusCode, routing::post};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn cast_event(fid: u64, mentions: Vec<u64>, parent_url: Option<&str>) -> HubEvent {
+        let data = MessageData {
+            r#type: crate::proto::MessageType::CastAdd as i32,
+            fid,
+            timestamp: 100,
+            network: 1,
+            body: Some(message_data::Body::CastAddBody(CastAddBody {
+                text: "hello".to_string(),
+                mentions,
+                parent: parent_url.map(|url| cast_add_body::Parent::ParentUrl(url.to_string())),
+                ..Default::default()
+            })),
+        };
+        HubEvent {
+            id: 7,
+            r#type: crate::proto::HubEventType::MergeMessage as i32,
+            body: Some(Body::MergeMessageBody(MergeMessageBody {
+                message: Some(Message { data: Some(data), hash: vec![0xab], ..Default::default() }),
+                deleted_messages: vec![],
+            })),
+            ..Default::default()
+        }
+    }
+
+    fn onchain_event(fid: u64, event_type: OnChainEventType) -> HubEvent {
+        HubEvent {
+            id: 8,
+            r#type: crate::proto::HubEventType::MergeOnChainEvent as i32,
+            body: Some(Body::MergeOnChainEventBody(crate::proto::MergeOnChainEventBody {
+                on_chain_event: Some(OnChainEvent {
+                    r#type: event_type as i32,
+                    fid,
+                    ..Default::default()
+                }),
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cast_event_payload() {
+        let event = WebhookEvent::from_hub_event(&cast_event(3, vec![5], None)).unwrap();
+
+        assert_eq!(event.kind, EventKind::Message("cast_add".to_string()));
+        assert_eq!(event.mentioned_fids, vec![5]);
+        assert_eq!(event.payload["event_id"], 7);
+        assert_eq!(event.payload["hash"], "ab");
+        assert_eq!(event.payload["data"]["text"], "hello");
+    }
+
+    #[test]
+    fn test_filters() {
+        let cast =
+            WebhookEvent::from_hub_event(&cast_event(3, vec![5], Some("https://a.xyz"))).unwrap();
+        let register =
+            WebhookEvent::from_hub_event(&onchain_event(3, OnChainEventType::EventTypeIdRegister))
+                .unwrap();
+        assert_eq!(register.kind, EventKind::Onchain("id_register".to_string()));
+
+        let any = WebhookFilters::default();
+        assert!(cast.matches(&any) && register.matches(&any));
+
+        let by_fid = WebhookFilters { fids: vec![3], ..Default::default() };
+        assert!(cast.matches(&by_fid) && register.matches(&by_fid));
+        assert!(!cast.matches(&WebhookFilters { fids: vec![4], ..Default::default() }));
+
+        let mentions = WebhookFilters { mentioned_fids: vec![5, 6], ..Default::default() };
+        assert!(cast.matches(&mentions) && !register.matches(&mentions));
+
+        let parent =
+            WebhookFilters { parent_urls: vec!["https://a.xyz".to_string()], ..Default::default() };
+        assert!(cast.matches(&parent) && !register.matches(&parent));
+
+        // Type filters select kinds: listing only on-chain types excludes messages
+        let onchain = WebhookFilters {
+            onchain_event_types: vec!["id_register".to_string()],
+            ..Default::default()
+        };
+        assert!(!cast.matches(&onchain) && register.matches(&onchain));
+
+        let both = WebhookFilters {
+            fids: vec![3],
+            message_types: vec!["cast_add".to_string()],
+            ..Default::default()
+        };
+        assert!(cast.matches(&both) && !register.matches(&both));
+    }
+
+    #[test]
+    fn test_type_names() {
+        assert!(is_message_type_name("cast_add"));
+        assert!(is_message_type_name("verification_add_eth_address"));
+        assert!(!is_message_type_name("CAST_ADD"));
+        assert!(!is_message_type_name("none"));
+        assert!(is_onchain_event_type_name("id_register"));
+        assert!(!is_onchain_event_type_name("cast_add"));
+    }
+
+    #[test]
+    fn test_backoff() {
+        let config = WebhookConfig {
+            initial_backoff_secs: 5,
+            max_backoff_secs: 60,
+            ..WebhookConfig::default()
+        };
+
+        assert_eq!(backoff(&config, 1), Duration::from_secs(5));
+        assert_eq!(backoff(&config, 2), Duration::from_secs(10));
+        assert_eq!(backoff(&config, 4), Duration::from_secs(40));
+        assert_eq!(backoff(&config, 5), Duration::from_secs(60));
+        assert_eq!(backoff(&config, 200), Duration::from_secs(60));
+    }
+
+    #[derive(Clone, Default)]
+    struct Endpoint {
+        calls: Arc<AtomicUsize>,
+        /// Answer 500 for this many calls before succeeding
+        failures: usize,
+    }
+
+    async fn receive(
+        State(endpoint): State<Endpoint>,
+        headers: HeaderMap,
+        body: String,
+    ) -> StatusCode {
+        let call = endpoint.calls.fetch_add(1, Ordering::SeqCst);
+        let timestamp: i64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+        if headers[SIGNATURE_HEADER] != sign("secret", timestamp, body.as_bytes()).as_str() {
+            return StatusCode::UNAUTHORIZED;
+        }
+        if call < endpoint.failures { StatusCode::INTERNAL_SERVER_ERROR } else { StatusCode::OK }
+    }
+
+    async fn spawn_endpoint(endpoint: Endpoint) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = Router::new().route("/hook", post(receive)).with_state(endpoint);
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{}/hook", addr)
+    }
+
+    fn delivery(url: String, secret: &str) -> ClaimedDelivery {
+        ClaimedDelivery {
+            id: uuid::Uuid::new_v4(),
+            subscription_id: uuid::Uuid::new_v4(),
+            url,
+            secret: secret.to_string(),
+            payload: json!({ "event_id": 1, "type": "cast_add" }),
+            attempts: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_post_delivery_signs_and_retries() {
+        let endpoint = Endpoint { failures: 1, ..Default::default() };
+        let url = spawn_endpoint(endpoint.clone()).await;
+        let client = reqwest::Client::new();
+
+        let first = post_delivery(&client, &delivery(url.clone(), "secret")).await;
+        assert_eq!(first.status_code, Some(500));
+        assert!(!first.succeeded());
+
+        let second = post_delivery(&client, &delivery(url.clone(), "secret")).await;
+        assert_eq!(second.status_code, Some(200));
+        assert!(second.succeeded());
+
+        let wrong_secret = post_delivery(&client, &delivery(url, "other")).await;
+        assert_eq!(wrong_secret.status_code, Some(401));
+        assert_eq!(endpoint.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_post_delivery_unreachable() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        drop(listener);
+
+        let outcome = post_delivery(&reqwest::Client::new(), &delivery(url, "secret")).await;
+        assert_eq!(outcome.status_code, None);
+        assert!(!outcome.succeeded());
+    }
+}
@@ -829,6 +829,46 @@ impl Redis {
         Ok(results)
     }
 
+    /// Read up to `count` entries with IDs after `after` (exclusive), oldest first
+    pub async fn xrange_after(
+        &self,
+        key: &str,
+        after: &str,
+        count: u64,
+    ) -> Result<Vec<(String, Vec<u8>)>, CrateError> {
+        let response: Value = self
+            .pool
+            .xrange(key, format!("({}", after), "+", Some(count))
+            .await
+            .map_err(CrateError::RedisError)?;
+
+        let mut results = Vec::new();
+        if let Value::Array(messages) = response {
+            for msg in messages {
+                let Value::Array(msg_data) = msg else { continue };
+                if msg_data.len() < 2 {
+                    continue;
+                }
+                let id = msg_data[0].as_string().unwrap_or_default().to_string();
+                let Value::Array(fields) = &msg_data[1] else { continue };
+
+                // Fields are key-value pairs; the payload is under "d"
+                let data = fields
+                    .chunks_exact(2)
+                    .find(|pair| pair[0].as_string().is_some_and(|s| s == "d"))
+                    .and_then(|pair| match &pair[1] {
+                        Value::Bytes(data) => Some(data.to_vec()),
+                        other => other.as_string().map(|s| s.into_bytes()),
+                    });
+                if let Some(data) = data {
+                    results.push((id, data));
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
     pub async fn xlen(&self, key: &str) -> Result<u64, CrateError> {
         let result: u64 = self.pool.xlen(key).await.map_err(CrateError::RedisError)?;
 
@@ -952,6 +992,49 @@ impl Redis {
         Ok(result)
     }
 
+    // Hash operations for backfill checkpoints and progress
+    pub async fn hset(&self, key: &str, field: &str, value: &str) -> Result<(), CrateError> {
+        let _: u64 = self.pool.hset(key, (field, value)).await.map_err(CrateError::RedisError)?;
+
+        Ok(())
+    }
+
+    pub async fn hget(&self, key: &str, field: &str) -> Result<Option<String>, CrateError> {
+        let result: Option<String> =
+            self.pool.hget(key, field).await.map_err(CrateError::RedisError)?;
+
+        Ok(result)
+    }
+
+    pub async fn hincrby(&self, key: &str, field: &str, increment: i64) -> Result<i64, CrateError> {
+        let result: i64 =
+            self.pool.hincrby(key, field, increment).await.map_err(CrateError::RedisError)?;
+
+        Ok(result)
+    }
+
+    pub async fn hgetall(
+        &self,
+        key: &str,
+    ) -> Result<std::collections::HashMap<String, String>, CrateError> {
+        let result: std::collections::HashMap<String, String> =
+            self.pool.hgetall(key).await.map_err(CrateError::RedisError)?;
+
+        Ok(result)
+    }
+
+    pub async fn expire(&self, key: &str, seconds: i64) -> Result<(), CrateError> {
+        let _: bool = self.pool.expire(key, seconds, None).await.map_err(CrateError::RedisError)?;
+
+        Ok(())
+    }
+
+    pub async fn del(&self, key: &str) -> Result<u64, CrateError> {
+        let result: u64 = self.pool.del(key).await.map_err(CrateError::RedisError)?;
+
+        Ok(result)
+    }
+
     pub async fn keys(&self, pattern: &str) -> Result<Vec<String>, CrateError> {
         use fred::types::scan::Scanner;
         use futures::stream::TryStreamExt;
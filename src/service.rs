@@ -5,8 +5,10 @@ use waypoint::{
     app::App,
     config::{Config, ServiceMode},
     services::{
-        consumer::ConsumerService, mcp::McpService, producer::ProducerService,
-        streaming::StreamingService,
+        anti_entropy::AntiEntropyService, consumer::ConsumerService, graphql::GraphqlService,
+        grpc::GrpcService, live::LiveFeedService, mcp::McpService, outbox::OutboxService,
+        producer::ProducerService, rest::RestService, streaming::StreamingService,
+        webhooks::WebhookService,
     },
 };
 
@@ -74,8 +76,7 @@ pub async fn run_service(config: &Config, mode: ServiceMode) -> Result<()> {
 
     // Register MCP service if enabled (only for consumer or both modes, needs database)
     if config.mcp.enabled && matches!(mode, ServiceMode::Consumer | ServiceMode::Both) {
-        let mcp_service =
-            McpService::new().configure(config.mcp.bind_address.clone(), config.mcp.port);
+        let mcp_service = McpService::new(config.clone());
         app.register_service(mcp_service);
         info!(
             "MCP service registered with bind address {}:{}",
@@ -87,6 +88,90 @@ pub async fn run_service(config: &Config, mode: ServiceMode) -> Result<()> {
         info!("MCP service disabled in configuration");
     }
 
+    // Register the REST API if enabled (reads from PostgreSQL, so not with the SQLite backend)
+    if config.rest.enabled
+        && matches!(mode, ServiceMode::Consumer | ServiceMode::Both)
+        && !config.database.is_sqlite()
+    {
+        app.register_service(RestService::new(config.clone()));
+        info!(
+            "REST API registered with bind address {}:{}",
+            config.rest.bind_address, config.rest.port
+        );
+    } else if config.rest.enabled {
+        info!("REST API disabled (requires consumer mode and a PostgreSQL database)");
+    }
+
+    // Register the GraphQL endpoint if enabled (reads from PostgreSQL, like the REST API)
+    if config.graphql.enabled
+        && matches!(mode, ServiceMode::Consumer | ServiceMode::Both)
+        && !config.database.is_sqlite()
+    {
+        app.register_service(GraphqlService::new(config.clone()));
+        info!(
+            "GraphQL endpoint registered with bind address {}:{}",
+            config.graphql.bind_address, config.graphql.port
+        );
+    } else if config.graphql.enabled {
+        info!("GraphQL endpoint disabled (requires consumer mode and a PostgreSQL database)");
+    }
+
+    // Register the gRPC read server if enabled (reads from PostgreSQL, like the REST API)
+    if config.grpc.enabled
+        && matches!(mode, ServiceMode::Consumer | ServiceMode::Both)
+        && !config.database.is_sqlite()
+    {
+        app.register_service(GrpcService::new(config.clone()));
+        info!(
+            "gRPC server registered with bind address {}:{}",
+            config.grpc.bind_address, config.grpc.port
+        );
+    } else if config.grpc.enabled {
+        info!("gRPC server disabled (requires consumer mode and a PostgreSQL database)");
+    }
+
+    // Register the webhook dispatcher if enabled; the consumer registers the matching processor
+    if config.webhooks.enabled
+        && matches!(mode, ServiceMode::Consumer | ServiceMode::Both)
+        && !config.database.is_sqlite()
+    {
+        app.register_service(WebhookService::new(config.webhooks.clone()));
+        info!("Webhook dispatcher registered");
+    } else if config.webhooks.enabled {
+        info!("Webhooks disabled (requires consumer mode and a PostgreSQL database)");
+    }
+
+    // Register outbox retention if enabled; the consumer's store writes the outbox itself
+    if config.outbox.enabled
+        && matches!(mode, ServiceMode::Consumer | ServiceMode::Both)
+        && !config.database.is_sqlite()
+    {
+        app.register_service(OutboxService::new(config.outbox.clone()));
+        info!("Change outbox registered (retention {}h)", config.outbox.retention_hours);
+    } else if config.outbox.enabled {
+        info!("Change outbox disabled (requires consumer mode and a PostgreSQL database)");
+    }
+
+    // Register anti-entropy if enabled; it compares the Hub with PostgreSQL and reconciles drift
+    if config.anti_entropy.enabled
+        && matches!(mode, ServiceMode::Consumer | ServiceMode::Both)
+        && !config.database.is_sqlite()
+    {
+        app.register_service(AntiEntropyService::new(config.clone()));
+        info!("Anti-entropy registered ({} FIDs/minute)", config.anti_entropy.fids_per_minute);
+    } else if config.anti_entropy.enabled {
+        info!("Anti-entropy disabled (requires consumer mode and a PostgreSQL database)");
+    }
+
+    // Register the live feed if enabled; it only needs Redis, so it runs in every mode
+    if config.live.enabled {
+        app.register_service(LiveFeedService::new(config.clone()));
+        info!(
+            "Live feed registered with bind address {}:{}",
+            config.live.bind_address, config.live.port
+        );
+    }
+
     // Run the application
     info!("Starting Waypoint service in {} mode", mode);
     app.run_until_shutdown()
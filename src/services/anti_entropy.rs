@@ -0,0 +1,144 @@
+//! Anti-entropy service
+//!
+//! Samples FIDs at `anti_entropy.fids_per_minute` and reconciles the ones whose
+//! fingerprints show drift from the Hub; see [`crate::backfill::anti_entropy`].
+//! Recently active FIDs and the highest registered FID are reloaded every
+//! `anti_entropy.refresh_interval_secs`.
+
+use crate::{
+    app::{Service, ServiceContext, ServiceError, ServiceHandle},
+    backfill::{
+        anti_entropy::{AntiEntropy, FidSampler},
+        reconciler::MessageReconciler,
+    },
+    config::{AntiEntropyConfig, Config},
+    database::{audit, priority},
+    hub::client::Hub,
+    processor::{AppResources, database::DatabaseProcessor},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::Mutex, time::MissedTickBehavior};
+use tracing::{debug, info, warn};
+
+/// Anti-entropy service
+pub struct AntiEntropyService {
+    config: Config,
+}
+
+impl AntiEntropyService {
+    /// Create a new anti-entropy service
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Service for AntiEntropyService {
+    fn name(&self) -> &str {
+        "anti_entropy"
+    }
+
+    async fn start<'a>(&'a self, context: ServiceContext<'a>) -> crate::app::Result<ServiceHandle> {
+        let database = context.state.database.clone().ok_or_else(|| {
+            ServiceError::Initialization("Anti-entropy requires a PostgreSQL database".to_string())
+        })?;
+        let store = context.state.store.clone().ok_or_else(|| {
+            ServiceError::Initialization("Anti-entropy requires a storage backend".to_string())
+        })?;
+
+        // A dedicated Hub client, so sampling doesn't queue behind the subscriber
+        let mut hub = Hub::new(self.config.hub.clone())
+            .map_err(|e| ServiceError::Initialization(format!("Failed to create Hub: {}", e)))?;
+        hub.connect().await.map_err(|e| {
+            ServiceError::Initialization(format!("Failed to connect to Hub: {}", e))
+        })?;
+        let hub = Arc::new(hub);
+
+        let resources = Arc::new(AppResources::with_config(
+            Arc::new(Mutex::new(hub.as_ref().clone())),
+            Arc::clone(&context.state.redis),
+            store,
+            self.config.clone(),
+        ));
+        let reconciler =
+            Arc::new(MessageReconciler::new(hub.clone(), Duration::from_secs(30), true));
+        let checker = AntiEntropy::new(
+            hub,
+            database.pool.clone(),
+            reconciler,
+            Arc::new(DatabaseProcessor::new(resources)),
+        );
+
+        let config = self.config.anti_entropy.clone();
+        info!(
+            "Anti-entropy started ({} FIDs/minute, {:.0}% hot)",
+            config.fids_per_minute,
+            config.hot_share * 100.0
+        );
+        let run_handle = tokio::spawn(run(checker, database.pool.clone(), config));
+
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let join_handle = tokio::spawn(async move {
+            let _ = stop_rx.await;
+            // A FID cut off mid-reconcile is simply checked again on a later pass
+            run_handle.abort();
+            info!("Anti-entropy stopped");
+        });
+
+        Ok(ServiceHandle::new(stop_tx, join_handle))
+    }
+}
+
+async fn run(mut checker: AntiEntropy, pool: PgPool, config: AntiEntropyConfig) {
+    let mut sampler = FidSampler::new(0, config.hot_share);
+
+    let mut refresh =
+        tokio::time::interval(Duration::from_secs(config.refresh_interval_secs.max(1)));
+    let mut sample =
+        tokio::time::interval(Duration::from_secs_f64(60.0 / config.fids_per_minute.max(1) as f64));
+    sample.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    // Load the FID space before the first sample
+    refresh.tick().await;
+    refresh_fids(&pool, &config, &mut sampler).await;
+
+    loop {
+        tokio::select! {
+            _ = refresh.tick() => refresh_fids(&pool, &config, &mut sampler).await,
+            _ = sample.tick() => {
+                let Some(fid) = sampler.next_fid() else {
+                    continue;
+                };
+                match checker.check_fid(fid).await {
+                    Ok(outcome) if outcome.reconciled => {},
+                    Ok(_) => debug!("FID {} matches the Hub", fid),
+                    Err(e) => warn!("Anti-entropy check of FID {} failed: {}", fid, e),
+                }
+            },
+        }
+    }
+}
+
+/// Reload the highest FID and the hot FIDs; on failure the previous ones are kept
+async fn refresh_fids(pool: &PgPool, config: &AntiEntropyConfig, sampler: &mut FidSampler) {
+    match audit::max_fid(pool).await {
+        Ok(Some(max_fid)) if max_fid > 0 => sampler.set_max_fid(max_fid as u64),
+        Ok(_) => warn!("No registered FIDs yet; anti-entropy only checks hot FIDs"),
+        Err(e) => warn!("Failed to load the highest FID: {}", e),
+    }
+
+    if config.hot_share <= 0.0 || config.hot_fids == 0 {
+        return;
+    }
+    let since = Utc::now() - chrono::Duration::hours(config.hot_window_hours as i64);
+    match priority::recently_active_fids(pool, since, config.hot_fids as i64).await {
+        Ok(fids) => {
+            debug!("Queued {} hot FIDs (round-robin at FID {})", fids.len(), sampler.cursor());
+            sampler.set_hot_fids(fids.into_iter().filter(|&fid| fid > 0).map(|fid| fid as u64));
+        },
+        Err(e) => warn!("Failed to load recently active FIDs: {}", e),
+    }
+}
@@ -14,7 +14,7 @@ use crate::{
 use async_trait::async_trait;
 use std::{sync::Arc, time::Duration};
 use tokio::sync::oneshot;
-use tracing::info;
+use tracing::{info, warn};
 
 /// Consumer service that reads from Redis and writes to PostgreSQL
 ///
@@ -78,12 +78,15 @@ impl Service for ConsumerService {
     }
 
     async fn start<'a>(&'a self, context: ServiceContext<'a>) -> Result<ServiceHandle> {
-        use crate::processor::{AppResources, database::DatabaseProcessor, print::PrintProcessor};
+        use crate::processor::{
+            AppResources, database::DatabaseProcessor, print::PrintProcessor,
+            webhook::WebhookProcessor,
+        };
 
-        // Ensure Database is available (required for consumer mode)
-        let database = context.state.database.as_ref().ok_or_else(|| {
+        // Ensure storage is available (required for consumer mode)
+        let store = context.state.store.as_ref().ok_or_else(|| {
             AppError::Service(ServiceError::Initialization(
-                "Database client not available. Consumer mode requires database configuration."
+                "Storage backend not available. Consumer mode requires database configuration."
                     .to_string(),
             ))
         })?;
@@ -92,7 +95,7 @@ impl Service for ConsumerService {
         // In consumer-only mode, hub is not available
         let app_resources = AppResources::with_config_consumer_only(
             Arc::clone(&context.state.redis),
-            Arc::clone(database),
+            Arc::clone(store),
             context.config.clone(),
         );
 
@@ -181,6 +184,48 @@ impl Service for ConsumerService {
             processor_registry.register(wrapper);
         }
 
+        // Register webhook processor if enabled (subscriptions and the outbox live in PostgreSQL)
+        if context.config.webhooks.enabled {
+            match context.state.database.as_ref() {
+                Some(database) => {
+                    info!("Registering webhook processor for consumer service");
+
+                    struct WebhookWrapper {
+                        processor: WebhookProcessor,
+                    }
+
+                    #[async_trait::async_trait]
+                    impl crate::app::EventProcessor for WebhookWrapper {
+                        fn name(&self) -> &str {
+                            "webhook"
+                        }
+
+                        async fn process(
+                            &self,
+                            event: crate::proto::HubEvent,
+                        ) -> crate::app::ProcessorResult<()> {
+                            use crate::processor::consumer::EventProcessor;
+                            self.processor
+                                .process_event(event)
+                                .await
+                                .map_err(|e| crate::app::ProcessorError::Processing(e.to_string()))
+                        }
+
+                        fn supported_types(&self) -> Vec<MessageType> {
+                            MessageType::all().collect()
+                        }
+                    }
+
+                    let processor =
+                        WebhookProcessor::new(database.pool.clone(), &context.config.webhooks);
+                    processor_registry.register(WebhookWrapper { processor });
+                },
+                None => warn!(
+                    "Webhooks require a PostgreSQL database; webhook processor not registered"
+                ),
+            }
+        }
+
         // Create Redis stream
         let redis_stream = Arc::new(
             RedisStream::new(Arc::clone(&context.state.redis)).with_config(&context.config.stream),
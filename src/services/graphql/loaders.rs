@@ -0,0 +1,415 @@
+//! Batched loaders behind the GraphQL resolvers.
+//!
+//! Every per-parent lookup (a cast's author, its reaction counts, its first
+//! replies, ...) goes through a [`DataLoader`](async_graphql::dataloader::DataLoader)
+//! keyed by one of the newtypes below, so resolving a list of N casts costs one
+//! query per field rather than one per cast.
+
+use crate::{core::types::Channel, database::hub_messages::CastRow};
+use async_graphql::dataloader::Loader;
+use sqlx::{PgPool, types::time::OffsetDateTime};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+/// Loader errors have to be `Clone`, so share the underlying database error
+pub type LoadError = Arc<sqlx::Error>;
+
+/// Runs the batched queries for every key type
+pub struct PgLoader {
+    pool: PgPool,
+}
+
+impl PgLoader {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// A live cast, by hash
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CastKey(pub Vec<u8>);
+
+/// Where a cast's thread starts, by cast hash
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CastRootKey(pub Vec<u8>);
+
+/// Root of a cast's thread: the first cast, or the URL (channel) it was posted under
+#[derive(Debug, Clone, Default)]
+pub struct CastRoot {
+    pub fid: Option<i64>,
+    pub hash: Option<Vec<u8>>,
+    pub url: Option<String>,
+}
+
+/// Current user data of an FID, by user data type
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProfileKey(pub i64);
+
+/// Latest value of each user data type set by an FID
+pub type Profile = HashMap<i16, String>;
+
+/// IdRegistry state of an FID
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RegistrationKey(pub i64);
+
+#[derive(Debug, Clone)]
+pub struct Registration {
+    pub custody_address: Option<Vec<u8>>,
+    pub recovery_address: Option<Vec<u8>>,
+    pub registered_at: Option<OffsetDateTime>,
+}
+
+/// Live reaction counts of a cast, by cast hash
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReactionCountsKey(pub Vec<u8>);
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReactionCounts {
+    pub likes: i64,
+    pub recasts: i64,
+}
+
+/// Number of live direct replies to a cast, by cast hash
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReplyCountKey(pub Vec<u8>);
+
+/// The newest `first` direct replies to a cast
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RepliesKey {
+    pub parent_hash: Vec<u8>,
+    pub first: usize,
+}
+
+/// Follower and following counts of an FID
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FollowCountsKey(pub i64);
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FollowCounts {
+    pub followers: i64,
+    pub following: i64,
+}
+
+/// A channel, by URL
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChannelKey(pub String);
+
+/// Keys deduplicated into a bind parameter
+fn distinct<K, T: Clone + Eq + std::hash::Hash>(keys: &[K], value: impl Fn(&K) -> &T) -> Vec<T> {
+    keys.iter().map(value).collect::<HashSet<_>>().into_iter().cloned().collect()
+}
+
+impl Loader<CastKey> for PgLoader {
+    type Value = CastRow;
+    type Error = LoadError;
+
+    async fn load(&self, keys: &[CastKey]) -> Result<HashMap<CastKey, CastRow>, LoadError> {
+        let hashes = distinct(keys, |k| &k.0);
+        let rows = sqlx::query_as!(
+            CastRow,
+            r#"
+            SELECT hash, fid AS "fid!", timestamp, text, parent_fid, parent_hash, parent_url,
+                   embeds, mentions, mentions_positions
+            FROM casts
+            WHERE hash = ANY($1) AND deleted_at IS NULL AND fid IS NOT NULL
+            "#,
+            &hashes
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| (CastKey(row.hash.clone()), row)).collect())
+    }
+}
+
+impl Loader<CastRootKey> for PgLoader {
+    type Value = CastRoot;
+    type Error = LoadError;
+
+    async fn load(
+        &self,
+        keys: &[CastRootKey],
+    ) -> Result<HashMap<CastRootKey, CastRoot>, LoadError> {
+        let hashes = distinct(keys, |k| &k.0);
+        let rows = sqlx::query!(
+            r#"
+            SELECT hash, root_parent_fid, root_parent_hash, root_parent_url
+            FROM casts
+            WHERE hash = ANY($1)
+            "#,
+            &hashes
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let root = CastRoot {
+                    fid: row.root_parent_fid,
+                    hash: row.root_parent_hash,
+                    url: row.root_parent_url,
+                };
+                (CastRootKey(row.hash), root)
+            })
+            .collect())
+    }
+}
+
+impl Loader<ProfileKey> for PgLoader {
+    type Value = Profile;
+    type Error = LoadError;
+
+    async fn load(&self, keys: &[ProfileKey]) -> Result<HashMap<ProfileKey, Profile>, LoadError> {
+        let fids = distinct(keys, |k| &k.0);
+        let rows = sqlx::query!(
+            r#"
+            SELECT fid, type AS data_type, value
+            FROM user_data
+            WHERE fid = ANY($1) AND deleted_at IS NULL
+            "#,
+            &fids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut profiles: HashMap<ProfileKey, Profile> = HashMap::new();
+        for row in rows {
+            profiles.entry(ProfileKey(row.fid)).or_default().insert(row.data_type, row.value);
+        }
+        Ok(profiles)
+    }
+}
+
+impl Loader<RegistrationKey> for PgLoader {
+    type Value = Registration;
+    type Error = LoadError;
+
+    async fn load(
+        &self,
+        keys: &[RegistrationKey],
+    ) -> Result<HashMap<RegistrationKey, Registration>, LoadError> {
+        let fids = distinct(keys, |k| &k.0);
+        let rows = sqlx::query!(
+            r#"
+            SELECT fid, custody_address, recovery_address, registered_at
+            FROM fids
+            WHERE fid = ANY($1) AND deleted_at IS NULL
+            "#,
+            &fids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let registration = Registration {
+                    custody_address: row.custody_address,
+                    recovery_address: row.recovery_address,
+                    registered_at: row.registered_at,
+                };
+                (RegistrationKey(row.fid), registration)
+            })
+            .collect())
+    }
+}
+
+impl Loader<ReactionCountsKey> for PgLoader {
+    type Value = ReactionCounts;
+    type Error = LoadError;
+
+    async fn load(
+        &self,
+        keys: &[ReactionCountsKey],
+    ) -> Result<HashMap<ReactionCountsKey, ReactionCounts>, LoadError> {
+        let hashes = distinct(keys, |k| &k.0);
+        let rows = sqlx::query!(
+            r#"
+            SELECT target_cast_hash AS "hash!",
+                   COUNT(*) FILTER (WHERE type = 1) AS "likes!",
+                   COUNT(*) FILTER (WHERE type = 2) AS "recasts!"
+            FROM reactions
+            WHERE target_cast_hash = ANY($1) AND deleted_at IS NULL
+            GROUP BY target_cast_hash
+            "#,
+            &hashes
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let counts = ReactionCounts { likes: row.likes, recasts: row.recasts };
+                (ReactionCountsKey(row.hash), counts)
+            })
+            .collect())
+    }
+}
+
+impl Loader<ReplyCountKey> for PgLoader {
+    type Value = i64;
+    type Error = LoadError;
+
+    async fn load(&self, keys: &[ReplyCountKey]) -> Result<HashMap<ReplyCountKey, i64>, LoadError> {
+        let hashes = distinct(keys, |k| &k.0);
+        let rows = sqlx::query!(
+            r#"
+            SELECT parent_hash AS "hash!", COUNT(*) AS "count!"
+            FROM casts
+            WHERE parent_hash = ANY($1) AND deleted_at IS NULL
+            GROUP BY parent_hash
+            "#,
+            &hashes
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| (ReplyCountKey(row.hash), row.count)).collect())
+    }
+}
+
+impl Loader<RepliesKey> for PgLoader {
+    type Value = Vec<CastRow>;
+    type Error = LoadError;
+
+    /// One windowed query for every parent, fetching the largest `first` asked for and
+    /// trimming per key
+    async fn load(
+        &self,
+        keys: &[RepliesKey],
+    ) -> Result<HashMap<RepliesKey, Vec<CastRow>>, LoadError> {
+        let hashes = distinct(keys, |k| &k.parent_hash);
+        let first = keys.iter().map(|k| k.first).max().unwrap_or_default();
+        let rows = sqlx::query!(
+            r#"
+            SELECT hash AS "hash!", fid AS "fid!", timestamp AS "timestamp!", text, parent_fid,
+                   parent_hash, parent_url, embeds AS "embeds!", mentions AS "mentions!",
+                   mentions_positions AS "mentions_positions!"
+            FROM (
+                SELECT c.*, ROW_NUMBER() OVER (
+                    PARTITION BY parent_hash ORDER BY timestamp DESC, hash DESC
+                ) AS position
+                FROM casts c
+                WHERE parent_hash = ANY($1) AND deleted_at IS NULL AND fid IS NOT NULL
+            ) replies
+            WHERE position <= $2
+            ORDER BY parent_hash, position
+            "#,
+            &hashes,
+            first as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut by_parent: HashMap<Vec<u8>, Vec<CastRow>> = HashMap::new();
+        for row in rows {
+            let Some(parent_hash) = row.parent_hash.clone() else { continue };
+            by_parent.entry(parent_hash).or_default().push(CastRow {
+                hash: row.hash,
+                fid: row.fid,
+                timestamp: row.timestamp,
+                text: row.text,
+                parent_fid: row.parent_fid,
+                parent_hash: row.parent_hash,
+                parent_url: row.parent_url,
+                embeds: row.embeds,
+                mentions: row.mentions,
+                mentions_positions: row.mentions_positions,
+            });
+        }
+
+        Ok(keys
+            .iter()
+            .map(|key| {
+                let mut replies = by_parent.get(&key.parent_hash).cloned().unwrap_or_default();
+                replies.truncate(key.first);
+                (key.clone(), replies)
+            })
+            .collect())
+    }
+}
+
+impl Loader<FollowCountsKey> for PgLoader {
+    type Value = FollowCounts;
+    type Error = LoadError;
+
+    async fn load(
+        &self,
+        keys: &[FollowCountsKey],
+    ) -> Result<HashMap<FollowCountsKey, FollowCounts>, LoadError> {
+        let fids = distinct(keys, |k| &k.0);
+        let followers = sqlx::query!(
+            r#"
+            SELECT target_fid AS "fid!", COUNT(*) AS "count!"
+            FROM links
+            WHERE target_fid = ANY($1) AND type = 'follow' AND deleted_at IS NULL
+            GROUP BY target_fid
+            "#,
+            &fids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let following = sqlx::query!(
+            r#"
+            SELECT fid AS "fid!", COUNT(*) AS "count!"
+            FROM links
+            WHERE fid = ANY($1) AND type = 'follow' AND deleted_at IS NULL
+            GROUP BY fid
+            "#,
+            &fids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut counts: HashMap<FollowCountsKey, FollowCounts> = HashMap::new();
+        for row in followers {
+            counts.entry(FollowCountsKey(row.fid)).or_default().followers = row.count;
+        }
+        for row in following {
+            counts.entry(FollowCountsKey(row.fid)).or_default().following = row.count;
+        }
+        Ok(counts)
+    }
+}
+
+impl Loader<ChannelKey> for PgLoader {
+    type Value = Channel;
+    type Error = LoadError;
+
+    async fn load(&self, keys: &[ChannelKey]) -> Result<HashMap<ChannelKey, Channel>, LoadError> {
+        let urls = distinct(keys, |k| &k.0);
+        let rows = sqlx::query!(
+            r#"
+            SELECT url, channel_id, name, description, lead_fid, first_cast_at, last_cast_at,
+                   cast_count, reply_count
+            FROM channels
+            WHERE url = ANY($1) AND deleted_at IS NULL
+            "#,
+            &urls
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let channel = Channel {
+                    url: row.url.clone(),
+                    channel_id: row.channel_id,
+                    name: row.name,
+                    description: row.description,
+                    lead_fid: row.lead_fid.map(|f| f as u64),
+                    first_cast_at: row.first_cast_at.map(|t| t.unix_timestamp() as u64),
+                    last_cast_at: row.last_cast_at.map(|t| t.unix_timestamp() as u64),
+                    cast_count: row.cast_count as u64,
+                    reply_count: row.reply_count as u64,
+                };
+                (ChannelKey(row.url), channel)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,162 @@
+//! GraphQL endpoint over the synced tables.
+//!
+//! Serves `POST /graphql` from PostgreSQL, with the schema SDL at
+//! `GET /graphql/schema`. Per-parent lookups are batched through dataloaders,
+//! and every query is checked against the configured depth and complexity
+//! limits before it runs.
+
+mod loaders;
+mod types;
+
+pub use types::Query;
+
+use crate::{
+    app::{Service, ServiceContext, ServiceError, ServiceHandle},
+    config::{Config, GraphqlConfig},
+    database::{PostgresDatabaseClient, client::Database},
+};
+use async_graphql::{EmptyMutation, EmptySubscription, Schema, dataloader::DataLoader};
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    extract::State,
+    routing::{get, post},
+};
+use loaders::PgLoader;
+use std::{net::SocketAddr, sync::Arc};
+use tracing::{error, info};
+use types::GraphqlData;
+
+pub type WaypointSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Build the schema with its loaders and limits
+pub fn schema(database: Arc<Database>, config: &GraphqlConfig) -> WaypointSchema {
+    let pool = database.pool.clone();
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .limit_depth(config.max_depth)
+        .limit_complexity(config.max_complexity)
+        .data(DataLoader::new(PgLoader::new(pool.clone()), tokio::spawn))
+        .data(GraphqlData {
+            pool,
+            db: PostgresDatabaseClient::new(database),
+            max_page_size: config.max_page_size,
+        })
+        .finish()
+}
+
+async fn execute(
+    State(schema): State<WaypointSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request).await)
+}
+
+async fn sdl(State(schema): State<WaypointSchema>) -> String {
+    schema.sdl()
+}
+
+/// Build the `/graphql` router
+pub fn router(schema: WaypointSchema) -> Router {
+    Router::new()
+        .route("/graphql", post(execute))
+        .route("/graphql/schema", get(sdl))
+        .with_state(schema)
+}
+
+/// GraphQL endpoint service
+pub struct GraphqlService {
+    config: Config,
+}
+
+impl GraphqlService {
+    /// Create a new GraphQL service from the application configuration
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Service for GraphqlService {
+    fn name(&self) -> &str {
+        "graphql"
+    }
+
+    async fn start<'a>(&'a self, context: ServiceContext<'a>) -> crate::app::Result<ServiceHandle> {
+        let graphql = &self.config.graphql;
+        let socket_addr = format!("{}:{}", graphql.bind_address, graphql.port)
+            .parse::<SocketAddr>()
+            .map_err(|e| ServiceError::Initialization(format!("Invalid socket address: {}", e)))?;
+
+        let database = context.state.database.clone().ok_or_else(|| {
+            ServiceError::Initialization(
+                "GraphQL endpoint requires a PostgreSQL database".to_string(),
+            )
+        })?;
+        let schema = schema(database, graphql);
+
+        let listener = tokio::net::TcpListener::bind(socket_addr).await.map_err(|e| {
+            ServiceError::Start(format!(
+                "Failed to bind GraphQL endpoint to {}: {}",
+                socket_addr, e
+            ))
+        })?;
+        info!("GraphQL endpoint listening on {}", socket_addr);
+
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let server_handle = tokio::spawn(async move {
+            let server = axum::serve(listener, router(schema)).with_graceful_shutdown(async {
+                let _ = stop_rx.await;
+                info!("GraphQL endpoint shutting down");
+            });
+
+            if let Err(e) = server.await {
+                error!("GraphQL endpoint shutdown with error: {}", e);
+            }
+        });
+
+        Ok(ServiceHandle::new(stop_tx, server_handle))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_schema(config: &GraphqlConfig) -> WaypointSchema {
+        schema(Arc::new(Database::empty()), config)
+    }
+
+    #[tokio::test]
+    async fn test_sdl_has_types() {
+        let sdl = test_schema(&GraphqlConfig::default()).sdl();
+
+        for name in ["type User", "type Cast", "type Reaction", "type Link", "type Verification"] {
+            assert!(sdl.contains(name), "missing {}", name);
+        }
+        assert!(sdl.contains("type OnchainEvent"));
+        assert!(sdl.contains("type Channel"));
+        assert!(sdl.contains("type CastPage"));
+    }
+
+    #[tokio::test]
+    async fn test_depth_limit() {
+        let config = GraphqlConfig { max_depth: 3, ..GraphqlConfig::default() };
+        let response = test_schema(&config)
+            .execute(r#"{ cast(hash: "00") { parent { parent { parent { hash } } } } }"#)
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.contains("nested too deep"));
+    }
+
+    #[tokio::test]
+    async fn test_complexity_limit() {
+        let config = GraphqlConfig { max_complexity: 100, ..GraphqlConfig::default() };
+        let response = test_schema(&config)
+            .execute(r#"{ user(fid: 1) { casts(first: 50) { items { hash text } } } }"#)
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.contains("too complex"));
+    }
+}
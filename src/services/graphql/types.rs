@@ -0,0 +1,672 @@
+//! GraphQL object types over the synced tables.
+//!
+//! Objects wrap the `hub_messages`/`pages` rows. Fields that need another
+//! table go through the batched loaders; paginated fields take `first`/`after`
+//! and cost `first` times their selection in the complexity limit.
+
+use super::loaders::{
+    CastKey, CastRootKey, ChannelKey, FollowCountsKey, LoadError, PgLoader, ProfileKey,
+    ReactionCountsKey, RegistrationKey, RepliesKey, ReplyCountKey,
+};
+use crate::{
+    core::types::Channel as ChannelRecord,
+    database::{
+        PostgresDatabaseClient,
+        hub_messages::{CastRow, LinkRow, ReactionRow, VerificationRow},
+        pages::{self, OnchainEventRow, PageCursor},
+    },
+    services::mcp::handlers::utils::{parse_hash_bytes, protocol_name},
+};
+use async_graphql::{
+    Context, Enum, Error, Json, Object, OutputType, Result, SimpleObject,
+    dataloader::{DataLoader, Loader},
+};
+use sqlx::{PgPool, types::time::OffsetDateTime};
+use std::{fmt::Display, hash::Hash};
+use tracing::error;
+
+/// Shared state available to every resolver
+pub struct GraphqlData {
+    pub pool: PgPool,
+    pub db: PostgresDatabaseClient,
+    pub max_page_size: usize,
+}
+
+/// Hide query details from clients, like the REST API does
+fn internal(e: impl Display) -> Error {
+    error!("GraphQL resolver error: {}", e);
+    Error::new("Internal server error")
+}
+
+fn data<'a>(ctx: &Context<'a>) -> &'a GraphqlData {
+    ctx.data_unchecked::<GraphqlData>()
+}
+
+/// Load one key through the shared dataloader, batched with the rest of the query
+async fn load<K>(ctx: &Context<'_>, key: K) -> Result<Option<<PgLoader as Loader<K>>::Value>>
+where
+    K: Send + Sync + Hash + Eq + Clone + 'static,
+    PgLoader: Loader<K, Error = LoadError>,
+{
+    ctx.data_unchecked::<DataLoader<PgLoader>>().load_one(key).await.map_err(internal)
+}
+
+/// Clamp `first` to the configured page size and decode `after`
+fn page_args(
+    ctx: &Context<'_>,
+    first: i32,
+    after: Option<&str>,
+) -> Result<(usize, Option<PageCursor>)> {
+    let limit = (first.max(1) as usize).min(data(ctx).max_page_size);
+    let cursor = match after {
+        Some(after) => Some(PageCursor::decode(after).ok_or_else(|| Error::new("Invalid cursor"))?),
+        None => None,
+    };
+    Ok((limit, cursor))
+}
+
+fn parse_hash(hash: &str) -> Result<Vec<u8>> {
+    parse_hash_bytes(hash).map_err(Error::new)
+}
+
+fn unix(timestamp: OffsetDateTime) -> i64 {
+    timestamp.unix_timestamp()
+}
+
+fn address(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// One page of a list field
+#[derive(SimpleObject)]
+#[graphql(concrete(name = "CastPage", params(Cast)))]
+#[graphql(concrete(name = "ReactionPage", params(Reaction)))]
+#[graphql(concrete(name = "LinkPage", params(Link)))]
+#[graphql(concrete(name = "VerificationPage", params(Verification)))]
+#[graphql(concrete(name = "OnchainEventPage", params(OnchainEvent)))]
+pub struct Page<T: OutputType> {
+    pub items: Vec<T>,
+    /// Pass as `after` to fetch the next page; null on the last page
+    pub next_cursor: Option<String>,
+}
+
+impl<T: OutputType> Page<T> {
+    fn from_rows<R>(page: pages::Page<R>, item: impl Fn(R) -> T) -> Self {
+        Self {
+            items: page.items.into_iter().map(item).collect(),
+            next_cursor: page.next.map(|cursor| cursor.encode()),
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReactionType {
+    Like,
+    Recast,
+}
+
+impl ReactionType {
+    fn from_db(value: i16) -> Option<Self> {
+        match value {
+            1 => Some(Self::Like),
+            2 => Some(Self::Recast),
+            _ => None,
+        }
+    }
+
+    fn to_db(self) -> i16 {
+        match self {
+            Self::Like => 1,
+            Self::Recast => 2,
+        }
+    }
+}
+
+#[derive(SimpleObject, Clone, Copy)]
+pub struct ReactionCounts {
+    pub likes: i64,
+    pub recasts: i64,
+}
+
+/// Root query
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// A user by FID; null if the FID has neither a profile nor an IdRegistry record
+    async fn user(&self, ctx: &Context<'_>, fid: i64) -> Result<Option<User>> {
+        let profile = load(ctx, ProfileKey(fid)).await?;
+        let registration = load(ctx, RegistrationKey(fid)).await?;
+        Ok((profile.is_some() || registration.is_some()).then_some(User { fid }))
+    }
+
+    /// A live cast by hash (hex, with or without `0x`)
+    async fn cast(&self, ctx: &Context<'_>, hash: String) -> Result<Option<Cast>> {
+        let hash = parse_hash(&hash)?;
+        Ok(load(ctx, CastKey(hash)).await?.map(Cast))
+    }
+
+    /// A channel by ID (`memes`) or URL
+    async fn channel(&self, ctx: &Context<'_>, id: String) -> Result<Option<Channel>> {
+        use crate::core::data_context::Database;
+        Ok(data(ctx).db.get_channel(&id).await.map_err(internal)?.map(Channel))
+    }
+}
+
+/// A Farcaster user
+pub struct User {
+    pub fid: i64,
+}
+
+impl User {
+    async fn profile_field(&self, ctx: &Context<'_>, data_type: i16) -> Result<Option<String>> {
+        let profile = load(ctx, ProfileKey(self.fid)).await?;
+        Ok(profile.and_then(|mut profile| profile.remove(&data_type)))
+    }
+}
+
+#[Object]
+impl User {
+    async fn fid(&self) -> i64 {
+        self.fid
+    }
+
+    async fn username(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        self.profile_field(ctx, 6).await
+    }
+
+    async fn display_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        self.profile_field(ctx, 2).await
+    }
+
+    async fn pfp(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        self.profile_field(ctx, 1).await
+    }
+
+    async fn bio(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        self.profile_field(ctx, 3).await
+    }
+
+    async fn url(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        self.profile_field(ctx, 5).await
+    }
+
+    async fn location(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        self.profile_field(ctx, 7).await
+    }
+
+    async fn twitter(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        self.profile_field(ctx, 8).await
+    }
+
+    async fn github(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        self.profile_field(ctx, 9).await
+    }
+
+    async fn custody_address(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let registration = load(ctx, RegistrationKey(self.fid)).await?;
+        Ok(registration.and_then(|r| r.custody_address).map(|a| address(&a)))
+    }
+
+    async fn recovery_address(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let registration = load(ctx, RegistrationKey(self.fid)).await?;
+        Ok(registration.and_then(|r| r.recovery_address).map(|a| address(&a)))
+    }
+
+    /// Unix seconds
+    async fn registered_at(&self, ctx: &Context<'_>) -> Result<Option<i64>> {
+        let registration = load(ctx, RegistrationKey(self.fid)).await?;
+        Ok(registration.and_then(|r| r.registered_at).map(unix))
+    }
+
+    async fn follower_count(&self, ctx: &Context<'_>) -> Result<i64> {
+        Ok(load(ctx, FollowCountsKey(self.fid)).await?.unwrap_or_default().followers)
+    }
+
+    async fn following_count(&self, ctx: &Context<'_>) -> Result<i64> {
+        Ok(load(ctx, FollowCountsKey(self.fid)).await?.unwrap_or_default().following)
+    }
+
+    /// Casts by this user, newest first
+    #[graphql(complexity = "first.max(1) as usize * child_complexity")]
+    async fn casts(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 25)] first: i32,
+        after: Option<String>,
+    ) -> Result<Page<Cast>> {
+        let (limit, cursor) = page_args(ctx, first, after.as_deref())?;
+        let page = pages::casts_by_fid(&data(ctx).pool, self.fid, cursor.as_ref(), limit)
+            .await
+            .map_err(internal)?;
+        Ok(Page::from_rows(page, Cast))
+    }
+
+    /// Reactions made by this user, newest first
+    #[graphql(complexity = "first.max(1) as usize * child_complexity")]
+    async fn reactions(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "type")] reaction_type: Option<ReactionType>,
+        #[graphql(default = 25)] first: i32,
+        after: Option<String>,
+    ) -> Result<Page<Reaction>> {
+        let (limit, cursor) = page_args(ctx, first, after.as_deref())?;
+        let page = pages::reactions_by_fid(
+            &data(ctx).pool,
+            self.fid,
+            reaction_type.map(ReactionType::to_db),
+            cursor.as_ref(),
+            limit,
+        )
+        .await
+        .map_err(internal)?;
+        Ok(Page::from_rows(page, Reaction))
+    }
+
+    /// Follow links pointing at this user, newest first
+    #[graphql(complexity = "first.max(1) as usize * child_complexity")]
+    async fn followers(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 25)] first: i32,
+        after: Option<String>,
+    ) -> Result<Page<Link>> {
+        let (limit, cursor) = page_args(ctx, first, after.as_deref())?;
+        let page =
+            pages::links_by_target(&data(ctx).pool, self.fid, "follow", cursor.as_ref(), limit)
+                .await
+                .map_err(internal)?;
+        Ok(Page::from_rows(page, Link))
+    }
+
+    /// Follow links made by this user, newest first
+    #[graphql(complexity = "first.max(1) as usize * child_complexity")]
+    async fn following(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 25)] first: i32,
+        after: Option<String>,
+    ) -> Result<Page<Link>> {
+        let (limit, cursor) = page_args(ctx, first, after.as_deref())?;
+        let page = pages::links_by_fid(&data(ctx).pool, self.fid, "follow", cursor.as_ref(), limit)
+            .await
+            .map_err(internal)?;
+        Ok(Page::from_rows(page, Link))
+    }
+
+    #[graphql(complexity = "first.max(1) as usize * child_complexity")]
+    async fn verifications(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 25)] first: i32,
+        after: Option<String>,
+    ) -> Result<Page<Verification>> {
+        let (limit, cursor) = page_args(ctx, first, after.as_deref())?;
+        let page = pages::verifications_by_fid(&data(ctx).pool, self.fid, cursor.as_ref(), limit)
+            .await
+            .map_err(internal)?;
+        Ok(Page::from_rows(page, Verification))
+    }
+
+    /// `type` is `signer`, `signer_migrated`, `id_register`, `storage_rent` or `tier_purchase`
+    #[graphql(complexity = "first.max(1) as usize * child_complexity")]
+    async fn onchain_events(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "type")] event_type: Option<String>,
+        #[graphql(default = 25)] first: i32,
+        after: Option<String>,
+    ) -> Result<Page<OnchainEvent>> {
+        let event_type = match event_type.as_deref() {
+            Some(name) => Some(
+                pages::onchain_event_type(name)
+                    .ok_or_else(|| Error::new(format!("Unknown on-chain event type: {}", name)))?,
+            ),
+            None => None,
+        };
+        let (limit, cursor) = page_args(ctx, first, after.as_deref())?;
+        let page = pages::onchain_events_by_fid(
+            &data(ctx).pool,
+            self.fid,
+            event_type,
+            cursor.as_ref(),
+            limit,
+        )
+        .await
+        .map_err(internal)?;
+        Ok(Page::from_rows(page, OnchainEvent))
+    }
+}
+
+/// A live cast
+pub struct Cast(pub CastRow);
+
+#[Object]
+impl Cast {
+    /// Hex, without `0x`
+    async fn hash(&self) -> String {
+        hex::encode(&self.0.hash)
+    }
+
+    async fn fid(&self) -> i64 {
+        self.0.fid
+    }
+
+    async fn text(&self) -> Option<&str> {
+        self.0.text.as_deref()
+    }
+
+    /// Unix seconds
+    async fn timestamp(&self) -> i64 {
+        unix(self.0.timestamp)
+    }
+
+    async fn embeds(&self) -> Json<serde_json::Value> {
+        Json(self.0.embeds.clone())
+    }
+
+    async fn mentions(&self) -> Vec<i64> {
+        serde_json::from_value(self.0.mentions.clone()).unwrap_or_default()
+    }
+
+    async fn parent_fid(&self) -> Option<i64> {
+        self.0.parent_fid
+    }
+
+    async fn parent_hash(&self) -> Option<String> {
+        self.0.parent_hash.as_ref().map(hex::encode)
+    }
+
+    async fn parent_url(&self) -> Option<&str> {
+        self.0.parent_url.as_deref()
+    }
+
+    /// Author of the cast this thread starts from; null for top-level casts
+    async fn root_parent_fid(&self, ctx: &Context<'_>) -> Result<Option<i64>> {
+        let root = load(ctx, CastRootKey(self.0.hash.clone())).await?;
+        Ok(root.and_then(|root| root.fid))
+    }
+
+    /// Hash of the cast this thread starts from; null for top-level casts
+    async fn root_parent_hash(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let root = load(ctx, CastRootKey(self.0.hash.clone())).await?;
+        Ok(root.and_then(|root| root.hash).map(hex::encode))
+    }
+
+    /// URL the thread was posted under (its channel), if any
+    async fn root_parent_url(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let root = load(ctx, CastRootKey(self.0.hash.clone())).await?;
+        Ok(root.and_then(|root| root.url))
+    }
+
+    async fn author(&self) -> User {
+        User { fid: self.0.fid }
+    }
+
+    /// The cast this one replies to, if it is a reply and the parent is still live
+    async fn parent(&self, ctx: &Context<'_>) -> Result<Option<Cast>> {
+        let Some(parent_hash) = self.0.parent_hash.clone() else {
+            return Ok(None);
+        };
+        Ok(load(ctx, CastKey(parent_hash)).await?.map(Cast))
+    }
+
+    async fn channel(&self, ctx: &Context<'_>) -> Result<Option<Channel>> {
+        let root = load(ctx, CastRootKey(self.0.hash.clone())).await?;
+        let Some(url) = root.and_then(|root| root.url) else {
+            return Ok(None);
+        };
+        Ok(load(ctx, ChannelKey(url)).await?.map(Channel))
+    }
+
+    async fn reaction_counts(&self, ctx: &Context<'_>) -> Result<ReactionCounts> {
+        let counts = load(ctx, ReactionCountsKey(self.0.hash.clone())).await?.unwrap_or_default();
+        Ok(ReactionCounts { likes: counts.likes, recasts: counts.recasts })
+    }
+
+    async fn reply_count(&self, ctx: &Context<'_>) -> Result<i64> {
+        Ok(load(ctx, ReplyCountKey(self.0.hash.clone())).await?.unwrap_or_default())
+    }
+
+    /// The newest `first` direct replies, batched across every cast in the query
+    #[graphql(complexity = "first.max(1) as usize * child_complexity")]
+    async fn replies(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 10)] first: i32,
+    ) -> Result<Vec<Cast>> {
+        let (first, _) = page_args(ctx, first, None)?;
+        let key = RepliesKey { parent_hash: self.0.hash.clone(), first };
+        Ok(load(ctx, key).await?.unwrap_or_default().into_iter().map(Cast).collect())
+    }
+
+    /// Reactions to this cast, newest first
+    #[graphql(complexity = "first.max(1) as usize * child_complexity")]
+    async fn reactions(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "type")] reaction_type: Option<ReactionType>,
+        #[graphql(default = 25)] first: i32,
+        after: Option<String>,
+    ) -> Result<Page<Reaction>> {
+        let (limit, cursor) = page_args(ctx, first, after.as_deref())?;
+        let page = pages::reactions_by_cast(
+            &data(ctx).pool,
+            &self.0.hash,
+            reaction_type.map(ReactionType::to_db),
+            cursor.as_ref(),
+            limit,
+        )
+        .await
+        .map_err(internal)?;
+        Ok(Page::from_rows(page, Reaction))
+    }
+}
+
+/// A live reaction
+pub struct Reaction(pub ReactionRow);
+
+#[Object]
+impl Reaction {
+    async fn hash(&self) -> String {
+        hex::encode(&self.0.hash)
+    }
+
+    async fn fid(&self) -> i64 {
+        self.0.fid
+    }
+
+    #[graphql(name = "type")]
+    async fn reaction_type(&self) -> Option<ReactionType> {
+        ReactionType::from_db(self.0.reaction_type)
+    }
+
+    /// Unix seconds
+    async fn timestamp(&self) -> i64 {
+        unix(self.0.timestamp)
+    }
+
+    async fn target_cast_hash(&self) -> Option<String> {
+        self.0.target_cast_hash.as_ref().map(hex::encode)
+    }
+
+    async fn target_url(&self) -> Option<&str> {
+        self.0.target_url.as_deref()
+    }
+
+    async fn user(&self) -> User {
+        User { fid: self.0.fid }
+    }
+
+    /// The cast reacted to, if it is still live
+    async fn cast(&self, ctx: &Context<'_>) -> Result<Option<Cast>> {
+        let Some(hash) = self.0.target_cast_hash.clone() else {
+            return Ok(None);
+        };
+        Ok(load(ctx, CastKey(hash)).await?.map(Cast))
+    }
+}
+
+/// A live link (follow) between two users
+pub struct Link(pub LinkRow);
+
+#[Object]
+impl Link {
+    async fn hash(&self) -> String {
+        hex::encode(&self.0.hash)
+    }
+
+    async fn fid(&self) -> i64 {
+        self.0.fid
+    }
+
+    #[graphql(name = "type")]
+    async fn link_type(&self) -> &str {
+        &self.0.link_type
+    }
+
+    async fn target_fid(&self) -> i64 {
+        self.0.target_fid
+    }
+
+    /// Unix seconds
+    async fn timestamp(&self) -> i64 {
+        unix(self.0.timestamp)
+    }
+
+    async fn user(&self) -> User {
+        User { fid: self.0.fid }
+    }
+
+    async fn target(&self) -> User {
+        User { fid: self.0.target_fid }
+    }
+}
+
+/// A live verified address
+pub struct Verification(pub VerificationRow);
+
+#[Object]
+impl Verification {
+    async fn hash(&self) -> String {
+        hex::encode(&self.0.hash)
+    }
+
+    async fn fid(&self) -> i64 {
+        self.0.fid
+    }
+
+    async fn address(&self) -> String {
+        address(&self.0.signer_address)
+    }
+
+    async fn protocol(&self) -> &'static str {
+        protocol_name(self.0.protocol.unwrap_or_default() as i32)
+    }
+
+    /// Unix seconds
+    async fn timestamp(&self) -> i64 {
+        unix(self.0.timestamp)
+    }
+}
+
+/// An on-chain registry event
+pub struct OnchainEvent(pub OnchainEventRow);
+
+#[Object]
+impl OnchainEvent {
+    async fn fid(&self) -> i64 {
+        self.0.fid
+    }
+
+    #[graphql(name = "type")]
+    async fn event_type(&self) -> &'static str {
+        self.0.type_name()
+    }
+
+    async fn chain_id(&self) -> i64 {
+        self.0.chain_id
+    }
+
+    async fn block_number(&self) -> i64 {
+        self.0.block_number
+    }
+
+    async fn block_hash(&self) -> String {
+        address(&self.0.block_hash)
+    }
+
+    async fn log_index(&self) -> i32 {
+        self.0.log_index
+    }
+
+    async fn transaction_hash(&self) -> String {
+        address(&self.0.tx_hash)
+    }
+
+    /// Unix seconds
+    async fn timestamp(&self) -> i64 {
+        unix(self.0.timestamp)
+    }
+}
+
+/// A channel, keyed by the URL its casts use as their root parent
+pub struct Channel(pub ChannelRecord);
+
+#[Object]
+impl Channel {
+    async fn url(&self) -> &str {
+        &self.0.url
+    }
+
+    /// Short ID (`memes`), when metadata has been imported
+    async fn id(&self) -> Option<&str> {
+        self.0.channel_id.as_deref()
+    }
+
+    async fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.0.description.as_deref()
+    }
+
+    async fn lead(&self) -> Option<User> {
+        self.0.lead_fid.map(|fid| User { fid: fid as i64 })
+    }
+
+    /// Unix seconds
+    async fn first_cast_at(&self) -> Option<i64> {
+        self.0.first_cast_at.map(|t| t as i64)
+    }
+
+    /// Unix seconds
+    async fn last_cast_at(&self) -> Option<i64> {
+        self.0.last_cast_at.map(|t| t as i64)
+    }
+
+    async fn cast_count(&self) -> i64 {
+        self.0.cast_count as i64
+    }
+
+    async fn reply_count(&self) -> i64 {
+        self.0.reply_count as i64
+    }
+
+    /// Casts in the channel, including replies, newest first
+    #[graphql(complexity = "first.max(1) as usize * child_complexity")]
+    async fn casts(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 25)] first: i32,
+        after: Option<String>,
+    ) -> Result<Page<Cast>> {
+        let (limit, cursor) = page_args(ctx, first, after.as_deref())?;
+        let page = pages::casts_by_channel(&data(ctx).pool, &self.0.url, cursor.as_ref(), limit)
+            .await
+            .map_err(internal)?;
+        Ok(Page::from_rows(page, Cast))
+    }
+}
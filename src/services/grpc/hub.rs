@@ -0,0 +1,565 @@
+//! Read-only `HubService` answered from PostgreSQL.
+
+use crate::{
+    core::{data_context::DataAccessError, types::MessageType},
+    database::{
+        hub_messages::from_farcaster_bound,
+        pages::{Page, PageCursor},
+        signed_messages::{self, CastParent, MessageQuery, ReactionTarget, StoredMessage},
+    },
+    proto::{
+        self, Block, CastId, CastsByParentRequest, EventRequest, EventsRequest, EventsResponse,
+        FidAddressTypeRequest, FidAddressTypeResponse, FidRequest, FidTimestampRequest,
+        FidsRequest, FidsResponse, GetConnectedPeersRequest, GetConnectedPeersResponse,
+        GetInfoRequest, GetInfoResponse, HubEvent, IdRegistryEventByAddressRequest, LinkRequest,
+        LinksByFidRequest, LinksByTargetRequest, MessagesResponse, OnChainEvent,
+        OnChainEventRequest, OnChainEventResponse, ReactionRequest, ReactionsByFidRequest,
+        ReactionsByTargetRequest, ShardChunksRequest, ShardChunksResponse, SignerRequest,
+        StorageLimitsResponse, SubmitBulkMessagesRequest, SubmitBulkMessagesResponse,
+        SubscribeRequest, TrieNodeMetadataRequest, TrieNodeMetadataResponse, UserDataRequest,
+        UserNameProof, UsernameProofRequest, UsernameProofsResponse, ValidationResponse,
+        VerificationRequest, casts_by_parent_request, hub_service_server::HubService, link_request,
+        links_by_target_request, reaction_request, reactions_by_target_request,
+    },
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tokio_stream::Empty;
+use tonic::{Request, Response, Status};
+use tracing::error;
+
+type RpcResult<T> = Result<Response<T>, Status>;
+
+/// Paging fields shared by the list requests
+struct PageParams {
+    cursor: Option<PageCursor>,
+    reverse: bool,
+    limit: usize,
+}
+
+/// `HubService` implementation over the synced tables
+#[derive(Clone)]
+pub struct HubReadService {
+    pool: PgPool,
+    default_page_size: usize,
+    max_page_size: usize,
+}
+
+impl HubReadService {
+    pub fn new(pool: PgPool, default_page_size: usize, max_page_size: usize) -> Self {
+        Self { pool, default_page_size, max_page_size }
+    }
+
+    fn page_params(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<Vec<u8>>,
+        reverse: Option<bool>,
+    ) -> Result<PageParams, Status> {
+        let cursor = match page_token.filter(|token| !token.is_empty()) {
+            Some(token) => Some(
+                std::str::from_utf8(&token)
+                    .ok()
+                    .and_then(PageCursor::decode)
+                    .ok_or_else(|| Status::invalid_argument("Invalid page token"))?,
+            ),
+            None => None,
+        };
+
+        let limit = match page_size {
+            Some(size) if size > 0 => (size as usize).min(self.max_page_size),
+            _ => self.default_page_size,
+        };
+
+        Ok(PageParams { cursor, reverse: reverse.unwrap_or(false), limit: limit.max(1) })
+    }
+
+    async fn list(
+        &self,
+        query: MessageQuery<'_>,
+        params: PageParams,
+    ) -> RpcResult<MessagesResponse> {
+        let Page { items, next } = signed_messages::messages(
+            &self.pool,
+            query,
+            params.cursor.as_ref(),
+            params.reverse,
+            params.limit,
+        )
+        .await
+        .map_err(internal)?;
+
+        let messages = items
+            .into_iter()
+            .map(StoredMessage::into_proto)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(internal)?;
+
+        Ok(Response::new(MessagesResponse {
+            messages,
+            next_page_token: next.map(|cursor| cursor.encode().into_bytes()),
+        }))
+    }
+
+    async fn one(&self, query: MessageQuery<'_>) -> RpcResult<proto::Message> {
+        match signed_messages::message(&self.pool, query).await.map_err(internal)? {
+            Some(row) => Ok(Response::new(row.into_proto().map_err(internal)?)),
+            None => Err(Status::not_found("Message not found")),
+        }
+    }
+
+    async fn all_by_fid(
+        &self,
+        request: FidTimestampRequest,
+        family: MessageType,
+    ) -> RpcResult<MessagesResponse> {
+        let params = self.page_params(request.page_size, request.page_token, request.reverse)?;
+        let query = MessageQuery::AllByFid {
+            fid: fid(request.fid)?,
+            family,
+            start: from_farcaster_bound(request.start_timestamp),
+            stop: from_farcaster_bound(request.stop_timestamp),
+        };
+        self.list(query, params).await
+    }
+}
+
+fn internal(e: DataAccessError) -> Status {
+    error!("gRPC read failed: {}", e);
+    Status::internal("Failed to read from the database")
+}
+
+fn unsupported(method: &str) -> Status {
+    Status::unimplemented(format!("{} is not served by Waypoint", method))
+}
+
+fn fid(fid: u64) -> Result<i64, Status> {
+    match i64::try_from(fid) {
+        Ok(fid) if fid > 0 => Ok(fid),
+        _ => Err(Status::invalid_argument("Invalid fid")),
+    }
+}
+
+/// Reaction type filter; `0` (none) matches every type
+fn reaction_type(reaction_type: Option<i32>) -> Option<i16> {
+    reaction_type.filter(|t| *t != 0).map(|t| t as i16)
+}
+
+fn missing(field: &str) -> Status {
+    Status::invalid_argument(format!("{} is required", field))
+}
+
+#[async_trait]
+impl HubService for HubReadService {
+    async fn submit_message(&self, _: Request<proto::Message>) -> RpcResult<proto::Message> {
+        Err(unsupported("SubmitMessage"))
+    }
+
+    async fn submit_bulk_messages(
+        &self,
+        _: Request<SubmitBulkMessagesRequest>,
+    ) -> RpcResult<SubmitBulkMessagesResponse> {
+        Err(unsupported("SubmitBulkMessages"))
+    }
+
+    async fn validate_message(&self, _: Request<proto::Message>) -> RpcResult<ValidationResponse> {
+        Err(unsupported("ValidateMessage"))
+    }
+
+    type GetBlocksStream = Empty<Result<Block, Status>>;
+
+    async fn get_blocks(
+        &self,
+        _: Request<proto::BlocksRequest>,
+    ) -> RpcResult<Self::GetBlocksStream> {
+        Err(unsupported("GetBlocks"))
+    }
+
+    async fn get_shard_chunks(
+        &self,
+        _: Request<ShardChunksRequest>,
+    ) -> RpcResult<ShardChunksResponse> {
+        Err(unsupported("GetShardChunks"))
+    }
+
+    async fn get_info(&self, _: Request<GetInfoRequest>) -> RpcResult<GetInfoResponse> {
+        Err(unsupported("GetInfo"))
+    }
+
+    async fn get_fids(&self, _: Request<FidsRequest>) -> RpcResult<FidsResponse> {
+        Err(unsupported("GetFids"))
+    }
+
+    async fn get_connected_peers(
+        &self,
+        _: Request<GetConnectedPeersRequest>,
+    ) -> RpcResult<GetConnectedPeersResponse> {
+        Err(unsupported("GetConnectedPeers"))
+    }
+
+    type SubscribeStream = Empty<Result<HubEvent, Status>>;
+
+    async fn subscribe(&self, _: Request<SubscribeRequest>) -> RpcResult<Self::SubscribeStream> {
+        Err(unsupported("Subscribe"))
+    }
+
+    async fn get_event(&self, _: Request<EventRequest>) -> RpcResult<HubEvent> {
+        Err(unsupported("GetEvent"))
+    }
+
+    async fn get_events(&self, _: Request<EventsRequest>) -> RpcResult<EventsResponse> {
+        Err(unsupported("GetEvents"))
+    }
+
+    async fn get_cast(&self, request: Request<CastId>) -> RpcResult<proto::Message> {
+        let cast_id = request.into_inner();
+        self.one(MessageQuery::Cast { fid: fid(cast_id.fid)?, hash: &cast_id.hash }).await
+    }
+
+    async fn get_casts_by_fid(&self, request: Request<FidRequest>) -> RpcResult<MessagesResponse> {
+        let request = request.into_inner();
+        let params = self.page_params(request.page_size, request.page_token, request.reverse)?;
+        self.list(MessageQuery::CastsByFid(fid(request.fid)?), params).await
+    }
+
+    async fn get_casts_by_parent(
+        &self,
+        request: Request<CastsByParentRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        let request = request.into_inner();
+        let params = self.page_params(request.page_size, request.page_token, request.reverse)?;
+        let parent = match &request.parent {
+            Some(casts_by_parent_request::Parent::ParentCastId(cast_id)) => {
+                CastParent::Cast(&cast_id.hash)
+            },
+            Some(casts_by_parent_request::Parent::ParentUrl(url)) => CastParent::Url(url),
+            None => return Err(missing("parent")),
+        };
+        self.list(MessageQuery::CastsByParent(parent), params).await
+    }
+
+    async fn get_casts_by_mention(
+        &self,
+        request: Request<FidRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        let request = request.into_inner();
+        let params = self.page_params(request.page_size, request.page_token, request.reverse)?;
+        self.list(MessageQuery::CastsByMention(fid(request.fid)?), params).await
+    }
+
+    async fn get_reaction(&self, request: Request<ReactionRequest>) -> RpcResult<proto::Message> {
+        let request = request.into_inner();
+        let target = match &request.target {
+            Some(reaction_request::Target::TargetCastId(cast_id)) => {
+                ReactionTarget::Cast(&cast_id.hash)
+            },
+            Some(reaction_request::Target::TargetUrl(url)) => ReactionTarget::Url(url),
+            None => return Err(missing("target")),
+        };
+        self.one(MessageQuery::Reaction {
+            fid: fid(request.fid)?,
+            reaction_type: request.reaction_type as i16,
+            target,
+        })
+        .await
+    }
+
+    async fn get_reactions_by_fid(
+        &self,
+        request: Request<ReactionsByFidRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        let request = request.into_inner();
+        let params = self.page_params(request.page_size, request.page_token, request.reverse)?;
+        let query = MessageQuery::ReactionsByFid {
+            fid: fid(request.fid)?,
+            reaction_type: reaction_type(request.reaction_type),
+        };
+        self.list(query, params).await
+    }
+
+    async fn get_reactions_by_cast(
+        &self,
+        request: Request<ReactionsByTargetRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        self.get_reactions_by_target(request).await
+    }
+
+    async fn get_reactions_by_target(
+        &self,
+        request: Request<ReactionsByTargetRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        let request = request.into_inner();
+        let params = self.page_params(request.page_size, request.page_token, request.reverse)?;
+        let target = match &request.target {
+            Some(reactions_by_target_request::Target::TargetCastId(cast_id)) => {
+                ReactionTarget::Cast(&cast_id.hash)
+            },
+            Some(reactions_by_target_request::Target::TargetUrl(url)) => ReactionTarget::Url(url),
+            None => return Err(missing("target")),
+        };
+        let query = MessageQuery::ReactionsByTarget {
+            target,
+            reaction_type: reaction_type(request.reaction_type),
+        };
+        self.list(query, params).await
+    }
+
+    async fn get_user_data(&self, request: Request<UserDataRequest>) -> RpcResult<proto::Message> {
+        let request = request.into_inner();
+        self.one(MessageQuery::UserData {
+            fid: fid(request.fid)?,
+            data_type: request.user_data_type as i16,
+        })
+        .await
+    }
+
+    async fn get_user_data_by_fid(
+        &self,
+        request: Request<FidRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        let request = request.into_inner();
+        let params = self.page_params(request.page_size, request.page_token, request.reverse)?;
+        self.list(MessageQuery::UserDataByFid(fid(request.fid)?), params).await
+    }
+
+    async fn get_username_proof(
+        &self,
+        request: Request<UsernameProofRequest>,
+    ) -> RpcResult<UserNameProof> {
+        let request = request.into_inner();
+        let name = std::str::from_utf8(&request.name)
+            .map_err(|_| Status::invalid_argument("Invalid name"))?;
+        match signed_messages::username_proof(&self.pool, name).await.map_err(internal)? {
+            Some(proof) => Ok(Response::new(proof)),
+            None => Err(Status::not_found("Username proof not found")),
+        }
+    }
+
+    async fn get_user_name_proofs_by_fid(
+        &self,
+        request: Request<FidRequest>,
+    ) -> RpcResult<UsernameProofsResponse> {
+        let fid = fid(request.into_inner().fid)?;
+        let proofs =
+            signed_messages::username_proofs_by_fid(&self.pool, fid).await.map_err(internal)?;
+        Ok(Response::new(UsernameProofsResponse { proofs }))
+    }
+
+    async fn get_verification(
+        &self,
+        request: Request<VerificationRequest>,
+    ) -> RpcResult<proto::Message> {
+        let request = request.into_inner();
+        self.one(MessageQuery::Verification { fid: fid(request.fid)?, address: &request.address })
+            .await
+    }
+
+    async fn get_verifications_by_fid(
+        &self,
+        request: Request<FidRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        let request = request.into_inner();
+        let params = self.page_params(request.page_size, request.page_token, request.reverse)?;
+        self.list(MessageQuery::VerificationsByFid(fid(request.fid)?), params).await
+    }
+
+    async fn get_on_chain_signer(&self, _: Request<SignerRequest>) -> RpcResult<OnChainEvent> {
+        Err(unsupported("GetOnChainSigner"))
+    }
+
+    async fn get_on_chain_signers_by_fid(
+        &self,
+        _: Request<FidRequest>,
+    ) -> RpcResult<OnChainEventResponse> {
+        Err(unsupported("GetOnChainSignersByFid"))
+    }
+
+    async fn get_on_chain_events(
+        &self,
+        _: Request<OnChainEventRequest>,
+    ) -> RpcResult<OnChainEventResponse> {
+        Err(unsupported("GetOnChainEvents"))
+    }
+
+    async fn get_id_registry_on_chain_event(
+        &self,
+        _: Request<FidRequest>,
+    ) -> RpcResult<OnChainEvent> {
+        Err(unsupported("GetIdRegistryOnChainEvent"))
+    }
+
+    async fn get_id_registry_on_chain_event_by_address(
+        &self,
+        _: Request<IdRegistryEventByAddressRequest>,
+    ) -> RpcResult<OnChainEvent> {
+        Err(unsupported("GetIdRegistryOnChainEventByAddress"))
+    }
+
+    async fn get_current_storage_limits_by_fid(
+        &self,
+        _: Request<FidRequest>,
+    ) -> RpcResult<StorageLimitsResponse> {
+        Err(unsupported("GetCurrentStorageLimitsByFid"))
+    }
+
+    async fn get_fid_address_type(
+        &self,
+        _: Request<FidAddressTypeRequest>,
+    ) -> RpcResult<FidAddressTypeResponse> {
+        Err(unsupported("GetFidAddressType"))
+    }
+
+    async fn get_link(&self, request: Request<LinkRequest>) -> RpcResult<proto::Message> {
+        let request = request.into_inner();
+        let Some(link_request::Target::TargetFid(target_fid)) = request.target else {
+            return Err(missing("target"));
+        };
+        self.one(MessageQuery::Link {
+            fid: fid(request.fid)?,
+            link_type: &request.link_type,
+            target_fid: fid(target_fid)?,
+        })
+        .await
+    }
+
+    async fn get_links_by_fid(
+        &self,
+        request: Request<LinksByFidRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        let request = request.into_inner();
+        let params = self.page_params(request.page_size, request.page_token, request.reverse)?;
+        let query = MessageQuery::LinksByFid {
+            fid: fid(request.fid)?,
+            link_type: request.link_type.as_deref().filter(|t| !t.is_empty()),
+        };
+        self.list(query, params).await
+    }
+
+    async fn get_links_by_target(
+        &self,
+        request: Request<LinksByTargetRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        let request = request.into_inner();
+        let params = self.page_params(request.page_size, request.page_token, request.reverse)?;
+        let Some(links_by_target_request::Target::TargetFid(target_fid)) = request.target else {
+            return Err(missing("target"));
+        };
+        let query = MessageQuery::LinksByTarget {
+            target_fid: fid(target_fid)?,
+            link_type: request.link_type.as_deref().filter(|t| !t.is_empty()),
+        };
+        self.list(query, params).await
+    }
+
+    async fn get_link_compact_state_message_by_fid(
+        &self,
+        request: Request<FidRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        let request = request.into_inner();
+        let params = self.page_params(request.page_size, request.page_token, request.reverse)?;
+        self.list(MessageQuery::LinkCompactStateByFid(fid(request.fid)?), params).await
+    }
+
+    async fn get_all_cast_messages_by_fid(
+        &self,
+        request: Request<FidTimestampRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        self.all_by_fid(request.into_inner(), MessageType::Cast).await
+    }
+
+    async fn get_all_reaction_messages_by_fid(
+        &self,
+        request: Request<FidTimestampRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        self.all_by_fid(request.into_inner(), MessageType::Reaction).await
+    }
+
+    async fn get_all_verification_messages_by_fid(
+        &self,
+        request: Request<FidTimestampRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        self.all_by_fid(request.into_inner(), MessageType::Verification).await
+    }
+
+    async fn get_all_user_data_messages_by_fid(
+        &self,
+        request: Request<FidTimestampRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        self.all_by_fid(request.into_inner(), MessageType::UserData).await
+    }
+
+    async fn get_all_link_messages_by_fid(
+        &self,
+        request: Request<FidTimestampRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        self.all_by_fid(request.into_inner(), MessageType::Link).await
+    }
+
+    async fn get_all_lend_storage_messages_by_fid(
+        &self,
+        request: Request<FidTimestampRequest>,
+    ) -> RpcResult<MessagesResponse> {
+        self.all_by_fid(request.into_inner(), MessageType::LendStorage).await
+    }
+
+    async fn get_trie_metadata_by_prefix(
+        &self,
+        _: Request<TrieNodeMetadataRequest>,
+    ) -> RpcResult<TrieNodeMetadataResponse> {
+        Err(unsupported("GetTrieMetadataByPrefix"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+
+    fn service() -> HubReadService {
+        let pool = PgPoolOptions::new().connect_lazy("postgres://localhost/waypoint").unwrap();
+        HubReadService::new(pool, 100, 1000)
+    }
+
+    #[tokio::test]
+    async fn test_page_params() {
+        let service = service();
+
+        let params = service.page_params(None, None, None).unwrap();
+        assert_eq!(params.limit, 100);
+        assert!(params.cursor.is_none() && !params.reverse);
+
+        let params = service.page_params(Some(5000), Some(Vec::new()), Some(true)).unwrap();
+        assert_eq!(params.limit, 1000);
+        assert!(params.cursor.is_none() && params.reverse);
+
+        let token = b"1700000000000000.abcd".to_vec();
+        let params = service.page_params(Some(10), Some(token), None).unwrap();
+        assert_eq!(params.cursor.unwrap().hash, vec![0xab, 0xcd]);
+
+        let status = service.page_params(None, Some(b"garbage".to_vec()), None).err().unwrap();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_writes_are_unimplemented() {
+        let service = service();
+
+        let status = service.submit_message(Request::new(proto::Message::default())).await;
+        assert_eq!(status.unwrap_err().code(), tonic::Code::Unimplemented);
+
+        let status = service.subscribe(Request::new(SubscribeRequest::default())).await;
+        assert_eq!(status.err().unwrap().code(), tonic::Code::Unimplemented);
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_validated() {
+        let service = service();
+
+        let status = service.get_casts_by_fid(Request::new(FidRequest::default())).await;
+        assert_eq!(status.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+        let status =
+            service.get_casts_by_parent(Request::new(CastsByParentRequest::default())).await;
+        assert_eq!(status.unwrap_err().code(), tonic::Code::InvalidArgument);
+    }
+}
@@ -0,0 +1,74 @@
+//! Hub-compatible gRPC read server.
+//!
+//! Serves the read-only subset of the Snapchain `HubService` from PostgreSQL,
+//! so tools written against a hub can point at Waypoint instead. Messages are
+//! the stored signed messages, with their original hash, signer and signature.
+//! Writes, subscriptions, blocks and on-chain event reads return `Unimplemented`.
+
+mod hub;
+
+pub use hub::HubReadService;
+
+use crate::{
+    app::{Service, ServiceContext, ServiceError, ServiceHandle},
+    config::Config,
+    proto::hub_service_server::HubServiceServer,
+};
+use async_trait::async_trait;
+use std::net::SocketAddr;
+use tonic::transport::{Server, server::TcpIncoming};
+use tracing::{error, info};
+
+/// gRPC read server service
+pub struct GrpcService {
+    config: Config,
+}
+
+impl GrpcService {
+    /// Create a new gRPC service from the application configuration
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Service for GrpcService {
+    fn name(&self) -> &str {
+        "grpc"
+    }
+
+    async fn start<'a>(&'a self, context: ServiceContext<'a>) -> crate::app::Result<ServiceHandle> {
+        let grpc = &self.config.grpc;
+        let socket_addr = format!("{}:{}", grpc.bind_address, grpc.port)
+            .parse::<SocketAddr>()
+            .map_err(|e| ServiceError::Initialization(format!("Invalid socket address: {}", e)))?;
+
+        let database = context.state.database.clone().ok_or_else(|| {
+            ServiceError::Initialization("gRPC server requires a PostgreSQL database".to_string())
+        })?;
+
+        let service =
+            HubReadService::new(database.pool.clone(), grpc.default_page_size, grpc.max_page_size);
+
+        let incoming = TcpIncoming::bind(socket_addr).map_err(|e| {
+            ServiceError::Start(format!("Failed to bind gRPC server to {}: {}", socket_addr, e))
+        })?;
+        info!("gRPC server listening on {}", socket_addr);
+
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let server_handle = tokio::spawn(async move {
+            let server = Server::builder()
+                .add_service(HubServiceServer::new(service))
+                .serve_with_incoming_shutdown(incoming, async {
+                    let _ = stop_rx.await;
+                    info!("gRPC server shutting down");
+                });
+
+            if let Err(e) = server.await {
+                error!("gRPC server shutdown with error: {}", e);
+            }
+        });
+
+        Ok(ServiceHandle::new(stop_tx, server_handle))
+    }
+}
@@ -0,0 +1,333 @@
+//! Fan-out of the messages stream to live connections.
+//!
+//! One reader per process pulls the messages stream through its own consumer
+//! group and broadcasts each decoded event. Every connection gets a task that
+//! filters the broadcast into a small bounded queue; a connection that falls
+//! further behind than the broadcast buffer catches up from the stream with
+//! XRANGE, within its replay budget, and is closed once that runs out.
+
+use crate::{
+    database::webhooks::WebhookFilters,
+    processor::webhook::WebhookEvent,
+    proto::HubEvent,
+    redis::{client::Redis, stream::RedisStream},
+};
+use prost::Message as _;
+use std::{fmt, str::FromStr, sync::Arc, time::Duration};
+use tokio::sync::{OwnedSemaphorePermit, broadcast, mpsc};
+use tracing::{debug, error, warn};
+
+/// Entries read per XREADGROUP or XRANGE call
+const READ_BATCH: usize = 100;
+
+/// Events queued per connection between its task and the socket
+const CONNECTION_QUEUE: usize = 16;
+
+/// Position in the messages stream: a Redis stream ID (`<ms>-<seq>`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cursor {
+    ms: u64,
+    seq: u64,
+}
+
+impl FromStr for Cursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid cursor: {}", s);
+        let (ms, seq) = s.split_once('-').unwrap_or((s, "0"));
+        Ok(Self {
+            ms: ms.parse().map_err(|_| invalid())?,
+            seq: seq.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+/// A decoded event from the messages stream
+#[derive(Debug)]
+pub struct LiveEvent {
+    pub cursor: Cursor,
+    pub event: WebhookEvent,
+    /// `event.payload`, serialized once for every connection
+    pub json: String,
+}
+
+impl LiveEvent {
+    /// Decode a stream entry; `None` for entries that aren't a merged message
+    pub fn decode(id: &str, data: &[u8]) -> Option<Self> {
+        let cursor = id.parse().ok()?;
+        let hub_event = HubEvent::decode(data).ok()?;
+        let event = WebhookEvent::from_hub_event(&hub_event)?;
+        let json = event.payload.to_string();
+        Some(Self { cursor, event, json })
+    }
+}
+
+/// What a connection receives
+#[derive(Debug)]
+pub enum FeedItem {
+    Event(Arc<LiveEvent>),
+    /// Last item on a connection the server is closing; resume from `cursor`
+    Closed {
+        reason: CloseReason,
+        cursor: Option<Cursor>,
+    },
+}
+
+/// Why the server closed a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseReason {
+    /// The connection used up its replay budget catching up
+    ReplayLimit,
+    /// Redis failed while replaying
+    Unavailable,
+}
+
+impl CloseReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ReplayLimit => "replay_limit",
+            Self::Unavailable => "unavailable",
+        }
+    }
+}
+
+/// Shared state of the live feed
+pub struct LiveFeed {
+    redis: Arc<Redis>,
+    key: String,
+    sender: broadcast::Sender<Arc<LiveEvent>>,
+    max_replay: usize,
+}
+
+impl LiveFeed {
+    /// `buffer_size` is how many events a connection may fall behind by before
+    /// it has to catch up from the stream
+    pub fn new(redis: Arc<Redis>, key: String, buffer_size: usize, max_replay: usize) -> Self {
+        let (sender, _) = broadcast::channel(buffer_size.max(1));
+        Self { redis, key, sender, max_replay }
+    }
+
+    /// Broadcast a stream entry to the open connections
+    pub fn publish(&self, id: &str, data: &[u8]) {
+        if let Some(event) = LiveEvent::decode(id, data) {
+            // An error only means nobody is connected
+            let _ = self.sender.send(Arc::new(event));
+        }
+    }
+
+    /// Read the messages stream with `group` and broadcast every entry, until aborted
+    pub async fn run(self: Arc<Self>, stream: RedisStream, group: String, consumer: String) {
+        let mut backoff = Duration::from_millis(100);
+
+        // A new group starts at the end of the stream; older events are only reached by replay
+        while let Err(e) = stream.create_group(&self.key, &group).await {
+            error!("Live feed failed to create group {} on {}: {}", group, self.key, e);
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(Duration::from_secs(30));
+        }
+
+        loop {
+            match stream.reserve(&self.key, &group, READ_BATCH, Some(&consumer)).await {
+                Ok(entries) => {
+                    backoff = Duration::from_millis(100);
+                    if entries.is_empty() {
+                        continue;
+                    }
+
+                    let ids: Vec<String> = entries.iter().map(|entry| entry.id.clone()).collect();
+                    for entry in &entries {
+                        self.publish(&entry.id, &entry.data);
+                    }
+
+                    // Connections resume from the stream itself, so nothing is left pending
+                    if let Err(e) = stream.ack(&self.key, &group, ids).await {
+                        warn!("Live feed failed to acknowledge entries: {}", e);
+                    }
+                },
+                Err(e) => {
+                    error!("Live feed failed to read {}: {}", self.key, e);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(Duration::from_secs(30));
+                },
+            }
+        }
+    }
+
+    /// Open a connection: events matching `filters`, after `cursor` if given,
+    /// otherwise from now on. The connection holds `permit` until it closes.
+    pub fn subscribe(
+        self: &Arc<Self>,
+        filters: WebhookFilters,
+        cursor: Option<Cursor>,
+        permit: OwnedSemaphorePermit,
+    ) -> mpsc::Receiver<FeedItem> {
+        let (tx, rx) = mpsc::channel(CONNECTION_QUEUE);
+        let receiver = self.sender.subscribe();
+        tokio::spawn(Arc::clone(self).forward(filters, cursor, receiver, tx, permit));
+        rx
+    }
+
+    async fn forward(
+        self: Arc<Self>,
+        filters: WebhookFilters,
+        cursor: Option<Cursor>,
+        mut receiver: broadcast::Receiver<Arc<LiveEvent>>,
+        tx: mpsc::Sender<FeedItem>,
+        _permit: OwnedSemaphorePermit,
+    ) {
+        // Last stream entry this connection has seen, matching or not
+        let mut position = cursor;
+        let mut replaying = cursor.is_some();
+        let mut budget = self.max_replay;
+
+        loop {
+            if replaying {
+                let after = position.expect("replay starts from a position");
+                let count = READ_BATCH.min(budget);
+                if count == 0 {
+                    let _ = tx
+                        .send(FeedItem::Closed {
+                            reason: CloseReason::ReplayLimit,
+                            cursor: position,
+                        })
+                        .await;
+                    return;
+                }
+
+                let entries = match self
+                    .redis
+                    .xrange_after(&self.key, &after.to_string(), count as u64)
+                    .await
+                {
+                    Ok(entries) => entries,
+                    Err(e) => {
+                        warn!("Live feed replay from {} failed: {}", after, e);
+                        let _ = tx
+                            .send(FeedItem::Closed {
+                                reason: CloseReason::Unavailable,
+                                cursor: position,
+                            })
+                            .await;
+                        return;
+                    },
+                };
+
+                replaying = entries.len() == count;
+                budget -= entries.len();
+                for (id, data) in entries {
+                    let Ok(entry_cursor) = id.parse::<Cursor>() else { continue };
+                    position = Some(entry_cursor);
+                    if let Some(event) = LiveEvent::decode(&id, &data)
+                        && event.event.matches(&filters)
+                        && tx.send(FeedItem::Event(Arc::new(event))).await.is_err()
+                    {
+                        return;
+                    }
+                }
+                continue;
+            }
+
+            let received = tokio::select! {
+                // Notice disconnects even when no events are flowing
+                _ = tx.closed() => return,
+                received = receiver.recv() => received,
+            };
+            let event = match received {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!("Live connection fell {} events behind, catching up", skipped);
+                    // Nothing seen yet means it subscribed moments ago; start from the oldest
+                    // event still buffered rather than replaying the whole stream
+                    replaying = position.is_some();
+                    continue;
+                },
+                Err(broadcast::error::RecvError::Closed) => return,
+            };
+
+            // Already delivered by the replay
+            if position.is_some_and(|position| event.cursor <= position) {
+                continue;
+            }
+            position = Some(event.cursor);
+
+            if event.event.matches(&filters) && tx.send(FeedItem::Event(event)).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{
+        CastAddBody, HubEventType, MergeMessageBody, Message, MessageData, MessageType, hub_event,
+        message_data,
+    };
+    use tokio::sync::Semaphore;
+
+    fn cast(fid: u64, text: &str) -> Vec<u8> {
+        let data = MessageData {
+            r#type: MessageType::CastAdd as i32,
+            fid,
+            timestamp: 1,
+            network: 1,
+            body: Some(message_data::Body::CastAddBody(CastAddBody {
+                text: text.to_string(),
+                ..Default::default()
+            })),
+        };
+        HubEvent {
+            id: fid,
+            r#type: HubEventType::MergeMessage as i32,
+            body: Some(hub_event::Body::MergeMessageBody(MergeMessageBody {
+                message: Some(Message { data: Some(data), hash: vec![1], ..Default::default() }),
+                deleted_messages: vec![],
+            })),
+            ..Default::default()
+        }
+        .encode_to_vec()
+    }
+
+    fn permit() -> OwnedSemaphorePermit {
+        Arc::new(Semaphore::new(1)).try_acquire_owned().unwrap()
+    }
+
+    fn feed(buffer_size: usize) -> Arc<LiveFeed> {
+        Arc::new(LiveFeed::new(Arc::new(Redis::empty()), "test".to_string(), buffer_size, 100))
+    }
+
+    #[test]
+    fn test_cursor_order_and_format() {
+        let a: Cursor = "1700000000000-1".parse().unwrap();
+        let b: Cursor = "1700000000000-2".parse().unwrap();
+        let c: Cursor = "1700000000001".parse().unwrap();
+        assert!(a < b && b < c);
+        assert_eq!(c.to_string(), "1700000000001-0");
+        assert!("abc".parse::<Cursor>().is_err());
+        assert!("1-x".parse::<Cursor>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_live_events_are_filtered() {
+        let feed = feed(8);
+        let filters = WebhookFilters { fids: vec![3], ..Default::default() };
+        let mut rx = feed.subscribe(filters, None, permit());
+
+        feed.publish("1-0", &cast(2, "skip"));
+        feed.publish("2-0", &cast(3, "keep"));
+        feed.publish("3-0", b"not a hub event");
+
+        let Some(FeedItem::Event(event)) = rx.recv().await else { panic!("expected an event") };
+        assert_eq!(event.cursor.to_string(), "2-0");
+        assert_eq!(event.event.payload["data"]["text"], "keep");
+        assert!(rx.try_recv().is_err());
+    }
+}
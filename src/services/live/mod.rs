@@ -0,0 +1,414 @@
+//! Live event feed over SSE and WebSocket.
+//!
+//! Streams new messages to clients as they arrive, read from the combined
+//! messages stream through a dedicated consumer group. Clients filter by FID,
+//! mentioned FID, channel and message type, and resume from a cursor (the
+//! stream ID of the last event they saw). Events are the same JSON the
+//! webhooks deliver.
+
+mod feed;
+
+pub use feed::{CloseReason, Cursor, FeedItem, LiveEvent, LiveFeed};
+
+use crate::{
+    app::{Service, ServiceContext, ServiceError, ServiceHandle},
+    config::Config,
+    core::{MessageType, data_context::Database},
+    database::{PostgresDatabaseClient, webhooks::WebhookFilters},
+    processor::webhook::is_message_type_name,
+    redis::stream::RedisStream,
+};
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    extract::{
+        Query, State,
+        ws::{Message as WsMessage, WebSocket, WebSocketUpgrade},
+    },
+    http::{HeaderMap, StatusCode},
+    response::{
+        IntoResponse, Response,
+        sse::{Event as SseEvent, KeepAlive, Sse},
+    },
+    routing::get,
+};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{convert::Infallible, net::SocketAddr, sync::Arc, time::Duration};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+/// Shared state of the live endpoints
+#[derive(Clone)]
+pub struct LiveState {
+    pub feed: Arc<LiveFeed>,
+    /// Resolves channel IDs to URLs; without it only channel URLs are accepted
+    pub db: Option<PostgresDatabaseClient>,
+    pub connections: Arc<Semaphore>,
+    pub max_filter_values: usize,
+    pub keepalive: Duration,
+    /// Cancelled on shutdown to end open connections
+    pub shutdown: CancellationToken,
+}
+
+/// Query parameters shared by both endpoints; lists are comma-separated
+#[derive(Debug, Default, Deserialize)]
+pub struct FeedQuery {
+    /// Authors
+    pub fids: Option<String>,
+    /// Mentioned FIDs
+    pub mentions: Option<String>,
+    /// Channel IDs or URLs
+    pub channels: Option<String>,
+    /// Message types, e.g. `cast_add,reaction_add`
+    pub types: Option<String>,
+    /// Resume after this event; SSE clients can send `Last-Event-ID` instead
+    pub cursor: Option<String>,
+}
+
+/// Error body, as the REST API returns it
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// Errors answered before a connection is opened
+#[derive(Debug)]
+pub enum LiveError {
+    BadRequest(String),
+    TooManyConnections,
+    Internal(String),
+}
+
+impl IntoResponse for LiveError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            Self::TooManyConnections => {
+                (StatusCode::SERVICE_UNAVAILABLE, "Too many open connections".to_string())
+            },
+            Self::Internal(message) => {
+                error!("Live feed error: {}", message);
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string())
+            },
+        };
+
+        (status, Json(ErrorBody { error: message })).into_response()
+    }
+}
+
+/// Split a comma-separated parameter, enforcing the per-parameter limit
+fn list(name: &str, value: Option<&str>, max: usize) -> Result<Vec<String>, LiveError> {
+    let values: Vec<String> = value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect();
+    if values.len() > max {
+        return Err(LiveError::BadRequest(format!("At most {} values allowed in {}", max, name)));
+    }
+    Ok(values)
+}
+
+fn fid_list(name: &str, value: Option<&str>, max: usize) -> Result<Vec<i64>, LiveError> {
+    list(name, value, max)?
+        .into_iter()
+        .map(|fid| fid.parse().map_err(|_| LiveError::BadRequest(format!("Invalid FID: {}", fid))))
+        .collect()
+}
+
+impl LiveState {
+    /// Validate the query and build the filters it asks for
+    async fn filters(&self, query: &FeedQuery) -> Result<WebhookFilters, LiveError> {
+        let max = self.max_filter_values;
+
+        let message_types = list("types", query.types.as_deref(), max)?;
+        if let Some(name) = message_types.iter().find(|t| !is_message_type_name(t)) {
+            return Err(LiveError::BadRequest(format!("Unknown message type: {}", name)));
+        }
+
+        let mut parent_urls = Vec::new();
+        for channel in list("channels", query.channels.as_deref(), max)? {
+            if channel.contains("://") {
+                parent_urls.push(channel);
+                continue;
+            }
+            let Some(db) = &self.db else {
+                return Err(LiveError::BadRequest(format!(
+                    "Channel IDs need a database; pass the channel URL for {}",
+                    channel
+                )));
+            };
+            match db.get_channel(&channel).await.map_err(|e| LiveError::Internal(e.to_string()))? {
+                Some(found) => parent_urls.push(found.url),
+                None => return Err(LiveError::BadRequest(format!("Unknown channel: {}", channel))),
+            }
+        }
+
+        Ok(WebhookFilters {
+            fids: fid_list("fids", query.fids.as_deref(), max)?,
+            mentioned_fids: fid_list("mentions", query.mentions.as_deref(), max)?,
+            parent_urls,
+            message_types,
+            onchain_event_types: Vec::new(),
+        })
+    }
+
+    /// Check the query and the connection limit, then start the connection
+    async fn open(
+        &self,
+        query: &FeedQuery,
+        last_event_id: Option<&str>,
+    ) -> Result<mpsc::Receiver<FeedItem>, LiveError> {
+        let filters = self.filters(query).await?;
+        let cursor = query
+            .cursor
+            .as_deref()
+            .or(last_event_id)
+            .map(str::parse::<Cursor>)
+            .transpose()
+            .map_err(LiveError::BadRequest)?;
+        let permit: OwnedSemaphorePermit = Arc::clone(&self.connections)
+            .try_acquire_owned()
+            .map_err(|_| LiveError::TooManyConnections)?;
+
+        Ok(self.feed.subscribe(filters, cursor, permit))
+    }
+}
+
+/// JSON sent when the server closes a connection
+fn close_notice(reason: CloseReason, cursor: Option<Cursor>) -> serde_json::Value {
+    json!({ "reason": reason.as_str(), "cursor": cursor.map(|c| c.to_string()) })
+}
+
+/// `GET /live/sse`: one `message` per event with the cursor as its ID, and a
+/// final `close` event if the server ends the stream
+async fn sse(
+    State(state): State<LiveState>,
+    headers: HeaderMap,
+    Query(query): Query<FeedQuery>,
+) -> Result<Sse<impl Stream<Item = Result<SseEvent, Infallible>>>, LiveError> {
+    let last_event_id = headers.get("last-event-id").and_then(|v| v.to_str().ok());
+    let rx = state.open(&query, last_event_id).await?;
+
+    let shutdown = state.shutdown.clone();
+    let events = ReceiverStream::new(rx)
+        .map(|item| {
+            Ok(match item {
+                FeedItem::Event(event) => {
+                    SseEvent::default().id(event.cursor.to_string()).data(event.json.as_str())
+                },
+                FeedItem::Closed { reason, cursor } => SseEvent::default()
+                    .event("close")
+                    .data(close_notice(reason, cursor).to_string()),
+            })
+        })
+        .take_until(async move { shutdown.cancelled().await });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::new().interval(state.keepalive)))
+}
+
+/// `GET /live/ws`: one text frame per event, `{"type":"event","cursor":..,"event":..}`,
+/// and `{"type":"close",..}` before the server closes the socket
+async fn ws(
+    State(state): State<LiveState>,
+    Query(query): Query<FeedQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, LiveError> {
+    let rx = state.open(&query, None).await?;
+    Ok(upgrade.on_upgrade(move |socket| ws_session(socket, rx, state)))
+}
+
+async fn ws_session(mut socket: WebSocket, mut rx: mpsc::Receiver<FeedItem>, state: LiveState) {
+    let mut keepalive = tokio::time::interval(state.keepalive);
+    keepalive.tick().await;
+
+    loop {
+        let frame = tokio::select! {
+            _ = state.shutdown.cancelled() => break,
+            _ = keepalive.tick() => WsMessage::Ping(Default::default()),
+            item = rx.recv() => match item {
+                Some(FeedItem::Event(event)) => WsMessage::Text(
+                    format!(
+                        r#"{{"type":"event","cursor":"{}","event":{}}}"#,
+                        event.cursor, event.json
+                    )
+                    .into(),
+                ),
+                Some(FeedItem::Closed { reason, cursor }) => {
+                    let mut notice = close_notice(reason, cursor);
+                    notice["type"] = json!("close");
+                    let _ = socket.send(WsMessage::Text(notice.to_string().into())).await;
+                    break;
+                },
+                None => break,
+            },
+            incoming = socket.recv() => match incoming {
+                // Clients have nothing to say beyond pongs; only watch for them leaving
+                Some(Ok(WsMessage::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => continue,
+            },
+        };
+
+        if socket.send(frame).await.is_err() {
+            return;
+        }
+    }
+
+    let _ = socket.send(WsMessage::Close(None)).await;
+}
+
+/// Build the `/live` router
+pub fn router(state: LiveState) -> Router {
+    Router::new().route("/live/sse", get(sse)).route("/live/ws", get(ws)).with_state(state)
+}
+
+/// Live feed service
+pub struct LiveFeedService {
+    config: Config,
+}
+
+impl LiveFeedService {
+    /// Create a new live feed service from the application configuration
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Service for LiveFeedService {
+    fn name(&self) -> &str {
+        "live"
+    }
+
+    async fn start<'a>(&'a self, context: ServiceContext<'a>) -> crate::app::Result<ServiceHandle> {
+        let live = &self.config.live;
+        let socket_addr = format!("{}:{}", live.bind_address, live.port)
+            .parse::<SocketAddr>()
+            .map_err(|e| ServiceError::Initialization(format!("Invalid socket address: {}", e)))?;
+
+        // Same key the producer publishes to and the consumer reads
+        let key = crate::types::get_stream_key(
+            &self.config.hub.url,
+            MessageType::Messages.to_stream_key(),
+        );
+        let feed = Arc::new(LiveFeed::new(
+            Arc::clone(&context.state.redis),
+            key,
+            live.buffer_size,
+            live.max_replay,
+        ));
+
+        let stream =
+            RedisStream::new(Arc::clone(&context.state.redis)).with_config(&self.config.stream);
+        let consumer = format!("live-{}", std::process::id());
+        let reader_handle =
+            tokio::spawn(Arc::clone(&feed).run(stream, live.consumer_group.clone(), consumer));
+
+        let state = LiveState {
+            feed,
+            db: context.state.database.clone().map(PostgresDatabaseClient::new),
+            connections: Arc::new(Semaphore::new(live.max_connections)),
+            max_filter_values: live.max_filter_values,
+            keepalive: Duration::from_secs(live.keepalive_secs.max(1)),
+            shutdown: CancellationToken::new(),
+        };
+        let shutdown = state.shutdown.clone();
+
+        let listener = tokio::net::TcpListener::bind(socket_addr).await.map_err(|e| {
+            ServiceError::Start(format!("Failed to bind live feed to {}: {}", socket_addr, e))
+        })?;
+        info!("Live feed listening on {}", socket_addr);
+
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let server_handle = tokio::spawn(async move {
+            let server = axum::serve(listener, router(state)).with_graceful_shutdown(async move {
+                let _ = stop_rx.await;
+                info!("Live feed shutting down");
+                // Open streams would otherwise hold the graceful shutdown forever
+                shutdown.cancel();
+            });
+
+            if let Err(e) = server.await {
+                error!("Live feed shutdown with error: {}", e);
+            }
+            reader_handle.abort();
+        });
+
+        Ok(ServiceHandle::new(stop_tx, server_handle))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::client::Redis;
+
+    fn state(max_connections: usize) -> LiveState {
+        LiveState {
+            feed: Arc::new(LiveFeed::new(Arc::new(Redis::empty()), "test".to_string(), 8, 100)),
+            db: None,
+            connections: Arc::new(Semaphore::new(max_connections)),
+            max_filter_values: 2,
+            keepalive: Duration::from_secs(15),
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filters_from_query() {
+        let query = FeedQuery {
+            fids: Some("3, 5".to_string()),
+            channels: Some("https://warpcast.com/~/channel/memes".to_string()),
+            types: Some("cast_add,reaction_add".to_string()),
+            ..Default::default()
+        };
+        let filters = state(1).filters(&query).await.unwrap();
+        assert_eq!(filters.fids, vec![3, 5]);
+        assert!(filters.mentioned_fids.is_empty());
+        assert_eq!(filters.parent_urls, vec!["https://warpcast.com/~/channel/memes"]);
+        assert_eq!(filters.message_types, vec!["cast_add", "reaction_add"]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_queries_are_rejected() {
+        let state = state(1);
+        for query in [
+            FeedQuery { fids: Some("1,2,3".to_string()), ..Default::default() },
+            FeedQuery { mentions: Some("x".to_string()), ..Default::default() },
+            FeedQuery { types: Some("cast".to_string()), ..Default::default() },
+            FeedQuery { channels: Some("memes".to_string()), ..Default::default() },
+            FeedQuery { cursor: Some("nope".to_string()), ..Default::default() },
+        ] {
+            assert!(
+                matches!(state.open(&query, None).await, Err(LiveError::BadRequest(_))),
+                "{:?} accepted",
+                query
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_limit() {
+        let state = state(1);
+        let first = state.open(&FeedQuery::default(), None).await.unwrap();
+        assert!(matches!(
+            state.open(&FeedQuery::default(), None).await,
+            Err(LiveError::TooManyConnections)
+        ));
+
+        drop(first);
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while state.open(&FeedQuery::default(), None).await.is_err() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("slot freed after disconnect");
+    }
+}
@@ -25,10 +25,13 @@ use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
 use crate::app::{Service, ServiceContext, ServiceHandle};
+use crate::config::Config;
 use crate::core::{
-    data_context::DataContext,
+    data_context::{DataContext, DataContextBuilder, ReadPolicy},
     types::{Fid, Message as FarcasterMessage},
 };
+use crate::database::providers::PostgresDatabaseClient;
+use crate::hub::providers::FarcasterHubClient;
 
 // NullDB implementation that satisfies the Database trait
 #[derive(Debug, Clone)]
@@ -70,6 +73,244 @@ impl crate::core::data_context::Database for NullDb {
     ) -> crate::core::data_context::Result<()> {
         Ok(())
     }
+
+    async fn get_fid_registration(
+        &self,
+        _fid: Fid,
+    ) -> crate::core::data_context::Result<Option<crate::core::types::FidRegistration>> {
+        Ok(None)
+    }
+
+    async fn get_fid_by_custody_address(
+        &self,
+        _address: &[u8],
+    ) -> crate::core::data_context::Result<Option<crate::core::types::FidRegistration>> {
+        Ok(None)
+    }
+
+    async fn get_signers_by_fid(
+        &self,
+        _fid: Fid,
+        _include_removed: bool,
+    ) -> crate::core::data_context::Result<Vec<crate::core::types::Signer>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_app_signer_stats(
+        &self,
+        _fid: Fid,
+    ) -> crate::core::data_context::Result<Vec<crate::core::types::AppSignerStats>> {
+        Err(crate::core::data_context::DataAccessError::Other(
+            "NullDb does not track signers".to_string(),
+        ))
+    }
+
+    async fn get_channel(
+        &self,
+        _channel: &str,
+    ) -> crate::core::data_context::Result<Option<crate::core::types::Channel>> {
+        Ok(None)
+    }
+
+    async fn get_channel_activity(
+        &self,
+        _url: &str,
+        _days: u32,
+    ) -> crate::core::data_context::Result<Vec<crate::core::types::ChannelActivity>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_user_data_by_fid(
+        &self,
+        _fid: Fid,
+        _limit: usize,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_user_data(
+        &self,
+        _fid: Fid,
+        _data_type: &str,
+    ) -> crate::core::data_context::Result<Option<FarcasterMessage>> {
+        Ok(None)
+    }
+
+    async fn get_username_proofs_by_fid(
+        &self,
+        _fid: Fid,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_username_proof_by_name(
+        &self,
+        _name: &str,
+    ) -> crate::core::data_context::Result<Option<crate::proto::UserNameProof>> {
+        Ok(None)
+    }
+
+    async fn get_verifications_by_fid(
+        &self,
+        _fid: Fid,
+        _limit: usize,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_verification(
+        &self,
+        _fid: Fid,
+        _address: &[u8],
+    ) -> crate::core::data_context::Result<Option<FarcasterMessage>> {
+        Ok(None)
+    }
+
+    async fn get_casts_by_fid(
+        &self,
+        _fid: Fid,
+        _limit: usize,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_cast(
+        &self,
+        _fid: Fid,
+        _hash: &[u8],
+    ) -> crate::core::data_context::Result<Option<FarcasterMessage>> {
+        Ok(None)
+    }
+
+    async fn get_casts_by_mention(
+        &self,
+        _fid: Fid,
+        _limit: usize,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_casts_by_parent(
+        &self,
+        _parent_fid: Fid,
+        _parent_hash: &[u8],
+        _limit: usize,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_casts_by_parent_url(
+        &self,
+        _parent_url: &str,
+        _limit: usize,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_all_casts_by_fid(
+        &self,
+        _fid: Fid,
+        _limit: usize,
+        _start_time: Option<u64>,
+        _end_time: Option<u64>,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_reaction(
+        &self,
+        _fid: Fid,
+        _reaction_type: u8,
+        _target_cast_fid: Option<Fid>,
+        _target_cast_hash: Option<&[u8]>,
+        _target_url: Option<&str>,
+    ) -> crate::core::data_context::Result<Option<FarcasterMessage>> {
+        Ok(None)
+    }
+
+    async fn get_reactions_by_fid(
+        &self,
+        _fid: Fid,
+        _reaction_type: Option<u8>,
+        _limit: usize,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_reactions_by_target(
+        &self,
+        _target_cast_fid: Option<Fid>,
+        _target_cast_hash: Option<&[u8]>,
+        _target_url: Option<&str>,
+        _reaction_type: Option<u8>,
+        _limit: usize,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_all_reactions_by_fid(
+        &self,
+        _fid: Fid,
+        _limit: usize,
+        _start_time: Option<u64>,
+        _end_time: Option<u64>,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_all_verification_messages_by_fid(
+        &self,
+        _fid: Fid,
+        _limit: usize,
+        _start_time: Option<u64>,
+        _end_time: Option<u64>,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_link(
+        &self,
+        _fid: Fid,
+        _link_type: &str,
+        _target_fid: Fid,
+    ) -> crate::core::data_context::Result<Option<FarcasterMessage>> {
+        Ok(None)
+    }
+
+    async fn get_links_by_fid(
+        &self,
+        _fid: Fid,
+        _link_type: Option<&str>,
+        _limit: usize,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_links_by_target(
+        &self,
+        _target_fid: Fid,
+        _link_type: Option<&str>,
+        _limit: usize,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_link_compact_state_by_fid(
+        &self,
+        _fid: Fid,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_all_links_by_fid(
+        &self,
+        _fid: Fid,
+        _limit: usize,
+        _start_time: Option<u64>,
+        _end_time: Option<u64>,
+    ) -> crate::core::data_context::Result<Vec<FarcasterMessage>> {
+        Ok(Vec::new())
+    }
 }
 
 // Simple MooCow service to demonstrate MCP functionality
@@ -269,29 +510,32 @@ where
     }
 }
 
-/// MCP Service that integrates with the App's service lifecycle
-pub struct McpService {
-    bind_address: String,
-    port: u16,
+/// Build the data context the MCP tools read through.
+///
+/// Without a database every read goes to the Hub, whatever the configured policy.
+pub fn build_data_context(
+    config: &Config,
+    hub_client: FarcasterHubClient,
+    database: Option<Arc<crate::database::client::Database>>,
+) -> DataContext<PostgresDatabaseClient, FarcasterHubClient> {
+    let read_policy = if database.is_some() { config.mcp.read_policy } else { ReadPolicy::HubOnly };
+    let mut builder = DataContextBuilder::new().with_hub_client(hub_client);
+    if let Some(database) = database {
+        builder = builder.with_database(PostgresDatabaseClient::new(database));
+    }
+
+    builder.with_read_policy(read_policy).build()
 }
 
-impl Default for McpService {
-    fn default() -> Self {
-        Self { bind_address: "127.0.0.1".to_string(), port: 8000 }
-    }
+/// MCP Service that integrates with the App's service lifecycle
+pub struct McpService {
+    config: Config,
 }
 
 impl McpService {
-    /// Create a new MCP service with default settings
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    /// Configure the service with custom settings
-    pub fn configure(mut self, bind_address: String, port: u16) -> Self {
-        self.bind_address = bind_address;
-        self.port = port;
-        self
+    /// Create a new MCP service from the application configuration
+    pub fn new(config: Config) -> Self {
+        Self { config }
     }
 }
 
@@ -303,15 +547,16 @@ impl Service for McpService {
 
     async fn start<'a>(&'a self, context: ServiceContext<'a>) -> crate::app::Result<ServiceHandle> {
         // Create socket address from configuration
-        let socket_addr =
-            format!("{}:{}", self.bind_address, self.port).parse::<SocketAddr>().map_err(|e| {
+        let socket_addr = format!("{}:{}", self.config.mcp.bind_address, self.config.mcp.port)
+            .parse::<SocketAddr>()
+            .map_err(|e| {
                 crate::app::ServiceError::Initialization(format!("Invalid socket address: {}", e))
             })?;
 
         info!("Starting MCP service on {}", socket_addr);
 
         // Create the required clients for the DataContext
-        let hub_config = context.config.hub.clone();
+        let hub_config = self.config.hub.clone();
 
         // Create Hub client
         let mut hub = crate::hub::client::Hub::new(Arc::new(hub_config)).map_err(|e| {
@@ -324,14 +569,12 @@ impl Service for McpService {
         }
 
         // Create Hub client for data context
-        let hub_client = crate::hub::providers::FarcasterHubClient::new(Arc::new(Mutex::new(hub)));
-
-        // Create a data context with a NullDb and the hub client
-        let data_context: DataContext<NullDb, _> =
-            crate::core::data_context::DataContextBuilder::new()
-                .with_database(NullDb)
-                .with_hub_client(hub_client)
-                .build();
+        let hub_client = FarcasterHubClient::new(Arc::new(Mutex::new(hub)));
+
+        // Serve reads from the consumer's database where the policy allows
+        let data_context =
+            build_data_context(&self.config, hub_client, context.state.database.clone());
+        info!("MCP reads use the {} policy", data_context.read_policy());
 
         // Create a cancellation token for the service
         let cancellation_token = CancellationToken::new();
@@ -956,6 +956,216 @@ mod tests {
         ) -> crate::core::data_context::Result<()> {
             Ok(())
         }
+        async fn get_fid_registration(
+            &self,
+            _fid: Fid,
+        ) -> crate::core::data_context::Result<Option<crate::core::types::FidRegistration>>
+        {
+            Ok(None)
+        }
+        async fn get_fid_by_custody_address(
+            &self,
+            _address: &[u8],
+        ) -> crate::core::data_context::Result<Option<crate::core::types::FidRegistration>>
+        {
+            Ok(None)
+        }
+        async fn get_signers_by_fid(
+            &self,
+            _fid: Fid,
+            _include_removed: bool,
+        ) -> crate::core::data_context::Result<Vec<crate::core::types::Signer>> {
+            Ok(vec![])
+        }
+        async fn get_app_signer_stats(
+            &self,
+            _fid: Fid,
+        ) -> crate::core::data_context::Result<Vec<crate::core::types::AppSignerStats>> {
+            Ok(vec![])
+        }
+        async fn get_channel(
+            &self,
+            _channel: &str,
+        ) -> crate::core::data_context::Result<Option<crate::core::types::Channel>> {
+            Ok(None)
+        }
+        async fn get_channel_activity(
+            &self,
+            _url: &str,
+            _days: u32,
+        ) -> crate::core::data_context::Result<Vec<crate::core::types::ChannelActivity>> {
+            Ok(vec![])
+        }
+        async fn get_user_data_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_user_data(
+            &self,
+            _fid: Fid,
+            _data_type: &str,
+        ) -> crate::core::data_context::Result<Option<Message>> {
+            Ok(None)
+        }
+        async fn get_username_proofs_by_fid(
+            &self,
+            _fid: Fid,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_username_proof_by_name(
+            &self,
+            _name: &str,
+        ) -> crate::core::data_context::Result<Option<crate::proto::UserNameProof>> {
+            Ok(None)
+        }
+        async fn get_verifications_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_verification(
+            &self,
+            _fid: Fid,
+            _address: &[u8],
+        ) -> crate::core::data_context::Result<Option<Message>> {
+            Ok(None)
+        }
+        async fn get_casts_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_cast(
+            &self,
+            _fid: Fid,
+            _hash: &[u8],
+        ) -> crate::core::data_context::Result<Option<Message>> {
+            Ok(None)
+        }
+        async fn get_casts_by_mention(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_casts_by_parent(
+            &self,
+            _parent_fid: Fid,
+            _parent_hash: &[u8],
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_casts_by_parent_url(
+            &self,
+            _parent_url: &str,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_all_casts_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+            _start_time: Option<u64>,
+            _end_time: Option<u64>,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_reaction(
+            &self,
+            _fid: Fid,
+            _reaction_type: u8,
+            _target_cast_fid: Option<Fid>,
+            _target_cast_hash: Option<&[u8]>,
+            _target_url: Option<&str>,
+        ) -> crate::core::data_context::Result<Option<Message>> {
+            Ok(None)
+        }
+        async fn get_reactions_by_fid(
+            &self,
+            _fid: Fid,
+            _reaction_type: Option<u8>,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_reactions_by_target(
+            &self,
+            _target_cast_fid: Option<Fid>,
+            _target_cast_hash: Option<&[u8]>,
+            _target_url: Option<&str>,
+            _reaction_type: Option<u8>,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_all_reactions_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+            _start_time: Option<u64>,
+            _end_time: Option<u64>,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_all_verification_messages_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+            _start_time: Option<u64>,
+            _end_time: Option<u64>,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_link(
+            &self,
+            _fid: Fid,
+            _link_type: &str,
+            _target_fid: Fid,
+        ) -> crate::core::data_context::Result<Option<Message>> {
+            Ok(None)
+        }
+        async fn get_links_by_fid(
+            &self,
+            _fid: Fid,
+            _link_type: Option<&str>,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_links_by_target(
+            &self,
+            _target_fid: Fid,
+            _link_type: Option<&str>,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_link_compact_state_by_fid(
+            &self,
+            _fid: Fid,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+        async fn get_all_links_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+            _start_time: Option<u64>,
+            _end_time: Option<u64>,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
     }
 
     #[derive(Clone, Debug)]
@@ -1133,6 +1343,24 @@ mod tests {
         ) -> crate::core::data_context::Result<Vec<Message>> {
             Ok(vec![])
         }
+        async fn get_id_registry_event(
+            &self,
+            _fid: Fid,
+        ) -> crate::core::data_context::Result<Option<crate::proto::OnChainEvent>> {
+            Ok(None)
+        }
+        async fn get_id_registry_event_by_address(
+            &self,
+            _address: &[u8],
+        ) -> crate::core::data_context::Result<Option<crate::proto::OnChainEvent>> {
+            Ok(None)
+        }
+        async fn get_onchain_signers_by_fid(
+            &self,
+            _fid: Fid,
+        ) -> crate::core::data_context::Result<Vec<crate::proto::OnChainEvent>> {
+            Ok(vec![])
+        }
     }
 
     type TestService = WaypointMcpService<MockDb, MockHub>;
@@ -0,0 +1,87 @@
+//! MCP handlers for channel feeds and stats
+
+use crate::services::mcp::base::WaypointMcpService;
+
+use prost::Message as ProstMessage;
+
+impl<DB, HC> WaypointMcpService<DB, HC>
+where
+    DB: crate::core::data_context::Database + Clone + Send + Sync + 'static,
+    HC: crate::core::data_context::HubClient + Clone + Send + Sync + 'static,
+{
+    /// Get the newest top-level casts in a channel
+    pub async fn do_get_channel_feed(&self, channel: &str, limit: usize) -> String {
+        tracing::info!("MCP: Fetching channel feed: {}", channel);
+
+        match self.data_context.get_channel_feed(channel, limit).await {
+            Ok(messages) => {
+                let casts: Vec<serde_json::Value> = messages
+                    .iter()
+                    .filter_map(|message| {
+                        let msg_data: crate::proto::MessageData =
+                            ProstMessage::decode(&*message.payload).ok()?;
+                        super::utils::process_cast_message(message, &msg_data)
+                            .map(serde_json::Value::Object)
+                    })
+                    .collect();
+
+                let result = serde_json::json!({
+                    "channel": channel,
+                    "count": casts.len(),
+                    "casts": casts
+                });
+
+                serde_json::to_string_pretty(&result)
+                    .unwrap_or_else(|_| format!("Error formatting feed for channel {}", channel))
+            },
+            Err(e) => format!("Error fetching channel feed: {}", e),
+        }
+    }
+
+    /// Get totals and daily activity for a channel
+    pub async fn do_get_channel_stats(&self, channel: &str, days: u32) -> String {
+        tracing::info!("MCP: Fetching channel stats: {}", channel);
+
+        let result = match self.data_context.get_channel_stats(channel, days).await {
+            Ok(Some((found, activity))) => {
+                let activity: Vec<serde_json::Value> = activity
+                    .iter()
+                    .map(|day| {
+                        serde_json::json!({
+                            "day": day.day,
+                            "cast_count": day.cast_count,
+                            "reply_count": day.reply_count,
+                        })
+                    })
+                    .collect();
+
+                serde_json::json!({
+                    "url": found.url,
+                    "channel_id": found.channel_id,
+                    "name": found.name,
+                    "description": found.description,
+                    "lead_fid": found.lead_fid,
+                    "first_cast_at": found.first_cast_at,
+                    "last_cast_at": found.last_cast_at,
+                    "cast_count": found.cast_count,
+                    "reply_count": found.reply_count,
+                    "activity": activity,
+                    "found": true
+                })
+            },
+            Ok(None) => serde_json::json!({
+                "channel": channel,
+                "found": false,
+                "error": "Channel not found"
+            }),
+            Err(e) => serde_json::json!({
+                "channel": channel,
+                "found": false,
+                "error": format!("Error: {}", e)
+            }),
+        };
+
+        serde_json::to_string_pretty(&result)
+            .unwrap_or_else(|_| format!("Error formatting stats for channel {}", channel))
+    }
+}
@@ -13,6 +13,11 @@ pub(crate) fn default_link_type() -> String {
     "follow".to_string()
 }
 
+/// Default window for channel activity
+pub(crate) fn default_activity_days() -> u32 {
+    30
+}
+
 /// Request for a user by FID
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct UserByFidRequest {
@@ -228,6 +233,43 @@ pub struct GetUsernameProofRequest {
     pub name: String,
 }
 
+/// Request to look up a FID by custody address
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetFidByCustodyAddressRequest {
+    #[schemars(description = "Custody address in hex format (with or without 0x prefix)")]
+    pub address: String,
+}
+
+/// Request for a FID's app keys
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetSignersRequest {
+    #[schemars(description = "Farcaster user ID")]
+    pub fid: u64,
+    #[schemars(description = "Whether to include removed and admin-reset keys")]
+    #[serde(default = "default_false")]
+    pub include_removed: bool,
+}
+
+/// Request for a channel's feed
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetChannelFeedRequest {
+    #[schemars(description = "Channel URL, or channel ID if channel metadata has been imported")]
+    pub channel: String,
+    #[schemars(description = "Maximum number of results to return")]
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+/// Request for a channel's stats
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetChannelStatsRequest {
+    #[schemars(description = "Channel URL, or channel ID if channel metadata has been imported")]
+    pub channel: String,
+    #[schemars(description = "Number of days of daily activity to return")]
+    #[serde(default = "default_activity_days")]
+    pub days: u32,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetConversationRequest {
     #[schemars(description = "Farcaster user ID of the cast author")]
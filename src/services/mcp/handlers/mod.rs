@@ -1,16 +1,19 @@
 //! MCP API handlers for Waypoint
 
 mod casts;
+mod channels;
 mod common;
 mod links;
 mod reactions;
+mod registry;
 mod user_data;
-mod utils;
+pub(crate) mod utils;
 
 use std::sync::Arc;
 
 use crate::core::types::Fid;
-use crate::services::mcp::base::{NullDb, WaypointMcpService};
+use crate::database::providers::PostgresDatabaseClient;
+use crate::services::mcp::base::WaypointMcpService;
 
 use rmcp::{
     ErrorData as McpError, RoleServer, ServerHandler,
@@ -31,14 +34,18 @@ pub const MCP_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::V_2025_03_26;
 // Non-generic wrapper for WaypointMcpService to use with RMCP macros
 #[derive(Clone)]
 pub struct WaypointMcpTools {
-    service: Arc<WaypointMcpService<NullDb, crate::hub::providers::FarcasterHubClient>>,
+    service:
+        Arc<WaypointMcpService<PostgresDatabaseClient, crate::hub::providers::FarcasterHubClient>>,
     tool_router: ToolRouter<WaypointMcpTools>,
     prompt_router: PromptRouter<WaypointMcpTools>,
 }
 
 impl WaypointMcpTools {
     pub fn new(
-        service: WaypointMcpService<NullDb, crate::hub::providers::FarcasterHubClient>,
+        service: WaypointMcpService<
+            PostgresDatabaseClient,
+            crate::hub::providers::FarcasterHubClient,
+        >,
     ) -> Self {
         Self {
             service: Arc::new(service),
@@ -497,6 +504,91 @@ impl WaypointMcpTools {
         let result = self.service.do_get_username_proofs_by_fid(fid).await;
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
+
+    // FID registry APIs
+    #[tool(
+        description = "Get the custody address, recovery address and registration time for a FID",
+        annotations(read_only_hint = true)
+    )]
+    async fn get_fid_registration(
+        &self,
+        Parameters(common::FidRequest { fid }): Parameters<common::FidRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let fid = Fid::from(fid);
+        let result = self.service.do_get_fid_registration(fid).await;
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Find the FID currently owned by a custody address",
+        annotations(read_only_hint = true)
+    )]
+    async fn get_fid_by_custody_address(
+        &self,
+        Parameters(common::GetFidByCustodyAddressRequest { address }): Parameters<
+            common::GetFidByCustodyAddressRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        let result = self.service.do_get_fid_by_custody_address(&address).await;
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    // Signer APIs
+    #[tool(
+        description = "Get app keys (signers) for a Farcaster user, including the app FID that requested each key",
+        annotations(read_only_hint = true)
+    )]
+    async fn get_signers_by_fid(
+        &self,
+        Parameters(common::GetSignersRequest { fid, include_removed }): Parameters<
+            common::GetSignersRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        let fid = Fid::from(fid);
+        let result = self.service.do_get_signers_by_fid(fid, include_removed).await;
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Get per-app signer and message counts for a Farcaster user",
+        annotations(read_only_hint = true)
+    )]
+    async fn get_signer_apps_by_fid(
+        &self,
+        Parameters(common::FidRequest { fid }): Parameters<common::FidRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let fid = Fid::from(fid);
+        let result = self.service.do_get_signer_apps_by_fid(fid).await;
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Get the newest top-level casts in a channel",
+        annotations(read_only_hint = true)
+    )]
+    async fn get_channel_feed(
+        &self,
+        Parameters(common::GetChannelFeedRequest { channel, limit }): Parameters<
+            common::GetChannelFeedRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        let result = self.service.do_get_channel_feed(&channel, limit).await;
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Get cast totals and daily activity for a channel",
+        annotations(read_only_hint = true)
+    )]
+    async fn get_channel_stats(
+        &self,
+        Parameters(common::GetChannelStatsRequest { channel, days }): Parameters<
+            common::GetChannelStatsRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        let result = self.service.do_get_channel_stats(&channel, days).await;
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
 }
 
 #[prompt_router]
@@ -662,6 +754,18 @@ impl ServerHandler for WaypointMcpTools {
                 utils::WaypointResource::UsernameProofsByFid { fid } => {
                     self.service.do_get_username_proofs_by_fid(Fid::from(fid)).await
                 },
+                utils::WaypointResource::FidRegistration { fid } => {
+                    self.service.do_get_fid_registration(Fid::from(fid)).await
+                },
+                utils::WaypointResource::FidByCustodyAddress { address } => {
+                    self.service.do_get_fid_by_custody_address(&address).await
+                },
+                utils::WaypointResource::SignersByFid { fid } => {
+                    self.service.do_get_signers_by_fid(Fid::from(fid), false).await
+                },
+                utils::WaypointResource::SignerAppsByFid { fid } => {
+                    self.service.do_get_signer_apps_by_fid(Fid::from(fid)).await
+                },
             };
 
             return Ok(Self::resource_json_contents(&uri, result));
@@ -797,6 +901,28 @@ impl ServerHandler for WaypointMcpTools {
                     "username-proofs-by-fid",
                     "Username proofs (fname and ENS registrations) for a FID",
                 ),
+                // FID registry
+                Self::create_resource_template_json(
+                    "waypoint://fids/{fid}",
+                    "fid-registration",
+                    "Custody and recovery addresses for a FID",
+                ),
+                Self::create_resource_template_json(
+                    "waypoint://fids/by-custody/{address}",
+                    "fid-by-custody-address",
+                    "FID currently owned by a custody address",
+                ),
+                // Signers
+                Self::create_resource_template_json(
+                    "waypoint://signers/{fid}",
+                    "signers-by-fid",
+                    "Active app keys for a FID",
+                ),
+                Self::create_resource_template_json(
+                    "waypoint://signers/apps/{fid}",
+                    "signer-apps-by-fid",
+                    "Signer and message counts per app for a FID",
+                ),
             ],
             next_cursor: None,
             meta: None,
@@ -0,0 +1,130 @@
+//! MCP handlers for FID registry (IdRegistry) and signer (KeyRegistry) lookups
+
+use crate::core::types::{Fid, Signer};
+use crate::services::mcp::base::WaypointMcpService;
+
+impl<DB, HC> WaypointMcpService<DB, HC>
+where
+    DB: crate::core::data_context::Database + Clone + Send + Sync + 'static,
+    HC: crate::core::data_context::HubClient + Clone + Send + Sync + 'static,
+{
+    /// Get the IdRegistry state for a FID
+    pub async fn do_get_fid_registration(&self, fid: Fid) -> String {
+        tracing::info!("MCP: Fetching registry state for FID: {}", fid);
+
+        let result = match self.data_context.get_fid_registration(fid).await {
+            Ok(Some(registration)) => {
+                let mut value = super::utils::process_fid_registration(&registration);
+                value["found"] = serde_json::json!(true);
+                value
+            },
+            Ok(None) => serde_json::json!({
+                "fid": fid.value(),
+                "found": false,
+                "error": "FID not registered"
+            }),
+            Err(e) => serde_json::json!({
+                "fid": fid.value(),
+                "found": false,
+                "error": format!("Error: {}", e)
+            }),
+        };
+
+        serde_json::to_string_pretty(&result)
+            .unwrap_or_else(|_| format!("Error formatting registry state for FID {}", fid))
+    }
+
+    /// Look up the FID currently held by a custody address
+    pub async fn do_get_fid_by_custody_address(&self, address: &str) -> String {
+        tracing::info!("MCP: Fetching FID for custody address: {}", address);
+
+        let address_bytes = match super::utils::parse_hash_bytes(address) {
+            Ok(bytes) => bytes,
+            Err(e) => return format!("Invalid address: {}", e),
+        };
+
+        let result = match self.data_context.get_fid_by_custody_address(&address_bytes).await {
+            Ok(Some(registration)) => {
+                let mut value = super::utils::process_fid_registration(&registration);
+                value["found"] = serde_json::json!(true);
+                value
+            },
+            Ok(None) => serde_json::json!({
+                "custody_address": address,
+                "found": false,
+                "error": "No FID found for custody address"
+            }),
+            Err(e) => serde_json::json!({
+                "custody_address": address,
+                "found": false,
+                "error": format!("Error: {}", e)
+            }),
+        };
+
+        serde_json::to_string_pretty(&result)
+            .unwrap_or_else(|_| format!("Error formatting FID for custody address {}", address))
+    }
+
+    fn signer_to_json(signer: &Signer) -> serde_json::Value {
+        serde_json::json!({
+            "key": format!("0x{}", hex::encode(&signer.key)),
+            "key_type": signer.key_type,
+            "request_fid": signer.request_fid,
+            "status": signer.status,
+            "added_at": signer.added_at,
+            "removed_at": signer.removed_at,
+            "migrated_at": signer.migrated_at,
+        })
+    }
+
+    /// Get app keys (signers) for a FID
+    pub async fn do_get_signers_by_fid(&self, fid: Fid, include_removed: bool) -> String {
+        tracing::info!("MCP: Fetching signers for FID: {}", fid);
+
+        match self.data_context.get_signers_by_fid(fid, include_removed).await {
+            Ok(signers) => {
+                let signers: Vec<serde_json::Value> =
+                    signers.iter().map(Self::signer_to_json).collect();
+                let result = serde_json::json!({
+                    "fid": fid.value(),
+                    "count": signers.len(),
+                    "signers": signers
+                });
+
+                serde_json::to_string_pretty(&result)
+                    .unwrap_or_else(|_| format!("Error formatting signers for FID {}", fid))
+            },
+            Err(e) => format!("Error fetching signers: {}", e),
+        }
+    }
+
+    /// Get signer and message totals per app for a FID
+    pub async fn do_get_signer_apps_by_fid(&self, fid: Fid) -> String {
+        tracing::info!("MCP: Fetching signer apps for FID: {}", fid);
+
+        match self.data_context.get_app_signer_stats(fid).await {
+            Ok(stats) => {
+                let apps: Vec<serde_json::Value> = stats
+                    .iter()
+                    .map(|app| {
+                        serde_json::json!({
+                            "request_fid": app.request_fid,
+                            "signer_count": app.signer_count,
+                            "active_signer_count": app.active_signer_count,
+                            "message_count": app.message_count,
+                        })
+                    })
+                    .collect();
+                let result = serde_json::json!({
+                    "fid": fid.value(),
+                    "count": apps.len(),
+                    "apps": apps
+                });
+
+                serde_json::to_string_pretty(&result)
+                    .unwrap_or_else(|_| format!("Error formatting signer apps for FID {}", fid))
+            },
+            Err(e) => format!("Error fetching signer apps: {}", e),
+        }
+    }
+}
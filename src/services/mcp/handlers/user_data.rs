@@ -1,9 +1,8 @@
 //! MCP handlers for User Data operations
 
-use crate::core::types::{Fid, Message, MessageType};
+use crate::core::types::Fid;
 use crate::services::mcp::base::WaypointMcpService;
-
-use prost::Message as ProstMessage;
+use crate::services::mcp::handlers::utils;
 
 impl<DB, HC> WaypointMcpService<DB, HC>
 where
@@ -18,41 +17,7 @@ where
                     return format!("No user data found for FID {}", fid);
                 }
 
-                let mut profile = serde_json::Map::new();
-                profile.insert(
-                    "fid".to_string(),
-                    serde_json::Value::Number(serde_json::Number::from(fid.value())),
-                );
-
-                for message in messages {
-                    if message.message_type != MessageType::UserData {
-                        continue;
-                    }
-
-                    if let Ok(msg_data) =
-                        <crate::proto::MessageData as ProstMessage>::decode(&*message.payload)
-                        && let Some(crate::proto::message_data::Body::UserDataBody(user_data)) =
-                            msg_data.body
-                    {
-                        let field_name = match user_data.r#type {
-                            1 => "pfp",
-                            2 => "display_name",
-                            3 => "bio",
-                            5 => "url",
-                            6 => "username",
-                            7 => "location",
-                            8 => "twitter",
-                            9 => "github",
-                            _ => continue,
-                        };
-
-                        profile.insert(
-                            field_name.to_string(),
-                            serde_json::Value::String(user_data.value),
-                        );
-                    }
-                }
-
+                let profile = utils::process_user_data_messages(fid, &messages);
                 serde_json::to_string_pretty(&profile)
                     .unwrap_or_else(|_| format!("Error formatting user data for FID {}", fid))
             },
@@ -60,78 +25,6 @@ where
         }
     }
 
-    fn protocol_name(protocol: i32) -> &'static str {
-        match protocol {
-            0 => "ethereum",
-            1 => "solana",
-            _ => "unknown",
-        }
-    }
-
-    fn verification_type_name(verification_type: u32) -> &'static str {
-        match verification_type {
-            0 => "eoa",
-            1 => "contract",
-            _ => "unknown",
-        }
-    }
-
-    fn proof_type_name(proof_type: i32) -> &'static str {
-        match proof_type {
-            1 => "fname",
-            2 => "ens_l1",
-            3 => "basename",
-            _ => "unknown",
-        }
-    }
-
-    fn username_proof_to_json(proof: &crate::proto::UserNameProof) -> serde_json::Value {
-        serde_json::json!({
-            "name": String::from_utf8_lossy(&proof.name),
-            "type": Self::proof_type_name(proof.r#type),
-            "fid": proof.fid,
-            "timestamp": proof.timestamp,
-            "owner": format!("0x{}", hex::encode(&proof.owner)),
-        })
-    }
-
-    fn verification_message_to_json(message: &Message) -> Option<serde_json::Value> {
-        if message.message_type != MessageType::Verification {
-            return None;
-        }
-
-        let msg_data: crate::proto::MessageData = ProstMessage::decode(&*message.payload).ok()?;
-
-        match msg_data.body {
-            Some(crate::proto::message_data::Body::VerificationAddAddressBody(verification)) => {
-                let mut value = serde_json::json!({
-                    "fid": msg_data.fid,
-                    "address": format!("0x{}", hex::encode(&verification.address)),
-                    "protocol": Self::protocol_name(verification.protocol),
-                    "type": Self::verification_type_name(verification.verification_type),
-                    "action": "add",
-                    "timestamp": msg_data.timestamp,
-                });
-
-                if verification.chain_id > 0 {
-                    value["chain_id"] = serde_json::json!(verification.chain_id);
-                }
-
-                Some(value)
-            },
-            Some(crate::proto::message_data::Body::VerificationRemoveBody(verification)) => {
-                Some(serde_json::json!({
-                    "fid": msg_data.fid,
-                    "address": format!("0x{}", hex::encode(&verification.address)),
-                    "protocol": Self::protocol_name(verification.protocol),
-                    "action": "remove",
-                    "timestamp": msg_data.timestamp,
-                }))
-            },
-            _ => None,
-        }
-    }
-
     /// Get user verifications by FID
     pub async fn do_get_verifications_by_fid(&self, fid: Fid, limit: usize) -> String {
         tracing::info!("MCP: Fetching verifications for FID: {}", fid);
@@ -143,7 +36,7 @@ where
                 }
 
                 let verifications: Vec<serde_json::Value> =
-                    messages.iter().filter_map(Self::verification_message_to_json).collect();
+                    messages.iter().filter_map(utils::process_verification_message).collect();
 
                 let result = serde_json::json!({
                     "fid": fid.value(),
@@ -173,7 +66,7 @@ where
         };
 
         match self.data_context.get_verification(fid, &address_bytes).await {
-            Ok(Some(message)) => match Self::verification_message_to_json(&message) {
+            Ok(Some(message)) => match utils::process_verification_message(&message) {
                 Some(verification) => {
                     let result = serde_json::json!({
                         "fid": fid.value(),
@@ -226,7 +119,7 @@ where
         {
             Ok(messages) => {
                 let verifications: Vec<serde_json::Value> =
-                    messages.iter().filter_map(Self::verification_message_to_json).collect();
+                    messages.iter().filter_map(utils::process_verification_message).collect();
 
                 if verifications.is_empty() {
                     let time_range = match (start_time, end_time) {
@@ -313,56 +206,11 @@ where
                             );
                         }
 
-                        // Create a structured user profile from the messages
-                        let mut profile = serde_json::Map::new();
-
-                        // Add the FID and username to the profile
-                        profile.insert(
-                            "fid".to_string(),
-                            serde_json::Value::Number(serde_json::Number::from(fid.value())),
-                        );
-
-                        profile.insert(
-                            "username".to_string(),
-                            serde_json::Value::String(username.to_string()),
-                        );
-
-                        // Process each message to extract user data
-                        for message in messages {
-                            if message.message_type != crate::core::types::MessageType::UserData {
-                                continue;
-                            }
-
-                            // Try to decode the message payload as MessageData
-                            if let Ok(data) = prost::Message::decode(&*message.payload) {
-                                let msg_data: crate::proto::MessageData = data;
-
-                                // Extract the user_data_body if present
-                                if let Some(crate::proto::message_data::Body::UserDataBody(
-                                    user_data,
-                                )) = msg_data.body
-                                {
-                                    // Map user data type to field name
-                                    let field_name = match user_data.r#type {
-                                        1 => "pfp",          // USER_DATA_TYPE_PFP
-                                        2 => "display_name", // USER_DATA_TYPE_DISPLAY
-                                        3 => "bio",          // USER_DATA_TYPE_BIO
-                                        5 => "url",          // USER_DATA_TYPE_URL
-                                        6 => "username", // USER_DATA_TYPE_USERNAME (already set above, but include for completeness)
-                                        7 => "location", // USER_DATA_TYPE_LOCATION
-                                        8 => "twitter",  // USER_DATA_TYPE_TWITTER
-                                        9 => "github",   // USER_DATA_TYPE_GITHUB
-                                        _ => continue,   // Unknown type
-                                    };
-
-                                    // Add to the profile
-                                    profile.insert(
-                                        field_name.to_string(),
-                                        serde_json::Value::String(user_data.value),
-                                    );
-                                }
-                            }
-                        }
+                        // The username from user data takes precedence over the one looked up
+                        let mut profile = utils::process_user_data_messages(fid, &messages);
+                        profile
+                            .entry("username")
+                            .or_insert_with(|| serde_json::Value::String(username.to_string()));
 
                         // Convert the profile to a JSON string
                         serde_json::to_string_pretty(&profile).unwrap_or_else(|_| {
@@ -413,7 +261,7 @@ where
                     .filter_map(|message| {
                         serde_json::from_slice::<crate::proto::UserNameProof>(&message.payload)
                             .ok()
-                            .map(|proof| Self::username_proof_to_json(&proof))
+                            .map(|proof| utils::process_username_proof(&proof))
                     })
                     .collect();
 
@@ -436,7 +284,7 @@ where
 
         match self.data_context.get_username_proof_by_name(name).await {
             Ok(Some(proof)) => {
-                let mut result = Self::username_proof_to_json(&proof);
+                let mut result = utils::process_username_proof(&proof);
                 result["found"] = serde_json::json!(true);
 
                 serde_json::to_string_pretty(&result)
@@ -460,9 +308,10 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::data_context::{DataAccessError, DataContextBuilder};
-    use crate::core::types::MessageId;
+    use crate::core::data_context::{DataAccessError, DataContextBuilder, ReadPolicy};
+    use crate::core::types::{Message, MessageId, MessageType};
     use async_trait::async_trait;
+    use prost::Message as ProstMessage;
 
     #[derive(Clone, Debug)]
     struct MockDb;
@@ -498,6 +347,244 @@ mod tests {
         ) -> crate::core::data_context::Result<()> {
             Ok(())
         }
+
+        async fn get_fid_registration(
+            &self,
+            _fid: Fid,
+        ) -> crate::core::data_context::Result<Option<crate::core::types::FidRegistration>>
+        {
+            Ok(None)
+        }
+
+        async fn get_fid_by_custody_address(
+            &self,
+            _address: &[u8],
+        ) -> crate::core::data_context::Result<Option<crate::core::types::FidRegistration>>
+        {
+            Ok(None)
+        }
+
+        async fn get_signers_by_fid(
+            &self,
+            _fid: Fid,
+            _include_removed: bool,
+        ) -> crate::core::data_context::Result<Vec<crate::core::types::Signer>> {
+            Ok(vec![])
+        }
+
+        async fn get_app_signer_stats(
+            &self,
+            _fid: Fid,
+        ) -> crate::core::data_context::Result<Vec<crate::core::types::AppSignerStats>> {
+            Ok(vec![])
+        }
+
+        async fn get_channel(
+            &self,
+            _channel: &str,
+        ) -> crate::core::data_context::Result<Option<crate::core::types::Channel>> {
+            Ok(None)
+        }
+
+        async fn get_channel_activity(
+            &self,
+            _url: &str,
+            _days: u32,
+        ) -> crate::core::data_context::Result<Vec<crate::core::types::ChannelActivity>> {
+            Ok(vec![])
+        }
+
+        async fn get_user_data_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_user_data(
+            &self,
+            _fid: Fid,
+            _data_type: &str,
+        ) -> crate::core::data_context::Result<Option<Message>> {
+            Ok(None)
+        }
+
+        async fn get_username_proofs_by_fid(
+            &self,
+            _fid: Fid,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_username_proof_by_name(
+            &self,
+            _name: &str,
+        ) -> crate::core::data_context::Result<Option<crate::proto::UserNameProof>> {
+            Ok(None)
+        }
+
+        async fn get_verifications_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_verification(
+            &self,
+            _fid: Fid,
+            _address: &[u8],
+        ) -> crate::core::data_context::Result<Option<Message>> {
+            Ok(None)
+        }
+
+        async fn get_casts_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_cast(
+            &self,
+            _fid: Fid,
+            _hash: &[u8],
+        ) -> crate::core::data_context::Result<Option<Message>> {
+            Ok(None)
+        }
+
+        async fn get_casts_by_mention(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_casts_by_parent(
+            &self,
+            _parent_fid: Fid,
+            _parent_hash: &[u8],
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_casts_by_parent_url(
+            &self,
+            _parent_url: &str,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_all_casts_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+            _start_time: Option<u64>,
+            _end_time: Option<u64>,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_reaction(
+            &self,
+            _fid: Fid,
+            _reaction_type: u8,
+            _target_cast_fid: Option<Fid>,
+            _target_cast_hash: Option<&[u8]>,
+            _target_url: Option<&str>,
+        ) -> crate::core::data_context::Result<Option<Message>> {
+            Ok(None)
+        }
+
+        async fn get_reactions_by_fid(
+            &self,
+            _fid: Fid,
+            _reaction_type: Option<u8>,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_reactions_by_target(
+            &self,
+            _target_cast_fid: Option<Fid>,
+            _target_cast_hash: Option<&[u8]>,
+            _target_url: Option<&str>,
+            _reaction_type: Option<u8>,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_all_reactions_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+            _start_time: Option<u64>,
+            _end_time: Option<u64>,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_all_verification_messages_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+            _start_time: Option<u64>,
+            _end_time: Option<u64>,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_link(
+            &self,
+            _fid: Fid,
+            _link_type: &str,
+            _target_fid: Fid,
+        ) -> crate::core::data_context::Result<Option<Message>> {
+            Ok(None)
+        }
+
+        async fn get_links_by_fid(
+            &self,
+            _fid: Fid,
+            _link_type: Option<&str>,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_links_by_target(
+            &self,
+            _target_fid: Fid,
+            _link_type: Option<&str>,
+            _limit: usize,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_link_compact_state_by_fid(
+            &self,
+            _fid: Fid,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
+
+        async fn get_all_links_by_fid(
+            &self,
+            _fid: Fid,
+            _limit: usize,
+            _start_time: Option<u64>,
+            _end_time: Option<u64>,
+        ) -> crate::core::data_context::Result<Vec<Message>> {
+            Ok(vec![])
+        }
     }
 
     #[derive(Clone, Debug, Default)]
@@ -698,6 +785,27 @@ mod tests {
         ) -> crate::core::data_context::Result<Vec<Message>> {
             Ok(vec![])
         }
+
+        async fn get_id_registry_event(
+            &self,
+            _fid: Fid,
+        ) -> crate::core::data_context::Result<Option<crate::proto::OnChainEvent>> {
+            Ok(None)
+        }
+
+        async fn get_id_registry_event_by_address(
+            &self,
+            _address: &[u8],
+        ) -> crate::core::data_context::Result<Option<crate::proto::OnChainEvent>> {
+            Ok(None)
+        }
+
+        async fn get_onchain_signers_by_fid(
+            &self,
+            _fid: Fid,
+        ) -> crate::core::data_context::Result<Vec<crate::proto::OnChainEvent>> {
+            Ok(vec![])
+        }
     }
 
     type TestService = WaypointMcpService<MockDb, MockHub>;
@@ -759,7 +867,7 @@ mod tests {
     fn test_verification_message_to_json_add_includes_chain_id() {
         let message = make_verification_add_message(12345, &[0x1a, 0x2b], 0, 1, 10, 1672531200);
 
-        let result = TestService::verification_message_to_json(&message).unwrap();
+        let result = utils::process_verification_message(&message).unwrap();
         assert_eq!(result["fid"], 12345);
         assert_eq!(result["address"], "0x1a2b");
         assert_eq!(result["protocol"], "ethereum");
@@ -773,7 +881,7 @@ mod tests {
     fn test_verification_message_to_json_add_omits_chain_id_when_zero() {
         let message = make_verification_add_message(12345, &[0xaa, 0xbb], 0, 0, 0, 1672531200);
 
-        let result = TestService::verification_message_to_json(&message).unwrap();
+        let result = utils::process_verification_message(&message).unwrap();
         let obj = result.as_object().unwrap();
         assert!(!obj.contains_key("chain_id"));
     }
@@ -782,7 +890,7 @@ mod tests {
     fn test_verification_message_to_json_remove() {
         let message = make_verification_remove_message(12345, &[0xde, 0xad], 1, 1672617600);
 
-        let result = TestService::verification_message_to_json(&message).unwrap();
+        let result = utils::process_verification_message(&message).unwrap();
         assert_eq!(result["fid"], 12345);
         assert_eq!(result["address"], "0xdead");
         assert_eq!(result["protocol"], "solana");
@@ -794,7 +902,7 @@ mod tests {
     fn test_verification_message_to_json_invalid_payload_returns_none() {
         let message =
             Message::new("invalid", MessageType::Verification, vec![0xde, 0xad, 0xbe, 0xef]);
-        assert!(TestService::verification_message_to_json(&message).is_none());
+        assert!(utils::process_verification_message(&message).is_none());
     }
 
     #[tokio::test]
@@ -841,4 +949,20 @@ mod tests {
         assert_eq!(parsed["verification"]["protocol"], "ethereum");
         assert_eq!(parsed["verification"]["type"], "eoa");
     }
+
+    #[tokio::test]
+    async fn test_do_get_verification_db_only_does_not_ask_hub() {
+        let message = make_verification_add_message(12345, &[0xaa, 0xbb], 0, 0, 0, 1672531200);
+        let data_context = DataContextBuilder::new()
+            .with_database(MockDb)
+            .with_hub_client(MockHub { verification: Some(message) })
+            .with_read_policy(ReadPolicy::DbOnly)
+            .build();
+        let service: TestService = WaypointMcpService::new(data_context);
+
+        let result = service.do_get_verification(Fid::from(12345), "0xaabb").await;
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+
+        assert_eq!(parsed["found"], false);
+    }
 }
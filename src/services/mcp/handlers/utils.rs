@@ -223,6 +223,143 @@ pub fn process_link_message(
     Some(link_obj)
 }
 
+/// Map a user data type to its profile field name
+pub fn user_data_field_name(data_type: i32) -> Option<&'static str> {
+    match data_type {
+        1 => Some("pfp"),
+        2 => Some("display_name"),
+        3 => Some("bio"),
+        5 => Some("url"),
+        6 => Some("username"),
+        7 => Some("location"),
+        8 => Some("twitter"),
+        9 => Some("github"),
+        _ => None,
+    }
+}
+
+/// Build a profile object from a FID's user data messages
+pub fn process_user_data_messages(
+    fid: Fid,
+    messages: &[FarcasterMessage],
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut profile = serde_json::Map::new();
+    profile.insert(
+        "fid".to_string(),
+        serde_json::Value::Number(serde_json::Number::from(fid.value())),
+    );
+
+    for message in messages {
+        if message.message_type != MessageType::UserData {
+            continue;
+        }
+
+        if let Ok(msg_data) = <crate::proto::MessageData as ProstMessage>::decode(&*message.payload)
+            && let Some(crate::proto::message_data::Body::UserDataBody(user_data)) = msg_data.body
+            && let Some(field_name) = user_data_field_name(user_data.r#type)
+        {
+            profile.insert(field_name.to_string(), serde_json::Value::String(user_data.value));
+        }
+    }
+
+    profile
+}
+
+/// Name of a verification protocol
+pub fn protocol_name(protocol: i32) -> &'static str {
+    match protocol {
+        0 => "ethereum",
+        1 => "solana",
+        _ => "unknown",
+    }
+}
+
+/// Name of a verification type
+pub fn verification_type_name(verification_type: u32) -> &'static str {
+    match verification_type {
+        0 => "eoa",
+        1 => "contract",
+        _ => "unknown",
+    }
+}
+
+/// Name of a username proof type
+pub fn proof_type_name(proof_type: i32) -> &'static str {
+    match proof_type {
+        1 => "fname",
+        2 => "ens_l1",
+        3 => "basename",
+        _ => "unknown",
+    }
+}
+
+/// Process a username proof to extract relevant data
+pub fn process_username_proof(proof: &crate::proto::UserNameProof) -> serde_json::Value {
+    serde_json::json!({
+        "name": String::from_utf8_lossy(&proof.name),
+        "type": proof_type_name(proof.r#type),
+        "fid": proof.fid,
+        "timestamp": proof.timestamp,
+        "owner": format!("0x{}", hex::encode(&proof.owner)),
+    })
+}
+
+/// Process a verification add or remove message to extract relevant data
+pub fn process_verification_message(message: &FarcasterMessage) -> Option<serde_json::Value> {
+    if message.message_type != MessageType::Verification {
+        return None;
+    }
+
+    let msg_data: crate::proto::MessageData = ProstMessage::decode(&*message.payload).ok()?;
+
+    match msg_data.body {
+        Some(crate::proto::message_data::Body::VerificationAddAddressBody(verification)) => {
+            let mut value = serde_json::json!({
+                "fid": msg_data.fid,
+                "address": format!("0x{}", hex::encode(&verification.address)),
+                "protocol": protocol_name(verification.protocol),
+                "type": verification_type_name(verification.verification_type),
+                "action": "add",
+                "timestamp": msg_data.timestamp,
+            });
+
+            if verification.chain_id > 0 {
+                value["chain_id"] = serde_json::json!(verification.chain_id);
+            }
+
+            Some(value)
+        },
+        Some(crate::proto::message_data::Body::VerificationRemoveBody(verification)) => {
+            Some(serde_json::json!({
+                "fid": msg_data.fid,
+                "address": format!("0x{}", hex::encode(&verification.address)),
+                "protocol": protocol_name(verification.protocol),
+                "action": "remove",
+                "timestamp": msg_data.timestamp,
+            }))
+        },
+        _ => None,
+    }
+}
+
+/// Process the IdRegistry state of a FID
+pub fn process_fid_registration(
+    registration: &crate::core::types::FidRegistration,
+) -> serde_json::Value {
+    let hex_address =
+        |bytes: &Option<Vec<u8>>| bytes.as_ref().map(|b| format!("0x{}", hex::encode(b)));
+
+    serde_json::json!({
+        "fid": registration.fid.value(),
+        "custody_address": hex_address(&registration.custody_address),
+        "recovery_address": hex_address(&registration.recovery_address),
+        "registered_at": registration.registered_at,
+        "last_transfer_at": registration.last_transfer_at,
+        "transfer_count": registration.transfer_count,
+        "chain_id": registration.chain_id,
+    })
+}
+
 /// Format an array of cast messages into a JSON response
 pub fn format_casts_response(messages: Vec<FarcasterMessage>, fid: Option<Fid>) -> String {
     if messages.is_empty() {
@@ -403,6 +540,14 @@ pub enum WaypointResource {
     UsernameProofByName { name: String },
     /// Username proofs for a user
     UsernameProofsByFid { fid: u64 },
+    /// IdRegistry state for a FID
+    FidRegistration { fid: u64 },
+    /// FID currently held by a custody address
+    FidByCustodyAddress { address: String },
+    /// Active app keys for a FID
+    SignersByFid { fid: u64 },
+    /// Signer and message totals per app for a FID
+    SignerAppsByFid { fid: u64 },
 }
 
 /// Parse a waypoint:// resource URI into a WaypointResource
@@ -445,6 +590,14 @@ pub enum WaypointResource {
 /// ## Username Proofs
 /// - `waypoint://username-proofs/by-name/{name}` - Username proof for a specific name
 /// - `waypoint://username-proofs/{fid}` - Username proofs for a user
+///
+/// ## FID registry
+/// - `waypoint://fids/{fid}` - Custody/recovery state for a FID
+/// - `waypoint://fids/by-custody/{address}` - FID held by a custody address
+///
+/// ## Signers
+/// - `waypoint://signers/{fid}` - Active app keys for a FID
+/// - `waypoint://signers/apps/{fid}` - Signer and message totals per app
 pub fn parse_waypoint_resource_uri(uri: &str) -> Result<WaypointResource, String> {
     let url = Url::parse(uri).map_err(|err| format!("Invalid resource URI: {err}"))?;
 
@@ -551,6 +704,16 @@ pub fn parse_waypoint_resource_uri(uri: &str) -> Result<WaypointResource, String
             Ok(WaypointResource::UsernameProofsByFid { fid: parse_fid(fid)? })
         },
 
+        // FID registry
+        ["fids", "by-custody", address] => {
+            Ok(WaypointResource::FidByCustodyAddress { address: address.to_string() })
+        },
+        ["fids", fid] => Ok(WaypointResource::FidRegistration { fid: parse_fid(fid)? }),
+
+        // Signers
+        ["signers", "apps", fid] => Ok(WaypointResource::SignerAppsByFid { fid: parse_fid(fid)? }),
+        ["signers", fid] => Ok(WaypointResource::SignersByFid { fid: parse_fid(fid)? }),
+
         _ => Err(format!("Unsupported resource path: {}", segments.join("/"))),
     }
 }
@@ -782,4 +945,28 @@ mod tests {
             WaypointResource::UsernameProofByName { name: "vitalik.eth".to_string() }
         );
     }
+
+    #[test]
+    fn test_parse_fid_registration() {
+        let result = parse_waypoint_resource_uri("waypoint://fids/123").unwrap();
+        assert_eq!(result, WaypointResource::FidRegistration { fid: 123 });
+    }
+
+    #[test]
+    fn test_parse_fid_by_custody_address() {
+        let result = parse_waypoint_resource_uri("waypoint://fids/by-custody/0xabc123").unwrap();
+        assert_eq!(
+            result,
+            WaypointResource::FidByCustodyAddress { address: "0xabc123".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_parse_signers() {
+        let result = parse_waypoint_resource_uri("waypoint://signers/123").unwrap();
+        assert_eq!(result, WaypointResource::SignersByFid { fid: 123 });
+
+        let result = parse_waypoint_resource_uri("waypoint://signers/apps/123").unwrap();
+        assert_eq!(result, WaypointResource::SignerAppsByFid { fid: 123 });
+    }
 }
@@ -1,7 +1,7 @@
 //! MCP (Machine-Readable Client Protocol) service implementation
 
 mod base;
-pub use base::{McpService, MooCow, NullDb, WaypointMcpService};
+pub use base::{McpService, MooCow, NullDb, WaypointMcpService, build_data_context};
 
-mod handlers;
+pub(crate) mod handlers;
 pub use handlers::WaypointMcpTools;
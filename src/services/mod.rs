@@ -1,5 +1,12 @@
 //! Service implementations
+pub mod anti_entropy;
 pub mod consumer;
+pub mod graphql;
+pub mod grpc;
+pub mod live;
 pub mod mcp;
+pub mod outbox;
 pub mod producer;
+pub mod rest;
 pub mod streaming;
+pub mod webhooks;
@@ -0,0 +1,79 @@
+//! Change outbox retention service
+//!
+//! Deletes changes older than `outbox.retention_hours` from the change outbox.
+//! The outbox itself is written by the Postgres store as changes are applied.
+
+use crate::{
+    app::{Service, ServiceContext, ServiceError, ServiceHandle},
+    config::OutboxConfig,
+    database::outbox,
+};
+use async_trait::async_trait;
+use sqlx::types::time::OffsetDateTime;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Change outbox cleanup service
+pub struct OutboxService {
+    config: OutboxConfig,
+}
+
+impl OutboxService {
+    /// Create a new outbox cleanup service
+    pub fn new(config: OutboxConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Service for OutboxService {
+    fn name(&self) -> &str {
+        "outbox"
+    }
+
+    async fn start<'a>(&'a self, context: ServiceContext<'a>) -> crate::app::Result<ServiceHandle> {
+        let database = context.state.database.clone().ok_or_else(|| {
+            ServiceError::Initialization(
+                "The change outbox requires a PostgreSQL database".to_string(),
+            )
+        })?;
+        let config = self.config.clone();
+
+        let (stop_tx, mut stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let join_handle = tokio::spawn(async move {
+            if config.retention_hours == 0 {
+                info!("Change outbox retention disabled; changes are kept forever");
+                let _ = stop_rx.await;
+                return;
+            }
+
+            let retention = Duration::from_secs(config.retention_hours * 3600);
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(config.cleanup_interval_secs.max(1)));
+            info!("Change outbox cleanup started (retention {}h)", config.retention_hours);
+
+            loop {
+                tokio::select! {
+                    _ = &mut stop_rx => break,
+                    _ = interval.tick() => {
+                        let cutoff = OffsetDateTime::now_utc() - retention;
+                        match outbox::cleanup(
+                            &database.pool,
+                            cutoff,
+                            config.cleanup_batch_size as i64,
+                        )
+                        .await
+                        {
+                            Ok(0) => {},
+                            Ok(deleted) => info!("Deleted {} expired outbox changes", deleted),
+                            Err(e) => error!("Failed to clean up change outbox: {}", e),
+                        }
+                    },
+                }
+            }
+            info!("Change outbox cleanup stopped");
+        });
+
+        Ok(ServiceHandle::new(stop_tx, join_handle))
+    }
+}
@@ -0,0 +1,486 @@
+//! REST API endpoint handlers
+
+use super::response::{ApiError, ErrorBody, PageBody, json_with_etag};
+use crate::{
+    core::{
+        data_context::{self, Database as _},
+        types::{Fid, Message},
+    },
+    database::{
+        PostgresDatabaseClient,
+        hub_messages::{CastRow, LinkRow, ReactionRow, VerificationRow},
+        pages::{self, OnchainEventRow, Page, PageCursor},
+    },
+    proto::MessageData,
+    services::mcp::handlers::utils,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::Response,
+};
+use prost::Message as _;
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::IntoParams;
+
+/// Shared state of the REST handlers
+#[derive(Clone)]
+pub struct RestState {
+    pub pool: PgPool,
+    pub db: PostgresDatabaseClient,
+    pub default_page_size: usize,
+    pub max_page_size: usize,
+}
+
+impl RestState {
+    fn page_size(&self, limit: Option<usize>) -> usize {
+        limit.unwrap_or(self.default_page_size).clamp(1, self.max_page_size.max(1))
+    }
+}
+
+type ApiResult = Result<Response, ApiError>;
+
+/// Pagination query parameters
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageParams {
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    /// Page size, capped by `rest.max_page_size`
+    pub limit: Option<usize>,
+}
+
+impl PageParams {
+    fn cursor(&self) -> Result<Option<PageCursor>, ApiError> {
+        self.cursor
+            .as_deref()
+            .map(|c| {
+                PageCursor::decode(c).ok_or_else(|| ApiError::BadRequest("Invalid cursor".into()))
+            })
+            .transpose()
+    }
+}
+
+/// Reaction filter query parameters
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReactionParams {
+    /// `like` or `recast`
+    #[serde(rename = "type")]
+    #[param(rename = "type")]
+    pub reaction_type: Option<String>,
+}
+
+impl ReactionParams {
+    fn reaction_type(&self) -> Result<Option<i16>, ApiError> {
+        match self.reaction_type.as_deref() {
+            None => Ok(None),
+            Some("like") => Ok(Some(1)),
+            Some("recast") => Ok(Some(2)),
+            Some(other) => Err(ApiError::BadRequest(format!("Unknown reaction type: {}", other))),
+        }
+    }
+}
+
+/// On-chain event filter query parameters
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OnchainEventParams {
+    /// `signer`, `signer_migrated`, `id_register`, `storage_rent` or `tier_purchase`
+    #[serde(rename = "type")]
+    #[param(rename = "type")]
+    pub event_type: Option<String>,
+}
+
+impl OnchainEventParams {
+    fn event_type(&self) -> Result<Option<i16>, ApiError> {
+        self.event_type
+            .as_deref()
+            .map(|name| {
+                pages::onchain_event_type(name)
+                    .ok_or_else(|| ApiError::BadRequest(format!("Unknown event type: {}", name)))
+            })
+            .transpose()
+    }
+}
+
+fn parse_hash(hash: &str) -> Result<Vec<u8>, ApiError> {
+    utils::parse_hash_bytes(hash).map_err(ApiError::BadRequest)
+}
+
+/// Decode a rebuilt message and shape it with the MCP formatter
+fn shape(
+    message: data_context::Result<Message>,
+    process: fn(&Message, &MessageData) -> Option<serde_json::Map<String, serde_json::Value>>,
+) -> Result<Option<serde_json::Value>, ApiError> {
+    let message = message?;
+    let Ok(data) = MessageData::decode(&*message.payload) else {
+        return Ok(None);
+    };
+    Ok(process(&message, &data).map(serde_json::Value::Object))
+}
+
+fn page_body<T>(
+    page: Page<T>,
+    item: impl Fn(T) -> Result<Option<serde_json::Value>, ApiError>,
+) -> Result<PageBody, ApiError> {
+    let mut items = Vec::with_capacity(page.items.len());
+    for row in page.items {
+        items.extend(item(row)?);
+    }
+    Ok(PageBody { items, next_cursor: page.next.as_ref().map(PageCursor::encode) })
+}
+
+fn cast_json(row: CastRow) -> Result<Option<serde_json::Value>, ApiError> {
+    shape(row.into_message(), utils::process_cast_message)
+}
+
+fn reaction_json(row: ReactionRow) -> Result<Option<serde_json::Value>, ApiError> {
+    shape(row.into_message(), utils::process_reaction_message)
+}
+
+fn link_json(row: LinkRow) -> Result<Option<serde_json::Value>, ApiError> {
+    shape(row.into_message(), utils::process_link_message)
+}
+
+fn verification_json(row: VerificationRow) -> Result<Option<serde_json::Value>, ApiError> {
+    Ok(utils::process_verification_message(&row.into_message()?))
+}
+
+fn onchain_event_json(row: OnchainEventRow) -> Result<Option<serde_json::Value>, ApiError> {
+    Ok(Some(serde_json::json!({
+        "fid": row.fid,
+        "type": row.type_name(),
+        "type_id": row.event_type,
+        "chain_id": row.chain_id,
+        "block_number": row.block_number,
+        "block_hash": format!("0x{}", hex::encode(&row.block_hash)),
+        "block_timestamp": row.timestamp.unix_timestamp(),
+        "log_index": row.log_index,
+        "tx_index": row.tx_index,
+        "tx_hash": format!("0x{}", hex::encode(&row.tx_hash)),
+    })))
+}
+
+/// Profile, user data and registry state of a user
+#[utoipa::path(
+    get,
+    path = "/v1/users/{fid}",
+    tag = "users",
+    params(("fid" = u64, Path, description = "Farcaster ID")),
+    responses(
+        (status = 200, description = "User profile", body = serde_json::Value),
+        (status = 304, description = "Not modified"),
+        (status = 404, description = "Unknown FID", body = ErrorBody),
+    )
+)]
+pub async fn get_user(
+    State(state): State<RestState>,
+    Path(fid): Path<u64>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let fid = Fid::from(fid);
+    let user_data = state.db.get_user_data_by_fid(fid, 20).await?;
+    let registration = state.db.get_fid_registration(fid).await?;
+    if user_data.is_empty() && registration.is_none() {
+        return Err(ApiError::NotFound(format!("FID {} not found", fid)));
+    }
+
+    let mut profile = utils::process_user_data_messages(fid, &user_data);
+    profile.insert(
+        "registration".to_string(),
+        registration.as_ref().map(utils::process_fid_registration).unwrap_or_default(),
+    );
+
+    Ok(json_with_etag(&headers, &profile))
+}
+
+/// Casts by a user, newest first
+#[utoipa::path(
+    get,
+    path = "/v1/users/{fid}/casts",
+    tag = "casts",
+    params(("fid" = u64, Path, description = "Farcaster ID"), PageParams),
+    responses((status = 200, description = "Casts", body = PageBody), (status = 304, description = "Not modified"))
+)]
+pub async fn get_user_casts(
+    State(state): State<RestState>,
+    Path(fid): Path<u64>,
+    Query(params): Query<PageParams>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let page = pages::casts_by_fid(
+        &state.pool,
+        fid as i64,
+        params.cursor()?.as_ref(),
+        state.page_size(params.limit),
+    )
+    .await?;
+    Ok(json_with_etag(&headers, &page_body(page, cast_json)?))
+}
+
+/// Reactions made by a user, newest first
+#[utoipa::path(
+    get,
+    path = "/v1/users/{fid}/reactions",
+    tag = "reactions",
+    params(("fid" = u64, Path, description = "Farcaster ID"), ReactionParams, PageParams),
+    responses((status = 200, description = "Reactions", body = PageBody), (status = 304, description = "Not modified"))
+)]
+pub async fn get_user_reactions(
+    State(state): State<RestState>,
+    Path(fid): Path<u64>,
+    Query(filter): Query<ReactionParams>,
+    Query(params): Query<PageParams>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let page = pages::reactions_by_fid(
+        &state.pool,
+        fid as i64,
+        filter.reaction_type()?,
+        params.cursor()?.as_ref(),
+        state.page_size(params.limit),
+    )
+    .await?;
+    Ok(json_with_etag(&headers, &page_body(page, reaction_json)?))
+}
+
+/// Users following a user, most recent follow first
+#[utoipa::path(
+    get,
+    path = "/v1/users/{fid}/followers",
+    tag = "links",
+    params(("fid" = u64, Path, description = "Farcaster ID"), PageParams),
+    responses((status = 200, description = "Follow links", body = PageBody), (status = 304, description = "Not modified"))
+)]
+pub async fn get_user_followers(
+    State(state): State<RestState>,
+    Path(fid): Path<u64>,
+    Query(params): Query<PageParams>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let page = pages::links_by_target(
+        &state.pool,
+        fid as i64,
+        "follow",
+        params.cursor()?.as_ref(),
+        state.page_size(params.limit),
+    )
+    .await?;
+    Ok(json_with_etag(&headers, &page_body(page, link_json)?))
+}
+
+/// Users a user follows, most recent follow first
+#[utoipa::path(
+    get,
+    path = "/v1/users/{fid}/following",
+    tag = "links",
+    params(("fid" = u64, Path, description = "Farcaster ID"), PageParams),
+    responses((status = 200, description = "Follow links", body = PageBody), (status = 304, description = "Not modified"))
+)]
+pub async fn get_user_following(
+    State(state): State<RestState>,
+    Path(fid): Path<u64>,
+    Query(params): Query<PageParams>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let page = pages::links_by_fid(
+        &state.pool,
+        fid as i64,
+        "follow",
+        params.cursor()?.as_ref(),
+        state.page_size(params.limit),
+    )
+    .await?;
+    Ok(json_with_etag(&headers, &page_body(page, link_json)?))
+}
+
+/// Verified addresses of a user, newest first
+#[utoipa::path(
+    get,
+    path = "/v1/users/{fid}/verifications",
+    tag = "users",
+    params(("fid" = u64, Path, description = "Farcaster ID"), PageParams),
+    responses((status = 200, description = "Verifications", body = PageBody), (status = 304, description = "Not modified"))
+)]
+pub async fn get_user_verifications(
+    State(state): State<RestState>,
+    Path(fid): Path<u64>,
+    Query(params): Query<PageParams>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let page = pages::verifications_by_fid(
+        &state.pool,
+        fid as i64,
+        params.cursor()?.as_ref(),
+        state.page_size(params.limit),
+    )
+    .await?;
+    Ok(json_with_etag(&headers, &page_body(page, verification_json)?))
+}
+
+/// On-chain events of a user, newest first
+#[utoipa::path(
+    get,
+    path = "/v1/users/{fid}/onchain-events",
+    tag = "onchain",
+    params(("fid" = u64, Path, description = "Farcaster ID"), OnchainEventParams, PageParams),
+    responses((status = 200, description = "On-chain events", body = PageBody), (status = 304, description = "Not modified"))
+)]
+pub async fn get_user_onchain_events(
+    State(state): State<RestState>,
+    Path(fid): Path<u64>,
+    Query(filter): Query<OnchainEventParams>,
+    Query(params): Query<PageParams>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let page = pages::onchain_events_by_fid(
+        &state.pool,
+        fid as i64,
+        filter.event_type()?,
+        params.cursor()?.as_ref(),
+        state.page_size(params.limit),
+    )
+    .await?;
+    Ok(json_with_etag(&headers, &page_body(page, onchain_event_json)?))
+}
+
+/// A single cast
+#[utoipa::path(
+    get,
+    path = "/v1/casts/{hash}",
+    tag = "casts",
+    params(("hash" = String, Path, description = "Cast hash, hex")),
+    responses(
+        (status = 200, description = "Cast", body = serde_json::Value),
+        (status = 304, description = "Not modified"),
+        (status = 404, description = "Unknown or removed cast", body = ErrorBody),
+    )
+)]
+pub async fn get_cast(
+    State(state): State<RestState>,
+    Path(hash): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let cast = pages::cast_by_hash(&state.pool, &parse_hash(&hash)?)
+        .await?
+        .map(cast_json)
+        .transpose()?
+        .flatten()
+        .ok_or_else(|| ApiError::NotFound(format!("Cast {} not found", hash)))?;
+    Ok(json_with_etag(&headers, &cast))
+}
+
+/// The whole thread a cast belongs to, starting from its root, oldest first
+#[utoipa::path(
+    get,
+    path = "/v1/casts/{hash}/thread",
+    tag = "casts",
+    params(("hash" = String, Path, description = "Hash of any cast in the thread, hex"), PageParams),
+    responses(
+        (status = 200, description = "Thread casts", body = PageBody),
+        (status = 304, description = "Not modified"),
+        (status = 404, description = "Unknown or removed cast", body = ErrorBody),
+    )
+)]
+pub async fn get_cast_thread(
+    State(state): State<RestState>,
+    Path(hash): Path<String>,
+    Query(params): Query<PageParams>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let root = pages::cast_thread_root(&state.pool, &parse_hash(&hash)?)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Cast {} not found", hash)))?;
+    let page = pages::cast_thread(
+        &state.pool,
+        &root,
+        params.cursor()?.as_ref(),
+        state.page_size(params.limit),
+    )
+    .await?;
+    Ok(json_with_etag(&headers, &page_body(page, cast_json)?))
+}
+
+/// Direct replies to a cast, newest first
+#[utoipa::path(
+    get,
+    path = "/v1/casts/{hash}/replies",
+    tag = "casts",
+    params(("hash" = String, Path, description = "Cast hash, hex"), PageParams),
+    responses((status = 200, description = "Replies", body = PageBody), (status = 304, description = "Not modified"))
+)]
+pub async fn get_cast_replies(
+    State(state): State<RestState>,
+    Path(hash): Path<String>,
+    Query(params): Query<PageParams>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let page = pages::cast_replies(
+        &state.pool,
+        &parse_hash(&hash)?,
+        params.cursor()?.as_ref(),
+        state.page_size(params.limit),
+    )
+    .await?;
+    Ok(json_with_etag(&headers, &page_body(page, cast_json)?))
+}
+
+/// Reactions to a cast, newest first
+#[utoipa::path(
+    get,
+    path = "/v1/casts/{hash}/reactions",
+    tag = "reactions",
+    params(("hash" = String, Path, description = "Cast hash, hex"), ReactionParams, PageParams),
+    responses((status = 200, description = "Reactions", body = PageBody), (status = 304, description = "Not modified"))
+)]
+pub async fn get_cast_reactions(
+    State(state): State<RestState>,
+    Path(hash): Path<String>,
+    Query(filter): Query<ReactionParams>,
+    Query(params): Query<PageParams>,
+    headers: HeaderMap,
+) -> ApiResult {
+    let page = pages::reactions_by_cast(
+        &state.pool,
+        &parse_hash(&hash)?,
+        filter.reaction_type()?,
+        params.cursor()?.as_ref(),
+        state.page_size(params.limit),
+    )
+    .await?;
+    Ok(json_with_etag(&headers, &page_body(page, reaction_json)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_onchain_event_type_filter() {
+        let params = |t: &str| OnchainEventParams { event_type: Some(t.to_string()) };
+
+        assert_eq!(params("signer").event_type().unwrap(), Some(1));
+        assert_eq!(params("id_register").event_type().unwrap(), Some(3));
+        assert_eq!(params("tier_purchase").event_type().unwrap(), Some(5));
+        assert!(params("none").event_type().is_err());
+        assert!(params("bogus").event_type().is_err());
+        assert_eq!(OnchainEventParams { event_type: None }.event_type().unwrap(), None);
+    }
+
+    #[test]
+    fn test_reaction_type_filter() {
+        let params = |t: &str| ReactionParams { reaction_type: Some(t.to_string()) };
+
+        assert_eq!(params("like").reaction_type().unwrap(), Some(1));
+        assert_eq!(params("recast").reaction_type().unwrap(), Some(2));
+        assert!(params("love").reaction_type().is_err());
+    }
+
+    #[test]
+    fn test_invalid_cursor_is_rejected() {
+        let params = PageParams { cursor: Some("not-a-cursor".to_string()), limit: None };
+        assert!(matches!(params.cursor(), Err(ApiError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,138 @@
+//! Read-only REST API over the synced tables.
+//!
+//! Versioned JSON endpoints under `/v1`, served from PostgreSQL. Lists are
+//! cursor-paginated (`?cursor=&limit=`), every response carries an ETag and
+//! honours `If-None-Match`, and the OpenAPI document is at `/v1/openapi.json`.
+//! Items are shaped by the same formatters as the MCP tools.
+
+mod handlers;
+mod response;
+
+pub use handlers::RestState;
+
+use crate::{
+    app::{Service, ServiceContext, ServiceError, ServiceHandle},
+    config::Config,
+    database::PostgresDatabaseClient,
+};
+use async_trait::async_trait;
+use axum::{Json, Router, routing::get};
+use std::net::SocketAddr;
+use tracing::{error, info};
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Waypoint REST API", description = "Read-only access to synced Farcaster data"),
+    paths(
+        handlers::get_user,
+        handlers::get_user_casts,
+        handlers::get_user_reactions,
+        handlers::get_user_followers,
+        handlers::get_user_following,
+        handlers::get_user_verifications,
+        handlers::get_user_onchain_events,
+        handlers::get_cast,
+        handlers::get_cast_thread,
+        handlers::get_cast_replies,
+        handlers::get_cast_reactions,
+    ),
+    components(schemas(response::PageBody, response::ErrorBody))
+)]
+pub struct ApiDoc;
+
+/// Build the `/v1` router
+pub fn router(state: RestState) -> Router {
+    Router::new()
+        .route("/v1/openapi.json", get(|| async { Json(ApiDoc::openapi()) }))
+        .route("/v1/users/{fid}", get(handlers::get_user))
+        .route("/v1/users/{fid}/casts", get(handlers::get_user_casts))
+        .route("/v1/users/{fid}/reactions", get(handlers::get_user_reactions))
+        .route("/v1/users/{fid}/followers", get(handlers::get_user_followers))
+        .route("/v1/users/{fid}/following", get(handlers::get_user_following))
+        .route("/v1/users/{fid}/verifications", get(handlers::get_user_verifications))
+        .route("/v1/users/{fid}/onchain-events", get(handlers::get_user_onchain_events))
+        .route("/v1/casts/{hash}", get(handlers::get_cast))
+        .route("/v1/casts/{hash}/thread", get(handlers::get_cast_thread))
+        .route("/v1/casts/{hash}/replies", get(handlers::get_cast_replies))
+        .route("/v1/casts/{hash}/reactions", get(handlers::get_cast_reactions))
+        .with_state(state)
+}
+
+/// REST API service
+pub struct RestService {
+    config: Config,
+}
+
+impl RestService {
+    /// Create a new REST service from the application configuration
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Service for RestService {
+    fn name(&self) -> &str {
+        "rest"
+    }
+
+    async fn start<'a>(&'a self, context: ServiceContext<'a>) -> crate::app::Result<ServiceHandle> {
+        let rest = &self.config.rest;
+        let socket_addr = format!("{}:{}", rest.bind_address, rest.port)
+            .parse::<SocketAddr>()
+            .map_err(|e| ServiceError::Initialization(format!("Invalid socket address: {}", e)))?;
+
+        let database = context.state.database.clone().ok_or_else(|| {
+            ServiceError::Initialization("REST API requires a PostgreSQL database".to_string())
+        })?;
+
+        let state = RestState {
+            pool: database.pool.clone(),
+            db: PostgresDatabaseClient::new(database),
+            default_page_size: rest.default_page_size,
+            max_page_size: rest.max_page_size,
+        };
+
+        let listener = tokio::net::TcpListener::bind(socket_addr).await.map_err(|e| {
+            ServiceError::Start(format!("Failed to bind REST API to {}: {}", socket_addr, e))
+        })?;
+        info!("REST API listening on {}", socket_addr);
+
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let server_handle = tokio::spawn(async move {
+            let server = axum::serve(listener, router(state)).with_graceful_shutdown(async {
+                let _ = stop_rx.await;
+                info!("REST API shutting down");
+            });
+
+            if let Err(e) = server.await {
+                error!("REST API shutdown with error: {}", e);
+            }
+        });
+
+        Ok(ServiceHandle::new(stop_tx, server_handle))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_lists_endpoints() {
+        let doc = ApiDoc::openapi();
+
+        for path in [
+            "/v1/users/{fid}",
+            "/v1/users/{fid}/casts",
+            "/v1/users/{fid}/followers",
+            "/v1/users/{fid}/onchain-events",
+            "/v1/casts/{hash}/thread",
+            "/v1/casts/{hash}/reactions",
+        ] {
+            assert!(doc.paths.paths.contains_key(path), "missing {}", path);
+        }
+        assert!(doc.components.unwrap().schemas.contains_key("PageBody"));
+    }
+}
@@ -0,0 +1,132 @@
+//! Response helpers for the REST API: errors, pages and ETags
+
+use axum::{
+    Json,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use tracing::error;
+use utoipa::ToSchema;
+
+/// Error body returned with every non-2xx response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,
+}
+
+/// REST API error
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    NotFound(String),
+    Internal(String),
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::Internal(e.to_string())
+    }
+}
+
+impl From<crate::core::data_context::DataAccessError> for ApiError {
+    fn from(e: crate::core::data_context::DataAccessError) -> Self {
+        Self::Internal(e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            Self::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            Self::Internal(message) => {
+                // Don't leak query details to clients
+                error!("REST API error: {}", message);
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string())
+            },
+        };
+
+        (status, Json(ErrorBody { error: message })).into_response()
+    }
+}
+
+/// One page of results
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PageBody {
+    pub items: Vec<serde_json::Value>,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Strong ETag over the serialized body
+pub fn etag(body: &[u8]) -> String {
+    format!("\"{}\"", &blake3::hash(body).to_hex()[..32])
+}
+
+/// Whether an `If-None-Match` header matches `etag`
+fn not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+/// Serialize `body` as JSON with an ETag, or answer 304 if the client already has it
+pub fn json_with_etag<T: Serialize>(request_headers: &HeaderMap, body: &T) -> Response {
+    let bytes = match serde_json::to_vec(body) {
+        Ok(bytes) => bytes,
+        Err(e) => return ApiError::Internal(e.to_string()).into_response(),
+    };
+    let etag = etag(&bytes);
+    let etag_header = HeaderValue::from_str(&etag).expect("ETag is ASCII");
+
+    if not_modified(request_headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag_header)]).into_response();
+    }
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static("application/json")),
+            (header::ETAG, etag_header),
+            (header::CACHE_CONTROL, HeaderValue::from_static("no-cache")),
+        ],
+        bytes,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_with_etag_sets_etag() {
+        let response = json_with_etag(&HeaderMap::new(), &serde_json::json!({ "fid": 1 }));
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers().get(header::ETAG).unwrap().to_str().unwrap();
+        assert_eq!(etag, self::etag(br#"{"fid":1}"#));
+    }
+
+    #[test]
+    fn test_json_with_etag_not_modified() {
+        let body = serde_json::json!({ "fid": 1 });
+        let etag = etag(&serde_json::to_vec(&body).unwrap());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(&format!("\"other\", W/{}", etag)).unwrap(),
+        );
+        assert_eq!(json_with_etag(&headers, &body).status(), StatusCode::NOT_MODIFIED);
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"other\""));
+        assert_eq!(json_with_etag(&headers, &body).status(), StatusCode::OK);
+    }
+}
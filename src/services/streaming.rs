@@ -104,6 +104,11 @@ impl Consumer {
         self
     }
 
+    /// Token that stops the consumer gracefully once it has been started
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
     /// Start the consumer
     pub async fn start(self) -> JoinHandle<()> {
         let consumer = Arc::new(self);
@@ -1129,7 +1134,10 @@ impl Service for StreamingService {
     async fn start<'a>(&'a self, context: ServiceContext<'a>) -> Result<ServiceHandle> {
         // Import necessary modules
         use crate::core::MessageType;
-        use crate::processor::{AppResources, database::DatabaseProcessor, print::PrintProcessor};
+        use crate::processor::{
+            AppResources, database::DatabaseProcessor, print::PrintProcessor,
+            webhook::WebhookProcessor,
+        };
 
         // StreamingService requires both hub and database (used for "both" mode)
         let hub = context.state.hub.as_ref().ok_or_else(|| {
@@ -1138,9 +1146,9 @@ impl Service for StreamingService {
                     .to_string(),
             ))
         })?;
-        let database = context.state.database.as_ref().ok_or_else(|| {
+        let store = context.state.store.as_ref().ok_or_else(|| {
             AppError::Service(ServiceError::Initialization(
-                "Database client not available. StreamingService requires database configuration."
+                "Storage backend not available. StreamingService requires database configuration."
                     .to_string(),
             ))
         })?;
@@ -1150,7 +1158,7 @@ impl Service for StreamingService {
         let app_resources = AppResources::with_config(
             Arc::clone(hub),
             Arc::clone(&context.state.redis),
-            Arc::clone(database),
+            Arc::clone(store),
             context.config.clone(),
         );
 
@@ -1245,6 +1253,48 @@ impl Service for StreamingService {
             processor_registry.register(wrapper);
         }
 
+        // Register webhook processor if enabled (subscriptions and the outbox live in PostgreSQL)
+        if context.config.webhooks.enabled {
+            match context.state.database.as_ref() {
+                Some(database) => {
+                    info!("Registering webhook processor");
+
+                    struct WebhookWrapper {
+                        processor: WebhookProcessor,
+                    }
+
+                    #[async_trait::async_trait]
+                    impl crate::app::EventProcessor for WebhookWrapper {
+                        fn name(&self) -> &str {
+                            "webhook"
+                        }
+
+                        async fn process(
+                            &self,
+                            event: crate::proto::HubEvent,
+                        ) -> crate::app::ProcessorResult<()> {
+                            use crate::processor::consumer::EventProcessor;
+                            self.processor
+                                .process_event(event)
+                                .await
+                                .map_err(|e| crate::app::ProcessorError::Processing(e.to_string()))
+                        }
+
+                        fn supported_types(&self) -> Vec<MessageType> {
+                            MessageType::all().collect()
+                        }
+                    }
+
+                    let processor =
+                        WebhookProcessor::new(database.pool.clone(), &context.config.webhooks);
+                    processor_registry.register(WebhookWrapper { processor });
+                },
+                None => warn!(
+                    "Webhooks require a PostgreSQL database; webhook processor not registered"
+                ),
+            }
+        }
+
         // Register any other processors from config for backward compatibility
         for processor_type in &self.options.processors {
             match processor_type {
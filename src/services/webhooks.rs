@@ -0,0 +1,55 @@
+//! Webhook dispatcher service
+//!
+//! Runs the [`WebhookDispatcher`] that delivers what the webhook processor
+//! enqueues. The processor itself is registered by the consumer and streaming
+//! services.
+
+use crate::{
+    app::{Service, ServiceContext, ServiceError, ServiceHandle},
+    config::WebhookConfig,
+    processor::webhook::WebhookDispatcher,
+};
+use async_trait::async_trait;
+use tracing::info;
+
+/// Webhook dispatcher service
+pub struct WebhookService {
+    config: WebhookConfig,
+}
+
+impl WebhookService {
+    /// Create a new webhook dispatcher service
+    pub fn new(config: WebhookConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Service for WebhookService {
+    fn name(&self) -> &str {
+        "webhooks"
+    }
+
+    async fn start<'a>(&'a self, context: ServiceContext<'a>) -> crate::app::Result<ServiceHandle> {
+        let database = context.state.database.clone().ok_or_else(|| {
+            ServiceError::Initialization("Webhooks require a PostgreSQL database".to_string())
+        })?;
+        let dispatcher = WebhookDispatcher::new(database.pool.clone(), self.config.clone())
+            .map_err(|e| {
+                ServiceError::Initialization(format!("Failed to create HTTP client: {}", e))
+            })?;
+
+        info!("Webhook dispatcher started (concurrency {})", self.config.concurrency);
+        let dispatch_handle = tokio::spawn(async move { dispatcher.run().await });
+
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let join_handle = tokio::spawn(async move {
+            let _ = stop_rx.await;
+            // Deliveries cut off mid-request are retried once their claim expires
+            dispatch_handle.abort();
+            info!("Webhook dispatcher stopped");
+        });
+
+        Ok(ServiceHandle::new(stop_tx, join_handle))
+    }
+}